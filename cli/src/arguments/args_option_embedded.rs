@@ -3,7 +3,7 @@ use std::collections::{ HashMap };
 use super::{ CtrlArg, EArgumentsNames, EArgumentsValues };
 use super::{ helpers };
 
-mod keys {
+pub mod keys {
     pub const EMBEDDED: &str = "--embedded";
     pub const EM: &str = "--em";
     pub const E: &str = "-e";
@@ -1,21 +1,15 @@
 use super::{
-    helpers,
-    protocol::Parser as ProtocolParser,
-    render::{rust::RustRender, typescript::TypescriptRender, Render},
-    workflow::Parser as WorkflowParser,
-    workflow_render::{puml::PumlRender, render as workflow_render},
-    CtrlArg, EArgumentsNames, EArgumentsValues,
+    commands::generate::{generate, Options},
+    helpers, CtrlArg, EArgumentsNames, EArgumentsValues,
 };
 
 use std::{
     collections::HashMap,
     fs,
-    fs::remove_file,
     path::{Path, PathBuf},
-    time::Instant,
 };
 
-mod keys {
+pub mod keys {
     pub const SOURCE: &str = "--source";
     pub const SRC: &str = "--src";
     pub const S: &str = "-s";
@@ -293,86 +287,26 @@ impl CtrlArg for ArgsOptionFiles {
                 "Workflow cannot be generated without reference to protocol",
             ));
         }
-        if let Some(src) = self.src.clone() {
-            let t_parsing = Instant::now();
-            let overwrite: bool = self.get_overwrite_flag(ctrls);
-            let embedded: bool = self.get_embedded_flag(ctrls);
-            let mut protocol: ProtocolParser = ProtocolParser::new(src.clone());
-            match protocol.parse() {
-                Ok(mut protocol_store) => {
-                    println!(
-                        "[OK][{}ms] parsed {:?}",
-                        t_parsing.elapsed().as_millis(),
-                        src
-                    );
-                    if self.workflow.is_none() && self.dest_uml.is_some() {
-                        return Err(String::from("PUML scheme can be created only based on workflow scheme. Please define path to workflow scheme"));
-                    }
-                    if let Some(workflow_path) = self.workflow.as_ref() {
-                        // TODO: remove workflow dest folder
-                        let mut workflow: WorkflowParser =
-                            WorkflowParser::new(workflow_path.to_owned())?;
-                        match workflow.parse(&mut protocol_store) {
-                            Ok(workflow_store) => {
-                                if let Some(uml_path) = self.dest_uml.as_ref() {
-                                    (PumlRender::new()).render(
-                                        uml_path,
-                                        &workflow_store,
-                                        &mut protocol_store,
-                                    )?;
-                                } else {
-                                    workflow_render(
-                                        self.dest_consumer.clone(),
-                                        self.dest_producer.clone(),
-                                        workflow_store,
-                                        &mut protocol_store,
-                                    )?;
-                                }
-                            }
-                            Err(err) => {
-                                return Err(err);
-                            }
-                        };
-                    }
-                    if let Some(dest) = self.dest_rs.clone() {
-                        if dest.exists() && !overwrite {
-                            return Err(format!("File {:?} exists. Use key \"overwrite\" to overwrite file. -h to get more info", dest));
-                        } else if dest.exists() {
-                            println!("[INFO] {:?} will be overwritten", dest);
-                            if let Err(err) = remove_file(dest.clone()) {
-                                return Err(format!(
-                                    "Fail to remove file {:?} due error: {}",
-                                    dest, err
-                                ));
-                            } else {
-                                println!("[INFO] {:?} clean", dest);
-                            }
-                        }
-                        RustRender::new(embedded, 0).render(&mut protocol_store, &dest)?;
-                    }
-                    if let Some(dest) = self.dest_ts.clone() {
-                        if dest.exists() && !overwrite {
-                            return Err(format!("File {:?} exists. Use key \"overwrite\" to overwrite file. -h to get more info", dest));
-                        } else if dest.exists() {
-                            println!("[INFO] {:?} will be overwritten", dest);
-                            if let Err(err) = remove_file(dest.clone()) {
-                                return Err(format!(
-                                    "Fail to remove file {:?} due error: {}",
-                                    dest, err
-                                ));
-                            } else {
-                                println!("[INFO] {:?} clean", dest);
-                            }
-                        }
-                        TypescriptRender::new(embedded, 0).render(&mut protocol_store, &dest)?;
-                    }
-                    Ok(())
-                }
-                Err(errs) => Err(errs.join("\n")),
-            }
-        } else {
-            Err(String::from("protocol file isn't defined"))
-        }
+        generate(&Options {
+            src: self.src.clone(),
+            workflow: self.workflow.clone(),
+            dest_rs: self.dest_rs.clone(),
+            dest_ts: self.dest_ts.clone(),
+            dest_consumer: self.dest_consumer.clone(),
+            dest_producer: self.dest_producer.clone(),
+            dest_uml: self.dest_uml.clone(),
+            dest_crates: None,
+            signature: 0,
+            overwrite: self.get_overwrite_flag(ctrls),
+            embedded: self.get_embedded_flag(ctrls),
+            templates: None,
+            stubs: Default::default(),
+            attributes: Default::default(),
+            views: false,
+            unknown_fields: false,
+            config: None,
+            compatible: vec![],
+        })
     }
 
     fn get_help(&self) -> String {
@@ -3,7 +3,7 @@ use std::collections::{ HashMap };
 use super::{ CtrlArg, EArgumentsNames, EArgumentsValues };
 use super:: { helpers };
 
-mod keys {
+pub mod keys {
     pub const OVERWRITE: &str = "--overwrite";
     pub const OW: &str = "--ow";
     pub const O: &str = "-o";
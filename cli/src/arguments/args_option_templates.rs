@@ -0,0 +1,106 @@
+use super::{helpers, CtrlArg, EArgumentsNames, EArgumentsValues};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub mod keys {
+    pub const TEMPLATES: &str = "--templates";
+    pub const TPL: &str = "-tpl";
+}
+
+pub struct ArgsOptionTemplates {
+    templates: Option<PathBuf>,
+    err: Option<String>,
+}
+
+impl CtrlArg for ArgsOptionTemplates {
+    fn new(
+        pwd: &Path,
+        args: Vec<String>,
+        _ctrls: &HashMap<EArgumentsNames, Box<dyn CtrlArg + 'static>>,
+    ) -> Self {
+        let mut templates: Option<PathBuf> = None;
+        let mut err: Option<String> = None;
+        if let Some(index) = args
+            .iter()
+            .position(|arg| arg == keys::TEMPLATES || arg == keys::TPL)
+        {
+            match args.get(index + 1) {
+                Some(dir) => {
+                    let dir = pwd.join(dir);
+                    if !dir.is_dir() {
+                        err = Some(format!(
+                            "Templates folder doesn't exist. Path: {}",
+                            dir.to_string_lossy()
+                        ));
+                    } else {
+                        // Overrides should be available before any renderer starts
+                        helpers::template::set_overrides(&dir);
+                        templates = Some(dir);
+                    }
+                }
+                None => {
+                    err = Some(format!("Key {} requires path to folder", keys::TEMPLATES));
+                }
+            }
+        }
+        ArgsOptionTemplates { templates, err }
+    }
+
+    fn name(&self) -> EArgumentsNames {
+        EArgumentsNames::OptionTemplates
+    }
+
+    fn value(&self) -> EArgumentsValues {
+        EArgumentsValues::OptionTemplates(self.templates.clone())
+    }
+
+    fn get_err(&self) -> Option<String> {
+        self.err.clone()
+    }
+
+    fn is_action_available(&self) -> bool {
+        false
+    }
+
+    fn action(
+        &self,
+        _ctrls: &HashMap<EArgumentsNames, Box<dyn CtrlArg + 'static>>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn get_help(&self) -> String {
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", keys::TEMPLATES, keys::TPL)),
+            helpers::output::desk(&format!(
+                "path to folder with templates, which overwrite bundled templates. File should be named as <name>.{} (for example: header/rs.{}, header/ts.{}, protocol/rust/struct.{}, protocol/typescript/struct.{}, producer/rust/request_handler/module_with_conclusion.{}). Templates \"header/rs\" and \"header/ts\" are added at the beginning of each generated file. Bundled parts of protocol can be overwritten as protocol/rust/static/<name> and protocol/typescript/static/<name> (for example: protocol/rust/static/protocol.json.{}, protocol/typescript/static/packing.{}).",
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+                helpers::template::EXTENSION,
+            )),
+        )
+    }
+}
+
+pub fn get_cleaner() -> impl Fn(Vec<String>) -> Vec<String> {
+    move |mut args: Vec<String>| {
+        if let Some(index) = args
+            .iter()
+            .position(|arg| arg == keys::TEMPLATES || arg == keys::TPL)
+        {
+            if args.get(index + 1).is_some() {
+                args.remove(index + 1);
+            }
+            args.remove(index);
+        }
+        args
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn new(args: Vec<String>) -> Self {
+        Args { args }
+    }
+
+    pub fn flag(&mut self, keys: &[&str]) -> bool {
+        if let Some(index) = self.position(keys) {
+            self.args.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn value(&mut self, keys: &[&str]) -> Result<Option<String>, String> {
+        if let Some(index) = self.position(keys) {
+            if index + 1 >= self.args.len() {
+                return Err(format!("Key {} requires value", self.args[index]));
+            }
+            let value = self.args.remove(index + 1);
+            self.args.remove(index);
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn path(&mut self, pwd: &Path, keys: &[&str]) -> Result<Option<PathBuf>, String> {
+        Ok(self.value(keys)?.map(|path| pwd.join(path)))
+    }
+
+    pub fn rest(self) -> Result<Vec<String>, String> {
+        let unknown: Vec<&String> = self.args.iter().filter(|a| a.starts_with('-')).collect();
+        if unknown.is_empty() {
+            Ok(self.args)
+        } else {
+            Err(format!(
+                "Unknown keys/arguments: \n\t- {}",
+                unknown
+                    .iter()
+                    .map(|a| a.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("\n\t- ")
+            ))
+        }
+    }
+
+    pub fn done(self) -> Result<(), String> {
+        let rest = self.rest()?;
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Unexpected arguments: {}", rest.join(", ")))
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.args.clone()
+    }
+
+    fn position(&self, keys: &[&str]) -> Option<usize> {
+        self.args
+            .iter()
+            .position(|arg| keys.iter().any(|k| k == arg))
+    }
+}
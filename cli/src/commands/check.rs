@@ -0,0 +1,104 @@
+use super::{
+    generate::{generate, get_render_help, get_sources_help, Options},
+    helpers, Args, Command,
+};
+use similar::TextDiff;
+use std::{fs, path::Path};
+
+// Amount of unchanged lines around changes in diff
+const CONTEXT: usize = 3;
+
+// Generated file, which differs from file on disk
+pub struct Changed {
+    pub filename: String,
+    pub diff: String,
+}
+
+// Renders files in memory and compares them with files on disk. Returns
+// amount of rendered files and files, which are changed
+pub fn compare(pwd: &Path, mut args: Args) -> Result<(usize, Vec<Changed>), String> {
+    let mut options = Options::from_args(pwd, &mut args)?;
+    args.done()?;
+    // Files are compared only, nothing is overwritten
+    options.overwrite = true;
+    helpers::fs::capture();
+    let result = generate(&options);
+    let files = helpers::fs::take_captured();
+    result?;
+    let mut changed: Vec<Changed> = vec![];
+    for (path, content) in files.iter() {
+        let filename = path.to_string_lossy().to_string();
+        let actual = if path.exists() {
+            fs::read(path).map_err(|e| format!("Fail to read file {}: {}", filename, e))?
+        } else {
+            vec![]
+        };
+        if actual == content.as_bytes() {
+            continue;
+        }
+        let (old, new) = match path.strip_prefix(pwd) {
+            Ok(relative) => (
+                format!("a/{}", relative.to_string_lossy()),
+                format!("b/{}", relative.to_string_lossy()),
+            ),
+            Err(_) => (filename.clone(), filename.clone()),
+        };
+        let old = if path.exists() {
+            old
+        } else {
+            String::from("/dev/null")
+        };
+        let actual = String::from_utf8_lossy(&actual);
+        let diff = TextDiff::from_lines(actual.as_ref(), content.as_str())
+            .unified_diff()
+            .context_radius(CONTEXT)
+            .header(&old, &new)
+            .to_string();
+        changed.push(Changed { filename, diff });
+    }
+    Ok((files.len(), changed))
+}
+
+pub struct Check {}
+
+impl Command for Check {
+    fn name(&self) -> &'static str {
+        super::names::CHECK
+    }
+
+    fn desc(&self) -> &'static str {
+        "checks whether generated files are up to date; nothing is written"
+    }
+
+    fn run(&self, pwd: &Path, args: Args) -> Result<(), String> {
+        let (total, changed) = compare(pwd, args)?;
+        if changed.is_empty() {
+            println!("[OK] {} file(s) are up to date", total);
+            Ok(())
+        } else {
+            for changed in changed.iter() {
+                print!("{}", changed.diff);
+            }
+            Err(format!(
+                "Next file(s) aren't up to date:\n\t- {}",
+                changed
+                    .iter()
+                    .map(|changed| changed.filename.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n\t- ")
+            ))
+        }
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!(
+                "Usage: clibri {} [options]\n\nRenders files in memory and compares them with files on disk. Prints unified diff for each outdated file and fails.\n",
+                self.name()
+            ),
+            get_sources_help(),
+            get_render_help(),
+        ]
+        .join("\n")
+    }
+}
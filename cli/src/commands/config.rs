@@ -0,0 +1,163 @@
+use super::{
+    generate::{Compatible, Options},
+    helpers::stubs,
+    protocol::attributes::Attributes,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::Value;
+
+pub const FILE_NAME: &str = "clibri.toml";
+
+mod sections {
+    pub const SOURCES: &str = "sources";
+    pub const TARGETS: &str = "targets";
+    pub const DESTINATIONS: &str = "destinations";
+    pub const OPTIONS: &str = "options";
+    pub const RUST: &str = "rust";
+}
+
+pub fn find(pwd: &Path) -> Option<PathBuf> {
+    let path = pwd.join(FILE_NAME);
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+pub fn load(path: &Path) -> Result<Options, String> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Fail to read config file {}. Error: {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    let root: Value = content.parse::<Value>().map_err(|e| {
+        format!(
+            "Fail to parse config file {}. Error: {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    let base = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    let config = Config {
+        root: &root,
+        base: &base,
+    };
+    if let Some(table) = root.as_table() {
+        for section in table.keys() {
+            if ![
+                sections::SOURCES,
+                sections::TARGETS,
+                sections::DESTINATIONS,
+                sections::OPTIONS,
+                sections::RUST,
+            ]
+            .contains(&section.as_str())
+            {
+                return Err(format!(
+                    "{}: unknown section [{}]",
+                    path.to_string_lossy(),
+                    section
+                ));
+            }
+        }
+    }
+    let signature = match config.get(sections::OPTIONS, "signature") {
+        Some(Value::Integer(signature)) => {
+            if *signature < 0 || *signature > u16::MAX as i64 {
+                return Err(format!("Signature should be in range 0..{}", u16::MAX));
+            }
+            *signature as u16
+        }
+        Some(_) => {
+            return Err(String::from("options.signature should be a number"));
+        }
+        None => 0,
+    };
+    let stubs = match config.get(sections::OPTIONS, "stubs") {
+        Some(Value::String(mode)) => stubs::Mode::parse(mode)?,
+        Some(_) => {
+            return Err(String::from("options.stubs should be a string"));
+        }
+        None => stubs::Mode::default(),
+    };
+    let mut attributes = Attributes::default();
+    for derive in config.strings(sections::RUST, "derive")? {
+        attributes.add_derive(&derive)?;
+    }
+    for attr in config.strings(sections::RUST, "attrs")? {
+        attributes.add_attr(&attr)?;
+    }
+    Ok(Options {
+        src: config.path(sections::SOURCES, "protocol")?,
+        workflow: config.path(sections::SOURCES, "workflow")?,
+        dest_rs: config.path(sections::TARGETS, "rust")?,
+        dest_ts: config.path(sections::TARGETS, "typescript")?,
+        dest_consumer: config.path(sections::DESTINATIONS, "consumer")?,
+        dest_producer: config.path(sections::DESTINATIONS, "producer")?,
+        dest_uml: config.path(sections::DESTINATIONS, "puml")?,
+        dest_crates: config.path(sections::DESTINATIONS, "crates")?,
+        signature,
+        overwrite: config.bool(sections::OPTIONS, "overwrite")?,
+        embedded: config.bool(sections::OPTIONS, "embedded")?,
+        templates: config.path(sections::OPTIONS, "templates")?,
+        stubs,
+        attributes,
+        views: config.bool(sections::RUST, "views")?,
+        unknown_fields: config.bool(sections::RUST, "unknown_fields")?,
+        config: Some(path.to_path_buf()),
+        compatible: config
+            .strings(sections::SOURCES, "compatible")?
+            .iter()
+            .map(|value| Compatible::parse(&base, value))
+            .collect(),
+    })
+}
+
+struct Config<'a> {
+    root: &'a Value,
+    base: &'a Path,
+}
+
+impl<'a> Config<'a> {
+    fn get(&self, section: &str, key: &str) -> Option<&'a Value> {
+        self.root.get(section).and_then(|s| s.get(key))
+    }
+
+    fn path(&self, section: &str, key: &str) -> Result<Option<PathBuf>, String> {
+        match self.get(section, key) {
+            Some(Value::String(path)) => Ok(Some(self.base.join(path))),
+            Some(_) => Err(format!("{}.{} should be a string", section, key)),
+            None => Ok(None),
+        }
+    }
+
+    fn strings(&self, section: &str, key: &str) -> Result<Vec<String>, String> {
+        match self.get(section, key) {
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|value| {
+                    value
+                        .as_str()
+                        .map(|s| s.to_owned())
+                        .ok_or_else(|| format!("{}.{} should be a list of strings", section, key))
+                })
+                .collect(),
+            Some(_) => Err(format!("{}.{} should be a list of strings", section, key)),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn bool(&self, section: &str, key: &str) -> Result<bool, String> {
+        match self.get(section, key) {
+            Some(Value::Boolean(value)) => Ok(*value),
+            Some(_) => Err(format!("{}.{} should be true or false", section, key)),
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use super::{
+    generate::{get_sources_help, Options},
+    helpers,
+    protocol::{
+        enums::Enum, fields::Field, store::Store as Protocol, structs::Struct,
+        types::PrimitiveTypes, Parser as ProtocolParser,
+    },
+    workflow::Parser as WorkflowParser,
+    Args, Command,
+};
+use data_encoding::HEXLOWER_PERMISSIVE;
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, convert::TryInto, fs, path::Path};
+
+pub mod keys {
+    pub const FILE: &str = "--file";
+    pub const F: &str = "-f";
+}
+
+// {u32} id + {u16} signature + {u32} sequence + {u64} timestamp + {u64} body size
+const HEADER_LEN: usize = 4 + 2 + 4 + 8 + 8;
+
+pub struct Decode {}
+
+impl Command for Decode {
+    fn name(&self) -> &'static str {
+        super::names::DECODE
+    }
+
+    fn desc(&self) -> &'static str {
+        "decodes binary package(s) into JSON"
+    }
+
+    fn run(&self, pwd: &Path, mut args: Args) -> Result<(), String> {
+        let file = args.path(pwd, &[keys::FILE, keys::F])?;
+        let options = Options::from_args(pwd, &mut args)?;
+        let rest = args.rest()?;
+        options.validate()?;
+        let buf: Vec<u8> = if let Some(file) = file {
+            fs::read(&file)
+                .map_err(|e| format!("Fail to read file {}: {}", file.to_string_lossy(), e))?
+        } else if !rest.is_empty() {
+            let hex: String = rest
+                .join("")
+                .trim_start_matches("0x")
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            HEXLOWER_PERMISSIVE
+                .decode(hex.as_bytes())
+                .map_err(|e| format!("Fail to decode hex string: {}", e))?
+        } else {
+            return Err(format!(
+                "Package isn't defined. Use {} <path> or pass package as hex string",
+                keys::FILE
+            ));
+        };
+        let mut protocol = ProtocolParser::new(options.get_src()?.clone())
+            .parse()
+            .map_err(|errs| errs.join("\n"))?;
+        if let Some(workflow) = options.workflow.as_ref() {
+            // Workflow adds service messages into protocol
+            WorkflowParser::new(workflow.clone())?.parse(&mut protocol)?;
+        }
+        let decoder = Decoder {
+            protocol: &protocol,
+        };
+        let mut packages: Vec<Value> = vec![];
+        let mut position: usize = 0;
+        while position < buf.len() {
+            let (package, len) = decoder.package(&buf[position..])?;
+            packages.push(package);
+            position += len;
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Value::Array(packages))
+                .map_err(|e| format!("Fail to serialize output: {}", e))?
+        );
+        Ok(())
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!(
+                "Usage: clibri {} [options] [hex]\n\nPackage can be passed as hex string or as path to binary file.\n",
+                self.name()
+            ),
+            get_sources_help(),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!("{} ({})", keys::FILE, keys::F)),
+                helpers::output::desk("path to file with binary package(s)"),
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.pos + len > self.buf.len() {
+            return Err(format!(
+                "Expecting {} bytes, but only {} bytes left",
+                len,
+                self.buf.len() - self.pos
+            ));
+        }
+        let slice = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self, len: u64) -> Result<&'a [u8], String> {
+        self.take(
+            len.try_into()
+                .map_err(|_| format!("Length {} is too big", len))?,
+        )
+    }
+}
+
+struct Decoder<'a> {
+    protocol: &'a Protocol,
+}
+
+impl<'a> Decoder<'a> {
+    fn package(&self, buf: &[u8]) -> Result<(Value, usize), String> {
+        let mut reader = Reader::new(buf);
+        let id = reader.u32()?;
+        let signature = reader.u16()?;
+        let sequence = reader.u32()?;
+        let ts = reader.u64()?;
+        let len = reader.u64()?;
+        let body = reader
+            .len(len)
+            .map_err(|e| format!("Fail to read body of package id={}: {}", id, e))?;
+        let (name, message) = if let Some(strct) = self.find_struct(id as usize) {
+            (
+                self.protocol.get_struct_path(strct.id).join("."),
+                self.strct(strct, body)?,
+            )
+        } else if let Some(enums) = self.find_enum(id as usize) {
+            (
+                self.protocol.get_enum_path(enums.id).join("."),
+                self.enums(enums, body)?,
+            )
+        } else {
+            return Err(format!("Message with id={} isn't found in protocol", id));
+        };
+        Ok((
+            json!({
+                "header": {
+                    "id": id,
+                    "signature": signature,
+                    "sequence": sequence,
+                    "ts": ts,
+                    "len": len,
+                },
+                "name": name,
+                "message": message,
+            }),
+            HEADER_LEN + body.len(),
+        ))
+    }
+
+    fn find_struct(&self, id: usize) -> Option<&Struct> {
+        self.protocol.structs.iter().find(|s| s.id == id)
+    }
+
+    fn find_enum(&self, id: usize) -> Option<&Enum> {
+        self.protocol.enums.iter().find(|e| e.id == id)
+    }
+
+    fn storage(&self, buf: &[u8]) -> Result<HashMap<u16, Vec<u8>>, String> {
+        let mut map: HashMap<u16, Vec<u8>> = HashMap::new();
+        let mut reader = Reader::new(buf);
+        while !reader.is_empty() {
+            let id = reader.u16()?;
+            let len = match reader.u8()? {
+                8 => reader.u8()? as u64,
+                16 => reader.u16()? as u64,
+                32 => reader.u32()? as u64,
+                64 => reader.u64()?,
+                rank => {
+                    return Err(format!("Unknown rank has been gotten: {}", rank));
+                }
+            };
+            map.insert(id, reader.len(len)?.to_vec());
+        }
+        Ok(map)
+    }
+
+    fn strct(&self, strct: &Struct, buf: &[u8]) -> Result<Value, String> {
+        let storage = self.storage(buf)?;
+        let mut output = Map::new();
+        for field in strct.fields.iter() {
+            let value = match storage.get(&(field.id as u16)) {
+                Some(buf) if field.optional && buf.is_empty() => Value::Null,
+                Some(buf) => self
+                    .field(field, buf)
+                    .map_err(|e| format!("{}.{}: {}", strct.name, field.name, e))?,
+                None if field.optional => Value::Null,
+                None => {
+                    return Err(format!(
+                        "{}.{}: field isn't found in package",
+                        strct.name, field.name
+                    ));
+                }
+            };
+            output.insert(field.name.clone(), value);
+        }
+        Ok(Value::Object(output))
+    }
+
+    fn field(&self, field: &Field, buf: &[u8]) -> Result<Value, String> {
+        if let Some(ref_type_id) = field.ref_type_id {
+            if field.repeated {
+                let mut items: Vec<Value> = vec![];
+                let mut reader = Reader::new(buf);
+                while !reader.is_empty() {
+                    let len = reader.u64()?;
+                    items.push(self.reference(ref_type_id, reader.len(len)?)?);
+                }
+                Ok(Value::Array(items))
+            } else {
+                self.reference(ref_type_id, buf)
+            }
+        } else {
+            self.primitive(&field.kind, field.repeated, buf)
+        }
+    }
+
+    fn reference(&self, id: usize, buf: &[u8]) -> Result<Value, String> {
+        if let Some(strct) = self.find_struct(id) {
+            self.strct(strct, buf)
+        } else if let Some(enums) = self.find_enum(id) {
+            self.enums(enums, buf)
+        } else {
+            Err(format!("Type with id={} isn't found in protocol", id))
+        }
+    }
+
+    fn enums(&self, enums: &Enum, buf: &[u8]) -> Result<Value, String> {
+        let mut reader = Reader::new(buf);
+        let index = reader.u16()? as usize;
+        let variant = enums.variants.get(index).ok_or(format!(
+            "{}: variant with index {} doesn't exist",
+            enums.name, index
+        ))?;
+        let body = &buf[2..];
+        let value = if let Some(ref_type_id) = variant.ref_type_id {
+            self.reference(ref_type_id, body)?
+        } else if let Some(kind) = variant.types.clone() {
+            let kind = PrimitiveTypes::get_entity_as_string(kind).ok_or(format!(
+                "{}: type of {} is unknown",
+                enums.name, variant.name
+            ))?;
+            self.primitive(&kind, variant.repeated, body)?
+        } else {
+            return Err(format!(
+                "{}: type of {} is unknown",
+                enums.name, variant.name
+            ));
+        };
+        let mut output = Map::new();
+        output.insert(variant.name.clone(), value);
+        Ok(Value::Object(output))
+    }
+
+    fn primitive(&self, kind: &str, repeated: bool, buf: &[u8]) -> Result<Value, String> {
+        let mut reader = Reader::new(buf);
+        if !repeated {
+            return self.value(kind, &mut reader, buf.len());
+        }
+        let mut items: Vec<Value> = vec![];
+        while !reader.is_empty() {
+            let value = if kind == "str" {
+                let len = reader.u32()? as usize;
+                self.value(kind, &mut reader, len)?
+            } else {
+                self.value(kind, &mut reader, 0)?
+            };
+            items.push(value);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn value(&self, kind: &str, reader: &mut Reader, len: usize) -> Result<Value, String> {
+        Ok(match kind {
+            "bool" => json!(reader.u8()? != 0),
+            "u8" => json!(reader.u8()?),
+            "u16" => json!(reader.u16()?),
+            "u32" => json!(reader.u32()?),
+            // 64-bit integers are strings as in canonical JSON of generated protocol
+            "u64" => json!(reader.u64()?.to_string()),
+            "i8" => json!(reader.u8()? as i8),
+            "i16" => json!(reader.u16()? as i16),
+            "i32" => json!(reader.u32()? as i32),
+            "i64" => json!((reader.u64()? as i64).to_string()),
+            "f32" => json!(f32::from_bits(reader.u32()?)),
+            "f64" => json!(f64::from_bits(reader.u64()?)),
+            "str" => json!(String::from_utf8_lossy(reader.take(len)?)),
+            _ => {
+                return Err(format!("Type {} isn't supported", kind));
+            }
+        })
+    }
+}
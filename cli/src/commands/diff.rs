@@ -0,0 +1,40 @@
+use super::{
+    check::compare,
+    generate::{get_render_help, get_sources_help},
+    Args, Command,
+};
+use std::path::Path;
+
+pub struct Diff {}
+
+impl Command for Diff {
+    fn name(&self) -> &'static str {
+        super::names::DIFF
+    }
+
+    fn desc(&self) -> &'static str {
+        "prints difference between generated files and files on disk; nothing is written"
+    }
+
+    fn run(&self, pwd: &Path, args: Args) -> Result<(), String> {
+        let (_, changed) = compare(pwd, args)?;
+        // As git diff, it doesn't fail if there are changes; "check" should be used as gate
+        for changed in changed.iter() {
+            print!("{}", changed.diff);
+        }
+        Ok(())
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!(
+                "Usage: clibri {} [options]\n\nRenders files in memory and prints unified diff between files on disk (a/) and generated files (b/). Missed files are shown as /dev/null. Unlike \"clibri {}\", it doesn't fail if files are outdated.\n",
+                self.name(),
+                super::names::CHECK,
+            ),
+            get_sources_help(),
+            get_render_help(),
+        ]
+        .join("\n")
+    }
+}
@@ -0,0 +1,239 @@
+use super::{
+    generate::{get_sources_help, Options},
+    helpers, Args, Command,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub mod keys {
+    pub const CHECK: &str = "--check";
+}
+
+const INDENT: usize = 4;
+const WORKFLOW_EXT: &str = "workflow";
+
+struct Line {
+    level: usize,
+    code: String,
+    comment: Option<String>,
+}
+
+pub struct Fmt {}
+
+impl Command for Fmt {
+    fn name(&self) -> &'static str {
+        super::names::FMT
+    }
+
+    fn desc(&self) -> &'static str {
+        "formats protocol (*.prot) and workflow (*.workflow) files"
+    }
+
+    fn run(&self, pwd: &Path, mut args: Args) -> Result<(), String> {
+        let check = args.flag(&[keys::CHECK]);
+        let options = Options::from_args(pwd, &mut args)?;
+        let mut files: Vec<PathBuf> = args.rest()?.iter().map(|f| pwd.join(f)).collect();
+        if files.is_empty() {
+            files.push(options.get_src()?.clone());
+            if let Some(workflow) = options.workflow {
+                files.push(workflow);
+            }
+        }
+        let mut unformatted: Vec<String> = vec![];
+        for file in files {
+            let content = fs::read_to_string(&file)
+                .map_err(|e| format!("Fail to read file {}: {}", file.to_string_lossy(), e))?;
+            let formatted = format(
+                &content,
+                file.extension().and_then(|e| e.to_str()) == Some(WORKFLOW_EXT),
+            );
+            if formatted == content {
+                continue;
+            }
+            if check {
+                unformatted.push(file.to_string_lossy().to_string());
+            } else {
+                helpers::fs::write(file, formatted, true)?;
+            }
+        }
+        if unformatted.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Next file(s) aren't formatted:\n\t- {}",
+                unformatted.join("\n\t- ")
+            ))
+        }
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!(
+                "Usage: clibri {} [options] [files]\n\nWithout files formats protocol and workflow files from options or config.\n",
+                self.name()
+            ),
+            get_sources_help(),
+            format!(
+                "{}{}",
+                helpers::output::keys(keys::CHECK),
+                helpers::output::desk("doesn't change files; fails if some file isn't formatted"),
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+pub fn format(content: &str, workflow: bool) -> String {
+    let mut lines = get_lines(content);
+    if workflow {
+        align_conclusions(&mut lines);
+    }
+    let mut output = String::new();
+    for line in lines.iter() {
+        let indent = " ".repeat(line.level * INDENT);
+        output = match line.comment.as_ref() {
+            Some(comment) if line.code.is_empty() => {
+                format!("{}{}{}\n", output, indent, comment)
+            }
+            Some(comment) => format!("{}{}{} {}\n", output, indent, line.code, comment),
+            None if line.code.is_empty() => format!("{}\n", output),
+            None => format!("{}{}{}\n", output, indent, line.code),
+        };
+    }
+    output
+}
+
+fn get_lines(content: &str) -> Vec<Line> {
+    let mut lines: Vec<Line> = vec![];
+    let mut level: usize = 0;
+    for src in content.lines() {
+        let (code, comment) = split_comment(src);
+        let code = collapse(code);
+        let closing = code.starts_with('}');
+        let line = Line {
+            level: if closing && level > 0 {
+                level - 1
+            } else {
+                level
+            },
+            code: code.clone(),
+            comment,
+        };
+        for c in code.chars() {
+            if c == '{' {
+                level += 1;
+            } else if c == '}' && level > 0 {
+                level -= 1;
+            }
+        }
+        let empty = line.code.is_empty() && line.comment.is_none();
+        if empty {
+            // Only one empty line in a row; no empty lines after opening of block
+            match lines.last() {
+                None => continue,
+                Some(prev) if prev.code.is_empty() && prev.comment.is_none() => continue,
+                Some(prev) if prev.code.ends_with('{') => continue,
+                _ => {}
+            }
+        } else if closing {
+            // No empty lines before closing of block
+            while lines
+                .last()
+                .is_some_and(|prev| prev.code.is_empty() && prev.comment.is_none())
+            {
+                lines.pop();
+            }
+        }
+        lines.push(line);
+    }
+    while lines
+        .last()
+        .is_some_and(|prev| prev.code.is_empty() && prev.comment.is_none())
+    {
+        lines.pop();
+    }
+    lines
+}
+
+/// Aligns conclusions of requests: (Accept > A) > B; and continuation lines of broadcasts
+fn align_conclusions(lines: &mut [Line]) {
+    let mut start = 0;
+    while start < lines.len() {
+        if !lines[start].code.starts_with('(') {
+            start += 1;
+            continue;
+        }
+        let level = lines[start].level;
+        let mut end = start;
+        while end < lines.len()
+            && lines[end].level == level
+            && (lines[end].code.starts_with('(') || lines[end].code.starts_with('>'))
+        {
+            end += 1;
+        }
+        let width = lines[start..end]
+            .iter()
+            .filter_map(|line| get_conclusion(&line.code).map(|(name, _)| name.len()))
+            .max()
+            .unwrap_or(0);
+        let mut anchor: Option<usize> = None;
+        for line in lines[start..end].iter_mut() {
+            if let Some((name, rest)) = get_conclusion(&line.code) {
+                line.code = format!("({:width$} > {}", name, rest, width = width);
+            }
+            if line.code.starts_with('(') {
+                anchor = line.code.find(") >").map(|pos| pos + 2);
+            } else if let Some(anchor) = anchor {
+                line.code = format!("{}{}", " ".repeat(anchor), line.code);
+            }
+        }
+        start = end;
+    }
+}
+
+fn get_conclusion(code: &str) -> Option<(String, String)> {
+    if !code.starts_with('(') {
+        return None;
+    }
+    let close = code.find(')')?;
+    let arrow = code[..close].find(" > ")?;
+    Some((
+        code[1..arrow].trim().to_owned(),
+        code[arrow + 3..].to_owned(),
+    ))
+}
+
+fn split_comment(line: &str) -> (&str, Option<String>) {
+    let mut quoted = false;
+    for (pos, c) in line.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == '#' && !quoted {
+            return (&line[..pos], Some(line[pos..].trim_end().to_owned()));
+        }
+    }
+    (line, None)
+}
+
+fn collapse(code: &str) -> String {
+    let mut output = String::new();
+    let mut quoted = false;
+    let mut space = false;
+    for c in code.trim().chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            space = true;
+            continue;
+        }
+        if space {
+            output.push(' ');
+            space = false;
+        }
+        output.push(c);
+    }
+    output
+}
@@ -0,0 +1,548 @@
+use super::{
+    arg_option_embedded, arg_option_files, arg_option_overwrite, arg_option_templates, config,
+    helpers::{self, stubs},
+    protocol::{
+        attributes::Attributes, codec::Codec, encryption::Encryption,
+        store::Store as ProtocolStore, Parser as ProtocolParser,
+    },
+    render::{rust::RustRender, typescript::TypescriptRender, Render},
+    watch,
+    workflow::Parser as WorkflowParser,
+    workflow_render::{crates::render_crates, puml::PumlRender, render as workflow_render},
+    Args, Command,
+};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+pub mod keys {
+    pub const CONFIG: &str = "--config";
+    pub const C: &str = "-c";
+    pub const SIGNATURE: &str = "--signature";
+    pub const SIG: &str = "-sig";
+    pub const STUBS: &str = "--stubs";
+    pub const CRATES: &str = "--crates";
+    pub const VIEWS: &str = "--views";
+    pub const UNKNOWN_FIELDS: &str = "--unknown-fields";
+    pub const COMPATIBLE: &str = "--compatible";
+    pub const WATCH: &str = "--watch";
+    pub const W: &str = "-w";
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub src: Option<PathBuf>,
+    pub workflow: Option<PathBuf>,
+    pub dest_rs: Option<PathBuf>,
+    pub dest_ts: Option<PathBuf>,
+    pub dest_consumer: Option<PathBuf>,
+    pub dest_producer: Option<PathBuf>,
+    pub dest_uml: Option<PathBuf>,
+    pub dest_crates: Option<PathBuf>,
+    pub signature: u16,
+    pub overwrite: bool,
+    pub embedded: bool,
+    pub templates: Option<PathBuf>,
+    pub stubs: stubs::Mode,
+    pub attributes: Attributes,
+    pub views: bool,
+    pub unknown_fields: bool,
+    pub config: Option<PathBuf>,
+    pub compatible: Vec<Compatible>,
+}
+
+/// Previous version of protocol and workflow, which is declared as wire-compatible with
+/// current one. Defined as "<protocol>[,<workflow>]" or as path to IR (*.json)
+#[derive(Debug, Clone)]
+pub enum Compatible {
+    /// Protocol file and workflow file; if workflow isn't defined, current one is used
+    Sources(PathBuf, Option<PathBuf>),
+    /// IR, which includes workflow (clibri ir --workflow ...)
+    Ir(PathBuf),
+}
+
+impl Compatible {
+    pub fn parse(base: &Path, value: &str) -> Self {
+        let mut paths = value.splitn(2, ',').map(|path| base.join(path.trim()));
+        let src = paths.next().unwrap_or_else(|| base.to_path_buf());
+        if src.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            Compatible::Ir(src)
+        } else {
+            Compatible::Sources(src, paths.next())
+        }
+    }
+
+    pub fn get_paths(&self) -> Vec<&PathBuf> {
+        match self {
+            Compatible::Sources(src, workflow) => {
+                let mut paths = vec![src];
+                if let Some(workflow) = workflow.as_ref() {
+                    paths.push(workflow);
+                }
+                paths
+            }
+            Compatible::Ir(ir) => vec![ir],
+        }
+    }
+
+    /// Returns hashes of protocol and workflow
+    pub fn get_hashes(&self, current_workflow: &Path) -> Result<(String, String), String> {
+        match self {
+            Compatible::Sources(src, workflow) => {
+                let mut protocol = ProtocolParser::new(src.clone())
+                    .parse()
+                    .map_err(|errs| errs.join("\n"))?;
+                let workflow = WorkflowParser::new(
+                    workflow
+                        .clone()
+                        .unwrap_or_else(|| current_workflow.to_path_buf()),
+                )?
+                .parse(&mut protocol)?;
+                Ok((protocol.get_hash(), workflow.get_hash()))
+            }
+            Compatible::Ir(path) => {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| format!("Fail to read IR {}: {}", path.to_string_lossy(), e))?;
+                let ir: Value = serde_json::from_str(&content)
+                    .map_err(|e| format!("Fail to parse IR {}: {}", path.to_string_lossy(), e))?;
+                match (
+                    ir["protocol"]["hash"].as_str(),
+                    ir["workflow"]["hash"].as_str(),
+                ) {
+                    (Some(protocol), Some(workflow)) => {
+                        Ok((protocol.to_owned(), workflow.to_owned()))
+                    }
+                    _ => Err(format!(
+                        "IR {} doesn't have hashes of protocol and workflow. IR should be created with workflow",
+                        path.to_string_lossy()
+                    )),
+                }
+            }
+        }
+    }
+}
+
+impl Options {
+    /// Reads clibri.toml (defined with --config or found in pwd) and applies
+    /// keys from command line over it
+    pub fn from_args(pwd: &Path, args: &mut Args) -> Result<Self, String> {
+        let mut options = if let Some(path) = args.path(pwd, &[keys::CONFIG, keys::C])? {
+            config::load(&path)?
+        } else if let Some(path) = config::find(pwd) {
+            config::load(&path)?
+        } else {
+            Options::default()
+        };
+        use arg_option_files::keys as files;
+        if let Some(path) = args.path(pwd, &[files::SOURCE, files::SRC, files::S])? {
+            options.src = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::WORKFLOW, files::WF])? {
+            options.workflow = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::DESTINATION_RS, files::DEST_RS, files::RS])? {
+            options.dest_rs = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::DESTINATION_TS, files::DEST_TS, files::TS])? {
+            options.dest_ts = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::CONSUMER_DEST, files::CD])? {
+            options.dest_consumer = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::PRODUCER_DEST, files::PD])? {
+            options.dest_producer = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[files::PUML, files::D])? {
+            options.dest_uml = Some(path);
+        }
+        if let Some(path) = args.path(pwd, &[keys::CRATES])? {
+            options.dest_crates = Some(path);
+        }
+        if let Some(path) = args.path(
+            pwd,
+            &[
+                arg_option_templates::keys::TEMPLATES,
+                arg_option_templates::keys::TPL,
+            ],
+        )? {
+            options.templates = Some(path);
+        }
+        if let Some(signature) = args.value(&[keys::SIGNATURE, keys::SIG])? {
+            options.signature = signature
+                .parse::<u16>()
+                .map_err(|e| format!("Invalid signature {}: {}", signature, e))?;
+        }
+        if let Some(mode) = args.value(&[keys::STUBS])? {
+            options.stubs = stubs::Mode::parse(&mode)?;
+        }
+        if args.flag(&[
+            arg_option_overwrite::keys::OVERWRITE,
+            arg_option_overwrite::keys::OW,
+            arg_option_overwrite::keys::O,
+        ]) {
+            options.overwrite = true;
+        }
+        if args.flag(&[
+            arg_option_embedded::keys::EMBEDDED,
+            arg_option_embedded::keys::EM,
+            arg_option_embedded::keys::E,
+        ]) {
+            options.embedded = true;
+        }
+        if args.flag(&[keys::VIEWS]) {
+            options.views = true;
+        }
+        if args.flag(&[keys::UNKNOWN_FIELDS]) {
+            options.unknown_fields = true;
+        }
+        let mut compatible: Vec<Compatible> = vec![];
+        while let Some(value) = args.value(&[keys::COMPATIBLE])? {
+            compatible.push(Compatible::parse(pwd, &value));
+        }
+        if !compatible.is_empty() {
+            options.compatible = compatible;
+        }
+        Ok(options)
+    }
+
+    pub fn get_src(&self) -> Result<&PathBuf, String> {
+        if let Some(src) = self.src.as_ref() {
+            Ok(src)
+        } else {
+            Err(format!(
+                "Protocol file isn't defined. Use {} or create {}",
+                arg_option_files::keys::SOURCE,
+                config::FILE_NAME
+            ))
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let src = self.get_src()?;
+        if !src.exists() {
+            return Err(format!(
+                "Source file doesn't exist. Path: {}",
+                src.to_string_lossy()
+            ));
+        }
+        if let Some(workflow) = self.workflow.as_ref() {
+            if !workflow.exists() {
+                return Err(format!(
+                    "Workflow source file doesn't exist. Path: {}",
+                    workflow.to_string_lossy()
+                ));
+            }
+        } else if self.dest_uml.is_some() {
+            return Err(String::from("PUML scheme can be created only based on workflow scheme. Please define path to workflow scheme"));
+        } else if self.dest_crates.is_some() {
+            return Err(String::from("Standalone crates can be created only based on workflow scheme. Please define path to workflow scheme"));
+        } else if !self.compatible.is_empty() {
+            return Err(String::from("Compatible versions can be defined only with workflow scheme. Please define path to workflow scheme"));
+        }
+        for path in self.compatible.iter().flat_map(|c| c.get_paths()) {
+            if !path.exists() {
+                return Err(format!(
+                    "File of compatible version doesn't exist. Path: {}",
+                    path.to_string_lossy()
+                ));
+            }
+        }
+        if let Some(templates) = self.templates.as_ref() {
+            if !templates.is_dir() {
+                return Err(format!(
+                    "Templates folder doesn't exist. Path: {}",
+                    templates.to_string_lossy()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks options against settings of protocol, which come from workflow
+    pub fn validate_store(&self, store: &ProtocolStore) -> Result<(), String> {
+        if self.views {
+            if store.get_codec() != Codec::Binary {
+                return Err(format!(
+                    "Views can be used only with {} codec",
+                    Codec::Binary.as_str()
+                ));
+            }
+            if store.get_encryption() != Encryption::None {
+                return Err(String::from("Views cannot be used with encryption"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_compatible_hashes(&self, workflow: &Path) -> Result<Vec<(String, String)>, String> {
+        self.compatible
+            .iter()
+            .map(|compatible| compatible.get_hashes(workflow))
+            .collect()
+    }
+}
+
+pub fn generate(options: &Options) -> Result<(), String> {
+    options.validate()?;
+    if let Some(templates) = options.templates.as_ref() {
+        helpers::template::set_overrides(templates);
+    }
+    helpers::stubs::set_mode(options.stubs);
+    let src = options.get_src()?;
+    let t_parsing = Instant::now();
+    let mut protocol: ProtocolParser = ProtocolParser::new(src.clone());
+    let mut protocol_store = protocol.parse().map_err(|errs| errs.join("\n"))?;
+    protocol_store.set_defaults(options.attributes.clone())?;
+    protocol_store.set_views(options.views);
+    protocol_store.set_unknown_fields(options.unknown_fields);
+    println!(
+        "[OK][{}ms] parsed {:?}",
+        t_parsing.elapsed().as_millis(),
+        src
+    );
+    // Workflow defines codec and encryption, so options, which depend on them, are checked
+    // after it's parsed, but before anything is written
+    let workflow_store = if let Some(workflow_path) = options.workflow.as_ref() {
+        let mut workflow: WorkflowParser = WorkflowParser::new(workflow_path.to_owned())?;
+        let mut workflow_store = workflow.parse(&mut protocol_store)?;
+        workflow_store.set_compatible(options.get_compatible_hashes(workflow_path)?);
+        Some(workflow_store)
+    } else {
+        None
+    };
+    options.validate_store(&protocol_store)?;
+    if let Some(workflow_store) = workflow_store {
+        if let Some(uml_path) = options.dest_uml.as_ref() {
+            (PumlRender::new()).render(uml_path, &workflow_store, &mut protocol_store)?;
+        } else if let Some(crates_path) = options.dest_crates.as_ref() {
+            render_crates(
+                crates_path,
+                workflow_store,
+                &mut protocol_store,
+                options.signature,
+            )?;
+        } else {
+            workflow_render(
+                options.dest_consumer.clone(),
+                options.dest_producer.clone(),
+                workflow_store,
+                &mut protocol_store,
+                options.signature,
+                None,
+            )?;
+        }
+    }
+    if let Some(dest) = options.dest_rs.as_ref() {
+        clean(dest, options.overwrite)?;
+        RustRender::new(options.embedded, options.signature).render(&mut protocol_store, dest)?;
+    }
+    if let Some(dest) = options.dest_ts.as_ref() {
+        clean(dest, options.overwrite)?;
+        TypescriptRender::new(options.embedded, options.signature)
+            .render(&mut protocol_store, dest)?;
+    }
+    Ok(())
+}
+
+fn clean(dest: &Path, overwrite: bool) -> Result<(), String> {
+    if helpers::fs::is_capturing() {
+        return Ok(());
+    }
+    if dest.exists() && !overwrite {
+        return Err(format!(
+            "File {:?} exists. Use key \"overwrite\" to overwrite file. -h to get more info",
+            dest
+        ));
+    } else if dest.exists() {
+        println!("[INFO] {:?} will be overwritten", dest);
+        if let Err(err) = helpers::fs::remove_file(dest) {
+            return Err(format!("Fail to remove file {:?} due error: {}", dest, err));
+        } else {
+            println!("[INFO] {:?} clean", dest);
+        }
+    }
+    Ok(())
+}
+
+pub struct Generate {}
+
+impl Command for Generate {
+    fn name(&self) -> &'static str {
+        super::names::GENERATE
+    }
+
+    fn desc(&self) -> &'static str {
+        "generates protocol and workflow implementation"
+    }
+
+    fn run(&self, pwd: &Path, mut args: Args) -> Result<(), String> {
+        let watch = args.flag(&[keys::WATCH, keys::W]);
+        let mut raw = args.to_vec();
+        let options = Options::from_args(pwd, &mut args)?;
+        args.done()?;
+        if watch {
+            options.validate()?;
+            if !options.overwrite {
+                // Output of previous iteration should be overwritten
+                raw.push(arg_option_overwrite::keys::OVERWRITE.to_owned());
+            }
+            watch::watch(pwd, raw)
+        } else {
+            generate(&options)
+        }
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!(
+                "Usage: clibri {} [options]\n\nWithout options settings are taken from {} in current folder. Keys overwrite values of config file.\n",
+                self.name(),
+                config::FILE_NAME
+            ),
+            get_sources_help(),
+            get_render_help(),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!(
+                    "{} ({}, {})",
+                    arg_option_overwrite::keys::OVERWRITE,
+                    arg_option_overwrite::keys::OW,
+                    arg_option_overwrite::keys::O
+                )),
+                helpers::output::desk("overwrite destination files"),
+            ),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!("{} <mode>", keys::STUBS)),
+                helpers::output::desk(&format!(
+                    "what to do with handler stubs, which don't match workflow: {} (default), {} (writes *.new file), {} (updates signature)",
+                    stubs::modes::REPORT,
+                    stubs::modes::NEW,
+                    stubs::modes::PATCH
+                )),
+            ),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!("{} <protocol>[,<workflow>]", keys::COMPATIBLE)),
+                helpers::output::desk("previous version, which is declared as wire-compatible; producer accepts consumers of it. Path to IR (*.json) can be used instead. Can be repeated"),
+            ),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!("{} ({})", keys::WATCH, keys::W)),
+                helpers::output::desk("regenerates on changes of protocol, workflow or config files. Output is always overwritten"),
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/// Help for keys, which are shared by commands working with sources
+pub fn get_sources_help() -> String {
+    use arg_option_files::keys as files;
+    [
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", keys::CONFIG, keys::C)),
+            helpers::output::desk(&format!(
+                "path to config file. Default: ./{}",
+                config::FILE_NAME
+            )),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({}, {})", files::SOURCE, files::SRC, files::S)),
+            helpers::output::desk("path to protocol file"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", files::WORKFLOW, files::WF)),
+            helpers::output::desk("path to workflow file"),
+        ),
+    ]
+    .join("\n")
+}
+
+/// Help for keys, which define output of generation
+pub fn get_render_help() -> String {
+    use arg_option_files::keys as files;
+    [
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!(
+                "{} ({}, {})",
+                files::DESTINATION_RS,
+                files::DEST_RS,
+                files::RS
+            )),
+            helpers::output::desk("path to destination rs (rust) file"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!(
+                "{} ({}, {})",
+                files::DESTINATION_TS,
+                files::DEST_TS,
+                files::TS
+            )),
+            helpers::output::desk("path to destination ts (typescript) file"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", files::CONSUMER_DEST, files::CD)),
+            helpers::output::desk("path to destination for consumer's code"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", files::PRODUCER_DEST, files::PD)),
+            helpers::output::desk("path to destination for producer's code"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", files::PUML, files::D)),
+            helpers::output::desk("path to destination of PUML scheme"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} <dir>", keys::CRATES)),
+            helpers::output::desk("renders producer and consumer as standalone crates with shared protocol crate. Name of folder is used as prefix of crates' names. Optional parts of protocol (compression) are cargo features"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!(
+                "{} ({}, {})",
+                arg_option_embedded::keys::EMBEDDED,
+                arg_option_embedded::keys::EM,
+                arg_option_embedded::keys::E
+            )),
+            helpers::output::desk("include code to use protocol independently on clibri"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(keys::VIEWS),
+            helpers::output::desk("adds borrowed views of rust structs (StructView<'a>), which are decoded without copying. Only for binary codec"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(keys::UNKNOWN_FIELDS),
+            helpers::output::desk("keeps unknown properties of rust structs (__unknown) and writes them back on encoding, so fields of newer versions of protocol aren't lost by relays"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!(
+                "{} ({})",
+                arg_option_templates::keys::TEMPLATES,
+                arg_option_templates::keys::TPL
+            )),
+            helpers::output::desk("path to folder with overrides of templates"),
+        ),
+        format!(
+            "{}{}",
+            helpers::output::keys(&format!("{} ({})", keys::SIGNATURE, keys::SIG)),
+            helpers::output::desk("signature of protocol (u16). Default: 0"),
+        ),
+    ]
+    .join("\n")
+}
@@ -0,0 +1,70 @@
+use super::{
+    generate::{get_sources_help, Options},
+    helpers,
+    protocol::Parser as ProtocolParser,
+    workflow::Parser as WorkflowParser,
+    workflow_render::external::ir,
+    Args, Command,
+};
+use serde_json::json;
+use std::path::Path;
+
+pub mod keys {
+    pub const OUTPUT: &str = "--output";
+    pub const OUT: &str = "-out";
+}
+
+pub struct Ir {}
+
+impl Command for Ir {
+    fn name(&self) -> &'static str {
+        super::names::IR
+    }
+
+    fn desc(&self) -> &'static str {
+        "prints intermediate representation (JSON) of protocol and workflow"
+    }
+
+    fn run(&self, pwd: &Path, mut args: Args) -> Result<(), String> {
+        let output = args.path(pwd, &[keys::OUTPUT, keys::OUT])?;
+        let options = Options::from_args(pwd, &mut args)?;
+        args.done()?;
+        options.validate()?;
+        let mut protocol = ProtocolParser::new(options.get_src()?.clone())
+            .parse()
+            .map_err(|errs| errs.join("\n"))?;
+        let value = if let Some(workflow) = options.workflow.as_ref() {
+            let mut store = WorkflowParser::new(workflow.clone())?.parse(&mut protocol)?;
+            store.set_compatible(options.get_compatible_hashes(workflow)?);
+            ir::get(&protocol, &store)?
+        } else {
+            json!({
+                "version": ir::VERSION,
+                "protocol": ir::get_protocol(&protocol),
+            })
+        };
+        let content = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("Fail to serialize IR: {}", e))?;
+        if let Some(output) = output {
+            helpers::fs::write(output, content, true)
+        } else {
+            println!("{}", content);
+            Ok(())
+        }
+    }
+
+    fn get_help(&self) -> String {
+        [
+            format!("Usage: clibri {} [options]\n", self.name()),
+            get_sources_help(),
+            format!(
+                "{}{}",
+                helpers::output::keys(&format!("{} ({})", keys::OUTPUT, keys::OUT)),
+                helpers::output::desk(
+                    "path to output file. If isn't defined, IR is printed to stdout"
+                ),
+            ),
+        ]
+        .join("\n")
+    }
+}
@@ -0,0 +1,100 @@
+pub mod args;
+pub mod check;
+pub mod config;
+pub mod decode;
+pub mod diff;
+pub mod fmt;
+pub mod generate;
+pub mod ir;
+pub mod watch;
+
+use super::{
+    arg_option_embedded, arg_option_files, arg_option_help, arg_option_overwrite,
+    arg_option_templates, helpers, protocol, render, workflow, workflow_render,
+};
+use args::Args;
+use std::path::Path;
+
+pub trait Command {
+    fn name(&self) -> &'static str;
+    fn desc(&self) -> &'static str;
+    fn run(&self, pwd: &Path, args: Args) -> Result<(), String>;
+    fn get_help(&self) -> String;
+}
+
+pub mod names {
+    pub const GENERATE: &str = "generate";
+    pub const CHECK: &str = "check";
+    pub const DIFF: &str = "diff";
+    pub const FMT: &str = "fmt";
+    pub const IR: &str = "ir";
+    pub const DECODE: &str = "decode";
+    pub const HELP: &str = "help";
+}
+
+pub fn get_commands() -> Vec<Box<dyn Command>> {
+    vec![
+        Box::new(generate::Generate {}),
+        Box::new(check::Check {}),
+        Box::new(diff::Diff {}),
+        Box::new(fmt::Fmt {}),
+        Box::new(ir::Ir {}),
+        Box::new(decode::Decode {}),
+    ]
+}
+
+pub fn get(name: &str) -> Option<Box<dyn Command>> {
+    get_commands()
+        .into_iter()
+        .find(|command| command.name() == name)
+}
+
+pub fn is_command(name: &str) -> bool {
+    name == names::HELP || get(name).is_some()
+}
+
+pub fn run(pwd: &Path, mut args: Vec<String>) -> Result<(), String> {
+    if args.is_empty() {
+        print_help();
+        return Ok(());
+    }
+    let name = args.remove(0);
+    if name == names::HELP {
+        if let Some(name) = args.first() {
+            if let Some(command) = get(name) {
+                println!("{}", command.get_help());
+                return Ok(());
+            } else {
+                return Err(format!("Unknown command: {}", name));
+            }
+        }
+        print_help();
+        return Ok(());
+    }
+    let command = get(&name).ok_or(format!("Unknown command: {}", name))?;
+    let mut args = Args::new(args);
+    if args.flag(&[arg_option_help::keys::HELP, arg_option_help::keys::H]) {
+        println!("{}", command.get_help());
+        return Ok(());
+    }
+    command.run(pwd, args)
+}
+
+pub fn print_help() {
+    println!("Usage: clibri <command> [options]\n\nCommands:");
+    for command in get_commands() {
+        println!(
+            "{}{}",
+            helpers::output::keys(command.name()),
+            helpers::output::desk(command.desc())
+        );
+    }
+    println!(
+        "{}{}",
+        helpers::output::keys(names::HELP),
+        helpers::output::desk("shows help for command: clibri help <command>")
+    );
+    println!(
+        "\nWithout command clibri accepts flat keys (-s, -wf, -rs, ...). Use --help to list them."
+    );
+}
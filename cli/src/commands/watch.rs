@@ -0,0 +1,128 @@
+use super::{config, generate::Options, Args};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    process,
+    sync::mpsc::channel,
+    time::Duration,
+};
+
+// Editors usually produce a couple of events on save; all of them are handled as one change
+const DEBOUNCE_MS: u64 = 200;
+
+/// Runs generation in a separate process on each change of sources. Parser stops
+/// a process on error, that's why generation cannot be done in scope of watcher.
+pub fn watch(pwd: &Path, args: Vec<String>) -> Result<(), String> {
+    let (tx, rx) = channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Fail to create watcher: {}", e))?;
+    let mut folders: HashSet<PathBuf> = HashSet::new();
+    let mut files: HashSet<PathBuf> = HashSet::new();
+    let mut reload = true;
+    loop {
+        if reload {
+            // Config file could define other sources
+            match get_files(pwd, &args) {
+                Ok(actual) => {
+                    files = actual;
+                    for file in files.iter() {
+                        println!("[WATCH] {}", file.to_string_lossy());
+                    }
+                }
+                Err(e) => eprintln!("[WATCH] {}", e),
+            }
+            let actual: HashSet<PathBuf> = files
+                .iter()
+                .filter_map(|file| file.parent().map(|p| p.to_path_buf()))
+                .collect();
+            for folder in folders.difference(&actual) {
+                let _ = watcher.unwatch(folder);
+            }
+            for folder in actual.difference(&folders) {
+                // Folder is watched instead of file, because editors often replace file on saving
+                watcher
+                    .watch(folder, RecursiveMode::NonRecursive)
+                    .map_err(|e| format!("Fail to watch {}: {}", folder.to_string_lossy(), e))?;
+            }
+            folders = actual;
+        }
+        run(pwd, &args);
+        let changed = loop {
+            let event = rx
+                .recv()
+                .map_err(|e| format!("Watcher has been stopped: {}", e))?;
+            let changed = get_changed(&event, &files);
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        while rx.recv_timeout(Duration::from_millis(DEBOUNCE_MS)).is_ok() {}
+        reload = changed
+            .iter()
+            .any(|file| file.file_name().and_then(|f| f.to_str()) == Some(config::FILE_NAME));
+    }
+}
+
+fn get_files(pwd: &Path, args: &[String]) -> Result<HashSet<PathBuf>, String> {
+    let options = Options::from_args(pwd, &mut Args::new(args.to_vec()))?;
+    let mut files: HashSet<PathBuf> = HashSet::new();
+    files.insert(canonicalize(options.get_src()?));
+    if let Some(workflow) = options.workflow.as_ref() {
+        files.insert(canonicalize(workflow));
+    }
+    if let Some(config) = options.config.as_ref() {
+        files.insert(canonicalize(config));
+    }
+    for path in options.compatible.iter().flat_map(|c| c.get_paths()) {
+        files.insert(canonicalize(path));
+    }
+    Ok(files)
+}
+
+fn get_changed(event: &notify::Result<notify::Event>, files: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => event
+            .paths
+            .iter()
+            .map(|path| canonicalize(path))
+            .filter(|path| files.contains(path))
+            .collect(),
+        Ok(_) => vec![],
+        Err(e) => {
+            eprintln!("[WATCH] error: {}", e);
+            vec![]
+        }
+    }
+}
+
+fn run(pwd: &Path, args: &[String]) {
+    let executable = match env::current_exe() {
+        Ok(executable) => executable,
+        Err(e) => {
+            eprintln!("[WATCH] fail to detect executable: {}", e);
+            return;
+        }
+    };
+    println!("[WATCH] generating...");
+    match process::Command::new(executable)
+        .arg(super::names::GENERATE)
+        .args(args)
+        .current_dir(pwd)
+        .status()
+    {
+        Ok(status) if status.success() => println!("[WATCH] done; waiting for changes"),
+        Ok(status) => {
+            println!(
+                "[WATCH] generation has been failed ({}); waiting for changes",
+                status
+            )
+        }
+        Err(e) => eprintln!("[WATCH] fail to start generation: {}", e),
+    }
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
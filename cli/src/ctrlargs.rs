@@ -11,6 +11,10 @@ pub mod arg_option_files;
 pub mod arg_option_help;
 #[path = "./arguments/args_option_overwrite.rs"]
 pub mod arg_option_overwrite;
+#[path = "./arguments/args_option_templates.rs"]
+pub mod arg_option_templates;
+#[path = "./commands/mod.rs"]
+pub mod commands;
 #[path = "./protocol/parser.rs"]
 pub mod protocol;
 #[path = "./protocol/render/render.rs"]
@@ -26,12 +30,14 @@ pub enum EArgumentsNames {
     OptionOverwrite,
     OptionEmbedded,
     OptionHelp,
+    OptionTemplates,
 }
 
 pub enum EArgumentsValues {
     Files((PathBuf, PathBuf, PathBuf)),
     OptionOverwrite(bool),
     OptionEmbedded(bool),
+    OptionTemplates(Option<PathBuf>),
     Empty(()),
 }
 pub trait CtrlArg {
@@ -96,6 +102,14 @@ impl CtrlArgs {
                 &ctrls,
             )),
         );
+        ctrls.insert(
+            EArgumentsNames::OptionTemplates,
+            Box::new(arg_option_templates::ArgsOptionTemplates::new(
+                &pwd,
+                args.clone(),
+                &ctrls,
+            )),
+        );
         ctrls.insert(
             EArgumentsNames::OptionFiles,
             Box::new(arg_option_files::ArgsOptionFiles::new(&pwd, args, &ctrls)),
@@ -153,6 +167,15 @@ impl CtrlArgs {
                 EArgumentsValues::OptionEmbedded(em) => {
                     println!("{:?} = {}", EArgumentsNames::OptionEmbedded, em)
                 }
+                EArgumentsValues::OptionTemplates(templates) => {
+                    if let Some(templates) = templates {
+                        println!(
+                            "{:?} = {}",
+                            EArgumentsNames::OptionTemplates,
+                            templates.as_path().display()
+                        )
+                    }
+                }
                 EArgumentsValues::Files((src, dest_rs, dest_ts)) => {
                     println!(
                         "{:?}: src = {}",
@@ -180,6 +203,7 @@ impl CtrlArgs {
             Box::new(arg_option_help::get_cleaner()),
             Box::new(arg_option_embedded::get_cleaner()),
             Box::new(arg_option_overwrite::get_cleaner()),
+            Box::new(arg_option_templates::get_cleaner()),
             Box::new(arg_option_files::get_cleaner()),
         ];
         for cleaner in cleaners {
@@ -14,4 +14,6 @@ pub const OPEN_SQ_BRACKET: char = '[';
 pub const CLOSE_SQ_BRACKET: char = ']';
 pub const ARROW: char = '>';
 pub const EXCLAMATION: char = '!';
-pub const CARET: char = '\n';
\ No newline at end of file
+pub const CARET: char = '\n';
+pub const COMMA: char = ',';
+pub const QUOTES: char = '"';
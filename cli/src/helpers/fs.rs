@@ -1,17 +1,80 @@
+use super::{stubs, template};
+use std::io::prelude::*;
 use std::{
-    fs::{
-        OpenOptions,
-        remove_file,
-    },
-    path::{
-        PathBuf,
-    }
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
 };
-use std::io::prelude::*;
+
+// If capturing is enabled, files are collected in memory instead of writing on disk
+static CAPTURED: Mutex<Option<Vec<(PathBuf, String)>>> = Mutex::new(None);
+
+pub fn capture() {
+    if let Ok(mut captured) = CAPTURED.lock() {
+        *captured = Some(vec![]);
+    }
+}
+
+pub fn take_captured() -> Vec<(PathBuf, String)> {
+    if let Ok(mut captured) = CAPTURED.lock() {
+        captured.take().unwrap_or_default()
+    } else {
+        vec![]
+    }
+}
+
+fn store(filename: &Path, content: &str) -> bool {
+    if let Ok(mut captured) = CAPTURED.lock() {
+        if let Some(files) = captured.as_mut() {
+            files.retain(|(path, _)| path != filename);
+            files.push((filename.to_path_buf(), content.to_owned()));
+            return true;
+        }
+    }
+    false
+}
+
+pub fn is_capturing() -> bool {
+    CAPTURED.lock().map(|c| c.is_some()).unwrap_or(false)
+}
+
+pub fn create_dir_all(path: &Path) -> io::Result<()> {
+    if is_capturing() {
+        Ok(())
+    } else {
+        fs::create_dir_all(path)
+    }
+}
+
+pub fn remove_file(path: &Path) -> io::Result<()> {
+    if is_capturing() {
+        Ok(())
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+pub fn get_header(filename: &Path) -> String {
+    match filename.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext == "rs" || ext == "ts" => template::get(&format!("header/{}", ext), ""),
+        _ => String::new(),
+    }
+}
 
 pub fn write(filename: PathBuf, content: String, overwrite: bool) -> Result<(), String> {
+    stubs::known(&filename);
+    let content = format!("{}{}", get_header(&filename), content);
+    save(filename, content, overwrite)
+}
+
+/// Writes content as it is, without header
+pub fn save(filename: PathBuf, content: String, overwrite: bool) -> Result<(), String> {
+    if store(&filename, &content) {
+        return Ok(());
+    }
     if filename.exists() && overwrite {
-        if let Err(e) = remove_file(filename.clone()) {
+        if let Err(e) = fs::remove_file(filename.clone()) {
             return Err(e.to_string());
         }
     } else if filename.exists() && !overwrite {
@@ -22,15 +85,14 @@ pub fn write(filename: PathBuf, content: String, overwrite: bool) -> Result<(),
         .create(true)
         .open(filename.clone())
     {
-        Ok(mut file) => if let Err(e) = file.write_all(content.as_bytes()) {
-            Err(e.to_string())
-        } else {
-            println!(
-                "[OK] {:?} has been written",
-                filename
-            );
-            Ok(())
+        Ok(mut file) => {
+            if let Err(e) = file.write_all(content.as_bytes()) {
+                Err(e.to_string())
+            } else {
+                println!("[OK] {:?} has been written", filename);
+                Ok(())
+            }
         }
-        Err(e) => Err(e.to_string())
+        Err(e) => Err(e.to_string()),
     }
-} 
\ No newline at end of file
+}
@@ -10,6 +10,11 @@ pub fn get(filename: &Path) -> Result<String, String> {
     Ok(HEXUPPER.encode(digest.as_ref()))
 }
 
+pub fn get_from_str(src: &str) -> Result<String, String> {
+    let digest = calc_sha256_digest(src.as_bytes())?;
+    Ok(HEXUPPER.encode(digest.as_ref()))
+}
+
 fn calc_sha256_digest<R: Read>(mut reader: R) -> Result<Digest, String> {
     let mut context = Context::new(&SHA256);
     let mut buffer = [0; 1024];
@@ -4,3 +4,5 @@ pub mod hash;
 pub mod output;
 pub mod render;
 pub mod string;
+pub mod stubs;
+pub mod template;
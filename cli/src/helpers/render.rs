@@ -19,6 +19,21 @@ pub fn inject_tabs(num: usize, input: String) -> String {
     output
 }
 
+/// Adds tabs to each line of input; empty lines stay empty
+pub fn indent(num: usize, input: &str) -> String {
+    input
+        .split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", tabs(num), line)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 pub fn inject_tabs_except(num: usize, input: String, exceptions: Vec<usize>) -> String {
     let mut output: String = String::new();
     let lines = input.split('\n').collect::<Vec<&str>>();
@@ -0,0 +1,278 @@
+use super::fs;
+use similar::TextDiff;
+use std::{
+    collections::HashSet,
+    fs as std_fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+// Handler stubs are owned by user: they are rendered once and never overwritten. If
+// stub exists, signature of it (everything between imports and body of handler) is
+// compared with actual one.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    // Only reports outdated stubs
+    #[default]
+    Report,
+    // Writes actual stub next to outdated one as <file>.new
+    New,
+    // Replaces signature block of outdated stub
+    Patch,
+}
+
+impl Mode {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            modes::REPORT => Ok(Mode::Report),
+            modes::NEW => Ok(Mode::New),
+            modes::PATCH => Ok(Mode::Patch),
+            _ => Err(format!(
+                "Invalid stubs mode \"{}\". Available: {}, {}, {}",
+                value,
+                modes::REPORT,
+                modes::NEW,
+                modes::PATCH
+            )),
+        }
+    }
+}
+
+pub mod modes {
+    pub const REPORT: &str = "report";
+    pub const NEW: &str = "new";
+    pub const PATCH: &str = "patch";
+}
+
+const NEW_EXTENSION: &str = "new";
+const HANDLERS: &[&str] = &[
+    "pub async fn ",
+    "pub fn ",
+    "export async function ",
+    "export function ",
+];
+const IMPORTS: &[&str] = &["use ", "pub use ", "import "];
+
+struct State {
+    mode: Mode,
+    stubs: HashSet<PathBuf>,
+    known: HashSet<PathBuf>,
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+fn with_state<T, F: FnOnce(&mut State) -> T>(cb: F) -> Option<T> {
+    if let Ok(mut state) = STATE.lock() {
+        let state = state.get_or_insert_with(|| State {
+            mode: Mode::default(),
+            stubs: HashSet::new(),
+            known: HashSet::new(),
+        });
+        Some(cb(state))
+    } else {
+        None
+    }
+}
+
+pub fn set_mode(mode: Mode) {
+    with_state(|state| state.mode = mode);
+}
+
+/// Marks file as generated; such files are never reported as orphaned
+pub fn known(filename: &Path) {
+    with_state(|state| state.known.insert(filename.to_path_buf()));
+}
+
+/// Writes stub if it doesn't exist; otherwise checks whether its signature is still actual
+pub fn write(filename: PathBuf, content: String) -> Result<(), String> {
+    let mode = with_state(|state| {
+        state.stubs.insert(filename.clone());
+        state.mode
+    })
+    .unwrap_or_default();
+    if !filename.exists() {
+        return fs::write(filename, content, true);
+    }
+    let actual = std_fs::read_to_string(&filename)
+        .map_err(|e| format!("Fail to read {}: {}", filename.to_string_lossy(), e))?;
+    let content = format!("{}{}", fs::get_header(&filename), content);
+    let (current, expected) = match (Signature::new(&actual), Signature::new(&content)) {
+        (Some(current), Some(expected)) => (current, expected),
+        _ => {
+            println!("[SKIP]: {}", filename.to_string_lossy());
+            return Ok(());
+        }
+    };
+    if normalize(current.block(&actual)) == normalize(expected.block(&content)) {
+        println!("[SKIP]: {}", filename.to_string_lossy());
+        return Ok(());
+    }
+    println!(
+        "[STALE]: {} doesn't match workflow",
+        filename.to_string_lossy()
+    );
+    print!(
+        "{}",
+        TextDiff::from_lines(current.block(&actual), expected.block(&content))
+            .unified_diff()
+            .header("current", "expected")
+    );
+    match mode {
+        Mode::Report => {
+            println!(
+                "[STALE]: use --stubs {} to write actual stub as *.{} or --stubs {} to update signature",
+                modes::NEW,
+                NEW_EXTENSION,
+                modes::PATCH
+            );
+            Ok(())
+        }
+        Mode::New => {
+            let mut dest = filename.into_os_string();
+            dest.push(format!(".{}", NEW_EXTENSION));
+            fs::save(PathBuf::from(dest), content, true)
+        }
+        Mode::Patch => {
+            let existing = current.imports(&actual);
+            let imports = expected
+                .imports(&content)
+                .into_iter()
+                .map(|(from, to)| &content[from..to])
+                .filter(|import| {
+                    !existing
+                        .iter()
+                        .any(|(from, to)| normalize(&actual[*from..*to]) == normalize(import))
+                })
+                .map(|import| format!("{}\n", import.trim()))
+                .collect::<String>();
+            // Missing imports are added after existing one
+            let at = existing.last().map(|(_, to)| *to).unwrap_or(current.start);
+            let patched = format!(
+                "{}{}{}{}{}",
+                &actual[..at],
+                imports,
+                &actual[at..current.start],
+                expected.block(&content),
+                &actual[current.end..]
+            );
+            fs::save(filename, patched, true)
+        }
+    }
+}
+
+/// Reports files in folders of stubs, which don't belong to any entity of workflow
+pub fn report_orphans() {
+    let orphans = with_state(|state| {
+        let mut orphans: Vec<PathBuf> = vec![];
+        let folders: HashSet<(PathBuf, String)> = state
+            .stubs
+            .iter()
+            .filter_map(|stub| {
+                match (stub.parent(), stub.extension().and_then(|ext| ext.to_str())) {
+                    (Some(parent), Some(ext)) => Some((parent.to_path_buf(), ext.to_owned())),
+                    _ => None,
+                }
+            })
+            .collect();
+        for (folder, ext) in folders.iter() {
+            if let Ok(entries) = std_fs::read_dir(folder) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_file()
+                        && path.extension().and_then(|e| e.to_str()) == Some(ext.as_str())
+                        && !state.stubs.contains(&path)
+                        && !state.known.contains(&path)
+                    {
+                        orphans.push(path);
+                    }
+                }
+            }
+        }
+        orphans.sort();
+        orphans
+    })
+    .unwrap_or_default();
+    for orphan in orphans.iter() {
+        println!(
+            "[ORPHAN]: {} doesn't belong to any entity of workflow and can be removed",
+            orphan.to_string_lossy()
+        );
+    }
+}
+
+// Positions (in bytes) of signature block: from first statement after imports
+// till opening of handler's body
+struct Signature {
+    start: usize,
+    end: usize,
+}
+
+impl Signature {
+    fn new(content: &str) -> Option<Self> {
+        let mut start: Option<usize> = None;
+        let mut import = false;
+        let mut handler = false;
+        let mut pos = 0;
+        for line in content.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if start.is_none() {
+                if import || IMPORTS.iter().any(|i| trimmed.starts_with(i)) {
+                    import = !trimmed.ends_with(';');
+                } else if !(trimmed.is_empty()
+                    || trimmed.starts_with("//")
+                    || trimmed.starts_with("/*")
+                    || trimmed.starts_with('*'))
+                {
+                    start = Some(pos);
+                }
+            }
+            if start.is_some() {
+                if HANDLERS.iter().any(|h| trimmed.starts_with(h)) {
+                    handler = true;
+                }
+                if handler && trimmed.ends_with('{') {
+                    return start.map(|start| Signature {
+                        start,
+                        end: pos + line.len(),
+                    });
+                }
+            }
+            pos += line.len();
+        }
+        None
+    }
+
+    fn block<'a>(&self, content: &'a str) -> &'a str {
+        &content[self.start..self.end]
+    }
+
+    fn imports(&self, content: &str) -> Vec<(usize, usize)> {
+        let mut imports: Vec<(usize, usize)> = vec![];
+        let mut from: Option<usize> = None;
+        let mut pos = 0;
+        for line in content[..self.start].split_inclusive('\n') {
+            let trimmed = line.trim();
+            if from.is_none() && IMPORTS.iter().any(|i| trimmed.starts_with(i)) {
+                from = Some(pos);
+            }
+            pos += line.len();
+            if let Some(start) = from {
+                if trimmed.ends_with(';') {
+                    imports.push((start, pos));
+                    from = None;
+                }
+            }
+        }
+        imports
+    }
+}
+
+// Formatting doesn't matter: whitespaces and trailing commas are ignored
+fn normalize(block: &str) -> String {
+    let mut output: String = block.chars().filter(|c| !c.is_whitespace()).collect();
+    for closing in [")", "}", ">", "]"] {
+        output = output.replace(&format!(",{}", closing), closing);
+    }
+    output
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+pub const EXTENSION: &str = "tpl";
+
+static OVERRIDES: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn set_overrides(dir: &Path) {
+    if let Ok(mut overrides) = OVERRIDES.lock() {
+        *overrides = Some(dir.to_path_buf());
+    }
+}
+
+pub fn get_override_file(name: &str) -> Option<PathBuf> {
+    if let Ok(overrides) = OVERRIDES.lock() {
+        overrides
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}", name, EXTENSION)))
+    } else {
+        None
+    }
+}
+
+pub fn get(name: &str, default: &str) -> String {
+    if let Some(file) = get_override_file(name) {
+        if file.exists() {
+            match fs::read_to_string(&file) {
+                Ok(content) => {
+                    return content;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[WARN] Fail to read template {}; default would be used. Error: {}",
+                        file.to_string_lossy(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+    default.to_owned()
+}
+
+pub struct Template {
+    content: String,
+    values: HashMap<String, String>,
+}
+
+impl Template {
+    pub fn new(name: &str, default: &str) -> Self {
+        Template {
+            content: get(name, default),
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.values.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = self.content.clone();
+        // Conditional blocks: [[?key]] ... [[/key]] stays only if key has not empty value
+        while let Some(start) = output.find("[[?") {
+            let key = match output[start + 3..].find("]]") {
+                Some(end) => output[start + 3..start + 3 + end].to_owned(),
+                None => break,
+            };
+            let open = format!("[[?{}]]", key);
+            let close = format!("[[/{}]]", key);
+            let end = match output[start..].find(&close) {
+                Some(end) => start + end,
+                None => break,
+            };
+            let inner = output[start + open.len()..end].to_owned();
+            let visible = self.values.get(&key).is_some_and(|v| !v.is_empty());
+            output = format!(
+                "{}{}{}",
+                &output[..start],
+                if visible { inner.as_str() } else { "" },
+                &output[end + close.len()..]
+            );
+        }
+        // Placeholders are substituted in one pass: inserted values aren't scanned again,
+        // so value with [[...]] inside stays as it is
+        let mut rendered = String::with_capacity(output.len());
+        let mut rest = output.as_str();
+        while let Some(start) = rest.find("[[") {
+            rendered.push_str(&rest[..start]);
+            let placeholder = rest[start + 2..]
+                .find("]]")
+                .map(|end| &rest[start + 2..start + 2 + end])
+                .and_then(|key| self.values.get(key).map(|value| (key, value)));
+            match placeholder {
+                Some((key, value)) => {
+                    rendered.push_str(value);
+                    rest = &rest[start + key.len() + 4..];
+                }
+                None => {
+                    // Not a placeholder (for example vec![[[key]]]): only first bracket is taken
+                    rendered.push('[');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
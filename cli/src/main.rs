@@ -16,6 +16,18 @@ macro_rules! stop {
 }
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    args.remove(0);
+    if args.is_empty() || ctrlargs::commands::is_command(&args[0]) {
+        let pwd = match std::env::current_dir() {
+            Ok(pwd) => pwd,
+            Err(e) => stop!("Fail to detect pwd folder: {}", e),
+        };
+        if let Err(err) = ctrlargs::commands::run(&pwd, args) {
+            stop!("{}\n", err);
+        }
+        return;
+    }
     let ctrl: ctrlargs::CtrlArgs = ctrlargs::CtrlArgs::new();
     if ctrl.has_errors() {
         std::process::exit(1);
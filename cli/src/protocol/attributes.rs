@@ -0,0 +1,92 @@
+pub mod annotations {
+    pub const DERIVE: &str = "derive";
+    pub const ATTR: &str = "attr";
+}
+
+// Derives, which are always added to structs and enums
+pub const BASE: &[&str] = &["Debug", "Clone", "PartialEq"];
+// Derives, which cannot be implemented for types with f32/f64
+pub const NOT_FOR_FLOAT: &[&str] = &["Eq", "Hash", "Ord"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    pub derive: Vec<String>,
+    pub attrs: Vec<String>,
+}
+
+impl Attributes {
+    pub fn is_empty(&self) -> bool {
+        self.derive.is_empty() && self.attrs.is_empty()
+    }
+
+    pub fn add_derive(&mut self, derive: &str) -> Result<(), String> {
+        if derive.is_empty()
+            || derive.starts_with(|c: char| c.is_ascii_digit())
+            || !derive
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        {
+            return Err(format!("Invalid derive: \"{}\"", derive));
+        }
+        if !self.derive.iter().any(|d| d == derive) {
+            self.derive.push(derive.to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn add_attr(&mut self, attr: &str) -> Result<(), String> {
+        let attr = attr.trim();
+        if attr.is_empty() {
+            return Err(String::from("Attribute cannot be empty"));
+        }
+        // Both forms are accepted: "serde(default)" and "#[serde(default)]"
+        let attr = if attr.starts_with("#[") {
+            attr.to_owned()
+        } else {
+            format!("#[{}]", attr)
+        };
+        if !self.attrs.contains(&attr) {
+            self.attrs.push(attr);
+        }
+        Ok(())
+    }
+
+    /// Returns derives, which require type without floats
+    pub fn get_not_for_float(&self) -> Vec<String> {
+        self.derive
+            .iter()
+            .filter(|d| NOT_FOR_FLOAT.contains(&get_name(d)))
+            .cloned()
+            .collect()
+    }
+}
+
+// Derive can be defined with path (std::hash::Hash)
+fn get_name(derive: &str) -> &str {
+    derive.rsplit("::").next().unwrap_or(derive)
+}
+
+// Base derives are added to all types, so they are always available
+pub fn is_base(derive: &str) -> bool {
+    BASE.contains(&get_name(derive))
+}
+
+/// Checks, whether derive is in list; derive can be defined with or without path
+pub fn contains(derives: &[String], derive: &str) -> bool {
+    derives
+        .iter()
+        .any(|current| get_name(current) == get_name(derive))
+}
+
+/// Joins derives without duplicates
+pub fn merge(sources: &[&[String]]) -> Vec<String> {
+    let mut derive: Vec<String> = BASE.iter().map(|d| d.to_string()).collect();
+    for source in sources {
+        for d in source.iter() {
+            if !contains(&derive, d) {
+                derive.push(d.clone());
+            }
+        }
+    }
+    derive
+}
@@ -0,0 +1,46 @@
+pub mod names {
+    pub const BINARY: &str = "binary";
+    pub const MSGPACK: &str = "msgpack";
+    pub const CBOR: &str = "cbor";
+    pub const COMPACT: &str = "compact";
+}
+
+// Encoding of message's body. Header of package is same for all codecs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codec {
+    // Own layout: u16 id + length rank + body per field
+    #[default]
+    Binary,
+    MsgPack,
+    Cbor,
+    // Own layout with varint ids, lengths and integers
+    Compact,
+}
+
+impl Codec {
+    pub fn parse(value: &str) -> Result<Codec, String> {
+        match value {
+            names::BINARY => Ok(Codec::Binary),
+            names::MSGPACK => Ok(Codec::MsgPack),
+            names::CBOR => Ok(Codec::Cbor),
+            names::COMPACT => Ok(Codec::Compact),
+            _ => Err(format!(
+                "Invalid codec: {}. Available: {}, {}, {}, {}",
+                value,
+                names::BINARY,
+                names::MSGPACK,
+                names::CBOR,
+                names::COMPACT
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Codec::Binary => names::BINARY,
+            Codec::MsgPack => names::MSGPACK,
+            Codec::Cbor => names::CBOR,
+            Codec::Compact => names::COMPACT,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+pub mod names {
+    pub const NONE: &str = "none";
+    pub const AES_GCM: &str = "aes_gcm";
+    pub const CHACHA20_POLY1305: &str = "chacha20_poly1305";
+}
+
+// Authenticated encryption of message's body. Session keys are derived from X25519
+// exchange, which is a part of hash check (HashRequest / HashResponse)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encryption {
+    #[default]
+    None,
+    AesGcm,
+    ChaCha20Poly1305,
+}
+
+impl Encryption {
+    pub fn parse(value: &str) -> Result<Encryption, String> {
+        match value {
+            names::NONE => Ok(Encryption::None),
+            names::AES_GCM => Ok(Encryption::AesGcm),
+            names::CHACHA20_POLY1305 => Ok(Encryption::ChaCha20Poly1305),
+            _ => Err(format!(
+                "Invalid encryption: {}. Available: {}, {}, {}",
+                value,
+                names::NONE,
+                names::AES_GCM,
+                names::CHACHA20_POLY1305
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encryption::None => names::NONE,
+            Encryption::AesGcm => names::AES_GCM,
+            Encryption::ChaCha20Poly1305 => names::CHACHA20_POLY1305,
+        }
+    }
+
+    // Name of variant of EncryptionAlgorithm in generated rust code
+    pub fn as_variant(&self) -> &'static str {
+        match self {
+            Encryption::None => "None",
+            Encryption::AesGcm => "AesGcm",
+            Encryption::ChaCha20Poly1305 => "ChaCha20Poly1305",
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{stop, PrimitiveTypes, Store};
+use super::{stop, Attributes, PrimitiveTypes, Store};
 
 #[derive(Debug, Clone)]
 pub struct EnumItem {
@@ -61,6 +61,7 @@ pub struct Enum {
     pub name: String,
     pub variants: Vec<EnumItem>,
     pub path: Vec<String>,
+    pub attributes: Attributes,
     current: Option<EnumItem>,
 }
 
@@ -73,6 +74,7 @@ impl Enum {
             variants: vec![],
             current: None,
             path,
+            attributes: Attributes::default(),
         }
     }
 
@@ -1,3 +1,6 @@
+pub mod attributes;
+pub mod codec;
+pub mod encryption;
 pub mod entities;
 pub mod enums;
 pub mod fields;
@@ -7,6 +10,9 @@ pub mod structs;
 pub mod types;
 
 use super::{helpers, stop};
+use attributes::Attributes;
+use codec::Codec;
+use encryption::Encryption;
 use entities::Entities;
 use enums::Enum;
 use fields::Field;
@@ -27,6 +33,7 @@ enum ENext {
     Repeated(usize),
     Optional(usize),
     PathSpliter(usize),
+    Annotation((String, Vec<String>, usize)),
     End(),
 }
 
@@ -281,6 +288,24 @@ impl Parser {
                             expectation = vec![EExpectation::FieldType];
                             offset
                         }
+                        ENext::Annotation((name, args, offset)) => {
+                            if !is_in(&expectation, &EExpectation::StructDef)
+                                && !is_in(&expectation, &EExpectation::EnumDef)
+                            {
+                                errs.push(format!(
+                                    "Unexpecting next step: {:?}. Value: @{}",
+                                    expectation, name
+                                ));
+                                break;
+                            }
+                            if let Err(e) = store.add_annotation(&name, &args) {
+                                errs.push(e);
+                                break;
+                            }
+                            // Annotation can be followed only by struct or enum
+                            expectation = vec![EExpectation::StructDef, EExpectation::EnumDef];
+                            offset
+                        }
                         ENext::End() => {
                             break;
                         }
@@ -301,7 +326,7 @@ impl Parser {
             }
         }
         if errs.is_empty() {
-            match store.order() {
+            match store.order().and_then(|_| store.validate_attributes()) {
                 Ok(_) => Ok(store),
                 Err(e) => Err(vec![e]),
             }
@@ -336,6 +361,9 @@ impl Parser {
             if char.is_ascii_whitespace() && str.is_empty() {
                 continue;
             }
+            if char == chars::AT && str.is_empty() {
+                return self.annotation(&content[pass - 1..], pass - 1);
+            }
             let mut breakable: Option<char> = None;
             if break_chars.iter().any(|&c| c == char) {
                 breakable = Some(char);
@@ -393,6 +421,62 @@ impl Parser {
         }
     }
 
+    // Reads @name(arg, arg) or @name("quoted arg"); offset is position of @ in content
+    fn annotation(&self, content: &str, offset: usize) -> Result<ENext, ENextErr> {
+        let open = if let Some(open) = content.find(chars::OPEN_BRACKET) {
+            open
+        } else {
+            return Err(ENextErr::NotSupported(String::from(
+                "annotation should be defined as @name(...)",
+            )));
+        };
+        let name = content[1..open].trim().to_owned();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(ENextErr::NotSupported(format!(
+                "invalid name of annotation: @{}",
+                name
+            )));
+        }
+        let mut args: Vec<String> = vec![];
+        let mut arg = String::new();
+        let mut quoted = false;
+        let mut escaped = false;
+        for (pos, char) in content[open + 1..].char_indices() {
+            if quoted {
+                if escaped {
+                    arg.push(char);
+                    escaped = false;
+                } else if char == '\\' {
+                    escaped = true;
+                } else if char == chars::QUOTES {
+                    quoted = false;
+                } else {
+                    arg.push(char);
+                }
+            } else if char == chars::QUOTES {
+                quoted = true;
+            } else if char == chars::COMMA || char == chars::CLOSE_BRACKET {
+                if !arg.trim().is_empty() {
+                    args.push(arg.trim().to_owned());
+                }
+                arg = String::new();
+                if char == chars::CLOSE_BRACKET {
+                    return Ok(ENext::Annotation((
+                        name,
+                        args,
+                        offset + open + 1 + pos + 1,
+                    )));
+                }
+            } else {
+                arg.push(char);
+            }
+        }
+        Err(ENextErr::NotSupported(format!(
+            "annotation @{} isn't closed",
+            name
+        )))
+    }
+
     pub fn get_content(&self, target: PathBuf) -> Result<String, String> {
         if !target.exists() {
             Err(format!(
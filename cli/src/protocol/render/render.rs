@@ -9,6 +9,7 @@ use std::path::Path;
 pub enum Target {
     Rust,
     TypeScript,
+    External(String),
 }
 
 pub trait Render {
@@ -1,4 +1,7 @@
-use super::helpers;
+use super::helpers::{self, render::indent, template::Template};
+use super::protocol::attributes;
+use super::protocol::codec::Codec;
+use super::protocol::encryption::Encryption;
 use super::protocol::enums::{Enum, EnumItem};
 use super::protocol::fields::Field;
 use super::protocol::groups::Group;
@@ -8,442 +11,1262 @@ use super::protocol::types::PrimitiveTypes;
 use super::Render;
 use regex::Regex;
 use std::{include_str, path::Path};
+
+mod templates {
+    pub const PROTOCOL: &str = r#"[[includes]][[features]][[json]]
+pub const CODEC: &str = "[[codec]]";
+[[codec_impl]][[views]][[messages]][[entities]][[buffer]]
+pub fn hash() -> String { String::from("[[hash]]") }
+"#;
+    pub const REQUIRED_FEATURE: &str = r#"#[cfg(not(feature = "[[feature]]"))]
+compile_error!("Feature \"[[feature]]\" is required by protocol");
+"#;
+    pub const COMPRESSION_FEATURE: &str = r#"#[cfg(feature = "[[feature]]")]
+mod compression_algorithms {
+    use super::*;
+[[algorithms]]
+}
+#[cfg(feature = "[[feature]]")]
+pub use compression_algorithms::*;
+// Without compression peer is told, that compressed packages cannot be read
+#[cfg(not(feature = "[[feature]]"))]
+pub const COMPRESSION_FLAGS: u8 = 0;
+#[cfg(not(feature = "[[feature]]"))]
+fn compress_body(_flag: u8, _body: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+#[cfg(not(feature = "[[feature]]"))]
+fn decompress_body(_flag: u8, _body: &[u8], _len: usize) -> Result<Vec<u8>, String> {
+    Err(String::from("Compression is disabled with feature \"[[feature]]\""))
+}
+"#;
+    pub const ENCRYPTION: &str = r#"[[encryption]]pub const ENCRYPTION_ALGORITHM: EncryptionAlgorithm = EncryptionAlgorithm::[[algorithm]];
+const ENCRYPTION_UNENCRYPTED: &[u32] = &[[[unencrypted]]];
+"#;
+    pub const GROUP: &str = r#"pub mod [[name]] {
+    use super::*;
+    use std::io::Cursor;
+    use bytes::{ Buf, BufMut };
+[[messages]][[entities]]
+}
+"#;
+    pub const MESSAGES: &str = r#"#[derive([[derive]])]
+pub enum AvailableMessages {[[messages]][[?unknown]]
+    Unknown { id: u32, body: Vec<u8> },[[/unknown]]
+}
+[[json]]
+"#;
+    pub const MESSAGE: &str = r#"[[name]]([[kind]]),"#;
+    pub const STRUCT: &str = r#"#[derive([[derive]])]
+[[attrs]]pub struct [[name]] {[[fields]][[?unknown]]
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,[[/unknown]]
+}"#;
+    pub const STRUCT_FIELD: &str = r#"pub [[name]]: [[kind]],"#;
+    pub const STRUCT_IMPL: &str = r#"#[allow(unused_variables)]
+#[allow(unused_mut)]
+impl StructDecode for [[name]] {
+    fn get_id() -> u32 {
+        [[id]]
+    }
+    fn defaults() -> [[name]] {
+        [[name]] {[[defaults]][[?unknown]]
+            __unknown: vec![],[[/unknown]]
+        }
+    }
+[[decode]]
+}
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+impl StructEncode for [[name]] {
+    fn get_id(&self) -> u32 { [[id]] }
+    fn get_signature(&self) -> u16 { [[signature]] }
+[[encode]]
+}
+impl PackingStruct for [[name]] {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}[[?default]]
+impl Default for [[name]] {
+    fn default() -> Self {
+        <[[name]] as StructDecode>::defaults()
+    }
+}[[/default]]"#;
+    pub const STRUCT_DEFAULT: &str = r#"[[name]] {[[fields]][[?unknown]]
+    __unknown: vec![],[[/unknown]]
+}"#;
+    pub const FIELD_DEFAULT: &str = r#"[[name]]: [[value]],"#;
+    pub const STRUCT_DECODE: &str = r#"fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {[[fields]][[?unknown]]
+    self.__unknown = storage.unknown([[known]]);[[/unknown]]
+    Ok(())
+}"#;
+    pub const FIELD_DECODE: &str = r#"self.[[name]] = match [[kind]]::get_from_storage(Source::Storage(&mut storage), Some([[id]])) {
+    Ok(val) => val,
+    Err(e) => { return Err(e) },
+};"#;
+    pub const FIELD_DECODE_OPTIONAL_ENUM: &str = r#"if let Some(buf) = storage.get([[id]]) {
+    if buf.is_empty() {
+        self.[[name]] = None;
+    } else {
+        self.[[name]] = match [[kind]]::get_from_storage(Source::Storage(&mut storage), Some([[id]])) {
+            Ok(val) => Some(val),
+            Err(e) => { return Err(e) },
+        };
+    }
+} else {
+    return Err("Buffer for property [[name]] isn't found".to_string());
+}"#;
+    pub const STRUCT_ENCODE: &str = r#"fn abduct(&mut self) -> Result<Vec<u8>, String> {
+    encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+}
+fn encoded_len(&self) -> usize {
+    let mut len: usize = 0;[[len]][[?unknown]]
+    len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();[[/unknown]]
+    len
+}
+fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {[[encode]][[?unknown]]
+    for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }[[/unknown]]
+    Ok(())
+}"#;
+    pub const FIELD_LEN: &str = r#"len += self.[[name]].len_to_store(Some([[id]]));"#;
+    pub const FIELD_ENCODE: &str = r#"self.[[name]].put_to_store(Some([[id]]), buf)?;"#;
+    pub const FIELD_LEN_OPTIONAL_ENUM: &str = r#"len += match &self.[[name]] { Some(val) => val.len_to_store(Some([[id]])), None => get_empty_len_to_store(Some([[id]])) };"#;
+    pub const FIELD_ENCODE_OPTIONAL_ENUM: &str = r#"match &self.[[name]] { Some(val) => val.put_to_store(Some([[id]]), buf)?, None => put_empty_to_store(Some([[id]]), buf) };"#;
+    pub const ENUM: &str = r#"#[derive([[derive]])]
+[[attrs]]pub enum [[name]] {[[variants]]
+    Defaults,
+}"#;
+    pub const ENUM_VARIANT: &str = r#"[[name]]([[kind]]),"#;
+    pub const ENUM_IMPL: &str = r#"impl EnumDecode for [[name]] {
+    fn get_id(&self) -> u32 { [[id]] }
+[[decode]]
+}
+impl EnumEncode for [[name]] {
+    fn get_id(&self) -> u32 { [[id]] }
+    fn get_signature(&self) -> u16 { [[signature]] }
+[[encode]]
+}
+impl PackingEnum for [[name]] {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}"#;
+    pub const ENUM_DECODE: &str = r#"fn extract(buf: Vec<u8>) -> Result<[[name]], String> {
+    if buf.len() <= sizes::U16_LEN {
+        return Err(String::from("Fail to extract value for [[name]] because buffer too small"));
+    }
+    let mut cursor: Cursor<&[u8]> = Cursor::new(&buf);
+    let index = cursor.get_u16_le();
+    let mut body_buf = vec![0; buf.len() - sizes::U16_LEN];
+    body_buf.copy_from_slice(&buf[sizes::U16_LEN..]);
+    match index {[[variants]]
+        _ => Err(String::from("Fail to find relevant value for [[name]]")),
+    }
+}"#;
+    pub const ENUM_DECODE_VARIANT: &str = r#"[[index]] => match [[kind]]::decode(&body_buf) {
+    Ok(v) => Ok([[enum]]::[[name]](v)),
+    Err(e) => Err(e)
+},"#;
+    pub const ENUM_ENCODE: &str = r#"fn abduct(&mut self) -> Result<Vec<u8>, String> {
+    encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+}
+fn encoded_len(&self) -> usize {
+    match self {[[len]]
+        _ => 0,
+    }
+}
+fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+    match self {[[encode]]
+        _ => Err(String::from("Not supportable option")),
+    }
+}"#;
+    pub const ENUM_VARIANT_LEN: &str =
+        r#"Self::[[name]](v) => sizes::U16_LEN + v.len_to_store(None),"#;
+    pub const ENUM_VARIANT_ENCODE: &str = r#"Self::[[name]](v) => {
+    buf.put_u16_le([[index]]);
+    v.put_to_store(None, buf)
+}"#;
+    pub const STRUCT_JSON: &str = r#"impl ToJson for [[name]] {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![[[to_json]]
+        ])
+    }
+}
+impl FromJson for [[name]] {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for [[name]]"));
+        }
+        Ok([[name]] {[[from_json]][[?unknown]]
+            __unknown: vec![],[[/unknown]]
+        })
+    }
+}"#;
+    pub const STRUCT_JSON_TO: &str =
+        r#"(String::from("[[name]]"), self.[[name]].to_json_value()),"#;
+    pub const STRUCT_JSON_FROM: &str = r#"[[name]]: FromJson::from_json_value(value.get("[[name]]")).map_err(|e| format!("[[struct]].[[name]]: {}", e))?,"#;
+    pub const ENUM_JSON: &str = r#"impl ToJson for [[name]] {
+    fn to_json_value(&self) -> JsonValue {
+        let (name, value) = match self {[[to_json]]
+            Self::Defaults => ("Defaults", JsonValue::Null),
+        };
+        JsonValue::Object(vec![(String::from(name), value)])
+    }
+}
+impl FromJson for [[name]] {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Object(fields) if fields.len() == 1 => {
+                let (name, value) = &fields[0];
+                match name.as_str() {[[from_json]]
+                    "Defaults" => Ok(Self::Defaults),
+                    _ => Err(format!("Unknown variant {} of [[name]]", name)),
+                }
+            }
+            _ => Err(String::from("Expecting object with one field for [[name]]")),
+        }
+    }
+}"#;
+    pub const ENUM_JSON_TO: &str =
+        r#"Self::[[name]](value) => ("[[name]]", value.to_json_value()),"#;
+    pub const ENUM_JSON_FROM: &str = r#""[[name]]" => Ok(Self::[[name]](FromJson::from_json_value(value).map_err(|e| format!("[[enum]].[[name]]: {}", e))?)),"#;
+    pub const STRUCT_VALUE: &str = r#"impl ToCodecValue for [[name]] {
+    fn to_codec_value(&self) -> CodecValue {
+        codec_map(vec![[[to_value]]
+        ], [[unknown_fields]])
+    }
+}
+impl FromCodecValue for [[name]] {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        if !matches!(value, CodecValue::Map(_)) {
+            return Err(String::from("Expecting map for [[name]]"));
+        }
+        Ok([[name]] {[[from_value]][[?unknown]]
+            __unknown: codec_unknown(value, [[known]]),[[/unknown]]
+        })
+    }
+}"#;
+    pub const STRUCT_VALUE_TO: &str =
+        r#"(String::from("[[name]]"), self.[[name]].to_codec_value()),"#;
+    pub const STRUCT_VALUE_FROM: &str = r#"[[name]]: FromCodecValue::from_codec_value(value.get("[[name]]")).map_err(|e| format!("[[struct]].[[name]]: {}", e))?,"#;
+    pub const ENUM_VALUE: &str = r#"impl ToCodecValue for [[name]] {
+    fn to_codec_value(&self) -> CodecValue {
+        let (name, value) = match self {[[to_value]]
+            Self::Defaults => ("Defaults", CodecValue::Nil),
+        };
+        CodecValue::Map(vec![(String::from(name), value)])
+    }
+}
+impl FromCodecValue for [[name]] {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        match value {
+            CodecValue::Map(entries) if entries.len() == 1 => {
+                let (name, value) = &entries[0];
+                match name.as_str() {[[from_value]]
+                    "Defaults" => Ok(Self::Defaults),
+                    _ => Err(format!("Unknown variant {} of [[name]]", name)),
+                }
+            }
+            _ => Err(String::from("Expecting map with one entry for [[name]]")),
+        }
+    }
+}"#;
+    pub const ENUM_VALUE_TO: &str =
+        r#"Self::[[name]](value) => ("[[name]]", value.to_codec_value()),"#;
+    pub const ENUM_VALUE_FROM: &str = r#""[[name]]" => Ok(Self::[[name]](FromCodecValue::from_codec_value(value).map_err(|e| format!("[[enum]].[[name]]: {}", e))?)),"#;
+    pub const STRUCT_COMPACT: &str = r#"#[allow(unused_variables)]
+impl CompactValue for [[name]] {
+    fn compact_len(&self) -> usize {
+        [[unknown_len]][[len]]
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {[[write]][[?unknown]]
+        compact_write_unknown(buf, &self.__unknown);[[/unknown]]
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        let fields = compact_fields(buf)?;
+        Ok([[name]] {[[read]][[?unknown]]
+            __unknown: compact_unknown(&fields, [[known]]),[[/unknown]]
+        })
+    }
+}"#;
+    pub const STRUCT_COMPACT_LEN: &str = r#"+ compact_field_len([[id]], &self.[[name]])"#;
+    pub const STRUCT_COMPACT_WRITE: &str = r#"compact_field(buf, [[id]], &self.[[name]]);"#;
+    pub const STRUCT_COMPACT_READ: &str = r#"[[name]]: compact_get(&fields, [[id]]).map_err(|e| format!("[[struct]].[[name]]: {}", e))?,"#;
+    pub const ENUM_COMPACT: &str = r#"impl CompactValue for [[name]] {
+    fn compact_len(&self) -> usize {
+        match self {[[len]]
+            Self::Defaults => 0,
+        }
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {
+        match self {[[write]]
+            Self::Defaults => {}
+        }
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        if buf.is_empty() {
+            return Ok(Self::Defaults);
+        }
+        let mut pos: usize = 0;
+        let index = compact_read_varint(buf, &mut pos)?;
+        match index {[[read]]
+            _ => Err(format!("Unknown variant {} of [[name]]", index)),
+        }
+    }
+}"#;
+    pub const ENUM_COMPACT_LEN: &str =
+        r#"Self::[[name]](value) => compact_varint_len([[index]]) + value.compact_len(),"#;
+    pub const ENUM_COMPACT_WRITE: &str = r#"Self::[[name]](value) => {
+    compact_write_varint([[index]], buf);
+    value.compact_write(buf);
+}"#;
+    pub const ENUM_COMPACT_READ: &str = r#"[[index]] => Ok(Self::[[name]](CompactValue::compact_read(&buf[pos..]).map_err(|e| format!("[[enum]].[[name]]: {}", e))?)),"#;
+    pub const STRUCT_VIEW: &str = r#"#[derive(Debug, Clone)]
+pub struct [[name]]View<'a> {[[fields]][[?unknown]]
+    pub __unknown: Vec<(u16, &'a [u8])>,[[/unknown]][[?phantom]]
+    _buf: std::marker::PhantomData<&'a ()>,[[/phantom]]
+}
+impl<'a> [[name]]View<'a> {
+    pub fn get_id() -> u32 {
+        [[id]]
+    }
+    pub fn to_owned(&self) -> Result<[[name]], String> {
+        self.view_to_owned()
+    }
+}
+#[allow(unused_variables)]
+impl<'a> ViewDecode<'a> for [[name]]View<'a> {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        let fields = ViewFields::new(buf)?;
+        Ok([[name]]View {[[view]][[?unknown]]
+            __unknown: fields.unknown([[known]]),[[/unknown]][[?phantom]]
+            _buf: std::marker::PhantomData,[[/phantom]]
+        })
+    }
+}
+impl<'a> ViewItem<'a> for [[name]]View<'a> {
+    fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String> {
+        view_prefixed(buf, pos)
+    }
+}
+impl<'a> ViewOwned for [[name]]View<'a> {
+    type Owned = [[name]];
+    fn view_to_owned(&self) -> Result<[[name]], String> {
+        Ok([[name]] {[[owned]][[?unknown]]
+            __unknown: self
+                .__unknown
+                .iter()
+                .map(|(id, body)| (*id, body.to_vec()))
+                .collect(),[[/unknown]]
+        })
+    }
+}"#;
+    pub const STRUCT_VIEW_GET: &str =
+        r#"[[name]]: fields.get([[id]]).map_err(|e| format!("[[struct]].[[name]]: {}", e))?,"#;
+    pub const STRUCT_VIEW_OWNED: &str = r#"[[name]]: self.[[name]].view_to_owned()?,"#;
+    pub const ENUM_VIEW: &str = r#"[[?declare]]#[derive(Debug, Clone)]
+pub enum [[name]]View<'a> {[[variants]]
+    Defaults,
+}
+impl<'a> [[name]]View<'a> {
+    pub fn get_id() -> u32 {
+        [[id]]
+    }
+    pub fn to_owned(&self) -> Result<[[name]], String> {
+        self.view_to_owned()
+    }
+}
+[[/declare]]impl<'a> ViewDecode<'a> for [[view]] {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        if buf.len() <= 2 {
+            return Err(String::from("Fail to extract value for [[name]] because buffer too small"));
+        }
+        let body = &buf[2..];
+        match u16::from_le_bytes([buf[0], buf[1]]) {[[decode]]
+            _ => Err(String::from("Fail to find relevant value for [[name]]")),
+        }
+    }
+}
+impl<'a> ViewItem<'a> for [[view]] {
+    fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String> {
+        view_prefixed(buf, pos)
+    }
+}
+impl<'a> ViewOwned for [[view]] {
+    type Owned = [[name]];
+    fn view_to_owned(&self) -> Result<[[name]], String> {
+        match self {[[owned]]
+            Self::Defaults => Ok([[name]]::Defaults),
+        }
+    }
+}"#;
+    pub const ENUM_VIEW_DECODE: &str = r#"[[index]] => Ok(Self::[[name]](ViewDecode::view(body).map_err(|e| format!("[[enum]].[[name]]: {}", e))?)),"#;
+    pub const ENUM_VIEW_OWNED: &str =
+        r#"Self::[[name]](value) => Ok([[enum]]::[[name]](value.view_to_owned()?)),"#;
+    pub const CODEC_DECODE: &str = r#"[[?storage]]fn extract_from_storage(&mut self, _storage: Storage) -> Result<(), String> {
+    Err(String::from("[[name]] is encoded with [[codec]] and cannot be read from storage"))
+}
+[[/storage]]fn extract(buf: Vec<u8>) -> Result<[[name]], String> {
+    [[read]].map_err(|e| format!("[[name]]: {}", e))
+}"#;
+    pub const CODEC_ABDUCT: &str = r#"fn abduct(&mut self) -> Result<Vec<u8>, String> {
+    Ok([[write]])
+}
+fn encoded_len(&self) -> usize {
+    [[len]]
+}
+fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+    [[write_into]];
+    Ok(())
+}"#;
+    pub const MESSAGES_JSON: &str = r#"impl ToJson for AvailableMessages {
+    fn to_json_value(&self) -> JsonValue {
+        match [[target]] {[[to_json]][[?unknown]]
+            Self::Unknown { id, body } => json_tagged(
+                "Unknown",
+                JsonValue::Object(vec![
+                    (String::from("id"), id.to_json_value()),
+                    (String::from("body"), body.to_json_value()),
+                ]),
+            ),[[/unknown]]
+        }
+    }
+}
+impl FromJson for AvailableMessages {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        let name = json_tag(value)?;
+        match name {[[from_json]][[?unknown]]
+            "Unknown" => {
+                let message = value.get("message");
+                Ok(Self::Unknown {
+                    id: FromJson::from_json_value(message.get("id"))?,
+                    body: FromJson::from_json_value(message.get("body"))?,
+                })
+            }[[/unknown]]
+            _ => Err(format!("Unknown message {}", name)),
+        }
+    }
+}"#;
+    pub const MESSAGE_JSON_TO: &str =
+        r#"Self::[[name]](m) => json_tagged("[[path]]", m.to_json_value()),"#;
+    pub const MESSAGE_JSON_FROM: &str =
+        r#""[[path]]" => Ok(Self::[[name]](FromJson::from_json_value(value.get("message"))?)),"#;
+    pub const GROUP_JSON_TO: &str = r#"Self::[[name]](m) => m.to_json_value(),"#;
+    pub const GROUP_JSON_FROM: &str = r#"name if name.starts_with("[[path]].") => Ok(Self::[[name]](FromJson::from_json_value(value)?)),"#;
+    pub const BUFFER: &str = r#"impl DecodeBuffer<AvailableMessages> for Buffer<AvailableMessages> {
+    fn get_msg(&self, id: u32, buf: &[u8]) -> Result<AvailableMessages, String> {
+        match id {[[messages]]
+            _ => Ok(AvailableMessages::Unknown { id, body: buf.to_vec() }),
+        }
+    }
+    fn get_signature(&self) -> u16 { [[signature]] }
+}
+"#;
+    pub const BUFFER_MESSAGE: &str = r#"[[id]] => match [[name]]::extract(buf.to_vec()) {
+    Ok(m) => Ok([[message]]),
+    Err(e) => Err(e),
+},"#;
+}
+
+pub mod features {
+    pub const COMPRESSION: &str = "compression";
+    pub const ENCRYPTION: &str = "encryption";
+}
+
+/// Cargo features of standalone protocol crate. Codec and encryption are defined by
+/// protocol, so only compression can be disabled
+pub fn get_features(store: &Store) -> Vec<(&'static str, bool)> {
+    let mut features = vec![];
+    if store.get_codec() != Codec::Binary {
+        features.push((store.get_codec().as_str(), true));
+    }
+    if store.get_encryption() != Encryption::None {
+        features.push((features::ENCRYPTION, true));
+    }
+    features.push((features::COMPRESSION, false));
+    features
+}
+
 pub struct RustRender {
     embedded: bool,
     signature: u16,
+    features: bool,
 }
 
 impl RustRender {
-    fn groups(&self, group: &Group, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}pub mod {} {{\n", self.spaces(level), group.name);
-        body = format!("{}{}use super::*;\n", body, self.spaces(level + 1));
-        body = format!("{}{}use std::io::Cursor;\n", body, self.spaces(level + 1));
-        body = format!("{}{}use bytes::{{ Buf }};\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}",
-            body,
-            self.get_messages_list(Some(group), &mut store.clone(), level + 1)
-        );
+    /// Optional parts of protocol are gated by cargo features (see get_features)
+    pub fn with_features(mut self) -> Self {
+        self.features = true;
+        self
+    }
+
+    fn groups(&self, group: &Group, store: &mut Store) -> String {
+        let mut entities = vec![];
         for enum_id in &group.enums {
             if let Some(enums) = store.get_enum(*enum_id) {
-                body = format!("{}\n{}", body, self.enums(&enums, level + 1));
+                entities.push(self.enums(&enums, store));
             }
         }
         for struct_id in &group.structs {
             if let Some(strct) = store.get_struct(*struct_id) {
-                body = format!(
-                    "{}\n{}",
-                    body,
-                    self.structs(&strct, &mut store.clone(), level + 1)
-                );
+                entities.push(self.structs(&strct, &mut store.clone()));
             }
         }
-        let childs = store.get_child_groups(group.id);
-        for group in childs {
-            body = format!(
-                "{}\n{}",
-                body,
-                self.groups(&group, &mut store.clone(), level + 1)
-            );
+        for child in store.get_child_groups(group.id) {
+            entities.push(self.groups(&child, &mut store.clone()));
         }
-        format!("{}\n{}}}\n", body, self.spaces(level))
+        Template::new("protocol/rust/group", templates::GROUP)
+            .set("name", &group.name)
+            .set(
+                "messages",
+                &indent(1, &self.get_messages_list(Some(group), &mut store.clone())),
+            )
+            .set("entities", &self.lines(&entities, 1))
+            .render()
     }
 
-    fn structs(&self, strct: &Struct, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}#[derive(Debug, Clone, PartialEq)]\n", self.spaces(level));
-        body = format!("{}{}pub struct {} {{", body, self.spaces(level), strct.name);
-        for field in &strct.fields {
-            body = format!(
-                "{}\n{}{}",
-                body,
-                self.spaces(level + 1),
-                format!("pub {}: {},", field.name, self.get_declare_type_ref(field)),
-            );
-        }
-        body = format!("{}\n{}}}\n", body, self.spaces(level));
-        body = format!("{}{}#[allow(unused_variables)]\n", body, self.spaces(level));
-        body = format!("{}{}#[allow(unused_mut)]\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl StructDecode for {} {{\n",
-            body,
-            self.spaces(level),
-            strct.name
-        );
-        body = format!("{}{}fn get_id() -> u32 {{\n", body, self.spaces(level + 1));
-        body = format!("{}{}{}\n", body, self.spaces(level + 2), strct.id);
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}fn defaults() -> {} {{\n",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!("{}{}{} {{\n", body, self.spaces(level + 2), strct.name);
-        for field in &strct.fields {
-            body = format!(
-                "{}{}{}\n",
-                body,
-                self.spaces(level + 3),
-                self.field_default(field, &mut store.clone(), level + 3)
-            );
-        }
-        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        for field in &strct.fields {
-            if field.optional {
-                if let Some(id) = field.ref_type_id {
-                    if let Some(enums) = store.get_enum(id) {
-                        body = format!(
-                            "{}{}if let Some(buf) = storage.get({}) {{\n",
-                            body,
-                            self.spaces(level + 2),
-                            field.id
-                        );
-                        body = format!("{}{}if buf.is_empty() {{\n", body, self.spaces(level + 3));
-                        body = format!(
-                            "{}{}self.{} = None;\n",
-                            body,
-                            self.spaces(level + 4),
-                            field.name
-                        );
-                        body = format!("{}{}}} else {{\n", body, self.spaces(level + 3));
-                        body = format!("{}{}self.{} = match {}::get_from_storage(Source::Storage(&mut storage), Some({})) {{\n", body, self.spaces(level + 4), field.name, enums.name, field.id);
-                        body = format!("{}{}Ok(val) => Some(val),\n", body, self.spaces(level + 5));
-                        body = format!(
-                            "{}{}Err(e) => {{ return Err(e) }},\n",
-                            body,
-                            self.spaces(level + 5)
-                        );
-                        body = format!("{}{}}};\n", body, self.spaces(level + 4));
-                        body = format!("{}{}}}\n", body, self.spaces(level + 3));
-                        body = format!("{}{}}} else {{\n", body, self.spaces(level + 2));
-                        body = format!(
-                            "{}{}return Err(\"Buffer for property {} isn't found\".to_string());\n",
-                            body,
-                            self.spaces(level + 3),
-                            field.name
-                        );
-                        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-                        continue;
+    fn structs(&self, strct: &Struct, store: &mut Store) -> String {
+        let fields = strct
+            .fields
+            .iter()
+            .map(|field| {
+                Template::new("protocol/rust/struct_field", templates::STRUCT_FIELD)
+                    .set("name", &field.name)
+                    .set("kind", &self.get_declare_type_ref(field))
+                    .render()
+            })
+            .collect::<Vec<String>>();
+        let defaults = strct
+            .fields
+            .iter()
+            .map(|field| self.field_default(field, &mut store.clone()))
+            .collect::<Vec<String>>();
+        let mut body = vec![
+            Template::new("protocol/rust/struct", templates::STRUCT)
+                .set("derive", &store.get_derive(strct.id).join(", "))
+                .set("attrs", &self.attrs(store.get_attrs(strct.id)))
+                .set("name", &strct.name)
+                .set("fields", &self.lines(&fields, 1))
+                .set("unknown", self.unknown(store))
+                .render(),
+            Template::new("protocol/rust/struct_impl", templates::STRUCT_IMPL)
+                .set("name", &strct.name)
+                .set("id", &strct.id.to_string())
+                .set("signature", &self.signature.to_string())
+                .set("defaults", &self.lines(&defaults, 3))
+                .set("decode", &indent(1, &self.struct_decode(strct, store)))
+                .set("encode", &indent(1, &self.struct_abduct(strct, store)))
+                .set("unknown", self.unknown(store))
+                // Default gives a way to construct struct without listing all fields
+                .set(
+                    "default",
+                    if attributes::contains(&store.get_derive(strct.id), "Default") {
+                        ""
+                    } else {
+                        "true"
+                    },
+                )
+                .render(),
+            self.struct_json(strct, store),
+        ];
+        if store.get_codec() == Codec::Compact {
+            body.push(self.struct_compact(strct, store));
+        } else if store.get_codec() != Codec::Binary {
+            body.push(self.struct_value(strct, store));
+        } else if store.get_views() {
+            body.push(self.struct_view(strct, store));
+        }
+        self.block(&body)
+    }
+
+    fn struct_decode(&self, strct: &Struct, store: &mut Store) -> String {
+        if store.get_codec() != Codec::Binary {
+            return self.codec_decode(&strct.name, true, store);
+        }
+        let fields = strct
+            .fields
+            .iter()
+            .map(|field| {
+                if field.optional {
+                    if let Some(enums) = field.ref_type_id.and_then(|id| store.get_enum(id)) {
+                        return Template::new(
+                            "protocol/rust/field_decode_optional_enum",
+                            templates::FIELD_DECODE_OPTIONAL_ENUM,
+                        )
+                        .set("name", &field.name)
+                        .set("kind", &enums.name)
+                        .set("id", &field.id.to_string())
+                        .render();
                     }
                 }
-            }
-            body = format!(
-                "{}{}self.{} = match {}::get_from_storage(Source::Storage(&mut storage), Some({})) {{\n",
-                body,
-                self.spaces(level + 2),
-                field.name,
-                self.get_decode_type_ref(field),
-                field.id
-            );
-            body = format!("{}{}Ok(val) => val,\n", body, self.spaces(level + 3));
-            body = format!(
-                "{}{}Err(e) => {{ return Err(e) }},\n",
-                body,
-                self.spaces(level + 3)
-            );
-            body = format!("{}{}}};\n", body, self.spaces(level + 2));
-        }
-        body = format!("{}{}Ok(())\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!("{}{}#[allow(unused_variables)]\n", body, self.spaces(level));
-        body = format!("{}{}#[allow(unused_mut)]\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl StructEncode for {} {{\n",
-            body,
-            self.spaces(level),
-            strct.name
-        );
-        body = format!(
-            "{}{}fn get_id(&self) -> u32 {{ {} }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.id
-        );
-        body = format!(
-            "{}{}fn get_signature(&self) -> u16 {{ {} }}\n",
-            body,
-            self.spaces(level + 1),
-            self.signature,
-        );
-        body = format!(
-            "{}{}fn abduct(&mut self) -> Result<Vec<u8>, String> {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!(
-            "{}{}let mut buffer: Vec<u8> = vec!();\n",
-            body,
-            self.spaces(level + 2)
-        );
+                Template::new("protocol/rust/field_decode", templates::FIELD_DECODE)
+                    .set("name", &field.name)
+                    .set("kind", &self.get_decode_type_ref(field))
+                    .set("id", &field.id.to_string())
+                    .render()
+            })
+            .collect::<Vec<String>>();
+        Template::new("protocol/rust/struct_decode", templates::STRUCT_DECODE)
+            .set("fields", &self.lines(&fields, 1))
+            .set("known", &self.known_ids(strct))
+            .set("unknown", self.unknown(store))
+            .render()
+    }
+
+    fn struct_abduct(&self, strct: &Struct, store: &mut Store) -> String {
+        if store.get_codec() != Codec::Binary {
+            return self.codec_abduct(store);
+        }
+        let mut len = vec![];
+        let mut encode = vec![];
         for field in &strct.fields {
-            if field.optional {
-                if let Some(id) = field.ref_type_id {
-                    if store.get_enum(id).is_some() {
-                        body = format!(
-                            "{}{}if let Some(mut val) = self.{}.clone() {{\n",
-                            body,
-                            self.spaces(level + 2),
-                            field.name
-                        );
-                        body = format!(
-                            "{}{}match val.get_buf_to_store(Some({})) {{\n",
-                            body,
-                            self.spaces(level + 3),
-                            field.id
-                        );
-                        body = format!(
-                            "{}{}Ok(mut buf) => {{ buffer.append(&mut buf); }},\n",
-                            body,
-                            self.spaces(level + 4)
-                        );
-                        body = format!(
-                            "{}{}Err(e) => {{ return  Err(e); }},\n",
-                            body,
-                            self.spaces(level + 4)
-                        );
-                        body = format!("{}{}}};\n", body, self.spaces(level + 3));
-                        body = format!("{}{}}} else {{\n", body, self.spaces(level + 2));
-                        body = format!(
-                            "{}{}match get_empty_buffer_val(Some({})) {{\n",
-                            body,
-                            self.spaces(level + 3),
-                            field.id
-                        );
-                        body = format!(
-                            "{}{}Ok(mut buf) => {{ buffer.append(&mut buf); }},\n",
-                            body,
-                            self.spaces(level + 4)
-                        );
-                        body = format!(
-                            "{}{}Err(e) => {{ return  Err(e); }},\n",
-                            body,
-                            self.spaces(level + 4)
-                        );
-                        body = format!("{}{}}};\n", body, self.spaces(level + 3));
-                        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-                        continue;
-                    }
-                }
-            }
-            body = format!(
-                "{}{}match self.{}.get_buf_to_store(Some({})) {{\n",
-                body,
-                self.spaces(level + 2),
-                field.name,
-                field.id
-            );
-            body = format!(
-                "{}{}Ok(mut buf) => {{ buffer.append(&mut buf); }}\n",
-                body,
-                self.spaces(level + 3)
-            );
-            body = format!(
-                "{}{}Err(e) => {{ return Err(e) }},\n",
-                body,
-                self.spaces(level + 3)
-            );
-            body = format!("{}{}}};\n", body, self.spaces(level + 2));
-        }
-        body = format!("{}{}Ok(buffer)\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl PackingStruct for {} {{ }}\n",
-            body,
-            self.spaces(level),
-            strct.name
-        );
-        body
+            // Option<Enum> isn't Encode, empty property is written for None
+            let optional_enum = field.optional
+                && field
+                    .ref_type_id
+                    .is_some_and(|id| store.get_enum(id).is_some());
+            let (len_tpl, encode_tpl) = if optional_enum {
+                (
+                    Template::new(
+                        "protocol/rust/field_len_optional_enum",
+                        templates::FIELD_LEN_OPTIONAL_ENUM,
+                    ),
+                    Template::new(
+                        "protocol/rust/field_encode_optional_enum",
+                        templates::FIELD_ENCODE_OPTIONAL_ENUM,
+                    ),
+                )
+            } else {
+                (
+                    Template::new("protocol/rust/field_len", templates::FIELD_LEN),
+                    Template::new("protocol/rust/field_encode", templates::FIELD_ENCODE),
+                )
+            };
+            len.push(
+                len_tpl
+                    .set("name", &field.name)
+                    .set("id", &field.id.to_string())
+                    .render(),
+            );
+            encode.push(
+                encode_tpl
+                    .set("name", &field.name)
+                    .set("id", &field.id.to_string())
+                    .render(),
+            );
+        }
+        // Unknown properties are written back as they were gotten
+        Template::new("protocol/rust/struct_encode", templates::STRUCT_ENCODE)
+            .set("len", &self.lines(&len, 1))
+            .set("encode", &self.lines(&encode, 1))
+            .set("unknown", self.unknown(store))
+            .render()
     }
 
-    fn enums(&self, enums: &Enum, level: u8) -> String {
-        let mut body = format!("{}#[derive(Debug, Clone, PartialEq)]\n", self.spaces(level));
-        body = format!("{}{}pub enum {} {{\n", body, self.spaces(level), enums.name);
-        for item in &enums.variants {
-            let item_type = self.enum_item_type(item.clone());
-            body = format!(
-                "{}{}{},\n",
-                body,
-                self.spaces(level + 1),
-                format!(
-                    "{}({})",
-                    item.name,
-                    if item.repeated {
-                        format!("Vec<{}>", item_type)
+    // Unknown properties are kept by structs only if it's enabled with --unknown-fields
+    fn unknown(&self, store: &Store) -> &'static str {
+        if store.get_unknown_fields() {
+            "true"
+        } else {
+            ""
+        }
+    }
+
+    fn known_ids(&self, strct: &Struct) -> String {
+        format!(
+            "&[{}]",
+            strct
+                .fields
+                .iter()
+                .map(|field| field.id.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    fn known_names(&self, strct: &Struct) -> String {
+        format!(
+            "&[{}]",
+            strct
+                .fields
+                .iter()
+                .map(|field| format!("\"{}\"", field.name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    fn enum_extract(&self, enums: &Enum, store: &Store) -> String {
+        if store.get_codec() != Codec::Binary {
+            return self.codec_decode(&enums.name, false, store);
+        }
+        let variants = enums
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let item_type = self.enum_item_type(item.clone());
+                Template::new(
+                    "protocol/rust/enum_decode_variant",
+                    templates::ENUM_DECODE_VARIANT,
+                )
+                .set("index", &index.to_string())
+                .set(
+                    "kind",
+                    &if item.repeated {
+                        format!("Vec::<{}>", item_type)
                     } else {
                         item_type
-                    }
-                ),
+                    },
+                )
+                .set("enum", &enums.name)
+                .set("name", &item.name)
+                .render()
+            })
+            .collect::<Vec<String>>();
+        Template::new("protocol/rust/enum_decode", templates::ENUM_DECODE)
+            .set("name", &enums.name)
+            .set("variants", &self.lines(&variants, 2))
+            .render()
+    }
+
+    fn enum_abduct(&self, enums: &Enum, store: &Store) -> String {
+        if store.get_codec() != Codec::Binary {
+            return self.codec_abduct(store);
+        }
+        let mut len = vec![];
+        let mut encode = vec![];
+        for (index, item) in enums.variants.iter().enumerate() {
+            len.push(
+                Template::new(
+                    "protocol/rust/enum_variant_len",
+                    templates::ENUM_VARIANT_LEN,
+                )
+                .set("name", &item.name)
+                .render(),
+            );
+            encode.push(
+                Template::new(
+                    "protocol/rust/enum_variant_encode",
+                    templates::ENUM_VARIANT_ENCODE,
+                )
+                .set("name", &item.name)
+                .set("index", &index.to_string())
+                .render(),
             );
         }
-        body = format!("{}{}Defaults,\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl EnumDecode for {} {{\n",
-            body,
-            self.spaces(level),
-            enums.name
-        );
-        body = format!(
-            "{}{}fn get_id(&self) -> u32 {{ {} }}\n",
-            body,
-            self.spaces(level + 1),
-            enums.id
-        );
-        body = format!(
-            "{}{}fn extract(buf: Vec<u8>) -> Result<{}, String> {{\n",
-            body,
-            self.spaces(level + 1),
-            enums.name
-        );
-        body = format!(
-            "{}{}if buf.len() <= sizes::U16_LEN {{\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}return Err(String::from(\"Fail to extract value for {} because buffer too small\"));\n", body, self.spaces(level + 3), enums.name);
-        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-        body = format!(
-            "{}{}let mut cursor: Cursor<&[u8]> = Cursor::new(&buf);\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}{}let index = cursor.get_u16_le();\n",
-            body,
-            self.spaces(level + 2)
-        );
+        Template::new("protocol/rust/enum_encode", templates::ENUM_ENCODE)
+            .set("len", &self.lines(&len, 2))
+            .set("encode", &self.lines(&encode, 2))
+            .render()
+    }
 
-        body = format!(
-            "{}{}let mut body_buf = vec![0; buf.len() - sizes::U16_LEN];\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}{}body_buf.copy_from_slice(&buf[sizes::U16_LEN..]);\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}match index {{\n", body, self.spaces(level + 2));
-        for (index, item) in enums.variants.iter().enumerate() {
-            let item_type = self.enum_item_type(item.clone());
-            body = format!(
-                "{}{}{} => match {}::decode(&body_buf) {{\n",
-                body,
-                self.spaces(level + 3),
-                index,
-                if item.repeated {
-                    format!("Vec::<{}>", item_type)
+    fn codec_decode(&self, name: &str, storage: bool, store: &Store) -> String {
+        Template::new("protocol/rust/codec_decode", templates::CODEC_DECODE)
+            .set("storage", if storage { "true" } else { "" })
+            .set("name", name)
+            .set("codec", store.get_codec().as_str())
+            .set(
+                "read",
+                if store.get_codec() == Codec::Compact {
+                    "CompactValue::compact_read(&buf)"
                 } else {
-                    item_type
-                }
+                    "FromCodecValue::from_codec_value(&decode_body(&buf)?)"
+                },
+            )
+            .render()
+    }
+
+    fn codec_abduct(&self, store: &Store) -> String {
+        // Size is calculated without encoding and body is written directly into buffer
+        let (write, len, write_into) = if store.get_codec() == Codec::Compact {
+            (
+                "compact_body(self)",
+                "self.compact_len()",
+                "self.compact_write(buf)",
+            )
+        } else {
+            (
+                "encode_body(&self.to_codec_value())",
+                "encoded_body_len(&self.to_codec_value())",
+                "encode_body_into(&self.to_codec_value(), buf)",
+            )
+        };
+        Template::new("protocol/rust/codec_abduct", templates::CODEC_ABDUCT)
+            .set("write", write)
+            .set("len", len)
+            .set("write_into", write_into)
+            .render()
+    }
+
+    fn struct_compact(&self, strct: &Struct, store: &Store) -> String {
+        let mut len = vec![];
+        let mut write = vec![];
+        let mut read = vec![];
+        for field in &strct.fields {
+            len.push(
+                Template::new(
+                    "protocol/rust/struct_compact_len",
+                    templates::STRUCT_COMPACT_LEN,
+                )
+                .set("name", &field.name)
+                .set("id", &field.id.to_string())
+                .render(),
             );
-            body = format!(
-                "{}{}Ok(v) => Ok({}::{}(v)),\n",
-                body,
-                self.spaces(level + 4),
-                enums.name,
-                item.name
-            );
-            body = format!("{}{}Err(e) => Err(e)\n", body, self.spaces(level + 4));
-            body = format!("{}{}}},\n", body, self.spaces(level + 3));
-        }
-        body = format!(
-            "{}{}_ => Err(String::from(\"Fail to find relevant value for {}\")),\n",
-            body,
-            self.spaces(level + 3),
-            enums.name
-        );
-        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl EnumEncode for {} {{\n",
-            body,
-            self.spaces(level),
-            enums.name
-        );
-        body = format!(
-            "{}{}fn get_id(&self) -> u32 {{ {} }}\n",
-            body,
-            self.spaces(level + 1),
-            enums.id,
-        );
-        body = format!(
-            "{}{}fn get_signature(&self) -> u16 {{ {} }}\n",
-            body,
-            self.spaces(level + 1),
-            self.signature,
-        );
-        body = format!(
-            "{}{}fn abduct(&mut self) -> Result<Vec<u8>, String> {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!(
-            "{}{}let (buf, index) = match self {{\n",
-            body,
-            self.spaces(level + 2)
-        );
+            write.push(
+                Template::new(
+                    "protocol/rust/struct_compact_write",
+                    templates::STRUCT_COMPACT_WRITE,
+                )
+                .set("name", &field.name)
+                .set("id", &field.id.to_string())
+                .render(),
+            );
+            read.push(
+                Template::new(
+                    "protocol/rust/struct_compact_read",
+                    templates::STRUCT_COMPACT_READ,
+                )
+                .set("name", &field.name)
+                .set("id", &field.id.to_string())
+                .set("struct", &strct.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/rust/struct_compact", templates::STRUCT_COMPACT)
+            .set("name", &strct.name)
+            .set("known", &self.known_ids(strct))
+            .set("unknown", self.unknown(store))
+            .set(
+                "unknown_len",
+                if store.get_unknown_fields() {
+                    "compact_unknown_len(&self.__unknown)"
+                } else {
+                    "0"
+                },
+            )
+            .set("len", &self.lines(&len, 3))
+            .set("write", &self.lines(&write, 2))
+            .set("read", &self.lines(&read, 3))
+            .render()
+    }
+
+    fn enum_compact(&self, enums: &Enum) -> String {
+        let mut len = vec![];
+        let mut write = vec![];
+        let mut read = vec![];
         for (index, item) in enums.variants.iter().enumerate() {
-            body = format!(
-                "{}{}Self::{}(v) => (v.encode(), {}),\n",
-                body,
-                self.spaces(level + 3),
-                item.name,
-                index
-            );
-        }
-        body = format!(
-            "{}{}_ => {{ return Err(String::from(\"Not supportable option\")); }},\n",
-            body,
-            self.spaces(level + 3)
-        );
-        body = format!("{}{}}};\n", body, self.spaces(level + 2));
-        body = format!(
-            "{}{}let mut buf = match buf {{\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}Ok(buf) => buf,\n", body, self.spaces(level + 3));
-        body = format!(
-            "{}{}Err(e) => {{ return Err(e); }},\n",
-            body,
-            self.spaces(level + 3)
-        );
-        body = format!("{}{}}};\n", body, self.spaces(level + 2));
-        body = format!(
-            "{}{}let mut buffer: Vec<u8> = vec!();\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}{}buffer.append(&mut (index as u16).to_le_bytes().to_vec());\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}{}buffer.append(&mut buf);\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}Ok(buffer)\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!(
-            "{}{}impl PackingEnum for {} {{}}\n",
-            body,
-            self.spaces(level),
-            enums.name
-        );
-        body
+            len.push(
+                Template::new(
+                    "protocol/rust/enum_compact_len",
+                    templates::ENUM_COMPACT_LEN,
+                )
+                .set("name", &item.name)
+                .set("index", &index.to_string())
+                .render(),
+            );
+            write.push(
+                Template::new(
+                    "protocol/rust/enum_compact_write",
+                    templates::ENUM_COMPACT_WRITE,
+                )
+                .set("name", &item.name)
+                .set("index", &index.to_string())
+                .render(),
+            );
+            read.push(
+                Template::new(
+                    "protocol/rust/enum_compact_read",
+                    templates::ENUM_COMPACT_READ,
+                )
+                .set("name", &item.name)
+                .set("index", &index.to_string())
+                .set("enum", &enums.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/rust/enum_compact", templates::ENUM_COMPACT)
+            .set("name", &enums.name)
+            .set("len", &self.lines(&len, 3))
+            .set("write", &self.lines(&write, 3))
+            .set("read", &self.lines(&read, 3))
+            .render()
+    }
+
+    fn struct_view(&self, strct: &Struct, store: &Store) -> String {
+        let mut fields = vec![];
+        let mut view = vec![];
+        let mut owned = vec![];
+        let mut borrows = store.get_unknown_fields();
+        for field in &strct.fields {
+            let field_type = self.view_type(
+                &field.kind,
+                &field.get_full_name(),
+                field.ref_type_id,
+                field.repeated,
+                field.optional,
+                store,
+            );
+            borrows = borrows || field_type.contains("'a");
+            fields.push(
+                Template::new("protocol/rust/struct_field", templates::STRUCT_FIELD)
+                    .set("name", &field.name)
+                    .set("kind", &field_type)
+                    .render(),
+            );
+            view.push(
+                Template::new("protocol/rust/struct_view_get", templates::STRUCT_VIEW_GET)
+                    .set("name", &field.name)
+                    .set("id", &field.id.to_string())
+                    .set("struct", &strct.name)
+                    .render(),
+            );
+            owned.push(
+                Template::new(
+                    "protocol/rust/struct_view_owned",
+                    templates::STRUCT_VIEW_OWNED,
+                )
+                .set("name", &field.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/rust/struct_view", templates::STRUCT_VIEW)
+            .set("name", &strct.name)
+            .set("id", &strct.id.to_string())
+            .set("known", &self.known_ids(strct))
+            .set("unknown", self.unknown(store))
+            // Lifetime should be used anyway
+            .set("phantom", if borrows { "" } else { "true" })
+            .set("fields", &self.lines(&fields, 1))
+            .set("view", &self.lines(&view, 3))
+            .set("owned", &self.lines(&owned, 3))
+            .render()
+    }
+
+    fn enum_view(&self, enums: &Enum, store: &Store) -> String {
+        let mut variants = vec![];
+        let mut decode = vec![];
+        let mut owned = vec![];
+        for (index, item) in enums.variants.iter().enumerate() {
+            variants.push(
+                Template::new("protocol/rust/enum_variant", templates::ENUM_VARIANT)
+                    .set("name", &item.name)
+                    .set(
+                        "kind",
+                        &self.view_type(
+                            &item
+                                .types
+                                .clone()
+                                .and_then(PrimitiveTypes::get_entity_as_string)
+                                .unwrap_or_default(),
+                            &item.type_path,
+                            item.ref_type_id,
+                            item.repeated,
+                            false,
+                            store,
+                        ),
+                    )
+                    .render(),
+            );
+            decode.push(
+                Template::new(
+                    "protocol/rust/enum_view_decode",
+                    templates::ENUM_VIEW_DECODE,
+                )
+                .set("name", &item.name)
+                .set("index", &index.to_string())
+                .set("enum", &enums.name)
+                .render(),
+            );
+            owned.push(
+                Template::new("protocol/rust/enum_view_owned", templates::ENUM_VIEW_OWNED)
+                    .set("name", &item.name)
+                    .set("enum", &enums.name)
+                    .render(),
+            );
+        }
+        let borrows = self.enum_borrows(enums);
+        Template::new("protocol/rust/enum_view", templates::ENUM_VIEW)
+            .set("name", &enums.name)
+            .set("id", &enums.id.to_string())
+            .set("declare", if borrows { "true" } else { "" })
+            .set(
+                "view",
+                &if borrows {
+                    format!("{}View<'a>", enums.name)
+                } else {
+                    enums.name.clone()
+                },
+            )
+            .set("variants", &self.lines(&variants, 1))
+            .set("decode", &self.lines(&decode, 3))
+            .set("owned", &self.lines(&owned, 3))
+            .render()
+    }
+
+    // Enum, which has only numbers and booleans, is own view: it doesn't borrow anything
+    fn enum_borrows(&self, enums: &Enum) -> bool {
+        enums.variants.iter().any(|item| {
+            item.repeated
+                || item.ref_type_id.is_some()
+                || matches!(item.types, Some(PrimitiveTypes::ETypes::Estr))
+        })
+    }
+
+    fn view_type(
+        &self,
+        kind: &str,
+        type_path: &[String],
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        optional: bool,
+        store: &Store,
+    ) -> String {
+        let item = match kind {
+            "bool" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32"
+            | "f64" => kind.to_owned(),
+            "str" => String::from("&'a str"),
+            _ => {
+                let ref_type_id = ref_type_id.unwrap_or_else(|| {
+                    panic!("Invalid type definition: {}", kind);
+                });
+                let mut path = type_path.to_vec();
+                let name = path.pop().unwrap_or_default();
+                path.push(match store.get_enum(ref_type_id) {
+                    Some(enums) if !self.enum_borrows(&enums) => name,
+                    _ => format!("{}View<'a>", name),
+                });
+                path.join("::")
+            }
+        };
+        let mut type_str = if repeated && kind == "u8" {
+            String::from("&'a [u8]")
+        } else if repeated {
+            format!("ListView<'a, {}>", item)
+        } else if ref_type_id.is_some() {
+            format!("Lazy<'a, {}>", item)
+        } else {
+            item
+        };
+        if optional {
+            type_str = format!("Option<{}>", type_str);
+        }
+        type_str
+    }
+
+    fn struct_value(&self, strct: &Struct, store: &Store) -> String {
+        let mut to_value = vec![];
+        let mut from_value = vec![];
+        for field in &strct.fields {
+            to_value.push(
+                Template::new("protocol/rust/struct_value_to", templates::STRUCT_VALUE_TO)
+                    .set("name", &field.name)
+                    .render(),
+            );
+            from_value.push(
+                Template::new(
+                    "protocol/rust/struct_value_from",
+                    templates::STRUCT_VALUE_FROM,
+                )
+                .set("name", &field.name)
+                .set("struct", &strct.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/rust/struct_value", templates::STRUCT_VALUE)
+            .set("name", &strct.name)
+            .set("known", &self.known_names(strct))
+            .set("unknown", self.unknown(store))
+            .set(
+                "unknown_fields",
+                if store.get_unknown_fields() {
+                    "&self.__unknown"
+                } else {
+                    "&[]"
+                },
+            )
+            .set("to_value", &self.lines(&to_value, 3))
+            .set("from_value", &self.lines(&from_value, 3))
+            .render()
+    }
+
+    fn enum_value(&self, enums: &Enum) -> String {
+        let mut to_value = vec![];
+        let mut from_value = vec![];
+        for item in &enums.variants {
+            to_value.push(
+                Template::new("protocol/rust/enum_value_to", templates::ENUM_VALUE_TO)
+                    .set("name", &item.name)
+                    .render(),
+            );
+            from_value.push(
+                Template::new("protocol/rust/enum_value_from", templates::ENUM_VALUE_FROM)
+                    .set("name", &item.name)
+                    .set("enum", &enums.name)
+                    .render(),
+            );
+        }
+        Template::new("protocol/rust/enum_value", templates::ENUM_VALUE)
+            .set("name", &enums.name)
+            .set("to_value", &self.lines(&to_value, 3))
+            .set("from_value", &self.lines(&from_value, 5))
+            .render()
+    }
+
+    fn struct_json(&self, strct: &Struct, store: &Store) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
+        for field in &strct.fields {
+            to_json.push(
+                Template::new("protocol/rust/struct_json_to", templates::STRUCT_JSON_TO)
+                    .set("name", &field.name)
+                    .render(),
+            );
+            from_json.push(
+                Template::new(
+                    "protocol/rust/struct_json_from",
+                    templates::STRUCT_JSON_FROM,
+                )
+                .set("name", &field.name)
+                .set("struct", &strct.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/rust/struct_json", templates::STRUCT_JSON)
+            .set("name", &strct.name)
+            .set("unknown", self.unknown(store))
+            .set("to_json", &self.lines(&to_json, 3))
+            .set("from_json", &self.lines(&from_json, 3))
+            .render()
+    }
+
+    fn enums(&self, enums: &Enum, store: &Store) -> String {
+        let variants = enums
+            .variants
+            .iter()
+            .map(|item| {
+                let item_type = self.enum_item_type(item.clone());
+                Template::new("protocol/rust/enum_variant", templates::ENUM_VARIANT)
+                    .set("name", &item.name)
+                    .set(
+                        "kind",
+                        &if item.repeated {
+                            format!("Vec<{}>", item_type)
+                        } else {
+                            item_type
+                        },
+                    )
+                    .render()
+            })
+            .collect::<Vec<String>>();
+        let mut body = vec![
+            Template::new("protocol/rust/enum", templates::ENUM)
+                .set("derive", &store.get_derive(enums.id).join(", "))
+                .set("attrs", &self.attrs(store.get_attrs(enums.id)))
+                .set("name", &enums.name)
+                .set("variants", &self.lines(&variants, 1))
+                .render(),
+            Template::new("protocol/rust/enum_impl", templates::ENUM_IMPL)
+                .set("name", &enums.name)
+                .set("id", &enums.id.to_string())
+                .set("signature", &self.signature.to_string())
+                .set("decode", &indent(1, &self.enum_extract(enums, store)))
+                .set("encode", &indent(1, &self.enum_abduct(enums, store)))
+                .render(),
+            self.enum_json(enums),
+        ];
+        if store.get_codec() == Codec::Compact {
+            body.push(self.enum_compact(enums));
+        } else if store.get_codec() != Codec::Binary {
+            body.push(self.enum_value(enums));
+        } else if store.get_views() {
+            body.push(self.enum_view(enums, store));
+        }
+        self.block(&body)
+    }
+
+    fn enum_json(&self, enums: &Enum) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
+        for item in &enums.variants {
+            to_json.push(
+                Template::new("protocol/rust/enum_json_to", templates::ENUM_JSON_TO)
+                    .set("name", &item.name)
+                    .render(),
+            );
+            from_json.push(
+                Template::new("protocol/rust/enum_json_from", templates::ENUM_JSON_FROM)
+                    .set("name", &item.name)
+                    .set("enum", &enums.name)
+                    .render(),
+            );
+        }
+        Template::new("protocol/rust/enum_json", templates::ENUM_JSON)
+            .set("name", &enums.name)
+            .set("to_json", &self.lines(&to_json, 3))
+            .set("from_json", &self.lines(&from_json, 5))
+            .render()
+    }
+
+    fn messages_json(&self, group: Option<&Group>, store: &mut Store) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
+        let mut entities: Vec<(String, Vec<String>)> = vec![];
+        let mut groups: Vec<(String, Vec<String>)> = vec![];
+        let parent = group.map(|group| group.id).unwrap_or(0);
+        for enums in store.enums.iter().filter(|e| e.parent == parent) {
+            entities.push((enums.name.clone(), store.get_enum_path(enums.id)));
+        }
+        for strct in store.structs.iter().filter(|s| s.parent == parent) {
+            entities.push((strct.name.clone(), store.get_struct_path(strct.id)));
+        }
+        for child in store.groups.iter().filter(|g| g.parent == parent) {
+            groups.push((child.name.clone(), store.get_path_by_parent(child.id)));
+        }
+        for (name, path) in entities.iter() {
+            to_json.push(
+                Template::new("protocol/rust/message_json_to", templates::MESSAGE_JSON_TO)
+                    .set("name", name)
+                    .set("path", &path.join("."))
+                    .render(),
+            );
+            from_json.push(
+                Template::new(
+                    "protocol/rust/message_json_from",
+                    templates::MESSAGE_JSON_FROM,
+                )
+                .set("name", name)
+                .set("path", &path.join("."))
+                .render(),
+            );
+        }
+        for (name, path) in groups.iter() {
+            to_json.push(
+                Template::new("protocol/rust/group_json_to", templates::GROUP_JSON_TO)
+                    .set("name", name)
+                    .render(),
+            );
+            from_json.push(
+                Template::new("protocol/rust/group_json_from", templates::GROUP_JSON_FROM)
+                    .set("name", name)
+                    .set("path", &path.join("."))
+                    .render(),
+            );
+        }
+        Template::new("protocol/rust/messages_json", templates::MESSAGES_JSON)
+            .set(
+                "target",
+                if group.is_none() || !entities.is_empty() || !groups.is_empty() {
+                    "self"
+                } else {
+                    "*self"
+                },
+            )
+            .set("unknown", if group.is_none() { "true" } else { "" })
+            .set("to_json", &self.lines(&to_json, 3))
+            .set("from_json", &self.lines(&from_json, 3))
+            .render()
     }
 
     fn enum_item_type(&self, item: EnumItem) -> String {
@@ -471,51 +1294,45 @@ impl RustRender {
         }
     }
 
-    fn entity_default(&self, entity_id: usize, store: &mut Store, level: u8) -> String {
+    fn entity_default(&self, entity_id: usize, store: &mut Store) -> String {
         if let Some(strct) = store.get_struct(entity_id) {
-            let path = if !strct.path.is_empty() {
-                format!("{}::", strct.path.join("::"))
-            } else {
-                String::new()
-            };
-            let mut body = format!("{}{} {{\n", path, strct.name);
-            for field in &strct.fields {
-                body = format!(
-                    "{}{}{}\n",
-                    body,
-                    self.spaces(level),
-                    self.field_default(field, &mut store.clone(), level)
-                );
-            }
-            format!("{}{}}}", body, self.spaces(level - 1))
+            let mut path = strct.path.clone();
+            path.push(strct.name.clone());
+            let fields = strct
+                .fields
+                .iter()
+                .map(|field| self.field_default(field, &mut store.clone()))
+                .collect::<Vec<String>>();
+            Template::new("protocol/rust/struct_default", templates::STRUCT_DEFAULT)
+                .set("name", &path.join("::"))
+                .set("fields", &self.lines(&fields, 1))
+                .set("unknown", self.unknown(store))
+                .render()
         } else if let Some(enums) = store.get_enum(entity_id) {
-            let path = if !enums.path.is_empty() {
-                format!("{}::", enums.path.join("::"))
-            } else {
-                String::new()
-            };
-            format!("{}{}::Defaults", path, enums.name)
+            let mut path = enums.path.clone();
+            path.push(enums.name.clone());
+            format!("{}::Defaults", path.join("::"))
         } else {
             panic!("Fail to find a struct/enum id: {}", entity_id);
         }
     }
 
-    fn field_default(&self, field: &Field, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}: ", field.name);
-        if field.repeated && !field.optional {
-            body = format!("{}vec![],", body);
+    fn field_default(&self, field: &Field, store: &mut Store) -> String {
+        let value = if field.repeated && !field.optional {
+            String::from("vec![]")
         } else if field.optional {
-            body = format!("{}None,", body);
+            String::from("None")
         } else if let Some(default_value) = self.type_default_value(&field.kind) {
-            body = format!("{}{},", body, default_value);
+            default_value.to_owned()
         } else if let Some(struct_id) = field.ref_type_id {
-            body = format!(
-                "{}{},",
-                body,
-                self.entity_default(struct_id, store, level + 1)
-            );
-        }
-        body
+            self.entity_default(struct_id, store)
+        } else {
+            String::new()
+        };
+        Template::new("protocol/rust/field_default", templates::FIELD_DEFAULT)
+            .set("name", &field.name)
+            .set("value", &value)
+            .render()
     }
 
     fn type_default_value(&self, type_ref: &str) -> Option<&str> {
@@ -582,91 +1399,73 @@ impl RustRender {
         }
     }
 
-    fn get_messages_list(&self, group: Option<&Group>, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}#[derive(Debug, Clone)]\n", self.spaces(level));
-        if let Some(group) = group {
-            body = format!(
-                "{}{}pub enum AvailableMessages {{\n",
-                body,
-                self.spaces(level)
-            );
-            for enum_id in &group.enums {
-                if let Some(enums) = store.get_enum(*enum_id) {
-                    body = format!(
-                        "{}{}{}({}),\n",
-                        body,
-                        self.spaces(level + 1),
-                        enums.name,
-                        enums.name
-                    );
-                }
-            }
-            for struct_id in &group.structs {
-                if let Some(strct) = store.get_struct(*struct_id) {
-                    body = format!(
-                        "{}{}{}({}),\n",
-                        body,
-                        self.spaces(level + 1),
-                        strct.name,
-                        strct.name
-                    );
-                }
-            }
-            let childs = store.get_child_groups(group.id);
-            for child in childs {
-                if child.parent == group.id {
-                    body = format!(
-                        "{}{}{}({}::AvailableMessages),\n",
-                        body,
-                        self.spaces(level + 1),
-                        child.name,
-                        child.name
-                    );
-                }
-            }
-            body = format!("{}{}}}\n", body, self.spaces(level));
+    fn get_messages_list(&self, group: Option<&Group>, store: &mut Store) -> String {
+        // Entities and groups of root are taken by parent; it gives the same order as for groups
+        let (enums, structs, groups) = if let Some(group) = group {
+            (
+                group.enums.clone(),
+                group.structs.clone(),
+                store
+                    .get_child_groups(group.id)
+                    .iter()
+                    .filter(|child| child.parent == group.id)
+                    .map(|child| child.name.clone())
+                    .collect::<Vec<String>>(),
+            )
         } else {
-            body = format!(
-                "{}{}pub enum AvailableMessages {{\n",
-                body,
-                self.spaces(level)
-            );
-            for enums in &store.enums {
-                if enums.parent == 0 {
-                    body = format!(
-                        "{}{}{}({}),\n",
-                        body,
-                        self.spaces(level + 1),
-                        enums.name,
-                        enums.name
-                    );
-                }
-            }
-            for strct in &store.structs {
-                if strct.parent == 0 {
-                    body = format!(
-                        "{}{}{}({}),\n",
-                        body,
-                        self.spaces(level + 1),
-                        strct.name,
-                        strct.name
-                    );
-                }
-            }
-            for group in &store.groups {
-                if group.parent == 0 {
-                    body = format!(
-                        "{}{}{}({}::AvailableMessages),\n",
-                        body,
-                        self.spaces(level + 1),
-                        group.name,
-                        group.name
-                    );
-                }
-            }
-            body = format!("{}{}}}\n", body, self.spaces(level));
+            (
+                store
+                    .enums
+                    .iter()
+                    .filter(|enums| enums.parent == 0)
+                    .map(|enums| enums.id)
+                    .collect::<Vec<usize>>(),
+                store
+                    .structs
+                    .iter()
+                    .filter(|strct| strct.parent == 0)
+                    .map(|strct| strct.id)
+                    .collect::<Vec<usize>>(),
+                store
+                    .groups
+                    .iter()
+                    .filter(|group| group.parent == 0)
+                    .map(|group| group.name.clone())
+                    .collect::<Vec<String>>(),
+            )
+        };
+        let mut messages = vec![];
+        for name in enums
+            .iter()
+            .filter_map(|id| store.get_enum(*id).map(|enums| enums.name))
+            .chain(
+                structs
+                    .iter()
+                    .filter_map(|id| store.get_struct(*id).map(|strct| strct.name)),
+            )
+        {
+            messages.push(
+                Template::new("protocol/rust/message", templates::MESSAGE)
+                    .set("name", &name)
+                    .set("kind", &name)
+                    .render(),
+            );
+        }
+        for name in groups.iter() {
+            messages.push(
+                Template::new("protocol/rust/message", templates::MESSAGE)
+                    .set("name", name)
+                    .set("kind", &format!("{}::AvailableMessages", name))
+                    .render(),
+            );
         }
-        body
+        Template::new("protocol/rust/messages", templates::MESSAGES)
+            .set("derive", &store.get_messages_derive().join(", "))
+            .set("messages", &self.lines(&messages, 1))
+            // Message, which isn't known by current version of protocol
+            .set("unknown", if group.is_none() { "true" } else { "" })
+            .set("json", &self.messages_json(group, store))
+            .render()
     }
 
     fn get_path(&self, mut parent: usize, store: &mut Store) -> Vec<String> {
@@ -736,91 +1535,216 @@ impl RustRender {
     }
 
     fn buffer(&self, store: &mut Store) -> String {
-        let mut body = format!(
-            "{}impl DecodeBuffer<AvailableMessages> for Buffer<AvailableMessages> {{\n",
-            self.spaces(0)
-        );
-        body = format!(
-            "{}{}fn get_msg(&self, id: u32, buf: &[u8]) -> Result<AvailableMessages, String> {{\n",
-            body,
-            self.spaces(1)
-        );
-        body = format!("{}{}match id {{\n", body, self.spaces(2));
-        for enums in &store.enums {
-            body = format!(
-                "{}{}{} => match {}::extract(buf.to_vec()) {{\n",
-                body,
-                self.spaces(3),
-                enums.id,
-                self.get_full_name(enums.name.clone(), enums.parent, &mut store.clone())
-            );
-            body = format!(
-                "{}{}Ok(m) => Ok({}),\n",
-                body,
-                self.spaces(4),
-                self.get_available_entity(enums.parent, &enums.name, &mut store.clone())
-            );
-            body = format!("{}{}Err(e) => Err(e),\n", body, self.spaces(4));
-            body = format!("{}{}}},\n", body, self.spaces(3));
-        }
-        for structs in &store.structs {
-            body = format!(
-                "{}{}{} => match {}::extract(buf.to_vec()) {{\n",
-                body,
-                self.spaces(3),
-                structs.id,
-                self.get_full_name(structs.name.clone(), structs.parent, &mut store.clone())
-            );
-            body = format!(
-                "{}{}Ok(m) => Ok({}),\n",
-                body,
-                self.spaces(4),
-                self.get_available_entity(structs.parent, &structs.name, &mut store.clone())
-            );
-            body = format!("{}{}Err(e) => Err(e),\n", body, self.spaces(4));
-            body = format!("{}{}}},\n", body, self.spaces(3));
-        }
-        body = format!(
-            "{}{}_ => Err(String::from(\"No message has been found\"))\n",
-            body,
-            self.spaces(3)
-        );
-        body = format!("{}{}}}\n", body, self.spaces(2));
-        body = format!("{}{}}}\n", body, self.spaces(1));
-        body = format!(
-            "{}{}fn get_signature(&self) -> u16 {{ {} }}\n",
-            body,
-            self.spaces(1),
-            self.signature
-        );
-        body = format!("{}{}}}\n", body, self.spaces(0));
-        body
+        let entities = store
+            .enums
+            .iter()
+            .map(|enums| (enums.id, enums.name.clone(), enums.parent))
+            .chain(
+                store
+                    .structs
+                    .iter()
+                    .map(|strct| (strct.id, strct.name.clone(), strct.parent)),
+            )
+            .collect::<Vec<(usize, String, usize)>>();
+        let messages = entities
+            .iter()
+            .map(|(id, name, parent)| {
+                Template::new("protocol/rust/buffer_message", templates::BUFFER_MESSAGE)
+                    .set("id", &id.to_string())
+                    .set(
+                        "name",
+                        &self.get_full_name(name.clone(), *parent, &mut store.clone()),
+                    )
+                    .set(
+                        "message",
+                        &self.get_available_entity(*parent, name, &mut store.clone()),
+                    )
+                    .render()
+            })
+            .collect::<Vec<String>>();
+        Template::new("protocol/rust/buffer", templates::BUFFER)
+            .set("messages", &self.lines(&messages, 3))
+            .set("signature", &self.signature.to_string())
+            .render()
     }
 
-    fn includes(&self) -> String {
+    fn includes(&self, store: &Store) -> String {
         if self.embedded {
-            format!(
-                "{}{}{}{}{}{}{}\n",
-                self.get_injectable(include_str!("./static/protocol.uses.rs")),
-                self.get_injectable(include_str!("./static/protocol.sizes.mod.rs")),
-                self.get_injectable(include_str!("./static/protocol.decode.rs")),
-                self.get_injectable(include_str!("./static/protocol.encode.rs")),
-                self.get_injectable(include_str!("./static/protocol.storage.rs")),
-                self.get_injectable(include_str!("./static/protocol.packing.rs")),
-                self.get_injectable(include_str!("./static/protocol.buffer.rs")),
-            )
+            let mut includes = [
+                self.get_injectable("protocol.uses", include_str!("./static/protocol.uses.rs")),
+                self.get_injectable(
+                    "protocol.sizes.mod",
+                    include_str!("./static/protocol.sizes.mod.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.decode",
+                    include_str!("./static/protocol.decode.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.compression",
+                    include_str!("./static/protocol.compression.rs"),
+                ),
+                self.compression_algorithms(),
+                self.get_injectable(
+                    "protocol.checksum",
+                    include_str!("./static/protocol.checksum.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.encode",
+                    include_str!("./static/protocol.encode.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.storage",
+                    include_str!("./static/protocol.storage.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.packing",
+                    include_str!("./static/protocol.packing.rs"),
+                ),
+                self.middleware(store),
+                self.get_injectable(
+                    "protocol.buffer",
+                    include_str!("./static/protocol.buffer.rs"),
+                ),
+                self.get_injectable(
+                    "protocol.capabilities",
+                    include_str!("./static/protocol.capabilities.rs"),
+                ),
+            ]
+            .concat();
+            includes.push('\n');
+            includes
         } else {
             String::new()
         }
     }
 
-    fn get_injectable(&self, content: &str) -> String {
+    fn compression_algorithms(&self) -> String {
+        let algorithms = self.get_injectable(
+            "protocol.compression.algorithms",
+            include_str!("./static/protocol.compression.algorithms.rs"),
+        );
+        if self.features {
+            Template::new(
+                "protocol/rust/compression_feature",
+                templates::COMPRESSION_FEATURE,
+            )
+            .set("feature", features::COMPRESSION)
+            .set("algorithms", &indent(1, algorithms.trim_end()))
+            .render()
+        } else {
+            algorithms
+        }
+    }
+
+    fn required_features(&self, store: &Store) -> String {
+        if !self.features {
+            return String::new();
+        }
+        get_features(store)
+            .iter()
+            .filter(|(_, required)| *required)
+            .map(|(feature, _)| {
+                Template::new(
+                    "protocol/rust/required_feature",
+                    templates::REQUIRED_FEATURE,
+                )
+                .set("feature", feature)
+                .render()
+            })
+            .collect::<String>()
+    }
+
+    // Default middleware doesn't change body; with encryption it's replaced by encrypting one
+    fn middleware(&self, store: &Store) -> String {
+        match store.get_encryption() {
+            Encryption::None => self.get_injectable(
+                "protocol.middleware",
+                include_str!("./static/protocol.middleware.rs"),
+            ),
+            encryption => Template::new("protocol/rust/encryption", templates::ENCRYPTION)
+                .set(
+                    "encryption",
+                    &self.get_injectable(
+                        "protocol.encryption",
+                        include_str!("./static/protocol.encryption.rs"),
+                    ),
+                )
+                .set("algorithm", encryption.as_variant())
+                .set(
+                    "unencrypted",
+                    &store
+                        .get_unencrypted()
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                )
+                .render(),
+        }
+    }
+
+    // Body codec is embedded in any mode, because it's defined by protocol
+    fn codec(&self, store: &Store) -> String {
+        match store.get_codec() {
+            Codec::Binary => String::new(),
+            Codec::MsgPack => [
+                self.get_injectable("protocol.codec", include_str!("./static/protocol.codec.rs")),
+                self.get_injectable(
+                    "protocol.codec.msgpack",
+                    include_str!("./static/protocol.codec.msgpack.rs"),
+                ),
+                String::from("\n"),
+            ]
+            .concat(),
+            Codec::Cbor => [
+                self.get_injectable("protocol.codec", include_str!("./static/protocol.codec.rs")),
+                self.get_injectable(
+                    "protocol.codec.cbor",
+                    include_str!("./static/protocol.codec.cbor.rs"),
+                ),
+                String::from("\n"),
+            ]
+            .concat(),
+            Codec::Compact => [
+                self.get_injectable(
+                    "protocol.compact",
+                    include_str!("./static/protocol.compact.rs"),
+                ),
+                String::from("\n"),
+            ]
+            .concat(),
+        }
+    }
+
+    // Static parts can be overridden as templates protocol/rust/static/<name>
+    fn get_injectable(&self, name: &str, content: &str) -> String {
+        let content = helpers::template::get(&format!("protocol/rust/static/{}", name), content);
         let re = Regex::new(r"^([\n\r]|.)*(//\s?injectable)").unwrap();
-        re.replace_all(content, "").to_string()
+        re.replace_all(&content, "").to_string()
+    }
+
+    fn attrs(&self, attrs: Vec<String>) -> String {
+        attrs
+            .iter()
+            .map(|attr| format!("{}\n", attr))
+            .collect::<String>()
+    }
+
+    // Each item is placed on own line with given indent
+    fn lines(&self, items: &[String], level: usize) -> String {
+        items
+            .iter()
+            .map(|item| format!("\n{}", indent(level, item)))
+            .collect::<String>()
     }
 
-    fn spaces(&self, level: u8) -> String {
-        "    ".repeat(level as usize)
+    // Parts of entity are placed one by one; each ends with new line
+    fn block(&self, parts: &[String]) -> String {
+        parts
+            .iter()
+            .map(|part| format!("{}\n", part))
+            .collect::<String>()
     }
 }
 
@@ -829,37 +1753,47 @@ impl Render for RustRender {
         RustRender {
             embedded,
             signature,
+            features: false,
         }
     }
 
     fn render(&self, store: &mut Store, dest: &Path) -> Result<(), String> {
-        let mut body = format!("{}\n", self.includes());
-        body = format!(
-            "{}{}",
-            body,
-            self.get_messages_list(None, &mut store.clone(), 0)
-        );
-        for enums in &store.enums {
-            if enums.parent == 0 {
-                body = format!("{}{}\n", body, self.enums(enums, 0));
-            }
+        let views = if store.get_views() {
+            let mut views =
+                self.get_injectable("protocol.view", include_str!("./static/protocol.view.rs"));
+            views.push('\n');
+            views
+        } else {
+            String::new()
+        };
+        let mut entities = vec![];
+        for enums in store.enums.iter().filter(|enums| enums.parent == 0) {
+            entities.push(self.enums(enums, store));
         }
-        for strct in &store.structs {
-            if strct.parent == 0 {
-                body = format!("{}{}\n", body, self.structs(strct, &mut store.clone(), 0));
-            }
+        for strct in store.structs.iter().filter(|strct| strct.parent == 0) {
+            entities.push(self.structs(strct, &mut store.clone()));
         }
-        for group in &store.groups {
-            if group.parent == 0 {
-                body = format!("{}{}\n", body, self.groups(group, &mut store.clone(), 0));
-            }
+        for group in store.groups.iter().filter(|group| group.parent == 0) {
+            entities.push(self.groups(group, &mut store.clone()));
         }
-        body = format!("{}{}\n", body, self.buffer(&mut store.clone()));
-        body = format!(
-            "{}pub fn hash() -> String {{ String::from(\"{}\") }}\n",
-            body,
-            store.get_hash()
-        );
+        let body = Template::new("protocol/rust/protocol", templates::PROTOCOL)
+            .set("features", &self.required_features(store))
+            .set("includes", &self.includes(store))
+            .set(
+                "json",
+                &self.get_injectable("protocol.json", include_str!("./static/protocol.json.rs")),
+            )
+            .set("codec", store.get_codec().as_str())
+            .set("codec_impl", &self.codec(store))
+            .set("views", &views)
+            .set(
+                "messages",
+                &self.get_messages_list(None, &mut store.clone()),
+            )
+            .set("entities", &self.block(&entities))
+            .set("buffer", &self.buffer(&mut store.clone()))
+            .set("hash", &store.get_hash())
+            .render();
         helpers::fs::write(dest.to_path_buf(), body, true)
     }
 }
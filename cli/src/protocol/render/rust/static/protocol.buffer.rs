@@ -1,6 +1,10 @@
+use super::decode::{with_limits, Limits};
 use super::packing;
 use packing::PackageHeader;
-use packing::{get_body_from_buffer, get_header_from_buffer, has_buffer_body, has_buffer_header};
+use packing::{
+    get_body_from_buffer, get_header_from_buffer, get_package_len, has_buffer_body,
+    has_buffer_header,
+};
 
 // injectable
 #[derive(Debug)]
@@ -8,6 +12,18 @@ pub enum ReadError {
     Header(String),
     Parsing(String),
     Signature(String),
+    // One of Limits is exceeded
+    Limit(String),
+    // Body of package doesn't match checksum in header
+    Checksum(String),
+}
+
+// Error of one package. Package is skipped, but following packages are read as usual
+#[derive(Debug)]
+pub struct FrameError {
+    pub id: u32,
+    pub sequence: u32,
+    pub error: ReadError,
 }
 
 #[derive(Clone)]
@@ -24,6 +40,8 @@ pub trait DecodeBuffer<T> {
 pub struct Buffer<T: Clone> {
     buffer: Vec<u8>,
     queue: Vec<IncomeMessage<T>>,
+    errors: Vec<FrameError>,
+    limits: Limits,
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -40,9 +58,13 @@ where
                 header.signature
             )))
         } else {
-            match self.get_msg(header.id, buf) {
-                Ok(msg) => Ok(msg),
-                Err(e) => Err(ReadError::Parsing(format!(
+            match with_limits(&self.limits, || self.get_msg(header.id, buf)) {
+                (Ok(msg), _) => Ok(msg),
+                (Err(e), Some(_)) => Err(ReadError::Limit(format!(
+                    "Fail get message id={}, signature={} due error: {}",
+                    header.id, header.signature, e
+                ))),
+                (Err(e), None) => Err(ReadError::Parsing(format!(
                     "Fail get message id={}, signature={} due error: {}",
                     header.id, header.signature, e
                 ))),
@@ -51,47 +73,68 @@ where
     }
 
     pub fn new() -> Self {
+        Self::with_limits(Limits::default())
+    }
+
+    pub fn with_limits(limits: Limits) -> Self {
         Buffer {
             buffer: vec![],
             queue: vec![],
+            errors: vec![],
+            limits,
         }
     }
 
+    // Fails only if stream can't be read further (broken header, too big package or too
+    // many pending messages). Errors of separate packages are available via take_errors()
     #[allow(clippy::ptr_arg)]
     pub fn chunk(&mut self, buf: &Vec<u8>, uuid: Option<String>) -> Result<(), ReadError> {
         // Add data into buffer
-        self.buffer.append(&mut buf.clone());
-        if !has_buffer_header(&self.buffer) {
-            return Ok(());
-        }
-        // Get header
-        let header: PackageHeader = match get_header_from_buffer(&self.buffer) {
-            Ok(v) => v,
-            Err(e) => {
-                return Err(ReadError::Header(e));
+        self.buffer.extend_from_slice(buf);
+        while has_buffer_header(&self.buffer) {
+            // Get header
+            let header: PackageHeader = match get_header_from_buffer(&self.buffer) {
+                Ok(v) => v,
+                Err(e) => {
+                    return Err(ReadError::Header(e));
+                }
+            };
+            if header.len_usize > self.limits.frame {
+                // Rest of buffer can't be trusted anymore
+                self.buffer.clear();
+                return Err(ReadError::Limit(format!(
+                    "Size of package id={} ({} bytes) is bigger than limit ({} bytes)",
+                    header.id, header.len, self.limits.frame
+                )));
             }
-        };
-        if !has_buffer_body(&self.buffer, &header) {
-            return Ok(());
-        }
-        let (body, rest) = match get_body_from_buffer(&self.buffer, &header, uuid.clone()) {
-            Ok(v) => v,
-            Err(e) => {
-                return Err(ReadError::Parsing(e));
+            if !has_buffer_body(&self.buffer, &header) {
+                return Ok(());
             }
-        };
-        self.buffer = rest;
-        match Self::get_message(self, &header, &body) {
-            Ok(msg) => {
-                self.queue.push(IncomeMessage { header, msg });
-                if !self.buffer.is_empty() {
-                    self.chunk(&vec![], uuid)
-                } else {
-                    Ok(())
-                }
+            if self.queue.len() >= self.limits.pending {
+                return Err(ReadError::Limit(format!(
+                    "Count of pending messages is bigger than limit ({})",
+                    self.limits.pending
+                )));
+            }
+            // Package is removed from buffer in any case; header defines where next one starts
+            let package: Vec<u8> = self.buffer.drain(..get_package_len(&header)).collect();
+            let msg = match with_limits(&self.limits, || {
+                get_body_from_buffer(&package, &header, uuid.clone())
+            }) {
+                (Ok((body, _)), _) => self.get_message(&header, &body),
+                (Err(ReadError::Parsing(e)), Some(_)) => Err(ReadError::Limit(e)),
+                (Err(e), _) => Err(e),
+            };
+            match msg {
+                Ok(msg) => self.queue.push(IncomeMessage { header, msg }),
+                Err(error) => self.errors.push(FrameError {
+                    id: header.id,
+                    sequence: header.sequence,
+                    error,
+                }),
             }
-            Err(e) => Err(e),
         }
+        Ok(())
     }
 
     #[allow(clippy::should_implement_trait)]
@@ -108,6 +151,10 @@ where
         message
     }
 
+    pub fn take_errors(&mut self) -> Vec<FrameError> {
+        std::mem::take(&mut self.errors)
+    }
+
     pub fn len(&self) -> usize {
         self.buffer.len()
     }
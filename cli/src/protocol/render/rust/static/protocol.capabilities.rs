@@ -0,0 +1,195 @@
+use super::compression::{COMPRESSION_FLAG_DEFLATE, COMPRESSION_FLAG_LZ4};
+use super::compression_algorithms::COMPRESSION_FLAGS;
+use super::decode::Limits;
+use super::middleware::encryption_algorithms;
+use super::packing::{now_ms, PackageHeader, MSG_VERSION, MSG_VERSION_TS_MS};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// injectable
+// Capabilities are exchanged while hash is checked: consumer sends own ones with HashRequest,
+// producer responds with own ones in HashResponse. Each side agrees settings of packages for
+// peer, so producer and consumer can be built with different settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    // Highest version of header, which can be read
+    pub version: u8,
+    // Flags of compression algorithms, which can be read
+    pub compression: Vec<u8>,
+    // Names of encryption algorithms in order of preference
+    pub encryption: Vec<String>,
+    pub codec: String,
+    // Max size of package body in bytes, which can be read; 0 - isn't limited
+    pub frame: u64,
+    // Heartbeat packages are supported
+    pub heartbeat: bool,
+}
+
+// Settings of packages for peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agreement {
+    pub version: u8,
+    // Flags of compression algorithms, which peer can read
+    pub compression: u8,
+    pub encryption: Option<String>,
+    pub frame: u64,
+    pub heartbeat: bool,
+    // Clock of peer; None until it's estimated
+    pub clock: Option<ClockSkew>,
+}
+
+// Difference between clock of peer and own clock. It's estimated only by headers with timestamp
+// in milliseconds (version 2), which are sent after agreement. Consumer estimates it by round
+// trip of hash check; producer has only timestamp of first package of consumer, so its
+// estimation includes latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    // Clock of peer minus own clock in milliseconds
+    pub offset: i64,
+    // Round trip in milliseconds; error of offset isn't bigger than half of it.
+    // None - offset is estimated by one package
+    pub rtt: Option<u64>,
+}
+
+impl ClockSkew {
+    // sent and received - own time of sending request and getting response; peer_ts - timestamp
+    // of response (clock of peer). All values are in milliseconds
+    pub fn estimate(sent: u64, peer_ts: u64, received: u64) -> Self {
+        let rtt = received.saturating_sub(sent);
+        ClockSkew {
+            offset: peer_ts as i64 - (sent + rtt / 2) as i64,
+            rtt: Some(rtt),
+        }
+    }
+
+    // Estimation by timestamp of package, which is just received
+    pub fn one_way(peer_ts: u64) -> Self {
+        ClockSkew {
+            offset: peer_ts as i64 - now_ms() as i64,
+            rtt: None,
+        }
+    }
+
+    // Converts timestamp of peer (ms) into own clock
+    pub fn to_local(&self, peer_ts: u64) -> u64 {
+        (peer_ts as i64 - self.offset).max(0) as u64
+    }
+
+    // Milliseconds since package with given timestamp (clock of peer) was sent
+    pub fn age(&self, peer_ts: u64) -> i64 {
+        now_ms() as i64 - self.to_local(peer_ts) as i64
+    }
+}
+
+impl Capabilities {
+    pub fn local(codec: &str, limits: &Limits) -> Self {
+        Capabilities {
+            version: MSG_VERSION,
+            compression: vec![COMPRESSION_FLAG_DEFLATE, COMPRESSION_FLAG_LZ4],
+            encryption: encryption_algorithms(),
+            codec: codec.to_owned(),
+            frame: limits.frame as u64,
+            // Runtime doesn't send heartbeat packages
+            heartbeat: false,
+        }
+    }
+
+    // Capabilities of peer, which doesn't send them (runtime before negotiation): it reads
+    // headers of version 0 without compression
+    pub fn legacy(codec: &str) -> Self {
+        Capabilities {
+            version: 0,
+            compression: vec![],
+            encryption: vec![],
+            codec: codec.to_owned(),
+            frame: 0,
+            heartbeat: false,
+        }
+    }
+
+    // Encryption algorithm is chosen by own preference; peer should use it as well
+    pub fn negotiate(&self, peer: &Capabilities) -> Result<Agreement, String> {
+        if self.codec != peer.codec {
+            return Err(format!(
+                "Codec dismatch; own codec: {}; codec of peer: {}",
+                self.codec, peer.codec
+            ));
+        }
+        let encryption = if self.encryption.is_empty() && peer.encryption.is_empty() {
+            None
+        } else {
+            Some(
+                self.encryption
+                    .iter()
+                    .find(|algorithm| peer.encryption.contains(algorithm))
+                    .cloned()
+                    .ok_or_else(|| {
+                        format!(
+                            "No common encryption; own algorithms: {:?}; algorithms of peer: {:?}",
+                            self.encryption, peer.encryption
+                        )
+                    })?,
+            )
+        };
+        Ok(Agreement {
+            version: self.version.min(peer.version),
+            compression: peer
+                .compression
+                .iter()
+                .fold(0, |flags, flag| flags | (flag & COMPRESSION_FLAGS)),
+            encryption,
+            frame: peer.frame,
+            heartbeat: self.heartbeat && peer.heartbeat,
+            clock: None,
+        })
+    }
+
+    // Capabilities, which are sent in response: encryption is limited to agreed algorithm
+    pub fn agreed(&self, agreement: &Agreement) -> Self {
+        Capabilities {
+            encryption: agreement.encryption.iter().cloned().collect(),
+            ..self.clone()
+        }
+    }
+}
+
+static AGREEMENTS: Mutex<Option<HashMap<String, Agreement>>> = Mutex::new(None);
+
+fn agreements<T>(cb: impl FnOnce(&mut HashMap<String, Agreement>) -> T) -> Option<T> {
+    AGREEMENTS
+        .lock()
+        .ok()
+        .map(|mut agreements| cb(agreements.get_or_insert_with(HashMap::new)))
+}
+
+// Packages, which are packed with uuid, follow agreement with this peer
+pub fn set_agreement(uuid: &str, agreement: Agreement) {
+    agreements(|agreements| agreements.insert(uuid.to_owned(), agreement));
+}
+
+pub fn get_agreement(uuid: &str) -> Option<Agreement> {
+    agreements(|agreements| agreements.get(uuid).cloned()).flatten()
+}
+
+// Estimates clock of peer by package, which is just received, if it isn't estimated yet.
+// Timestamps of headers before version 2 are in seconds and aren't used
+pub fn observe_clock(uuid: &str, header: &PackageHeader) {
+    if header.version < MSG_VERSION_TS_MS {
+        return;
+    }
+    agreements(|agreements| {
+        if let Some(agreement) = agreements.get_mut(uuid) {
+            if agreement.clock.is_none() {
+                agreement.clock = Some(ClockSkew::one_way(header.ts));
+            }
+        }
+    });
+}
+
+pub fn get_clock_skew(uuid: &str) -> Option<ClockSkew> {
+    get_agreement(uuid).and_then(|agreement| agreement.clock)
+}
+
+pub fn remove_agreement(uuid: &str) {
+    agreements(|agreements| agreements.remove(uuid));
+}
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// injectable
+// Checksum is CRC32C (Castagnoli) of body as it's sent (after compression and middleware).
+// Package with checksum is marked with flag in header and has additional u32 field after
+// length of body, so packages with and without checksum can be mixed in one stream.
+// Typescript runtime has same implementation
+const CHECKSUM_POLYNOMIAL: u32 = 0x82F6_3B78;
+const CHECKSUM_TABLE: [u32; 256] = checksum_table();
+
+static CHECKSUM: AtomicBool = AtomicBool::new(false);
+
+const fn checksum_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CHECKSUM_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+// Checksum is added to all packages, which are packed by current process. Incoming packages
+// are validated, if they have checksum
+pub fn set_checksum(enabled: bool) {
+    CHECKSUM.store(enabled, Ordering::Relaxed);
+}
+
+pub fn get_checksum() -> bool {
+    CHECKSUM.load(Ordering::Relaxed)
+}
+
+pub fn crc32c(buf: &[u8]) -> u32 {
+    let mut crc: u32 = !0;
+    for byte in buf {
+        crc = CHECKSUM_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,259 @@
+use super::codec::CodecValue;
+use super::decode::{limits_items, limits_nested, limits_string};
+use bytes::BufMut;
+
+// injectable
+// CBOR: https://www.rfc-editor.org/rfc/rfc8949.html
+pub fn encode_body(value: &CodecValue) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(encoded_body_len(value));
+    cbor_write(value, &mut buf);
+    buf
+}
+
+pub fn encode_body_into(value: &CodecValue, buf: &mut impl BufMut) {
+    cbor_write(value, buf);
+}
+
+// Size of encoded value; it's calculated without encoding
+pub fn encoded_body_len(value: &CodecValue) -> usize {
+    match value {
+        CodecValue::Nil | CodecValue::Bool(_) => 1,
+        CodecValue::UInt(v) => cbor_head_len(*v),
+        CodecValue::Int(v) => {
+            if *v >= 0 {
+                cbor_head_len(*v as u64)
+            } else {
+                cbor_head_len(!(*v as u64))
+            }
+        }
+        CodecValue::F32(_) => 5,
+        CodecValue::F64(_) => 9,
+        CodecValue::Str(v) => cbor_head_len(v.len() as u64) + v.len(),
+        CodecValue::Array(items) => {
+            cbor_head_len(items.len() as u64) + items.iter().map(encoded_body_len).sum::<usize>()
+        }
+        CodecValue::Map(entries) => {
+            cbor_head_len(entries.len() as u64)
+                + entries
+                    .iter()
+                    .map(|(key, value)| {
+                        cbor_head_len(key.len() as u64) + key.len() + encoded_body_len(value)
+                    })
+                    .sum::<usize>()
+        }
+    }
+}
+
+fn cbor_head_len(n: u64) -> usize {
+    if n < 24 {
+        1
+    } else if n <= u8::MAX as u64 {
+        2
+    } else if n <= u16::MAX as u64 {
+        3
+    } else if n <= u32::MAX as u64 {
+        5
+    } else {
+        9
+    }
+}
+
+pub fn decode_body(buf: &[u8]) -> Result<CodecValue, String> {
+    let mut pos: usize = 0;
+    let value = cbor_read(buf, &mut pos)?;
+    if pos != buf.len() {
+        return Err(format!(
+            "Unexpected {} bytes after cbor value",
+            buf.len() - pos
+        ));
+    }
+    Ok(value)
+}
+
+fn cbor_write_head(major: u8, n: u64, buf: &mut impl BufMut) {
+    let major = major << 5;
+    if n < 24 {
+        buf.put_u8(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        buf.put_u8(major | 24);
+        buf.put_u8(n as u8);
+    } else if n <= u16::MAX as u64 {
+        buf.put_u8(major | 25);
+        buf.put_u16(n as u16);
+    } else if n <= u32::MAX as u64 {
+        buf.put_u8(major | 26);
+        buf.put_u32(n as u32);
+    } else {
+        buf.put_u8(major | 27);
+        buf.put_u64(n);
+    }
+}
+
+fn cbor_write_str(v: &str, buf: &mut impl BufMut) {
+    cbor_write_head(3, v.len() as u64, buf);
+    buf.put_slice(v.as_bytes());
+}
+
+fn cbor_write(value: &CodecValue, buf: &mut impl BufMut) {
+    match value {
+        CodecValue::Nil => buf.put_u8(0xf6),
+        CodecValue::Bool(v) => buf.put_u8(if *v { 0xf5 } else { 0xf4 }),
+        CodecValue::UInt(v) => cbor_write_head(0, *v, buf),
+        CodecValue::Int(v) => {
+            if *v >= 0 {
+                cbor_write_head(0, *v as u64, buf);
+            } else {
+                // Negative integer is encoded as -1 - n
+                cbor_write_head(1, !(*v as u64), buf);
+            }
+        }
+        CodecValue::F32(v) => {
+            buf.put_u8(0xfa);
+            buf.put_f32(*v);
+        }
+        CodecValue::F64(v) => {
+            buf.put_u8(0xfb);
+            buf.put_f64(*v);
+        }
+        CodecValue::Str(v) => cbor_write_str(v, buf),
+        CodecValue::Array(items) => {
+            cbor_write_head(4, items.len() as u64, buf);
+            for item in items.iter() {
+                cbor_write(item, buf);
+            }
+        }
+        CodecValue::Map(entries) => {
+            cbor_write_head(5, entries.len() as u64, buf);
+            for (key, value) in entries.iter() {
+                cbor_write_str(key, buf);
+                cbor_write(value, buf);
+            }
+        }
+    }
+}
+
+fn cbor_take<'a>(buf: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    if buf.len() < *pos + len {
+        return Err(format!(
+            "Unexpected end of cbor value: expecting {} bytes at {}",
+            len, pos
+        ));
+    }
+    let bytes = &buf[*pos..*pos + len];
+    *pos += len;
+    Ok(bytes)
+}
+
+fn cbor_uint(buf: &[u8], pos: &mut usize, len: usize) -> Result<u64, String> {
+    let bytes = cbor_take(buf, pos, len)?;
+    Ok(bytes.iter().fold(0_u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+fn cbor_argument(buf: &[u8], pos: &mut usize, info: u8) -> Result<u64, String> {
+    let len = match info {
+        0..=23 => return Ok(info as u64),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => {
+            return Err(format!(
+                "Unsupported additional information {} (indefinite length isn't supported)",
+                info
+            ))
+        }
+    };
+    cbor_uint(buf, pos, len)
+}
+
+fn cbor_len(n: u64) -> Result<usize, String> {
+    if n > usize::MAX as u64 {
+        Err(format!("Length {} is too big", n))
+    } else {
+        Ok(n as usize)
+    }
+}
+
+// Half-precision float isn't produced by encoder, but partners might send it
+fn cbor_half(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let mant = (bits & 0x3ff) as f32;
+    sign * match exp {
+        0 => mant * 2f32.powi(-24),
+        31 => {
+            if mant == 0.0 {
+                f32::INFINITY
+            } else {
+                f32::NAN
+            }
+        }
+        _ => (1.0 + mant / 1024.0) * 2f32.powi(exp - 15),
+    }
+}
+
+fn cbor_read(buf: &[u8], pos: &mut usize) -> Result<CodecValue, String> {
+    let head = cbor_take(buf, pos, 1)?[0];
+    let major = head >> 5;
+    let info = head & 0x1f;
+    match major {
+        0 => Ok(CodecValue::UInt(cbor_argument(buf, pos, info)?)),
+        1 => {
+            let n = cbor_argument(buf, pos, info)?;
+            if n > i64::MAX as u64 {
+                return Err(format!("Negative integer -1-{} is out of i64", n));
+            }
+            Ok(CodecValue::Int(-1 - n as i64))
+        }
+        3 => {
+            let len = cbor_len(cbor_argument(buf, pos, info)?)?;
+            limits_string(len)?;
+            String::from_utf8(cbor_take(buf, pos, len)?.to_vec())
+                .map(CodecValue::Str)
+                .map_err(|e| e.to_string())
+        }
+        4 => {
+            let len = cbor_len(cbor_argument(buf, pos, info)?)?;
+            limits_items(len)?;
+            limits_nested(|| {
+                let mut items: Vec<CodecValue> = vec![];
+                for _ in 0..len {
+                    items.push(cbor_read(buf, pos)?);
+                }
+                Ok(CodecValue::Array(items))
+            })
+        }
+        5 => {
+            let len = cbor_len(cbor_argument(buf, pos, info)?)?;
+            limits_items(len)?;
+            limits_nested(|| {
+                let mut entries: Vec<(String, CodecValue)> = vec![];
+                for _ in 0..len {
+                    let key = match cbor_read(buf, pos)? {
+                        CodecValue::Str(key) => key,
+                        key => {
+                            return Err(format!(
+                                "Expecting string as key of map, but got {:?}",
+                                key
+                            ))
+                        }
+                    };
+                    entries.push((key, cbor_read(buf, pos)?));
+                }
+                Ok(CodecValue::Map(entries))
+            })
+        }
+        7 => match info {
+            20 => Ok(CodecValue::Bool(false)),
+            21 => Ok(CodecValue::Bool(true)),
+            22 | 23 => Ok(CodecValue::Nil),
+            25 => Ok(CodecValue::F32(cbor_half(cbor_uint(buf, pos, 2)? as u16))),
+            26 => Ok(CodecValue::F32(f32::from_bits(
+                cbor_uint(buf, pos, 4)? as u32
+            ))),
+            27 => Ok(CodecValue::F64(f64::from_bits(cbor_uint(buf, pos, 8)?))),
+            _ => Err(format!("Unsupported cbor simple value {}", info)),
+        },
+        _ => Err(format!("Unsupported cbor major type {}", major)),
+    }
+}
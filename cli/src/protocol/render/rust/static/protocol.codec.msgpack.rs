@@ -0,0 +1,294 @@
+use super::codec::CodecValue;
+use super::decode::{limits_items, limits_nested, limits_string};
+use bytes::BufMut;
+
+// injectable
+// MessagePack: https://github.com/msgpack/msgpack/blob/master/spec.md
+pub fn encode_body(value: &CodecValue) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(encoded_body_len(value));
+    msgpack_write(value, &mut buf);
+    buf
+}
+
+pub fn encode_body_into(value: &CodecValue, buf: &mut impl BufMut) {
+    msgpack_write(value, buf);
+}
+
+// Size of encoded value; it's calculated without encoding
+pub fn encoded_body_len(value: &CodecValue) -> usize {
+    match value {
+        CodecValue::Nil | CodecValue::Bool(_) => 1,
+        CodecValue::UInt(v) => msgpack_uint_len(*v),
+        CodecValue::Int(v) => {
+            let v = *v;
+            if v >= 0 {
+                msgpack_uint_len(v as u64)
+            } else if v >= -32 {
+                1
+            } else if v >= i8::MIN as i64 {
+                2
+            } else if v >= i16::MIN as i64 {
+                3
+            } else if v >= i32::MIN as i64 {
+                5
+            } else {
+                9
+            }
+        }
+        CodecValue::F32(_) => 5,
+        CodecValue::F64(_) => 9,
+        CodecValue::Str(v) => msgpack_str_len(v),
+        CodecValue::Array(items) => {
+            msgpack_len_len(items.len(), 16, false)
+                + items.iter().map(encoded_body_len).sum::<usize>()
+        }
+        CodecValue::Map(entries) => {
+            msgpack_len_len(entries.len(), 16, false)
+                + entries
+                    .iter()
+                    .map(|(key, value)| msgpack_str_len(key) + encoded_body_len(value))
+                    .sum::<usize>()
+        }
+    }
+}
+
+fn msgpack_uint_len(v: u64) -> usize {
+    if v < 0x80 {
+        1
+    } else if v <= u8::MAX as u64 {
+        2
+    } else if v <= u16::MAX as u64 {
+        3
+    } else if v <= u32::MAX as u64 {
+        5
+    } else {
+        9
+    }
+}
+
+fn msgpack_str_len(v: &str) -> usize {
+    msgpack_len_len(v.len(), 32, true) + v.len()
+}
+
+// Size of marker with length; 8-bit length is used for strings only
+fn msgpack_len_len(len: usize, fix: usize, short: bool) -> usize {
+    if len < fix {
+        1
+    } else if len <= u8::MAX as usize && short {
+        2
+    } else if len <= u16::MAX as usize {
+        3
+    } else {
+        5
+    }
+}
+
+pub fn decode_body(buf: &[u8]) -> Result<CodecValue, String> {
+    let mut pos: usize = 0;
+    let value = msgpack_read(buf, &mut pos)?;
+    if pos != buf.len() {
+        return Err(format!(
+            "Unexpected {} bytes after msgpack value",
+            buf.len() - pos
+        ));
+    }
+    Ok(value)
+}
+
+fn msgpack_write_len(len: usize, fix: (u8, usize), markers: [u8; 3], buf: &mut impl BufMut) {
+    if len < fix.1 {
+        buf.put_u8(fix.0 | len as u8);
+    } else if len <= u8::MAX as usize && markers[0] != 0 {
+        buf.put_u8(markers[0]);
+        buf.put_u8(len as u8);
+    } else if len <= u16::MAX as usize {
+        buf.put_u8(markers[1]);
+        buf.put_u16(len as u16);
+    } else {
+        buf.put_u8(markers[2]);
+        buf.put_u32(len as u32);
+    }
+}
+
+fn msgpack_write_str(v: &str, buf: &mut impl BufMut) {
+    msgpack_write_len(v.len(), (0xa0, 32), [0xd9, 0xda, 0xdb], buf);
+    buf.put_slice(v.as_bytes());
+}
+
+fn msgpack_write(value: &CodecValue, buf: &mut impl BufMut) {
+    match value {
+        CodecValue::Nil => buf.put_u8(0xc0),
+        CodecValue::Bool(v) => buf.put_u8(if *v { 0xc3 } else { 0xc2 }),
+        CodecValue::UInt(v) => {
+            let v = *v;
+            if v < 0x80 {
+                buf.put_u8(v as u8);
+            } else if v <= u8::MAX as u64 {
+                buf.put_u8(0xcc);
+                buf.put_u8(v as u8);
+            } else if v <= u16::MAX as u64 {
+                buf.put_u8(0xcd);
+                buf.put_u16(v as u16);
+            } else if v <= u32::MAX as u64 {
+                buf.put_u8(0xce);
+                buf.put_u32(v as u32);
+            } else {
+                buf.put_u8(0xcf);
+                buf.put_u64(v);
+            }
+        }
+        CodecValue::Int(v) => {
+            let v = *v;
+            if v >= 0 {
+                msgpack_write(&CodecValue::UInt(v as u64), buf);
+            } else if v >= -32 {
+                buf.put_u8(v as i8 as u8);
+            } else if v >= i8::MIN as i64 {
+                buf.put_u8(0xd0);
+                buf.put_u8(v as i8 as u8);
+            } else if v >= i16::MIN as i64 {
+                buf.put_u8(0xd1);
+                buf.put_i16(v as i16);
+            } else if v >= i32::MIN as i64 {
+                buf.put_u8(0xd2);
+                buf.put_i32(v as i32);
+            } else {
+                buf.put_u8(0xd3);
+                buf.put_i64(v);
+            }
+        }
+        CodecValue::F32(v) => {
+            buf.put_u8(0xca);
+            buf.put_f32(*v);
+        }
+        CodecValue::F64(v) => {
+            buf.put_u8(0xcb);
+            buf.put_f64(*v);
+        }
+        CodecValue::Str(v) => msgpack_write_str(v, buf),
+        CodecValue::Array(items) => {
+            msgpack_write_len(items.len(), (0x90, 16), [0, 0xdc, 0xdd], buf);
+            for item in items.iter() {
+                msgpack_write(item, buf);
+            }
+        }
+        CodecValue::Map(entries) => {
+            msgpack_write_len(entries.len(), (0x80, 16), [0, 0xde, 0xdf], buf);
+            for (key, value) in entries.iter() {
+                msgpack_write_str(key, buf);
+                msgpack_write(value, buf);
+            }
+        }
+    }
+}
+
+fn msgpack_take<'a>(buf: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    if buf.len() < *pos + len {
+        return Err(format!(
+            "Unexpected end of msgpack value: expecting {} bytes at {}",
+            len, pos
+        ));
+    }
+    let bytes = &buf[*pos..*pos + len];
+    *pos += len;
+    Ok(bytes)
+}
+
+fn msgpack_uint(buf: &[u8], pos: &mut usize, len: usize) -> Result<u64, String> {
+    let bytes = msgpack_take(buf, pos, len)?;
+    Ok(bytes.iter().fold(0_u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+fn msgpack_str(buf: &[u8], pos: &mut usize, len: usize) -> Result<CodecValue, String> {
+    limits_string(len)?;
+    String::from_utf8(msgpack_take(buf, pos, len)?.to_vec())
+        .map(CodecValue::Str)
+        .map_err(|e| e.to_string())
+}
+
+fn msgpack_array(buf: &[u8], pos: &mut usize, len: usize) -> Result<CodecValue, String> {
+    limits_items(len)?;
+    limits_nested(|| {
+        let mut items: Vec<CodecValue> = vec![];
+        for _ in 0..len {
+            items.push(msgpack_read(buf, pos)?);
+        }
+        Ok(CodecValue::Array(items))
+    })
+}
+
+fn msgpack_map(buf: &[u8], pos: &mut usize, len: usize) -> Result<CodecValue, String> {
+    limits_items(len)?;
+    limits_nested(|| {
+        let mut entries: Vec<(String, CodecValue)> = vec![];
+        for _ in 0..len {
+            let key = match msgpack_read(buf, pos)? {
+                CodecValue::Str(key) => key,
+                key => return Err(format!("Expecting string as key of map, but got {:?}", key)),
+            };
+            entries.push((key, msgpack_read(buf, pos)?));
+        }
+        Ok(CodecValue::Map(entries))
+    })
+}
+
+fn msgpack_read(buf: &[u8], pos: &mut usize) -> Result<CodecValue, String> {
+    let marker = msgpack_take(buf, pos, 1)?[0];
+    match marker {
+        0x00..=0x7f => Ok(CodecValue::UInt(marker as u64)),
+        0x80..=0x8f => msgpack_map(buf, pos, (marker & 0x0f) as usize),
+        0x90..=0x9f => msgpack_array(buf, pos, (marker & 0x0f) as usize),
+        0xa0..=0xbf => msgpack_str(buf, pos, (marker & 0x1f) as usize),
+        0xc0 => Ok(CodecValue::Nil),
+        0xc2 => Ok(CodecValue::Bool(false)),
+        0xc3 => Ok(CodecValue::Bool(true)),
+        0xca => Ok(CodecValue::F32(f32::from_bits(
+            msgpack_uint(buf, pos, 4)? as u32
+        ))),
+        0xcb => Ok(CodecValue::F64(f64::from_bits(msgpack_uint(buf, pos, 8)?))),
+        0xcc => Ok(CodecValue::UInt(msgpack_uint(buf, pos, 1)?)),
+        0xcd => Ok(CodecValue::UInt(msgpack_uint(buf, pos, 2)?)),
+        0xce => Ok(CodecValue::UInt(msgpack_uint(buf, pos, 4)?)),
+        0xcf => Ok(CodecValue::UInt(msgpack_uint(buf, pos, 8)?)),
+        0xd0 => Ok(CodecValue::Int(
+            msgpack_uint(buf, pos, 1)? as u8 as i8 as i64
+        )),
+        0xd1 => Ok(CodecValue::Int(
+            msgpack_uint(buf, pos, 2)? as u16 as i16 as i64
+        )),
+        0xd2 => Ok(CodecValue::Int(
+            msgpack_uint(buf, pos, 4)? as u32 as i32 as i64
+        )),
+        0xd3 => Ok(CodecValue::Int(msgpack_uint(buf, pos, 8)? as i64)),
+        0xd9 => {
+            let len = msgpack_uint(buf, pos, 1)? as usize;
+            msgpack_str(buf, pos, len)
+        }
+        0xda => {
+            let len = msgpack_uint(buf, pos, 2)? as usize;
+            msgpack_str(buf, pos, len)
+        }
+        0xdb => {
+            let len = msgpack_uint(buf, pos, 4)? as usize;
+            msgpack_str(buf, pos, len)
+        }
+        0xdc => {
+            let len = msgpack_uint(buf, pos, 2)? as usize;
+            msgpack_array(buf, pos, len)
+        }
+        0xdd => {
+            let len = msgpack_uint(buf, pos, 4)? as usize;
+            msgpack_array(buf, pos, len)
+        }
+        0xde => {
+            let len = msgpack_uint(buf, pos, 2)? as usize;
+            msgpack_map(buf, pos, len)
+        }
+        0xdf => {
+            let len = msgpack_uint(buf, pos, 4)? as usize;
+            msgpack_map(buf, pos, len)
+        }
+        0xe0..=0xff => Ok(CodecValue::Int(marker as i8 as i64)),
+        _ => Err(format!("Unsupported msgpack type 0x{:02x}", marker)),
+    }
+}
@@ -0,0 +1,213 @@
+use super::body::{decode_body, encode_body};
+use super::storage::UnknownFields;
+
+// injectable
+// Body of message for self-describing codecs: structs are maps with names of fields,
+// enums are maps with one entry { "Variant": value }, empty optional fields are nil
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodecValue {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    F32(f32),
+    F64(f64),
+    Str(String),
+    Array(Vec<CodecValue>),
+    Map(Vec<(String, CodecValue)>),
+}
+
+static CODEC_NIL: CodecValue = CodecValue::Nil;
+
+impl CodecValue {
+    /// Returns value of map's entry or nil if entry doesn't exist
+    pub fn get(&self, key: &str) -> &CodecValue {
+        if let CodecValue::Map(entries) = self {
+            if let Some((_, value)) = entries.iter().find(|(name, _)| name == key) {
+                return value;
+            }
+        }
+        &CODEC_NIL
+    }
+}
+
+// Entries of map, which aren't fields of struct. Unknown fields are kept as bytes for all
+// codecs, so each entry is kept as encoded map with this entry only; id of field isn't used
+pub fn codec_unknown(value: &CodecValue, known: &[&str]) -> UnknownFields {
+    match value {
+        CodecValue::Map(entries) => entries
+            .iter()
+            .filter(|(name, _)| !known.contains(&name.as_str()))
+            .map(|entry| (0, encode_body(&CodecValue::Map(vec![entry.clone()]))))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+// Map of struct; unknown fields, which were kept on decoding, go after known ones
+pub fn codec_map(mut entries: Vec<(String, CodecValue)>, unknown: &[(u16, Vec<u8>)]) -> CodecValue {
+    for (_, body) in unknown.iter() {
+        if let Ok(CodecValue::Map(mut entry)) = decode_body(body) {
+            entries.append(&mut entry);
+        }
+    }
+    CodecValue::Map(entries)
+}
+
+pub trait ToCodecValue {
+    fn to_codec_value(&self) -> CodecValue;
+}
+
+pub trait FromCodecValue: Sized {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String>;
+}
+
+macro_rules! value_unsigned {
+    ($type:ty) => {
+        impl ToCodecValue for $type {
+            #[allow(clippy::unnecessary_cast)]
+            fn to_codec_value(&self) -> CodecValue {
+                CodecValue::UInt(*self as u64)
+            }
+        }
+        impl FromCodecValue for $type {
+            #[allow(clippy::useless_conversion)]
+            fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+                match value {
+                    CodecValue::UInt(v) => {
+                        <$type as std::convert::TryFrom<_>>::try_from(*v).map_err(|e| e.to_string())
+                    }
+                    CodecValue::Int(v) => {
+                        <$type as std::convert::TryFrom<_>>::try_from(*v).map_err(|e| e.to_string())
+                    }
+                    _ => Err(format!("Expecting integer for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! value_signed {
+    ($type:ty) => {
+        impl ToCodecValue for $type {
+            #[allow(clippy::unnecessary_cast)]
+            fn to_codec_value(&self) -> CodecValue {
+                CodecValue::Int(*self as i64)
+            }
+        }
+        impl FromCodecValue for $type {
+            #[allow(clippy::useless_conversion)]
+            fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+                match value {
+                    CodecValue::UInt(v) => {
+                        <$type as std::convert::TryFrom<_>>::try_from(*v).map_err(|e| e.to_string())
+                    }
+                    CodecValue::Int(v) => {
+                        <$type as std::convert::TryFrom<_>>::try_from(*v).map_err(|e| e.to_string())
+                    }
+                    _ => Err(format!("Expecting integer for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! value_float {
+    ($type:ty, $variant:ident) => {
+        impl ToCodecValue for $type {
+            fn to_codec_value(&self) -> CodecValue {
+                CodecValue::$variant(*self)
+            }
+        }
+        impl FromCodecValue for $type {
+            #[allow(clippy::unnecessary_cast)]
+            fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+                match value {
+                    CodecValue::F32(v) => Ok(*v as $type),
+                    CodecValue::F64(v) => Ok(*v as $type),
+                    CodecValue::UInt(v) => Ok(*v as $type),
+                    CodecValue::Int(v) => Ok(*v as $type),
+                    _ => Err(format!("Expecting number for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+value_unsigned!(u8);
+value_unsigned!(u16);
+value_unsigned!(u32);
+value_unsigned!(u64);
+value_signed!(i8);
+value_signed!(i16);
+value_signed!(i32);
+value_signed!(i64);
+value_float!(f32, F32);
+value_float!(f64, F64);
+
+impl ToCodecValue for bool {
+    fn to_codec_value(&self) -> CodecValue {
+        CodecValue::Bool(*self)
+    }
+}
+
+impl FromCodecValue for bool {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        match value {
+            CodecValue::Bool(value) => Ok(*value),
+            _ => Err(String::from("Expecting bool")),
+        }
+    }
+}
+
+impl ToCodecValue for String {
+    fn to_codec_value(&self) -> CodecValue {
+        CodecValue::Str(self.clone())
+    }
+}
+
+impl FromCodecValue for String {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        match value {
+            CodecValue::Str(value) => Ok(value.clone()),
+            _ => Err(String::from("Expecting string")),
+        }
+    }
+}
+
+impl<T: ToCodecValue> ToCodecValue for Vec<T> {
+    fn to_codec_value(&self) -> CodecValue {
+        CodecValue::Array(self.iter().map(|item| item.to_codec_value()).collect())
+    }
+}
+
+impl<T: FromCodecValue> FromCodecValue for Vec<T> {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        match value {
+            CodecValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| T::from_codec_value(item).map_err(|e| format!("[{}]: {}", i, e)))
+                .collect(),
+            _ => Err(String::from("Expecting array")),
+        }
+    }
+}
+
+impl<T: ToCodecValue> ToCodecValue for Option<T> {
+    fn to_codec_value(&self) -> CodecValue {
+        match self {
+            Some(value) => value.to_codec_value(),
+            None => CodecValue::Nil,
+        }
+    }
+}
+
+impl<T: FromCodecValue> FromCodecValue for Option<T> {
+    fn from_codec_value(value: &CodecValue) -> Result<Self, String> {
+        match value {
+            CodecValue::Nil => Ok(None),
+            value => T::from_codec_value(value).map(Some),
+        }
+    }
+}
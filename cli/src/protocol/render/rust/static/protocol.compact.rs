@@ -0,0 +1,351 @@
+use super::decode::{limits_items, limits_nested, limits_string};
+use super::storage::UnknownFields;
+use bytes::BufMut;
+
+// injectable
+// Compact body: | varint id | varint length | value | ... Unsigned integers are LEB128 varints,
+// signed integers are zigzag varints, empty optional fields aren't written at all
+pub trait CompactValue: Sized {
+    // Size of written value; it's calculated without writing, so value is written in one pass
+    fn compact_len(&self) -> usize;
+    fn compact_write(&self, buf: &mut impl BufMut);
+    fn compact_read(buf: &[u8]) -> Result<Self, String>;
+    // Items of arrays should be delimited; by default item is prefixed with length
+    fn compact_item_len(&self) -> usize {
+        let len = self.compact_len();
+        compact_varint_len(len as u64) + len
+    }
+    fn compact_write_item(&self, buf: &mut impl BufMut) {
+        compact_write_varint(self.compact_len() as u64, buf);
+        self.compact_write(buf);
+    }
+    fn compact_read_item(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+        let len = compact_read_len(buf, pos)?;
+        Self::compact_read(compact_take(buf, pos, len)?)
+    }
+    fn compact_skip(&self) -> bool {
+        false
+    }
+    fn compact_missing() -> Result<Self, String> {
+        Err(String::from("Field isn't found"))
+    }
+}
+
+pub fn compact_body<T: CompactValue>(value: &T) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(value.compact_len());
+    value.compact_write(&mut buf);
+    buf
+}
+
+pub fn compact_varint_len(value: u64) -> usize {
+    // Each byte keeps 7 bits; zero takes one byte
+    ((64 - value.leading_zeros() as usize).max(1) + 6) / 7
+}
+
+pub fn compact_write_varint(mut value: u64, buf: &mut impl BufMut) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+pub fn compact_read_varint(buf: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let byte = compact_take(buf, pos, 1)?[0];
+        // 10th byte can keep only one bit of u64
+        if shift == 63 && byte > 1 {
+            return Err(String::from("Varint is out of u64"));
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+pub fn compact_zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub fn compact_unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+pub fn compact_read_len(buf: &[u8], pos: &mut usize) -> Result<usize, String> {
+    let len = compact_read_varint(buf, pos)?;
+    if len > (buf.len() - *pos) as u64 {
+        return Err(format!(
+            "Length {} is bigger than rest of buffer ({} bytes)",
+            len,
+            buf.len() - *pos
+        ));
+    }
+    Ok(len as usize)
+}
+
+pub fn compact_take<'a>(buf: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    if buf.len() < *pos + len {
+        return Err(format!(
+            "Unexpected end of buffer: expecting {} bytes at {}",
+            len, pos
+        ));
+    }
+    let bytes = &buf[*pos..*pos + len];
+    *pos += len;
+    Ok(bytes)
+}
+
+fn compact_read_whole<T: CompactValue>(buf: &[u8]) -> Result<T, String> {
+    let mut pos: usize = 0;
+    let value = T::compact_read_item(buf, &mut pos)?;
+    if pos != buf.len() {
+        return Err(format!("Unexpected {} bytes after value", buf.len() - pos));
+    }
+    Ok(value)
+}
+
+pub fn compact_field_len<T: CompactValue>(id: u16, value: &T) -> usize {
+    if value.compact_skip() {
+        return 0;
+    }
+    let len = value.compact_len();
+    compact_varint_len(id as u64) + compact_varint_len(len as u64) + len
+}
+
+pub fn compact_field<T: CompactValue>(buf: &mut impl BufMut, id: u16, value: &T) {
+    if value.compact_skip() {
+        return;
+    }
+    compact_write_varint(id as u64, buf);
+    compact_write_varint(value.compact_len() as u64, buf);
+    value.compact_write(buf);
+}
+
+pub fn compact_fields(buf: &[u8]) -> Result<Vec<(u64, &[u8])>, String> {
+    let mut fields: Vec<(u64, &[u8])> = vec![];
+    let mut pos: usize = 0;
+    while pos < buf.len() {
+        let id = compact_read_varint(buf, &mut pos)?;
+        let len = compact_read_len(buf, &mut pos)?;
+        fields.push((id, compact_take(buf, &mut pos, len)?));
+    }
+    Ok(fields)
+}
+
+pub fn compact_get<T: CompactValue>(fields: &[(u64, &[u8])], id: u16) -> Result<T, String> {
+    match fields.iter().find(|(field, _)| *field == id as u64) {
+        Some((_, body)) => limits_nested(|| T::compact_read(body)),
+        None => T::compact_missing(),
+    }
+}
+
+pub fn compact_unknown(fields: &[(u64, &[u8])], known: &[u16]) -> UnknownFields {
+    let mut unknown: UnknownFields = fields
+        .iter()
+        .filter_map(
+            |(id, body)| match <u16 as std::convert::TryFrom<u64>>::try_from(*id) {
+                Ok(id) if !known.contains(&id) => Some((id, body.to_vec())),
+                _ => None,
+            },
+        )
+        .collect();
+    unknown.sort_by_key(|(id, _)| *id);
+    unknown
+}
+
+pub fn compact_unknown_len(unknown: &[(u16, Vec<u8>)]) -> usize {
+    unknown
+        .iter()
+        .map(|(id, body)| {
+            compact_varint_len(*id as u64) + compact_varint_len(body.len() as u64) + body.len()
+        })
+        .sum()
+}
+
+pub fn compact_write_unknown(buf: &mut impl BufMut, unknown: &[(u16, Vec<u8>)]) {
+    for (id, body) in unknown.iter() {
+        compact_write_varint(*id as u64, buf);
+        compact_write_varint(body.len() as u64, buf);
+        buf.put_slice(body);
+    }
+}
+
+macro_rules! compact_unsigned {
+    ($type:ty) => {
+        impl CompactValue for $type {
+            #[allow(clippy::unnecessary_cast)]
+            fn compact_len(&self) -> usize {
+                compact_varint_len(*self as u64)
+            }
+            #[allow(clippy::unnecessary_cast)]
+            fn compact_write(&self, buf: &mut impl BufMut) {
+                compact_write_varint(*self as u64, buf);
+            }
+            fn compact_read(buf: &[u8]) -> Result<Self, String> {
+                compact_read_whole(buf)
+            }
+            fn compact_item_len(&self) -> usize {
+                self.compact_len()
+            }
+            fn compact_write_item(&self, buf: &mut impl BufMut) {
+                self.compact_write(buf);
+            }
+            #[allow(clippy::useless_conversion)]
+            fn compact_read_item(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+                <$type as std::convert::TryFrom<u64>>::try_from(compact_read_varint(buf, pos)?)
+                    .map_err(|e| e.to_string())
+            }
+        }
+    };
+}
+
+macro_rules! compact_signed {
+    ($type:ty) => {
+        impl CompactValue for $type {
+            #[allow(clippy::unnecessary_cast)]
+            fn compact_len(&self) -> usize {
+                compact_varint_len(compact_zigzag(*self as i64))
+            }
+            #[allow(clippy::unnecessary_cast)]
+            fn compact_write(&self, buf: &mut impl BufMut) {
+                compact_write_varint(compact_zigzag(*self as i64), buf);
+            }
+            fn compact_read(buf: &[u8]) -> Result<Self, String> {
+                compact_read_whole(buf)
+            }
+            fn compact_item_len(&self) -> usize {
+                self.compact_len()
+            }
+            fn compact_write_item(&self, buf: &mut impl BufMut) {
+                self.compact_write(buf);
+            }
+            #[allow(clippy::useless_conversion)]
+            fn compact_read_item(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+                <$type as std::convert::TryFrom<i64>>::try_from(compact_unzigzag(
+                    compact_read_varint(buf, pos)?,
+                ))
+                .map_err(|e| e.to_string())
+            }
+        }
+    };
+}
+
+// Values, which are already as small as possible, are kept with fixed size
+macro_rules! compact_fixed {
+    ($type:ty, $len:expr) => {
+        impl CompactValue for $type {
+            fn compact_len(&self) -> usize {
+                $len
+            }
+            fn compact_write(&self, buf: &mut impl BufMut) {
+                buf.put_slice(&self.to_le_bytes());
+            }
+            fn compact_read(buf: &[u8]) -> Result<Self, String> {
+                compact_read_whole(buf)
+            }
+            fn compact_item_len(&self) -> usize {
+                $len
+            }
+            fn compact_write_item(&self, buf: &mut impl BufMut) {
+                self.compact_write(buf);
+            }
+            fn compact_read_item(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+                let mut bytes = [0u8; $len];
+                bytes.copy_from_slice(compact_take(buf, pos, $len)?);
+                Ok(<$type>::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+compact_unsigned!(u16);
+compact_unsigned!(u32);
+compact_unsigned!(u64);
+compact_signed!(i16);
+compact_signed!(i32);
+compact_signed!(i64);
+compact_fixed!(u8, 1);
+compact_fixed!(i8, 1);
+compact_fixed!(f32, 4);
+compact_fixed!(f64, 8);
+
+impl CompactValue for bool {
+    fn compact_len(&self) -> usize {
+        1
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {
+        buf.put_u8(if *self { 1 } else { 0 });
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        compact_read_whole(buf)
+    }
+    fn compact_item_len(&self) -> usize {
+        1
+    }
+    fn compact_write_item(&self, buf: &mut impl BufMut) {
+        self.compact_write(buf);
+    }
+    fn compact_read_item(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+        Ok(compact_take(buf, pos, 1)?[0] != 0)
+    }
+}
+
+impl CompactValue for String {
+    fn compact_len(&self) -> usize {
+        self.len()
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_bytes());
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        limits_string(buf.len())?;
+        String::from_utf8(buf.to_vec()).map_err(|e| e.to_string())
+    }
+}
+
+impl<T: CompactValue> CompactValue for Vec<T> {
+    fn compact_len(&self) -> usize {
+        self.iter().map(|item| item.compact_item_len()).sum()
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {
+        for item in self.iter() {
+            item.compact_write_item(buf);
+        }
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        let mut items: Vec<T> = vec![];
+        let mut pos: usize = 0;
+        while pos < buf.len() {
+            limits_items(items.len() + 1)?;
+            items.push(
+                T::compact_read_item(buf, &mut pos)
+                    .map_err(|e| format!("[{}]: {}", items.len(), e))?,
+            );
+        }
+        Ok(items)
+    }
+}
+
+impl<T: CompactValue> CompactValue for Option<T> {
+    fn compact_len(&self) -> usize {
+        self.as_ref().map(|value| value.compact_len()).unwrap_or(0)
+    }
+    fn compact_write(&self, buf: &mut impl BufMut) {
+        if let Some(value) = self {
+            value.compact_write(buf);
+        }
+    }
+    fn compact_read(buf: &[u8]) -> Result<Self, String> {
+        T::compact_read(buf).map(Some)
+    }
+    fn compact_skip(&self) -> bool {
+        self.is_none()
+    }
+    fn compact_missing() -> Result<Self, String> {
+        Ok(None)
+    }
+}
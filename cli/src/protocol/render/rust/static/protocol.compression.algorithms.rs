@@ -0,0 +1,509 @@
+use super::compression::{COMPRESSION_FLAG_DEFLATE, COMPRESSION_FLAG_LZ4};
+
+// injectable
+// Algorithms are kept apart from settings of compression, so standalone protocol crate
+// can be built without them
+pub const COMPRESSION_FLAGS: u8 = COMPRESSION_FLAG_DEFLATE | COMPRESSION_FLAG_LZ4;
+
+// Returns None, if flag doesn't define known algorithm
+pub(crate) fn compress_body(flag: u8, body: &[u8]) -> Option<Vec<u8>> {
+    match flag {
+        COMPRESSION_FLAG_DEFLATE => Some(deflate_compress(body)),
+        COMPRESSION_FLAG_LZ4 => Some(lz4_compress(body)),
+        _ => None,
+    }
+}
+
+pub(crate) fn decompress_body(flag: u8, body: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    match flag {
+        COMPRESSION_FLAG_DEFLATE => deflate_decompress(body, len),
+        COMPRESSION_FLAG_LZ4 => lz4_decompress(body, len),
+        _ => Err(format!("Unknown compression flag: {}", flag)),
+    }
+}
+
+fn compression_hash(value: u32, bits: u32) -> usize {
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - bits)) as usize
+}
+
+const LZ4_HASH_BITS: u32 = 12;
+const LZ4_MIN_MATCH: usize = 4;
+// Last 5 bytes are always literals; last match starts 12 bytes before end at least
+const LZ4_LAST_LITERALS: usize = 5;
+const LZ4_MF_LIMIT: usize = 12;
+const LZ4_MAX_OFFSET: usize = 65535;
+
+fn lz4_read_u32(src: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([src[pos], src[pos + 1], src[pos + 2], src[pos + 3]])
+}
+
+fn lz4_put_len(mut len: usize, out: &mut Vec<u8>) {
+    while len >= 255 {
+        out.push(255);
+        len -= 255;
+    }
+    out.push(len as u8);
+}
+
+fn lz4_put_sequence(literals: &[u8], matched: Option<(usize, usize)>, out: &mut Vec<u8>) {
+    let match_len = matched.map(|(_, len)| len - LZ4_MIN_MATCH).unwrap_or(0);
+    out.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literals.len() >= 15 {
+        lz4_put_len(literals.len() - 15, out);
+    }
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            lz4_put_len(match_len - 15, out);
+        }
+    }
+}
+
+fn lz4_compress(src: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::with_capacity(src.len());
+    let mut table: Vec<usize> = vec![usize::MAX; 1 << LZ4_HASH_BITS];
+    let mut anchor: usize = 0;
+    let mut pos: usize = 0;
+    while pos + LZ4_MF_LIMIT <= src.len() {
+        let sequence = lz4_read_u32(src, pos);
+        let hash = compression_hash(sequence, LZ4_HASH_BITS);
+        let candidate = table[hash];
+        table[hash] = pos;
+        if candidate != usize::MAX
+            && pos - candidate <= LZ4_MAX_OFFSET
+            && lz4_read_u32(src, candidate) == sequence
+        {
+            let mut len = LZ4_MIN_MATCH;
+            while pos + len < src.len() - LZ4_LAST_LITERALS
+                && src[candidate + len] == src[pos + len]
+            {
+                len += 1;
+            }
+            lz4_put_sequence(&src[anchor..pos], Some((pos - candidate, len)), &mut out);
+            pos += len;
+            anchor = pos;
+        } else {
+            pos += 1;
+        }
+    }
+    lz4_put_sequence(&src[anchor..], None, &mut out);
+    out
+}
+
+fn lz4_read_len(src: &[u8], pos: &mut usize) -> Result<usize, String> {
+    let mut len: usize = 0;
+    loop {
+        let byte = *src
+            .get(*pos)
+            .ok_or_else(|| String::from("LZ4: unexpected end of data"))?;
+        *pos += 1;
+        len += byte as usize;
+        if byte != 255 {
+            return Ok(len);
+        }
+    }
+}
+
+fn lz4_decompress(src: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut out: Vec<u8> = Vec::new();
+    let mut pos: usize = 0;
+    loop {
+        let token = *src
+            .get(pos)
+            .ok_or_else(|| String::from("LZ4: unexpected end of data"))?;
+        pos += 1;
+        let mut literals = (token >> 4) as usize;
+        if literals == 15 {
+            literals += lz4_read_len(src, &mut pos)?;
+        }
+        if src.len() - pos < literals || len - out.len() < literals {
+            return Err(String::from("LZ4: literals are out of bounds"));
+        }
+        out.extend_from_slice(&src[pos..pos + literals]);
+        pos += literals;
+        // Last sequence has only literals
+        if pos == src.len() {
+            return Ok(out);
+        }
+        if src.len() - pos < 2 {
+            return Err(String::from("LZ4: unexpected end of data"));
+        }
+        let offset = u16::from_le_bytes([src[pos], src[pos + 1]]) as usize;
+        pos += 2;
+        if offset == 0 || offset > out.len() {
+            return Err(format!("LZ4: invalid offset {}", offset));
+        }
+        let mut match_len = (token & 15) as usize;
+        if match_len == 15 {
+            match_len += lz4_read_len(src, &mut pos)?;
+        }
+        match_len += LZ4_MIN_MATCH;
+        if len - out.len() < match_len {
+            return Err(String::from("LZ4: match is out of bounds"));
+        }
+        let start = out.len() - offset;
+        for i in 0..match_len {
+            let byte = out[start + i];
+            out.push(byte);
+        }
+    }
+}
+
+const DEFLATE_HASH_BITS: u32 = 15;
+const DEFLATE_MIN_MATCH: usize = 3;
+const DEFLATE_MAX_MATCH: usize = 258;
+const DEFLATE_WINDOW: usize = 32768;
+const DEFLATE_LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const DEFLATE_LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DEFLATE_DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DEFLATE_DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const DEFLATE_CODES_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct DeflateWriter {
+    out: Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl DeflateWriter {
+    fn put(&mut self, value: u32, len: u32) {
+        self.bits |= value << self.count;
+        self.count += len;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Huffman codes are written starting from most significant bit
+    fn put_code(&mut self, code: u32, len: u32) {
+        let mut reversed: u32 = 0;
+        for i in 0..len {
+            reversed |= ((code >> i) & 1) << (len - 1 - i);
+        }
+        self.put(reversed, len);
+    }
+
+    fn put_symbol(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.put_code(0x30 + symbol, 8),
+            144..=255 => self.put_code(0x190 + symbol - 144, 9),
+            256..=279 => self.put_code(symbol - 256, 7),
+            _ => self.put_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
+    }
+}
+
+fn deflate_code(base: &[u16], value: usize) -> usize {
+    base.iter()
+        .rposition(|b| (*b as usize) <= value)
+        .unwrap_or(0)
+}
+
+// One final block with fixed Huffman codes
+fn deflate_compress(src: &[u8]) -> Vec<u8> {
+    let mut writer = DeflateWriter {
+        out: Vec::with_capacity(src.len()),
+        bits: 0,
+        count: 0,
+    };
+    writer.put(1, 1);
+    writer.put(1, 2);
+    let mut table: Vec<usize> = vec![usize::MAX; 1 << DEFLATE_HASH_BITS];
+    let mut pos: usize = 0;
+    while pos < src.len() {
+        let mut matched: Option<(usize, usize)> = None;
+        if pos + DEFLATE_MIN_MATCH <= src.len() {
+            let hash = compression_hash(
+                ((src[pos] as u32) << 16) | ((src[pos + 1] as u32) << 8) | src[pos + 2] as u32,
+                DEFLATE_HASH_BITS,
+            );
+            let candidate = table[hash];
+            table[hash] = pos;
+            if candidate != usize::MAX && pos - candidate <= DEFLATE_WINDOW {
+                let mut len: usize = 0;
+                while len < DEFLATE_MAX_MATCH
+                    && pos + len < src.len()
+                    && src[candidate + len] == src[pos + len]
+                {
+                    len += 1;
+                }
+                if len >= DEFLATE_MIN_MATCH {
+                    matched = Some((pos - candidate, len));
+                }
+            }
+        }
+        if let Some((distance, len)) = matched {
+            let code = deflate_code(&DEFLATE_LENGTH_BASE, len);
+            writer.put_symbol(257 + code as u32);
+            writer.put(
+                (len - DEFLATE_LENGTH_BASE[code] as usize) as u32,
+                DEFLATE_LENGTH_EXTRA[code] as u32,
+            );
+            let code = deflate_code(&DEFLATE_DIST_BASE, distance);
+            writer.put_code(code as u32, 5);
+            writer.put(
+                (distance - DEFLATE_DIST_BASE[code] as usize) as u32,
+                DEFLATE_DIST_EXTRA[code] as u32,
+            );
+            pos += len;
+        } else {
+            writer.put_symbol(src[pos] as u32);
+            pos += 1;
+        }
+    }
+    writer.put_symbol(256);
+    writer.finish()
+}
+
+struct DeflateReader<'a> {
+    src: &'a [u8],
+    pos: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl<'a> DeflateReader<'a> {
+    fn get(&mut self, len: u32) -> Result<u32, String> {
+        while self.count < len {
+            let byte = *self
+                .src
+                .get(self.pos)
+                .ok_or_else(|| String::from("Deflate: unexpected end of data"))?;
+            self.bits |= (byte as u32) << self.count;
+            self.count += 8;
+            self.pos += 1;
+        }
+        let value = self.bits & ((1u32 << len) - 1);
+        self.bits >>= len;
+        self.count -= len;
+        Ok(value)
+    }
+}
+
+struct DeflateHuffman {
+    count: [u16; 16],
+    symbol: Vec<u16>,
+}
+
+impl DeflateHuffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut count = [0u16; 16];
+        for len in lengths.iter() {
+            count[*len as usize] += 1;
+        }
+        let mut left: i32 = 1;
+        for len in 1..16 {
+            left = (left << 1) - count[len] as i32;
+            if left < 0 {
+                return Err(String::from("Deflate: over-subscribed Huffman code"));
+            }
+        }
+        let mut offsets = [0usize; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + count[len] as usize;
+        }
+        let mut symbol: Vec<u16> = vec![0; lengths.len()];
+        for (sym, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                symbol[offsets[*len as usize]] = sym as u16;
+                offsets[*len as usize] += 1;
+            }
+        }
+        Ok(DeflateHuffman { count, symbol })
+    }
+
+    fn decode(&self, reader: &mut DeflateReader) -> Result<usize, String> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= reader.get(1)? as i32;
+            let count = self.count[len] as i32;
+            if code - count < first {
+                return Ok(self.symbol[(index + (code - first)) as usize] as usize);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(String::from("Deflate: invalid Huffman code"))
+    }
+}
+
+fn deflate_codes(
+    reader: &mut DeflateReader,
+    lengths: &DeflateHuffman,
+    distances: &DeflateHuffman,
+    out: &mut Vec<u8>,
+    len: usize,
+) -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(reader)?;
+        if symbol < 256 {
+            if out.len() >= len {
+                return Err(String::from("Deflate: data is out of bounds"));
+            }
+            out.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let code = symbol - 257;
+            if code >= DEFLATE_LENGTH_BASE.len() {
+                return Err(format!("Deflate: invalid length code {}", symbol));
+            }
+            let length = DEFLATE_LENGTH_BASE[code] as usize
+                + reader.get(DEFLATE_LENGTH_EXTRA[code] as u32)? as usize;
+            let code = distances.decode(reader)?;
+            if code >= DEFLATE_DIST_BASE.len() {
+                return Err(format!("Deflate: invalid distance code {}", code));
+            }
+            let distance = DEFLATE_DIST_BASE[code] as usize
+                + reader.get(DEFLATE_DIST_EXTRA[code] as u32)? as usize;
+            if distance > out.len() {
+                return Err(format!("Deflate: invalid distance {}", distance));
+            }
+            if len - out.len() < length {
+                return Err(String::from("Deflate: data is out of bounds"));
+            }
+            let start = out.len() - distance;
+            for i in 0..length {
+                let byte = out[start + i];
+                out.push(byte);
+            }
+        }
+    }
+}
+
+fn deflate_dynamic(reader: &mut DeflateReader) -> Result<(DeflateHuffman, DeflateHuffman), String> {
+    let nlen = reader.get(5)? as usize + 257;
+    let ndist = reader.get(5)? as usize + 1;
+    let ncode = reader.get(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(String::from("Deflate: invalid count of codes"));
+    }
+    let mut lengths = [0u8; 19];
+    for index in DEFLATE_CODES_ORDER.iter().take(ncode) {
+        lengths[*index] = reader.get(3)? as u8;
+    }
+    let codes = DeflateHuffman::new(&lengths)?;
+    let mut lengths: Vec<u8> = vec![0; nlen + ndist];
+    let mut index: usize = 0;
+    while index < nlen + ndist {
+        let symbol = codes.decode(reader)?;
+        if symbol < 16 {
+            lengths[index] = symbol as u8;
+            index += 1;
+            continue;
+        }
+        let (len, repeat) = match symbol {
+            16 => {
+                if index == 0 {
+                    return Err(String::from("Deflate: repeat without previous length"));
+                }
+                (lengths[index - 1], 3 + reader.get(2)? as usize)
+            }
+            17 => (0, 3 + reader.get(3)? as usize),
+            _ => (0, 11 + reader.get(7)? as usize),
+        };
+        if index + repeat > nlen + ndist {
+            return Err(String::from("Deflate: too many lengths"));
+        }
+        for length in lengths.iter_mut().skip(index).take(repeat) {
+            *length = len;
+        }
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(String::from("Deflate: no end of block code"));
+    }
+    Ok((
+        DeflateHuffman::new(&lengths[..nlen])?,
+        DeflateHuffman::new(&lengths[nlen..])?,
+    ))
+}
+
+fn deflate_decompress(src: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut reader = DeflateReader {
+        src,
+        pos: 0,
+        bits: 0,
+        count: 0,
+    };
+    let mut out: Vec<u8> = Vec::new();
+    loop {
+        let last = reader.get(1)?;
+        match reader.get(2)? {
+            0 => {
+                // Stored block starts from next byte
+                reader.bits = 0;
+                reader.count = 0;
+                if src.len() - reader.pos < 4 {
+                    return Err(String::from("Deflate: unexpected end of data"));
+                }
+                let size = u16::from_le_bytes([src[reader.pos], src[reader.pos + 1]]);
+                let nsize = u16::from_le_bytes([src[reader.pos + 2], src[reader.pos + 3]]);
+                if size != !nsize {
+                    return Err(String::from("Deflate: invalid size of stored block"));
+                }
+                reader.pos += 4;
+                let size = size as usize;
+                if src.len() - reader.pos < size || len - out.len() < size {
+                    return Err(String::from("Deflate: stored block is out of bounds"));
+                }
+                out.extend_from_slice(&src[reader.pos..reader.pos + size]);
+                reader.pos += size;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                for (symbol, length) in lengths.iter_mut().enumerate() {
+                    *length = match symbol {
+                        0..=143 => 8,
+                        144..=255 => 9,
+                        256..=279 => 7,
+                        _ => 8,
+                    };
+                }
+                deflate_codes(
+                    &mut reader,
+                    &DeflateHuffman::new(&lengths)?,
+                    &DeflateHuffman::new(&[5u8; 30])?,
+                    &mut out,
+                    len,
+                )?;
+            }
+            2 => {
+                let (lengths, distances) = deflate_dynamic(&mut reader)?;
+                deflate_codes(&mut reader, &lengths, &distances, &mut out, len)?;
+            }
+            kind => {
+                return Err(format!("Deflate: invalid type of block {}", kind));
+            }
+        }
+        if last == 1 {
+            return Ok(out);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use super::compression_algorithms::{compress_body, decompress_body};
+use super::decode::limits_frame;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+// injectable
+// Body of package can be compressed with deflate (RFC 1951) or LZ4 (block format). Compressed
+// body: | u32 length of original body | compressed data |. Algorithm is marked with flag in
+// header of package, so compressed and not compressed packages can be mixed in one stream.
+// Typescript runtime has same implementation and gives same bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    None,
+    Deflate,
+    Lz4,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    pub algorithm: CompressionAlgorithm,
+    // Bodies, which are smaller (in bytes), aren't compressed
+    pub threshold: usize,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression {
+            algorithm: CompressionAlgorithm::None,
+            threshold: 1024,
+        }
+    }
+}
+
+pub const COMPRESSION_FLAG_DEFLATE: u8 = 1;
+pub const COMPRESSION_FLAG_LZ4: u8 = 2;
+
+static COMPRESSION_ALGORITHM: AtomicU8 = AtomicU8::new(0);
+static COMPRESSION_THRESHOLD: AtomicUsize = AtomicUsize::new(1024);
+
+// Compression is used for all packages, which are packed by current process. Incoming packages
+// are decompressed in any case
+pub fn set_compression(compression: &Compression) {
+    COMPRESSION_ALGORITHM.store(
+        match compression.algorithm {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Deflate => COMPRESSION_FLAG_DEFLATE,
+            CompressionAlgorithm::Lz4 => COMPRESSION_FLAG_LZ4,
+        },
+        Ordering::Relaxed,
+    );
+    COMPRESSION_THRESHOLD.store(compression.threshold, Ordering::Relaxed);
+}
+
+pub fn get_compression() -> Compression {
+    Compression {
+        algorithm: match COMPRESSION_ALGORITHM.load(Ordering::Relaxed) {
+            COMPRESSION_FLAG_DEFLATE => CompressionAlgorithm::Deflate,
+            COMPRESSION_FLAG_LZ4 => CompressionAlgorithm::Lz4,
+            _ => CompressionAlgorithm::None,
+        },
+        threshold: COMPRESSION_THRESHOLD.load(Ordering::Relaxed),
+    }
+}
+
+// Returns flag of algorithm, which should be used for body of given size; 0 - no compression.
+// Configured algorithm is used, if peer can read it (readable - flags of algorithms); otherwise
+// other algorithm, which peer can read
+pub fn compression_flag(len: usize, readable: u8) -> u8 {
+    let flag = match COMPRESSION_ALGORITHM.load(Ordering::Relaxed) {
+        0 => 0,
+        flag if flag & readable != 0 => flag,
+        _ => readable & readable.wrapping_neg(),
+    };
+    if flag == 0 || len < COMPRESSION_THRESHOLD.load(Ordering::Relaxed) || len > u32::MAX as usize {
+        0
+    } else {
+        flag
+    }
+}
+
+// Body is kept as it is, if compression doesn't make it smaller
+pub fn compress(body: Vec<u8>, readable: u8) -> (u8, Vec<u8>) {
+    let flag = compression_flag(body.len(), readable);
+    let data = match compress_body(flag, &body) {
+        Some(data) => data,
+        None => {
+            return (0, body);
+        }
+    };
+    if data.len() + 4 >= body.len() {
+        return (0, body);
+    }
+    let mut compressed: Vec<u8> = Vec::with_capacity(data.len() + 4);
+    compressed.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    compressed.extend_from_slice(&data);
+    (flag, compressed)
+}
+
+pub fn decompress(flag: u8, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    if flag == 0 {
+        return Ok(body);
+    }
+    if body.len() < 4 {
+        return Err(String::from("Compressed body is too small"));
+    }
+    let len = u32::from_le_bytes([body[0], body[1], body[2], body[3]]) as usize;
+    limits_frame(len)?;
+    let decompressed = decompress_body(flag, &body[4..], len)?;
+    if decompressed.len() != len {
+        return Err(format!(
+            "Decompressed body has {} bytes, but expected {} bytes",
+            decompressed.len(),
+            len
+        ));
+    }
+    Ok(decompressed)
+}
@@ -4,6 +4,131 @@ use std::io::Cursor;
 use storage::Storage;
 
 // injectable
+// Limits of incoming data. Buffer checks size of frames and count of pending messages;
+// other limits are applied while message is decoded
+#[derive(Debug, Clone)]
+pub struct Limits {
+    // Max length of package body in bytes
+    pub frame: usize,
+    // Max length of string in bytes
+    pub string: usize,
+    // Max count of items in array
+    pub items: usize,
+    // Max nesting of structs and enums
+    pub depth: usize,
+    // Max count of decoded messages, which aren't taken from buffer
+    pub pending: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            frame: 64 * 1024 * 1024,
+            string: 16 * 1024 * 1024,
+            items: 4 * 1024 * 1024,
+            depth: 64,
+            pending: 4096,
+        }
+    }
+}
+
+struct LimitsContext {
+    limits: Limits,
+    depth: usize,
+    exceeded: Option<String>,
+}
+
+thread_local! {
+    static LIMITS: std::cell::RefCell<Option<LimitsContext>> = std::cell::RefCell::new(None);
+}
+
+// Applies limits to everything decoded by decode. Returns description of exceeded
+// limit (if it was) beside result
+pub fn with_limits<T>(limits: &Limits, decode: impl FnOnce() -> T) -> (T, Option<String>) {
+    let prev = LIMITS.with(|ctx| {
+        ctx.replace(Some(LimitsContext {
+            limits: limits.clone(),
+            depth: 0,
+            exceeded: None,
+        }))
+    });
+    let result = decode();
+    let ctx = LIMITS.with(|ctx| ctx.replace(prev));
+    (result, ctx.and_then(|ctx| ctx.exceeded))
+}
+
+fn limits_check(check: impl FnOnce(&mut LimitsContext) -> Option<String>) -> Result<(), String> {
+    LIMITS.with(|ctx| {
+        if let Some(ctx) = ctx.borrow_mut().as_mut() {
+            if let Some(err) = check(ctx) {
+                ctx.exceeded = Some(err.clone());
+                return Err(err);
+            }
+        }
+        Ok(())
+    })
+}
+
+pub fn limits_frame(len: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if len > ctx.limits.frame {
+            Some(format!(
+                "Size of decompressed body ({} bytes) is bigger than limit ({} bytes)",
+                len, ctx.limits.frame
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_string(len: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if len > ctx.limits.string {
+            Some(format!(
+                "Length of string ({} bytes) is bigger than limit ({} bytes)",
+                len, ctx.limits.string
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_items(count: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if count > ctx.limits.items {
+            Some(format!(
+                "Count of items in array ({}) is bigger than limit ({})",
+                count, ctx.limits.items
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_nested<T>(decode: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    limits_check(|ctx| {
+        if ctx.depth >= ctx.limits.depth {
+            Some(format!(
+                "Nesting is deeper than limit ({})",
+                ctx.limits.depth
+            ))
+        } else {
+            ctx.depth += 1;
+            None
+        }
+    })?;
+    let result = decode();
+    LIMITS.with(|ctx| {
+        if let Some(ctx) = ctx.borrow_mut().as_mut() {
+            ctx.depth -= 1;
+        }
+    });
+    result
+}
+
 pub enum Source<'a> {
     Storage(&'a mut Storage),
     Buffer(&'a Vec<u8>),
@@ -17,17 +142,19 @@ where
     fn defaults() -> Self;
     fn extract_from_storage(&mut self, storage: Storage) -> Result<(), String>;
     fn extract(buf: Vec<u8>) -> Result<Self, String> {
-        let mut instance: Self = Self::defaults();
-        let storage = match Storage::new(buf) {
-            Ok(storage) => storage,
-            Err(e) => {
-                return Err(e);
+        limits_nested(|| {
+            let mut instance: Self = Self::defaults();
+            let storage = match Storage::new(buf) {
+                Ok(storage) => storage,
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+            match instance.extract_from_storage(storage) {
+                Ok(()) => Ok(instance),
+                Err(e) => Err(e),
             }
-        };
-        match instance.extract_from_storage(storage) {
-            Ok(()) => Ok(instance),
-            Err(e) => Err(e),
-        }
+        })
     }
 }
 
@@ -67,7 +194,7 @@ where
 {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<T, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
-            Self::extract(buf.clone())
+            limits_nested(|| Self::extract(buf.clone()))
         } else {
             Err("Fail get buffer".to_string())
         }
@@ -101,7 +228,8 @@ where
                 buffer = buffer
                     .drain((sizes::U64_LEN + item_len as usize)..)
                     .collect();
-                match T::extract(item_buf) {
+                limits_items(res.len() + 1)?;
+                match limits_nested(|| T::extract(item_buf)) {
                     Ok(i) => res.push(i),
                     Err(e) => {
                         return Err(e);
@@ -295,6 +423,7 @@ impl Decode<bool> for bool {
 impl Decode<String> for String {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<String, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_string(buf.len())?;
             Ok(String::from_utf8_lossy(buf).to_string())
         } else {
             Err("Fail get buffer".to_string())
@@ -308,17 +437,19 @@ where
 {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<T, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
-            let sctruct_storage = match Storage::new(buf.to_vec()) {
-                Ok(storage) => storage,
-                Err(e) => {
-                    return Err(e);
+            limits_nested(|| {
+                let sctruct_storage = match Storage::new(buf.to_vec()) {
+                    Ok(storage) => storage,
+                    Err(e) => {
+                        return Err(e);
+                    }
+                };
+                let mut strct: T = T::defaults();
+                match strct.extract_from_storage(sctruct_storage) {
+                    Ok(_) => Ok(strct),
+                    Err(e) => Err(e),
                 }
-            };
-            let mut strct: T = T::defaults();
-            match strct.extract_from_storage(sctruct_storage) {
-                Ok(_) => Ok(strct),
-                Err(e) => Err(e),
-            }
+            })
         } else {
             Err("Fail get buffer".to_string())
         }
@@ -328,6 +459,7 @@ where
 impl Decode<Vec<u8>> for Vec<u8> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u8>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U8_LEN)?;
             let mut res: Vec<u8> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -346,6 +478,7 @@ impl Decode<Vec<u8>> for Vec<u8> {
 impl Decode<Vec<u16>> for Vec<u16> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u16>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U16_LEN)?;
             let mut res: Vec<u16> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -368,6 +501,7 @@ impl Decode<Vec<u16>> for Vec<u16> {
 impl Decode<Vec<u32>> for Vec<u32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U32_LEN)?;
             let mut res: Vec<u32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -390,6 +524,7 @@ impl Decode<Vec<u32>> for Vec<u32> {
 impl Decode<Vec<u64>> for Vec<u64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U64_LEN)?;
             let mut res: Vec<u64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -412,6 +547,7 @@ impl Decode<Vec<u64>> for Vec<u64> {
 impl Decode<Vec<i8>> for Vec<i8> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i8>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I8_LEN)?;
             let mut res: Vec<i8> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -430,6 +566,7 @@ impl Decode<Vec<i8>> for Vec<i8> {
 impl Decode<Vec<i16>> for Vec<i16> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i16>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I16_LEN)?;
             let mut res: Vec<i16> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -452,6 +589,7 @@ impl Decode<Vec<i16>> for Vec<i16> {
 impl Decode<Vec<i32>> for Vec<i32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I32_LEN)?;
             let mut res: Vec<i32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -474,6 +612,7 @@ impl Decode<Vec<i32>> for Vec<i32> {
 impl Decode<Vec<i64>> for Vec<i64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I64_LEN)?;
             let mut res: Vec<i64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -496,6 +635,7 @@ impl Decode<Vec<i64>> for Vec<i64> {
 impl Decode<Vec<f32>> for Vec<f32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<f32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::F32_LEN)?;
             let mut res: Vec<f32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -518,6 +658,7 @@ impl Decode<Vec<f32>> for Vec<f32> {
 impl Decode<Vec<f64>> for Vec<f64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<f64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::F64_LEN)?;
             let mut res: Vec<f64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -540,6 +681,7 @@ impl Decode<Vec<f64>> for Vec<f64> {
 impl Decode<Vec<bool>> for Vec<bool> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<bool>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U8_LEN)?;
             let mut res: Vec<bool> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -570,6 +712,8 @@ impl Decode<Vec<String>> for Vec<String> {
                     return Err(format!("To extract length of string (u32) value from array buffer should have length at least {} bytes, but length is {}", sizes::U32_LEN, buf.len()));
                 }
                 let item_len: u32 = cursor.get_u32_le();
+                limits_string(item_len as usize)?;
+                limits_items(res.len() + 1)?;
                 if buffer.len() < sizes::U32_LEN + item_len as usize {
                     return Err(format!("Cannot extract string, because expecting {} bytes, but length of buffer is {}", item_len, (buffer.len() - sizes::U32_LEN)));
                 }
@@ -615,19 +759,13 @@ where
                 buffer = buffer
                     .drain((sizes::U64_LEN + item_len as usize)..)
                     .collect();
-                let sctruct_storage = match Storage::new(item_buf) {
-                    Ok(storage) => storage,
-                    Err(e) => {
-                        return Err(e);
-                    }
-                };
-                let mut strct: T = T::defaults();
-                match strct.extract_from_storage(sctruct_storage) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(e);
-                    }
-                }
+                limits_items(res.len() + 1)?;
+                let strct = limits_nested(|| {
+                    let sctruct_storage = Storage::new(item_buf)?;
+                    let mut strct: T = T::defaults();
+                    strct.extract_from_storage(sctruct_storage)?;
+                    Ok(strct)
+                })?;
                 res.push(strct);
             }
             Ok(res)
@@ -1,4 +1,5 @@
 use super::sizes;
+use bytes::BufMut;
 use sizes::ESize;
 
 // injectable
@@ -33,15 +34,95 @@ pub fn get_empty_buffer_val(id: Option<u16>) -> Result<Vec<u8>, String> {
     get_value_buffer(id, ESize::U8(0), vec![])
 }
 
+fn get_size_value(size: &ESize) -> usize {
+    match size {
+        ESize::U8(size) => *size as usize,
+        ESize::U16(size) => *size as usize,
+        ESize::U32(size) => *size as usize,
+        ESize::U64(size) => *size as usize,
+    }
+}
+
+// Same layout as get_value_buffer gives, but without intermediate buffers
+pub fn get_value_len(id: Option<u16>, size: &ESize) -> usize {
+    let header = match id {
+        Some(_) => {
+            sizes::U16_LEN
+                + sizes::U8_LEN
+                + match size {
+                    ESize::U8(_) => sizes::U8_LEN,
+                    ESize::U16(_) => sizes::U16_LEN,
+                    ESize::U32(_) => sizes::U32_LEN,
+                    ESize::U64(_) => sizes::U64_LEN,
+                }
+        }
+        None => 0,
+    };
+    header + get_size_value(size)
+}
+
+pub fn put_value_header(id: Option<u16>, size: &ESize, buf: &mut impl BufMut) {
+    if let Some(id) = id {
+        buf.put_u16_le(id);
+        match size {
+            ESize::U8(size) => {
+                buf.put_u8(8);
+                buf.put_u8(*size);
+            }
+            ESize::U16(size) => {
+                buf.put_u8(16);
+                buf.put_u16_le(*size);
+            }
+            ESize::U32(size) => {
+                buf.put_u8(32);
+                buf.put_u32_le(*size);
+            }
+            ESize::U64(size) => {
+                buf.put_u8(64);
+                buf.put_u64_le(*size);
+            }
+        };
+    }
+}
+
+pub fn get_empty_len_to_store(id: Option<u16>) -> usize {
+    get_value_len(id, &ESize::U8(0))
+}
+
+pub fn put_empty_to_store(id: Option<u16>, buf: &mut impl BufMut) {
+    put_value_header(id, &ESize::U8(0), buf);
+}
+
+// Generic encode_into/pack_into aren't available on trait objects, so generated
+// types implement abduct and pack with this helper
+pub fn encode_to_vec<F>(capacity: usize, encode: F) -> Result<Vec<u8>, String>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<(), String>,
+{
+    let mut buffer: Vec<u8> = Vec::with_capacity(capacity);
+    encode(&mut buffer)?;
+    Ok(buffer)
+}
+
 pub trait StructEncode {
     fn get_id(&self) -> u32;
     fn get_signature(&self) -> u16;
+    // Exact size of encoded struct; nothing is encoded to get it
+    fn encoded_len(&self) -> usize;
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String>
+    where
+        Self: Sized;
     fn abduct(&mut self) -> Result<Vec<u8>, String>;
 }
 
 pub trait EnumEncode {
     fn get_id(&self) -> u32;
     fn get_signature(&self) -> u16;
+    // Exact size of encoded enum; nothing is encoded to get it
+    fn encoded_len(&self) -> usize;
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String>
+    where
+        Self: Sized;
     fn abduct(&mut self) -> Result<Vec<u8>, String>;
 }
 
@@ -50,6 +131,16 @@ pub trait EncodeEnum {
     fn encode(&mut self) -> Result<Vec<u8>, String> {
         self.get_buf_to_store(None)
     }
+    // Size of value without header of property
+    fn value_size(&self) -> ESize;
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String>;
+    fn len_to_store(&self, id: Option<u16>) -> usize {
+        get_value_len(id, &self.value_size())
+    }
+    fn put_to_store(&self, id: Option<u16>, buf: &mut impl BufMut) -> Result<(), String> {
+        put_value_header(id, &self.value_size(), buf);
+        self.value_into(buf)
+    }
 }
 
 impl<T> EncodeEnum for T
@@ -62,6 +153,12 @@ where
             Err(e) => Err(e),
         }
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.encoded_len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.encode_into(buf)
+    }
 }
 
 impl<T> EncodeEnum for Vec<T>
@@ -82,6 +179,20 @@ where
         }
         get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U64_LEN + val.encoded_len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(val.encoded_len() as u64);
+            val.encode_into(buf)?;
+        }
+        Ok(())
+    }
 }
 
 pub trait Encode {
@@ -89,6 +200,16 @@ pub trait Encode {
     fn encode(&mut self) -> Result<Vec<u8>, String> {
         self.get_buf_to_store(None)
     }
+    // Size of value without header of property
+    fn value_size(&self) -> ESize;
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String>;
+    fn len_to_store(&self, id: Option<u16>) -> usize {
+        get_value_len(id, &self.value_size())
+    }
+    fn put_to_store(&self, id: Option<u16>, buf: &mut impl BufMut) -> Result<(), String> {
+        put_value_header(id, &self.value_size(), buf);
+        self.value_into(buf)
+    }
 }
 
 impl Encode for u8 {
@@ -99,6 +220,13 @@ impl Encode for u8 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U8_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u8(*self);
+        Ok(())
+    }
 }
 
 impl Encode for u16 {
@@ -109,6 +237,13 @@ impl Encode for u16 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U16_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u16_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for u32 {
@@ -119,6 +254,13 @@ impl Encode for u32 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u32_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for u64 {
@@ -129,6 +271,13 @@ impl Encode for u64 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u64_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for i8 {
@@ -139,6 +288,13 @@ impl Encode for i8 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I8_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i8(*self);
+        Ok(())
+    }
 }
 
 impl Encode for i16 {
@@ -149,6 +305,13 @@ impl Encode for i16 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I16_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i16_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for i32 {
@@ -159,6 +322,13 @@ impl Encode for i32 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i32_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for i64 {
@@ -169,6 +339,13 @@ impl Encode for i64 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i64_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for f32 {
@@ -179,6 +356,13 @@ impl Encode for f32 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::F32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_f32_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for f64 {
@@ -189,6 +373,13 @@ impl Encode for f64 {
             self.to_le_bytes().to_vec(),
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::F64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_f64_le(*self);
+        Ok(())
+    }
 }
 
 impl Encode for bool {
@@ -199,6 +390,13 @@ impl Encode for bool {
             if self == &true { vec![1] } else { vec![0] },
         )
     }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::BOOL_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u8(if *self { 1 } else { 0 });
+        Ok(())
+    }
 }
 
 impl Encode for String {
@@ -206,6 +404,13 @@ impl Encode for String {
         let buf = self.as_bytes();
         get_value_buffer(id, ESize::U64(buf.len() as u64), buf.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_slice(self.as_bytes());
+        Ok(())
+    }
 }
 
 impl<T> Encode for T
@@ -218,6 +423,12 @@ where
             Err(e) => Err(e),
         }
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.encoded_len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.encode_into(buf)
+    }
 }
 
 impl Encode for Vec<u8> {
@@ -229,6 +440,13 @@ impl Encode for Vec<u8> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_slice(self);
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u16> {
@@ -240,6 +458,15 @@ impl Encode for Vec<u16> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U16_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u16_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u32> {
@@ -251,6 +478,15 @@ impl Encode for Vec<u32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u64> {
@@ -262,6 +498,15 @@ impl Encode for Vec<u64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i8> {
@@ -273,6 +518,15 @@ impl Encode for Vec<i8> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i8(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i16> {
@@ -284,6 +538,15 @@ impl Encode for Vec<i16> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I16_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i16_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i32> {
@@ -295,6 +558,15 @@ impl Encode for Vec<i32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i64> {
@@ -306,6 +578,15 @@ impl Encode for Vec<i64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<f32> {
@@ -317,6 +598,15 @@ impl Encode for Vec<f32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::F32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_f32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<f64> {
@@ -328,6 +618,15 @@ impl Encode for Vec<f64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::F64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_f64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<String> {
@@ -340,6 +639,20 @@ impl Encode for Vec<String> {
         }
         get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U32_LEN + val.len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u32_le(val.len() as u32);
+            buf.put_slice(val.as_bytes());
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<bool> {
@@ -352,6 +665,15 @@ impl Encode for Vec<bool> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u8(if *val { 1 } else { 0 });
+        }
+        Ok(())
+    }
 }
 
 impl<T> Encode for Vec<T>
@@ -372,6 +694,20 @@ where
         }
         get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U64_LEN + val.encoded_len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(val.encoded_len() as u64);
+            val.encode_into(buf)?;
+        }
+        Ok(())
+    }
 }
 
 impl<T> Encode for Option<T>
@@ -384,4 +720,16 @@ where
             None => get_empty_buffer_val(id),
         }
     }
+    fn value_size(&self) -> ESize {
+        match self {
+            Some(v) => v.value_size(),
+            None => ESize::U8(0),
+        }
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match self {
+            Some(v) => v.value_into(buf),
+            None => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,378 @@
+use super::capabilities::get_agreement;
+use super::decode::StructDecode;
+use super::packing::PackingMiddlewareInterface;
+use super::sizes;
+use super::InternalServiceGroup;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// injectable
+use ring::{aead, agreement, hkdf, rand};
+
+// Body of package is encrypted with session keys of consumer. Session is established while hash
+// is checked: consumer sends own X25519 public key with HashRequest, producer responds with
+// own one in HashResponse. Both sides derive keys (one per direction) with HKDF-SHA256.
+// Encrypted body: | u64 counter | ciphertext | tag |. Nonce is built from sequence and counter
+// of package; id and sequence are authenticated as well. Packages, which were already accepted,
+// are rejected as replays. Messages of handshake (self-key and hash check) aren't encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionAlgorithm {
+    AesGcm,
+    ChaCha20Poly1305,
+}
+
+impl EncryptionAlgorithm {
+    fn aead(&self) -> &'static aead::Algorithm {
+        match self {
+            Self::AesGcm => &aead::AES_256_GCM,
+            Self::ChaCha20Poly1305 => &aead::CHACHA20_POLY1305,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AesGcm => "aes_gcm",
+            Self::ChaCha20Poly1305 => "chacha20_poly1305",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ENCRYPTION_ALGORITHMS
+            .iter()
+            .find(|algorithm| algorithm.as_str() == name)
+            .copied()
+    }
+}
+
+// Both algorithms are supported; configured one is preferred
+pub fn encryption_algorithms() -> Vec<String> {
+    let mut algorithms = vec![ENCRYPTION_ALGORITHM.as_str().to_owned()];
+    for algorithm in ENCRYPTION_ALGORITHMS.iter() {
+        if *algorithm != ENCRYPTION_ALGORITHM {
+            algorithms.push(algorithm.as_str().to_owned());
+        }
+    }
+    algorithms
+}
+
+const ENCRYPTION_ALGORITHMS: [EncryptionAlgorithm; 2] = [
+    EncryptionAlgorithm::AesGcm,
+    EncryptionAlgorithm::ChaCha20Poly1305,
+];
+const ENCRYPTION_COUNTER_LEN: usize = sizes::U64_LEN;
+// Count of last packages, which are tracked to accept packages out of order
+const ENCRYPTION_WINDOW: u64 = 64;
+const ENCRYPTION_CONSUMER_INFO: &[u8] = b"clibri consumer to producer";
+const ENCRYPTION_PRODUCER_INFO: &[u8] = b"clibri producer to consumer";
+
+struct EncryptionSession {
+    tx: aead::LessSafeKey,
+    rx: aead::LessSafeKey,
+    sent: u64,
+    // Highest accepted counter and bitmap of accepted counters before it
+    received: u64,
+    window: u64,
+}
+
+impl EncryptionSession {
+    fn accept(&mut self, counter: u64) -> bool {
+        if counter > self.received {
+            let shift = counter - self.received;
+            self.window = if shift > ENCRYPTION_WINDOW {
+                0
+            } else {
+                self.window.checked_shl(shift as u32).unwrap_or(0) | (1 << (shift - 1))
+            };
+            self.received = counter;
+            return true;
+        }
+        let offset = self.received - counter;
+        if counter == 0
+            || offset == 0
+            || offset > ENCRYPTION_WINDOW
+            || self.window & (1 << (offset - 1)) != 0
+        {
+            return false;
+        }
+        self.window |= 1 << (offset - 1);
+        true
+    }
+}
+
+enum EncryptionState {
+    // Consumer has sent own public key and waits for key of producer
+    Offered(agreement::EphemeralPrivateKey, Vec<u8>),
+    Established(EncryptionSession),
+}
+
+static ENCRYPTION_SESSIONS: Mutex<Option<HashMap<String, EncryptionState>>> = Mutex::new(None);
+
+fn encryption_sessions<T>(
+    cb: impl FnOnce(&mut HashMap<String, EncryptionState>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut sessions = ENCRYPTION_SESSIONS
+        .lock()
+        .map_err(|e| format!("Fail to access encryption sessions: {}", e))?;
+    cb(sessions.get_or_insert_with(HashMap::new))
+}
+
+fn encryption_key_pair() -> Result<(agreement::EphemeralPrivateKey, Vec<u8>), String> {
+    let rng = rand::SystemRandom::new();
+    let private = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &rng)
+        .map_err(|_| String::from("Fail to generate private key"))?;
+    let public = private
+        .compute_public_key()
+        .map_err(|_| String::from("Fail to compute public key"))?;
+    Ok((private, public.as_ref().to_vec()))
+}
+
+// Salt is bound to both public keys, so keys of session depend on whole exchange
+fn encryption_session(
+    algorithm: EncryptionAlgorithm,
+    private: agreement::EphemeralPrivateKey,
+    peer: &[u8],
+    consumer: &[u8],
+    producer: &[u8],
+    tx: &[u8],
+    rx: &[u8],
+) -> Result<EncryptionSession, String> {
+    let salt = [consumer, producer].concat();
+    agreement::agree_ephemeral(
+        private,
+        &agreement::UnparsedPublicKey::new(&agreement::X25519, peer),
+        String::from("Fail to agree keys of session; public key is invalid"),
+        |secret| {
+            let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, &salt).extract(secret);
+            let key = |info: &[u8]| -> Result<aead::LessSafeKey, String> {
+                let info = [info];
+                let okm = prk
+                    .expand(&info, algorithm.aead())
+                    .map_err(|_| String::from("Fail to derive key of session"))?;
+                Ok(aead::LessSafeKey::new(aead::UnboundKey::from(okm)))
+            };
+            Ok(EncryptionSession {
+                tx: key(tx)?,
+                rx: key(rx)?,
+                sent: 0,
+                received: 0,
+                window: 0,
+            })
+        },
+    )
+}
+
+/// Starts handshake on consumer side. Returns public key, which should be sent with HashRequest
+pub fn encryption_offer(uuid: &str) -> Result<Vec<u8>, String> {
+    let (private, public) = encryption_key_pair()?;
+    encryption_sessions(|sessions| {
+        sessions.insert(
+            uuid.to_owned(),
+            EncryptionState::Offered(private, public.clone()),
+        );
+        Ok(public)
+    })
+}
+
+/// Establishes session on producer side with algorithm, which is agreed with consumer. Returns
+/// public key, which should be sent with HashResponse
+pub fn encryption_accept(uuid: &str, key: &[u8]) -> Result<Vec<u8>, String> {
+    let algorithm = match get_agreement(uuid).and_then(|agreement| agreement.encryption) {
+        Some(name) => EncryptionAlgorithm::from_name(&name)
+            .ok_or_else(|| format!("Unknown encryption algorithm: {}", name))?,
+        None => ENCRYPTION_ALGORITHM,
+    };
+    let (private, public) = encryption_key_pair()?;
+    let session = encryption_session(
+        algorithm,
+        private,
+        key,
+        key,
+        &public,
+        ENCRYPTION_PRODUCER_INFO,
+        ENCRYPTION_CONSUMER_INFO,
+    )?;
+    encryption_sessions(|sessions| {
+        sessions.insert(uuid.to_owned(), EncryptionState::Established(session));
+        Ok(public)
+    })
+}
+
+/// Establishes session on consumer side with public key of producer. It's called by middleware,
+/// when HashResponse is read, because next packages are encrypted already
+pub fn encryption_complete(
+    uuid: &str,
+    key: &[u8],
+    algorithm: EncryptionAlgorithm,
+) -> Result<(), String> {
+    encryption_sessions(|sessions| match sessions.remove(uuid) {
+        Some(EncryptionState::Offered(private, public)) => {
+            let session = encryption_session(
+                algorithm,
+                private,
+                key,
+                &public,
+                key,
+                ENCRYPTION_CONSUMER_INFO,
+                ENCRYPTION_PRODUCER_INFO,
+            )?;
+            sessions.insert(uuid.to_owned(), EncryptionState::Established(session));
+            Ok(())
+        }
+        Some(state) => {
+            sessions.insert(uuid.to_owned(), state);
+            Err(format!("Session of {} is already established", uuid))
+        }
+        None => Err(format!("Handshake for {} wasn't started", uuid)),
+    })
+}
+
+pub fn encryption_ready(uuid: &str) -> bool {
+    encryption_sessions(|sessions| {
+        Ok(matches!(
+            sessions.get(uuid),
+            Some(EncryptionState::Established(_))
+        ))
+    })
+    .unwrap_or(false)
+}
+
+/// Removes session (on disconnect)
+pub fn encryption_close(uuid: &str) {
+    let _ = encryption_sessions(|sessions| {
+        sessions.remove(uuid);
+        Ok(())
+    });
+}
+
+fn encryption_nonce(sequence: u32, counter: u64) -> aead::Nonce {
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    nonce[..sizes::U32_LEN].copy_from_slice(&sequence.to_le_bytes());
+    nonce[sizes::U32_LEN..].copy_from_slice(&counter.to_le_bytes());
+    aead::Nonce::assume_unique_for_key(nonce)
+}
+
+fn encryption_aad(id: u32, sequence: u32) -> aead::Aad<[u8; 8]> {
+    let mut aad = [0u8; 8];
+    aad[..sizes::U32_LEN].copy_from_slice(&id.to_le_bytes());
+    aad[sizes::U32_LEN..].copy_from_slice(&sequence.to_le_bytes());
+    aead::Aad::from(aad)
+}
+
+pub struct PackingMiddleware {}
+
+impl PackingMiddlewareInterface for PackingMiddleware {
+    fn bound() -> bool {
+        true
+    }
+    fn decode(
+        buffer: Vec<u8>,
+        id: u32,
+        sequence: u32,
+        uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        if ENCRYPTION_UNENCRYPTED.contains(&id) {
+            if id == <InternalServiceGroup::HashResponse as StructDecode>::get_id() {
+                if let Some(uuid) = uuid.as_ref() {
+                    if let Ok(response) =
+                        InternalServiceGroup::HashResponse::extract(buffer.clone())
+                    {
+                        // Producer responds with agreed algorithm
+                        let algorithm =
+                            match response.encryption.as_ref().and_then(|names| names.first()) {
+                                Some(name) => EncryptionAlgorithm::from_name(name),
+                                None => Some(ENCRYPTION_ALGORITHM),
+                            };
+                        if let (None, Some(algorithm)) = (response.error.as_ref(), algorithm) {
+                            // Consumer checks session with encryption_ready after response
+                            let _ = encryption_complete(uuid, &response.key, algorithm);
+                        }
+                    }
+                }
+            }
+            return Ok(buffer);
+        }
+        let uuid = uuid.ok_or_else(|| {
+            format!(
+                "Package id={} is encrypted, but session isn't established",
+                id
+            )
+        })?;
+        encryption_sessions(|sessions| {
+            let session = match sessions.get_mut(&uuid) {
+                Some(EncryptionState::Established(session)) => session,
+                _ => {
+                    return Err(format!("No encrypted session for {}", uuid));
+                }
+            };
+            if buffer.len() < ENCRYPTION_COUNTER_LEN + session.rx.algorithm().tag_len() {
+                return Err(format!("Encrypted body of package id={} is too small", id));
+            }
+            let mut counter = [0u8; ENCRYPTION_COUNTER_LEN];
+            counter.copy_from_slice(&buffer[..ENCRYPTION_COUNTER_LEN]);
+            let counter = u64::from_le_bytes(counter);
+            let mut body = buffer[ENCRYPTION_COUNTER_LEN..].to_vec();
+            let len = session
+                .rx
+                .open_in_place(
+                    encryption_nonce(sequence, counter),
+                    encryption_aad(id, sequence),
+                    &mut body,
+                )
+                .map_err(|_| format!("Fail to decrypt package id={}", id))?
+                .len();
+            // Counter is checked after authentication, so forged packages don't move window
+            if !session.accept(counter) {
+                return Err(format!(
+                    "Package id={} (sequence {}) has been rejected as replay",
+                    id, sequence
+                ));
+            }
+            body.truncate(len);
+            Ok(body)
+        })
+    }
+    fn encode(
+        buffer: Vec<u8>,
+        id: u32,
+        sequence: u32,
+        uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        if ENCRYPTION_UNENCRYPTED.contains(&id) {
+            return Ok(buffer);
+        }
+        // Package isn't bound to consumer; it will be encrypted with rebind
+        let uuid = if let Some(uuid) = uuid {
+            uuid
+        } else {
+            return Ok(buffer);
+        };
+        encryption_sessions(|sessions| {
+            let session = match sessions.get_mut(&uuid) {
+                Some(EncryptionState::Established(session)) => session,
+                _ => {
+                    return Err(format!("No encrypted session for {}", uuid));
+                }
+            };
+            let counter = session
+                .sent
+                .checked_add(1)
+                .ok_or_else(|| format!("Counter of packages for {} is exhausted", uuid))?;
+            session.sent = counter;
+            let mut body: Vec<u8> = Vec::with_capacity(
+                ENCRYPTION_COUNTER_LEN + buffer.len() + session.tx.algorithm().tag_len(),
+            );
+            body.extend_from_slice(&counter.to_le_bytes());
+            let mut encrypted = buffer;
+            session
+                .tx
+                .seal_in_place_append_tag(
+                    encryption_nonce(sequence, counter),
+                    encryption_aad(id, sequence),
+                    &mut encrypted,
+                )
+                .map_err(|_| format!("Fail to encrypt package id={}", id))?;
+            body.extend_from_slice(&encrypted);
+            Ok(body)
+        })
+    }
+}
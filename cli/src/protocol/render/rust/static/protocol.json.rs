@@ -0,0 +1,370 @@
+// injectable
+// Canonical JSON form of messages: names of fields are taken from protocol, enums are
+// { "Variant": value }, i64/u64 are strings (JS cannot keep such numbers safely)
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    // Number is kept as text to avoid lost of precision
+    Number(String),
+    Str(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+static JSON_NULL: JsonValue = JsonValue::Null;
+
+impl JsonValue {
+    pub fn parse(src: &str) -> Result<JsonValue, String> {
+        let mut reader = JsonReader {
+            chars: src.chars().collect(),
+            pos: 0,
+        };
+        let value = reader.value()?;
+        reader.spaces();
+        if reader.pos < reader.chars.len() {
+            Err(format!("Unexpected symbol at position {}", reader.pos))
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn stringify(&self) -> String {
+        match self {
+            JsonValue::Null => String::from("null"),
+            JsonValue::Bool(value) => value.to_string(),
+            JsonValue::Number(value) => value.clone(),
+            JsonValue::Str(value) => json_escape(value),
+            JsonValue::Array(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| item.stringify())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            JsonValue::Object(fields) => format!(
+                "{{{}}}",
+                fields
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", json_escape(key), value.stringify()))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    /// Returns value of object's field or null if field doesn't exist
+    pub fn get(&self, key: &str) -> &JsonValue {
+        if let JsonValue::Object(fields) = self {
+            if let Some((_, value)) = fields.iter().find(|(name, _)| name == key) {
+                return value;
+            }
+        }
+        &JSON_NULL
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+struct JsonReader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl JsonReader {
+    fn spaces(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        if let Some(c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            Ok(*c)
+        } else {
+            Err(String::from("Unexpected end of JSON"))
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        for c in word.chars() {
+            if self.next()? != c {
+                return Err(format!("Expecting \"{}\" at position {}", word, self.pos));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<JsonValue, String> {
+        self.spaces();
+        match self.chars.get(self.pos) {
+            Some('n') => self.expect("null").map(|_| JsonValue::Null),
+            Some('t') => self.expect("true").map(|_| JsonValue::Bool(true)),
+            Some('f') => self.expect("false").map(|_| JsonValue::Bool(false)),
+            Some('"') => self.string().map(JsonValue::Str),
+            Some('[') => {
+                self.pos += 1;
+                let mut items: Vec<JsonValue> = vec![];
+                self.spaces();
+                if self.chars.get(self.pos) == Some(&']') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.spaces();
+                    match self.next()? {
+                        ',' => continue,
+                        ']' => return Ok(JsonValue::Array(items)),
+                        c => return Err(format!("Unexpected \"{}\" in array", c)),
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut fields: Vec<(String, JsonValue)> = vec![];
+                self.spaces();
+                if self.chars.get(self.pos) == Some(&'}') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    self.spaces();
+                    let key = self.string()?;
+                    self.spaces();
+                    if self.next()? != ':' {
+                        return Err(format!("Expecting \":\" after key \"{}\"", key));
+                    }
+                    fields.push((key, self.value()?));
+                    self.spaces();
+                    match self.next()? {
+                        ',' => continue,
+                        '}' => return Ok(JsonValue::Object(fields)),
+                        c => return Err(format!("Unexpected \"{}\" in object", c)),
+                    }
+                }
+            }
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && (self.chars[self.pos].is_ascii_digit()
+                        || ['-', '+', '.', 'e', 'E'].contains(&self.chars[self.pos]))
+                {
+                    self.pos += 1;
+                }
+                Ok(JsonValue::Number(self.chars[start..self.pos].iter().collect()))
+            }
+            Some(c) => Err(format!("Unexpected \"{}\" at position {}", c, self.pos)),
+            None => Err(String::from("Unexpected end of JSON")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.next()? != '"' {
+            return Err(format!("Expecting string at position {}", self.pos));
+        }
+        let mut output = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Ok(output),
+                '\\' => match self.next()? {
+                    'n' => output.push('\n'),
+                    'r' => output.push('\r'),
+                    't' => output.push('\t'),
+                    'b' => output.push('\u{8}'),
+                    'f' => output.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex()?;
+                        if (0xD800..0xDC00).contains(&code) {
+                            // Surrogate pair
+                            self.expect("\\u")?;
+                            let low = self.hex()?;
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                        }
+                        output.push(
+                            std::char::from_u32(code)
+                                .ok_or_else(|| format!("Invalid unicode code {}", code))?,
+                        );
+                    }
+                    c => output.push(c),
+                },
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let mut code: u32 = 0;
+        for _ in 0..4 {
+            let c = self.next()?;
+            code = code * 16
+                + c.to_digit(16)
+                    .ok_or_else(|| format!("Invalid hex symbol \"{}\"", c))?;
+        }
+        Ok(code)
+    }
+}
+
+pub trait ToJson {
+    fn to_json_value(&self) -> JsonValue;
+    fn to_json(&self) -> String {
+        self.to_json_value().stringify()
+    }
+}
+
+pub trait FromJson: Sized {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String>;
+    fn from_json(src: &str) -> Result<Self, String> {
+        Self::from_json_value(&JsonValue::parse(src)?)
+    }
+}
+
+macro_rules! json_number {
+    ($type:ty) => {
+        impl ToJson for $type {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Number(self.to_string())
+            }
+        }
+        impl FromJson for $type {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                match value {
+                    JsonValue::Number(n) => n.parse::<$type>().map_err(|e| e.to_string()),
+                    _ => Err(format!("Expecting number for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! json_number_as_string {
+    ($type:ty) => {
+        impl ToJson for $type {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Str(self.to_string())
+            }
+        }
+        impl FromJson for $type {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                match value {
+                    JsonValue::Str(n) | JsonValue::Number(n) => {
+                        n.parse::<$type>().map_err(|e| e.to_string())
+                    }
+                    _ => Err(format!("Expecting string for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+json_number!(u8);
+json_number!(u16);
+json_number!(u32);
+json_number!(i8);
+json_number!(i16);
+json_number!(i32);
+json_number!(f32);
+json_number!(f64);
+json_number_as_string!(u64);
+json_number_as_string!(i64);
+
+impl ToJson for bool {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err(String::from("Expecting bool")),
+        }
+    }
+}
+
+impl ToJson for String {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Str(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Str(value) => Ok(value.clone()),
+            _ => Err(String::from("Expecting string")),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(|item| item.to_json_value()).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| T::from_json_value(item).map_err(|e| format!("[{}]: {}", i, e)))
+                .collect(),
+            _ => Err(String::from("Expecting array")),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json_value(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json_value(),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json_value(value).map(Some),
+        }
+    }
+}
+
+/// Message with type tag: { "name": "Group.Message", "message": {...} }
+pub fn json_tagged(name: &str, message: JsonValue) -> JsonValue {
+    JsonValue::Object(vec![
+        (String::from("name"), JsonValue::Str(name.to_owned())),
+        (String::from("message"), message),
+    ])
+}
+
+/// Returns type tag of message defined with json_tagged
+pub fn json_tag(value: &JsonValue) -> Result<&str, String> {
+    match value.get("name") {
+        JsonValue::Str(name) => Ok(name),
+        _ => Err(String::from("Expecting message with field \"name\"")),
+    }
+}
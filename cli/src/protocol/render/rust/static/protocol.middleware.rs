@@ -0,0 +1,32 @@
+use super::packing::PackingMiddlewareInterface;
+
+// injectable
+// Names of encryption algorithms, which are offered to peer
+pub fn encryption_algorithms() -> Vec<String> {
+    vec![]
+}
+
+// Methods can be overridden with inherent impl of PackingMiddleware (impl PackingMiddleware
+// { fn encode(...) }). Packages are passed through encode() unless inherent transparent()
+// returns true: it declares, that encode() doesn't change body, so package is written in
+// one pass
+pub struct PackingMiddleware {}
+
+impl PackingMiddlewareInterface for PackingMiddleware {
+    fn decode(
+        buffer: Vec<u8>,
+        _id: u32,
+        _sequence: u32,
+        _uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(buffer)
+    }
+    fn encode(
+        buffer: Vec<u8>,
+        _id: u32,
+        _sequence: u32,
+        _uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(buffer)
+    }
+}
@@ -1,8 +1,15 @@
+use super::buffer::ReadError;
+use super::capabilities::get_agreement;
+use super::checksum::{crc32c, get_checksum};
+use super::compression::{compress, compression_flag, decompress};
+use super::compression_algorithms::COMPRESSION_FLAGS;
 use super::encode::{EnumEncode, StructEncode};
+use super::middleware::PackingMiddleware;
 use super::sizes;
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 use std::convert::TryFrom;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // injectable
@@ -11,29 +18,54 @@ const MSG_HEADER_LEN: usize = sizes::U32_LEN + // {u32} message ID
                                 sizes::U32_LEN + // {u32} sequence
                                 sizes::U64_LEN + // {u64} body size
                                 sizes::U64_LEN; // {u64} timestamp
+const MSG_CHECKSUM_LEN: usize = sizes::U32_LEN; // {u32} CRC32C of body (if flag is set)
 
-pub trait PackingMiddlewareInterface {
-    fn decode(
-        buffer: Vec<u8>,
-        _id: u32,
-        _sequence: u32,
-        _uuid: Option<String>,
-    ) -> Result<Vec<u8>, String> {
-        Ok(buffer)
-    }
-    fn encode(
-        buffer: Vec<u8>,
-        _id: u32,
-        _sequence: u32,
-        _uuid: Option<String>,
-    ) -> Result<Vec<u8>, String> {
-        Ok(buffer)
-    }
+// Highest byte of body size is used for flags of package (compression, version, checksum)
+const MSG_LEN_MASK: u64 = 0x00FF_FFFF_FFFF_FFFF;
+const MSG_FLAGS_SHIFT: u64 = 56;
+// Header with this flag has checksum of body after body size
+pub const MSG_FLAG_CHECKSUM: u8 = 0x80;
+// Bits 4..6 of flags keep version of header; packages of older runtimes have version 0.
+// Headers of all versions have same layout; since version 2 timestamp is in milliseconds
+// (before in seconds)
+pub const MSG_VERSION: u8 = 2;
+pub const MSG_VERSION_TS_MS: u8 = 2;
+const MSG_VERSION_MASK: u8 = 0x70;
+const MSG_VERSION_SHIFT: u8 = 4;
+
+static LAST_TS: AtomicU64 = AtomicU64::new(0);
+
+// Unix time in milliseconds. It doesn't go back within process even if system clock does, so
+// packages of one producer (consumer) are ordered by timestamp
+pub fn now_ms() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    LAST_TS.fetch_max(now, Ordering::Relaxed).max(now)
 }
 
-pub struct PackingMiddleware {}
+// Converts timestamp between versions of header
+fn convert_ts(ts: u64, from: u8, to: u8) -> u64 {
+    match (from >= MSG_VERSION_TS_MS, to >= MSG_VERSION_TS_MS) {
+        (true, false) => ts / 1000,
+        (false, true) => ts.saturating_mul(1000),
+        _ => ts,
+    }
+}
 
-impl PackingMiddlewareInterface for PackingMiddleware {
+pub trait PackingMiddlewareInterface {
+    // Transparent middleware doesn't change body; package is written in one pass then.
+    // It's opt-in, because inherent encode() of PackingMiddleware cannot change trait's
+    // default
+    fn transparent() -> bool {
+        false
+    }
+    // Bound middleware encodes body for certain consumer. Package, which is packed without
+    // uuid (broadcast), isn't encoded and should be rebound for each consumer
+    fn bound() -> bool {
+        false
+    }
     fn decode(
         buffer: Vec<u8>,
         _id: u32,
@@ -58,12 +90,32 @@ pub struct PackageHeader {
     pub signature: u16,
     pub sequence: u32,
     pub len: u64,
+    // Unix time of sending in units of version of header; use ts_ms() to get milliseconds
     pub ts: u64,
     pub len_usize: usize,
+    pub flags: u8,
+    pub version: u8,
+    pub checksum: Option<u32>,
 }
 
+impl PackageHeader {
+    // Unix time of sending in milliseconds (headers before version 2 have precision in seconds)
+    pub fn ts_ms(&self) -> u64 {
+        convert_ts(self.ts, self.version, MSG_VERSION_TS_MS)
+    }
+}
+
+fn get_header_len(flags: u8) -> usize {
+    if flags & MSG_FLAG_CHECKSUM != 0 {
+        MSG_HEADER_LEN + MSG_CHECKSUM_LEN
+    } else {
+        MSG_HEADER_LEN
+    }
+}
+
+// Flags are in last byte of basic header; they define whether header has checksum
 pub fn has_buffer_header(buf: &[u8]) -> bool {
-    buf.len() >= MSG_HEADER_LEN
+    buf.len() >= MSG_HEADER_LEN && buf.len() >= get_header_len(buf[MSG_HEADER_LEN - 1])
 }
 
 pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
@@ -81,6 +133,24 @@ pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
     let ts: u64 = header.get_u64_le();
     // Get length of payload and payload
     let len: u64 = header.get_u64_le();
+    let flags: u8 = (len >> MSG_FLAGS_SHIFT) as u8;
+    let len: u64 = len & MSG_LEN_MASK;
+    let version: u8 = (flags & MSG_VERSION_MASK) >> MSG_VERSION_SHIFT;
+    if version > MSG_VERSION {
+        return Err(format!(
+            "Version of header {} isn't supported; supported versions: 0..{}",
+            version, MSG_VERSION
+        ));
+    }
+    // Get checksum of body
+    let checksum: Option<u32> = if flags & MSG_FLAG_CHECKSUM != 0 {
+        if buf.len() < get_header_len(flags) {
+            return Err(format!("Cannot extract checksum of package because size of header {} bytes, but size of buffer {} bytes.", get_header_len(flags), buf.len()));
+        }
+        Some(header.get_u32_le())
+    } else {
+        None
+    };
     let len_usize = match usize::try_from(len) {
         Ok(v) => v,
         Err(e) => {
@@ -94,40 +164,214 @@ pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
         len,
         ts,
         len_usize,
+        flags,
+        version,
+        checksum,
     })
 }
 
 pub fn has_buffer_body(buf: &[u8], header: &PackageHeader) -> bool {
-    buf.len() >= header.len_usize + MSG_HEADER_LEN
+    buf.len() >= get_package_len(header)
+}
+
+pub fn get_package_len(header: &PackageHeader) -> usize {
+    header.len_usize + get_header_len(header.flags)
+}
+
+// Body is checked before middleware, so corrupted package doesn't get into decryption
+// or decompression
+pub fn check_body(header: &PackageHeader, body: &[u8]) -> Result<(), String> {
+    match header.checksum {
+        Some(checksum) if crc32c(body) != checksum => Err(format!(
+            "Checksum of package id={} (sequence {}) dismatch; expectation: {}; body: {}",
+            header.id,
+            header.sequence,
+            checksum,
+            crc32c(body)
+        )),
+        _ => Ok(()),
+    }
 }
 
 pub fn get_body_from_buffer(
     buf: &[u8],
     header: &PackageHeader,
     uuid: Option<String>,
-) -> Result<(Vec<u8>, Vec<u8>), String> {
-    if buf.len() < header.len_usize + MSG_HEADER_LEN {
-        return Err(format!("Cannot extract body of package because size in header {} bytes, but size of buffer {} bytes.", header.len, buf.len() - MSG_HEADER_LEN));
+) -> Result<(Vec<u8>, Vec<u8>), ReadError> {
+    let header_len = get_header_len(header.flags);
+    if buf.len() < header.len_usize + header_len {
+        return Err(ReadError::Parsing(format!("Cannot extract body of package because size in header {} bytes, but size of buffer {} bytes.", header.len, buf.len() - header_len)));
     }
     // Get body
     let mut body = vec![0; header.len_usize];
-    body.copy_from_slice(&buf[MSG_HEADER_LEN..(MSG_HEADER_LEN + header.len_usize)]);
-    let mut rest = vec![0; buf.len() - MSG_HEADER_LEN - header.len_usize];
-    rest.copy_from_slice(&buf[(MSG_HEADER_LEN + header.len_usize)..]);
+    body.copy_from_slice(&buf[header_len..(header_len + header.len_usize)]);
+    let mut rest = vec![0; buf.len() - header_len - header.len_usize];
+    rest.copy_from_slice(&buf[(header_len + header.len_usize)..]);
+    check_body(header, &body).map_err(ReadError::Checksum)?;
     match PackingMiddleware::decode(body, header.id, header.sequence, uuid) {
-        Ok(buffer) => Ok((buffer, rest)),
-        Err(e) => Err(e),
+        Ok(buffer) => Ok((
+            decompress(header.flags & COMPRESSION_FLAGS, buffer).map_err(ReadError::Parsing)?,
+            rest,
+        )),
+        Err(e) => Err(ReadError::Parsing(e)),
     }
 }
 
-pub fn pack<T>(mut msg: T, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>
+// Adapts package, which was packed without uuid (broadcast), for consumer: body is encoded by
+// bound middleware; compression and version of header follow agreement with consumer
+pub fn rebind(buf: &[u8], uuid: Option<String>) -> Result<Vec<u8>, String> {
+    let header = get_header_from_buffer(buf)?;
+    if !has_buffer_body(buf, &header) {
+        return Err(format!(
+            "Cannot rebind package because size in header {} bytes, but size of buffer {} bytes.",
+            header.len,
+            buf.len() - get_header_len(header.flags)
+        ));
+    }
+    let peer = get_peer(&uuid);
+    let compression = header.flags & COMPRESSION_FLAGS;
+    let readable = compression & !peer.compression == 0;
+    if !PackingMiddleware::bound() && readable && header.version == peer.version {
+        peer.check(header.len_usize)?;
+        return Ok(buf.to_vec());
+    }
+    let body = buf[get_header_len(header.flags)..get_package_len(&header)].to_vec();
+    let (compression, body) = if readable {
+        (compression, body)
+    } else if PackingMiddleware::bound() || PackingMiddleware::transparent() {
+        compress(decompress(compression, body)?, peer.compression)
+    } else {
+        return Err(format!(
+            "Package id={} is compressed with algorithm, which consumer cannot read",
+            header.id
+        ));
+    };
+    let body = if PackingMiddleware::bound() {
+        PackingMiddleware::encode(body, header.id, header.sequence, uuid)?
+    } else {
+        body
+    };
+    peer.check(body.len())?;
+    let checksum = header.checksum.map(|_| crc32c(&body));
+    let mut flags = compression | peer.version << MSG_VERSION_SHIFT;
+    if checksum.is_some() {
+        flags |= MSG_FLAG_CHECKSUM;
+    }
+    let mut rebound: Vec<u8> = Vec::with_capacity(get_header_len(flags) + body.len());
+    // Timestamp, body size (and checksum) are last fields of header
+    rebound.put_slice(&buf[..(MSG_HEADER_LEN - sizes::U64_LEN * 2)]);
+    rebound.put_u64_le(convert_ts(header.ts, header.version, peer.version));
+    rebound.put_u64_le(body.len() as u64 | (flags as u64) << MSG_FLAGS_SHIFT);
+    if let Some(checksum) = checksum {
+        rebound.put_u32_le(checksum);
+    }
+    rebound.put_slice(&body);
+    Ok(rebound)
+}
+
+// Settings of packages for peer: agreed ones or own ones, if there is no agreement. Until
+// agreement headers have version 0, because peer might be older runtime
+struct Peer {
+    version: u8,
+    // Flags of compression algorithms, which peer can read
+    compression: u8,
+    // Max size of body; 0 - isn't limited
+    frame: u64,
+}
+
+impl Peer {
+    fn check(&self, len: usize) -> Result<(), String> {
+        if self.frame != 0 && len as u64 > self.frame {
+            Err(format!(
+                "Size of package body ({} bytes) is bigger than limit of peer ({} bytes)",
+                len, self.frame
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn get_peer(uuid: &Option<String>) -> Peer {
+    match uuid.as_ref().and_then(|uuid| get_agreement(uuid)) {
+        Some(agreement) => Peer {
+            version: agreement.version,
+            compression: agreement.compression,
+            frame: agreement.frame,
+        },
+        None => Peer {
+            version: 0,
+            compression: COMPRESSION_FLAGS,
+            frame: 0,
+        },
+    }
+}
+
+pub fn pack<T>(msg: T, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>
 where
-    T: StructEncode,
+    T: PackingStruct,
 {
-    match msg.abduct() {
-        Ok(buffer) => pack_buffer(msg.get_id(), msg.get_signature(), sequence, buffer, uuid),
-        Err(e) => Err(e),
+    let mut buf: Vec<u8> = vec![];
+    msg.pack_into(sequence, uuid, &mut buf)?;
+    Ok(buf)
+}
+
+fn put_header(
+    msg_id: u32,
+    signature: u16,
+    sequence: u32,
+    len: usize,
+    flags: u8,
+    checksum: Option<u32>,
+    buf: &mut impl BufMut,
+) -> Result<(), String> {
+    let flags = if checksum.is_some() {
+        flags | MSG_FLAG_CHECKSUM
+    } else {
+        flags
+    };
+    let version = (flags & MSG_VERSION_MASK) >> MSG_VERSION_SHIFT;
+    buf.put_u32_le(msg_id);
+    buf.put_u16_le(signature);
+    buf.put_u32_le(sequence);
+    buf.put_u64_le(convert_ts(now_ms(), MSG_VERSION_TS_MS, version));
+    buf.put_u64_le(len as u64 | (flags as u64) << MSG_FLAGS_SHIFT);
+    if let Some(checksum) = checksum {
+        buf.put_u32_le(checksum);
     }
+    Ok(())
+}
+
+fn put_buffer(
+    msg_id: u32,
+    signature: u16,
+    sequence: u32,
+    msg_buf: Vec<u8>,
+    uuid: Option<String>,
+    buf: &mut impl BufMut,
+) -> Result<(), String> {
+    let peer = get_peer(&uuid);
+    peer.check(msg_buf.len())?;
+    // Body is compressed before middleware, because middleware can encrypt it
+    let (flags, msg_buf) = compress(msg_buf, peer.compression);
+    let buffer = PackingMiddleware::encode(msg_buf, msg_id, sequence, uuid)?;
+    peer.check(buffer.len())?;
+    let checksum = if get_checksum() {
+        Some(crc32c(&buffer))
+    } else {
+        None
+    };
+    put_header(
+        msg_id,
+        signature,
+        sequence,
+        buffer.len(),
+        flags | peer.version << MSG_VERSION_SHIFT,
+        checksum,
+        buf,
+    )?;
+    buf.put_slice(&buffer);
+    Ok(())
 }
 
 pub fn pack_buffer(
@@ -137,41 +381,93 @@ pub fn pack_buffer(
     msg_buf: Vec<u8>,
     uuid: Option<String>,
 ) -> Result<Vec<u8>, String> {
-    let buffer = match PackingMiddleware::encode(msg_buf, msg_id, sequence, uuid) {
-        Ok(buffer) => buffer,
-        Err(e) => {
-            return Err(e);
-        }
-    };
-    match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(duration) => {
-            let mut buf: Vec<u8> = vec![];
-            buf.append(&mut msg_id.to_le_bytes().to_vec());
-            buf.append(&mut signature.to_le_bytes().to_vec());
-            buf.append(&mut sequence.to_le_bytes().to_vec());
-            buf.append(&mut duration.as_secs().to_le_bytes().to_vec());
-            buf.append(&mut (buffer.len() as u64).to_le_bytes().to_vec());
-            buf.append(&mut buffer.to_vec());
-            Ok(buf)
-        }
-        Err(e) => Err(e.to_string()),
-    }
+    let mut buf: Vec<u8> = Vec::with_capacity(MSG_HEADER_LEN + MSG_CHECKSUM_LEN + msg_buf.len());
+    put_buffer(msg_id, signature, sequence, msg_buf, uuid, &mut buf)?;
+    Ok(buf)
 }
 
 pub trait PackingStruct: StructEncode {
-    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
-        match self.abduct() {
-            Ok(buf) => pack_buffer(self.get_id(), self.get_signature(), sequence, buf, uuid),
-            Err(e) => Err(e),
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>;
+    // Writes package in one pass; buffer (for example BytesMut) can be reused
+    fn pack_into(
+        &self,
+        sequence: u32,
+        uuid: Option<String>,
+        buf: &mut impl BufMut,
+    ) -> Result<(), String>
+    where
+        Self: Sized,
+    {
+        let len = self.encoded_len();
+        let peer = get_peer(&uuid);
+        if PackingMiddleware::transparent()
+            && compression_flag(len, peer.compression) == 0
+            && !get_checksum()
+        {
+            peer.check(len)?;
+            put_header(
+                self.get_id(),
+                self.get_signature(),
+                sequence,
+                len,
+                peer.version << MSG_VERSION_SHIFT,
+                None,
+                buf,
+            )?;
+            return self.encode_into(buf);
         }
+        let mut body: Vec<u8> = Vec::with_capacity(len);
+        self.encode_into(&mut body)?;
+        put_buffer(
+            self.get_id(),
+            self.get_signature(),
+            sequence,
+            body,
+            uuid,
+            buf,
+        )
     }
 }
 
 pub trait PackingEnum: EnumEncode {
-    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
-        match self.abduct() {
-            Ok(buf) => pack_buffer(self.get_id(), self.get_signature(), sequence, buf, uuid),
-            Err(e) => Err(e),
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>;
+    // Writes package in one pass; buffer (for example BytesMut) can be reused
+    fn pack_into(
+        &self,
+        sequence: u32,
+        uuid: Option<String>,
+        buf: &mut impl BufMut,
+    ) -> Result<(), String>
+    where
+        Self: Sized,
+    {
+        let len = self.encoded_len();
+        let peer = get_peer(&uuid);
+        if PackingMiddleware::transparent()
+            && compression_flag(len, peer.compression) == 0
+            && !get_checksum()
+        {
+            peer.check(len)?;
+            put_header(
+                self.get_id(),
+                self.get_signature(),
+                sequence,
+                len,
+                peer.version << MSG_VERSION_SHIFT,
+                None,
+                buf,
+            )?;
+            return self.encode_into(buf);
         }
+        let mut body: Vec<u8> = Vec::with_capacity(len);
+        self.encode_into(&mut body)?;
+        put_buffer(
+            self.get_id(),
+            self.get_signature(),
+            sequence,
+            body,
+            uuid,
+            buf,
+        )
     }
 }
@@ -5,6 +5,10 @@ use std::convert::TryFrom;
 use std::io::Cursor;
 
 // injectable
+// Properties, which aren't known by current version of protocol (id, body). Struct
+// keeps them and writes them back on encoding
+pub type UnknownFields = Vec<(u16, Vec<u8>)>;
+
 #[derive(Debug, Clone)]
 pub struct Storage {
     map: HashMap<u16, Vec<u8>>,
@@ -49,6 +53,13 @@ impl Storage {
         } else {
             return Err("Fail to set cursor position".to_string());
         }
+        if buf.len() < pos + sizes::U16_LEN {
+            return Err(format!(
+                "Cannot extract id of property at {}, because length of buffer is {}",
+                pos,
+                buf.len()
+            ));
+        }
         let id = cursor.get_u16_le();
         Ok((id, pos + sizes::U16_LEN))
     }
@@ -60,15 +71,27 @@ impl Storage {
         } else {
             return Err("Fail to set cursor position".to_string());
         }
+        let prop_rank_len: usize = 1;
+        let prop_size_len = match buf.get(pos) {
+            Some(8) => sizes::U8_LEN,
+            Some(16) => sizes::U16_LEN,
+            Some(32) => sizes::U32_LEN,
+            Some(64) => sizes::U64_LEN,
+            _ => 0,
+        };
+        if buf.len() < pos + prop_rank_len + prop_size_len {
+            return Err(format!(
+                "Cannot extract length of property at {}, because length of buffer is {}",
+                pos,
+                buf.len()
+            ));
+        }
         let prop_body_len_rank = cursor.get_u8();
         let prop_body_len_usize: usize;
-        let prop_rank_len: usize = 1;
-        let prop_size_len: usize;
         match prop_body_len_rank {
             8 => {
                 if let Ok(val) = usize::try_from(cursor.get_u8()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U8_LEN;
                 } else {
                     return Err("Fail convert length of name from u8 to usize".to_string());
                 }
@@ -76,7 +99,6 @@ impl Storage {
             16 => {
                 if let Ok(val) = usize::try_from(cursor.get_u16_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U16_LEN;
                 } else {
                     return Err("Fail convert length of name from u16 to usize".to_string());
                 }
@@ -84,7 +106,6 @@ impl Storage {
             32 => {
                 if let Ok(val) = usize::try_from(cursor.get_u32_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U32_LEN;
                 } else {
                     return Err("Fail convert length of name from u32 to usize".to_string());
                 }
@@ -92,7 +113,6 @@ impl Storage {
             64 => {
                 if let Ok(val) = usize::try_from(cursor.get_u64_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U64_LEN;
                 } else {
                     return Err("Fail convert length of name from u64 to usize".to_string());
                 }
@@ -101,6 +121,14 @@ impl Storage {
                 return Err(format!("Unknown rank has been gotten: {}", v));
             }
         };
+        // Length is taken from incoming data; it shouldn't be trusted
+        if buf.len() - (pos + prop_rank_len + prop_size_len) < prop_body_len_usize {
+            return Err(format!(
+                "Length of property body ({} bytes) is bigger than rest of buffer ({} bytes)",
+                prop_body_len_usize,
+                buf.len() - (pos + prop_rank_len + prop_size_len)
+            ));
+        }
         let mut prop_body_buf = vec![0; prop_body_len_usize];
         prop_body_buf.copy_from_slice(
             &buf[(pos + prop_rank_len + prop_size_len)
@@ -125,4 +153,15 @@ impl Storage {
     pub fn get(&mut self, id: u16) -> Option<&Vec<u8>> {
         self.map.get(&id)
     }
+
+    pub fn unknown(&self, known: &[u16]) -> UnknownFields {
+        let mut fields: UnknownFields = self
+            .map
+            .iter()
+            .filter(|(id, _)| !known.contains(id))
+            .map(|(id, body)| (*id, body.clone()))
+            .collect();
+        fields.sort_by_key(|(id, _)| *id);
+        fields
+    }
 }
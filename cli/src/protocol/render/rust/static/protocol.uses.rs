@@ -6,5 +6,7 @@
 use std::convert::TryFrom;
 use std::io::Cursor;
 use std::collections::{ HashMap };
-use bytes::{ Buf };
+use bytes::{ Buf, BufMut };
 use std::time::{ SystemTime, UNIX_EPOCH };
+use std::sync::atomic::{ AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering };
+use std::sync::Mutex;
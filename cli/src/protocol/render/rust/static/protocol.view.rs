@@ -0,0 +1,338 @@
+use super::compression::COMPRESSION_FLAGS;
+use super::packing::{
+    check_body, get_header_from_buffer, get_package_len, has_buffer_header, PackageHeader,
+};
+
+// injectable
+// Views borrow data from buffer of package: strings are &str, nested structs and lists
+// are decoded only on access. to_owned() gives regular (owned) struct
+pub trait ViewDecode<'a>: Sized {
+    fn view(buf: &'a [u8]) -> Result<Self, String>;
+}
+
+// Reads item of list; moves position to next item
+pub trait ViewItem<'a>: Sized {
+    fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String>;
+}
+
+pub trait ViewOwned {
+    type Owned;
+    fn view_to_owned(&self) -> Result<Self::Owned, String>;
+}
+
+pub fn view_take<'a>(buf: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    if buf.len() < *pos + len {
+        return Err(format!(
+            "Unexpected end of buffer: expecting {} bytes at {}",
+            len, pos
+        ));
+    }
+    let bytes = &buf[*pos..*pos + len];
+    *pos += len;
+    Ok(bytes)
+}
+
+fn view_uint(buf: &[u8], pos: &mut usize, len: usize) -> Result<u64, String> {
+    Ok(view_take(buf, pos, len)?
+        .iter()
+        .rev()
+        .fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+// Item of list, which is prefixed with u64 length (structs and enums)
+pub fn view_prefixed<'a, T: ViewDecode<'a>>(buf: &'a [u8], pos: &mut usize) -> Result<T, String> {
+    let len = <usize as std::convert::TryFrom<u64>>::try_from(view_uint(buf, pos, 8)?)
+        .map_err(|e| e.to_string())?;
+    T::view(view_take(buf, pos, len)?)
+}
+
+/*
+| PROP_ID  | PROP_BODY_LEN_GRAD | PROP_BODY_LEN | PROP_BODY | ... |
+| 2 bytes  | 1 byte             | 1 - 8 bytes   | n bytes   | ... |
+*/
+#[derive(Debug, Clone)]
+pub struct ViewFields<'a> {
+    fields: Vec<(u16, &'a [u8])>,
+}
+
+impl<'a> ViewFields<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, String> {
+        let mut fields: Vec<(u16, &'a [u8])> = vec![];
+        let mut pos: usize = 0;
+        while pos < buf.len() {
+            let id = view_uint(buf, &mut pos, 2)? as u16;
+            let len = match view_take(buf, &mut pos, 1)?[0] {
+                8 => view_uint(buf, &mut pos, 1)?,
+                16 => view_uint(buf, &mut pos, 2)?,
+                32 => view_uint(buf, &mut pos, 4)?,
+                64 => view_uint(buf, &mut pos, 8)?,
+                v => {
+                    return Err(format!("Unknown rank has been gotten: {}", v));
+                }
+            };
+            let len =
+                <usize as std::convert::TryFrom<u64>>::try_from(len).map_err(|e| e.to_string())?;
+            fields.push((id, view_take(buf, &mut pos, len)?));
+        }
+        Ok(ViewFields { fields })
+    }
+
+    // Last property wins, as it does for Storage
+    pub fn get<T: ViewDecode<'a>>(&self, id: u16) -> Result<T, String> {
+        match self.fields.iter().rev().find(|(field, _)| *field == id) {
+            Some((_, body)) => T::view(body),
+            None => Err(format!("Buffer for property {} isn't found", id)),
+        }
+    }
+
+    pub fn unknown(&self, known: &[u16]) -> Vec<(u16, &'a [u8])> {
+        let mut fields: Vec<(u16, &'a [u8])> = self
+            .fields
+            .iter()
+            .filter(|(id, _)| !known.contains(id))
+            .copied()
+            .collect();
+        fields.sort_by_key(|(id, _)| *id);
+        fields
+    }
+}
+
+// Value, which is decoded only on request
+#[derive(Debug, Clone)]
+pub struct Lazy<'a, T> {
+    buf: &'a [u8],
+    value: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: ViewDecode<'a>> Lazy<'a, T> {
+    pub fn get(&self) -> Result<T, String> {
+        T::view(self.buf)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a, T> ViewDecode<'a> for Lazy<'a, T> {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        Ok(Lazy {
+            buf,
+            value: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<'a, T: ViewDecode<'a> + ViewOwned> ViewOwned for Lazy<'a, T> {
+    type Owned = T::Owned;
+    fn view_to_owned(&self) -> Result<T::Owned, String> {
+        self.get()?.view_to_owned()
+    }
+}
+
+// List, which items are decoded while iterating
+#[derive(Debug, Clone)]
+pub struct ListView<'a, T> {
+    buf: &'a [u8],
+    item: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: ViewItem<'a>> ListView<'a, T> {
+    pub fn iter(&self) -> ListViewIter<'a, T> {
+        ListViewIter {
+            buf: self.buf,
+            pos: 0,
+            item: std::marker::PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+impl<'a, T> ViewDecode<'a> for ListView<'a, T> {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        Ok(ListView {
+            buf,
+            item: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<'a, T: ViewItem<'a> + ViewOwned> ViewOwned for ListView<'a, T> {
+    type Owned = Vec<T::Owned>;
+    fn view_to_owned(&self) -> Result<Vec<T::Owned>, String> {
+        self.iter().map(|item| item?.view_to_owned()).collect()
+    }
+}
+
+pub struct ListViewIter<'a, T> {
+    buf: &'a [u8],
+    pos: usize,
+    item: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: ViewItem<'a>> Iterator for ListViewIter<'a, T> {
+    type Item = Result<T, String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.buf.len() {
+            return None;
+        }
+        let item = T::view_item(self.buf, &mut self.pos);
+        if item.is_err() {
+            // Position of next item is unknown after error
+            self.pos = self.buf.len();
+        }
+        Some(item)
+    }
+}
+
+macro_rules! view_fixed {
+    ($type:ty, $len:expr) => {
+        impl<'a> ViewDecode<'a> for $type {
+            fn view(buf: &'a [u8]) -> Result<Self, String> {
+                Self::view_item(buf, &mut 0)
+            }
+        }
+        impl<'a> ViewItem<'a> for $type {
+            fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String> {
+                let mut bytes = [0u8; $len];
+                bytes.copy_from_slice(view_take(buf, pos, $len)?);
+                Ok(<$type>::from_le_bytes(bytes))
+            }
+        }
+        impl ViewOwned for $type {
+            type Owned = $type;
+            fn view_to_owned(&self) -> Result<$type, String> {
+                Ok(*self)
+            }
+        }
+    };
+}
+
+view_fixed!(u8, 1);
+view_fixed!(u16, 2);
+view_fixed!(u32, 4);
+view_fixed!(u64, 8);
+view_fixed!(i8, 1);
+view_fixed!(i16, 2);
+view_fixed!(i32, 4);
+view_fixed!(i64, 8);
+view_fixed!(f32, 4);
+view_fixed!(f64, 8);
+
+impl<'a> ViewDecode<'a> for bool {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        Self::view_item(buf, &mut 0)
+    }
+}
+
+impl<'a> ViewItem<'a> for bool {
+    fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String> {
+        Ok(view_take(buf, pos, 1)?[0] != 0)
+    }
+}
+
+impl ViewOwned for bool {
+    type Owned = bool;
+    fn view_to_owned(&self) -> Result<bool, String> {
+        Ok(*self)
+    }
+}
+
+impl<'a> ViewDecode<'a> for &'a str {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        std::str::from_utf8(buf).map_err(|e| e.to_string())
+    }
+}
+
+// Strings in list are prefixed with u32 length
+impl<'a> ViewItem<'a> for &'a str {
+    fn view_item(buf: &'a [u8], pos: &mut usize) -> Result<Self, String> {
+        let len = view_uint(buf, pos, 4)? as usize;
+        Self::view(view_take(buf, pos, len)?)
+    }
+}
+
+impl<'a> ViewOwned for &'a str {
+    type Owned = String;
+    fn view_to_owned(&self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
+impl<'a> ViewDecode<'a> for &'a [u8] {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        Ok(buf)
+    }
+}
+
+impl<'a> ViewOwned for &'a [u8] {
+    type Owned = Vec<u8>;
+    fn view_to_owned(&self) -> Result<Vec<u8>, String> {
+        Ok(self.to_vec())
+    }
+}
+
+// Empty body of optional property means None
+impl<'a, T: ViewDecode<'a>> ViewDecode<'a> for Option<T> {
+    fn view(buf: &'a [u8]) -> Result<Self, String> {
+        if buf.is_empty() {
+            Ok(None)
+        } else {
+            T::view(buf).map(Some)
+        }
+    }
+}
+
+impl<T: ViewOwned> ViewOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+    fn view_to_owned(&self) -> Result<Option<T::Owned>, String> {
+        match self {
+            Some(value) => Ok(Some(value.view_to_owned()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+// Package, which body is borrowed from incoming buffer. PackingMiddleware isn't applied and
+// compressed body can't be viewed
+#[derive(Debug, Clone)]
+pub struct FrameView<'a> {
+    pub header: PackageHeader,
+    pub body: &'a [u8],
+}
+
+impl<'a> FrameView<'a> {
+    /// Returns first package of buffer and rest of buffer after it; None if buffer
+    /// doesn't have whole package yet
+    pub fn read(buf: &'a [u8]) -> Result<Option<(FrameView<'a>, &'a [u8])>, String> {
+        if !has_buffer_header(buf) {
+            return Ok(None);
+        }
+        let header = get_header_from_buffer(buf)?;
+        let end = get_package_len(&header);
+        if buf.len() < end {
+            return Ok(None);
+        }
+        let start = end - header.len_usize;
+        Ok(Some((
+            FrameView {
+                header,
+                body: &buf[start..end],
+            },
+            &buf[end..],
+        )))
+    }
+
+    pub fn view<T: ViewDecode<'a>>(&self) -> Result<T, String> {
+        if self.header.flags & COMPRESSION_FLAGS != 0 {
+            return Err(String::from(
+                "Body of package is compressed; it can be read with Buffer only",
+            ));
+        }
+        check_body(&self.header, self.body)?;
+        T::view(self.body)
+    }
+}
@@ -1,257 +1,571 @@
-use super::protocol::enums::Enum;
+use super::protocol::codec::Codec;
+use super::protocol::encryption::Encryption;
+use super::protocol::enums::{Enum, EnumItem};
 use super::protocol::fields::Field;
 use super::protocol::groups::Group;
 use super::protocol::store::Store;
 use super::protocol::structs::Struct;
 use super::protocol::types::PrimitiveTypes;
-use super::{helpers, stop, Render};
+use super::{
+    helpers::{self, render::indent, template::Template},
+    stop, Render,
+};
 use regex::Regex;
 use std::{include_str, path::Path};
 
+mod templates {
+    pub const PROTOCOL: &str = r#"[[includes]][[json]]
+[[?codec_impl]][[codec_impl]]
+[[/codec_impl]][[messages]][[entities]]
+[[buffer]]
+
+export function hash(): string { return `[[hash]]`; }
+export function codec(): string { return `[[codec]]`; }
+"#;
+    pub const GROUP: &str = r#"export namespace [[name]] {
+[[messages]]
+[[entities]]
+}"#;
+    pub const MESSAGES: &str = r#"export interface IAvailableMessages {[[messages]]
+}
+[[json]]"#;
+    pub const MESSAGE_ENUM: &str = r#"[[name]]?: I[[name]],"#;
+    pub const MESSAGE_STRUCT: &str = r#"[[name]]?: [[name]],"#;
+    pub const MESSAGE_GROUP: &str = r#"[[name]]?: [[name]].IAvailableMessages,"#;
+    pub const INTERFACE: &str = r#"export interface I[[name]] {[[fields]]
+}"#;
+    pub const INTERFACE_FIELD: &str = r#"[[name]]: [[type]];"#;
+    pub const INTERFACE_OPTION: &str = r#"[[name]]?: [[type]];"#;
+    pub const STRUCT: &str = r#"[[interface]]
+export class [[name]] extends Protocol.Convertor<[[name]]> implements I[[name]], ISigned<[[name]]> {
+
+    public static scheme: Protocol.IPropScheme[] = [[[scheme]]
+    ];
+
+    public static defaults(): [[name]] {
+        return [[defaults]];
+    }
+
+[[validator]]
+
+[[from]]
+
+[[json]]
+[[declarations]]
+    public static getSignature(): string { return '[[name]]'; }
+    public static getId(): number { return [[id]]; }
+
+
+[[constructor]]
+
+    public signature(): number { return [[signature]]; }
+
+    public getSignature(): string { return '[[name]]'; }
+
+    public get(): [[name]] { return this; }
+
+    public getId(): number { return [[id]]; }
+
+[[codec]]
+
+    public defaults(): [[name]] {
+        return [[name]].defaults();
+    }
+}"#;
+    pub const STRUCT_DECLARATION: &str = r#"public [[name]]!: [[type]];"#;
+    pub const STRUCT_ENUM_DECLARATION: &str = r#"private _[[name]]: Primitives.Enum;"#;
+    pub const STRUCT_VALIDATOR: &str = r#"public static getValidator(array: boolean): { validate(value: any): Error | undefined } {
+    if (array) {
+        return { validate(obj: any): Error | undefined {
+            if (!(obj instanceof Array)) {
+                return new Error(`Expecting Array<[[name]]>`);
+            }
+            try {
+                obj.forEach((o, index: number) => {
+                    if (!(o instanceof [[name]])) {
+                        throw new Error(`Expecting instance of [[name]] on index #${index}`);
+                    }
+                });
+            } catch (err) {
+                return err instanceof Error ? err : new Error(`Unknown error: ${err}`);
+            }
+        }};
+    } else {
+        return { validate(obj: any): Error | undefined {
+            return obj instanceof [[name]] ? undefined : new Error(`Expecting instance of [[name]]`);
+        }};
+    }
+}"#;
+    pub const STRUCT_FROM: &str = r#"public static from(obj: any): [[name]] | Error {
+    if (obj instanceof Buffer || obj instanceof ArrayBuffer || obj instanceof Uint8Array) {
+        const inst = [[name]].defaults();
+        const err = inst.decode(obj);
+        return err instanceof Error ? err : inst;
+    } else {
+        const error: Error | undefined = Protocol.validate(obj, [[name]].scheme);
+        return error instanceof Error ? error : new [[name]]({[[fields]]
+        });
+    }
+}"#;
+    pub const STRUCT_FROM_FIELD: &str = r#"[[name]]: obj.[[name]],"#;
+    pub const STRUCT_CONSTRUCTOR: &str = r#"constructor(params: I[[name]])  {
+    super();
+    Object.keys(params).forEach((key: string) => {
+        (this as any)[key] = (params as any)[key];
+    });[[enums]]
+}"#;
+    pub const STRUCT_CONSTRUCTOR_ENUM: &str = r#"this._[[name]] = new [[enum]]()
+[[?optional]]this.[[name]] !== undefined && [[/optional]]this._[[name]].set(this.[[name]]);"#;
+    pub const STRUCT_DEFAULT: &str = r#"new [[path]]({[[fields]]
+})"#;
+    pub const PROPERTY: &str = r#"[[name]]: [[value]],"#;
+    pub const FIELD_SCHEME_PRIMITIVE: &str =
+        r#"{ prop: '[[name]]', types: Protocol.Primitives.[[type]], optional: [[optional]], },"#;
+    pub const FIELD_SCHEME_STRUCT: &str = r#"{ prop: '[[name]]', types: [[type]].getValidator([[repeated]]), optional: [[optional]] },"#;
+    pub const FIELD_SCHEME_ENUM: &str = r#"{ prop: '[[name]]', optional: [[optional]], options: [[[options]]
+] },"#;
+    pub const FIELD_SCHEME_OPTION_PRIMITIVE: &str =
+        r#"{ prop: '[[name]]', types: Protocol.Primitives.[[type]], optional: false, },"#;
+    pub const FIELD_SCHEME_OPTION_STRUCT: &str =
+        r#"{ prop: '[[name]]', types: [[type]].getValidator([[repeated]]), optional: false },"#;
+    pub const STRUCT_BINARY: &str = r#"public encode(): ArrayBufferLike {
+    return this.collect([[[encode]]
+    ]);
+}
+
+public decode(buffer: ArrayBufferLike): Error | [[name]] {
+    return Protocol.ProtocolLimits.depth<[[name]]>(() => {
+        const storage = this.getStorage(buffer);
+        if (storage instanceof Error) {
+            return storage;
+        }[[decode]]
+        return this;
+    });
+}"#;
+    pub const FIELD_ENCODE_VALUE: &str = r#"() => [[?optional]]this.[[name]] === undefined ? this.getBuffer([[id]], Protocol.ESize.u8, 0, new Uint8Array()) : [[/optional]][[encode]]"#;
+    pub const FIELD_ENCODE_ARRAY: &str = r#"this.getBufferFromBuf<Array<[[type]]>>([[id]], Protocol.ESize.u64, Protocol.Primitives.[[primitive]].encode, this.[[name]])"#;
+    pub const FIELD_ENCODE_STR: &str = r#"this.getBufferFromBuf<string>([[id]], [[size]], Protocol.Primitives.[[primitive]].encode, this.[[name]])"#;
+    pub const FIELD_ENCODE_PRIMITIVE: &str = r#"this.getBuffer([[id]], [[size]], Protocol.Primitives.[[primitive]].getSize(), Protocol.Primitives.[[primitive]].encode(this.[[name]]))"#;
+    pub const FIELD_ENCODE_STRUCTS: &str = r#"() => {[[?optional]]if (this.[[name]] === undefined) { return this.getBuffer([[id]], Protocol.ESize.u8, 0, new Uint8Array()); }[[/optional]] const self: [[type]] = [[type]].defaults(); return this.getBufferFromBuf<[[type]][]>([[id]], Protocol.ESize.u64, self.encodeSelfArray.bind(self), this.[[name]]); }"#;
+    pub const FIELD_ENCODE_ENTITY: &str = r#"() => {[[?optional]]if (this.[[name]] === undefined) { return this.getBuffer([[id]], Protocol.ESize.u8, 0, new Uint8Array()); }[[/optional]] const buffer = this.[[target]].encode(); return this.getBuffer([[id]], Protocol.ESize.u64, BigInt(buffer.byteLength), buffer); }"#;
+    pub const FIELD_DECODE_OPTIONAL: &str = r#"const [[name]]Buf: ArrayBufferLike | undefined = storage.get([[id]]);
+if ([[name]]Buf === undefined) {
+    return new Error(`Fail to get property [[name]] (id=[[id]])`);
+}
+if ([[name]]Buf.byteLength === 0) {
+    this.[[name]] = undefined;
+} else {
+[[decode]]
+}"#;
+    pub const FIELD_DECODE_STRUCTS: &str = r#"const arr[[name]]Inst: [[path]] = [[path]].defaults();
+const arr[[name]]: Array<any> | Error = this.getValue<[[type]][]>(storage, [[id]], arr[[name]]Inst.decodeSelfArray.bind(arr[[name]]Inst));
+if (arr[[name]] instanceof Error) {
+    return arr[[name]];
+} else {
+    this.[[name]] = arr[[name]] as [[path]][];
+}"#;
+    pub const FIELD_DECODE_STRUCT: &str = r#"const [[name]]: [[type]] = [[default]];
+const [[name]]Buf: ArrayBufferLike | undefined = storage.get([[id]]);
+if ([[name]]Buf === undefined) {
+    return new Error(`Fail to find field "[[name]]" (id=[[id]]).`);
+}
+const [[name]]Err: Error | [[type]] = [[name]].decode([[name]]Buf);
+if ([[name]]Err instanceof Error) {
+    return [[name]]Err;
+} else {
+    this.[[name]] = [[name]];
+}"#;
+    pub const FIELD_DECODE_ENUM: &str = r#"this.[[name]] = {};
+const [[name]]Buf: ArrayBufferLike | undefined = storage.get([[id]]);
+if ([[name]]Buf === undefined) {
+    return new Error(`Fail to get property "[[name]]"`);
+}
+if ([[name]]Buf.byteLength > 0) {
+    const [[name]]Err: Error | undefined = this._[[name]].decode([[name]]Buf);
+    if ([[name]]Err instanceof Error) {
+        return [[name]]Err;
+    } else {
+        this.[[name]] = this._[[name]].get();
+    }
+}"#;
+    pub const FIELD_DECODE_PRIMITIVE: &str = r#"const [[name]]: [[type]] | Error = this.getValue<[[type]]>(storage, [[id]], Protocol.Primitives.[[primitive]].decode);
+if ([[name]] instanceof Error) {
+    return [[name]];
+} else {
+    this.[[name]] = [[name]];
+}"#;
+    pub const ENUM: &str = r#"[[interface]]
+
+export class [[name]] extends Protocol.Primitives.Enum<I[[name]]> {
+    public static from(obj: any): I[[name]] | Error {
+        const inst = new [[name]]();
+        let err: Error | undefined;
+        if (obj instanceof Buffer || obj instanceof ArrayBuffer || obj instanceof Uint8Array) {
+            err = inst.decode(obj);
+        } else {
+            err = inst.set(obj);
+        }
+        return err instanceof Error ? err : inst.get();
+    }
+    public static getId(): number { return [[id]]; }
+    public from(obj: any): I[[name]] | Error {
+        return [[name]].from(obj);
+    }
+[[json]]
+[[?codec]][[codec]]
+[[/codec]]    public signature(): number { return [[signature]]; }
+    public getId(): number { return [[id]]; }
+    public getAllowed(): string[] {
+        return [[[allowed]]
+        ];
+    }
+    public getOptionValue(id: number): ISigned<any> {
+        switch (id) {[[options]]
+            default: throw new Error(`No option with id=${id}`);
+        }
+    }
+    public get(): I[[name]] {
+        const target: I[[name]] = {};
+        switch (this.getValueIndex()) {[[getters]]
+        }
+        return target;
+    }
+    public set(src: I[[name]]): Error | undefined{
+        if (Object.keys(src).length > 1) {
+            return new Error(`Option cannot have more then 1 value.`);
+        }[[setters]]
+    }
+}"#;
+    pub const ENUM_ALLOWED: &str = r#"[[type]].getSignature(),"#;
+    pub const ENUM_OPTION_PRIMITIVE: &str =
+        r#"case [[index]]: return new Protocol.Primitives.[[type]]([[default]]);"#;
+    pub const ENUM_OPTION_STRUCT: &str = r#"case [[index]]: return [[path]].defaults();"#;
+    pub const ENUM_GETTER: &str =
+        r#"case [[index]]: target.[[name]] = this.getValue<[[type]]>(); break;"#;
+    pub const ENUM_SETTER: &str = r#"if (src.[[name]] !== undefined) {
+    const err: Error | undefined = this.setValue(new Protocol.Primitives.Option<[[type]]>([[index]], [[value]]));
+    if (err instanceof Error) {
+        return err;
+    }
+}"#;
+    pub const STRUCT_JSON: &str = r#"public toJson(): any {
+    return {[[to_json]]
+    };
+}
+public static fromJson(json: any): [[name]] | Error {
+    try {
+        ProtocolJson.object(json, '[[name]]');
+        return [[name]].from({[[from_json]]
+        });
+    } catch (err) {
+        return ProtocolJson.error(err);
+    }
+}"#;
+    pub const ENUM_JSON: &str = r#"public static toJson(value: I[[name]]): any {[[to_json]]
+    return { Defaults: null };
+}
+public static fromJson(json: any): I[[name]] | Error {
+    try {
+        const [name, value] = ProtocolJson.variant(json, '[[name]]');
+        switch (name) {[[from_json]]
+            case 'Defaults': return {};
+            default: throw new Error(`Unknown variant ${name} of [[name]]`);
+        }
+    } catch (err) {
+        return ProtocolJson.error(err);
+    }
+}"#;
+    pub const ENUM_JSON_TO: &str =
+        r#"if (value.[[name]] !== undefined) { return { [[name]]: [[value]] }; }"#;
+    pub const ENUM_VARIANT_FROM: &str = r#"case '[[name]]': return { [[name]]: [[value]] };"#;
+    pub const STRUCT_CODEC: &str = r#"public toValue(): ProtocolCodec.Value {
+    return ProtocolCodec.map({[[to_value]]
+    });
+}
+public static fromValue(value: ProtocolCodec.Value): [[name]] | Error {
+    try {
+        ProtocolCodec.object(value, '[[name]]');
+        return [[name]].from({[[from_value]]
+        });
+    } catch (err) {
+        return ProtocolCodec.error(err);
+    }
+}
+public encode(): ArrayBufferLike {
+    return ProtocolCodec.encodeBody(this.toValue());
+}
+public decode(buffer: ArrayBufferLike): Error | [[name]] {
+    try {
+        const inst = ProtocolCodec.unwrap([[name]].fromValue(ProtocolCodec.decodeBody(buffer)), '[[name]]');[[assign]]
+        return this;
+    } catch (err) {
+        return ProtocolCodec.error(err);
+    }
+}"#;
+    pub const STRUCT_ASSIGN: &str = r#"this.[[name]] = inst.[[name]];[[?enum]]
+this._[[name]] = inst._[[name]];[[/enum]]"#;
+    pub const ENUM_CODEC: &str = r#"public static toValue(value: I[[name]]): ProtocolCodec.Value {[[to_value]]
+    return ProtocolCodec.map({ Defaults: ProtocolCodec.nil() });
+}
+public static fromValue(value: ProtocolCodec.Value): I[[name]] | Error {
+    try {
+        const [name, inner] = ProtocolCodec.variant(value, '[[name]]');
+        switch (name) {[[from_value]]
+            case 'Defaults': return {};
+            default: throw new Error(`Unknown variant ${name} of [[name]]`);
+        }
+    } catch (err) {
+        return ProtocolCodec.error(err);
+    }
+}
+public encode(): ArrayBufferLike {
+    let value: I[[name]];
+    try {
+        value = this.get();
+    } catch (_) {
+        value = {};
+    }
+    return ProtocolCodec.encodeBody([[name]].toValue(value));
+}
+public decode(bytes: ArrayBufferLike): Error | undefined {
+    try {
+        const value = [[name]].fromValue(ProtocolCodec.decodeBody(bytes));
+        return value instanceof Error ? value : this.set(value);
+    } catch (err) {
+        return ProtocolCodec.error(err);
+    }
+}"#;
+    pub const ENUM_CODEC_TO: &str = r#"if (value.[[name]] !== undefined) { return ProtocolCodec.map({ [[name]]: [[value]] }); }"#;
+    pub const STRUCT_COMPACT: &str = r#"public encode(): ArrayBufferLike {
+    const writer = new ProtocolCompact.Writer();[[write]]
+    return writer.get();
+}
+public decode(buffer: ArrayBufferLike): Error | [[name]] {
+    try {
+        const fields = ProtocolCompact.fields(buffer);
+        const inst = [[name]].from({[[read]]
+        });
+        if (inst instanceof Error) {
+            return inst;
+        }[[assign]]
+        return this;
+    } catch (err) {
+        return ProtocolCompact.error(err);
+    }
+}"#;
+    pub const STRUCT_COMPACT_WRITE: &str = r#"writer.field([[id]], [[kind]], this.[[name]]);"#;
+    pub const STRUCT_COMPACT_READ: &str =
+        r#"[[name]]: ProtocolCompact.[[read]](fields, [[id]], [[kind]], '[[struct]].[[name]]'),"#;
+    pub const ENUM_COMPACT: &str = r#"public encode(): ArrayBufferLike {
+    let value: I[[name]];
+    try {
+        value = this.get();
+    } catch (_) {
+        value = {};
+    }
+    const writer = new ProtocolCompact.Writer();[[write]]
+    return writer.get();
+}
+public decode(bytes: ArrayBufferLike): Error | undefined {
+    try {
+        const reader = new ProtocolCompact.Reader(bytes);
+        if (reader.rest() === 0) {
+            return this.set({});
+        }
+        const index = Number(reader.varint());
+        switch (index) {[[read]]
+            default: throw new Error(`Unknown variant ${index} of [[name]]`);
+        }
+    } catch (err) {
+        return ProtocolCompact.error(err);
+    }
+}"#;
+    pub const ENUM_COMPACT_WRITE: &str = r#"if (value.[[name]] !== undefined) { writer.varint([[index]]); [[kind]].write(value.[[name]], writer); }"#;
+    pub const ENUM_COMPACT_READ: &str = r#"case [[index]]: return this.set({ [[name]]: ProtocolCompact.value([[kind]], reader, '[[enum]].[[name]]') });"#;
+    pub const MESSAGES_JSON: &str = r#"export function toJson(msg: IAvailableMessages): any {[[to_json]]
+    throw new Error(`No message to convert into JSON`);
+}
+export function fromJson(json: any): IAvailableMessages | Error {
+    try {
+        const name = ProtocolJson.tag(json);
+        switch (name) {[[from_json]]
+        }[[groups]]
+        throw new Error(`Unknown message ${name}`);
+    } catch (err) {
+        return ProtocolJson.error(err);
+    }
+}"#;
+    pub const MESSAGES_JSON_TO_ENUM: &str = r#"if (msg.[[name]] !== undefined) { return ProtocolJson.tagged('[[path]]', [[name]].toJson(msg.[[name]])); }"#;
+    pub const MESSAGES_JSON_TO_STRUCT: &str = r#"if (msg.[[name]] !== undefined) { return ProtocolJson.tagged('[[path]]', msg.[[name]].toJson()); }"#;
+    pub const MESSAGES_JSON_TO_GROUP: &str =
+        r#"if (msg.[[name]] !== undefined) { return [[name]].toJson(msg.[[name]]); }"#;
+    pub const MESSAGES_JSON_FROM: &str = r#"case '[[path]]': return { [[name]]: ProtocolJson.unwrap([[name]].fromJson(json.message), name) };"#;
+    pub const MESSAGES_JSON_FROM_GROUP: &str = r#"if (name.startsWith('[[path]].')) { return { [[name]]: ProtocolJson.unwrap([[name]].fromJson(json), name) }; }"#;
+    pub const BUFFER: &str = r#"export class BufferReaderMessages extends BufferReader<IAvailableMessage<IAvailableMessages>> {
+    public signature(): number { return [[signature]]; }
+    public getMessage(header: MessageHeader, buffer: Buffer | ArrayBuffer | ArrayBufferLike): IAvailableMessage<IAvailableMessages> | Error {
+        let instance: any;
+        let enum_instance: any = {};
+        let err: Error | undefined;
+        switch (header.id) {[[messages]]
+            default: throw new Error(`Unknown message id=${header.id}`);
+        }
+    }
+}"#;
+    pub const BUFFER_ENUM: &str = r#"case [[id]]:
+    instance = new [[path]]();
+    err = instance.decode(buffer);
+    if (err instanceof Error) { return err; }
+    enum_instance = instance.get();
+    instance = enum_instance;
+    return { header: { id: header.id, sequence: header.sequence, timestamp: header.tsMs(), version: header.version }, msg: { [[entity]]}, getRef: () => instance };"#;
+    pub const BUFFER_STRUCT: &str = r#"case [[id]]:
+    instance = [[path]].defaults();
+    err = instance.decode(buffer);
+    return err instanceof Error ? err : { header: { id: header.id, sequence: header.sequence, timestamp: header.tsMs(), version: header.version }, msg: { [[entity]]}, getRef: () => instance };"#;
+}
+
 pub struct TypescriptRender {
     embedded: bool,
     signature: u16,
 }
 
 impl TypescriptRender {
-    fn groups(&self, group: &Group, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}export namespace {} {{\n", self.spaces(level), group.name);
-        body = format!(
-            "{}{}",
-            body,
-            self.get_messages_list(Some(group), &mut store.clone(), level + 1)
-        );
+    fn groups(&self, group: &Group, store: &mut Store) -> String {
+        let mut entities = vec![];
         for enum_id in &group.enums {
             if let Some(enums) = store.get_enum(*enum_id) {
-                body = format!(
-                    "{}\n{}",
-                    body,
-                    self.enums(&enums, &mut store.clone(), level + 1)
-                );
+                entities.push(self.enums(&enums, &mut store.clone()));
             }
         }
         for struct_id in &group.structs {
             if let Some(strct) = store.get_struct(*struct_id) {
-                body = format!(
-                    "{}\n{}",
-                    body,
-                    self.structs(&strct, &mut store.clone(), level + 1)
-                );
+                entities.push(self.structs(&strct, &mut store.clone()));
             }
         }
-        let childs = store.get_child_groups(group.id);
-        for group in childs {
-            body = format!(
-                "{}\n{}",
-                body,
-                self.groups(&group, &mut store.clone(), level + 1)
-            );
+        for child in store.get_child_groups(group.id) {
+            entities.push(self.groups(&child, &mut store.clone()));
         }
-        format!("{}\n{}}}\n", body, self.spaces(level))
+        Template::new("protocol/typescript/group", templates::GROUP)
+            .set("name", &group.name)
+            .set(
+                "messages",
+                &indent(1, &self.get_messages_list(Some(group), &mut store.clone())),
+            )
+            .set("entities", &self.entities(&entities, 1))
+            .render()
     }
 
-    fn structs(&self, strct: &Struct, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}export interface I{} {{", self.spaces(level), strct.name);
+    fn structs(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut fields = vec![];
+        let mut declarations = vec![];
         for field in &strct.fields {
-            body = format!(
-                "{}\n{}{}",
-                body,
-                self.spaces(level + 1),
-                format!(
-                    "{}: {};",
-                    field.name,
-                    self.get_declare_type_ref(field, &mut store.clone())
-                ),
+            let kind = self.get_declare_type_ref(field, &mut store.clone());
+            fields.push(
+                Template::new(
+                    "protocol/typescript/struct_interface_field",
+                    templates::INTERFACE_FIELD,
+                )
+                .set("name", &field.name)
+                .set("type", &kind)
+                .render(),
             );
-        }
-        body = format!("{}\n{}}}\n", body, self.spaces(level));
-        body = format!(
-            "{}{}export class {} extends Protocol.Convertor<{}> implements I{}, ISigned<{}> {{\n",
-            body,
-            self.spaces(level),
-            strct.name,
-            strct.name,
-            strct.name,
-            strct.name,
-        );
-        body = format!(
-            "{}\n{}",
-            body,
-            self.struct_map(strct, &mut store.clone(), level + 1)
-        );
-
-        body = format!(
-            "{}\n{}public static defaults(): {} {{",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!(
-            "{}\n{}return {};",
-            body,
-            self.spaces(level + 2),
-            self.entity_default(strct.id, &mut store.clone(), level + 2)
-        );
-        body = format!("{}\n{}}}\n", body, self.spaces(level + 1));
-
-        body = format!("{}\n{}", body, self.struct_validator(strct, level + 1));
-
-        body = format!("{}\n{}", body, self.struct_from(strct, level + 1));
-
-        for field in &strct.fields {
-            body = format!(
-                "{}\n{}{}",
-                body,
-                self.spaces(level + 1),
-                format!(
-                    "public {}!: {};",
-                    field.name,
-                    self.get_declare_type_ref(field, &mut store.clone())
-                ),
+            declarations.push(
+                Template::new(
+                    "protocol/typescript/struct_declaration",
+                    templates::STRUCT_DECLARATION,
+                )
+                .set("name", &field.name)
+                .set("type", &kind)
+                .render(),
             );
         }
-
         for field in &strct.fields {
             if let Some(ref_type_id) = field.ref_type_id {
                 if store.get_enum(ref_type_id).is_some() {
-                    body = format!(
-                        "{}\n{}{}",
-                        body,
-                        self.spaces(level + 1),
-                        format!("private _{}: Primitives.Enum;", field.name,),
+                    declarations.push(
+                        Template::new(
+                            "protocol/typescript/struct_enum_declaration",
+                            templates::STRUCT_ENUM_DECLARATION,
+                        )
+                        .set("name", &field.name)
+                        .render(),
                     );
                 }
             }
         }
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public static getSignature(): string {{ return '{}'; }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!(
-            "{}{}public static getId(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.id
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}\n{}",
-            body,
-            self.struct_constructor(strct, &mut store.clone(), level + 1)
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public signature(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            self.signature
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public getSignature(): string {{ return '{}'; }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public get(): {} {{ return this; }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public getId(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            strct.id
-        );
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public encode(): ArrayBufferLike {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!("{}{}return this.collect([", body, self.spaces(level + 2));
-        for field in &strct.fields {
-            body = format!(
-                "{}\n{}{},",
-                body,
-                self.spaces(level + 3),
-                self.get_field_encode(field, &mut store.clone()),
-            );
-        }
-        body = format!("{}\n{}]);\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}public decode(buffer: ArrayBufferLike): Error | {} {{\n",
-            body,
-            self.spaces(level + 1),
-            strct.name,
-        );
-        body = format!(
-            "{}{}const storage = this.getStorage(buffer);",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}\n{}if (storage instanceof Error) {{",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}\n{}return storage;", body, self.spaces(level + 3));
-        body = format!("{}\n{}}}", body, self.spaces(level + 2));
+        let scheme = strct
+            .fields
+            .iter()
+            .map(|field| self.get_field_map_def(field, &mut store.clone()))
+            .collect::<Vec<String>>();
+        let codec = match store.get_codec() {
+            Codec::Binary => self.struct_binary(strct, &mut store.clone()),
+            Codec::Compact => self.struct_compact(strct, &mut store.clone()),
+            _ => self.struct_codec(strct, &mut store.clone()),
+        };
+        Template::new("protocol/typescript/struct", templates::STRUCT)
+            .set(
+                "interface",
+                &Template::new("protocol/typescript/struct_interface", templates::INTERFACE)
+                    .set("name", &strct.name)
+                    .set("fields", &self.lines(&fields, 1))
+                    .render(),
+            )
+            .set("name", &strct.name)
+            .set("id", &strct.id.to_string())
+            .set("signature", &self.signature.to_string())
+            .set("scheme", &self.lines(&scheme, 2))
+            .set(
+                "defaults",
+                &self.inline(2, &self.entity_default(strct.id, &mut store.clone())),
+            )
+            .set("validator", &indent(1, &self.struct_validator(strct)))
+            .set("from", &indent(1, &self.struct_from(strct)))
+            .set(
+                "json",
+                &indent(1, &self.struct_json(strct, &mut store.clone())),
+            )
+            .set("declarations", &self.lines(&declarations, 1))
+            .set(
+                "constructor",
+                &indent(1, &self.struct_constructor(strct, &mut store.clone())),
+            )
+            .set("codec", &indent(1, &codec))
+            .render()
+    }
+
+    fn struct_binary(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut encode = vec![];
+        let mut decode = vec![];
         for field in &strct.fields {
-            body = format!(
-                "{}\n{}",
-                body,
-                self.get_field_decode_wrap(field, &mut store.clone(), level + 2),
-            );
+            encode.push(format!(
+                "{},",
+                self.get_field_encode(field, &mut store.clone())
+            ));
+            decode.push(self.get_field_decode_wrap(field, &mut store.clone()));
         }
-        body = format!("{}\n{}return this;", body, self.spaces(level + 2));
-        body = format!("{}\n{}}}", body, self.spaces(level + 1));
-        body = format!("{}\n", body);
-        body = format!(
-            "{}\n{}public defaults(): {} {{",
-            body,
-            self.spaces(level + 1),
-            strct.name
-        );
-        body = format!(
-            "{}\n{}return {}.defaults();",
-            body,
-            self.spaces(level + 2),
-            strct.name
-        );
-        body = format!("{}\n{}}}\n", body, self.spaces(level + 1));
-
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body
-    }
-
-    fn enum_declaration(&self, enums: &Enum, store: &mut Store, level: u8) -> String {
-        let mut body = "[".to_string();
+        Template::new(
+            "protocol/typescript/struct_binary",
+            templates::STRUCT_BINARY,
+        )
+        .set("name", &strct.name)
+        .set("encode", &self.lines(&encode, 2))
+        .set("decode", &self.lines(&decode, 2))
+        .render()
+    }
+
+    fn enum_allowed(&self, enums: &Enum, store: &mut Store) -> Vec<String> {
+        let mut allowed = vec![];
         for variant in &enums.variants {
-            if let Some(prim_type_ref) = variant.types.clone() {
-                body = format!(
-                    "{}\n{}Protocol.Primitives.{}.getSignature(),",
-                    body,
-                    self.spaces(level),
+            let kind = if let Some(prim_type_ref) = variant.types.clone() {
+                format!(
+                    "Protocol.Primitives.{}",
                     self.etype(prim_type_ref, variant.repeated)
-                );
+                )
             } else if let Some(ref_type_id) = variant.ref_type_id {
                 if let Some(strct) = store.get_struct(ref_type_id) {
-                    body = format!(
-                        "{}\n{}{}.getSignature(),",
-                        body,
-                        self.spaces(level),
-                        store.get_struct_path(strct.id).join(".")
-                    );
+                    store.get_struct_path(strct.id).join(".")
                 } else {
                     stop!(
                         "Unknown type of data in scope of enum {} / {}, ref_type_id: {} ",
@@ -260,32 +574,42 @@ impl TypescriptRender {
                         ref_type_id
                     );
                 }
-            }
+            } else {
+                continue;
+            };
+            allowed.push(
+                Template::new("protocol/typescript/enum_allowed", templates::ENUM_ALLOWED)
+                    .set("type", &kind)
+                    .render(),
+            );
         }
-        body = format!("{}\n{}]", body, self.spaces(level - 1));
-        body
+        allowed
     }
 
-    fn enum_getter(&self, enums: &Enum, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}switch (id) {{", self.spaces(level));
+    fn enum_options(&self, enums: &Enum, store: &mut Store) -> Vec<String> {
+        let mut options = vec![];
         for (pos, variant) in enums.variants.iter().enumerate() {
             if let Some(prim_type_ref) = variant.types.clone() {
-                body = format!(
-                    "{}\n{}case {}: return new Protocol.Primitives.{}({});",
-                    body,
-                    self.spaces(level + 1),
-                    pos,
-                    self.etype(prim_type_ref.clone(), variant.repeated),
-                    self.etype_def(prim_type_ref, variant.repeated)
+                options.push(
+                    Template::new(
+                        "protocol/typescript/enum_option_primitive",
+                        templates::ENUM_OPTION_PRIMITIVE,
+                    )
+                    .set("index", &pos.to_string())
+                    .set("type", &self.etype(prim_type_ref.clone(), variant.repeated))
+                    .set("default", &self.etype_def(prim_type_ref, variant.repeated))
+                    .render(),
                 );
             } else if let Some(ref_type_id) = variant.ref_type_id {
                 if let Some(strct) = store.get_struct(ref_type_id) {
-                    body = format!(
-                        "{}\n{}case {}: return {}.defaults();",
-                        body,
-                        self.spaces(level + 1),
-                        pos,
-                        store.get_struct_path(strct.id).join(".")
+                    options.push(
+                        Template::new(
+                            "protocol/typescript/enum_option_struct",
+                            templates::ENUM_OPTION_STRUCT,
+                        )
+                        .set("index", &pos.to_string())
+                        .set("path", &store.get_struct_path(strct.id).join("."))
+                        .render(),
                     );
                 } else {
                     stop!(
@@ -297,28 +621,16 @@ impl TypescriptRender {
                 }
             }
         }
-        body = format!(
-            "{}\n{}default: throw new Error(`No option with id=${{id}}`);",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!("{}\n{}}}", body, self.spaces(level));
-        body
-    }
-
-    fn enum_setter(&self, enums: &Enum, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}if (Object.keys(src).length > 1) {{", self.spaces(level),);
-        body = format!(
-            "{}\n{}return new Error(`Option cannot have more then 1 value.`);",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!("{}\n{}}}", body, self.spaces(level));
+        options
+    }
+
+    fn enum_setters(&self, enums: &Enum, store: &mut Store) -> Vec<String> {
+        let mut setters = vec![];
         for (pos, variant) in enums.variants.iter().enumerate() {
             let value = if let Some(prim_type_ref) = variant.types.clone() {
                 format!(
                     "new Protocol.Primitives.{}(src.{})",
-                    self.etype(prim_type_ref.clone(), variant.repeated),
+                    self.etype(prim_type_ref, variant.repeated),
                     variant.name
                 )
             } else if variant.ref_type_id.is_some() {
@@ -330,260 +642,733 @@ impl TypescriptRender {
                     variant.name
                 );
             };
-            //
-            let types = if let Some(prim_type_ref) = variant.types.clone() {
-                self.etype_ts(prim_type_ref, variant.repeated)
-            } else if let Some(ref_type_id) = variant.ref_type_id {
-                if let Some(strct) = store.get_struct(ref_type_id) {
-                    store.get_struct_path(strct.id).join(".")
-                } else {
-                    stop!("Unknown type of data in scope of enum {} / {}, ref_type_id: {}. Failed to find a struct. ", enums.name, variant.name, ref_type_id);
-                }
-            } else {
-                stop!(
-                    "Unknown type of data in scope of enum {} / {}",
-                    enums.name,
-                    variant.name
-                );
-            };
-            body = format!(
-                "{}\n{}if (src.{} !== undefined) {{",
-                body,
-                self.spaces(level),
-                variant.name
+            setters.push(
+                Template::new("protocol/typescript/enum_setter", templates::ENUM_SETTER)
+                    .set("name", &variant.name)
+                    .set("type", &self.enum_variant_type(enums, variant, store))
+                    .set("index", &pos.to_string())
+                    .set("value", &value)
+                    .render(),
             );
-            body = format!("{}\n{}const err: Error | undefined = this.setValue(new Protocol.Primitives.Option<{}>({}, {}));", body, self.spaces(level + 1), types, pos, value);
-            body = format!(
-                "{}\n{}if (err instanceof Error) {{",
-                body,
-                self.spaces(level + 1)
-            );
-            body = format!("{}\n{}return err;", body, self.spaces(level + 2));
-            body = format!("{}\n{}}}", body, self.spaces(level + 1));
-            body = format!("{}\n{}}}", body, self.spaces(level));
         }
-        body
+        setters
     }
 
-    fn get_enum_decode(&self, enums: &Enum, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}switch (this.getValueIndex()) {{", self.spaces(level),);
+    fn enum_getters(&self, enums: &Enum, store: &mut Store) -> Vec<String> {
+        let mut getters = vec![];
         for (pos, variant) in enums.variants.iter().enumerate() {
-            let types = if let Some(prim_type_ref) = variant.types.clone() {
-                self.etype_ts(prim_type_ref, variant.repeated)
-            } else if let Some(ref_type_id) = variant.ref_type_id {
-                if let Some(strct) = store.get_struct(ref_type_id) {
-                    store.get_struct_path(strct.id).join(".")
-                } else {
-                    stop!("Unknown type of data in scope of enum {} / {}, ref_type_id: {}. Failed to find a struct. ", enums.name, variant.name, ref_type_id);
-                }
+            getters.push(
+                Template::new("protocol/typescript/enum_getter", templates::ENUM_GETTER)
+                    .set("index", &pos.to_string())
+                    .set("name", &variant.name)
+                    .set("type", &self.enum_variant_type(enums, variant, store))
+                    .render(),
+            );
+        }
+        getters
+    }
+
+    fn enum_variant_type(&self, enums: &Enum, variant: &EnumItem, store: &mut Store) -> String {
+        if let Some(prim_type_ref) = variant.types.clone() {
+            self.etype_ts(prim_type_ref, variant.repeated)
+        } else if let Some(ref_type_id) = variant.ref_type_id {
+            if let Some(strct) = store.get_struct(ref_type_id) {
+                store.get_struct_path(strct.id).join(".")
             } else {
-                stop!(
-                    "Unknown type of data in scope of enum {} / {}",
-                    enums.name,
-                    variant.name
-                );
-            };
-            body = format!(
-                "{}\n{}case {}: target.{} = this.getValue<{}>(); break;",
-                body,
-                self.spaces(level + 1),
-                pos,
-                variant.name,
-                types
+                stop!("Unknown type of data in scope of enum {} / {}, ref_type_id: {}. Failed to find a struct. ", enums.name, variant.name, ref_type_id);
+            }
+        } else {
+            stop!(
+                "Unknown type of data in scope of enum {} / {}",
+                enums.name,
+                variant.name
             );
         }
-        body = format!("{}\n{}}}", body, self.spaces(level));
-        body
-    }
-
-    fn struct_constructor(&self, strct: &Struct, store: &mut Store, level: u8) -> String {
-        let mut body = format!(
-            "{}constructor(params: I{})  {{\n",
-            self.spaces(level),
-            strct.name
-        );
-        body = format!("{}{}super();\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}Object.keys(params).forEach((key: string) => {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!(
-            "{}{}(this as any)[key] = (params as any)[key];\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}}});", body, self.spaces(level + 1));
+    }
+
+    fn struct_constructor(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut enums = vec![];
         for field in &strct.fields {
             if let Some(ref_type_id) = field.ref_type_id {
-                if let Some(enums) = store.get_enum(ref_type_id) {
-                    // -------
-                    body = format!(
-                        "{}\n{}this._{} = new {}()",
-                        body,
-                        self.spaces(level + 1),
-                        field.name,
-                        enums.name,
-                    );
-                    body = format!(
-                        "{}\n{}{}this._{}.set(this.{});",
-                        body,
-                        self.spaces(level + 1),
-                        if field.optional {
-                            format!("this.{} !== undefined && ", field.name)
-                        } else {
-                            "".to_string()
-                        },
-                        field.name,
-                        field.name,
+                if let Some(entity) = store.get_enum(ref_type_id) {
+                    enums.push(
+                        Template::new(
+                            "protocol/typescript/struct_constructor_enum",
+                            templates::STRUCT_CONSTRUCTOR_ENUM,
+                        )
+                        .set("name", &field.name)
+                        .set("enum", &entity.name)
+                        .set("optional", if field.optional { "true" } else { "" })
+                        .render(),
                     );
                 }
             }
         }
-        body = format!("{}\n{}}}\n", body, self.spaces(level));
-        body
+        Template::new(
+            "protocol/typescript/struct_constructor",
+            templates::STRUCT_CONSTRUCTOR,
+        )
+        .set("name", &strct.name)
+        .set("enums", &self.lines(&enums, 1))
+        .render()
+    }
+
+    fn struct_validator(&self, strct: &Struct) -> String {
+        Template::new(
+            "protocol/typescript/struct_validator",
+            templates::STRUCT_VALIDATOR,
+        )
+        .set("name", &strct.name)
+        .render()
+    }
+
+    fn struct_from(&self, strct: &Struct) -> String {
+        let fields = strct
+            .fields
+            .iter()
+            .map(|field| {
+                Template::new(
+                    "protocol/typescript/struct_from_field",
+                    templates::STRUCT_FROM_FIELD,
+                )
+                .set("name", &field.name)
+                .render()
+            })
+            .collect::<Vec<String>>();
+        Template::new("protocol/typescript/struct_from", templates::STRUCT_FROM)
+            .set("name", &strct.name)
+            .set("fields", &self.lines(&fields, 3))
+            .render()
     }
 
-    fn struct_map(&self, strct: &Struct, store: &mut Store, level: u8) -> String {
-        let mut body = format!(
-            "{}public static scheme: Protocol.IPropScheme[] = [",
-            self.spaces(level)
-        );
+    fn struct_json(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
         for field in &strct.fields {
-            body = format!(
-                "{}{}",
-                body,
-                self.get_field_map_def(field, &mut store.clone(), level + 1),
+            to_json.push(
+                Template::new("protocol/typescript/struct_json_to", templates::PROPERTY)
+                    .set("name", &field.name)
+                    .set(
+                        "value",
+                        &self.json_write(
+                            &field.kind,
+                            field.ref_type_id,
+                            field.repeated,
+                            field.optional,
+                            &format!("this.{}", field.name),
+                            store,
+                        ),
+                    )
+                    .render(),
+            );
+            from_json.push(
+                Template::new("protocol/typescript/struct_json_from", templates::PROPERTY)
+                    .set("name", &field.name)
+                    .set(
+                        "value",
+                        &self.json_read(
+                            &field.kind,
+                            field.ref_type_id,
+                            field.repeated,
+                            field.optional,
+                            &format!("json.{}", field.name),
+                            &format!("'{}.{}'", strct.name, field.name),
+                            store,
+                        ),
+                    )
+                    .render(),
+            );
+        }
+        Template::new("protocol/typescript/struct_json", templates::STRUCT_JSON)
+            .set("name", &strct.name)
+            .set("to_json", &self.lines(&to_json, 2))
+            .set("from_json", &self.lines(&from_json, 3))
+            .render()
+    }
+
+    fn enum_json(&self, enums: &Enum, store: &mut Store) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
+        for variant in &enums.variants {
+            let kind = variant
+                .types
+                .clone()
+                .and_then(PrimitiveTypes::get_entity_as_string)
+                .unwrap_or_default();
+            to_json.push(
+                Template::new("protocol/typescript/enum_json_to", templates::ENUM_JSON_TO)
+                    .set("name", &variant.name)
+                    .set(
+                        "value",
+                        &self.json_write(
+                            &kind,
+                            variant.ref_type_id,
+                            variant.repeated,
+                            false,
+                            &format!("value.{}", variant.name),
+                            store,
+                        ),
+                    )
+                    .render(),
+            );
+            from_json.push(
+                Template::new(
+                    "protocol/typescript/enum_json_from",
+                    templates::ENUM_VARIANT_FROM,
+                )
+                .set("name", &variant.name)
+                .set(
+                    "value",
+                    &self.json_read(
+                        &kind,
+                        variant.ref_type_id,
+                        variant.repeated,
+                        false,
+                        "value",
+                        &format!("'{}.{}'", enums.name, variant.name),
+                        store,
+                    ),
+                )
+                .render(),
+            );
+        }
+        Template::new("protocol/typescript/enum_json", templates::ENUM_JSON)
+            .set("name", &enums.name)
+            .set("to_json", &self.lines(&to_json, 1))
+            .set("from_json", &self.lines(&from_json, 3))
+            .render()
+    }
+
+    fn messages_json(&self, group: Option<&Group>, store: &mut Store) -> String {
+        let mut to_json = vec![];
+        let mut from_json = vec![];
+        let mut groups = vec![];
+        let parent = group.map(|group| group.id).unwrap_or(0);
+        for enums in store.enums.iter().filter(|e| e.parent == parent) {
+            let path = store.get_enum_path(enums.id).join(".");
+            to_json.push(
+                Template::new(
+                    "protocol/typescript/messages_json_to_enum",
+                    templates::MESSAGES_JSON_TO_ENUM,
+                )
+                .set("name", &enums.name)
+                .set("path", &path)
+                .render(),
+            );
+            from_json.push(
+                Template::new(
+                    "protocol/typescript/messages_json_from",
+                    templates::MESSAGES_JSON_FROM,
+                )
+                .set("name", &enums.name)
+                .set("path", &path)
+                .render(),
+            );
+        }
+        for strct in store.structs.iter().filter(|s| s.parent == parent) {
+            let path = store.get_struct_path(strct.id).join(".");
+            to_json.push(
+                Template::new(
+                    "protocol/typescript/messages_json_to_struct",
+                    templates::MESSAGES_JSON_TO_STRUCT,
+                )
+                .set("name", &strct.name)
+                .set("path", &path)
+                .render(),
+            );
+            from_json.push(
+                Template::new(
+                    "protocol/typescript/messages_json_from",
+                    templates::MESSAGES_JSON_FROM,
+                )
+                .set("name", &strct.name)
+                .set("path", &path)
+                .render(),
             );
         }
-        body = format!("{}\n{}];\n", body, self.spaces(level));
-        body
-    }
-
-    fn struct_validator(&self, strct: &Struct, level: u8) -> String {
-        let mut body = format!("{}public static getValidator(array: boolean): {{ validate(value: any): Error | undefined }} {{", self.spaces(level));
-        body = format!("{}\n{}if (array) {{", body, self.spaces(level + 1));
-        body = format!(
-            "{}\n{}return {{ validate(obj: any): Error | undefined {{",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}\n{}if (!(obj instanceof Array)) {{",
-            body,
-            self.spaces(level + 3)
-        );
-        body = format!(
-            "{}\n{}return new Error(`Expecting Array<{}>`);",
-            body,
-            self.spaces(level + 4),
-            strct.name
-        );
-        body = format!("{}\n{}}}", body, self.spaces(level + 3));
-        body = format!("{}\n{}try {{", body, self.spaces(level + 3));
-        body = format!(
-            "{}\n{}obj.forEach((o, index: number) => {{",
-            body,
-            self.spaces(level + 4)
-        );
-        body = format!(
-            "{}\n{}if (!(o instanceof {})) {{",
-            body,
-            self.spaces(level + 5),
-            strct.name
-        );
-        body = format!(
-            "{}\n{}throw new Error(`Expecting instance of {} on index #${{index}}`);",
-            body,
-            self.spaces(level + 6),
-            strct.name
-        );
-        body = format!("{}\n{}}}", body, self.spaces(level + 5));
-        body = format!("{}\n{}}});", body, self.spaces(level + 4));
-        body = format!("{}\n{}}} catch (err) {{", body, self.spaces(level + 3));
-        body = format!(
-            "{}\n{}return err instanceof Error ? err : new Error(`Unknown error: ${{err}}`);",
-            body,
-            self.spaces(level + 4)
-        );
-        body = format!("{}\n{}}}", body, self.spaces(level + 3));
-        body = format!("{}\n{}}}}};", body, self.spaces(level + 2));
-        body = format!("{}\n{}}} else {{", body, self.spaces(level + 1));
-        body = format!(
-            "{}\n{}return {{ validate(obj: any): Error | undefined {{",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}\n{}return obj instanceof {} ? undefined : new Error(`Expecting instance of {}`);",
-            body,
-            self.spaces(level + 3),
-            strct.name,
-            strct.name
-        );
-        body = format!("{}\n{}}}}};", body, self.spaces(level + 2));
-        body = format!("{}\n{}}}", body, self.spaces(level + 1));
-        body = format!("{}\n{}}}\n", body, self.spaces(level));
-        body
-    }
-
-    fn struct_from(&self, strct: &Struct, level: u8) -> String {
-        let mut body = format!(
-            "{}public static from(obj: any): {} | Error {{",
-            self.spaces(level),
-            strct.name
-        );
-        body = format!("{}\n{}if (obj instanceof Buffer || obj instanceof ArrayBuffer || obj instanceof Uint8Array) {{", body, self.spaces(level + 1));
-        body = format!(
-            "{}\n{}const inst = {}.defaults();",
-            body,
-            self.spaces(level + 2),
-            strct.name
-        );
-        body = format!(
-            "{}\n{}const err = inst.decode(obj);",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!(
-            "{}\n{}return err instanceof Error ? err : inst;",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}\n{}}} else {{", body, self.spaces(level + 1));
-        body = format!(
-            "{}\n{}const error: Error | undefined = Protocol.validate(obj, {}.scheme);",
-            body,
-            self.spaces(level + 2),
-            strct.name
-        );
-        body = format!(
-            "{}\n{}return error instanceof Error ? error : new {}({{",
-            body,
-            self.spaces(level + 2),
-            strct.name
-        );
+        for child in store.groups.iter().filter(|g| g.parent == parent) {
+            to_json.push(
+                Template::new(
+                    "protocol/typescript/messages_json_to_group",
+                    templates::MESSAGES_JSON_TO_GROUP,
+                )
+                .set("name", &child.name)
+                .render(),
+            );
+            groups.push(
+                Template::new(
+                    "protocol/typescript/messages_json_from_group",
+                    templates::MESSAGES_JSON_FROM_GROUP,
+                )
+                .set("name", &child.name)
+                .set("path", &store.get_path_by_parent(child.id).join("."))
+                .render(),
+            );
+        }
+        Template::new(
+            "protocol/typescript/messages_json",
+            templates::MESSAGES_JSON,
+        )
+        .set("to_json", &self.lines(&to_json, 1))
+        .set("from_json", &self.lines(&from_json, 3))
+        .set("groups", &self.lines(&groups, 2))
+        .render()
+    }
+
+    // Expression, which converts value into JSON
+    fn json_write(
+        &self,
+        kind: &str,
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        optional: bool,
+        value: &str,
+        store: &mut Store,
+    ) -> String {
+        let item = |item: &str| -> Option<String> {
+            match kind {
+                "u64" | "i64" => Some(format!("{}.toString()", item)),
+                _ => ref_type_id.map(|ref_type_id| {
+                    if store.get_struct(ref_type_id).is_some() {
+                        format!("{}.toJson()", item)
+                    } else {
+                        format!(
+                            "{}.toJson({})",
+                            store.get_enum_path(ref_type_id).join("."),
+                            item
+                        )
+                    }
+                }),
+            }
+        };
+        let output = if repeated {
+            item("item")
+                .map(|write| format!("{}.map((item) => {})", value, write))
+                .unwrap_or_else(|| value.to_owned())
+        } else {
+            item(value).unwrap_or_else(|| value.to_owned())
+        };
+        if optional {
+            format!("{} === undefined ? null : {}", value, output)
+        } else {
+            output
+        }
+    }
+
+    // Expression, which reads value from JSON or throws an error
+    #[allow(clippy::too_many_arguments)]
+    fn json_read(
+        &self,
+        kind: &str,
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        optional: bool,
+        json: &str,
+        path: &str,
+        store: &mut Store,
+    ) -> String {
+        let item = |json: &str, path: &str| -> String {
+            match kind {
+                "bool" => format!("ProtocolJson.boolean({}, {})", json, path),
+                "str" => format!("ProtocolJson.string({}, {})", json, path),
+                "u64" | "i64" => format!("ProtocolJson.bigint({}, {})", json, path),
+                "i8" | "i16" | "i32" | "u8" | "u16" | "u32" | "f32" | "f64" => {
+                    format!("ProtocolJson.number({}, {})", json, path)
+                }
+                _ => {
+                    let ref_type_id = ref_type_id.unwrap_or_else(|| {
+                        stop!("Invalid type definition for {}", path);
+                    });
+                    let entity = if store.get_struct(ref_type_id).is_some() {
+                        store.get_struct_path(ref_type_id)
+                    } else {
+                        store.get_enum_path(ref_type_id)
+                    };
+                    format!(
+                        "ProtocolJson.unwrap({}.fromJson({}), {})",
+                        entity.join("."),
+                        json,
+                        path
+                    )
+                }
+            }
+        };
+        let read = |json: &str, path: &str| -> String {
+            if repeated {
+                format!(
+                    "ProtocolJson.array({}, {}, (item: any, path: string) => {})",
+                    json,
+                    path,
+                    item("item", "path")
+                )
+            } else {
+                item(json, path)
+            }
+        };
+        if optional {
+            format!(
+                "ProtocolJson.optional({}, {}, (json: any, path: string) => {})",
+                json,
+                path,
+                read("json", "path")
+            )
+        } else {
+            read(json, path)
+        }
+    }
+
+    fn struct_assign(&self, name: &str, field: &Field, store: &mut Store) -> String {
+        let is_enum = field
+            .ref_type_id
+            .map(|ref_type_id| store.get_enum(ref_type_id).is_some())
+            .unwrap_or(false);
+        Template::new(name, templates::STRUCT_ASSIGN)
+            .set("name", &field.name)
+            .set("enum", if is_enum { "true" } else { "" })
+            .render()
+    }
+
+    fn struct_codec(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut to_value = vec![];
+        let mut from_value = vec![];
+        let mut assign = vec![];
         for field in &strct.fields {
-            body = format!(
-                "{}\n{}{}: obj.{},",
-                body,
-                self.spaces(level + 3),
-                field.name,
-                field.name
+            to_value.push(
+                Template::new("protocol/typescript/struct_codec_to", templates::PROPERTY)
+                    .set("name", &field.name)
+                    .set(
+                        "value",
+                        &self.codec_write(
+                            &field.kind,
+                            field.ref_type_id,
+                            field.repeated,
+                            field.optional,
+                            &format!("this.{}", field.name),
+                            store,
+                        ),
+                    )
+                    .render(),
             );
+            from_value.push(
+                Template::new("protocol/typescript/struct_codec_from", templates::PROPERTY)
+                    .set("name", &field.name)
+                    .set(
+                        "value",
+                        &self.codec_read(
+                            &field.kind,
+                            field.ref_type_id,
+                            field.repeated,
+                            field.optional,
+                            &format!("ProtocolCodec.field(value, '{}')", field.name),
+                            &format!("'{}.{}'", strct.name, field.name),
+                            store,
+                        ),
+                    )
+                    .render(),
+            );
+            assign.push(self.struct_assign(
+                "protocol/typescript/struct_codec_assign",
+                field,
+                store,
+            ));
         }
-        body = format!("{}\n{}}});", body, self.spaces(level + 2));
-        body = format!("{}\n{}}}", body, self.spaces(level + 1));
-        body = format!("{}\n{}}}\n", body, self.spaces(level));
-        body
-    }
-
-    fn enums(&self, enums: &Enum, store: &mut Store, level: u8) -> String {
-        let mut body = format!(
-            "{}export interface I{} {{\n",
-            self.spaces(level),
-            enums.name
-        );
+        Template::new("protocol/typescript/struct_codec", templates::STRUCT_CODEC)
+            .set("name", &strct.name)
+            .set("to_value", &self.lines(&to_value, 2))
+            .set("from_value", &self.lines(&from_value, 3))
+            .set("assign", &self.lines(&assign, 2))
+            .render()
+    }
+
+    fn enum_codec(&self, enums: &Enum, store: &mut Store) -> String {
+        let mut to_value = vec![];
+        let mut from_value = vec![];
+        for variant in &enums.variants {
+            let kind = variant
+                .types
+                .clone()
+                .and_then(PrimitiveTypes::get_entity_as_string)
+                .unwrap_or_default();
+            to_value.push(
+                Template::new(
+                    "protocol/typescript/enum_codec_to",
+                    templates::ENUM_CODEC_TO,
+                )
+                .set("name", &variant.name)
+                .set(
+                    "value",
+                    &self.codec_write(
+                        &kind,
+                        variant.ref_type_id,
+                        variant.repeated,
+                        false,
+                        &format!("value.{}", variant.name),
+                        store,
+                    ),
+                )
+                .render(),
+            );
+            from_value.push(
+                Template::new(
+                    "protocol/typescript/enum_codec_from",
+                    templates::ENUM_VARIANT_FROM,
+                )
+                .set("name", &variant.name)
+                .set(
+                    "value",
+                    &self.codec_read(
+                        &kind,
+                        variant.ref_type_id,
+                        variant.repeated,
+                        false,
+                        "inner",
+                        &format!("'{}.{}'", enums.name, variant.name),
+                        store,
+                    ),
+                )
+                .render(),
+            );
+        }
+        Template::new("protocol/typescript/enum_codec", templates::ENUM_CODEC)
+            .set("name", &enums.name)
+            .set("to_value", &self.lines(&to_value, 1))
+            .set("from_value", &self.lines(&from_value, 3))
+            .render()
+    }
+
+    fn struct_compact(&self, strct: &Struct, store: &mut Store) -> String {
+        let mut write = vec![];
+        let mut read = vec![];
+        let mut assign = vec![];
+        for field in &strct.fields {
+            let kind = self.compact_type(&field.kind, field.ref_type_id, field.repeated, store);
+            write.push(
+                Template::new(
+                    "protocol/typescript/struct_compact_write",
+                    templates::STRUCT_COMPACT_WRITE,
+                )
+                .set("id", &field.id.to_string())
+                .set("kind", &kind)
+                .set("name", &field.name)
+                .render(),
+            );
+            read.push(
+                Template::new(
+                    "protocol/typescript/struct_compact_read",
+                    templates::STRUCT_COMPACT_READ,
+                )
+                .set("name", &field.name)
+                .set("read", if field.optional { "optional" } else { "field" })
+                .set("id", &field.id.to_string())
+                .set("kind", &kind)
+                .set("struct", &strct.name)
+                .render(),
+            );
+            assign.push(self.struct_assign(
+                "protocol/typescript/struct_compact_assign",
+                field,
+                store,
+            ));
+        }
+        Template::new(
+            "protocol/typescript/struct_compact",
+            templates::STRUCT_COMPACT,
+        )
+        .set("name", &strct.name)
+        .set("write", &self.lines(&write, 1))
+        .set("read", &self.lines(&read, 3))
+        .set("assign", &self.lines(&assign, 2))
+        .render()
+    }
+
+    fn enum_compact(&self, enums: &Enum, store: &mut Store) -> String {
+        let mut write = vec![];
+        let mut read = vec![];
+        for (index, variant) in enums.variants.iter().enumerate() {
+            let kind = self.compact_type(
+                &variant
+                    .types
+                    .clone()
+                    .and_then(PrimitiveTypes::get_entity_as_string)
+                    .unwrap_or_default(),
+                variant.ref_type_id,
+                variant.repeated,
+                store,
+            );
+            write.push(
+                Template::new(
+                    "protocol/typescript/enum_compact_write",
+                    templates::ENUM_COMPACT_WRITE,
+                )
+                .set("name", &variant.name)
+                .set("index", &index.to_string())
+                .set("kind", &kind)
+                .render(),
+            );
+            read.push(
+                Template::new(
+                    "protocol/typescript/enum_compact_read",
+                    templates::ENUM_COMPACT_READ,
+                )
+                .set("index", &index.to_string())
+                .set("name", &variant.name)
+                .set("kind", &kind)
+                .set("enum", &enums.name)
+                .render(),
+            );
+        }
+        Template::new("protocol/typescript/enum_compact", templates::ENUM_COMPACT)
+            .set("name", &enums.name)
+            .set("write", &self.lines(&write, 1))
+            .set("read", &self.lines(&read, 3))
+            .render()
+    }
+    // Expression, which gives ProtocolCompact.Type of value
+    fn compact_type(
+        &self,
+        kind: &str,
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        store: &mut Store,
+    ) -> String {
+        let item = match kind {
+            "bool" | "str" | "f32" | "f64" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16"
+            | "u32" | "u64" => format!("ProtocolCompact.{}", kind),
+            _ => {
+                let ref_type_id = ref_type_id.unwrap_or_else(|| {
+                    stop!("Invalid type definition: {}", kind);
+                });
+                if store.get_struct(ref_type_id).is_some() {
+                    let path = store.get_struct_path(ref_type_id).join(".");
+                    format!("ProtocolCompact.entity(() => {}.defaults())", path)
+                } else {
+                    let path = store.get_enum_path(ref_type_id).join(".");
+                    format!("ProtocolCompact.enumeration(() => new {}())", path)
+                }
+            }
+        };
+        if repeated {
+            format!("ProtocolCompact.array({})", item)
+        } else {
+            item
+        }
+    }
+
+    // Expression, which converts value into ProtocolCodec.Value
+    fn codec_write(
+        &self,
+        kind: &str,
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        optional: bool,
+        value: &str,
+        store: &mut Store,
+    ) -> String {
+        let item = |item: &str| -> String {
+            match kind {
+                "bool" => format!("ProtocolCodec.bool({})", item),
+                "str" => format!("ProtocolCodec.str({})", item),
+                "f32" => format!("ProtocolCodec.f32({})", item),
+                "f64" => format!("ProtocolCodec.f64({})", item),
+                "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => {
+                    format!("ProtocolCodec.int({})", item)
+                }
+                _ => {
+                    let ref_type_id = ref_type_id.unwrap_or_else(|| {
+                        stop!("Invalid type definition for {}", value);
+                    });
+                    if store.get_struct(ref_type_id).is_some() {
+                        format!("{}.toValue()", item)
+                    } else {
+                        format!(
+                            "{}.toValue({})",
+                            store.get_enum_path(ref_type_id).join("."),
+                            item
+                        )
+                    }
+                }
+            }
+        };
+        let output = if repeated {
+            format!(
+                "ProtocolCodec.array({}.map((item) => {}))",
+                value,
+                item("item")
+            )
+        } else {
+            item(value)
+        };
+        if optional {
+            format!("{} === undefined ? ProtocolCodec.nil() : {}", value, output)
+        } else {
+            output
+        }
+    }
+
+    // Expression, which reads value from ProtocolCodec.Value or throws an error
+    #[allow(clippy::too_many_arguments)]
+    fn codec_read(
+        &self,
+        kind: &str,
+        ref_type_id: Option<usize>,
+        repeated: bool,
+        optional: bool,
+        value: &str,
+        path: &str,
+        store: &mut Store,
+    ) -> String {
+        let item = |value: &str, path: &str| -> String {
+            match kind {
+                "bool" => format!("ProtocolCodec.boolean({}, {})", value, path),
+                "str" => format!("ProtocolCodec.string({}, {})", value, path),
+                "u64" | "i64" => format!("ProtocolCodec.bigint({}, {})", value, path),
+                "i8" | "i16" | "i32" | "u8" | "u16" | "u32" | "f32" | "f64" => {
+                    format!("ProtocolCodec.number({}, {})", value, path)
+                }
+                _ => {
+                    let ref_type_id = ref_type_id.unwrap_or_else(|| {
+                        stop!("Invalid type definition for {}", path);
+                    });
+                    let entity = if store.get_struct(ref_type_id).is_some() {
+                        store.get_struct_path(ref_type_id)
+                    } else {
+                        store.get_enum_path(ref_type_id)
+                    };
+                    format!(
+                        "ProtocolCodec.unwrap({}.fromValue({}), {})",
+                        entity.join("."),
+                        value,
+                        path
+                    )
+                }
+            }
+        };
+        let read = |value: &str, path: &str| -> String {
+            if repeated {
+                format!(
+                    "ProtocolCodec.items({}, {}, (item: ProtocolCodec.Value, path: string) => {})",
+                    value,
+                    path,
+                    item("item", "path")
+                )
+            } else {
+                item(value, path)
+            }
+        };
+        if optional {
+            format!(
+                "ProtocolCodec.optional({}, {}, (value: ProtocolCodec.Value, path: string) => {})",
+                value,
+                path,
+                read("value", "path")
+            )
+        } else {
+            read(value, path)
+        }
+    }
+
+    fn enums(&self, enums: &Enum, store: &mut Store) -> String {
+        let mut fields = vec![];
         for variant in &enums.variants {
             let variant_type = if let Some(prim_type_ref) = variant.types.clone() {
                 self.etype_ts(prim_type_ref.clone(), variant.repeated)
@@ -605,134 +1390,42 @@ impl TypescriptRender {
                     variant.name
                 );
             };
-            body = format!(
-                "{}{}{}?: {};\n",
-                body,
-                self.spaces(level + 1),
-                variant.name,
-                variant_type
+            fields.push(
+                Template::new(
+                    "protocol/typescript/enum_interface_field",
+                    templates::INTERFACE_OPTION,
+                )
+                .set("name", &variant.name)
+                .set("type", &variant_type)
+                .render(),
             );
         }
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body = format!("{}\n", body);
-        body = format!(
-            "{}{}export class {} extends Protocol.Primitives.Enum<I{}> {{\n",
-            body,
-            self.spaces(level),
-            enums.name,
-            enums.name
-        );
-        body = format!(
-            "{}{}public static from(obj: any): I{} | Error {{\n",
-            body,
-            self.spaces(level + 1),
-            enums.name
-        );
-        body = format!(
-            "{}{}const inst = new {}();\n",
-            body,
-            self.spaces(level + 2),
-            enums.name
-        );
-        body = format!(
-            "{}{}let err: Error | undefined;\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}if (obj instanceof Buffer || obj instanceof ArrayBuffer || obj instanceof Uint8Array) {{\n", body, self.spaces(level + 2));
-        body = format!(
-            "{}{}err = inst.decode(obj);\n",
-            body,
-            self.spaces(level + 3)
-        );
-        body = format!("{}{}}} else {{\n", body, self.spaces(level + 2));
-        body = format!("{}{}err = inst.set(obj);\n", body, self.spaces(level + 3));
-        body = format!("{}{}}}\n", body, self.spaces(level + 2));
-        body = format!(
-            "{}{}return err instanceof Error ? err : inst.get();\n",
-            body,
-            self.spaces(level + 2)
-        );
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}public static getId(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            enums.id
-        );
-        body = format!(
-            "{}{}public from(obj: any): I{} | Error {{\n",
-            body,
-            self.spaces(level + 1),
-            enums.name
-        );
-        body = format!(
-            "{}{}return {}.from(obj);\n",
-            body,
-            self.spaces(level + 2),
-            enums.name
-        );
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}public signature(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            self.signature
-        );
-        body = format!(
-            "{}{}public getId(): number {{ return {}; }}\n",
-            body,
-            self.spaces(level + 1),
-            enums.id
-        );
-        body = format!(
-            "{}{}public getAllowed(): string[] {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!(
-            "{}{}return {};\n",
-            body,
-            self.spaces(level + 2),
-            self.enum_declaration(enums, store, level + 3)
-        );
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}public getOptionValue(id: number): ISigned<any> {{\n",
-            body,
-            self.spaces(level + 1)
-        );
-        body = format!("{}{}\n", body, self.enum_getter(enums, store, level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}public get(): I{} {{\n",
-            body,
-            self.spaces(level + 1),
-            enums.name
-        );
-        body = format!(
-            "{}{}const target: I{} = {{}};\n",
-            body,
-            self.spaces(level + 2),
-            enums.name
-        );
-        body = format!(
-            "{}{}\n",
-            body,
-            self.get_enum_decode(enums, store, level + 2)
-        );
-        body = format!("{}{}return target;\n", body, self.spaces(level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!(
-            "{}{}public set(src: I{}): Error | undefined{{\n",
-            body,
-            self.spaces(level + 1),
-            enums.name
-        );
-        body = format!("{}{}\n", body, self.enum_setter(enums, store, level + 2));
-        body = format!("{}{}}}\n", body, self.spaces(level + 1));
-        body = format!("{}{}}}\n", body, self.spaces(level));
-        body
+        let codec = match store.get_codec() {
+            Codec::Binary => String::new(),
+            Codec::Compact => indent(1, &self.enum_compact(enums, &mut store.clone())),
+            _ => indent(1, &self.enum_codec(enums, &mut store.clone())),
+        };
+        Template::new("protocol/typescript/enum", templates::ENUM)
+            .set(
+                "interface",
+                &Template::new("protocol/typescript/enum_interface", templates::INTERFACE)
+                    .set("name", &enums.name)
+                    .set("fields", &self.lines(&fields, 1))
+                    .render(),
+            )
+            .set("name", &enums.name)
+            .set("id", &enums.id.to_string())
+            .set("signature", &self.signature.to_string())
+            .set(
+                "json",
+                &indent(1, &self.enum_json(enums, &mut store.clone())),
+            )
+            .set("codec", &codec)
+            .set("allowed", &self.lines(&self.enum_allowed(enums, store), 3))
+            .set("options", &self.lines(&self.enum_options(enums, store), 3))
+            .set("getters", &self.lines(&self.enum_getters(enums, store), 3))
+            .set("setters", &self.lines(&self.enum_setters(enums, store), 2))
+            .render()
     }
 
     fn etype(&self, etype: PrimitiveTypes::ETypes, repeated: bool) -> String {
@@ -1014,18 +1707,20 @@ impl TypescriptRender {
         .to_string()
     }
 
-    fn entity_default(&self, entity_id: usize, store: &mut Store, level: u8) -> String {
+    fn entity_default(&self, entity_id: usize, store: &mut Store) -> String {
         if let Some(strct) = store.get_struct(entity_id) {
-            let mut body = format!("new {}({{", store.get_struct_path(entity_id).join("."));
-            for field in &strct.fields {
-                body = format!(
-                    "{}\n{}{},",
-                    body,
-                    self.spaces(level + 1),
-                    self.field_default(field, &mut store.clone(), level)
-                );
-            }
-            format!("{}\n{}}})", body, self.spaces(level))
+            let fields = strct
+                .fields
+                .iter()
+                .map(|field| self.field_default(field, &mut store.clone()))
+                .collect::<Vec<String>>();
+            Template::new(
+                "protocol/typescript/struct_default",
+                templates::STRUCT_DEFAULT,
+            )
+            .set("path", &store.get_struct_path(entity_id).join("."))
+            .set("fields", &self.lines(&fields, 1))
+            .render()
         } else if store.get_enum(entity_id).is_some() {
             "{}".to_string()
         } else {
@@ -1033,273 +1728,142 @@ impl TypescriptRender {
         }
     }
 
-    fn field_default(&self, field: &Field, store: &mut Store, level: u8) -> String {
-        let mut body = format!("{}: ", field.name);
-        if field.optional {
-            body = format!("{}undefined", body);
+    fn field_default(&self, field: &Field, store: &mut Store) -> String {
+        let value = if field.optional {
+            String::from("undefined")
         } else if field.repeated {
-            body = format!("{}[]", body);
+            String::from("[]")
         } else if let Some(default_value) = self.type_default_value(&field.kind) {
-            body = format!("{}{}", body, default_value);
+            default_value.to_owned()
         } else if let Some(struct_id) = field.ref_type_id {
-            body = format!(
-                "{}{}",
-                body,
-                self.entity_default(struct_id, store, level + 1)
-            );
-        }
-        body
+            self.entity_default(struct_id, store)
+        } else {
+            String::new()
+        };
+        Template::new("protocol/typescript/field_default", templates::PROPERTY)
+            .set("name", &field.name)
+            .set("value", &value)
+            .render()
     }
 
-    fn get_field_map_def(&self, field: &Field, store: &mut Store, level: u8) -> String {
-        let mut body: String = String::from("");
+    fn get_field_map_def(&self, field: &Field, store: &mut Store) -> String {
+        let optional = if field.optional { "true" } else { "false" };
         if let Some(entity_id) = field.ref_type_id {
             if let Some(strct) = store.get_struct(entity_id) {
-                body = format!(
-                    "{}\n{}{{ prop: '{}', types: {}.getValidator({}), optional: {} }},",
-                    body,
-                    self.spaces(level),
-                    field.name,
-                    store.get_struct_path(strct.id).join("."),
-                    if field.repeated { "true" } else { "false" },
-                    if field.optional { "true" } else { "false" }
-                );
+                Template::new(
+                    "protocol/typescript/field_scheme_struct",
+                    templates::FIELD_SCHEME_STRUCT,
+                )
+                .set("name", &field.name)
+                .set("type", &store.get_struct_path(strct.id).join("."))
+                .set("repeated", if field.repeated { "true" } else { "false" })
+                .set("optional", optional)
+                .render()
             } else if let Some(enums) = store.get_enum(entity_id) {
-                body = format!(
-                    "{}\n{}{{ prop: '{}', optional: {}, options: [",
-                    body,
-                    self.spaces(level),
-                    field.name,
-                    if field.optional { "true" } else { "false" }
-                );
+                let mut options = vec![];
                 for variant in &enums.variants {
                     if let Some(struct_id) = variant.ref_type_id {
                         if let Some(strct) = store.get_struct(struct_id) {
-                            body = format!("{}\n{}{{ prop: '{}', types: {}.getValidator({}), optional: false }},", body, self.spaces(level + 1), variant.name, strct.name, if variant.repeated { "true" } else { "false" });
+                            options.push(
+                                Template::new(
+                                    "protocol/typescript/field_scheme_option_struct",
+                                    templates::FIELD_SCHEME_OPTION_STRUCT,
+                                )
+                                .set("name", &variant.name)
+                                .set("type", &strct.name)
+                                .set("repeated", if variant.repeated { "true" } else { "false" })
+                                .render(),
+                            );
                         } else {
                             stop!("Nested enums aren't supported.");
                         }
                     } else if let Some(etype) = variant.types.clone() {
-                        body = format!("{}\n{}{{ prop: '{}', types: Protocol.Primitives.{}, optional: false, }},", body, self.spaces(level + 1), variant.name, self.etype(etype, variant.repeated));
+                        options.push(
+                            Template::new(
+                                "protocol/typescript/field_scheme_option_primitive",
+                                templates::FIELD_SCHEME_OPTION_PRIMITIVE,
+                            )
+                            .set("name", &variant.name)
+                            .set("type", &self.etype(etype, variant.repeated))
+                            .render(),
+                        );
                     } else {
                         stop!("Incorrect option definition for enum {}", enums.name);
                     }
                 }
-                body = format!("{}\n{}] }},", body, self.spaces(level));
+                Template::new(
+                    "protocol/typescript/field_scheme_enum",
+                    templates::FIELD_SCHEME_ENUM,
+                )
+                .set("name", &field.name)
+                .set("optional", optional)
+                .set("options", &self.lines(&options, 1))
+                .render()
+            } else {
+                String::new()
             }
         } else {
-            body = format!(
-                "{}\n{}{{ prop: '{}', types: Protocol.Primitives.{}, optional: {}, }},",
-                body,
-                self.spaces(level),
-                field.name,
-                self.get_primitive_ref(field),
-                if field.optional { "true" } else { "false" }
-            );
+            Template::new(
+                "protocol/typescript/field_scheme_primitive",
+                templates::FIELD_SCHEME_PRIMITIVE,
+            )
+            .set("name", &field.name)
+            .set("type", &self.get_primitive_ref(field))
+            .set("optional", optional)
+            .render()
         }
-        body
     }
 
-    fn get_field_decode_wrap(&self, field: &Field, store: &mut Store, level: u8) -> String {
+    fn get_field_decode_wrap(&self, field: &Field, store: &mut Store) -> String {
         if field.optional {
-            let mut body = format!(
-                "{}const {}Buf: ArrayBufferLike | undefined = storage.get({});",
-                self.spaces(level),
-                field.name,
-                field.id
-            );
-            body = format!(
-                "{}\n{}if ({}Buf === undefined) {{",
-                body,
-                self.spaces(level),
-                field.name
-            );
-            body = format!(
-                "{}\n{}return new Error(`Fail to get property {} (id={})`);",
-                body,
-                self.spaces(level + 1),
-                field.name,
-                field.id
-            );
-            body = format!("{}\n{}}}", body, self.spaces(level));
-            body = format!(
-                "{}\n{}if ({}Buf.byteLength === 0) {{",
-                body,
-                self.spaces(level),
-                field.name
-            );
-            body = format!(
-                "{}\n{}this.{} = undefined;",
-                body,
-                self.spaces(level + 1),
-                field.name
-            );
-            body = format!("{}\n{}}} else {{", body, self.spaces(level));
-            body = format!(
-                "{}\n{}",
-                body,
-                self.get_field_decode(field, store, level + 1)
-            );
-            body = format!("{}\n{}}}", body, self.spaces(level));
-            body
+            Template::new(
+                "protocol/typescript/field_decode_optional",
+                templates::FIELD_DECODE_OPTIONAL,
+            )
+            .set("name", &field.name)
+            .set("id", &field.id.to_string())
+            .set("decode", &indent(1, &self.get_field_decode(field, store)))
+            .render()
         } else {
-            self.get_field_decode(field, store, level)
+            self.get_field_decode(field, store)
         }
     }
 
-    fn get_field_decode(&self, field: &Field, store: &mut Store, level: u8) -> String {
-        let mut body: String;
+    fn get_field_decode(&self, field: &Field, store: &mut Store) -> String {
         if let Some(entity_id) = field.ref_type_id {
             if let Some(strct) = store.get_struct(entity_id) {
                 if field.repeated {
-                    body = format!(
-                        "{}const arr{}Inst: {} = {}.defaults();",
-                        self.spaces(level),
-                        field.name,
-                        store.get_struct_path(strct.id).join("."),
-                        store.get_struct_path(strct.id).join(".")
-                    );
-                    body = format!("{}\n{}const arr{}: Array<any> | Error = this.getValue<{}[]>(storage, {}, arr{}Inst.decodeSelfArray.bind(arr{}Inst));", body, self.spaces(level), field.name, strct.name, field.id, field.name, field.name);
-                    body = format!(
-                        "{}\n{}if (arr{} instanceof Error) {{",
-                        body,
-                        self.spaces(level),
-                        field.name
-                    );
-                    body = format!(
-                        "{}\n{}return arr{};",
-                        body,
-                        self.spaces(level + 1),
-                        field.name
-                    );
-                    body = format!("{}\n{}}} else {{", body, self.spaces(level));
-                    body = format!(
-                        "{}\n{}this.{} = arr{} as {}[];",
-                        body,
-                        self.spaces(level + 1),
-                        field.name,
-                        field.name,
-                        store.get_struct_path(strct.id).join(".")
-                    );
-                    body = format!("{}\n{}}}", body, self.spaces(level));
+                    Template::new(
+                        "protocol/typescript/field_decode_structs",
+                        templates::FIELD_DECODE_STRUCTS,
+                    )
+                    .set("name", &field.name)
+                    .set("path", &store.get_struct_path(strct.id).join("."))
+                    .set("type", &strct.name)
+                    .set("id", &field.id.to_string())
+                    .render()
                 } else {
-                    body = format!(
-                        "{}const {}: {} = {};",
-                        self.spaces(level),
-                        field.name,
-                        field.get_full_name().join("."),
-                        self.entity_default(entity_id, &mut store.clone(), level)
-                    );
-                    body = format!(
-                        "{}\n{}const {}Buf: ArrayBufferLike | undefined = storage.get({});",
-                        body,
-                        self.spaces(level),
-                        field.name,
-                        field.id
-                    );
-                    body = format!(
-                        "{}\n{}if ({}Buf === undefined) {{",
-                        body,
-                        self.spaces(level),
-                        field.name
-                    );
-                    body = format!(
-                        "{}\n{}return new Error(`Fail to find field \"{}\" (id={}).`);",
-                        body,
-                        self.spaces(level + 1),
-                        field.name,
-                        field.id,
-                    );
-                    body = format!("{}\n{}}}", body, self.spaces(level));
-                    body = format!(
-                        "{}\n{}const {}Err: Error | {} = {}.decode({}Buf);",
-                        body,
-                        self.spaces(level),
-                        field.name,
-                        field.get_full_name().join("."),
-                        field.name,
-                        field.name
-                    );
-                    body = format!(
-                        "{}\n{}if ({}Err instanceof Error) {{",
-                        body,
-                        self.spaces(level),
-                        field.name
-                    );
-                    body = format!(
-                        "{}\n{}return {}Err;",
-                        body,
-                        self.spaces(level + 1),
-                        field.name
-                    );
-                    body = format!("{}\n{}}} else {{", body, self.spaces(level));
-                    body = format!(
-                        "{}\n{}this.{} = {};",
-                        body,
-                        self.spaces(level + 1),
-                        field.name,
-                        field.name
-                    );
-                    body = format!("{}\n{}}}", body, self.spaces(level));
+                    Template::new(
+                        "protocol/typescript/field_decode_struct",
+                        templates::FIELD_DECODE_STRUCT,
+                    )
+                    .set("name", &field.name)
+                    .set("type", &field.get_full_name().join("."))
+                    .set(
+                        "default",
+                        &self.entity_default(entity_id, &mut store.clone()),
+                    )
+                    .set("id", &field.id.to_string())
+                    .render()
                 }
-            } else if let Some(_enums) = store.get_enum(entity_id) {
-                body = format!("{}this.{} = {{}};", self.spaces(level), field.name);
-                body = format!(
-                    "{}\n{}const {}Buf: ArrayBufferLike | undefined = storage.get({});",
-                    body,
-                    self.spaces(level),
-                    field.name,
-                    field.id
-                );
-                body = format!(
-                    "{}\n{}if ({}Buf === undefined) {{",
-                    body,
-                    self.spaces(level),
-                    field.name
-                );
-                body = format!(
-                    "{}\n{}return new Error(`Fail to get property \"{}\"`);",
-                    body,
-                    self.spaces(level + 1),
-                    field.name
-                );
-                body = format!("{}\n{}}}", body, self.spaces(level));
-                body = format!(
-                    "{}\n{}if ({}Buf.byteLength > 0) {{",
-                    body,
-                    self.spaces(level),
-                    field.name
-                );
-                body = format!(
-                    "{}\n{}const {}Err: Error | undefined = this._{}.decode({}Buf);",
-                    body,
-                    self.spaces(level + 1),
-                    field.name,
-                    field.name,
-                    field.name
-                );
-                body = format!(
-                    "{}\n{}if ({}Err instanceof Error) {{",
-                    body,
-                    self.spaces(level + 1),
-                    field.name
-                );
-                body = format!(
-                    "{}\n{}return {}Err;",
-                    body,
-                    self.spaces(level + 2),
-                    field.name
-                );
-                body = format!("{}\n{}}} else {{", body, self.spaces(level + 1));
-                body = format!(
-                    "{}\n{}this.{} = this._{}.get();",
-                    body,
-                    self.spaces(level + 2),
-                    field.name,
-                    field.name
-                );
-                body = format!("{}\n{}}}", body, self.spaces(level + 1));
-                body = format!("{}\n{}}}", body, self.spaces(level));
+            } else if store.get_enum(entity_id).is_some() {
+                Template::new(
+                    "protocol/typescript/field_decode_enum",
+                    templates::FIELD_DECODE_ENUM,
+                )
+                .set("name", &field.name)
+                .set("id", &field.id.to_string())
+                .render()
             } else {
                 stop!(
                     "Fail to find a type by ref {} for field {}",
@@ -1309,47 +1873,56 @@ impl TypescriptRender {
             }
         } else {
             let mut type_str = self.get_type_ref(field, &mut store.clone());
-            let primitive = self.get_primitive_ref(field);
             if field.repeated {
                 type_str = format!("Array<{}>", type_str);
             }
-            body = format!("{}const {}: {} | Error = this.getValue<{}>(storage, {}, Protocol.Primitives.{}.decode);", self.spaces(level), field.name, type_str, type_str, field.id, primitive);
-            body = format!(
-                "{}\n{}if ({} instanceof Error) {{",
-                body,
-                self.spaces(level),
-                field.name
-            );
-            body = format!("{}\n{}return {};", body, self.spaces(level + 1), field.name);
-            body = format!("{}\n{}}} else {{", body, self.spaces(level));
-            body = format!(
-                "{}\n{}this.{} = {};",
-                body,
-                self.spaces(level + 1),
-                field.name,
-                field.name
-            );
-            body = format!("{}\n{}}}", body, self.spaces(level));
+            Template::new(
+                "protocol/typescript/field_decode_primitive",
+                templates::FIELD_DECODE_PRIMITIVE,
+            )
+            .set("name", &field.name)
+            .set("type", &type_str)
+            .set("id", &field.id.to_string())
+            .set("primitive", &self.get_primitive_ref(field))
+            .render()
         }
-        body
     }
 
     fn get_field_encode(&self, field: &Field, store: &mut Store) -> String {
-        let mut body: String;
+        let optional = if field.optional { "true" } else { "" };
         if let Some(entity_id) = field.ref_type_id {
-            let optional = if field.optional {
-                format!("if (this.{} === undefined) {{ return this.getBuffer({}, Protocol.ESize.u8, 0, new Uint8Array()); }}", field.name, field.id)
-            } else {
-                format!("")
-            };
             if let Some(strct) = store.get_struct(entity_id) {
                 if field.repeated {
-                    body = format!("() => {{{} const self: {} = {}.defaults(); return this.getBufferFromBuf<{}[]>({}, Protocol.ESize.u64, self.encodeSelfArray.bind(self), this.{}); }}", optional, strct.name, strct.name, strct.name, field.id, field.name);
+                    Template::new(
+                        "protocol/typescript/field_encode_structs",
+                        templates::FIELD_ENCODE_STRUCTS,
+                    )
+                    .set("name", &field.name)
+                    .set("type", &strct.name)
+                    .set("id", &field.id.to_string())
+                    .set("optional", optional)
+                    .render()
                 } else {
-                    body = format!("() => {{{} const buffer = this.{}.encode(); return this.getBuffer({}, Protocol.ESize.u64, BigInt(buffer.byteLength), buffer); }}", optional, field.name, field.id);
+                    Template::new(
+                        "protocol/typescript/field_encode_entity",
+                        templates::FIELD_ENCODE_ENTITY,
+                    )
+                    .set("name", &field.name)
+                    .set("target", &field.name)
+                    .set("id", &field.id.to_string())
+                    .set("optional", optional)
+                    .render()
                 }
             } else if store.get_enum(entity_id).is_some() {
-                body = format!("() => {{{} const buffer = this._{}.encode(); return this.getBuffer({}, Protocol.ESize.u64, BigInt(buffer.byteLength), buffer); }}", optional, field.name, field.id);
+                Template::new(
+                    "protocol/typescript/field_encode_entity",
+                    templates::FIELD_ENCODE_ENTITY,
+                )
+                .set("name", &field.name)
+                .set("target", &format!("_{}", field.name))
+                .set("id", &field.id.to_string())
+                .set("optional", optional)
+                .render()
             } else {
                 stop!(
                     "Fail to find a type by ref {} for field {}",
@@ -1358,25 +1931,38 @@ impl TypescriptRender {
                 );
             }
         } else {
-            let type_str = self.get_type_ref(field, &mut store.clone());
-            let size_ref = self.get_size_ref(field);
-            let primitive = self.get_primitive_ref(field);
-            if field.repeated {
-                body = format!("this.getBufferFromBuf<Array<{}>>({}, Protocol.ESize.u64, Protocol.Primitives.{}.encode, this.{})", type_str, field.id, primitive, field.name);
-            } else {
-                body = if field.kind == "str" {
-                    format!("this.getBufferFromBuf<string>({}, {}, Protocol.Primitives.{}.encode, this.{})", field.id, size_ref, primitive, field.name)
-                } else {
-                    format!("this.getBuffer({}, {}, Protocol.Primitives.{}.getSize(), Protocol.Primitives.{}.encode(this.{}))", field.id, size_ref, primitive, primitive, field.name)
-                }
-            }
-            if field.optional {
-                body = format!("() => this.{} === undefined ? this.getBuffer({}, Protocol.ESize.u8, 0, new Uint8Array()) : {}", field.name, field.id, body);
+            let encode = if field.repeated {
+                Template::new(
+                    "protocol/typescript/field_encode_array",
+                    templates::FIELD_ENCODE_ARRAY,
+                )
+                .set("type", &self.get_type_ref(field, &mut store.clone()))
+            } else if field.kind == "str" {
+                Template::new(
+                    "protocol/typescript/field_encode_str",
+                    templates::FIELD_ENCODE_STR,
+                )
             } else {
-                body = format!("() => {}", body);
+                Template::new(
+                    "protocol/typescript/field_encode_primitive",
+                    templates::FIELD_ENCODE_PRIMITIVE,
+                )
             }
+            .set("name", &field.name)
+            .set("id", &field.id.to_string())
+            .set("size", &self.get_size_ref(field))
+            .set("primitive", &self.get_primitive_ref(field))
+            .render();
+            Template::new(
+                "protocol/typescript/field_encode_value",
+                templates::FIELD_ENCODE_VALUE,
+            )
+            .set("name", &field.name)
+            .set("id", &field.id.to_string())
+            .set("optional", optional)
+            .set("encode", &encode)
+            .render()
         }
-        body
     }
 
     fn type_default_value(&self, type_ref: &str) -> Option<&str> {
@@ -1500,91 +2086,68 @@ impl TypescriptRender {
         }
     }
 
-    fn get_messages_list(&self, group: Option<&Group>, store: &mut Store, level: u8) -> String {
-        let mut body = String::from("");
+    fn get_messages_list(&self, group: Option<&Group>, store: &mut Store) -> String {
+        let mut messages = vec![];
+        let message = |name: &str, template: &str, content: &str| -> String {
+            Template::new(&format!("protocol/typescript/{}", template), content)
+                .set("name", name)
+                .render()
+        };
         if let Some(group) = group {
-            body = format!(
-                "{}{}export interface IAvailableMessages {{\n",
-                body,
-                self.spaces(level)
-            );
             for enum_id in &group.enums {
                 if let Some(enums) = store.get_enum(*enum_id) {
-                    body = format!(
-                        "{}{}{}?: I{},\n",
-                        body,
-                        self.spaces(level + 1),
-                        enums.name,
-                        enums.name
-                    );
+                    messages.push(message(
+                        &enums.name,
+                        "message_enum",
+                        templates::MESSAGE_ENUM,
+                    ));
                 }
             }
             for struct_id in &group.structs {
                 if let Some(strct) = store.get_struct(*struct_id) {
-                    body = format!(
-                        "{}{}{}?: {},\n",
-                        body,
-                        self.spaces(level + 1),
-                        strct.name,
-                        strct.name
-                    );
+                    messages.push(message(
+                        &strct.name,
+                        "message_struct",
+                        templates::MESSAGE_STRUCT,
+                    ));
                 }
             }
-            let childs = store.get_child_groups(group.id);
-            for child in childs {
+            for child in store.get_child_groups(group.id) {
                 if child.parent == group.id {
-                    body = format!(
-                        "{}{}{}?: {}.IAvailableMessages,\n",
-                        body,
-                        self.spaces(level + 1),
-                        child.name,
-                        child.name
-                    );
+                    messages.push(message(
+                        &child.name,
+                        "message_group",
+                        templates::MESSAGE_GROUP,
+                    ));
                 }
             }
-            body = format!("{}{}}}\n", body, self.spaces(level));
         } else {
-            body = format!(
-                "{}{}export interface IAvailableMessages {{\n",
-                body,
-                self.spaces(level)
-            );
-            for enums in &store.enums {
-                if enums.parent == 0 {
-                    body = format!(
-                        "{}{}{}?: I{},\n",
-                        body,
-                        self.spaces(level + 1),
-                        enums.name,
-                        enums.name
-                    );
-                }
-            }
-            for strct in &store.structs {
-                if strct.parent == 0 {
-                    body = format!(
-                        "{}{}{}?: {},\n",
-                        body,
-                        self.spaces(level + 1),
-                        strct.name,
-                        strct.name
-                    );
-                }
+            for enums in store.enums.iter().filter(|enums| enums.parent == 0) {
+                messages.push(message(
+                    &enums.name,
+                    "message_enum",
+                    templates::MESSAGE_ENUM,
+                ));
+            }
+            for strct in store.structs.iter().filter(|strct| strct.parent == 0) {
+                messages.push(message(
+                    &strct.name,
+                    "message_struct",
+                    templates::MESSAGE_STRUCT,
+                ));
+            }
+            for group in store.groups.iter().filter(|group| group.parent == 0) {
+                messages.push(message(
+                    &group.name,
+                    "message_group",
+                    templates::MESSAGE_GROUP,
+                ));
             }
-            for group in &store.groups {
-                if group.parent == 0 {
-                    body = format!(
-                        "{}{}{}?: {}.IAvailableMessages,\n",
-                        body,
-                        self.spaces(level + 1),
-                        group.name,
-                        group.name
-                    );
-                }
-            }
-            body = format!("{}{}}}\n", body, self.spaces(level));
         }
-        body
+        Template::new("protocol/typescript/messages", templates::MESSAGES)
+            .set("messages", &self.lines(&messages, 1))
+            .set("json", &self.messages_json(group, &mut store.clone()))
+            .render()
     }
 
     fn get_entity_path(&self, parent: usize, store: &mut Store) -> Vec<String> {
@@ -1621,122 +2184,261 @@ impl TypescriptRender {
     }
 
     fn buffer(&self, store: &mut Store) -> String {
-        let mut body = format!(
-            "{}export class BufferReaderMessages extends BufferReader<IAvailableMessage<IAvailableMessages>> {{\n",
-            self.spaces(0)
-        );
-        body = format!(
-            "{}{}public signature(): number {{ return {}; }}\n",
-            body,
-            self.spaces(1),
-            self.signature
-        );
-        body = format!("{}{}public getMessage(header: MessageHeader, buffer: Buffer | ArrayBuffer | ArrayBufferLike): IAvailableMessage<IAvailableMessages> | Error {{\n", body, self.spaces(1));
-        body = format!("{}{}let instance: any;\n", body, self.spaces(2));
-        body = format!("{}{}let enum_instance: any = {{}};\n", body, self.spaces(2));
-        body = format!("{}{}let err: Error | undefined;\n", body, self.spaces(2));
-        body = format!("{}{}switch (header.id) {{\n", body, self.spaces(2));
+        let mut messages = vec![];
         for enums in &store.enums {
-            body = format!("{}{}case {}:\n", body, self.spaces(3), enums.id);
-            body = format!(
-                "{}{}instance = new {}();\n",
-                body,
-                self.spaces(4),
-                store.get_enum_path(enums.id).join(".")
+            messages.push(
+                Template::new("protocol/typescript/buffer_enum", templates::BUFFER_ENUM)
+                    .set("id", &enums.id.to_string())
+                    .set("path", &store.get_enum_path(enums.id).join("."))
+                    .set(
+                        "entity",
+                        &self.get_available_entity(enums.parent, &enums.name, &mut store.clone()),
+                    )
+                    .render(),
             );
-            body = format!("{}{}err = instance.decode(buffer);\n", body, self.spaces(4));
-            body = format!(
-                "{}{}if (err instanceof Error) {{ return err; }}\n",
-                body,
-                self.spaces(4)
-            );
-            body = format!(
-                "{}{}enum_instance = instance.get();\n",
-                body,
-                self.spaces(4)
-            );
-            body = format!("{}{}instance = enum_instance;\n", body, self.spaces(4));
-            body = format!("{}{}return {{ header: {{ id: header.id, sequence: header.sequence, timestamp: header.ts }}, msg: {{ {}}}, getRef: () => instance }};\n", body, self.spaces(4), self.get_available_entity(enums.parent, &enums.name, &mut store.clone()));
         }
         for structs in &store.structs {
-            body = format!("{}{}case {}:\n", body, self.spaces(3), structs.id);
-            body = format!(
-                "{}{}instance = {}.defaults();\n",
-                body,
-                self.spaces(4),
-                store.get_struct_path(structs.id).join(".")
+            messages.push(
+                Template::new(
+                    "protocol/typescript/buffer_struct",
+                    templates::BUFFER_STRUCT,
+                )
+                .set("id", &structs.id.to_string())
+                .set("path", &store.get_struct_path(structs.id).join("."))
+                .set(
+                    "entity",
+                    &self.get_available_entity(structs.parent, &structs.name, &mut store.clone()),
+                )
+                .render(),
             );
-            body = format!("{}{}err = instance.decode(buffer);\n", body, self.spaces(4));
-            body = format!("{}{}return err instanceof Error ? err : {{ header: {{ id: header.id, sequence: header.sequence, timestamp: header.ts }}, msg: {{ {}}}, getRef: () => instance }};\n", body, self.spaces(4), self.get_available_entity(structs.parent, &structs.name, &mut store.clone()));
         }
-        body = format!(
-            "{}{}default: throw new Error(`Unknown message id=${{header.id}}`);\n",
-            body,
-            self.spaces(3)
-        );
-        body = format!("{}{}}}\n", body, self.spaces(2));
-        body = format!("{}{}}}\n", body, self.spaces(1));
-        body = format!("{}{}}}\n", body, self.spaces(0));
-        body
+        Template::new("protocol/typescript/buffer", templates::BUFFER)
+            .set("signature", &self.signature.to_string())
+            .set("messages", &self.lines(&messages, 3))
+            .render()
     }
 
     fn includes(&self) -> String {
         if self.embedded {
-            format!(
-                "{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}\n",
-                self.get_injectable(include_str!("./static/protocol.injection.embedded.ts")),
-                self.get_injectable(include_str!("./static/tools/index.ts")),
-                self.get_injectable(include_str!("./static/tools/tools.arraybuffer.ts")),
-                self.get_injectable(include_str!("./static/protocol.sizes.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.interface.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.u8.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.u16.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.u32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.u64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.i8.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.i16.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.i32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.i64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.f32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.f64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.bool.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.string.utf8.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.u8.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.u16.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.u32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.u64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.i8.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.i16.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.i32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.i64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.f32.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.f64.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.array.bool.ts")),
-                self.get_injectable(include_str!(
-                    "./static/protocol.primitives.array.string.utf8.ts"
-                )),
-                self.get_injectable(include_str!("./static/protocol.primitives.enum.ts")),
-                self.get_injectable(include_str!("./static/protocol.validator.ts")),
-                self.get_injectable(include_str!("./static/protocol.primitives.ts")),
-                self.get_injectable(include_str!("./static/protocol.convertor.storage.ts")),
-                self.get_injectable(include_str!("./static/packing.header.ts")),
-                self.get_injectable(include_str!("./static/packing.middleware.ts")),
-                self.get_injectable(include_str!("./static/packing.ts")),
-                self.get_injectable(include_str!("./static/protocol.convertor.ts")),
-                self.get_injectable(include_str!("./static/index.ts")),
-            )
+            [
+                self.get_injectable(
+                    "protocol.injection.embedded",
+                    include_str!("./static/protocol.injection.embedded.ts"),
+                ),
+                self.get_injectable("tools/index", include_str!("./static/tools/index.ts")),
+                self.get_injectable(
+                    "tools/tools.arraybuffer",
+                    include_str!("./static/tools/tools.arraybuffer.ts"),
+                ),
+                self.get_injectable("protocol.sizes", include_str!("./static/protocol.sizes.ts")),
+                self.get_injectable(
+                    "protocol.limits",
+                    include_str!("./static/protocol.limits.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.interface",
+                    include_str!("./static/protocol.primitives.interface.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.u8",
+                    include_str!("./static/protocol.primitives.u8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.u16",
+                    include_str!("./static/protocol.primitives.u16.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.u32",
+                    include_str!("./static/protocol.primitives.u32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.u64",
+                    include_str!("./static/protocol.primitives.u64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.i8",
+                    include_str!("./static/protocol.primitives.i8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.i16",
+                    include_str!("./static/protocol.primitives.i16.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.i32",
+                    include_str!("./static/protocol.primitives.i32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.i64",
+                    include_str!("./static/protocol.primitives.i64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.f32",
+                    include_str!("./static/protocol.primitives.f32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.f64",
+                    include_str!("./static/protocol.primitives.f64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.bool",
+                    include_str!("./static/protocol.primitives.bool.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.string.utf8",
+                    include_str!("./static/protocol.primitives.string.utf8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.u8",
+                    include_str!("./static/protocol.primitives.array.u8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.u16",
+                    include_str!("./static/protocol.primitives.array.u16.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.u32",
+                    include_str!("./static/protocol.primitives.array.u32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.u64",
+                    include_str!("./static/protocol.primitives.array.u64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.i8",
+                    include_str!("./static/protocol.primitives.array.i8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.i16",
+                    include_str!("./static/protocol.primitives.array.i16.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.i32",
+                    include_str!("./static/protocol.primitives.array.i32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.i64",
+                    include_str!("./static/protocol.primitives.array.i64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.f32",
+                    include_str!("./static/protocol.primitives.array.f32.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.f64",
+                    include_str!("./static/protocol.primitives.array.f64.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.bool",
+                    include_str!("./static/protocol.primitives.array.bool.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.array.string.utf8",
+                    include_str!("./static/protocol.primitives.array.string.utf8.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives.enum",
+                    include_str!("./static/protocol.primitives.enum.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.validator",
+                    include_str!("./static/protocol.validator.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.primitives",
+                    include_str!("./static/protocol.primitives.ts"),
+                ),
+                self.get_injectable(
+                    "protocol.convertor.storage",
+                    include_str!("./static/protocol.convertor.storage.ts"),
+                ),
+                self.get_injectable("packing.header", include_str!("./static/packing.header.ts")),
+                self.get_injectable(
+                    "packing.middleware",
+                    include_str!("./static/packing.middleware.ts"),
+                ),
+                self.get_injectable(
+                    "packing.compression",
+                    include_str!("./static/packing.compression.ts"),
+                ),
+                self.get_injectable(
+                    "packing.checksum",
+                    include_str!("./static/packing.checksum.ts"),
+                ),
+                self.get_injectable(
+                    "packing.capabilities",
+                    include_str!("./static/packing.capabilities.ts"),
+                ),
+                self.get_injectable("packing", include_str!("./static/packing.ts")),
+                self.get_injectable(
+                    "protocol.convertor",
+                    include_str!("./static/protocol.convertor.ts"),
+                ),
+                self.get_injectable("index", include_str!("./static/index.ts")),
+                String::from("\n"),
+            ]
+            .concat()
         } else {
-            include_str!("./static/protocol.injection.ts").to_string()
+            helpers::template::get(
+                "protocol/typescript/static/protocol.injection",
+                include_str!("./static/protocol.injection.ts"),
+            )
         }
     }
 
-    fn get_injectable(&self, content: &str) -> String {
+    fn codec(&self, store: &Store) -> String {
+        let (name, codec) = match store.get_codec() {
+            Codec::Binary => {
+                return String::new();
+            }
+            Codec::Compact => {
+                return self.get_injectable(
+                    "protocol.compact",
+                    include_str!("./static/protocol.compact.ts"),
+                );
+            }
+            Codec::MsgPack => (
+                "protocol.codec.msgpack",
+                include_str!("./static/protocol.codec.msgpack.ts"),
+            ),
+            Codec::Cbor => (
+                "protocol.codec.cbor",
+                include_str!("./static/protocol.codec.cbor.ts"),
+            ),
+        };
+        [
+            self.get_injectable("protocol.codec", include_str!("./static/protocol.codec.ts")),
+            self.get_injectable(name, codec),
+        ]
+        .concat()
+    }
+
+    // Static parts can be overridden as templates protocol/typescript/static/<name>
+    fn get_injectable(&self, name: &str, content: &str) -> String {
+        let content =
+            helpers::template::get(&format!("protocol/typescript/static/{}", name), content);
         let re_injectable = Regex::new(r"^([\n\r]|.)*(//\s?injectable)").unwrap();
-        re_injectable.replace_all(content, "").to_string()
+        re_injectable.replace_all(&content, "").to_string()
+    }
+
+    // Each item is placed on own line with given indent
+    fn lines(&self, items: &[String], level: usize) -> String {
+        items
+            .iter()
+            .map(|item| format!("\n{}", indent(level, item)))
+            .collect::<String>()
     }
 
-    fn spaces(&self, level: u8) -> String {
-        "    ".repeat(level as usize)
+    // Entities are separated with empty line
+    fn entities(&self, items: &[String], level: usize) -> String {
+        items
+            .iter()
+            .map(|item| format!("\n{}\n", indent(level, item)))
+            .collect::<String>()
+    }
+
+    // Multiline value continues a line with given indent, so first line isn't indented
+    fn inline(&self, level: usize, content: &str) -> String {
+        indent(level, content).trim_start().to_owned()
     }
 }
 
@@ -1749,36 +2451,38 @@ impl Render for TypescriptRender {
     }
 
     fn render(&self, store: &mut Store, dest: &Path) -> Result<(), String> {
-        let mut body = format!("{}\n", self.includes());
-        body = format!(
-            "{}{}",
-            body,
-            self.get_messages_list(None, &mut store.clone(), 0)
-        );
-        for enums in &store.enums {
-            if enums.parent == 0 {
-                body =
-                    format!("{}{}\n", body, self.enums(enums, &mut store.clone(), 0)).to_string();
-            }
+        if store.get_encryption() != Encryption::None {
+            return Err(format!(
+                "Encryption {} isn't supported by typescript protocol",
+                store.get_encryption().as_str()
+            ));
         }
-        for strct in &store.structs {
-            if strct.parent == 0 {
-                body =
-                    format!("{}{}\n", body, self.structs(strct, &mut store.clone(), 0)).to_string();
-            }
+        let mut entities = vec![];
+        for enums in store.enums.iter().filter(|enums| enums.parent == 0) {
+            entities.push(self.enums(enums, &mut store.clone()));
         }
-        for group in &store.groups {
-            if group.parent == 0 {
-                body =
-                    format!("{}{}\n", body, self.groups(group, &mut store.clone(), 0)).to_string();
-            }
+        for strct in store.structs.iter().filter(|strct| strct.parent == 0) {
+            entities.push(self.structs(strct, &mut store.clone()));
         }
-        body = format!("{}{}\n", body, self.buffer(&mut store.clone()));
-        body = format!(
-            "{}export function hash(): string {{ return `{}`; }}\n",
-            body,
-            store.get_hash()
-        );
+        for group in store.groups.iter().filter(|group| group.parent == 0) {
+            entities.push(self.groups(group, &mut store.clone()));
+        }
+        let body = Template::new("protocol/typescript/protocol", templates::PROTOCOL)
+            .set("includes", &self.includes())
+            .set(
+                "json",
+                &self.get_injectable("protocol.json", include_str!("./static/protocol.json.ts")),
+            )
+            .set("codec_impl", &self.codec(store))
+            .set(
+                "messages",
+                &self.get_messages_list(None, &mut store.clone()),
+            )
+            .set("entities", &self.entities(&entities, 0))
+            .set("buffer", &self.buffer(&mut store.clone()))
+            .set("hash", &store.get_hash())
+            .set("codec", store.get_codec().as_str())
+            .render();
         helpers::fs::write(dest.to_path_buf(), body, true)
     }
 }
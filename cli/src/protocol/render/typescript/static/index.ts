@@ -5,6 +5,7 @@ import * as Primitives from "./protocol.primitives";
 import { Convertor } from "./protocol.convertor";
 import { validate, IPropScheme } from "./protocol.validator";
 import { ESize } from "./protocol.sizes";
+import { ProtocolLimits } from "./protocol.limits";
 
 export { ESize } from "./protocol.sizes";
 export { Primitives };
@@ -12,6 +13,21 @@ export { Convertor } from "./protocol.convertor";
 export { validate, IPropScheme } from "./protocol.validator";
 export { BufferReader, IAvailableMessage } from "./packing";
 export { MessageHeader } from "./packing.header";
+export {
+	CompressionAlgorithm,
+	ICompression,
+	setCompression,
+	getCompression,
+} from "./packing.compression";
+export { setChecksum, getChecksum } from "./packing.checksum";
+export { ILimits, ProtocolLimits } from "./protocol.limits";
+export {
+	ICapabilities,
+	IAgreement,
+	IClockSkew,
+	ClockSkew,
+	PackingCapabilities,
+} from "./packing.capabilities";
 
 // injectable
 type ESizeAlias = ESize;
@@ -21,6 +37,7 @@ const ConvertorAlias = Convertor;
 type IPropSchemeAlias = IPropScheme;
 const PrimitivesAlias = Primitives;
 const validateAlias = validate;
+const ProtocolLimitsAlias = ProtocolLimits;
 
 export namespace Protocol {
 	export const ESize = ESizeAlias;
@@ -30,4 +47,5 @@ export namespace Protocol {
 	export type IPropScheme = IPropSchemeAlias;
 	export const Primitives = PrimitivesAlias;
 	export const validate = validateAlias;
+	export const ProtocolLimits = ProtocolLimitsAlias;
 }
@@ -0,0 +1,228 @@
+// tslint:disable: no-namespace
+// tslint:disable: no-bitwise
+import { MessageHeader } from "./packing.header";
+import { PackingCompression } from "./packing.compression";
+import { ILimits } from "./protocol.limits";
+// injectable
+// Capabilities are exchanged while hash is checked: consumer sends own ones with HashRequest,
+// producer responds with own ones in HashResponse. Each side agrees settings of packages for
+// peer, so producer and consumer can be built with different settings.
+// Rust runtime has same implementation
+export interface ICapabilities {
+	// Highest version of header, which can be read
+	version: number;
+	// Flags of compression algorithms, which can be read
+	compression: number[];
+	// Names of encryption algorithms in order of preference
+	encryption: string[];
+	codec: string;
+	// Max size of package body in bytes, which can be read; 0 - isn't limited
+	frame: bigint;
+	// Heartbeat packages are supported
+	heartbeat: boolean;
+}
+
+// Settings of packages for peer
+export interface IAgreement {
+	version: number;
+	// Flags of compression algorithms, which peer can read
+	compression: number;
+	encryption: string | undefined;
+	frame: bigint;
+	heartbeat: boolean;
+	// Clock of peer; undefined until it's estimated
+	clock: IClockSkew | undefined;
+}
+
+// Difference between clock of peer and own clock. It's estimated only by headers with timestamp
+// in milliseconds (version 2), which are sent after agreement. Consumer estimates it by round
+// trip of hash check; producer has only timestamp of first package of consumer, so its
+// estimation includes latency
+export interface IClockSkew {
+	// Clock of peer minus own clock in milliseconds
+	offset: bigint;
+	// Round trip in milliseconds; error of offset isn't bigger than half of it.
+	// undefined - offset is estimated by one package
+	rtt: bigint | undefined;
+}
+
+export namespace ClockSkew {
+	// sent and received - own time of sending request and getting response; peerTs - timestamp
+	// of response (clock of peer). All values are in milliseconds
+	export function estimate(
+		sent: bigint,
+		peerTs: bigint,
+		received: bigint
+	): IClockSkew {
+		const rtt: bigint = received > sent ? received - sent : BigInt(0);
+		return {
+			offset: peerTs - (sent + rtt / BigInt(2)),
+			rtt,
+		};
+	}
+
+	// Estimation by timestamp of package, which is just received
+	export function oneWay(peerTs: bigint): IClockSkew {
+		return {
+			offset: peerTs - MessageHeader.now(),
+			rtt: undefined,
+		};
+	}
+
+	// Converts timestamp of peer (ms) into own clock
+	export function toLocal(skew: IClockSkew, peerTs: bigint): bigint {
+		const local: bigint = peerTs - skew.offset;
+		return local < BigInt(0) ? BigInt(0) : local;
+	}
+
+	// Milliseconds since package with given timestamp (clock of peer) was sent
+	export function age(skew: IClockSkew, peerTs: bigint): bigint {
+		return MessageHeader.now() - toLocal(skew, peerTs);
+	}
+}
+
+export namespace PackingCapabilities {
+	const agreements: Map<string, IAgreement> = new Map();
+
+	// Runtime doesn't encrypt packages and doesn't send heartbeat packages; size of body is
+	// limited by limits of BufferReader
+	export function local(codec: string, limits: ILimits): ICapabilities {
+		return {
+			version: MessageHeader.VERSION,
+			compression: [
+				PackingCompression.FLAG_DEFLATE,
+				PackingCompression.FLAG_LZ4,
+			],
+			encryption: [],
+			codec,
+			frame: BigInt(limits.frame),
+			heartbeat: false,
+		};
+	}
+
+	// Capabilities of peer, which doesn't send them (runtime before negotiation): it reads
+	// headers of version 0 without compression
+	export function legacy(codec: string): ICapabilities {
+		return {
+			version: 0,
+			compression: [],
+			encryption: [],
+			codec,
+			frame: BigInt(0),
+			heartbeat: false,
+		};
+	}
+
+	// Encryption algorithm is chosen by own preference; peer should use it as well
+	export function negotiate(
+		own: ICapabilities,
+		peer: ICapabilities
+	): IAgreement | Error {
+		if (own.codec !== peer.codec) {
+			return new Error(
+				`Codec dismatch; own codec: ${own.codec}; codec of peer: ${peer.codec}`
+			);
+		}
+		const encryption: string | undefined = own.encryption.find(
+			(algorithm: string) => peer.encryption.indexOf(algorithm) !== -1
+		);
+		if (
+			encryption === undefined &&
+			(own.encryption.length > 0 || peer.encryption.length > 0)
+		) {
+			return new Error(
+				`No common encryption; own algorithms: ${own.encryption.join(
+					", "
+				)}; algorithms of peer: ${peer.encryption.join(", ")}`
+			);
+		}
+		return {
+			version: Math.min(own.version, peer.version),
+			compression: peer.compression.reduce(
+				(flags: number, flag: number) =>
+					flags | (flag & PackingCompression.FLAGS),
+				0
+			),
+			encryption,
+			frame: peer.frame,
+			heartbeat: own.heartbeat && peer.heartbeat,
+			clock: undefined,
+		};
+	}
+
+	// Capabilities, which are sent in response: encryption is limited to agreed algorithm
+	export function agreed(
+		own: ICapabilities,
+		agreement: IAgreement
+	): ICapabilities {
+		return Object.assign({}, own, {
+			encryption:
+				agreement.encryption === undefined
+					? []
+					: [agreement.encryption],
+		});
+	}
+
+	// Packages, which are packed with uuid, follow agreement with this peer
+	export function set(uuid: string, agreement: IAgreement) {
+		agreements.set(uuid, agreement);
+	}
+
+	export function get(uuid: string): IAgreement | undefined {
+		return agreements.get(uuid);
+	}
+
+	// Estimates clock of peer by package, which is just received, if it isn't estimated yet.
+	// Timestamps of headers before version 2 are in seconds and aren't used
+	export function observe(
+		uuid: string,
+		header: { timestamp: bigint; version: number }
+	) {
+		const agreement: IAgreement | undefined = agreements.get(uuid);
+		if (
+			agreement !== undefined &&
+			agreement.clock === undefined &&
+			header.version >= MessageHeader.VERSION_TS_MS
+		) {
+			agreement.clock = ClockSkew.oneWay(header.timestamp);
+		}
+	}
+
+	export function clock(uuid: string): IClockSkew | undefined {
+		const agreement: IAgreement | undefined = agreements.get(uuid);
+		return agreement === undefined ? undefined : agreement.clock;
+	}
+
+	export function remove(uuid: string) {
+		agreements.delete(uuid);
+	}
+
+	// Settings of packages for peer: agreed ones or own ones, if there is no agreement. Until
+	// agreement headers have version 0, because peer might be older runtime
+	export function peer(uuid?: string): IAgreement {
+		const agreement: IAgreement | undefined =
+			uuid === undefined ? undefined : agreements.get(uuid);
+		return agreement === undefined
+			? {
+					version: 0,
+					compression: PackingCompression.FLAGS,
+					encryption: undefined,
+					frame: BigInt(0),
+					heartbeat: false,
+					clock: undefined,
+			  }
+			: agreement;
+	}
+
+	export function check(
+		agreement: IAgreement,
+		len: number
+	): Error | undefined {
+		if (agreement.frame !== BigInt(0) && BigInt(len) > agreement.frame) {
+			return new Error(
+				`Size of package body (${len} bytes) is bigger than limit of peer (${agreement.frame} bytes)`
+			);
+		}
+		return undefined;
+	}
+}
@@ -0,0 +1,51 @@
+// tslint:disable: no-namespace
+// tslint:disable: no-bitwise
+// injectable
+// Checksum is CRC32C (Castagnoli) of body as it's sent (after compression and middleware).
+// Package with checksum is marked with flag in header and has additional u32 field after
+// length of body, so packages with and without checksum can be mixed in one stream.
+// Rust runtime has same implementation
+
+// Checksum is added to all packages, which are packed by current process. Incoming packages
+// are validated, if they have checksum
+export function setChecksum(enabled: boolean) {
+	PackingChecksum.set(enabled);
+}
+
+export function getChecksum(): boolean {
+	return PackingChecksum.get();
+}
+
+export namespace PackingChecksum {
+	const POLYNOMIAL = 0x82f63b78;
+	const TABLE: Uint32Array = (() => {
+		const table = new Uint32Array(256);
+		for (let i = 0; i < 256; i += 1) {
+			let crc = i;
+			for (let bit = 0; bit < 8; bit += 1) {
+				crc = crc & 1 ? (crc >>> 1) ^ POLYNOMIAL : crc >>> 1;
+			}
+			table[i] = crc >>> 0;
+		}
+		return table;
+	})();
+
+	let enabled: boolean = false;
+
+	export function set(value: boolean) {
+		enabled = value;
+	}
+
+	export function get(): boolean {
+		return enabled;
+	}
+
+	export function crc32c(body: ArrayBufferLike | Uint8Array): number {
+		const src = body instanceof Uint8Array ? body : new Uint8Array(body);
+		let crc = 0xffffffff;
+		for (let i = 0; i < src.byteLength; i += 1) {
+			crc = TABLE[(crc ^ src[i]) & 0xff] ^ (crc >>> 8);
+		}
+		return (crc ^ 0xffffffff) >>> 0;
+	}
+}
@@ -0,0 +1,688 @@
+// tslint:disable: no-namespace
+// tslint:disable: no-bitwise
+import { ProtocolLimits } from "./protocol.limits";
+// injectable
+// Body of package can be compressed with deflate (RFC 1951) or LZ4 (block format). Compressed
+// body: | u32 length of original body | compressed data |. Algorithm is marked with flag in
+// header of package, so compressed and not compressed packages can be mixed in one stream.
+// Rust runtime has same implementation and gives same bytes
+export enum CompressionAlgorithm {
+	None = 0,
+	Deflate = 1,
+	Lz4 = 2,
+}
+
+export interface ICompression {
+	algorithm: CompressionAlgorithm;
+	// Bodies, which are smaller (in bytes), aren't compressed
+	threshold: number;
+}
+
+// Compression is used for all packages, which are packed by current process. Incoming packages
+// are decompressed in any case
+export function setCompression(compression: ICompression) {
+	PackingCompression.set(compression);
+}
+
+export function getCompression(): ICompression {
+	return PackingCompression.get();
+}
+
+export namespace PackingCompression {
+	export const FLAG_DEFLATE = 1;
+	export const FLAG_LZ4 = 2;
+	export const FLAGS = FLAG_DEFLATE | FLAG_LZ4;
+
+	let algorithm: CompressionAlgorithm = CompressionAlgorithm.None;
+	let threshold: number = 1024;
+
+	export function set(compression: ICompression) {
+		algorithm = compression.algorithm;
+		threshold = compression.threshold;
+	}
+
+	export function get(): ICompression {
+		return { algorithm, threshold };
+	}
+
+	// Returns flag of algorithm, which should be used for body of given size; 0 - no compression.
+	// Configured algorithm is used, if peer can read it (readable - flags of algorithms);
+	// otherwise other algorithm, which peer can read
+	export function flag(len: number, readable: number = FLAGS): number {
+		const used: number =
+			algorithm === CompressionAlgorithm.None
+				? 0
+				: algorithm & readable
+				? algorithm
+				: readable & -readable;
+		if (used === 0 || len < threshold || len > 0xffffffff) {
+			return 0;
+		}
+		return used;
+	}
+
+	// Body is kept as it is, if compression doesn't make it smaller
+	export function compress(
+		body: ArrayBufferLike,
+		readable: number = FLAGS
+	): {
+		flag: number;
+		body: ArrayBufferLike;
+	} {
+		const used = flag(body.byteLength, readable);
+		if (used === 0) {
+			return { flag: 0, body };
+		}
+		const src = new Uint8Array(body);
+		const data =
+			used === FLAG_DEFLATE ? deflateCompress(src) : lz4Compress(src);
+		if (data.byteLength + 4 >= src.byteLength) {
+			return { flag: 0, body };
+		}
+		const compressed = new Uint8Array(data.byteLength + 4);
+		new DataView(compressed.buffer).setUint32(0, src.byteLength, true);
+		compressed.set(data, 4);
+		return { flag: used, body: compressed.buffer };
+	}
+
+	export function decompress(
+		used: number,
+		body: ArrayBufferLike
+	): ArrayBufferLike | Error {
+		if (used === 0) {
+			return body;
+		}
+		if (body.byteLength < 4) {
+			return new Error(`Compressed body is too small`);
+		}
+		const src = new Uint8Array(body);
+		const len = new DataView(src.buffer, src.byteOffset, 4).getUint32(
+			0,
+			true
+		);
+		const limit: Error | undefined = ProtocolLimits.frame(len);
+		if (limit instanceof Error) {
+			return limit;
+		}
+		let decompressed: Uint8Array | Error;
+		if (used === FLAG_DEFLATE) {
+			decompressed = deflateDecompress(src.subarray(4), len);
+		} else if (used === FLAG_LZ4) {
+			decompressed = lz4Decompress(src.subarray(4), len);
+		} else {
+			return new Error(`Unknown compression flag: ${used}`);
+		}
+		if (decompressed instanceof Error) {
+			return decompressed;
+		}
+		if (decompressed.byteLength !== len) {
+			return new Error(
+				`Decompressed body has ${decompressed.byteLength} bytes, but expected ${len} bytes`
+			);
+		}
+		return decompressed.buffer;
+	}
+
+	class Output {
+		private _bytes: Uint8Array;
+		private _len: number = 0;
+
+		constructor(capacity: number) {
+			this._bytes = new Uint8Array(Math.max(capacity, 16));
+		}
+
+		public push(byte: number) {
+			if (this._len === this._bytes.byteLength) {
+				this.grow(1);
+			}
+			this._bytes[this._len++] = byte;
+		}
+
+		public append(bytes: Uint8Array) {
+			if (this._len + bytes.byteLength > this._bytes.byteLength) {
+				this.grow(bytes.byteLength);
+			}
+			this._bytes.set(bytes, this._len);
+			this._len += bytes.byteLength;
+		}
+
+		// Copies bytes, which are already written; source and target can overlap
+		public repeat(distance: number, len: number) {
+			if (this._len + len > this._bytes.byteLength) {
+				this.grow(len);
+			}
+			const start = this._len - distance;
+			for (let i = 0; i < len; i += 1) {
+				this._bytes[this._len++] = this._bytes[start + i];
+			}
+		}
+
+		public len(): number {
+			return this._len;
+		}
+
+		public bytes(): Uint8Array {
+			return this._bytes.slice(0, this._len);
+		}
+
+		private grow(len: number) {
+			const bytes = new Uint8Array(
+				Math.max(this._bytes.byteLength * 2, this._len + len)
+			);
+			bytes.set(this._bytes.subarray(0, this._len));
+			this._bytes = bytes;
+		}
+	}
+
+	function hash(value: number, bits: number): number {
+		return Math.imul(value, 0x9e3779b1) >>> (32 - bits);
+	}
+
+	const LZ4_HASH_BITS = 12;
+	const LZ4_MIN_MATCH = 4;
+	// Last 5 bytes are always literals; last match starts 12 bytes before end at least
+	const LZ4_LAST_LITERALS = 5;
+	const LZ4_MF_LIMIT = 12;
+	const LZ4_MAX_OFFSET = 65535;
+
+	function lz4ReadU32(src: Uint8Array, pos: number): number {
+		return (
+			(src[pos] |
+				(src[pos + 1] << 8) |
+				(src[pos + 2] << 16) |
+				(src[pos + 3] << 24)) >>>
+			0
+		);
+	}
+
+	function lz4PutLen(len: number, out: Output) {
+		while (len >= 255) {
+			out.push(255);
+			len -= 255;
+		}
+		out.push(len);
+	}
+
+	function lz4PutSequence(
+		literals: Uint8Array,
+		matched: [number, number] | undefined,
+		out: Output
+	) {
+		const matchLen =
+			matched === undefined ? 0 : matched[1] - LZ4_MIN_MATCH;
+		out.push(
+			(Math.min(literals.byteLength, 15) << 4) | Math.min(matchLen, 15)
+		);
+		if (literals.byteLength >= 15) {
+			lz4PutLen(literals.byteLength - 15, out);
+		}
+		out.append(literals);
+		if (matched !== undefined) {
+			out.push(matched[0] & 0xff);
+			out.push(matched[0] >>> 8);
+			if (matchLen >= 15) {
+				lz4PutLen(matchLen - 15, out);
+			}
+		}
+	}
+
+	function lz4Compress(src: Uint8Array): Uint8Array {
+		const out = new Output(src.byteLength);
+		const table = new Int32Array(1 << LZ4_HASH_BITS).fill(-1);
+		let anchor = 0;
+		let pos = 0;
+		while (pos + LZ4_MF_LIMIT <= src.byteLength) {
+			const sequence = lz4ReadU32(src, pos);
+			const index = hash(sequence, LZ4_HASH_BITS);
+			const candidate = table[index];
+			table[index] = pos;
+			if (
+				candidate !== -1 &&
+				pos - candidate <= LZ4_MAX_OFFSET &&
+				lz4ReadU32(src, candidate) === sequence
+			) {
+				let len = LZ4_MIN_MATCH;
+				while (
+					pos + len < src.byteLength - LZ4_LAST_LITERALS &&
+					src[candidate + len] === src[pos + len]
+				) {
+					len += 1;
+				}
+				lz4PutSequence(
+					src.subarray(anchor, pos),
+					[pos - candidate, len],
+					out
+				);
+				pos += len;
+				anchor = pos;
+			} else {
+				pos += 1;
+			}
+		}
+		lz4PutSequence(src.subarray(anchor), undefined, out);
+		return out.bytes();
+	}
+
+	function lz4Decompress(src: Uint8Array, len: number): Uint8Array | Error {
+		const out = new Output(Math.min(len, src.byteLength * 4));
+		let pos = 0;
+		const readLen = (): number | Error => {
+			let value = 0;
+			while (true) {
+				if (pos >= src.byteLength) {
+					return new Error(`LZ4: unexpected end of data`);
+				}
+				const byte = src[pos++];
+				value += byte;
+				if (byte !== 255) {
+					return value;
+				}
+			}
+		};
+		while (true) {
+			if (pos >= src.byteLength) {
+				return new Error(`LZ4: unexpected end of data`);
+			}
+			const token = src[pos++];
+			let literals = token >>> 4;
+			if (literals === 15) {
+				const extra = readLen();
+				if (extra instanceof Error) {
+					return extra;
+				}
+				literals += extra;
+			}
+			if (
+				src.byteLength - pos < literals ||
+				len - out.len() < literals
+			) {
+				return new Error(`LZ4: literals are out of bounds`);
+			}
+			out.append(src.subarray(pos, pos + literals));
+			pos += literals;
+			// Last sequence has only literals
+			if (pos === src.byteLength) {
+				return out.bytes();
+			}
+			if (src.byteLength - pos < 2) {
+				return new Error(`LZ4: unexpected end of data`);
+			}
+			const offset = src[pos] | (src[pos + 1] << 8);
+			pos += 2;
+			if (offset === 0 || offset > out.len()) {
+				return new Error(`LZ4: invalid offset ${offset}`);
+			}
+			let matchLen = token & 15;
+			if (matchLen === 15) {
+				const extra = readLen();
+				if (extra instanceof Error) {
+					return extra;
+				}
+				matchLen += extra;
+			}
+			matchLen += LZ4_MIN_MATCH;
+			if (len - out.len() < matchLen) {
+				return new Error(`LZ4: match is out of bounds`);
+			}
+			out.repeat(offset, matchLen);
+		}
+	}
+
+	const DEFLATE_HASH_BITS = 15;
+	const DEFLATE_MIN_MATCH = 3;
+	const DEFLATE_MAX_MATCH = 258;
+	const DEFLATE_WINDOW = 32768;
+	const DEFLATE_LENGTH_BASE = [
+		3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51,
+		59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+	];
+	const DEFLATE_LENGTH_EXTRA = [
+		0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4,
+		4, 5, 5, 5, 5, 0,
+	];
+	const DEFLATE_DIST_BASE = [
+		1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385,
+		513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385,
+		24577,
+	];
+	const DEFLATE_DIST_EXTRA = [
+		0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10,
+		10, 11, 11, 12, 12, 13, 13,
+	];
+	const DEFLATE_CODES_ORDER = [
+		16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+	];
+
+	class DeflateWriter {
+		public out: Output;
+		private _bits: number = 0;
+		private _count: number = 0;
+
+		constructor(capacity: number) {
+			this.out = new Output(capacity);
+		}
+
+		public put(value: number, len: number) {
+			this._bits |= value << this._count;
+			this._count += len;
+			while (this._count >= 8) {
+				this.out.push(this._bits & 0xff);
+				this._bits >>>= 8;
+				this._count -= 8;
+			}
+		}
+
+		// Huffman codes are written starting from most significant bit
+		public putCode(code: number, len: number) {
+			let reversed = 0;
+			for (let i = 0; i < len; i += 1) {
+				reversed |= ((code >>> i) & 1) << (len - 1 - i);
+			}
+			this.put(reversed, len);
+		}
+
+		public putSymbol(symbol: number) {
+			if (symbol <= 143) {
+				this.putCode(0x30 + symbol, 8);
+			} else if (symbol <= 255) {
+				this.putCode(0x190 + symbol - 144, 9);
+			} else if (symbol <= 279) {
+				this.putCode(symbol - 256, 7);
+			} else {
+				this.putCode(0xc0 + symbol - 280, 8);
+			}
+		}
+
+		public finish(): Uint8Array {
+			if (this._count > 0) {
+				this.out.push(this._bits & 0xff);
+			}
+			return this.out.bytes();
+		}
+	}
+
+	function deflateCode(base: number[], value: number): number {
+		for (let i = base.length - 1; i >= 0; i -= 1) {
+			if (base[i] <= value) {
+				return i;
+			}
+		}
+		return 0;
+	}
+
+	// One final block with fixed Huffman codes
+	function deflateCompress(src: Uint8Array): Uint8Array {
+		const writer = new DeflateWriter(src.byteLength);
+		writer.put(1, 1);
+		writer.put(1, 2);
+		const table = new Int32Array(1 << DEFLATE_HASH_BITS).fill(-1);
+		let pos = 0;
+		while (pos < src.byteLength) {
+			let matched: [number, number] | undefined;
+			if (pos + DEFLATE_MIN_MATCH <= src.byteLength) {
+				const index = hash(
+					(src[pos] << 16) | (src[pos + 1] << 8) | src[pos + 2],
+					DEFLATE_HASH_BITS
+				);
+				const candidate = table[index];
+				table[index] = pos;
+				if (candidate !== -1 && pos - candidate <= DEFLATE_WINDOW) {
+					let len = 0;
+					while (
+						len < DEFLATE_MAX_MATCH &&
+						pos + len < src.byteLength &&
+						src[candidate + len] === src[pos + len]
+					) {
+						len += 1;
+					}
+					if (len >= DEFLATE_MIN_MATCH) {
+						matched = [pos - candidate, len];
+					}
+				}
+			}
+			if (matched !== undefined) {
+				const [distance, len] = matched;
+				let code = deflateCode(DEFLATE_LENGTH_BASE, len);
+				writer.putSymbol(257 + code);
+				writer.put(
+					len - DEFLATE_LENGTH_BASE[code],
+					DEFLATE_LENGTH_EXTRA[code]
+				);
+				code = deflateCode(DEFLATE_DIST_BASE, distance);
+				writer.putCode(code, 5);
+				writer.put(
+					distance - DEFLATE_DIST_BASE[code],
+					DEFLATE_DIST_EXTRA[code]
+				);
+				pos += len;
+			} else {
+				writer.putSymbol(src[pos]);
+				pos += 1;
+			}
+		}
+		writer.putSymbol(256);
+		return writer.finish();
+	}
+
+	class DeflateReader {
+		public pos: number = 0;
+		public bits: number = 0;
+		public count: number = 0;
+
+		constructor(public readonly src: Uint8Array) {}
+
+		public get(len: number): number {
+			while (this.count < len) {
+				if (this.pos >= this.src.byteLength) {
+					throw new Error(`Deflate: unexpected end of data`);
+				}
+				this.bits |= this.src[this.pos] << this.count;
+				this.count += 8;
+				this.pos += 1;
+			}
+			const value = this.bits & ((1 << len) - 1);
+			this.bits >>>= len;
+			this.count -= len;
+			return value;
+		}
+	}
+
+	class DeflateHuffman {
+		private _count: Uint16Array = new Uint16Array(16);
+		private _symbol: Uint16Array;
+
+		constructor(lengths: ArrayLike<number>) {
+			for (let i = 0; i < lengths.length; i += 1) {
+				this._count[lengths[i]] += 1;
+			}
+			let left = 1;
+			for (let len = 1; len < 16; len += 1) {
+				left = (left << 1) - this._count[len];
+				if (left < 0) {
+					throw new Error(`Deflate: over-subscribed Huffman code`);
+				}
+			}
+			const offsets = new Uint32Array(16);
+			for (let len = 1; len < 15; len += 1) {
+				offsets[len + 1] = offsets[len] + this._count[len];
+			}
+			this._symbol = new Uint16Array(lengths.length);
+			for (let symbol = 0; symbol < lengths.length; symbol += 1) {
+				if (lengths[symbol] !== 0) {
+					this._symbol[offsets[lengths[symbol]]] = symbol;
+					offsets[lengths[symbol]] += 1;
+				}
+			}
+		}
+
+		public decode(reader: DeflateReader): number {
+			let code = 0;
+			let first = 0;
+			let index = 0;
+			for (let len = 1; len < 16; len += 1) {
+				code |= reader.get(1);
+				const count = this._count[len];
+				if (code - count < first) {
+					return this._symbol[index + (code - first)];
+				}
+				index += count;
+				first = (first + count) << 1;
+				code <<= 1;
+			}
+			throw new Error(`Deflate: invalid Huffman code`);
+		}
+	}
+
+	function deflateCodes(
+		reader: DeflateReader,
+		lengths: DeflateHuffman,
+		distances: DeflateHuffman,
+		out: Output,
+		len: number
+	) {
+		while (true) {
+			const symbol = lengths.decode(reader);
+			if (symbol < 256) {
+				if (out.len() >= len) {
+					throw new Error(`Deflate: data is out of bounds`);
+				}
+				out.push(symbol);
+			} else if (symbol === 256) {
+				return;
+			} else {
+				let code = symbol - 257;
+				if (code >= DEFLATE_LENGTH_BASE.length) {
+					throw new Error(`Deflate: invalid length code ${symbol}`);
+				}
+				const length =
+					DEFLATE_LENGTH_BASE[code] +
+					reader.get(DEFLATE_LENGTH_EXTRA[code]);
+				code = distances.decode(reader);
+				if (code >= DEFLATE_DIST_BASE.length) {
+					throw new Error(`Deflate: invalid distance code ${code}`);
+				}
+				const distance =
+					DEFLATE_DIST_BASE[code] +
+					reader.get(DEFLATE_DIST_EXTRA[code]);
+				if (distance > out.len()) {
+					throw new Error(`Deflate: invalid distance ${distance}`);
+				}
+				if (len - out.len() < length) {
+					throw new Error(`Deflate: data is out of bounds`);
+				}
+				out.repeat(distance, length);
+			}
+		}
+	}
+
+	function deflateDynamic(
+		reader: DeflateReader
+	): [DeflateHuffman, DeflateHuffman] {
+		const nlen = reader.get(5) + 257;
+		const ndist = reader.get(5) + 1;
+		const ncode = reader.get(4) + 4;
+		if (nlen > 286 || ndist > 30) {
+			throw new Error(`Deflate: invalid count of codes`);
+		}
+		const order = new Uint8Array(19);
+		for (let i = 0; i < ncode; i += 1) {
+			order[DEFLATE_CODES_ORDER[i]] = reader.get(3);
+		}
+		const codes = new DeflateHuffman(order);
+		const lengths = new Uint8Array(nlen + ndist);
+		let index = 0;
+		while (index < nlen + ndist) {
+			const symbol = codes.decode(reader);
+			if (symbol < 16) {
+				lengths[index] = symbol;
+				index += 1;
+				continue;
+			}
+			let len = 0;
+			let repeat = 0;
+			if (symbol === 16) {
+				if (index === 0) {
+					throw new Error(`Deflate: repeat without previous length`);
+				}
+				len = lengths[index - 1];
+				repeat = 3 + reader.get(2);
+			} else if (symbol === 17) {
+				repeat = 3 + reader.get(3);
+			} else {
+				repeat = 11 + reader.get(7);
+			}
+			if (index + repeat > nlen + ndist) {
+				throw new Error(`Deflate: too many lengths`);
+			}
+			lengths.fill(len, index, index + repeat);
+			index += repeat;
+		}
+		if (lengths[256] === 0) {
+			throw new Error(`Deflate: no end of block code`);
+		}
+		return [
+			new DeflateHuffman(lengths.subarray(0, nlen)),
+			new DeflateHuffman(lengths.subarray(nlen)),
+		];
+	}
+
+	function deflateDecompress(
+		src: Uint8Array,
+		len: number
+	): Uint8Array | Error {
+		const reader = new DeflateReader(src);
+		const out = new Output(Math.min(len, src.byteLength * 4));
+		try {
+			while (true) {
+				const last = reader.get(1);
+				const kind = reader.get(2);
+				if (kind === 0) {
+					// Stored block starts from next byte
+					reader.bits = 0;
+					reader.count = 0;
+					if (src.byteLength - reader.pos < 4) {
+						throw new Error(`Deflate: unexpected end of data`);
+					}
+					const size = src[reader.pos] | (src[reader.pos + 1] << 8);
+					const nsize =
+						src[reader.pos + 2] | (src[reader.pos + 3] << 8);
+					if (size !== (~nsize & 0xffff)) {
+						throw new Error(`Deflate: invalid size of stored block`);
+					}
+					reader.pos += 4;
+					if (
+						src.byteLength - reader.pos < size ||
+						len - out.len() < size
+					) {
+						throw new Error(`Deflate: stored block is out of bounds`);
+					}
+					out.append(src.subarray(reader.pos, reader.pos + size));
+					reader.pos += size;
+				} else if (kind === 1) {
+					const lengths = new Uint8Array(288);
+					lengths.fill(8, 0, 144);
+					lengths.fill(9, 144, 256);
+					lengths.fill(7, 256, 280);
+					lengths.fill(8, 280, 288);
+					deflateCodes(
+						reader,
+						new DeflateHuffman(lengths),
+						new DeflateHuffman(new Uint8Array(30).fill(5)),
+						out,
+						len
+					);
+				} else if (kind === 2) {
+					const [lengths, distances] = deflateDynamic(reader);
+					deflateCodes(reader, lengths, distances, out, len);
+				} else {
+					throw new Error(`Deflate: invalid type of block ${kind}`);
+				}
+				if (last === 1) {
+					return out.bytes();
+				}
+			}
+		} catch (e) {
+			return e instanceof Error ? e : new Error(`${e}`);
+		}
+	}
+}
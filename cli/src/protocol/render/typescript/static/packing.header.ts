@@ -5,18 +5,61 @@ export class MessageHeader {
 	public static readonly SEQ_LENGTH = 4;
 	public static readonly TS_LENGTH = 8;
 	public static readonly LEN_LENGTH = 8;
+	public static readonly CHECKSUM_LENGTH = 4;
 	public static readonly SIZE =
 		MessageHeader.ID_LENGTH +
 		MessageHeader.SIGN_LENGTH +
 		MessageHeader.SEQ_LENGTH +
 		MessageHeader.TS_LENGTH +
 		MessageHeader.LEN_LENGTH;
+	// Highest byte of length keeps flags (compression, version, checksum)
+	public static readonly FLAGS_SHIFT = 56;
+	// Header with this flag has checksum of body after length of body
+	public static readonly FLAG_CHECKSUM = 0x80;
+	// Bits 4..6 of flags keep version of header; packages of older runtimes have version 0.
+	// Headers of all versions have same layout; since version 2 timestamp is in milliseconds
+	// (before in seconds)
+	public static readonly VERSION = 2;
+	public static readonly VERSION_TS_MS = 2;
+	public static readonly VERSION_MASK = 0x70;
+	public static readonly VERSION_SHIFT = 4;
+
+	private static _last: bigint = BigInt(0);
+
+	// Unix time in milliseconds. It doesn't go back within process even if system clock does, so
+	// packages of one producer (consumer) are ordered by timestamp
+	public static now(): bigint {
+		const now: bigint = BigInt(Date.now());
+		if (now > MessageHeader._last) {
+			MessageHeader._last = now;
+		}
+		return MessageHeader._last;
+	}
+
+	// Converts timestamp between versions of header
+	public static convertTs(ts: bigint, from: number, to: number): bigint {
+		const fromMs = from >= MessageHeader.VERSION_TS_MS;
+		const toMs = to >= MessageHeader.VERSION_TS_MS;
+		if (fromMs && !toMs) {
+			return ts / BigInt(1000);
+		} else if (!fromMs && toMs) {
+			return ts * BigInt(1000);
+		} else {
+			return ts;
+		}
+	}
 
 	public readonly id: number;
 	public readonly signature: number;
 	public readonly sequence: number;
-	public readonly ts: BigInt;
+	// Unix time of sending in units of version of header; use tsMs() to get milliseconds
+	public readonly ts: bigint;
 	public readonly len: number;
+	public readonly flags: number;
+	public readonly version: number;
+	public readonly checksum: number | undefined;
+	// Size of header: basic header and checksum, if it's present
+	public readonly size: number;
 
 	constructor(buffer: Buffer) {
 		if (MessageHeader.enow(buffer) === false) {
@@ -34,18 +77,47 @@ export class MessageHeader {
 					MessageHeader.SIGN_LENGTH +
 					MessageHeader.SEQ_LENGTH
 			);
-			this.len = Number(
-				buffer.readBigUInt64LE(
-					MessageHeader.ID_LENGTH +
-						MessageHeader.SIGN_LENGTH +
-						MessageHeader.SEQ_LENGTH +
-						MessageHeader.TS_LENGTH
-				)
-			);
+			const offset =
+				MessageHeader.ID_LENGTH +
+				MessageHeader.SIGN_LENGTH +
+				MessageHeader.SEQ_LENGTH +
+				MessageHeader.TS_LENGTH;
+			const high = buffer.readUInt32LE(offset + 4);
+			this.len =
+				buffer.readUInt32LE(offset) + (high & 0x00ffffff) * 0x100000000;
+			this.flags = high >>> 24;
+			this.version =
+				(this.flags & MessageHeader.VERSION_MASK) >>>
+				MessageHeader.VERSION_SHIFT;
+			this.size = MessageHeader.getSize(this.flags);
+			this.checksum =
+				this.flags & MessageHeader.FLAG_CHECKSUM
+					? buffer.readUInt32LE(MessageHeader.SIZE)
+					: undefined;
 		}
 	}
 
+	// Unix time of sending in milliseconds (headers before version 2 have precision in seconds)
+	public tsMs(): bigint {
+		return MessageHeader.convertTs(
+			this.ts,
+			this.version,
+			MessageHeader.VERSION_TS_MS
+		);
+	}
+
+	public static getSize(flags: number): number {
+		return flags & MessageHeader.FLAG_CHECKSUM
+			? MessageHeader.SIZE + MessageHeader.CHECKSUM_LENGTH
+			: MessageHeader.SIZE;
+	}
+
+	// Flags are in last byte of basic header; they define whether header has checksum
 	public static enow(buffer: Buffer): boolean {
-		return buffer.byteLength >= MessageHeader.SIZE;
+		return (
+			buffer.byteLength >= MessageHeader.SIZE &&
+			buffer.byteLength >=
+				MessageHeader.getSize(buffer[MessageHeader.SIZE - 1])
+		);
 	}
 }
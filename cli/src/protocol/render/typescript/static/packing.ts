@@ -1,13 +1,19 @@
 import { Buffer } from "buffer";
 import { MessageHeader } from "./packing.header";
 import { getPackingMiddleware, PackingMiddleware } from "./packing.middleware";
+import { PackingCompression } from "./packing.compression";
+import { PackingChecksum } from "./packing.checksum";
+import { ILimits, ProtocolLimits } from "./protocol.limits";
 // injectable
 
 export interface IAvailableMessage<T> {
 	header: {
 		id: number;
 		sequence: number;
-		timestamp: BigInt;
+		// Unix time of sending in milliseconds
+		timestamp: bigint;
+		// Version of header; before version 2 timestamp has precision in seconds
+		version: number;
 	};
 	msg: T;
 	getRef: <Z>() => Z;
@@ -16,6 +22,11 @@ export interface IAvailableMessage<T> {
 export abstract class BufferReader<T> {
 	private _buffer: Buffer = Buffer.alloc(0);
 	private _queue: T[] = [];
+	private readonly _limits: ILimits;
+
+	constructor(limits: ILimits = ProtocolLimits.defaults()) {
+		this._limits = limits;
+	}
 
 	public abstract signature(): number;
 
@@ -37,13 +48,51 @@ export abstract class BufferReader<T> {
 			if (!MessageHeader.enow(this._buffer)) {
 				break;
 			}
-			const header: MessageHeader = new MessageHeader(
-				this._buffer.slice(0, MessageHeader.SIZE)
-			);
-			if (this._buffer.byteLength < header.len + MessageHeader.SIZE) {
+			const header: MessageHeader = new MessageHeader(this._buffer);
+			if (header.len > this._limits.frame) {
+				// Rest of buffer can't be trusted anymore
+				this._buffer = Buffer.alloc(0);
+				errors.push(
+					new Error(
+						`Size of package id=${header.id} (${header.len} bytes) is bigger than limit (${this._limits.frame} bytes)`
+					)
+				);
+				break;
+			}
+			if (this._buffer.byteLength < header.len + header.size) {
+				break;
+			}
+			if (this._queue.length >= this._limits.pending) {
+				// Package stays in buffer until pending messages are taken
+				errors.push(
+					new Error(
+						`Count of pending messages is bigger than limit (${this._limits.pending})`
+					)
+				);
 				break;
 			}
-			if (header.signature !== this.signature()) {
+			const checksum: number | undefined =
+				header.checksum === undefined
+					? undefined
+					: PackingChecksum.crc32c(
+							this._buffer.slice(
+								header.size,
+								header.size + header.len
+							)
+					  );
+			if (header.version > MessageHeader.VERSION) {
+				errors.push(
+					new Error(
+						`Version of header ${header.version} isn't supported; supported versions: 0..${MessageHeader.VERSION}`
+					)
+				);
+			} else if (checksum !== header.checksum) {
+				errors.push(
+					new Error(
+						`Checksum of message id="${header.id}" (sequence ${header.sequence}) dismatch; expectation: ${header.checksum}; body: ${checksum}`
+					)
+				);
+			} else if (header.signature !== this.signature()) {
 				errors.push(
 					new Error(
 						`Dismatch of signature for message id="${
@@ -54,14 +103,14 @@ export abstract class BufferReader<T> {
 					)
 				);
 			} else {
-				const body: ArrayBufferLike | Error = (() => {
+				const decoded: ArrayBufferLike | Error = (() => {
 					const middleware: PackingMiddleware | undefined =
 						getPackingMiddleware();
 					if (middleware instanceof PackingMiddleware) {
 						return middleware.decode(
 							this._buffer.slice(
-								MessageHeader.SIZE,
-								MessageHeader.SIZE + header.len
+								header.size,
+								header.size + header.len
 							),
 							header.id,
 							header.sequence,
@@ -69,25 +118,33 @@ export abstract class BufferReader<T> {
 						);
 					} else {
 						return this._buffer.slice(
-							MessageHeader.SIZE,
-							MessageHeader.SIZE + header.len
+							header.size,
+							header.size + header.len
 						);
 					}
 				})();
-				if (body instanceof Error) {
-					errors.push(body);
-				} else {
-					const msg = this.getMessage(header, body);
-					if (msg instanceof Error) {
-						errors.push(msg);
-					} else {
-						this._queue.push(msg);
+				const msg: T | Error = ProtocolLimits.apply(
+					this._limits,
+					(): T | Error => {
+						const body: ArrayBufferLike | Error =
+							decoded instanceof Error
+								? decoded
+								: PackingCompression.decompress(
+										header.flags & PackingCompression.FLAGS,
+										decoded
+								  );
+						return body instanceof Error
+							? body
+							: this.getMessage(header, body);
 					}
-				}
-				this._buffer = this._buffer.slice(
-					MessageHeader.SIZE + header.len
 				);
+				if (msg instanceof Error) {
+					errors.push(msg);
+				} else {
+					this._queue.push(msg);
+				}
 			}
+			this._buffer = this._buffer.slice(header.size + header.len);
 		} while (true);
 		return errors.length > 0 ? errors : undefined;
 	}
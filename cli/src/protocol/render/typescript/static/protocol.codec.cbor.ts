@@ -0,0 +1,166 @@
+// tslint:disable: no-namespace
+// injectable
+// CBOR: https://www.rfc-editor.org/rfc/rfc8949.html
+export namespace ProtocolCodec {
+	export function encodeBody(value: Value): ArrayBufferLike {
+		const writer = new BodyWriter();
+		cborWrite(value, writer);
+		return writer.get();
+	}
+
+	export function decodeBody(bytes: ArrayBufferLike): Value {
+		const reader = new BodyReader(bytes);
+		const value = cborRead(reader);
+		if (reader.rest() !== 0) {
+			throw new Error(`Unexpected ${reader.rest()} bytes after cbor value`);
+		}
+		return value;
+	}
+
+	function cborWriteHead(major: number, n: number | bigint, writer: BodyWriter) {
+		const value = BigInt(n);
+		major = major << 5;
+		if (value < BigInt(24)) {
+			writer.u8(major | Number(value));
+		} else if (value <= BigInt(0xff)) {
+			writer.u8(major | 24);
+			writer.uint(value, 1);
+		} else if (value <= BigInt(0xffff)) {
+			writer.u8(major | 25);
+			writer.uint(value, 2);
+		} else if (value <= BigInt(0xffffffff)) {
+			writer.u8(major | 26);
+			writer.uint(value, 4);
+		} else {
+			writer.u8(major | 27);
+			writer.uint(value, 8);
+		}
+	}
+
+	function cborWrite(value: Value, writer: BodyWriter) {
+		switch (value.t) {
+			case "nil":
+				writer.u8(0xf6);
+				break;
+			case "bool":
+				writer.u8(value.v ? 0xf5 : 0xf4);
+				break;
+			case "int":
+				if (value.v >= BigInt(0)) {
+					cborWriteHead(0, value.v, writer);
+				} else {
+					// Negative integer is encoded as -1 - n
+					cborWriteHead(1, BigInt(-1) - value.v, writer);
+				}
+				break;
+			case "f32":
+				writer.u8(0xfa);
+				writer.f32(value.v);
+				break;
+			case "f64":
+				writer.u8(0xfb);
+				writer.f64(value.v);
+				break;
+			case "str": {
+				const bytes = Buffer.from(value.v, "utf8");
+				cborWriteHead(3, bytes.byteLength, writer);
+				writer.raw(bytes);
+				break;
+			}
+			case "array":
+				cborWriteHead(4, value.v.length, writer);
+				value.v.forEach((item: Value) => cborWrite(item, writer));
+				break;
+			case "map":
+				cborWriteHead(5, value.v.length, writer);
+				value.v.forEach((entry: [string, Value]) => {
+					cborWrite(str(entry[0]), writer);
+					cborWrite(entry[1], writer);
+				});
+				break;
+		}
+	}
+
+	function cborArgument(reader: BodyReader, info: number): bigint {
+		switch (info) {
+			case 24: return reader.uint(1);
+			case 25: return reader.uint(2);
+			case 26: return reader.uint(4);
+			case 27: return reader.uint(8);
+			default:
+				if (info < 24) {
+					return BigInt(info);
+				}
+				throw new Error(
+					`Unsupported additional information ${info} (indefinite length isn't supported)`
+				);
+		}
+	}
+
+	// Half-precision float isn't produced by encoder, but partners might send it
+	function cborHalf(bits: number): number {
+		const sign = bits & 0x8000 ? -1 : 1;
+		const exp = (bits >> 10) & 0x1f;
+		const mant = bits & 0x3ff;
+		if (exp === 0) {
+			return sign * mant * Math.pow(2, -24);
+		} else if (exp === 31) {
+			return mant === 0 ? sign * Infinity : NaN;
+		}
+		return sign * (1 + mant / 1024) * Math.pow(2, exp - 15);
+	}
+
+	function cborRead(reader: BodyReader): Value {
+		const head = reader.u8();
+		const major = head >> 5;
+		const info = head & 0x1f;
+		switch (major) {
+			case 0:
+				return int(cborArgument(reader, info));
+			case 1:
+				return int(BigInt(-1) - cborArgument(reader, info));
+			case 3:
+				return str(reader.str(Number(cborArgument(reader, info))));
+			case 4: {
+				const len = Number(cborArgument(reader, info));
+				ProtocolLimits.check(ProtocolLimits.items(len));
+				return ProtocolLimits.nested(() => {
+					const values: Value[] = [];
+					for (let i = 0; i < len; i += 1) {
+						values.push(cborRead(reader));
+					}
+					return array(values);
+				});
+			}
+			case 5: {
+				const count = Number(cborArgument(reader, info));
+				ProtocolLimits.check(ProtocolLimits.items(count));
+				return ProtocolLimits.nested(() => {
+					const entries: Array<[string, Value]> = [];
+					for (let i = 0; i < count; i += 1) {
+						const key = cborRead(reader);
+						if (key.t !== "str") {
+							throw new Error(`Expecting string as key of map, but got ${key.t}`);
+						}
+						entries.push([key.v, cborRead(reader)]);
+					}
+					return { t: "map", v: entries } as Value;
+				});
+			}
+			case 7:
+				switch (info) {
+					case 20: return bool(false);
+					case 21: return bool(true);
+					case 22:
+					case 23:
+						return nil();
+					case 25: return f32(cborHalf(Number(reader.uint(2))));
+					case 26: return f32(reader.f32());
+					case 27: return f64(reader.f64());
+					default: throw new Error(`Unsupported cbor simple value ${info}`);
+				}
+			default:
+				throw new Error(`Unsupported cbor major type ${major}`);
+		}
+	}
+}
@@ -0,0 +1,178 @@
+// tslint:disable: no-namespace
+// injectable
+// MessagePack: https://github.com/msgpack/msgpack/blob/master/spec.md
+export namespace ProtocolCodec {
+	export function encodeBody(value: Value): ArrayBufferLike {
+		const writer = new BodyWriter();
+		msgpackWrite(value, writer);
+		return writer.get();
+	}
+
+	export function decodeBody(bytes: ArrayBufferLike): Value {
+		const reader = new BodyReader(bytes);
+		const value = msgpackRead(reader);
+		if (reader.rest() !== 0) {
+			throw new Error(
+				`Unexpected ${reader.rest()} bytes after msgpack value`
+			);
+		}
+		return value;
+	}
+
+	function msgpackWriteLen(
+		len: number,
+		fix: [number, number],
+		markers: [number, number, number],
+		writer: BodyWriter
+	) {
+		if (len < fix[1]) {
+			writer.u8(fix[0] | len);
+		} else if (len <= 0xff && markers[0] !== 0) {
+			writer.u8(markers[0]);
+			writer.uint(len, 1);
+		} else if (len <= 0xffff) {
+			writer.u8(markers[1]);
+			writer.uint(len, 2);
+		} else {
+			writer.u8(markers[2]);
+			writer.uint(len, 4);
+		}
+	}
+
+	function msgpackWriteInt(v: bigint, writer: BodyWriter) {
+		if (v >= BigInt(0)) {
+			if (v < BigInt(0x80)) {
+				writer.uint(v, 1);
+			} else if (v <= BigInt(0xff)) {
+				writer.u8(0xcc);
+				writer.uint(v, 1);
+			} else if (v <= BigInt(0xffff)) {
+				writer.u8(0xcd);
+				writer.uint(v, 2);
+			} else if (v <= BigInt(0xffffffff)) {
+				writer.u8(0xce);
+				writer.uint(v, 4);
+			} else {
+				writer.u8(0xcf);
+				writer.uint(v, 8);
+			}
+		} else if (v >= BigInt(-32)) {
+			writer.uint(v, 1);
+		} else if (v >= BigInt(-0x80)) {
+			writer.u8(0xd0);
+			writer.uint(v, 1);
+		} else if (v >= BigInt(-0x8000)) {
+			writer.u8(0xd1);
+			writer.uint(v, 2);
+		} else if (v >= BigInt(-0x80000000)) {
+			writer.u8(0xd2);
+			writer.uint(v, 4);
+		} else {
+			writer.u8(0xd3);
+			writer.uint(v, 8);
+		}
+	}
+
+	function msgpackWrite(value: Value, writer: BodyWriter) {
+		switch (value.t) {
+			case "nil":
+				writer.u8(0xc0);
+				break;
+			case "bool":
+				writer.u8(value.v ? 0xc3 : 0xc2);
+				break;
+			case "int":
+				msgpackWriteInt(value.v, writer);
+				break;
+			case "f32":
+				writer.u8(0xca);
+				writer.f32(value.v);
+				break;
+			case "f64":
+				writer.u8(0xcb);
+				writer.f64(value.v);
+				break;
+			case "str": {
+				const bytes = Buffer.from(value.v, "utf8");
+				msgpackWriteLen(bytes.byteLength, [0xa0, 32], [0xd9, 0xda, 0xdb], writer);
+				writer.raw(bytes);
+				break;
+			}
+			case "array":
+				msgpackWriteLen(value.v.length, [0x90, 16], [0, 0xdc, 0xdd], writer);
+				value.v.forEach((item: Value) => msgpackWrite(item, writer));
+				break;
+			case "map":
+				msgpackWriteLen(value.v.length, [0x80, 16], [0, 0xde, 0xdf], writer);
+				value.v.forEach((entry: [string, Value]) => {
+					msgpackWrite(str(entry[0]), writer);
+					msgpackWrite(entry[1], writer);
+				});
+				break;
+		}
+	}
+
+	function msgpackArray(reader: BodyReader, len: number): Value {
+		ProtocolLimits.check(ProtocolLimits.items(len));
+		return ProtocolLimits.nested(() => {
+			const values: Value[] = [];
+			for (let i = 0; i < len; i += 1) {
+				values.push(msgpackRead(reader));
+			}
+			return array(values);
+		});
+	}
+
+	function msgpackMap(reader: BodyReader, len: number): Value {
+		ProtocolLimits.check(ProtocolLimits.items(len));
+		return ProtocolLimits.nested(() => {
+			const entries: Array<[string, Value]> = [];
+			for (let i = 0; i < len; i += 1) {
+				const key = msgpackRead(reader);
+				if (key.t !== "str") {
+					throw new Error(`Expecting string as key of map, but got ${key.t}`);
+				}
+				entries.push([key.v, msgpackRead(reader)]);
+			}
+			return { t: "map", v: entries } as Value;
+		});
+	}
+
+	function msgpackRead(reader: BodyReader): Value {
+		const marker = reader.u8();
+		if (marker <= 0x7f) {
+			return int(marker);
+		} else if (marker <= 0x8f) {
+			return msgpackMap(reader, marker & 0x0f);
+		} else if (marker <= 0x9f) {
+			return msgpackArray(reader, marker & 0x0f);
+		} else if (marker <= 0xbf) {
+			return str(reader.str(marker & 0x1f));
+		} else if (marker >= 0xe0) {
+			return int(marker - 0x100);
+		}
+		switch (marker) {
+			case 0xc0: return nil();
+			case 0xc2: return bool(false);
+			case 0xc3: return bool(true);
+			case 0xca: return f32(reader.f32());
+			case 0xcb: return f64(reader.f64());
+			case 0xcc: return int(reader.uint(1));
+			case 0xcd: return int(reader.uint(2));
+			case 0xce: return int(reader.uint(4));
+			case 0xcf: return int(reader.uint(8));
+			case 0xd0: return int(BigInt.asIntN(8, reader.uint(1)));
+			case 0xd1: return int(BigInt.asIntN(16, reader.uint(2)));
+			case 0xd2: return int(BigInt.asIntN(32, reader.uint(4)));
+			case 0xd3: return int(BigInt.asIntN(64, reader.uint(8)));
+			case 0xd9: return str(reader.str(reader.len(1)));
+			case 0xda: return str(reader.str(reader.len(2)));
+			case 0xdb: return str(reader.str(reader.len(4)));
+			case 0xdc: return msgpackArray(reader, reader.len(2));
+			case 0xdd: return msgpackArray(reader, reader.len(4));
+			case 0xde: return msgpackMap(reader, reader.len(2));
+			case 0xdf: return msgpackMap(reader, reader.len(4));
+			default: throw new Error(`Unsupported msgpack type 0x${marker.toString(16)}`);
+		}
+	}
+}
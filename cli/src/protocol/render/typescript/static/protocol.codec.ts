@@ -0,0 +1,229 @@
+// tslint:disable: no-namespace
+// tslint:disable: max-classes-per-file
+// injectable
+// Body of message for self-describing codecs: structs are maps with names of fields,
+// enums are maps with one entry { "Variant": value }, empty optional fields are nil
+export namespace ProtocolCodec {
+	export type Value =
+		| { t: "nil" }
+		| { t: "bool"; v: boolean }
+		| { t: "int"; v: bigint }
+		| { t: "f32"; v: number }
+		| { t: "f64"; v: number }
+		| { t: "str"; v: string }
+		| { t: "array"; v: Value[] }
+		| { t: "map"; v: Array<[string, Value]> };
+
+	export type Reader<T> = (value: Value, path: string) => T;
+
+	export function nil(): Value {
+		return { t: "nil" };
+	}
+
+	export function bool(v: boolean): Value {
+		return { t: "bool", v };
+	}
+
+	export function int(v: number | bigint): Value {
+		return { t: "int", v: BigInt(v) };
+	}
+
+	export function f32(v: number): Value {
+		return { t: "f32", v };
+	}
+
+	export function f64(v: number): Value {
+		return { t: "f64", v };
+	}
+
+	export function str(v: string): Value {
+		return { t: "str", v };
+	}
+
+	export function array(v: Value[]): Value {
+		return { t: "array", v };
+	}
+
+	// Order of entries is kept as order of keys in object
+	export function map(entries: { [key: string]: Value }): Value {
+		return {
+			t: "map",
+			v: Object.keys(entries).map((key: string): [string, Value] => [
+				key,
+				entries[key],
+			]),
+		};
+	}
+
+	export function error(err: any): Error {
+		return err instanceof Error ? err : new Error(`${err}`);
+	}
+
+	export function unwrap<T>(value: T | Error, path: string): T {
+		if (value instanceof Error) {
+			throw new Error(`${path}: ${value.message}`);
+		}
+		return value;
+	}
+
+	export function object(value: Value, path: string): Value {
+		if (value.t !== "map") {
+			throw new Error(`${path}: expecting map`);
+		}
+		return value;
+	}
+
+	// Returns value of map's entry or nil if entry doesn't exist
+	export function field(value: Value, key: string): Value {
+		if (value.t === "map") {
+			const entry = value.v.find((e: [string, Value]) => e[0] === key);
+			if (entry !== undefined) {
+				return entry[1];
+			}
+		}
+		return nil();
+	}
+
+	export function boolean(value: Value, path: string): boolean {
+		if (value.t !== "bool") {
+			throw new Error(`${path}: expecting boolean`);
+		}
+		return value.v;
+	}
+
+	export function number(value: Value, path: string): number {
+		if (value.t === "int") {
+			return Number(value.v);
+		} else if (value.t === "f32" || value.t === "f64") {
+			return value.v;
+		}
+		throw new Error(`${path}: expecting number`);
+	}
+
+	export function bigint(value: Value, path: string): bigint {
+		if (value.t !== "int") {
+			throw new Error(`${path}: expecting integer`);
+		}
+		return value.v;
+	}
+
+	export function string(value: Value, path: string): string {
+		if (value.t !== "str") {
+			throw new Error(`${path}: expecting string`);
+		}
+		return value.v;
+	}
+
+	export function items<T>(value: Value, path: string, reader: Reader<T>): T[] {
+		if (value.t !== "array") {
+			throw new Error(`${path}: expecting array`);
+		}
+		return value.v.map((item: Value, i: number) => reader(item, `${path}[${i}]`));
+	}
+
+	export function optional<T>(
+		value: Value,
+		path: string,
+		reader: Reader<T>
+	): T | undefined {
+		return value.t === "nil" ? undefined : reader(value, path);
+	}
+
+	// Returns name and value of enum's variant: { "Variant": value }
+	export function variant(value: Value, path: string): [string, Value] {
+		if (value.t !== "map" || value.v.length !== 1) {
+			throw new Error(`${path}: expecting map with one entry`);
+		}
+		return value.v[0];
+	}
+
+	export class BodyWriter {
+		private _bytes: number[] = [];
+
+		public u8(v: number) {
+			this._bytes.push(v & 0xff);
+		}
+
+		// Big-endian unsigned integer; negative values are written as two's complement
+		public uint(v: number | bigint, size: number) {
+			const value = BigInt.asUintN(size * 8, BigInt(v));
+			for (let i = size - 1; i >= 0; i -= 1) {
+				this._bytes.push(
+					Number((value >> BigInt(i * 8)) & BigInt(0xff))
+				);
+			}
+		}
+
+		public f32(v: number) {
+			const view = new DataView(new ArrayBuffer(4));
+			view.setFloat32(0, v);
+			this.raw(new Uint8Array(view.buffer));
+		}
+
+		public f64(v: number) {
+			const view = new DataView(new ArrayBuffer(8));
+			view.setFloat64(0, v);
+			this.raw(new Uint8Array(view.buffer));
+		}
+
+		public raw(bytes: Uint8Array) {
+			bytes.forEach((b: number) => this._bytes.push(b));
+		}
+
+		public get(): ArrayBufferLike {
+			return new Uint8Array(this._bytes).buffer;
+		}
+	}
+
+	export class BodyReader {
+		private _bytes: Uint8Array;
+		private _pos: number = 0;
+
+		constructor(bytes: ArrayBufferLike) {
+			this._bytes = new Uint8Array(bytes);
+		}
+
+		public take(len: number): Uint8Array {
+			if (this._bytes.byteLength < this._pos + len) {
+				throw new Error(
+					`Unexpected end of value: expecting ${len} bytes at ${this._pos}`
+				);
+			}
+			const bytes = this._bytes.slice(this._pos, this._pos + len);
+			this._pos += len;
+			return bytes;
+		}
+
+		public u8(): number {
+			return this.take(1)[0];
+		}
+
+		public uint(size: number): bigint {
+			return this.take(size).reduce(
+				(acc: bigint, b: number) => (acc << BigInt(8)) | BigInt(b),
+				BigInt(0)
+			);
+		}
+
+		public len(size: number): number {
+			return Number(this.uint(size));
+		}
+
+		public f32(): number {
+			return new DataView(this.take(4).buffer).getFloat32(0);
+		}
+
+		public f64(): number {
+			return new DataView(this.take(8).buffer).getFloat64(0);
+		}
+
+		public str(len: number): string {
+			ProtocolLimits.check(ProtocolLimits.string(len));
+			return Buffer.from(this.take(len)).toString("utf8");
+		}
+
+		public rest(): number {
+			return this._bytes.byteLength - this._pos;
+		}
+	}
+}
@@ -0,0 +1,361 @@
+// tslint:disable: no-namespace
+// tslint:disable: max-classes-per-file
+// injectable
+// Compact body: | varint id | varint length | value | ... Unsigned integers are LEB128 varints,
+// signed integers are zigzag varints, empty optional fields aren't written at all
+export namespace ProtocolCompact {
+	export interface Type<T> {
+		// Self-delimited values are written into arrays without length
+		delimited: boolean;
+		write(value: T, writer: Writer): void;
+		// Not delimited values take rest of reader
+		read(reader: Reader): T;
+	}
+
+	export interface IEntity {
+		encode(): ArrayBufferLike;
+		decode(bytes: ArrayBufferLike): Error | any;
+	}
+
+	export interface IEnumeration<T> extends IEntity {
+		get(): T;
+		set(value: T): Error | undefined;
+	}
+
+	export class Writer {
+		private _bytes: number[] = [];
+
+		public u8(v: number) {
+			this._bytes.push(v & 0xff);
+		}
+
+		public varint(v: number | bigint) {
+			let value = BigInt.asUintN(64, BigInt(v));
+			while (value >= BigInt(0x80)) {
+				this._bytes.push(Number(value & BigInt(0x7f)) | 0x80);
+				value >>= BigInt(7);
+			}
+			this._bytes.push(Number(value));
+		}
+
+		public zigzag(v: number | bigint) {
+			const value = BigInt.asIntN(64, BigInt(v));
+			this.varint((value << BigInt(1)) ^ (value >> BigInt(63)));
+		}
+
+		public f32(v: number) {
+			const view = new DataView(new ArrayBuffer(4));
+			view.setFloat32(0, v, true);
+			this.raw(new Uint8Array(view.buffer));
+		}
+
+		public f64(v: number) {
+			const view = new DataView(new ArrayBuffer(8));
+			view.setFloat64(0, v, true);
+			this.raw(new Uint8Array(view.buffer));
+		}
+
+		public raw(bytes: Uint8Array) {
+			bytes.forEach((b: number) => this._bytes.push(b));
+		}
+
+		public item<T>(type: Type<T>, value: T) {
+			if (type.delimited) {
+				type.write(value, this);
+			} else {
+				const body = new Writer();
+				type.write(value, body);
+				this.varint(body.length());
+				this.raw(body.bytes());
+			}
+		}
+
+		public field<T>(id: number, type: Type<T>, value: T | undefined) {
+			if (value === undefined) {
+				return;
+			}
+			const body = new Writer();
+			type.write(value, body);
+			this.varint(id);
+			this.varint(body.length());
+			this.raw(body.bytes());
+		}
+
+		public length(): number {
+			return this._bytes.length;
+		}
+
+		public bytes(): Uint8Array {
+			return new Uint8Array(this._bytes);
+		}
+
+		public get(): ArrayBufferLike {
+			return this.bytes().buffer;
+		}
+	}
+
+	export class Reader {
+		private _bytes: Uint8Array;
+		private _pos: number = 0;
+
+		constructor(bytes: ArrayBufferLike | Uint8Array) {
+			this._bytes = new Uint8Array(bytes);
+		}
+
+		public take(len: number): Uint8Array {
+			if (this._bytes.byteLength < this._pos + len) {
+				throw new Error(
+					`Unexpected end of buffer: expecting ${len} bytes at ${this._pos}`
+				);
+			}
+			const bytes = this._bytes.slice(this._pos, this._pos + len);
+			this._pos += len;
+			return bytes;
+		}
+
+		public u8(): number {
+			return this.take(1)[0];
+		}
+
+		public varint(): bigint {
+			let value = BigInt(0);
+			let shift = BigInt(0);
+			while (true) {
+				const byte = this.u8();
+				// 10th byte can keep only one bit of u64
+				if (shift === BigInt(63) && byte > 1) {
+					throw new Error(`Varint is out of u64`);
+				}
+				value |= BigInt(byte & 0x7f) << shift;
+				if ((byte & 0x80) === 0) {
+					return value;
+				}
+				shift += BigInt(7);
+			}
+		}
+
+		public zigzag(): bigint {
+			const value = this.varint();
+			return (value >> BigInt(1)) ^ -(value & BigInt(1));
+		}
+
+		public len(): number {
+			const len = this.varint();
+			if (len > BigInt(this.rest())) {
+				throw new Error(
+					`Length ${len} is bigger than rest of buffer (${this.rest()} bytes)`
+				);
+			}
+			return Number(len);
+		}
+
+		public f32(): number {
+			return new DataView(this.take(4).buffer).getFloat32(0, true);
+		}
+
+		public f64(): number {
+			return new DataView(this.take(8).buffer).getFloat64(0, true);
+		}
+
+		public item<T>(type: Type<T>): T {
+			if (type.delimited) {
+				return type.read(this);
+			}
+			return whole(type, new Reader(this.take(this.len())));
+		}
+
+		public rest(): number {
+			return this._bytes.byteLength - this._pos;
+		}
+	}
+
+	export function error(err: any): Error {
+		return err instanceof Error ? err : new Error(`${err}`);
+	}
+
+	// Reads value, which should take all bytes of reader
+	export function whole<T>(type: Type<T>, reader: Reader): T {
+		const value = type.read(reader);
+		if (reader.rest() !== 0) {
+			throw new Error(`Unexpected ${reader.rest()} bytes after value`);
+		}
+		return value;
+	}
+
+	export function value<T>(type: Type<T>, reader: Reader, path: string): T {
+		try {
+			return whole(type, reader);
+		} catch (err) {
+			throw new Error(`${path}: ${error(err).message}`);
+		}
+	}
+
+	// Returns bodies of fields by ids; first field wins if id is repeated
+	export function fields(bytes: ArrayBufferLike): Map<number, Uint8Array> {
+		const reader = new Reader(bytes);
+		const found: Map<number, Uint8Array> = new Map();
+		while (reader.rest() > 0) {
+			const id = Number(reader.varint());
+			const body = reader.take(reader.len());
+			if (!found.has(id)) {
+				found.set(id, body);
+			}
+		}
+		return found;
+	}
+
+	export function field<T>(
+		found: Map<number, Uint8Array>,
+		id: number,
+		type: Type<T>,
+		path: string
+	): T {
+		const body = found.get(id);
+		if (body === undefined) {
+			throw new Error(`${path}: Field isn't found`);
+		}
+		return ProtocolLimits.nested(() => value(type, new Reader(body), path));
+	}
+
+	export function optional<T>(
+		found: Map<number, Uint8Array>,
+		id: number,
+		type: Type<T>,
+		path: string
+	): T | undefined {
+		const body = found.get(id);
+		return body === undefined
+			? undefined
+			: ProtocolLimits.nested(() => value(type, new Reader(body), path));
+	}
+
+	function scalar<T>(
+		write: (value: T, writer: Writer) => void,
+		read: (reader: Reader) => T
+	): Type<T> {
+		return { delimited: true, write, read };
+	}
+
+	function range(value: bigint, bits: number, signed: boolean): number {
+		const limited = signed
+			? BigInt.asIntN(bits, value)
+			: BigInt.asUintN(bits, value);
+		if (limited !== value) {
+			throw new Error(`Value ${value} is out of ${bits} bits`);
+		}
+		return Number(value);
+	}
+
+	export const u8 = scalar<number>(
+		(v: number, w: Writer) => w.u8(v),
+		(r: Reader) => r.u8()
+	);
+	export const i8 = scalar<number>(
+		(v: number, w: Writer) => w.u8(v),
+		(r: Reader) => (r.u8() << 24) >> 24
+	);
+	export const u16 = scalar<number>(
+		(v: number, w: Writer) => w.varint(v),
+		(r: Reader) => range(r.varint(), 16, false)
+	);
+	export const u32 = scalar<number>(
+		(v: number, w: Writer) => w.varint(v),
+		(r: Reader) => range(r.varint(), 32, false)
+	);
+	export const u64 = scalar<bigint>(
+		(v: bigint, w: Writer) => w.varint(v),
+		(r: Reader) => r.varint()
+	);
+	export const i16 = scalar<number>(
+		(v: number, w: Writer) => w.zigzag(v),
+		(r: Reader) => range(r.zigzag(), 16, true)
+	);
+	export const i32 = scalar<number>(
+		(v: number, w: Writer) => w.zigzag(v),
+		(r: Reader) => range(r.zigzag(), 32, true)
+	);
+	export const i64 = scalar<bigint>(
+		(v: bigint, w: Writer) => w.zigzag(v),
+		(r: Reader) => r.zigzag()
+	);
+	export const f32 = scalar<number>(
+		(v: number, w: Writer) => w.f32(v),
+		(r: Reader) => r.f32()
+	);
+	export const f64 = scalar<number>(
+		(v: number, w: Writer) => w.f64(v),
+		(r: Reader) => r.f64()
+	);
+	export const bool = scalar<boolean>(
+		(v: boolean, w: Writer) => w.u8(v ? 1 : 0),
+		(r: Reader) => r.u8() !== 0
+	);
+	export const str: Type<string> = {
+		delimited: false,
+		write: (v: string, w: Writer) => w.raw(Buffer.from(v, "utf8")),
+		read: (r: Reader) => {
+			ProtocolLimits.check(ProtocolLimits.string(r.rest()));
+			return Buffer.from(r.take(r.rest())).toString("utf8");
+		},
+	};
+
+	export function array<T>(type: Type<T>): Type<T[]> {
+		return {
+			delimited: false,
+			write: (v: T[], w: Writer) => v.forEach((item: T) => w.item(type, item)),
+			read: (r: Reader) => {
+				const items: T[] = [];
+				while (r.rest() > 0) {
+					ProtocolLimits.check(ProtocolLimits.items(items.length + 1));
+					try {
+						items.push(r.item(type));
+					} catch (err) {
+						throw new Error(`[${items.length}]: ${error(err).message}`);
+					}
+				}
+				return items;
+			},
+		};
+	}
+
+	export function entity<T extends IEntity>(create: () => T): Type<T> {
+		return {
+			delimited: false,
+			write: (v: T, w: Writer) => w.raw(new Uint8Array(v.encode())),
+			read: (r: Reader) => {
+				const inst = create();
+				const err = inst.decode(r.take(r.rest()).buffer);
+				if (err instanceof Error) {
+					throw err;
+				}
+				return inst;
+			},
+		};
+	}
+
+	// Empty body of enum means value isn't set (Defaults)
+	export function enumeration<T>(create: () => IEnumeration<T>): Type<T> {
+		return {
+			delimited: false,
+			write: (v: T, w: Writer) => {
+				const inst = create();
+				const err = inst.set(v);
+				if (err instanceof Error) {
+					throw err;
+				}
+				w.raw(new Uint8Array(inst.encode()));
+			},
+			read: (r: Reader) => {
+				if (r.rest() === 0) {
+					return {} as T;
+				}
+				const inst = create();
+				const err = inst.decode(r.take(r.rest()).buffer);
+				if (err instanceof Error) {
+					throw err;
+				}
+				return inst.get();
+			},
+		};
+	}
+}
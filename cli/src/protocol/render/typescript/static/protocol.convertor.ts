@@ -6,6 +6,11 @@ import { Storage } from "./protocol.convertor.storage";
 import { u32 } from "./protocol.primitives.u32";
 import { u64 } from "./protocol.primitives.u64";
 import { getPackingMiddleware, PackingMiddleware } from "./packing.middleware";
+import { PackingCompression } from "./packing.compression";
+import { PackingChecksum } from "./packing.checksum";
+import { MessageHeader } from "./packing.header";
+import { PackingCapabilities, IAgreement } from "./packing.capabilities";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export abstract class Convertor<T> {
@@ -177,6 +182,12 @@ export abstract class Convertor<T> {
 		}
 		let offset: number = 0;
 		do {
+			const limit: Error | undefined = ProtocolLimits.items(
+				selfs.length + 1
+			);
+			if (limit instanceof Error) {
+				return limit;
+			}
 			const len = buffer.readBigUInt64LE(offset);
 			if (isNaN(Number(len)) || !isFinite(Number(len))) {
 				return new Error(
@@ -207,7 +218,12 @@ export abstract class Convertor<T> {
 			this.signature()
 		);
 		const seq: ArrayBufferLike | Error = Primitives.u32.encode(sequence);
-		const ts = BigInt(new Date().getTime());
+		const peer: IAgreement = PackingCapabilities.peer(uuid);
+		const ts: bigint = MessageHeader.convertTs(
+			MessageHeader.now(),
+			MessageHeader.VERSION_TS_MS,
+			peer.version
+		);
 		const timestamp: ArrayBufferLike | Error = Primitives.u64.encode(ts);
 		if (id instanceof Error) {
 			throw new Error(
@@ -231,32 +247,67 @@ export abstract class Convertor<T> {
 				`Fail to encode timestamp (${ts}) due error: ${timestamp.message}`
 			);
 		}
+		const body: ArrayBufferLike = this.encode();
+		const oversize: Error | undefined = PackingCapabilities.check(
+			peer,
+			body.byteLength
+		);
+		if (oversize instanceof Error) {
+			throw oversize;
+		}
+		// Body is compressed before middleware, because middleware can encrypt it
+		const compressed = PackingCompression.compress(body, peer.compression);
 		const buffer: ArrayBufferLike | Error = (() => {
 			const middleware: PackingMiddleware | undefined =
 				getPackingMiddleware();
 			if (middleware instanceof PackingMiddleware) {
 				return middleware.encode(
-					this.encode(),
+					compressed.body,
 					this.getId(),
 					sequence,
 					uuid
 				);
 			} else {
-				return this.encode();
+				return compressed.body;
 			}
 		})();
 		if (buffer instanceof Error) {
 			throw buffer;
 		}
+		const overflow: Error | undefined = PackingCapabilities.check(
+			peer,
+			buffer.byteLength
+		);
+		if (overflow instanceof Error) {
+			throw overflow;
+		}
+		const checksum: boolean = PackingChecksum.get();
+		const flags: number =
+			compressed.flag |
+			(peer.version << MessageHeader.VERSION_SHIFT) |
+			(checksum ? MessageHeader.FLAG_CHECKSUM : 0);
+		// Highest byte of length is used for flags
 		const len: ArrayBufferLike | Error = Primitives.u64.encode(
-			BigInt(buffer.byteLength)
+			BigInt(buffer.byteLength) +
+				(BigInt(flags) << BigInt(MessageHeader.FLAGS_SHIFT))
 		);
 		if (len instanceof Error) {
 			throw new Error(
 				`Fail to encode len (${ts}) due error: ${len.message}`
 			);
 		}
-		return Tools.append([id, signature, seq, timestamp, len, buffer]);
+		if (!checksum) {
+			return Tools.append([id, signature, seq, timestamp, len, buffer]);
+		}
+		const crc: ArrayBufferLike | Error = Primitives.u32.encode(
+			PackingChecksum.crc32c(buffer)
+		);
+		if (crc instanceof Error) {
+			throw new Error(
+				`Fail to encode checksum due error: ${crc.message}`
+			);
+		}
+		return Tools.append([id, signature, seq, timestamp, len, crc, buffer]);
 	}
 
 	public abstract getSignature(): string;
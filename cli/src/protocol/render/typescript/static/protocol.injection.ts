@@ -7,3 +7,19 @@
 
 import * as Protocol from "@clibri/protocol";
 import { Primitives } from "@clibri/protocol";
+export {
+	CompressionAlgorithm,
+	ICompression,
+	setCompression,
+	getCompression,
+	setChecksum,
+	getChecksum,
+	ICapabilities,
+	IAgreement,
+	IClockSkew,
+	ClockSkew,
+	PackingCapabilities,
+	MessageHeader,
+	ILimits,
+	ProtocolLimits,
+} from "@clibri/protocol";
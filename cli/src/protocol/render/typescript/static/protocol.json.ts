@@ -0,0 +1,89 @@
+// tslint:disable: no-namespace
+// injectable
+// Canonical JSON form of messages: names of fields are taken from protocol, enums are
+// { "Variant": value }, i64/u64 are strings (JS cannot keep such numbers as number)
+export namespace ProtocolJson {
+	export type Reader<T> = (json: any, path: string) => T;
+
+	export function error(err: any): Error {
+		return err instanceof Error ? err : new Error(`${err}`);
+	}
+
+	export function unwrap<T>(value: T | Error, path: string): T {
+		if (value instanceof Error) {
+			throw new Error(`${path}: ${value.message}`);
+		}
+		return value;
+	}
+
+	export function object(json: any, path: string): any {
+		if (typeof json !== "object" || json === null || json instanceof Array) {
+			throw new Error(`${path}: expecting object`);
+		}
+		return json;
+	}
+
+	export function boolean(json: any, path: string): boolean {
+		if (typeof json !== "boolean") {
+			throw new Error(`${path}: expecting boolean`);
+		}
+		return json;
+	}
+
+	export function number(json: any, path: string): number {
+		if (typeof json !== "number") {
+			throw new Error(`${path}: expecting number`);
+		}
+		return json;
+	}
+
+	export function string(json: any, path: string): string {
+		if (typeof json !== "string") {
+			throw new Error(`${path}: expecting string`);
+		}
+		return json;
+	}
+
+	export function bigint(json: any, path: string): bigint {
+		if (
+			(typeof json === "string" && /^-?\d+$/.test(json)) ||
+			(typeof json === "number" && Number.isInteger(json))
+		) {
+			return BigInt(json);
+		}
+		throw new Error(`${path}: expecting integer as string`);
+	}
+
+	export function array<T>(json: any, path: string, reader: Reader<T>): T[] {
+		if (!(json instanceof Array)) {
+			throw new Error(`${path}: expecting array`);
+		}
+		return json.map((item: any, i: number) => reader(item, `${path}[${i}]`));
+	}
+
+	export function optional<T>(
+		json: any,
+		path: string,
+		reader: Reader<T>
+	): T | undefined {
+		return json === undefined || json === null ? undefined : reader(json, path);
+	}
+
+	// Returns name and value of enum's variant: { "Variant": value }
+	export function variant(json: any, path: string): [string, any] {
+		const keys = Object.keys(object(json, path));
+		if (keys.length !== 1) {
+			throw new Error(`${path}: expecting object with one field`);
+		}
+		return [keys[0], json[keys[0]]];
+	}
+
+	// Message with type tag: { "name": "Group.Message", "message": {...} }
+	export function tagged(name: string, message: any): any {
+		return { name, message };
+	}
+
+	export function tag(json: any): string {
+		return string(object(json, "message").name, "name");
+	}
+}
@@ -0,0 +1,128 @@
+// tslint:disable: no-namespace
+// injectable
+// Limits of incoming data. BufferReader checks size of frames and count of pending messages;
+// other limits are applied while message is decoded. Rust runtime has same limits and defaults
+export interface ILimits {
+	// Max length of package body in bytes
+	frame: number;
+	// Max length of string in bytes
+	string: number;
+	// Max count of items in array
+	items: number;
+	// Max nesting of structs and enums
+	depth: number;
+	// Max count of decoded messages, which aren't taken from buffer
+	pending: number;
+}
+
+export namespace ProtocolLimits {
+	interface IContext {
+		limits: ILimits;
+		depth: number;
+	}
+
+	// Decoding is synchronous, so limits of current decoding can be kept in module
+	let context: IContext | undefined;
+
+	export function defaults(): ILimits {
+		return {
+			frame: 64 * 1024 * 1024,
+			string: 16 * 1024 * 1024,
+			items: 4 * 1024 * 1024,
+			depth: 64,
+			pending: 4096,
+		};
+	}
+
+	// Applies limits to everything decoded by decode; without it nothing is limited
+	export function apply<T>(limits: ILimits, decode: () => T): T {
+		const prev: IContext | undefined = context;
+		context = { limits, depth: 0 };
+		try {
+			return decode();
+		} finally {
+			context = prev;
+		}
+	}
+
+	export function frame(len: number): Error | undefined {
+		const current: IContext | undefined = context;
+		if (current === undefined || len <= current.limits.frame) {
+			return undefined;
+		}
+		return new Error(
+			`Size of decompressed body (${len} bytes) is bigger than limit (${current.limits.frame} bytes)`
+		);
+	}
+
+	export function string(len: number): Error | undefined {
+		const current: IContext | undefined = context;
+		if (current === undefined || len <= current.limits.string) {
+			return undefined;
+		}
+		return new Error(
+			`Length of string (${len} bytes) is bigger than limit (${current.limits.string} bytes)`
+		);
+	}
+
+	export function items(count: number): Error | undefined {
+		const current: IContext | undefined = context;
+		if (current === undefined || count <= current.limits.items) {
+			return undefined;
+		}
+		return new Error(
+			`Count of items in array (${count}) is bigger than limit (${current.limits.items})`
+		);
+	}
+
+	// Returns error, if nesting is too deep; otherwise nesting is one level deeper till leave()
+	function enter(): Error | undefined {
+		const current: IContext | undefined = context;
+		if (current === undefined) {
+			return undefined;
+		}
+		if (current.depth >= current.limits.depth) {
+			return new Error(
+				`Nesting is deeper than limit (${current.limits.depth})`
+			);
+		}
+		current.depth += 1;
+		return undefined;
+	}
+
+	function leave() {
+		if (context !== undefined) {
+			context.depth -= 1;
+		}
+	}
+
+	// Throws, if nesting is too deep; used by codecs, which report errors with exceptions
+	export function nested<T>(decode: () => T): T {
+		check(enter());
+		try {
+			return decode();
+		} finally {
+			leave();
+		}
+	}
+
+	// Returns error, if nesting is too deep; used by binary decoding, which returns errors
+	export function depth<T>(decode: () => T | Error): T | Error {
+		const err: Error | undefined = enter();
+		if (err instanceof Error) {
+			return err;
+		}
+		try {
+			return decode();
+		} finally {
+			leave();
+		}
+	}
+
+	// Throws error of exceeded limit
+	export function check(err: Error | undefined) {
+		if (err instanceof Error) {
+			throw err;
+		}
+	}
+}
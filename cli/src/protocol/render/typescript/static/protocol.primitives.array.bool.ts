@@ -3,6 +3,7 @@
 
 import { u8 } from "./protocol.primitives.u8";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 import { bool } from "./protocol.primitives.bool";
 
 // injectable
@@ -39,6 +40,12 @@ export class ArrayBool extends Primitive<boolean[]> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / u8.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: boolean[] = [];
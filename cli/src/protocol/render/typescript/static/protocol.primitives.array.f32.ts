@@ -3,6 +3,7 @@
 
 import { f32 } from "./protocol.primitives.f32";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayF32 extends Primitive<number[]> {
@@ -38,6 +39,12 @@ export class ArrayF32 extends Primitive<number[]> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / f32.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: number[] = [];
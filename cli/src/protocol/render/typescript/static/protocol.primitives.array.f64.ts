@@ -3,6 +3,7 @@
 
 import { f64 } from "./protocol.primitives.f64";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayF64 extends Primitive<number[]> {
@@ -37,6 +38,12 @@ export class ArrayF64 extends Primitive<number[]> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / f64.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: number[] = [];
@@ -3,6 +3,7 @@
 
 import { i16 } from "./protocol.primitives.i16";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayI16 extends Primitive<number[]> {
@@ -38,6 +39,12 @@ export class ArrayI16 extends Primitive<number[]> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / i16.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: number[] = [];
@@ -3,6 +3,7 @@
 
 import { i64 } from "./protocol.primitives.i64";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayI64 extends Primitive<Array<bigint>> {
@@ -38,6 +39,12 @@ export class ArrayI64 extends Primitive<Array<bigint>> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / i64.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: Array<bigint> = [];
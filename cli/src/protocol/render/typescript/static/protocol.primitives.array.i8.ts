@@ -3,6 +3,7 @@
 
 import { i8 } from "./protocol.primitives.i8";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayI8 extends Primitive<number[]> {
@@ -38,6 +39,12 @@ export class ArrayI8 extends Primitive<number[]> {
 				} bytes`
 			);
 		}
+		const limit: Error | undefined = ProtocolLimits.items(
+			Math.floor(bytes.byteLength / i8.getSize())
+		);
+		if (limit instanceof Error) {
+			return limit;
+		}
 		try {
 			let offset: number = 0;
 			const array: number[] = [];
@@ -5,6 +5,7 @@ import * as Tools from "./tools/index";
 import { StrUTF8 } from "./protocol.primitives.string.utf8";
 import { u32 } from "./protocol.primitives.u32";
 import { Primitive } from "./protocol.primitives.interface";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class ArrayStrUTF8 extends Primitive<string[]> {
@@ -61,6 +62,12 @@ export class ArrayStrUTF8 extends Primitive<string[]> {
 		}
 		let offset: number = 0;
 		do {
+			const limit: Error | undefined = ProtocolLimits.items(
+				strings.length + 1
+			);
+			if (limit instanceof Error) {
+				return limit;
+			}
 			const len = buffer.readUInt32LE(offset);
 			if (isNaN(len) || !isFinite(len)) {
 				return new Error(`Invalid length of string in an array`);
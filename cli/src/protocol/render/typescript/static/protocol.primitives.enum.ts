@@ -6,6 +6,11 @@ import * as Primitives from "./protocol.primitives";
 import { ISigned } from "./protocol.primitives.interface";
 import { u16 } from "./protocol.primitives.u16";
 import { getPackingMiddleware, PackingMiddleware } from "./packing.middleware";
+import { PackingCompression } from "./packing.compression";
+import { PackingChecksum } from "./packing.checksum";
+import { MessageHeader } from "./packing.header";
+import { PackingCapabilities, IAgreement } from "./packing.capabilities";
+import { ProtocolLimits } from "./protocol.limits";
 
 // injectable
 export class Option<T> {
@@ -87,20 +92,22 @@ export abstract class Enum<T> {
 	}
 
 	public decode(bytes: ArrayBufferLike): Error | undefined {
-		const buffer = Buffer.from(bytes);
-		const id: number = buffer.readUInt16LE();
-		const target: ISigned<any> = this.getOptionValue(id);
-		const error: Error | undefined = target.decode(
-			bytes.slice(u16.getSize(), buffer.byteLength)
-		);
-		if (error instanceof Error) {
-			return error;
-		}
-		try {
-			this._value = new Option<any>(id, target);
-		} catch (e) {
-			return new Error(`Fail to decode due error: ${e}`);
-		}
+		return ProtocolLimits.depth<undefined>(() => {
+			const buffer = Buffer.from(bytes);
+			const id: number = buffer.readUInt16LE();
+			const target: ISigned<any> = this.getOptionValue(id);
+			const error: Error | undefined = target.decode(
+				bytes.slice(u16.getSize(), buffer.byteLength)
+			);
+			if (error instanceof Error) {
+				return error;
+			}
+			try {
+				this._value = new Option<any>(id, target);
+			} catch (e) {
+				return new Error(`Fail to decode due error: ${e}`);
+			}
+		});
 	}
 
 	public pack(sequence: number, uuid?: string): ArrayBufferLike {
@@ -109,7 +116,12 @@ export abstract class Enum<T> {
 			this.signature()
 		);
 		const seq: ArrayBufferLike | Error = Primitives.u32.encode(sequence);
-		const ts = BigInt(new Date().getTime());
+		const peer: IAgreement = PackingCapabilities.peer(uuid);
+		const ts: bigint = MessageHeader.convertTs(
+			MessageHeader.now(),
+			MessageHeader.VERSION_TS_MS,
+			peer.version
+		);
 		const timestamp: ArrayBufferLike | Error = Primitives.u64.encode(ts);
 		if (id instanceof Error) {
 			throw new Error(
@@ -133,32 +145,67 @@ export abstract class Enum<T> {
 				`Fail to encode timestamp (${ts}) due error: ${timestamp.message}`
 			);
 		}
+		const body: ArrayBufferLike = this.encode();
+		const oversize: Error | undefined = PackingCapabilities.check(
+			peer,
+			body.byteLength
+		);
+		if (oversize instanceof Error) {
+			throw oversize;
+		}
+		// Body is compressed before middleware, because middleware can encrypt it
+		const compressed = PackingCompression.compress(body, peer.compression);
 		const buffer: ArrayBufferLike | Error = (() => {
 			const middleware: PackingMiddleware | undefined =
 				getPackingMiddleware();
 			if (middleware instanceof PackingMiddleware) {
 				return middleware.encode(
-					this.encode(),
+					compressed.body,
 					this.getId(),
 					sequence,
 					uuid
 				);
 			} else {
-				return this.encode();
+				return compressed.body;
 			}
 		})();
 		if (buffer instanceof Error) {
 			throw buffer;
 		}
+		const overflow: Error | undefined = PackingCapabilities.check(
+			peer,
+			buffer.byteLength
+		);
+		if (overflow instanceof Error) {
+			throw overflow;
+		}
+		const checksum: boolean = PackingChecksum.get();
+		const flags: number =
+			compressed.flag |
+			(peer.version << MessageHeader.VERSION_SHIFT) |
+			(checksum ? MessageHeader.FLAG_CHECKSUM : 0);
+		// Highest byte of length is used for flags
 		const len: ArrayBufferLike | Error = Primitives.u64.encode(
-			BigInt(buffer.byteLength)
+			BigInt(buffer.byteLength) +
+				(BigInt(flags) << BigInt(MessageHeader.FLAGS_SHIFT))
 		);
 		if (len instanceof Error) {
 			throw new Error(
 				`Fail to encode len (${ts}) due error: ${len.message}`
 			);
 		}
-		return Tools.append([id, signature, seq, timestamp, len, buffer]);
+		if (!checksum) {
+			return Tools.append([id, signature, seq, timestamp, len, buffer]);
+		}
+		const crc: ArrayBufferLike | Error = Primitives.u32.encode(
+			PackingChecksum.crc32c(buffer)
+		);
+		if (crc instanceof Error) {
+			throw new Error(
+				`Fail to encode checksum due error: ${crc.message}`
+			);
+		}
+		return Tools.append([id, signature, seq, timestamp, len, crc, buffer]);
 	}
 
 	public abstract getAllowed(): string[];
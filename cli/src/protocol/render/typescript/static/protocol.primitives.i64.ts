@@ -5,8 +5,8 @@ import { CBits } from "./protocol.sizes";
 
 // injectable
 export class i64 extends Primitive<bigint> {
-	public static MIN: number = -Number.MAX_SAFE_INTEGER;
-	public static MAX: number = Number.MAX_SAFE_INTEGER;
+	public static MIN: bigint = BigInt("-9223372036854775808");
+	public static MAX: bigint = BigInt("9223372036854775807");
 
 	public static getSignature(): string {
 		return "i64";
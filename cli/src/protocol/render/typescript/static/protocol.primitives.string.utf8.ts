@@ -1,6 +1,7 @@
 // tslint:disable: class-name
 // tslint:disable: max-classes-per-file
 import { Primitive } from './protocol.primitives.interface';
+import { ProtocolLimits } from './protocol.limits';
 
 import { TextEncoder } from "util";
 
@@ -17,6 +18,10 @@ export class StrUTF8 extends Primitive<string> {
     }
 
     public static decode(bytes: ArrayBufferLike): string | Error {
+        const limit: Error | undefined = ProtocolLimits.string(bytes.byteLength);
+        if (limit instanceof Error) {
+            return limit;
+        }
         const decoder = new TextDecoder();
         return decoder.decode(bytes);
     }
@@ -5,8 +5,8 @@ import { CBits } from "./protocol.sizes";
 
 // injectable
 export class u64 extends Primitive<bigint> {
-	public static MIN: number = 0;
-	public static MAX: number = Number.MAX_SAFE_INTEGER;
+	public static MIN: bigint = BigInt(0);
+	public static MAX: bigint = BigInt("18446744073709551615");
 
 	public static getSignature(): string {
 		return "u64";
@@ -1,4 +1,7 @@
-use super::{stop, Enum, Field, Group, Struct};
+use super::{
+    attributes, hash, stop, Attributes, Codec, Encryption, Enum, Field, Group, PrimitiveTypes,
+    Struct,
+};
 
 pub const INTERNAL_SERVICE_GROUP: &str = "InternalServiceGroup";
 
@@ -13,7 +16,17 @@ pub struct Store {
     c_enum: Option<Enum>,
     c_field: Option<Field>,
     path: Vec<usize>,
+    // Hash of source file
     hash: String,
+    // Attributes defined with @derive and @attr for next struct or enum
+    c_attributes: Attributes,
+    defaults: Attributes,
+    codec: Codec,
+    encryption: Encryption,
+    // Paths of messages, which are sent before session is established (not encrypted)
+    unencrypted: Vec<String>,
+    views: bool,
+    unknown_fields: bool,
 }
 
 impl Store {
@@ -30,13 +43,114 @@ impl Store {
             c_group: None,
             path: vec![],
             hash,
+            c_attributes: Attributes::default(),
+            defaults: Attributes::default(),
+            codec: Codec::default(),
+            encryption: Encryption::default(),
+            unencrypted: vec![],
+            views: false,
+            unknown_fields: false,
         }
     }
 
+    /// Hash of protocol, which is checked on handshake. It's calculated from parsed entities
+    /// (names, types and ids), so formatting doesn't change it. Ids are part of wire format:
+    /// reordering, which changes ids, changes hash as well
     pub fn get_hash(&self) -> String {
+        hash::get_from_str(&self.get_canonical())
+            .unwrap_or_else(|e| stop!("Fail to get hash of protocol: {}", e))
+    }
+
+    /// Hash of source file of protocol; it isn't checked and can be used for debugging
+    pub fn get_source_hash(&self) -> String {
         self.hash.clone()
     }
 
+    // One line per entity; lines are sorted, so order of declarations doesn't matter
+    fn get_canonical(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        for group in self.groups.iter() {
+            lines.push(format!(
+                "group {}={}",
+                self.get_path_by_parent(group.id).join("."),
+                group.id
+            ));
+        }
+        for strct in self.structs.iter() {
+            let mut fields: Vec<&Field> = strct.fields.iter().collect();
+            fields.sort_by_key(|field| field.id);
+            lines.push(format!(
+                "struct {}={} {{{}}}",
+                self.get_canonical_name(strct.id),
+                strct.id,
+                fields
+                    .iter()
+                    .map(|field| format!(
+                        "{}={}:{}{}{}",
+                        field.name,
+                        field.id,
+                        match field.ref_type_id {
+                            Some(id) => self.get_canonical_name(id),
+                            None => field.kind.clone(),
+                        },
+                        if field.repeated { "[]" } else { "" },
+                        if field.optional { "?" } else { "" }
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(";")
+            ));
+        }
+        for enums in self.enums.iter() {
+            // Order of variants defines their indexes
+            lines.push(format!(
+                "enum {}={} {{{}}}",
+                self.get_canonical_name(enums.id),
+                enums.id,
+                enums
+                    .variants
+                    .iter()
+                    .map(|item| format!(
+                        "{}:{}{}",
+                        item.name,
+                        match (item.ref_type_id, item.types.clone()) {
+                            (Some(id), _) => self.get_canonical_name(id),
+                            (None, Some(kind)) =>
+                                PrimitiveTypes::get_entity_as_string(kind).unwrap_or_default(),
+                            (None, None) => String::new(),
+                        },
+                        if item.repeated { "[]" } else { "" }
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(";")
+            ));
+        }
+        lines.sort();
+        let mut unencrypted = self.unencrypted.clone();
+        unencrypted.sort();
+        lines.push(format!("codec {}", self.codec.as_str()));
+        lines.push(format!(
+            "encryption {} {}",
+            self.encryption.as_str(),
+            unencrypted.join(";")
+        ));
+        lines.join("\n")
+    }
+
+    fn get_canonical_name(&self, id: usize) -> String {
+        let (path, name) = if let Some(strct) = self.structs.iter().find(|s| s.id == id) {
+            (&strct.path, &strct.name)
+        } else if let Some(enums) = self.enums.iter().find(|e| e.id == id) {
+            (&enums.path, &enums.name)
+        } else {
+            stop!("Fail to find a struct or enum {}", id);
+        };
+        path.iter()
+            .chain(std::iter::once(name))
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
     pub fn add_service_struct(&mut self, name: String, mut fields: Vec<Field>) {
         if !self
             .groups
@@ -139,12 +253,9 @@ impl Store {
         self.sequence += 1;
         self.bind_struct_with_group(self.sequence);
         let parent = self.get_group_id();
-        self.c_struct = Some(Struct::new(
-            self.sequence,
-            parent,
-            name,
-            self.get_path_by_parent(parent),
-        ));
+        let mut strct = Struct::new(self.sequence, parent, name, self.get_path_by_parent(parent));
+        strct.attributes = std::mem::take(&mut self.c_attributes);
+        self.c_struct = Some(strct);
     }
 
     pub fn open_enum(&mut self, name: String) {
@@ -157,12 +268,9 @@ impl Store {
         self.sequence += 1;
         self.bind_enum_with_group(self.sequence);
         let parent = self.get_group_id();
-        self.c_enum = Some(Enum::new(
-            self.sequence,
-            parent,
-            name,
-            self.get_path_by_parent(parent),
-        ));
+        let mut enums = Enum::new(self.sequence, parent, name, self.get_path_by_parent(parent));
+        enums.attributes = std::mem::take(&mut self.c_attributes);
+        self.c_enum = Some(enums);
     }
 
     pub fn open_group(&mut self, name: String) {
@@ -172,6 +280,9 @@ impl Store {
         if self.c_enum.is_some() {
             stop!("Group cannot be defined inside enum");
         }
+        if !self.c_attributes.is_empty() {
+            stop!("Attributes can be defined only for struct or enum");
+        }
         let parent: usize = self.get_group_id();
         self.sequence += 1;
         self.bind_group_with_group(self.sequence);
@@ -179,6 +290,271 @@ impl Store {
         self.path.push(self.sequence);
     }
 
+    pub fn add_annotation(&mut self, name: &str, args: &[String]) -> Result<(), String> {
+        match name {
+            attributes::annotations::DERIVE => {
+                if args.is_empty() {
+                    return Err(String::from("@derive should have at least one argument"));
+                }
+                for arg in args.iter() {
+                    self.c_attributes.add_derive(arg)?;
+                }
+            }
+            attributes::annotations::ATTR => {
+                if args.len() != 1 {
+                    return Err(String::from("@attr should have exactly one argument"));
+                }
+                self.c_attributes.add_attr(&args[0])?;
+            }
+            _ => {
+                return Err(format!("Unknown annotation @{}", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets derives and attributes, which are applied to all structs and enums. Derives like
+    /// Eq or Hash cannot be applied to all if some type contains floats
+    pub fn set_defaults(&mut self, defaults: Attributes) -> Result<(), String> {
+        let derive = defaults.get_not_for_float();
+        if !derive.is_empty() {
+            let with_float = self
+                .structs
+                .iter()
+                .map(|s| (s.id, &s.name))
+                .chain(self.enums.iter().map(|e| (e.id, &e.name)))
+                .filter(|(id, _)| self.has_float(*id, &mut vec![]))
+                .map(|(_, name)| name.clone())
+                .collect::<Vec<String>>();
+            if !with_float.is_empty() {
+                return Err(format!(
+                    "Default derives {} cannot be applied to all types, because {} contain(s) f32 or f64. Use @derive({}) for types without floats instead",
+                    derive.join(", "),
+                    with_float.join(", "),
+                    derive.join(", ")
+                ));
+            }
+        }
+        self.defaults = defaults;
+        Ok(())
+    }
+
+    /// Sets codec of messages' body. Codec is part of hash, because it changes wire format
+    pub fn set_codec(&mut self, codec: Codec) -> Result<(), String> {
+        if self.codec == codec {
+            return Ok(());
+        }
+        if self.codec != Codec::Binary {
+            return Err(format!("Codec is already set to {}", self.codec.as_str()));
+        }
+        self.codec = codec;
+        Ok(())
+    }
+
+    pub fn get_codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// Sets encryption of messages' body. Encryption is part of hash, because handshake
+    /// requires own fields of HashRequest and HashResponse
+    pub fn set_encryption(
+        &mut self,
+        encryption: Encryption,
+        unencrypted: Vec<String>,
+    ) -> Result<(), String> {
+        if self.encryption == encryption {
+            return Ok(());
+        }
+        if self.encryption != Encryption::None {
+            return Err(format!(
+                "Encryption is already set to {}",
+                self.encryption.as_str()
+            ));
+        }
+        for path in unencrypted.iter() {
+            if self.get_struct_by_str_path(0, path).is_none() {
+                return Err(format!("Fail to find struct {}", path));
+            }
+        }
+        self.encryption = encryption;
+        self.unencrypted = unencrypted;
+        Ok(())
+    }
+
+    pub fn get_encryption(&self) -> Encryption {
+        self.encryption
+    }
+
+    /// Ids of messages, which are sent before session is established
+    pub fn get_unencrypted(&self) -> Vec<usize> {
+        self.unencrypted
+            .iter()
+            .filter_map(|path| self.get_struct_by_str_path(0, path).map(|strct| strct.id))
+            .collect()
+    }
+
+    /// Enables borrowed views of structs for rust. Views don't change wire format
+    pub fn set_views(&mut self, views: bool) {
+        self.views = views;
+    }
+
+    pub fn get_views(&self) -> bool {
+        self.views
+    }
+
+    /// Enables keeping of unknown properties of structs for rust (__unknown), so relays send
+    /// back fields of newer versions of protocol. Without it unknown properties are dropped
+    pub fn set_unknown_fields(&mut self, unknown_fields: bool) {
+        self.unknown_fields = unknown_fields;
+    }
+
+    pub fn get_unknown_fields(&self) -> bool {
+        self.unknown_fields
+    }
+
+    /// Checks, that derives like Eq or Hash aren't requested for types with floats
+    pub fn validate_attributes(&self) -> Result<(), String> {
+        if !self.c_attributes.is_empty() {
+            return Err(String::from(
+                "Attributes are defined, but no struct or enum follows them",
+            ));
+        }
+        let entities = self
+            .structs
+            .iter()
+            .map(|s| (s.id, &s.name, &s.attributes))
+            .chain(self.enums.iter().map(|e| (e.id, &e.name, &e.attributes)));
+        for (id, name, attributes) in entities {
+            let derive = attributes.get_not_for_float();
+            if !derive.is_empty() && self.has_float(id, &mut vec![]) {
+                return Err(format!(
+                    "{} cannot derive {}, because it contains f32 or f64",
+                    name,
+                    derive.join(", ")
+                ));
+            }
+            // Derived trait requires the same trait for types of fields
+            for (field, ref_type_id) in self.get_refs(id) {
+                let (ref_name, ref_attributes) = self.get_entity_attributes(ref_type_id);
+                let missed = attributes
+                    .derive
+                    .iter()
+                    .filter(|d| {
+                        !attributes::is_base(d) && !attributes::contains(&ref_attributes.derive, d)
+                    })
+                    .cloned()
+                    .collect::<Vec<String>>();
+                if !missed.is_empty() {
+                    return Err(format!(
+                        "{} cannot derive {}, because type {} of {} doesn't derive it. Add @derive({}) to {}",
+                        name,
+                        missed.join(", "),
+                        ref_name,
+                        field,
+                        missed.join(", "),
+                        ref_name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Fields (or variants) of struct (or enum), which refer to other struct or enum
+    fn get_refs(&self, id: usize) -> Vec<(String, usize)> {
+        if let Some(strct) = self.structs.iter().find(|s| s.id == id) {
+            strct
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    field
+                        .ref_type_id
+                        .map(|ref_type_id| (format!("field {}", field.name), ref_type_id))
+                })
+                .collect()
+        } else if let Some(enums) = self.enums.iter().find(|e| e.id == id) {
+            enums
+                .variants
+                .iter()
+                .filter_map(|item| {
+                    item.ref_type_id
+                        .map(|ref_type_id| (format!("variant {}", item.name), ref_type_id))
+                })
+                .collect()
+        } else {
+            vec![]
+        }
+    }
+
+    fn get_entity_attributes(&self, id: usize) -> (&String, &Attributes) {
+        if let Some(strct) = self.structs.iter().find(|s| s.id == id) {
+            (&strct.name, &strct.attributes)
+        } else if let Some(enums) = self.enums.iter().find(|e| e.id == id) {
+            (&enums.name, &enums.attributes)
+        } else {
+            stop!("Fail to find a struct or enum {}", id);
+        }
+    }
+
+    /// Derives of struct or enum: base, default and own ones
+    pub fn get_derive(&self, id: usize) -> Vec<String> {
+        let (_, own) = self.get_entity_attributes(id);
+        attributes::merge(&[&self.defaults.derive, &own.derive])
+    }
+
+    pub fn get_attrs(&self, id: usize) -> Vec<String> {
+        let own = if let Some(strct) = self.structs.iter().find(|s| s.id == id) {
+            &strct.attributes
+        } else if let Some(enums) = self.enums.iter().find(|e| e.id == id) {
+            &enums.attributes
+        } else {
+            stop!("Fail to find a struct or enum {}", id);
+        };
+        let mut attrs = self.defaults.attrs.clone();
+        for attr in own.attrs.iter() {
+            if !attrs.contains(attr) {
+                attrs.push(attr.clone());
+            }
+        }
+        attrs
+    }
+
+    /// Derives of AvailableMessages. Only default derives are used, because they are applied
+    /// to all nested types
+    pub fn get_messages_derive(&self) -> Vec<String> {
+        attributes::merge(&[&self.defaults.derive])
+    }
+
+    fn has_float(&self, id: usize, visited: &mut Vec<usize>) -> bool {
+        if visited.contains(&id) {
+            return false;
+        }
+        visited.push(id);
+        if let Some(strct) = self.structs.iter().find(|s| s.id == id) {
+            strct.fields.iter().any(|field| {
+                if let Some(ref_type_id) = field.ref_type_id {
+                    self.has_float(ref_type_id, visited)
+                } else {
+                    field.kind == PrimitiveTypes::ctypes::Tf32
+                        || field.kind == PrimitiveTypes::ctypes::Tf64
+                }
+            })
+        } else if let Some(enums) = self.enums.iter().find(|e| e.id == id) {
+            enums.variants.iter().any(|item| {
+                if let Some(ref_type_id) = item.ref_type_id {
+                    self.has_float(ref_type_id, visited)
+                } else {
+                    matches!(
+                        item.types,
+                        Some(PrimitiveTypes::ETypes::Ef32) | Some(PrimitiveTypes::ETypes::Ef64)
+                    )
+                }
+            })
+        } else {
+            false
+        }
+    }
+
     pub fn set_field_type(&mut self, type_str: &str) {
         if self.c_struct.is_none() {
             stop!("Fail to create new field, because no open struct.");
@@ -1,4 +1,4 @@
-use super::{stop, Field};
+use super::{stop, Attributes, Field};
 
 #[derive(Debug, Clone)]
 pub struct Struct {
@@ -7,6 +7,7 @@ pub struct Struct {
     pub name: String,
     pub fields: Vec<Field>,
     pub path: Vec<String>,
+    pub attributes: Attributes,
 }
 
 impl Struct {
@@ -17,6 +18,7 @@ impl Struct {
             name,
             fields: vec![],
             path,
+            attributes: Attributes::default(),
         }
     }
 
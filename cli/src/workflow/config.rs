@@ -1,5 +1,6 @@
 use super::{
-    ENext, EntityOut, EntityParser, Field, PrimitiveTypes, Protocol, Target, INTERNAL_SERVICE_GROUP,
+    Codec, ENext, Encryption, EntityOut, EntityParser, Field, PrimitiveTypes, Protocol, Target,
+    INTERNAL_SERVICE_GROUP,
 };
 
 mod key_words {
@@ -7,9 +8,33 @@ mod key_words {
     pub const CONSUMER: &str = "Consumer";
     pub const SELF_KEY: &str = "SelfKey";
     pub const ASSIGNED_KEY: &str = "AssignedKey";
+    pub const HANDLERS: &str = "Handlers";
+    pub const CODEC: &str = "Codec";
+    pub const COMPRESSION: &str = "Compression";
+    pub const COMPRESSION_THRESHOLD: &str = "CompressionThreshold";
+    pub const ENCRYPTION: &str = "Encryption";
+    pub const CHECKSUM: &str = "Checksum";
     pub const ALIAS: &str = "&config";
 }
 
+pub mod handlers {
+    pub const FILES: &str = "files";
+    pub const TRAIT: &str = "trait";
+}
+
+pub mod compression {
+    pub const NONE: &str = "none";
+    pub const DEFLATE: &str = "deflate";
+    pub const LZ4: &str = "lz4";
+    // Bodies smaller than threshold (in bytes) are sent without compression
+    pub const DEFAULT_THRESHOLD: usize = 1024;
+}
+
+pub mod checksum {
+    pub const NONE: &str = "none";
+    pub const CRC32C: &str = "crc32c";
+}
+
 pub mod names {
     pub const HASH_REQUEST_STRUCT: &str = "HashRequest";
     pub const HASH_RESPONSE_STRUCT: &str = "HashResponse";
@@ -37,6 +62,80 @@ enum Pending {
     Consumer,
     SelfKey(String),
     AssignedKey(String),
+    Handlers,
+    Codec,
+    Compression,
+    CompressionThreshold,
+    Encryption,
+    Checksum,
+}
+
+// How handlers of requests, beacons and events are delivered to user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handlers {
+    // Stub file per handler
+    #[default]
+    Files,
+    // Trait with method per handler
+    Trait,
+}
+
+impl Handlers {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Handlers::Files => handlers::FILES,
+            Handlers::Trait => handlers::TRAIT,
+        }
+    }
+}
+
+// Default compression of packages' bodies; it can be changed via options of producer and consumer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Deflate,
+    Lz4,
+}
+
+impl Compression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::None => compression::NONE,
+            Compression::Deflate => compression::DEFLATE,
+            Compression::Lz4 => compression::LZ4,
+        }
+    }
+
+    // Name of variant of CompressionAlgorithm in generated rust and typescript code
+    pub fn as_variant(&self) -> &'static str {
+        match self {
+            Compression::None => "None",
+            Compression::Deflate => "Deflate",
+            Compression::Lz4 => "Lz4",
+        }
+    }
+}
+
+// Default checksum of packages' bodies; it can be changed via options of producer and consumer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Checksum {
+    #[default]
+    None,
+    Crc32c,
+}
+
+impl Checksum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Checksum::None => checksum::NONE,
+            Checksum::Crc32c => checksum::CRC32C,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        *self != Checksum::None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +145,12 @@ pub struct Config {
     pub self_key: Option<String>,
     pub self_key_response: String,
     pub assigned_key: Option<String>,
+    pub handlers: Option<Handlers>,
+    pub codec: Option<Codec>,
+    pub compression: Option<Compression>,
+    pub compression_threshold: Option<usize>,
+    pub encryption: Option<Encryption>,
+    pub checksum: Option<Checksum>,
     closed: bool,
     expectation: Vec<EExpectation>,
     pending: Pending,
@@ -70,6 +175,12 @@ impl Config {
                 names::DEFAULT_SELF_KEY_RESPONSE_STRUCT
             ),
             assigned_key: None,
+            handlers: None,
+            codec: None,
+            compression: None,
+            compression_threshold: None,
+            encryption: None,
+            checksum: None,
             closed: false,
             expectation: vec![EExpectation::Open],
             pending: Pending::Nothing,
@@ -77,14 +188,22 @@ impl Config {
         }
     }
 
-    fn add_producer(&mut self, alias: String) -> Result<(), String> {
-        let target: Target = if alias == *"rust" {
-            Target::Rust
-        } else if alias == *"typescript" {
-            Target::TypeScript
+    fn get_target(alias: &str) -> Option<Target> {
+        if alias == "rust" {
+            Some(Target::Rust)
+        } else if alias == "typescript" {
+            Some(Target::TypeScript)
+        } else if !alias.is_empty() && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            Some(Target::External(alias.to_owned()))
         } else {
-            return Err(format!("Unknown producer target {}", alias));
-        };
+            None
+        }
+    }
+
+    fn add_producer(&mut self, alias: String) -> Result<(), String> {
+        let target: Target = Self::get_target(&alias)
+            .ok_or(format!("Unknown producer target {}", alias))?;
         if self.producer.contains(&target) {
             Err(format!(
                 "Target {} has been added already to producer",
@@ -97,13 +216,8 @@ impl Config {
     }
 
     fn add_consumer(&mut self, alias: String) -> Result<(), String> {
-        let target: Target = if alias == *"rust" {
-            Target::Rust
-        } else if alias == *"typescript" {
-            Target::TypeScript
-        } else {
-            return Err(format!("Unknown consumer target {}", alias));
-        };
+        let target: Target = Self::get_target(&alias)
+            .ok_or(format!("Unknown consumer target {}", alias))?;
         if self.consumer.contains(&target) {
             Err(format!(
                 "Target {} has been added already to consumer",
@@ -133,7 +247,96 @@ impl Config {
         }
     }
 
+    fn set_handlers(&mut self, value: String) -> Result<(), String> {
+        if self.handlers.is_some() {
+            return Err(String::from("Handlers are already defined"));
+        }
+        self.handlers = Some(match value.as_str() {
+            handlers::FILES => Handlers::Files,
+            handlers::TRAIT => Handlers::Trait,
+            _ => {
+                return Err(format!(
+                    "Invalid value of Handlers: {}. Available: {}, {}",
+                    value,
+                    handlers::FILES,
+                    handlers::TRAIT
+                ))
+            }
+        });
+        Ok(())
+    }
+
+    fn set_codec(&mut self, value: String) -> Result<(), String> {
+        if self.codec.is_some() {
+            return Err(String::from("Codec is already defined"));
+        }
+        self.codec = Some(Codec::parse(&value)?);
+        Ok(())
+    }
+
+    fn set_compression(&mut self, value: String) -> Result<(), String> {
+        if self.compression.is_some() {
+            return Err(String::from("Compression is already defined"));
+        }
+        self.compression = Some(match value.as_str() {
+            compression::NONE => Compression::None,
+            compression::DEFLATE => Compression::Deflate,
+            compression::LZ4 => Compression::Lz4,
+            _ => {
+                return Err(format!(
+                    "Invalid value of Compression: {}. Available: {}, {}, {}",
+                    value,
+                    compression::NONE,
+                    compression::DEFLATE,
+                    compression::LZ4
+                ))
+            }
+        });
+        Ok(())
+    }
+
+    fn set_compression_threshold(&mut self, value: String) -> Result<(), String> {
+        if self.compression_threshold.is_some() {
+            return Err(String::from("Compression threshold is already defined"));
+        }
+        self.compression_threshold = Some(value.parse::<usize>().map_err(|_| {
+            format!(
+                "Invalid value of CompressionThreshold: {}. Expected size in bytes",
+                value
+            )
+        })?);
+        Ok(())
+    }
+
+    fn set_encryption(&mut self, value: String) -> Result<(), String> {
+        if self.encryption.is_some() {
+            return Err(String::from("Encryption is already defined"));
+        }
+        self.encryption = Some(Encryption::parse(&value)?);
+        Ok(())
+    }
+
+    fn set_checksum(&mut self, value: String) -> Result<(), String> {
+        if self.checksum.is_some() {
+            return Err(String::from("Checksum is already defined"));
+        }
+        self.checksum = Some(match value.as_str() {
+            checksum::NONE => Checksum::None,
+            checksum::CRC32C => Checksum::Crc32c,
+            _ => {
+                return Err(format!(
+                    "Invalid value of Checksum: {}. Available: {}, {}",
+                    value,
+                    checksum::NONE,
+                    checksum::CRC32C
+                ))
+            }
+        });
+        Ok(())
+    }
+
     fn close(&mut self, protocol: &mut Protocol) -> Result<(), String> {
+        protocol.set_codec(self.get_codec())?;
         if let Some(self_key) = self.self_key.as_ref() {
             if protocol.find_by_str_path(0, self_key).is_none() {
                 return Err(format!("Self key {} isn't defined in protocol", self_key));
@@ -195,29 +398,69 @@ impl Config {
         } else if self.consumer.is_empty() {
             Err(String::from("No targets for consumer has been found"))
         } else {
-            protocol.add_service_struct(
-                names::HASH_REQUEST_STRUCT.to_owned(),
-                vec![
-                    Field::create_not_assigned_primitive(
-                        String::from("protocol"),
-                        PrimitiveTypes::ETypes::Estr,
-                        false,
-                    ),
-                    Field::create_not_assigned_primitive(
-                        String::from("workflow"),
-                        PrimitiveTypes::ETypes::Estr,
-                        false,
-                    ),
-                ],
-            );
-            protocol.add_service_struct(
-                names::HASH_RESPONSE_STRUCT.to_owned(),
-                vec![Field::create_not_assigned_primitive(
-                    String::from("error"),
+            let encryption = self.get_encryption();
+            if encryption != Encryption::None
+                && self
+                    .producer
+                    .iter()
+                    .chain(self.consumer.iter())
+                    .any(|target| !matches!(target, Target::Rust))
+            {
+                return Err(format!(
+                    "Encryption {} is supported only if producer and consumer are rust",
+                    encryption.as_str()
+                ));
+            }
+            let mut hash_request = vec![
+                Field::create_not_assigned_primitive(
+                    String::from("protocol"),
                     PrimitiveTypes::ETypes::Estr,
-                    true,
-                )],
-            );
+                    false,
+                ),
+                Field::create_not_assigned_primitive(
+                    String::from("workflow"),
+                    PrimitiveTypes::ETypes::Estr,
+                    false,
+                ),
+            ];
+            let mut hash_response = vec![Field::create_not_assigned_primitive(
+                String::from("error"),
+                PrimitiveTypes::ETypes::Estr,
+                true,
+            )];
+            // Capabilities of consumer and producer; settings of packages are agreed per peer.
+            // Fields are optional, so runtimes before negotiation can be handled as legacy peers
+            for fields in [&mut hash_request, &mut hash_response] {
+                for (name, kind, repeated) in [
+                    ("version", PrimitiveTypes::ETypes::Eu8, false),
+                    ("compression", PrimitiveTypes::ETypes::Eu8, true),
+                    ("encryption", PrimitiveTypes::ETypes::Estr, true),
+                    ("codec", PrimitiveTypes::ETypes::Estr, false),
+                    ("frame", PrimitiveTypes::ETypes::Eu64, false),
+                    ("heartbeat", PrimitiveTypes::ETypes::Ebool, false),
+                ] {
+                    let mut field =
+                        Field::create_not_assigned_primitive(String::from(name), kind, true);
+                    if repeated {
+                        field.set_as_repeated();
+                    }
+                    fields.push(field);
+                }
+            }
+            if encryption != Encryption::None {
+                // Public X25519 keys of consumer and producer
+                for fields in [&mut hash_request, &mut hash_response] {
+                    let mut key = Field::create_not_assigned_primitive(
+                        String::from("key"),
+                        PrimitiveTypes::ETypes::Eu8,
+                        false,
+                    );
+                    key.set_as_repeated();
+                    fields.push(key);
+                }
+            }
+            protocol.add_service_struct(names::HASH_REQUEST_STRUCT.to_owned(), hash_request);
+            protocol.add_service_struct(names::HASH_RESPONSE_STRUCT.to_owned(), hash_response);
             protocol.add_service_struct(
                 names::BEACON_CONFIRMATION_RESPONSE.to_owned(),
                 vec![Field::create_not_assigned_primitive(
@@ -227,6 +470,20 @@ impl Config {
                 )],
             );
             protocol.add_service_struct(names::CONNECT_CONFIRMATION_BEACON.to_owned(), vec![]);
+            protocol.set_encryption(
+                encryption,
+                vec![
+                    self.get_self()?,
+                    self.self_key_response.clone(),
+                    format!("{}.{}", INTERNAL_SERVICE_GROUP, names::HASH_REQUEST_STRUCT),
+                    format!("{}.{}", INTERNAL_SERVICE_GROUP, names::HASH_RESPONSE_STRUCT),
+                    format!(
+                        "{}.{}",
+                        INTERNAL_SERVICE_GROUP,
+                        names::CONNECT_CONFIRMATION_BEACON
+                    ),
+                ],
+            )?;
             self.closed = true;
             self.prev = None;
             Ok(())
@@ -248,6 +505,31 @@ impl Config {
             Err(String::from("Assigned key isn't defined for workflow"))
         }
     }
+
+    pub fn get_handlers(&self) -> Handlers {
+        self.handlers.unwrap_or_default()
+    }
+
+    pub fn get_codec(&self) -> Codec {
+        self.codec.unwrap_or_default()
+    }
+
+    pub fn get_compression(&self) -> Compression {
+        self.compression.unwrap_or_default()
+    }
+
+    pub fn get_compression_threshold(&self) -> usize {
+        self.compression_threshold
+            .unwrap_or(compression::DEFAULT_THRESHOLD)
+    }
+
+    pub fn get_encryption(&self) -> Encryption {
+        self.encryption.unwrap_or_default()
+    }
+
+    pub fn get_checksum(&self) -> Checksum {
+        self.checksum.unwrap_or_default()
+    }
 }
 
 impl EntityParser for Config {
@@ -314,6 +596,18 @@ impl EntityParser for Config {
                                     return Err(String::from("Self Key is already defined"));
                                 }
                                 self.pending = Pending::SelfKey(String::new());
+                            } else if word == key_words::HANDLERS {
+                                self.pending = Pending::Handlers;
+                            } else if word == key_words::CODEC {
+                                self.pending = Pending::Codec;
+                            } else if word == key_words::COMPRESSION {
+                                self.pending = Pending::Compression;
+                            } else if word == key_words::COMPRESSION_THRESHOLD {
+                                self.pending = Pending::CompressionThreshold;
+                            } else if word == key_words::ENCRYPTION {
+                                self.pending = Pending::Encryption;
+                            } else if word == key_words::CHECKSUM {
+                                self.pending = Pending::Checksum;
                             } else {
                                 return Err(format!("Unexpected keyword: {}", word));
                             }
@@ -356,6 +650,30 @@ impl EntityParser for Config {
                                 EExpectation::Semicolon,
                             ];
                         }
+                        Pending::Handlers => {
+                            self.set_handlers(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
+                        Pending::Codec => {
+                            self.set_codec(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
+                        Pending::Compression => {
+                            self.set_compression(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
+                        Pending::CompressionThreshold => {
+                            self.set_compression_threshold(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
+                        Pending::Encryption => {
+                            self.set_encryption(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
+                        Pending::Checksum => {
+                            self.set_checksum(word)?;
+                            self.expectation = vec![EExpectation::Semicolon];
+                        }
                         Pending::SelfKey(path_to_struct) => {
                             self.pending = Pending::SelfKey(format!(
                                 "{}{}{}",
@@ -431,6 +749,12 @@ impl EntityParser for Config {
                                 return Err(e);
                             }
                         }
+                        Pending::Handlers
+                        | Pending::Codec
+                        | Pending::Compression
+                        | Pending::CompressionThreshold
+                        | Pending::Encryption
+                        | Pending::Checksum => {}
                     };
                     self.pending = Pending::Nothing;
                     self.expectation = vec![EExpectation::Word];
@@ -7,11 +7,14 @@ pub mod store;
 use super::{
     helpers::{chars, hash},
     protocol::{
+        codec::Codec,
+        encryption::Encryption,
         fields::Field,
         store::{Store as Protocol, INTERNAL_SERVICE_GROUP},
         types::PrimitiveTypes,
     },
     render::Target,
+    stop,
 };
 use beacon::{Beacons, Broadcast};
 use config::Config;
@@ -66,6 +69,8 @@ pub struct Parser {
     cursor: usize,
     content: String,
     store: Store,
+    // Word after ":" is a value and can start with digit (CompressionThreshold: 512)
+    value: bool,
 }
 
 impl Parser {
@@ -75,6 +80,7 @@ impl Parser {
             cursor: 0,
             content: String::new(),
             store: Store::new(hash::get(&src)?),
+            value: false,
         })
     }
 
@@ -88,6 +94,7 @@ impl Parser {
         loop {
             match self.next(content.clone()) {
                 Ok(enext) => {
+                    self.value = matches!(enext, ENext::ValueDelimiter(_));
                     let mut offset: usize = match enext.clone() {
                         ENext::Word((word, offset, _)) => {
                             if opened.is_none() {
@@ -224,7 +231,7 @@ impl Parser {
                     char
                 )));
             }
-            if char.is_ascii_digit() && str.is_empty() {
+            if char.is_ascii_digit() && str.is_empty() && !self.value {
                 return Err(ENextErr::NumericFirst());
             }
             if char.is_ascii_whitespace() && str.is_empty() {
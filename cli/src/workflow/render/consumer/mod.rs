@@ -5,11 +5,6 @@ pub mod rust;
 pub mod typescript;
 
 use super::{
-    helpers,
-    ImplementationRender,
-    workflow,
-    Protocol,
-    ProtocolRender,
-    ProtocolRustRender,
-    ProtocolTypescriptRender,
-};
\ No newline at end of file
+    crates, helpers, protocol, workflow, ImplementationRender, Protocol, ProtocolRender,
+    ProtocolRustRender, ProtocolTypescriptRender,
+};
@@ -6,16 +6,26 @@ pub mod render_controller;
 pub mod render_options;
 pub mod render_protocol;
 pub mod render_static;
+pub mod render_traits;
 
 use super::{
-    helpers, workflow, workflow::beacon::Broadcast, workflow::store::Store as WorkflowStore,
-    ImplementationRender, Protocol, ProtocolRender, ProtocolRustRender,
+    crates, helpers, protocol, workflow, workflow::beacon::Broadcast, workflow::config::Handlers,
+    workflow::store::Store as WorkflowStore, ImplementationRender, Protocol, ProtocolRender,
+    ProtocolRustRender,
 };
 use std::path::Path;
 
-pub struct RustRender {}
+pub struct RustRender {
+    // Name of crates, if producer and consumer are rendered as standalone crates
+    crate_name: Option<String>,
+}
 
 impl RustRender {
+    pub fn with_crate(mut self, crate_name: Option<&str>) -> Self {
+        self.crate_name = crate_name.map(|name| name.to_owned());
+        self
+    }
+
     fn get_all_broadcasts(&self, store: &WorkflowStore) -> Vec<Broadcast> {
         let mut broadcasts: Vec<Broadcast> = vec![];
         for request in &store.requests {
@@ -50,7 +60,7 @@ impl RustRender {
 
 impl ImplementationRender<ProtocolRustRender> for RustRender {
     fn new() -> Self {
-        RustRender {}
+        RustRender { crate_name: None }
     }
 
     fn render(
@@ -60,17 +70,27 @@ impl ImplementationRender<ProtocolRustRender> for RustRender {
         protocol: &mut Protocol,
         protocol_render: ProtocolRustRender,
     ) -> Result<String, String> {
+        let handlers = store.get_config()?.get_handlers();
         let broadcasts = self.get_all_broadcasts(store);
-        for broadcast in &broadcasts {
-            (render_broadcast::Render::new()).render(base, broadcast)?;
+        if handlers == Handlers::Files {
+            for broadcast in &broadcasts {
+                (render_broadcast::Render::new()).render(base, broadcast)?;
+            }
+            (render_broadcast_mod::Render::new()).render(base, &broadcasts)?;
+        } else {
+            (render_traits::Render::new()).render(base, &broadcasts)?;
         }
-        (render_broadcast_mod::Render::new()).render(base, &broadcasts)?;
         (render_controller::Render::new()).render(base, store)?;
         (render_consumer::Render::new()).render(base, store, protocol, &broadcasts)?;
         (render_options::Render::new()).render(base, store)?;
-        (render_protocol::Render::new()).render(base, protocol, &protocol_render)?;
-        (render_static::Render::new()).render(base)?;
-        (render_cargo::Render::new()).render(base)?;
+        (render_protocol::Render::new()).render(
+            base,
+            protocol,
+            &protocol_render,
+            self.crate_name.as_deref(),
+        )?;
+        (render_static::Render::new()).render(base, &handlers, self.crate_name.as_deref())?;
+        (render_cargo::Render::new()).render(base, protocol, self.crate_name.as_deref())?;
         Ok(String::new())
     }
 }
@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::{protocol, Consumer, Context};
@@ -32,19 +29,16 @@ impl Render {
 
     pub fn render(&self, base: &Path, broadcast: &Broadcast) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, broadcast)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace("[[request]]", &(broadcast.reference).replace(".", "::"));
-        helpers::fs::write(dest, output, true)
+        let output = Template::new("consumer/rust/broadcast/module", templates::MODULE)
+            .set("request", &(broadcast.reference).replace(".", "::"))
+            .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn get_dest_file(&self, base: &Path, broadcast: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("broadcasts");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
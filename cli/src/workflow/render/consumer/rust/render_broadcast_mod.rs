@@ -1,12 +1,10 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"[[mods]]
 use super::{context::Context, implementation::Consumer, protocol};"#;
+    pub const MOD: &str = r#"pub mod [[name]];"#;
 }
 
 pub struct Render {}
@@ -24,23 +22,29 @@ impl Render {
 
     pub fn render(&self, base: &Path, broadcasts: &[Broadcast]) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output: String = templates::MODULE.to_owned();
         let mut mods: String = String::new();
         for broadcast in broadcasts {
             mods = format!(
-                "{}\npub mod {};",
+                "{}\n{}",
                 mods,
-                &(broadcast.reference).replace(".", "_").to_lowercase()
+                Template::new("consumer/rust/broadcast_mod/mod", templates::MOD)
+                    .set(
+                        "name",
+                        &(broadcast.reference).replace(".", "_").to_lowercase()
+                    )
+                    .render()
             );
         }
-        output = output.replace("[[mods]]", &mods);
+        let output = Template::new("consumer/rust/broadcast_mod/module", templates::MODULE)
+            .set("mods", &mods)
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("broadcasts");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
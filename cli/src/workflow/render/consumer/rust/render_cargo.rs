@@ -1,4 +1,4 @@
-use super::helpers;
+use super::{crates, helpers, Protocol};
 use std::{
     fs, include_str,
     path::{Path, PathBuf},
@@ -18,10 +18,22 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path) -> Result<(), String> {
-        let target = self.get_target_file(base)?;
-        let content = fs::read_to_string(target.clone())
-            .map_err(|e| format!("Fail read {}; error: {}", target.to_string_lossy(), e))?;
+    pub fn render(
+        &self,
+        base: &Path,
+        protocol: &Protocol,
+        crate_name: Option<&str>,
+    ) -> Result<(), String> {
+        let (target, content) = if let Some(manifest) =
+            crates::manifest(crate_name, base, crates::sides::CONSUMER, protocol)?
+        {
+            manifest
+        } else {
+            let target = self.get_target_file(base)?;
+            let content = fs::read_to_string(target.clone())
+                .map_err(|e| format!("Fail read {}; error: {}", target.to_string_lossy(), e))?;
+            (target, content)
+        };
         let cargo = content
             .parse::<Value>()
             .map_err(|e| format!("Fail parse {}; error: {}", target.to_string_lossy(), e))?;
@@ -36,7 +48,7 @@ impl Render {
             }
             _ => Value::Table(Map::new()),
         };
-        let required = match include_str!("./static/required.toml")
+        let mut required = match include_str!("./static/required.toml")
             .to_owned()
             .parse::<Value>()
             .map_err(|e| format!("Fail parse required.toml; error: {}", e))?
@@ -46,6 +58,12 @@ impl Render {
                 return Err(String::from("Fail parse required.toml"));
             }
         };
+        // Standalone protocol crate has own dependencies
+        if crate_name.is_none() {
+            for (name, version) in crates::protocol_dependencies(protocol) {
+                required.insert(name.to_owned(), Value::String(version.to_owned()));
+            }
+        }
         for (key, value) in required {
             deps = match deps {
                 Value::Table(mut deps) => {
@@ -1,11 +1,9 @@
 use super::{
-    helpers, helpers::render as tools, render_controller, workflow::beacon::Broadcast, Protocol,
-    WorkflowStore,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template,
+    protocol::encryption::Encryption, render_controller, workflow::beacon::Broadcast,
+    workflow::config::Handlers, Protocol, WorkflowStore,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"pub mod api;
@@ -13,7 +11,7 @@ pub mod controller;
 pub mod error;
 pub mod options;
 
-use super::{broadcasts, events, protocol, Context};
+[[context_use]]
 use api::{Api, Channel};
 use controller::Consumer;
 use error::ConsumerError;
@@ -38,6 +36,8 @@ use uuid::Uuid;
 pub mod hash {
     pub const PROTOCOL: &str = "[[protocol_hash]]";
     pub const WORKFLOW: &str = "[[workflow_hash]]";
+    pub const PROTOCOL_SOURCE: &str = "[[protocol_source_hash]]";
+    pub const WORKFLOW_SOURCE: &str = "[[workflow_source_hash]]";
 }
 
 #[derive(Debug)]
@@ -115,9 +115,9 @@ mod shortcuts {
     }
 }
 
-pub async fn connect<C, E, Ctrl>(
+pub async fn connect<C, E, Ctrl[[context_generic]]>(
     client: C,
-    context: Context,
+    context: [[context_type]],
     options: Options,
 ) -> Result<ConsumerGetter<E>, ConsumerError<E>>
 where
@@ -126,6 +126,8 @@ where
     Ctrl: 'static + client::Control<E> + Send + Sync + Clone,
 {
     env::logs::init();
+    protocol::set_compression(&options.compression);
+    protocol::set_checksum(options.checksum);
     let (tx_consumer_getter, rx_consumer_getter): ConsumerGetterChannel<E> = unbounded_channel();
     spawn(async move {
         trace!(target: logs::targets::CONSUMER, "main thread: started");
@@ -141,7 +143,7 @@ where
                                 target: logs::targets::CONSUMER,
                                 "reconnection in {} ms", timeout
                             );
-                            if events::reconnect::handler(timeout, &mut context).await {
+                            if [[on_reconnect]].await {
                                 sleep(Duration::from_millis(timeout)).await;
                                 Some((client, context, rx_consumer_getter))
                             } else {
@@ -168,15 +170,15 @@ where
     Ok(ConsumerGetter { tx_consumer_getter })
 }
 
-async fn listen<C, E, Ctrl>(
+async fn listen<C, E, Ctrl[[context_generic]]>(
     mut client: C,
-    context: Context,
+    context: [[context_type]],
     options: Options,
     mut rx_consumer_getter: UnboundedReceiver<oneshot::Sender<Consumer<E>>>,
 ) -> Result<
     (
         C,
-        Context,
+        [[context_type]],
         UnboundedReceiver<oneshot::Sender<Consumer<E>>>,
         bool,
     ),
@@ -237,7 +239,7 @@ where
                 "emitter subtask is started"
             );
             let context =
-                consumer_emitter_task::<E>(rx_consumer_event, emitter_consumer, context).await;
+                consumer_emitter_task::<E[[context_turbofish]]>(rx_consumer_event, emitter_consumer, context).await;
             debug!(
                 target: logs::targets::CONSUMER,
                 "emitter subtask is finished"
@@ -372,11 +374,11 @@ where
     }
 }
 
-async fn consumer_emitter_task<E>(
+async fn consumer_emitter_task<E[[context_generic]]>(
     mut rx_consumer_event: UnboundedReceiver<Emitter<E>>,
     consumer: Consumer<E>,
-    mut context: Context,
-) -> Context
+    mut context: [[context_type]],
+) -> [[context_type]]
 where
     E: client::Error,
 {
@@ -388,16 +390,16 @@ where
         match msg {
             Emitter::Error(err) => {
                 warn!(target: logs::targets::CONSUMER, "{}", err);
-                events::error::handler::<E>(err, &mut context, consumer.clone()).await;
+                [[on_error]].await;
             }
             Emitter::Connected => {
-                events::connected::handler(&mut context, consumer.clone()).await;
+                [[on_connected]].await;
             }
             Emitter::Disconnected => {
-                events::disconnected::handler(&mut context, consumer.clone()).await;
+                [[on_disconnected]].await;
             }
             Emitter::Shutdown(err) => {
-                events::shutdown::handler(err, &mut context, consumer.clone()).await;
+                [[on_shutdown]].await;
             }[[broadcast_callers]]
         };
     }
@@ -419,7 +421,7 @@ where
     E: client::Error,
     Ctrl: client::Control<E> + Send + Sync + Clone,
 {
-    let mut pending: HashMap<u32, oneshot::Sender<protocol::AvailableMessages>> = HashMap::new();
+    let mut pending: HashMap<u32, oneshot::Sender<api::Response>> = HashMap::new();
     let mut sequence: u32 = 10;
     while let Some(command) = select! {
         command = rx_client_api.recv() => command,
@@ -442,9 +444,10 @@ where
                     .await
                     .map_err(|e| ConsumerError::ClientChannel(e.to_string()))?;
             }
-            Channel::AcceptIncome((sequence, msg, tx_response)) => {
-                let accepted = if let Some(response) = pending.remove(&sequence) {
-                    response.send(msg).map_err(|_| {
+            Channel::AcceptIncome((header, msg, tx_response)) => {
+                let accepted = if let Some(response) = pending.remove(&header.sequence) {
+                    let sequence = header.sequence;
+                    response.send((msg, header)).map_err(|_| {
                         ConsumerError::APIChannel(format!(
                             "Fail to use pending channel; sequence: {}",
                             sequence
@@ -502,8 +505,8 @@ async fn client_messages_task<E>(
 where
     E: client::Error,
 {
-    let mut buffer = protocol::Buffer::new();
-    let mut uuid: Option<Uuid> = None;
+    let mut buffer = protocol::Buffer::with_limits(options.limits.clone());
+    let mut uuid: Option<Uuid> = None;[[encryption_declare]]
     while let Some(msg) = select! {
         msg = rx_client_event.recv() => msg.map(MergedClientChannel::Client),
         msg = rx_auth.recv() => msg.map(MergedClientChannel::Auth),
@@ -520,9 +523,19 @@ where
                                 "has been received {} bytes",
                                 income.len()
                             );
-                            match buffer.chunk(&income, None) {
+                            match buffer.chunk(&income, [[encryption_chunk]]) {
                                 Ok(()) => {
-                                    while let Some(msg) = buffer.next() {
+                                    for err in buffer.take_errors() {
+                                        shortcuts::emit_error::<E>(
+                                            ConsumerError::BufferError(format!(
+                                                "message (id: {}, sequence: {}) has been skipped: {:?}",
+                                                err.id, err.sequence, err.error
+                                            )),
+                                            &tx_consumer_event,
+                                        )
+                                        .await;
+                                    }
+                                    while let Some(msg) = buffer.next() {[[encryption_session]]
                                         if let protocol::AvailableMessages::InternalServiceGroup(
                                             protocol::InternalServiceGroup::AvailableMessages::ConnectConfirmationBeacon(msg)
                                         ) = msg.msg {
@@ -545,7 +558,7 @@ where
                                             });
                                             continue;
                                         }
-                                        match api.accept(msg.header.sequence, msg.msg.clone()).await
+                                        match api.accept(msg.header.clone(), msg.msg.clone()).await
                                         {
                                             Ok(accepted) => {
                                                 if accepted {
@@ -560,6 +573,12 @@ where
                                             }
                                         };
                                         match msg.msg {[[broadcast_handlers]]
+                                            protocol::AvailableMessages::Unknown { id, .. } => {
+                                                warn!(
+                                                    target: logs::targets::CONSUMER,
+                                                    "unknown message {} has been skipped", id
+                                                );
+                                            }
                                             _ => {
                                                 shortcuts::emit_error::<E>(
                                                     ConsumerError::UnknownMessage(format!("header: {:?}", msg.header)),
@@ -593,7 +612,9 @@ where
                         );
                     }
                     client::Event::Disconnected => {
-                        uuid = None;
+                        if let Some(uuid) = uuid.take() {
+                            protocol::remove_agreement(&uuid.to_string());
+                        }[[encryption_reset]]
                     }
                     client::Event::Error(err) => {
                         shortcuts::emit_error::<E>(ConsumerError::Client(err), &tx_consumer_event)
@@ -668,13 +689,22 @@ where
     debug!(
         target: logs::targets::CONSUMER,
         "self-key accepted; checking hash"
-    );
+    );[[encryption_offer]]
+    let capabilities = protocol::Capabilities::local(protocol::CODEC, &options.limits);
     let mut hash = protocol::InternalServiceGroup::HashRequest {
         protocol: hash::PROTOCOL.to_string(),
         workflow: hash::WORKFLOW.to_string(),
+        version: Some(capabilities.version),
+        compression: Some(capabilities.compression.clone()),
+        encryption: Some(capabilities.encryption.clone()),
+        codec: Some(capabilities.codec.clone()),
+        frame: Some(capabilities.frame),
+        heartbeat: Some(capabilities.heartbeat),[[encryption_key]]
+        __unknown: vec![],
     };
-    let response = api
-        .request(
+    let sent = protocol::now_ms();
+    let (response, header) = api
+        .request_with_header(
             1,
             &hash
                 .pack(1, None)
@@ -710,9 +740,67 @@ where
         );
         return Err(ConsumerError::HashCheck(err));
     }
+    // Producer without negotiation doesn't send capabilities
+    let legacy = protocol::Capabilities::legacy(protocol::CODEC);
+    let mut agreement = capabilities
+        .negotiate(&protocol::Capabilities {
+            version: response.version.unwrap_or(legacy.version),
+            compression: response.compression.unwrap_or(legacy.compression),
+            encryption: response.encryption.unwrap_or(legacy.encryption),
+            codec: response.codec.unwrap_or(legacy.codec),
+            frame: response.frame.unwrap_or(legacy.frame),
+            heartbeat: response.heartbeat.unwrap_or(legacy.heartbeat),
+        })
+        .map_err(|e| {
+            error!(
+                target: logs::targets::CONSUMER,
+                "capabilities of producer are incompatible: {}", e
+            );
+            ConsumerError::Handshake(format!("incompatible capabilities: {}", e))
+        })?;
+    // Response follows agreement; clock is estimated only by timestamp in milliseconds
+    if header.version >= protocol::MSG_VERSION_TS_MS {
+        agreement.clock = Some(protocol::ClockSkew::estimate(
+            sent,
+            header.ts,
+            protocol::now_ms(),
+        ));
+    }
+    protocol::set_agreement(&uuid, agreement);[[encryption_ready]]
     debug!(target: logs::targets::CONSUMER, "hash accepted");
     Ok(uuid)
 }"#;
+    pub const BROADCAST_EMITTER: &str = r#"[[name]](protocol::[[ref]]),"#;
+    pub const BROADCAST_CALLER: &str = r#"Emitter::[[name]](msg) => {
+    [[handler]].await;
+},"#;
+    pub const BROADCAST_HANDLER: &str = r#"[[ref]] => {
+    tx_consumer_event.send(Emitter::[[name]](msg)).map_err(|e| ConsumerError::APIChannel(e.to_string()))?;
+},"#;
+    pub const ENCRYPTION_SESSION: &str = r#"
+                                        if let protocol::AvailableMessages::InternalServiceGroup(
+                                            protocol::InternalServiceGroup::AvailableMessages::SelfKeyResponse(response)
+                                        ) = &msg.msg {
+                                            // Next packages (starting from HashResponse) are bound to session
+                                            session = Some(response.uuid.clone());
+                                        }"#;
+    pub const ENCRYPTION_RESET: &str = r#"
+                        if let Some(session) = session.take() {
+                            protocol::encryption_close(&session);
+                        }"#;
+    pub const ENCRYPTION_OFFER: &str = r#"
+    let key = protocol::encryption_offer(&uuid)
+        .map_err(|e| ConsumerError::Handshake(format!("fail to start encrypted session: {}", e)))?;"#;
+    pub const ENCRYPTION_READY: &str = r#"
+    if !protocol::encryption_ready(&uuid) {
+        error!(
+            target: logs::targets::CONSUMER,
+            "encrypted session hasn't been established"
+        );
+        return Err(ConsumerError::Handshake(String::from(
+            "fail to establish encrypted session",
+        )));
+    }"#;
 }
 
 pub struct Render {}
@@ -736,21 +824,113 @@ impl Render {
         broadcasts: &[Broadcast],
     ) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[broadcast_emitters]]",
-            &self.get_broadcast_emitters(broadcasts)?,
-        );
-        output = output.replace(
-            "[[broadcast_callers]]",
-            &self.get_broadcast_callers(broadcasts)?,
-        );
-        output = output.replace(
-            "[[broadcast_handlers]]",
-            &self.get_broadcast_handlers(broadcasts)?,
-        );
-        output = output.replace("[[protocol_hash]]", &protocol.get_hash());
-        output = output.replace("[[workflow_hash]]", &store.get_hash());
+        let handlers = store.get_config()?.get_handlers();
+        let mut output = Template::new("consumer/rust/consumer/module", templates::MODULE);
+        let context: &[(&str, &str)] = match handlers {
+            Handlers::Files => &[
+                (
+                    "context_use",
+                    "use super::{broadcasts, events, protocol, Context};",
+                ),
+                ("context_type", "Context"),
+                ("context_generic", ""),
+                ("context_turbofish", ""),
+                (
+                    "on_reconnect",
+                    "events::reconnect::handler(timeout, &mut context)",
+                ),
+                (
+                    "on_error",
+                    "events::error::handler::<E>(err, &mut context, consumer.clone())",
+                ),
+                (
+                    "on_connected",
+                    "events::connected::handler(&mut context, consumer.clone())",
+                ),
+                (
+                    "on_disconnected",
+                    "events::disconnected::handler(&mut context, consumer.clone())",
+                ),
+                (
+                    "on_shutdown",
+                    "events::shutdown::handler(err, &mut context, consumer.clone())",
+                ),
+            ],
+            Handlers::Trait => &[
+                ("context_use", "use super::{protocol, ConsumerHandlers};"),
+                ("context_type", "H"),
+                ("context_generic", ", H: ConsumerHandlers"),
+                ("context_turbofish", ", H"),
+                ("on_reconnect", "context.reconnect(timeout)"),
+                ("on_error", "context.error(err, consumer.clone())"),
+                ("on_connected", "context.connected(consumer.clone())"),
+                ("on_disconnected", "context.disconnected(consumer.clone())"),
+                ("on_shutdown", "context.shutdown(err, consumer.clone())"),
+            ],
+        };
+        for (key, value) in context.iter() {
+            output = output.set(key, value);
+        }
+        let encryption: Vec<(&str, String)> = if protocol.get_encryption() == Encryption::None {
+            vec![
+                ("encryption_declare", String::new()),
+                ("encryption_chunk", String::from("None")),
+                ("encryption_session", String::new()),
+                ("encryption_reset", String::new()),
+                ("encryption_offer", String::new()),
+                ("encryption_key", String::new()),
+                ("encryption_ready", String::new()),
+            ]
+        } else {
+            let encryption = |name: &str, template: &str| -> String {
+                Template::new(&format!("consumer/rust/consumer/{}", name), template).render()
+            };
+            vec![
+                (
+                    "encryption_declare",
+                    String::from("\n    let mut session: Option<String> = None;"),
+                ),
+                ("encryption_chunk", String::from("session.clone()")),
+                (
+                    "encryption_session",
+                    encryption("encryption_session", templates::ENCRYPTION_SESSION),
+                ),
+                (
+                    "encryption_reset",
+                    encryption("encryption_reset", templates::ENCRYPTION_RESET),
+                ),
+                (
+                    "encryption_offer",
+                    encryption("encryption_offer", templates::ENCRYPTION_OFFER),
+                ),
+                ("encryption_key", String::from("\n        key,")),
+                (
+                    "encryption_ready",
+                    encryption("encryption_ready", templates::ENCRYPTION_READY),
+                ),
+            ]
+        };
+        for (key, value) in encryption.iter() {
+            output = output.set(key, value);
+        }
+        let output = output
+            .set(
+                "broadcast_emitters",
+                &self.get_broadcast_emitters(broadcasts)?,
+            )
+            .set(
+                "broadcast_callers",
+                &self.get_broadcast_callers(broadcasts, &handlers)?,
+            )
+            .set(
+                "broadcast_handlers",
+                &self.get_broadcast_handlers(broadcasts)?,
+            )
+            .set("protocol_hash", &protocol.get_hash())
+            .set("workflow_hash", &store.get_hash())
+            .set("protocol_source_hash", &protocol.get_source_hash())
+            .set("workflow_source_hash", &store.get_source_hash())
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -758,26 +938,47 @@ impl Render {
         let mut output: String = String::new();
         for broadcast in broadcasts {
             output = format!(
-                "{}\n{}(protocol::{}),",
+                "{}\n{}",
                 output,
-                broadcast.reference.replace(".", ""),
-                broadcast.reference.replace(".", "::")
+                Template::new(
+                    "consumer/rust/consumer/broadcast_emitter",
+                    templates::BROADCAST_EMITTER,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set("ref", &broadcast.reference.replace(".", "::"))
+                .render()
             );
         }
         Ok(tools::inject_tabs(1, output))
     }
 
-    fn get_broadcast_callers(&self, broadcasts: &[Broadcast]) -> Result<String, String> {
+    fn get_broadcast_callers(
+        &self,
+        broadcasts: &[Broadcast],
+        handlers: &Handlers,
+    ) -> Result<String, String> {
         let mut output: String = String::new();
         for broadcast in broadcasts {
+            let name = broadcast.reference.replace(".", "_").to_lowercase();
             output = format!(
-                r#"{}{}Emitter::{}(msg) => {{
-    broadcasts::{}::handler(msg, &mut context, consumer.clone()).await;
-}},"#,
+                "{}\n{}",
                 output,
-                "\n",
-                broadcast.reference.replace(".", ""),
-                broadcast.reference.replace(".", "_").to_lowercase(),
+                Template::new(
+                    "consumer/rust/consumer/broadcast_caller",
+                    templates::BROADCAST_CALLER,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set(
+                    "handler",
+                    &match handlers {
+                        Handlers::Files => format!(
+                            "broadcasts::{}::handler(msg, &mut context, consumer.clone())",
+                            name
+                        ),
+                        Handlers::Trait => format!("context.{}(msg, consumer.clone())", name),
+                    },
+                )
+                .render()
             );
         }
         Ok(tools::inject_tabs(3, output))
@@ -787,13 +988,20 @@ impl Render {
         let mut output: String = String::new();
         for broadcast in broadcasts {
             output = format!(
-                r#"{}{}{} => {{
-    tx_consumer_event.send(Emitter::{}(msg)).map_err(|e| ConsumerError::APIChannel(e.to_string()))?;
-}},"#,
+                "{}\n{}",
                 output,
-                "\n",
-                render_controller::Render::get_request_enum_reference(broadcast.reference.clone())?,
-                broadcast.reference.replace(".", ""),
+                Template::new(
+                    "consumer/rust/consumer/broadcast_handler",
+                    templates::BROADCAST_HANDLER,
+                )
+                .set(
+                    "ref",
+                    &render_controller::Render::get_request_enum_reference(
+                        broadcast.reference.clone()
+                    )?,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .render()
             );
         }
         Ok(tools::inject_tabs(13, output))
@@ -802,7 +1010,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,11 +1,8 @@
 use super::{
-    helpers, helpers::render as tools, workflow::beacon::Broadcast, workflow::request::Request,
-    WorkflowStore,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast,
+    workflow::request::Request, WorkflowStore,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::{api::Api, error::ConsumerError, protocol, protocol::PackingStruct};
@@ -85,6 +82,12 @@ impl<E: client::Error> Consumer<E> {
         ))),
     }
 }"#;
+    pub const RESPONSE: &str = r#"[[ref]] =>
+    Ok([[type]]::[[variant]](msg)),"#;
+    pub const ENUM: &str = r#"pub enum [[name]] {
+[[items]]
+}"#;
+    pub const ENUM_ITEM: &str = r#"[[name]](protocol::[[ref]]),"#;
 }
 
 pub struct Render {}
@@ -102,13 +105,11 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &WorkflowStore) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[request_enums]]",
-            &self.get_request_enums(&store.requests)?,
-        );
-        output = output.replace("[[requests]]", &self.get_requests(&store.requests)?);
-        output = output.replace("[[beacons]]", &self.get_beacons(&store.beacons)?);
+        let output = Template::new("consumer/rust/controller/module", templates::MODULE)
+            .set("request_enums", &self.get_request_enums(&store.requests)?)
+            .set("requests", &self.get_requests(&store.requests)?)
+            .set("beacons", &self.get_beacons(&store.beacons)?)
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -117,34 +118,37 @@ impl Render {
         for request in requests.iter() {
             let mut out: String = String::new();
             for action in request.actions.iter() {
-                if action.get_conclusion().is_err() {
-                    out = format!(
-                        "{}Response(protocol::{}),\n",
-                        out,
-                        action.get_response()?.replace(".", "::"),
-                    );
-                } else {
-                    out = format!(
-                        "{}{}(protocol::{}),\n",
-                        out,
-                        action.get_conclusion()?,
-                        action.get_response()?.replace(".", "::"),
-                    );
-                }
+                out = format!(
+                    "{}{}\n",
+                    out,
+                    Template::new("consumer/rust/controller/enum_item", templates::ENUM_ITEM)
+                        .set(
+                            "name",
+                            &if action.get_conclusion().is_err() {
+                                String::from("Response")
+                            } else {
+                                action.get_conclusion()?
+                            },
+                        )
+                        .set("ref", &action.get_response()?.replace(".", "::"))
+                        .render()
+                );
             }
             out = format!(
-                "{}Err(protocol::{}),",
+                "{}{}",
                 out,
-                request.get_err()?.replace(".", "::"),
+                Template::new("consumer/rust/controller/enum_item", templates::ENUM_ITEM)
+                    .set("name", "Err")
+                    .set("ref", &request.get_err()?.replace(".", "::"))
+                    .render()
             );
             output = format!(
-                r#"{}
-pub enum {} {{
-{}
-}}"#,
+                "{}\n{}",
                 output,
-                &Render::get_request_response_type(request)?,
-                tools::inject_tabs(1, out)
+                Template::new("consumer/rust/controller/enum", templates::ENUM)
+                    .set("name", &Render::get_request_response_type(request)?)
+                    .set("items", &tools::inject_tabs(1, out))
+                    .render()
             );
         }
         Ok(output)
@@ -156,12 +160,10 @@ pub enum {} {{
             output = format!(
                 "{}\n{}",
                 output,
-                templates::BEACON
-                    .replace("[[request]]", &beacon.reference.replace(".", "::"))
-                    .replace(
-                        "[[name]]",
-                        &beacon.reference.replace(".", "_").to_lowercase()
-                    )
+                Template::new("consumer/rust/controller/beacon", templates::BEACON)
+                    .set("request", &beacon.reference.replace(".", "::"))
+                    .set("name", &beacon.reference.replace(".", "_").to_lowercase())
+                    .render()
             );
         }
         Ok(tools::inject_tabs(1, output))
@@ -170,42 +172,37 @@ pub enum {} {{
     fn get_requests(&self, requests: &[Request]) -> Result<String, String> {
         let mut output: String = String::new();
         for request in requests {
-            let mut out = String::from(templates::REQUEST);
-            out = out.replace("[[response]]", &Render::get_request_response_type(request)?);
-            out = out.replace("[[name]]", &Render::get_request_method_name(request)?);
-            out = out.replace("[[request]]", &request.get_request()?.replace(".", "::"));
+            let response = |reference: String, variant: &str| -> Result<String, String> {
+                Ok(
+                    Template::new("consumer/rust/controller/response", templates::RESPONSE)
+                        .set("ref", &Render::get_request_enum_reference(reference)?)
+                        .set("type", &Render::get_request_response_type(request)?)
+                        .set("variant", variant)
+                        .render(),
+                )
+            };
             let mut responses: String = String::new();
             for action in request.actions.iter() {
-                if action.get_conclusion().is_err() {
-                    responses = format!(
-                        r#"{}{}{} =>
-    Ok({}::Response(msg)),"#,
-                        responses,
-                        "\n",
-                        Render::get_request_enum_reference(action.get_response()?)?,
-                        Render::get_request_response_type(request)?,
-                    );
-                } else {
-                    responses = format!(
-                        r#"{}{}{} =>
-    Ok({}::{}(msg)),"#,
-                        responses,
-                        "\n",
-                        Render::get_request_enum_reference(action.get_response()?)?,
-                        Render::get_request_response_type(request)?,
-                        action.get_conclusion()?,
-                    );
-                }
+                responses = format!(
+                    "{}\n{}",
+                    responses,
+                    response(
+                        action.get_response()?,
+                        &if action.get_conclusion().is_err() {
+                            String::from("Response")
+                        } else {
+                            action.get_conclusion()?
+                        },
+                    )?
+                );
             }
-            responses = format!(
-                r#"{}{}{} =>
-    Ok({}::Err(msg)),"#,
-                responses,
-                "\n",
-                Render::get_request_enum_reference(request.get_err()?)?,
-                Render::get_request_response_type(request)?,
-            );
-            out = out.replace("[[responses]]", &tools::inject_tabs(2, responses));
+            responses = format!("{}\n{}", responses, response(request.get_err()?, "Err")?);
+            let out = Template::new("consumer/rust/controller/request", templates::REQUEST)
+                .set("response", &Render::get_request_response_type(request)?)
+                .set("name", &Render::get_request_method_name(request)?)
+                .set("request", &request.get_request()?.replace(".", "::"))
+                .set("responses", &tools::inject_tabs(2, responses))
+                .render();
             output = format!("{}\n{}", output, out);
         }
         Ok(tools::inject_tabs(1, output))
@@ -214,7 +211,7 @@ pub enum {} {{
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,8 +1,5 @@
-use super::{helpers, helpers::render as tools, WorkflowStore};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::render as tools, helpers::template::Template, WorkflowStore};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::protocol;
@@ -12,6 +9,9 @@ pub struct Options {
     pub reconnection: ReconnectionStrategy,
     pub request_timeout: u64,
     pub key: protocol::[[self_key]],
+    pub limits: protocol::Limits,
+    pub compression: protocol::Compression,
+    pub checksum: bool,
 }
 impl Options {
     pub fn defualt(key: protocol::[[self_key]]) -> Self {
@@ -19,6 +19,12 @@ impl Options {
             reconnection: ReconnectionStrategy::Reconnect(2000),
             request_timeout: 20000,
             key,
+            limits: protocol::Limits::default(),
+            compression: protocol::Compression {
+                algorithm: protocol::CompressionAlgorithm::[[compression_algorithm]],
+                threshold: [[compression_threshold]],
+            },
+            checksum: [[checksum]],
         }
     }
 }
@@ -44,18 +50,31 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &WorkflowStore) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[self_key]]",
-            &tools::into_rust_path(&store.get_config()?.get_self()?),
-        );
+        let output = Template::new("consumer/rust/options/module", templates::MODULE)
+            .set(
+                "self_key",
+                &tools::into_rust_path(&store.get_config()?.get_self()?),
+            )
+            .set(
+                "compression_algorithm",
+                store.get_config()?.get_compression().as_variant(),
+            )
+            .set(
+                "compression_threshold",
+                &store.get_config()?.get_compression_threshold().to_string(),
+            )
+            .set(
+                "checksum",
+                &store.get_config()?.get_checksum().enabled().to_string(),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
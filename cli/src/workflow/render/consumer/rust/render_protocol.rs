@@ -1,8 +1,5 @@
-use super::{Protocol, ProtocolRender, ProtocolRustRender};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{crates, helpers, Protocol, ProtocolRender, ProtocolRustRender};
+use std::path::{Path, PathBuf};
 
 pub struct Render {}
 
@@ -22,15 +19,20 @@ impl Render {
         base: &Path,
         protocol: &mut Protocol,
         protocol_render: &ProtocolRustRender,
+        crate_name: Option<&str>,
     ) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        protocol_render.render(protocol, &dest)
+        if let Some(content) = crates::protocol_module(crate_name) {
+            helpers::fs::write(dest, content, true)
+        } else {
+            protocol_render.render(protocol, &dest)
+        }
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("protocol");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,9 +1,6 @@
-use super::helpers;
+use super::{crates, helpers, workflow::config::Handlers};
 use std::include_str;
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 #[allow(non_upper_case_globals)]
 mod paths {
@@ -47,42 +44,16 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path) -> Result<(), String> {
-        self.create_if(
-            base,
-            paths::events::dest,
-            paths::events::connected,
-            include_str!("./static/events/connected.rs").to_owned(),
-        )?;
-        self.create_if(
-            base,
-            paths::events::dest,
-            paths::events::disconnected,
-            include_str!("./static/events/disconnected.rs").to_owned(),
-        )?;
-        self.create_if(
-            base,
-            paths::events::dest,
-            paths::events::error,
-            include_str!("./static/events/error.rs").to_owned(),
-        )?;
-        self.create_if(
-            base,
-            paths::events::dest,
-            paths::events::reconnect,
-            include_str!("./static/events/reconnect.rs").to_owned(),
-        )?;
-        self.create_if(
-            base,
-            paths::events::dest,
-            paths::events::shutdown,
-            include_str!("./static/events/shutdown.rs").to_owned(),
-        )?;
-        helpers::fs::write(
-            self.get_dest_file(base, paths::events::dest, paths::events::module)?,
-            include_str!("./static/events/mod.rs").to_owned(),
-            true,
-        )?;
+    pub fn render(
+        &self,
+        base: &Path,
+        handlers: &Handlers,
+        crate_name: Option<&str>,
+    ) -> Result<(), String> {
+        let stubs = handlers == &Handlers::Files;
+        if stubs {
+            self.render_events(base)?;
+        }
         helpers::fs::write(
             self.get_dest_file(base, paths::consumer::dest, paths::consumer::api)?,
             include_str!("./static/implementation/consumer/api.rs").to_owned(),
@@ -99,19 +70,64 @@ impl Render {
                 paths::implementation::dest,
                 paths::implementation::module,
             )?,
-            include_str!("./static/implementation/mod.rs").to_owned(),
+            if stubs {
+                include_str!("./static/implementation/mod.rs")
+            } else {
+                include_str!("./static/traits/implementation/mod.rs")
+            }
+            .to_owned(),
             true,
         )?;
         helpers::fs::write(
-            self.get_dest_file(base, paths::module::dest, paths::module::module)?,
-            include_str!("./static/mod.rs").to_owned(),
+            self.get_dest_file(
+                base,
+                paths::module::dest,
+                crates::root_file(crate_name, paths::module::module),
+            )?,
+            if stubs {
+                include_str!("./static/mod.rs")
+            } else {
+                include_str!("./static/traits/mod.rs")
+            }
+            .to_owned(),
             true,
         )?;
-        self.create_if(
-            base,
-            paths::context::dest,
-            paths::context::module,
-            include_str!("./static/context.rs").to_owned(),
+        if stubs {
+            self.create_if(
+                base,
+                paths::context::dest,
+                paths::context::module,
+                include_str!("./static/context.rs").to_owned(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn render_events(&self, base: &Path) -> Result<(), String> {
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::connected)?,
+            include_str!("./static/events/connected.rs").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::disconnected)?,
+            include_str!("./static/events/disconnected.rs").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::error)?,
+            include_str!("./static/events/error.rs").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::reconnect)?,
+            include_str!("./static/events/reconnect.rs").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::shutdown)?,
+            include_str!("./static/events/shutdown.rs").to_owned(),
+        )?;
+        helpers::fs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::module)?,
+            include_str!("./static/events/mod.rs").to_owned(),
+            true,
         )?;
         Ok(())
     }
@@ -135,7 +151,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, path: &str, file_name: &str) -> Result<PathBuf, String> {
         let dest = base.join(path);
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -0,0 +1,102 @@
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast,
+};
+use std::path::{Path, PathBuf};
+
+mod templates {
+    pub const MODULE: &str = r#"use super::{protocol, Consumer, ConsumerError};
+use clibri::client;
+use std::future::Future;
+
+// Each handler has default implementation, which does nothing. Futures are
+// required to be Send, because consumer is running in separated task.
+#[allow(unused_variables)]
+pub trait ConsumerHandlers: Send + 'static {
+[[broadcasts]]
+    fn connected<E: client::Error>(
+        &mut self,
+        consumer: Consumer<E>,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+    fn disconnected<E: client::Error>(
+        &mut self,
+        consumer: Consumer<E>,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+    fn error<E: client::Error>(
+        &mut self,
+        error: ConsumerError<E>,
+        consumer: Consumer<E>,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+    // Return true to confirm reconnection; false - to refuse
+    fn reconnect(&mut self, timeout: u64) -> impl Future<Output = bool> + Send {
+        async { true }
+    }
+    fn shutdown<E: client::Error>(
+        &mut self,
+        error: Option<ConsumerError<E>>,
+        consumer: Consumer<E>,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+"#;
+    pub const BROADCAST: &str = r#"fn [[name]]<E: client::Error>(
+    &mut self,
+    event: protocol::[[request]],
+    consumer: Consumer<E>,
+) -> impl Future<Output = ()> + Send {
+    async {}
+}"#;
+}
+
+pub struct Render {}
+
+impl Default for Render {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn render(&self, base: &Path, broadcasts: &[Broadcast]) -> Result<(), String> {
+        let dest: PathBuf = self.get_dest_file(base)?;
+        let mut handlers: Vec<String> = vec![];
+        for broadcast in broadcasts {
+            let handler = Template::new("consumer/rust/traits/broadcast", templates::BROADCAST)
+                .set(
+                    "name",
+                    &broadcast.reference.replace(".", "_").to_lowercase(),
+                )
+                .set("request", &broadcast.reference.replace(".", "::"))
+                .render();
+            handlers.push(tools::inject_tabs(1, handler));
+        }
+        let output = Template::new("consumer/rust/traits/module", templates::MODULE)
+            .set("broadcasts", &handlers.join("\n"))
+            .render();
+        helpers::fs::write(dest, output, true)
+    }
+
+    fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
+        let dest = base.join("implementation");
+        if !dest.exists() {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
+                return Err(format!(
+                    "Fail to create dest folder {}. Error: {}",
+                    dest.to_string_lossy(),
+                    e
+                ));
+            }
+        }
+        Ok(dest.join("traits.rs"))
+    }
+}
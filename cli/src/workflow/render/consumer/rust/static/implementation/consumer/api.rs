@@ -4,11 +4,19 @@ use tokio::sync::{mpsc::UnboundedSender, oneshot};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+pub type Response = (protocol::AvailableMessages, protocol::PackageHeader);
+
 #[derive(Debug)]
 pub enum Channel {
     Send(Vec<u8>),
-    Request((u32, Vec<u8>, oneshot::Sender<protocol::AvailableMessages>)),
-    AcceptIncome((u32, protocol::AvailableMessages, oneshot::Sender<bool>)),
+    Request((u32, Vec<u8>, oneshot::Sender<Response>)),
+    AcceptIncome(
+        (
+            protocol::PackageHeader,
+            protocol::AvailableMessages,
+            oneshot::Sender<bool>,
+        ),
+    ),
     Uuid(oneshot::Sender<Option<Uuid>>),
     Sequence(oneshot::Sender<u32>),
     Shutdown(oneshot::Sender<()>),
@@ -56,10 +64,17 @@ impl<E: client::Error> Api<E> {
         sequence: u32,
         buffer: &[u8],
     ) -> Result<protocol::AvailableMessages, ConsumerError<E>> {
-        let (tx_response, rx_response): (
-            oneshot::Sender<protocol::AvailableMessages>,
-            oneshot::Receiver<protocol::AvailableMessages>,
-        ) = oneshot::channel();
+        Ok(self.request_with_header(sequence, buffer).await?.0)
+    }
+
+    // Returns response with header; timestamp of header is used to estimate clock of producer
+    pub async fn request_with_header(
+        &self,
+        sequence: u32,
+        buffer: &[u8],
+    ) -> Result<Response, ConsumerError<E>> {
+        let (tx_response, rx_response): (oneshot::Sender<Response>, oneshot::Receiver<Response>) =
+            oneshot::channel();
         self.tx_client_api
             .send(Channel::Request((sequence, buffer.to_vec(), tx_response)))
             .map_err(|e| ConsumerError::APIChannel(e.to_string()))?;
@@ -71,13 +86,13 @@ impl<E: client::Error> Api<E> {
 
     pub async fn accept(
         &self,
-        sequence: u32,
+        header: protocol::PackageHeader,
         msg: protocol::AvailableMessages,
     ) -> Result<bool, ConsumerError<E>> {
         let (tx_response, rx_response): (oneshot::Sender<bool>, oneshot::Receiver<bool>) =
             oneshot::channel();
         self.tx_client_api
-            .send(Channel::AcceptIncome((sequence, msg, tx_response)))
+            .send(Channel::AcceptIncome((header, msg, tx_response)))
             .map_err(|e| ConsumerError::APIChannel(e.to_string()))?;
         match rx_response.await {
             Ok(response) => Ok(response),
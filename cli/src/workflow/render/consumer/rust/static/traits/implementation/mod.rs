@@ -0,0 +1,6 @@
+pub mod consumer;
+pub mod protocol;
+pub mod traits;
+
+pub use consumer::{connect, controller, controller::Consumer, error::ConsumerError};
+pub use traits::ConsumerHandlers;
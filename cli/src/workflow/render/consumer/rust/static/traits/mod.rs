@@ -0,0 +1,7 @@
+pub mod implementation;
+
+pub use implementation::{
+    connect,
+    consumer::options::{Options, ReconnectionStrategy},
+    protocol, Consumer, ConsumerError, ConsumerHandlers,
+};
@@ -35,7 +35,7 @@ impl ImplementationRender<ProtocolTypescriptRender> for TypescriptRender {
         }
         (render_consumer::Render::new()).render(base, store, protocol)?;
         (render_interfaces_request::Render::new()).render(base)?;
-        (render_options::Render::new()).render(base)?;
+        (render_options::Render::new()).render(base, store)?;
         (render_protocol::Render::new()).render(base, protocol, &protocol_render)?;
         (render_package::Render::new()).render(base)?;
         Ok(String::new())
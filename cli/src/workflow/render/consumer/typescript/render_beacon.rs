@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import * as Protocol from "../protocol/protocol";
@@ -55,7 +52,7 @@ export class [[reference]] extends Protocol.[[struct_ref]] {
         this._state = ERequestState.Pending;
         return new Promise((resolve, reject) => {
             consumer
-                .request(this.pack(sequence), sequence)
+                .request(this.pack(sequence, consumer.getUuid()), sequence)
                 .then((response: Protocol.IAvailableMessages) => {
                     switch (this._state) {
                         case ERequestState.Pending:
@@ -136,10 +133,11 @@ impl Render {
 
     pub fn render(&self, base: &Path, beacon: &Broadcast) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, beacon)?;
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace("[[reference]]", &(beacon.reference).replace(".", ""));
-        output = output.replace("[[struct_ref]]", &beacon.reference);
-        output = output.replace("[[struct_interface]]", &self.get_beacon_interface(beacon)?);
+        let output = Template::new("consumer/typescript/beacon/module", templates::MODULE)
+            .set("reference", &(beacon.reference).replace(".", ""))
+            .set("struct_ref", &beacon.reference)
+            .set("struct_interface", &self.get_beacon_interface(beacon)?)
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -159,7 +157,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, beacon: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,6 +1,7 @@
 use super::{
     helpers,
     helpers::render as tools,
+    helpers::template::Template,
     workflow::{beacon::Broadcast, request::Request},
     Protocol, WorkflowStore,
 };
@@ -41,6 +42,8 @@ export class Consumer {
 
     public static PROTOCOL_HASH: string = "[[protocol_hash]]";
     public static WORKFLOW_HASH: string = "[[workflow_hash]]";
+    public static PROTOCOL_SOURCE_HASH: string = "[[protocol_source_hash]]";
+    public static WORKFLOW_SOURCE_HASH: string = "[[workflow_source_hash]]";
     public static GUID: string = guid();
     public static GUID_SUBS: string = guid();
 
@@ -71,8 +74,8 @@ export class Consumer {
  
     private readonly _client: Client;
     private readonly _subscriptions: { [key: string]: Subscription } = {};
-    private readonly _pending: Map<number, (response: Protocol.IAvailableMessages) => void> = new Map();
-    private readonly _buffer: Protocol.BufferReaderMessages = new Protocol.BufferReaderMessages();
+    private readonly _pending: Map<number, (response: Protocol.IAvailableMessages, timestamp: bigint | undefined) => void> = new Map();
+    private readonly _buffer: Protocol.BufferReaderMessages;
     private readonly _logger: Logger;
     private readonly _options: Options;
     private _uuid: string | undefined;
@@ -115,6 +118,9 @@ export class Consumer {
         this._key = key;
         this._options = new Options(`Consumer ${Consumer.GUID}`, options);
         this._logger = this._options.logger;
+        this._buffer = new Protocol.BufferReaderMessages(this._options.limits);
+        Protocol.setCompression(this._options.compression);
+        Protocol.setChecksum(this._options.checksum);
         this._subscriptions.data = this._client.getEvents().data.subscribe(this._onData.bind(this));
         this._subscriptions.connected = this._client.getEvents().connected.subscribe(this._onClientConnected.bind(this));
         this._subscriptions.disconnected = this._client.getEvents().disconnected.subscribe(this._onClientDisconnected.bind(this));
@@ -150,6 +156,12 @@ export class Consumer {
 	}
 
     public request(buffer: ArrayBufferLike, sequence?: number): Promise<Protocol.IAvailableMessages> {
+        return this.requestWithTimestamp(buffer, sequence).then((response) => response[0]);
+    }
+
+    // Resolves with response and producer's timestamp (ms) from header of response; timestamp
+    // is undefined, if header has it in seconds (version of header before 2)
+    public requestWithTimestamp(buffer: ArrayBufferLike, sequence?: number): Promise<[Protocol.IAvailableMessages, bigint | undefined]> {
         if (sequence !== undefined && this._pending.has(sequence)) {
             return Promise.reject(new Error(this._logger.debug(`Request with sequence #${sequence} has been already sent and pending for response`)));
         }
@@ -158,10 +170,12 @@ export class Consumer {
             return Promise.reject(error);
         }
         if (sequence === undefined) {
-            return Promise.resolve({});
+            return Promise.resolve([{}, undefined]);
         }
         return new Promise((resolve) => {
-            this._pending.set(sequence, resolve);
+            this._pending.set(sequence, (response: Protocol.IAvailableMessages, timestamp: bigint | undefined) => {
+                resolve([response, timestamp]);
+            });
         });
     }
 
@@ -189,25 +203,55 @@ export class Consumer {
         return this._sequence ++;
     }
 
-    private _hash(): Promise<void> {
+    // Packages are packed with uuid to follow settings, which are agreed with producer
+    public getUuid(): string | undefined {
+        return this._uuid;
+    }
+
+    private _hash(uuid: string): Promise<void> {
         return new Promise((resolve, reject) => {
+            const capabilities: Protocol.ICapabilities = Protocol.PackingCapabilities.local(Protocol.codec(), this._options.limits);
             const request: Protocol.InternalServiceGroup.HashRequest = new Protocol.InternalServiceGroup.HashRequest({
                 protocol: Consumer.PROTOCOL_HASH,
                 workflow: Consumer.WORKFLOW_HASH,
+                version: capabilities.version,
+                compression: capabilities.compression,
+                encryption: capabilities.encryption,
+                codec: capabilities.codec,
+                frame: capabilities.frame,
+                heartbeat: capabilities.heartbeat,
             });
             const sequence: number = this.getSequence();
-            this.request(request.pack(sequence), sequence).then((response: Protocol.IAvailableMessages) => {
+            const sent: bigint = Protocol.MessageHeader.now();
+            this.requestWithTimestamp(request.pack(sequence), sequence).then(([response, timestamp]) => {
                 if (response.InternalServiceGroup === undefined) {
                     return reject(new Error(this._logger.err(`Expecting message from "InternalServiceGroup" group.`)));
                 }
                 if (response.InternalServiceGroup.HashResponse === undefined) {
                     return reject(new Error(this._logger.err(`Expecting message "InternalServiceGroup.HashResponse".`)));
                 }
-                if (response.InternalServiceGroup.HashResponse.error !== undefined) {
-                    reject(new Error(response.InternalServiceGroup.HashResponse.error));
-                } else {
-                    resolve(undefined);
+                const hash: Protocol.InternalServiceGroup.HashResponse = response.InternalServiceGroup.HashResponse;
+                if (hash.error !== undefined) {
+                    return reject(new Error(hash.error));
+                }
+                // Producer without negotiation doesn't send capabilities
+                const legacy: Protocol.ICapabilities = Protocol.PackingCapabilities.legacy(Protocol.codec());
+                const agreement: Protocol.IAgreement | Error = Protocol.PackingCapabilities.negotiate(capabilities, {
+                    version: hash.version === undefined ? legacy.version : hash.version,
+                    compression: hash.compression === undefined ? legacy.compression : hash.compression,
+                    encryption: hash.encryption === undefined ? legacy.encryption : hash.encryption,
+                    codec: hash.codec === undefined ? legacy.codec : hash.codec,
+                    frame: hash.frame === undefined ? legacy.frame : hash.frame,
+                    heartbeat: hash.heartbeat === undefined ? legacy.heartbeat : hash.heartbeat,
+                });
+                if (agreement instanceof Error) {
+                    return reject(new Error(this._logger.err(`Capabilities of producer are incompatible: ${agreement.message}`)));
                 }
+                if (timestamp !== undefined) {
+                    agreement.clock = Protocol.ClockSkew.estimate(sent, timestamp, Protocol.MessageHeader.now());
+                }
+                Protocol.PackingCapabilities.set(uuid, agreement);
+                resolve(undefined);
             }).catch((err: Error) => {
                 reject(new Error(this._logger.err(`Fail check consumer's hash due error: ${err.message}`)));
             });
@@ -217,7 +261,7 @@ export class Consumer {
     private _onClientConnected() {
         this._logger.debug(`Client is connected`);
         this.assign(this._key).then((uuid: string) => {
-            this._hash().then(() => {
+            this._hash(uuid).then(() => {
                 this._logger.debug(`Protocol and workflow hashes has been accepted`);
                 this.ready.emit(uuid);
             }).catch((err: Error) => {
@@ -231,6 +275,9 @@ export class Consumer {
 
     private _onClientDisconnected() {
         this._logger.debug(`Client is disconnected`);
+        if (this._uuid !== undefined) {
+            Protocol.PackingCapabilities.remove(this._uuid);
+        }
         this.uuid = undefined;
         this.disconnected.emit();
     }
@@ -254,7 +301,10 @@ export class Consumer {
             const pending = this._pending.get(msg.header.sequence);
             if (pending !== undefined) {
                 this._pending.delete(msg.header.sequence);
-                pending(msg.msg);
+                pending(
+                    msg.msg,
+                    msg.header.version >= Protocol.MessageHeader.VERSION_TS_MS ? msg.header.timestamp : undefined,
+                );
             }[[broadcasts_checking]]
             
         } while (true);
@@ -268,6 +318,14 @@ export class Consumer {
             break;
     }
 }"#;
+    pub const BROADCAST_CASE: &str = r#"case Protocol.[[reference]].getId():
+    this.broadcast.[[subject]].emit(msg.getRef<Protocol.[[reference]]>());
+    break;"#;
+    pub const BROADCAST_DECLARATION: &str = r#"[[name]]: Subject<Protocol.[[reference]]>,"#;
+    pub const BROADCAST_DEFINITION: &str = r#"[[name]]: new Subject<Protocol.[[reference]]>(),"#;
+    pub const REQUEST_EXPORT: &str = r#"export { [[name]] } from './requests/[[module]]';"#;
+    pub const BEACON_EXPORT: &str =
+        r#"export { [[name]] as Beacon[[name]] } from './beacons/[[module]]';"#;
 }
 
 pub struct Render {}
@@ -290,35 +348,38 @@ impl Render {
         protocol: &Protocol,
     ) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base);
-        let mut output: String = templates::MODULE.to_owned();
         let broadcasts: Vec<Broadcast> = self.get_all_broadcasts(store);
-        output = output.replace("[[self_key]]", &store.get_config()?.get_self()?);
-        output = output.replace(
-            "[[self_key_interface]]",
-            &self.get_self_key_interface(store.get_config()?.get_self()?),
-        );
-        output = output.replace(
-            "[[broadcasts_declarations]]",
-            &tools::inject_tabs(2, self.get_broadcasts_declarations(&broadcasts)),
-        );
-        output = output.replace(
-            "[[broadcasts_definitions]]",
-            &tools::inject_tabs(2, self.get_broadcasts_definitions(&broadcasts)),
-        );
-        output = output.replace(
-            "[[broadcasts_checking]]",
-            &self.get_broadcasts_checking(&broadcasts),
-        );
-        output = output.replace(
-            "[[request_exports]]",
-            &self.get_request_exports(&store.requests)?,
-        );
-        output = output.replace(
-            "[[beacons_exports]]",
-            &self.get_beacons_exports(&store.beacons)?,
-        );
-        output = output.replace("[[protocol_hash]]", &protocol.get_hash());
-        output = output.replace("[[workflow_hash]]", &store.get_hash());
+        let output = Template::new("consumer/typescript/consumer/module", templates::MODULE)
+            .set("self_key", &store.get_config()?.get_self()?)
+            .set(
+                "self_key_interface",
+                &self.get_self_key_interface(store.get_config()?.get_self()?),
+            )
+            .set(
+                "broadcasts_declarations",
+                &tools::inject_tabs(2, self.get_broadcasts_declarations(&broadcasts)),
+            )
+            .set(
+                "broadcasts_definitions",
+                &tools::inject_tabs(2, self.get_broadcasts_definitions(&broadcasts)),
+            )
+            .set(
+                "broadcasts_checking",
+                &self.get_broadcasts_checking(&broadcasts),
+            )
+            .set(
+                "request_exports",
+                &self.get_request_exports(&store.requests)?,
+            )
+            .set(
+                "beacons_exports",
+                &self.get_beacons_exports(&store.beacons)?,
+            )
+            .set("protocol_hash", &protocol.get_hash())
+            .set("workflow_hash", &store.get_hash())
+            .set("protocol_source_hash", &protocol.get_source_hash())
+            .set("workflow_source_hash", &store.get_source_hash())
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -370,10 +431,15 @@ impl Render {
         let mut output: String = String::new();
         for broadcast in broadcasts {
             output = format!(
-                "{}\n{}: Subject<Protocol.{}>,",
+                "{}\n{}",
                 output,
-                broadcast.reference.replace(".", ""),
-                broadcast.reference
+                Template::new(
+                    "consumer/typescript/consumer/broadcast_declaration",
+                    templates::BROADCAST_DECLARATION,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set("reference", &broadcast.reference)
+                .render()
             );
         }
         output
@@ -383,10 +449,15 @@ impl Render {
         let mut output: String = String::new();
         for broadcast in broadcasts {
             output = format!(
-                "{}\n{}: new Subject<Protocol.{}>(),",
+                "{}\n{}",
                 output,
-                broadcast.reference.replace(".", ""),
-                broadcast.reference
+                Template::new(
+                    "consumer/typescript/consumer/broadcast_definition",
+                    templates::BROADCAST_DEFINITION,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set("reference", &broadcast.reference)
+                .render()
             );
         }
         output
@@ -398,17 +469,24 @@ impl Render {
             output = format!(
                 "{}\n{}",
                 output,
-                r#"case Protocol.[[reference]].getId():
-    this.broadcast.[[subject]].emit(msg.getRef<Protocol.[[reference]]>());
-    break;"#
-                    .replace("[[reference]]", &broadcast.reference)
-                    .replace("[[subject]]", &broadcast.reference.replace(".", ""))
+                Template::new(
+                    "consumer/typescript/consumer/broadcast_case",
+                    templates::BROADCAST_CASE,
+                )
+                .set("reference", &broadcast.reference)
+                .set("subject", &broadcast.reference.replace(".", ""))
+                .render()
             );
         }
         if !broadcasts.is_empty() {
             output = tools::inject_tabs_except(
                 3,
-                templates::BROADCAST_CHECKS.replace("[[cases]]", &tools::inject_tabs(2, output)),
+                Template::new(
+                    "consumer/typescript/consumer/broadcast_checks",
+                    templates::BROADCAST_CHECKS,
+                )
+                .set("cases", &tools::inject_tabs(2, output))
+                .render(),
                 vec![0],
             );
         }
@@ -420,10 +498,15 @@ impl Render {
         for request in requests {
             let reference = request.get_request()?;
             output = format!(
-                "{}\nexport {{ {} }} from './requests/{}';",
+                "{}\n{}",
                 output,
-                reference.replace(".", ""),
-                reference.to_lowercase(),
+                Template::new(
+                    "consumer/typescript/consumer/request_export",
+                    templates::REQUEST_EXPORT,
+                )
+                .set("name", &reference.replace(".", ""))
+                .set("module", &reference.to_lowercase())
+                .render()
             );
         }
         Ok(output)
@@ -433,11 +516,15 @@ impl Render {
         let mut output: String = String::new();
         for beacon in beacons {
             output = format!(
-                "{}\nexport {{ {} as Beacon{} }} from './beacons/{}';",
+                "{}\n{}",
                 output,
-                beacon.reference.replace(".", ""),
-                beacon.reference.replace(".", ""),
-                beacon.reference.to_lowercase(),
+                Template::new(
+                    "consumer/typescript/consumer/beacon_export",
+                    templates::BEACON_EXPORT,
+                )
+                .set("name", &beacon.reference.replace(".", ""))
+                .set("module", &beacon.reference.to_lowercase())
+                .render()
             );
         }
         Ok(output)
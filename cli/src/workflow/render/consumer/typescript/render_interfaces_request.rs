@@ -1,9 +1,6 @@
-use super::helpers;
+use super::{helpers, helpers::template::Template};
 
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"export enum ERequestState {
@@ -28,13 +25,21 @@ impl Render {
 
     pub fn render(&self, base: &Path) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        helpers::fs::write(dest, templates::MODULE.to_owned(), true)
+        helpers::fs::write(
+            dest,
+            Template::new(
+                "consumer/typescript/interfaces_request/module",
+                templates::MODULE,
+            )
+            .render(),
+            true,
+        )
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("interfaces");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,15 +1,19 @@
-use super::helpers;
+use super::{helpers, helpers::template::Template, WorkflowStore};
 
 use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import { Logger, DefaultLogger } from 'clibri';
+import * as Protocol from './protocol/protocol';
 
 export interface IOptions {
     logger?: Logger;
     autoconnect?: boolean;
     reconnect?: number;
     global?: boolean;
+    compression?: Protocol.ICompression;
+    checksum?: boolean;
+    limits?: Protocol.ILimits;
 }
 
 export class Options {
@@ -18,6 +22,12 @@ export class Options {
     public global: boolean = true;
     public reconnect: number = 2000;
     public logger: Logger;
+    public compression: Protocol.ICompression = {
+        algorithm: Protocol.CompressionAlgorithm.[[compression_algorithm]],
+        threshold: [[compression_threshold]],
+    };
+    public checksum: boolean = [[checksum]];
+    public limits: Protocol.ILimits = Protocol.ProtocolLimits.defaults();
 
     constructor(alias: string, options: IOptions = {}) {
         if (options.logger !== undefined) {
@@ -28,6 +38,9 @@ export class Options {
         options.autoconnect !== undefined && (this.autoconnect = options.autoconnect);
         options.reconnect !== undefined && (this.reconnect = options.reconnect);
         options.global !== undefined && (this.global = options.global);
+        options.compression !== undefined && (this.compression = options.compression);
+        options.checksum !== undefined && (this.checksum = options.checksum);
+        options.limits !== undefined && (this.limits = options.limits);
     }
 
 }"#;
@@ -46,9 +59,23 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path) -> Result<(), String> {
+    pub fn render(&self, base: &Path, store: &WorkflowStore) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base);
-        helpers::fs::write(dest, templates::MODULE.to_owned(), true)
+        let output = Template::new("consumer/typescript/options/module", templates::MODULE)
+            .set(
+                "compression_algorithm",
+                store.get_config()?.get_compression().as_variant(),
+            )
+            .set(
+                "compression_threshold",
+                &store.get_config()?.get_compression_threshold().to_string(),
+            )
+            .set(
+                "checksum",
+                &store.get_config()?.get_checksum().enabled().to_string(),
+            )
+            .render();
+        helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> PathBuf {
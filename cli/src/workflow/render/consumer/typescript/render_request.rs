@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::request::Request,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import * as Protocol from '../protocol/protocol';
@@ -43,7 +42,7 @@ export class [[reference]] extends Protocol.[[struct_ref]] {
         const sequence: number = consumer.getSequence();
         this._state = ERequestState.Pending;
         return new Promise((resolve, reject) => {
-            consumer.request(this.pack(sequence), sequence).then((message: Protocol.IAvailableMessages) => {
+            consumer.request(this.pack(sequence, consumer.getUuid()), sequence).then((message: Protocol.IAvailableMessages) => {
                 switch (this._state) {
                     case ERequestState.Pending:
                         this._state = ERequestState.Ready;
@@ -74,6 +73,27 @@ export class [[reference]] extends Protocol.[[struct_ref]] {
     pub const HANDLER_SETTER: &str = r#"public [[name]](handler: T[[type]]Handler): [[reference]] {
     this._handlers.[[name]] = handler;
     return this;
+}"#;
+    pub const HANDLER_TYPE: &str =
+        r#"export type T[[name]]Handler = (response: Protocol.[[reference]]) => void"#;
+    pub const HANDLER_DECLARATION: &str = r#"[[name]]: T[[type]]Handler | undefined;"#;
+    pub const HANDLER_INIT: &str = r#"[[name]]: undefined,"#;
+    pub const MESSAGE_CHECK: &str = r#"if (message === undefined) {
+    return reject(new Error(`Expecting message for "[[reference]]".`));
+} "#;
+    pub const CONCLUSION_CHECK: &str = r#"else [[check_group]] {
+    this._handlers.[[handler]] !== undefined && this._handlers.[[handler]]([[group]].[[response]]);
+    return resolve([[group]].[[response]]);
+} "#;
+    pub const RESPONSE_CHECK: &str = r#"[[check_group]] {
+    this._handlers.response !== undefined && this._handlers.response(message.[[response]]);
+    return resolve(message.[[response]]);
+} "#;
+    pub const ERROR_CHECK: &str = r#"else [[check_group]] {
+    this._handlers.err !== undefined && this._handlers.err([[group]].[[error]]);
+    return resolve([[group]].[[error]]);
+} else {
+    return reject(new Error(`No message in "[[group]]" group.`));
 }"#;
 }
 
@@ -92,31 +112,29 @@ impl Render {
 
     pub fn render(&self, base: &Path, request: &Request) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, request)?;
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[types_declarations]]",
-            &self.get_types_declarations(request)?,
-        );
-        output = output.replace("[[reference]]", &(request.get_request()?).replace(".", ""));
-        output = output.replace("[[struct_ref]]", &request.get_request()?);
-        output = output.replace("[[struct_interface]]", &self.get_struct_interface(request)?);
-        output = output.replace("[[resolver]]", &self.get_resolver_type(request)?);
-        output = output.replace(
-            "[[handlers]]",
-            &tools::inject_tabs(1, self.get_handlers(request)?),
-        );
-        output = output.replace(
-            "[[handlers_defs]]",
-            &tools::inject_tabs(3, self.get_handlers_defs(request)?),
-        );
-        output = output.replace(
-            "[[handlers_setters]]",
-            &tools::inject_tabs(1, self.get_handlers_setters(request)?),
-        );
-        output = output.replace(
-            "[[response_handler]]",
-            &tools::inject_tabs(6, self.get_response_handler(request)?),
-        );
+        let output = Template::new("consumer/typescript/request/module", templates::MODULE)
+            .set("types_declarations", &self.get_types_declarations(request)?)
+            .set("reference", &(request.get_request()?).replace(".", ""))
+            .set("struct_ref", &request.get_request()?)
+            .set("struct_interface", &self.get_struct_interface(request)?)
+            .set("resolver", &self.get_resolver_type(request)?)
+            .set(
+                "handlers",
+                &tools::inject_tabs(1, self.get_handlers(request)?),
+            )
+            .set(
+                "handlers_defs",
+                &tools::inject_tabs(3, self.get_handlers_defs(request)?),
+            )
+            .set(
+                "handlers_setters",
+                &tools::inject_tabs(1, self.get_handlers_setters(request)?),
+            )
+            .set(
+                "response_handler",
+                &tools::inject_tabs(6, self.get_response_handler(request)?),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -162,51 +180,88 @@ impl Render {
                     return Err(String::from("Action doesn't have bound response reference"));
                 };
                 output = format!(
-                    "{}\nexport type T{}Handler = (response: Protocol.{}) => void",
-                    output, name, reference,
+                    "{}\n{}",
+                    output,
+                    Template::new(
+                        "consumer/typescript/request/handler_type",
+                        templates::HANDLER_TYPE,
+                    )
+                    .set("name", name)
+                    .set("reference", reference)
+                    .render()
                 );
             }
         } else {
             output = format!("{} | Protocol.{};", output, request.get_response()?,);
             output = format!(
-                "{}\nexport type TResponseHandler = (response: Protocol.{}) => void",
+                "{}\n{}",
                 output,
-                request.get_response()?,
+                Template::new(
+                    "consumer/typescript/request/handler_type",
+                    templates::HANDLER_TYPE,
+                )
+                .set("name", "Response")
+                .set("reference", &request.get_response()?)
+                .render()
             );
         }
         output = format!(
-            "{}\nexport type TErrHandler = (response: Protocol.{}) => void",
+            "{}\n{}",
             output,
-            request.get_err()?,
+            Template::new(
+                "consumer/typescript/request/handler_type",
+                templates::HANDLER_TYPE,
+            )
+            .set("name", "Err")
+            .set("reference", &request.get_err()?)
+            .render()
         );
         Ok(output)
     }
 
     fn get_handlers(&self, request: &Request) -> Result<String, String> {
-        let mut output: String = String::from(templates::HANDLERS);
         let mut declarations: String = String::new();
         let mut init: String = String::new();
+        let mut add = |name: &str, kind: &str| {
+            declarations = format!(
+                "{}\n{}",
+                declarations,
+                Template::new(
+                    "consumer/typescript/request/handler_declaration",
+                    templates::HANDLER_DECLARATION,
+                )
+                .set("name", name)
+                .set("type", kind)
+                .render()
+            );
+            init = format!(
+                "{}\n{}",
+                init,
+                Template::new(
+                    "consumer/typescript/request/handler_init",
+                    templates::HANDLER_INIT,
+                )
+                .set("name", name)
+                .render()
+            );
+        };
         if request.actions.len() > 1 {
             for action in &request.actions {
                 if let Some(name) = action.conclusion.as_ref() {
-                    declarations = format!(
-                        "{}\n{}: T{}Handler | undefined;",
-                        declarations,
-                        name.to_lowercase(),
-                        name,
-                    );
-                    init = format!("{}\n{}: undefined,", init, name.to_lowercase(),);
+                    add(&name.to_lowercase(), name);
                 } else {
                     return Err(String::from("Action doesn't have bound conclusion name"));
                 };
             }
         } else {
-            declarations = String::from("\nresponse: TResponseHandler | undefined;");
-            init = String::from("\nresponse: undefined,");
+            add("response", "Response");
         }
-        output = output.replace("[[declarations]]", &tools::inject_tabs(1, declarations));
-        output = output.replace("[[init]]", &tools::inject_tabs(1, init));
-        Ok(output)
+        Ok(
+            Template::new("consumer/typescript/request/handlers", templates::HANDLERS)
+                .set("declarations", &tools::inject_tabs(1, declarations))
+                .set("init", &tools::inject_tabs(1, init))
+                .render(),
+        )
     }
 
     fn get_handlers_defs(&self, request: &Request) -> Result<String, String> {
@@ -233,10 +288,14 @@ impl Render {
                     output = format!(
                         "{}\n{}",
                         output,
-                        templates::HANDLER_SETTER
-                            .replace("[[name]]", &name.to_lowercase())
-                            .replace("[[type]]", name)
-                            .replace("[[reference]]", &(request.get_request()?).replace(".", "")),
+                        Template::new(
+                            "consumer/typescript/request/handler_setter",
+                            templates::HANDLER_SETTER
+                        )
+                        .set("name", &name.to_lowercase())
+                        .set("type", name)
+                        .set("reference", &(request.get_request()?).replace(".", ""))
+                        .render(),
                     );
                 } else {
                     println!("{:?}", request);
@@ -246,53 +305,42 @@ impl Render {
         } else {
             output = format!(
                 "\n{}\n",
-                templates::HANDLER_SETTER
-                    .replace("[[name]]", "response")
-                    .replace("[[type]]", "Response")
-                    .replace("[[reference]]", &(request.get_request()?).replace(".", "")),
+                Template::new(
+                    "consumer/typescript/request/handler_setter",
+                    templates::HANDLER_SETTER
+                )
+                .set("name", "response")
+                .set("type", "Response")
+                .set("reference", &(request.get_request()?).replace(".", ""))
+                .render(),
             );
         }
         Ok(output)
     }
 
     fn get_response_handler(&self, request: &Request) -> Result<String, String> {
-        let reference: String = request.get_request()?;
-        let mut output: String = format!(
-            r#"if (message === undefined) {{
-    return reject(new Error(`Expecting message for "{}".`));
-}} "#,
-            reference
-        );
+        let mut output: String = Template::new(
+            "consumer/typescript/request/message_check",
+            templates::MESSAGE_CHECK,
+        )
+        .set("reference", &request.get_request()?)
+        .render();
         if request.actions.len() > 1 {
             for action in &request.actions {
                 let reference: String = action.get_response()?;
-                let parts: Vec<&str> = reference.split('.').collect();
-                let mut check_group: String = String::from("if (message !== undefined");
-                let mut extend_group = false;
-                let mut group: String = String::from("message");
-                for (pos, part) in parts.iter().enumerate() {
-                    if pos < parts.len() - 1 {
-                        group = format!("{}.{}", group, part);
-                        check_group = format!("{} && {} !== undefined", check_group, group);
-                        extend_group = true;
-                    }
-                }
-                check_group = if extend_group {
-                    format!("{} && {}.[[response]] !== undefined)", check_group, group)
-                } else {
-                    String::from("if ([[group]].[[response]] !== undefined)")
-                };
+                let (check_group, group) = self.get_check_group(&reference);
                 output = format!(
                     "{}{}",
                     output,
-                    r#"else [[check_group]] {
-    this._handlers.[[handler]] !== undefined && this._handlers.[[handler]]([[group]].[[response]]);
-    return resolve([[group]].[[response]]);
-} "#
-                    .replace("[[check_group]]", &check_group)
-                    .replace("[[group]]", &group)
-                    .replace("[[response]]", &action.get_last_response_entity()?)
-                    .replace("[[handler]]", &action.get_conclusion()?.to_lowercase())
+                    Template::new(
+                        "consumer/typescript/request/conclusion_check",
+                        templates::CONCLUSION_CHECK,
+                    )
+                    .set("check_group", &check_group)
+                    .set("group", &group)
+                    .set("response", &action.get_last_response_entity()?)
+                    .set("handler", &action.get_conclusion()?.to_lowercase())
+                    .render()
                 );
             }
         } else {
@@ -300,59 +348,66 @@ impl Render {
             let parts: Vec<&str> = reference.split('.').collect();
             let mut check_group: String = String::from("else if (message !== undefined");
             let mut group: String = String::from("message");
-            for (_pos, part) in parts.iter().enumerate() {
+            for part in parts.iter() {
                 group = format!("{}.{}", group, part);
                 check_group = format!("{} && {} !== undefined", check_group, group);
             }
-            check_group = format!("{})", check_group);
             output = format!(
-                "{}{}{}",
+                "{}{}",
                 output,
-                check_group.replace("[[response]]", &reference),
-                r#" {
-    this._handlers.response !== undefined && this._handlers.response(message.[[response]]);
-    return resolve(message.[[response]]);
-} "#
-                .replace("[[response]]", &reference)
+                Template::new(
+                    "consumer/typescript/request/response_check",
+                    templates::RESPONSE_CHECK,
+                )
+                .set("check_group", &format!("{})", check_group))
+                .set("response", &reference)
+                .render()
             );
         }
         let reference: String = request.get_err()?;
+        let (check_group, group) = self.get_check_group(&reference);
         let parts: Vec<&str> = reference.split('.').collect();
-        let mut check_group: String = String::from("if (message !== undefined");
-        let mut extend_group = false;
-        let mut group: String = String::from("message");
-        for (pos, part) in parts.iter().enumerate() {
-            if pos < parts.len() - 1 {
-                group = format!("{}.{}", group, part);
-                check_group = format!("{} && {} !== undefined", check_group, group);
-                extend_group = true;
-            }
-        }
-        check_group = if extend_group {
-            format!("{} && {}.[[error]] !== undefined)", check_group, group)
-        } else {
-            String::from("if ([[group]].[[error]] !== undefined)")
-        };
         output = format!(
             "{}{}",
             output,
-            r#"else [[check_group]] {
-    this._handlers.err !== undefined && this._handlers.err([[group]].[[error]]);
-    return resolve([[group]].[[error]]);
-} else {
-    return reject(new Error(`No message in "[[group]]" group.`));
-}"#
-            .replace("[[check_group]]", &check_group)
-            .replace("[[error]]", parts[parts.len() - 1])
-            .replace("[[group]]", &group)
+            Template::new(
+                "consumer/typescript/request/error_check",
+                templates::ERROR_CHECK,
+            )
+            .set("check_group", &check_group)
+            .set("error", parts[parts.len() - 1])
+            .set("group", &group)
+            .render()
         );
         Ok(output)
     }
 
+    // Returns condition to check presence of message inside its group and the group path
+    fn get_check_group(&self, reference: &str) -> (String, String) {
+        let parts: Vec<&str> = reference.split('.').collect();
+        let mut check_group: String = String::from("if (message !== undefined");
+        let mut group: String = String::from("message");
+        for part in parts.iter().take(parts.len() - 1) {
+            group = format!("{}.{}", group, part);
+            check_group = format!("{} && {} !== undefined", check_group, group);
+        }
+        let check_group = if parts.len() > 1 {
+            format!(
+                "{} && {}.{} !== undefined)",
+                check_group,
+                group,
+                parts[parts.len() - 1]
+            )
+        } else {
+            format!("if ({}.{} !== undefined)", group, parts[parts.len() - 1])
+        };
+        (check_group, group)
+    }
+
     fn get_dest_file(&self, base: &Path, request: &Request) -> Result<PathBuf, String> {
         let dest = base.join("requests");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -0,0 +1,309 @@
+use super::{
+    helpers,
+    helpers::template::Template,
+    mkdir,
+    protocol::encryption::Encryption,
+    protocol_rust::{features, get_features},
+    render, Protocol, ProtocolRender, ProtocolRustRender, Target, WorkflowStore,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::{map::Map, Value};
+
+pub mod sides {
+    pub const PROTOCOL: &str = "protocol";
+    pub const PRODUCER: &str = "producer";
+    pub const CONSUMER: &str = "consumer";
+}
+
+mod templates {
+    pub const PACKAGE: &str = r#"[package]
+name = "[[name]]"
+version = "0.1.0"
+edition = "2018"
+
+[dependencies]
+[[dependencies]]
+[[?features]]
+[features]
+[[features]]
+[[/features]]"#;
+    pub const PROTOCOL_DEPENDENCIES: &str = r#"bytes = "1.1.0""#;
+    pub const SIDE_DEPENDENCIES: &str =
+        r#"[[protocol]] = { path = "../[[protocol]]", default-features = false }"#;
+    pub const OPTIONAL_DEPENDENCY: &str =
+        r#"[[name]] = { version = "[[version]]", optional = true }"#;
+    pub const DEFAULT_FEATURES: &str = r#"default = [[[features]]]"#;
+    pub const FEATURE: &str = r#"[[name]] = [[[dependencies]]]"#;
+    pub const PROTOCOL_MODULE: &str = r#"pub use [[protocol]]::*;
+"#;
+}
+
+// Encryption middleware, which is embedded into protocol, requires ring
+const ENCRYPTION_DEPENDENCY: (&str, &str) = ("ring", "0.16.20");
+
+fn get_crate_name(name: &str, side: &str) -> String {
+    format!("{}-{}", name, side)
+}
+
+// Functions below get name of crates, if standalone crates are rendering (None otherwise).
+// In this mode producer and consumer are libraries, which share protocol crate instead of
+// embedding own copy
+
+/// Name of root module file: lib.rs for standalone crates
+pub fn root_file(name: Option<&str>, default: &'static str) -> &'static str {
+    if name.is_some() {
+        "lib.rs"
+    } else {
+        default
+    }
+}
+
+/// Content of implementation/protocol/mod.rs, which refers to shared protocol crate
+pub fn protocol_module(name: Option<&str>) -> Option<String> {
+    name.map(|name| {
+        Template::new("crates/protocol_module", templates::PROTOCOL_MODULE)
+            .set(
+                "protocol",
+                &get_crate_name(name, sides::PROTOCOL).replace('-', "_"),
+            )
+            .render()
+    })
+}
+
+/// Path and content of crate's Cargo.toml. Existing manifest is kept as it is,
+/// because it might be extended by user
+pub fn manifest(
+    name: Option<&str>,
+    base: &Path,
+    side: &str,
+    protocol: &Protocol,
+) -> Result<Option<(PathBuf, String)>, String> {
+    let name = if let Some(name) = name {
+        name
+    } else {
+        return Ok(None);
+    };
+    let target = base
+        .parent()
+        .ok_or_else(|| format!("Fail to get crate folder of {}", base.to_string_lossy()))?
+        .join("Cargo.toml");
+    if target.exists() {
+        let content = fs::read_to_string(&target)
+            .map_err(|e| format!("Fail read {}; error: {}", target.to_string_lossy(), e))?;
+        return Ok(Some((target, content)));
+    }
+    let protocol_crate = get_crate_name(name, sides::PROTOCOL);
+    let dependencies = Template::new("crates/side_dependencies", templates::SIDE_DEPENDENCIES)
+        .set("protocol", &protocol_crate)
+        .render();
+    // Features of protocol crate are forwarded, so they can be chosen on side's crate
+    let features = get_features(protocol)
+        .iter()
+        .map(|(feature, _)| (*feature, vec![format!("{}/{}", protocol_crate, feature)]))
+        .collect::<Vec<(&str, Vec<String>)>>();
+    Ok(Some((
+        target,
+        get_package(&get_crate_name(name, side), &dependencies, &features),
+    )))
+}
+
+/// Dependencies, which are required by protocol itself besides bytes
+pub fn protocol_dependencies(protocol: &Protocol) -> Vec<(&'static str, &'static str)> {
+    if protocol.get_encryption() == Encryption::None {
+        vec![]
+    } else {
+        vec![ENCRYPTION_DEPENDENCY]
+    }
+}
+
+// Each feature is listed with features and optional dependencies, which it enables. All
+// features are enabled by default
+fn get_package(name: &str, dependencies: &str, features: &[(&str, Vec<String>)]) -> String {
+    let quoted = |items: Vec<String>| {
+        items
+            .iter()
+            .map(|item| format!("\"{}\"", item))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let features = if features.is_empty() {
+        String::new()
+    } else {
+        let mut lines = vec![
+            Template::new("crates/default_features", templates::DEFAULT_FEATURES)
+                .set(
+                    "features",
+                    &quoted(features.iter().map(|(name, _)| name.to_string()).collect()),
+                )
+                .render(),
+        ];
+        for (name, dependencies) in features {
+            lines.push(
+                Template::new("crates/feature", templates::FEATURE)
+                    .set("name", name)
+                    .set("dependencies", &quoted(dependencies.clone()))
+                    .render(),
+            );
+        }
+        lines.join("\n")
+    };
+    Template::new("crates/package", templates::PACKAGE)
+        .set("name", name)
+        .set("dependencies", dependencies)
+        .set("features", &features)
+        .render()
+}
+
+/// Renders workspace with crates <name>-protocol, <name>-producer and <name>-consumer,
+/// where <name> is name of destination folder
+pub fn render_crates(
+    dest: &Path,
+    store: WorkflowStore,
+    protocol: &mut Protocol,
+    signature: u16,
+) -> Result<(), String> {
+    let name = dest
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Fail to get name of crates from {}", dest.to_string_lossy()))?
+        .to_owned();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Name of folder \"{}\" cannot be used as name of crates",
+            name
+        ));
+    }
+    let config = store.get_config()?;
+    for (side, outs) in [
+        (sides::CONSUMER, &config.consumer),
+        (sides::PRODUCER, &config.producer),
+    ] {
+        if outs.iter().any(|out| !matches!(out, Target::Rust)) {
+            return Err(format!(
+                "Standalone crates can be generated only for rust. Check targets of {} in workflow",
+                side
+            ));
+        }
+    }
+    let mut members: Vec<String> = vec![get_crate_name(&name, sides::PROTOCOL)];
+    let mut consumer_dest: Option<PathBuf> = None;
+    let mut producer_dest: Option<PathBuf> = None;
+    if !config.consumer.is_empty() {
+        members.push(get_crate_name(&name, sides::CONSUMER));
+        consumer_dest = Some(
+            dest.join(get_crate_name(&name, sides::CONSUMER))
+                .join("src"),
+        );
+    }
+    if !config.producer.is_empty() {
+        members.push(get_crate_name(&name, sides::PRODUCER));
+        producer_dest = Some(
+            dest.join(get_crate_name(&name, sides::PRODUCER))
+                .join("src"),
+        );
+    }
+    let protocol_dest = dest.join(get_crate_name(&name, sides::PROTOCOL));
+    mkdir(&protocol_dest.join("src"))?;
+    let protocol_manifest = protocol_dest.join("Cargo.toml");
+    if !protocol_manifest.exists() {
+        // Dependencies of protocol (ring for encryption) are enabled with features
+        let mut dependencies = vec![Template::new(
+            "crates/protocol_dependencies",
+            templates::PROTOCOL_DEPENDENCIES,
+        )
+        .render()];
+        for (name, version) in protocol_dependencies(protocol) {
+            dependencies.push(
+                Template::new("crates/optional_dependency", templates::OPTIONAL_DEPENDENCY)
+                    .set("name", name)
+                    .set("version", version)
+                    .render(),
+            );
+        }
+        let features = get_features(protocol)
+            .iter()
+            .map(|(feature, _)| {
+                if *feature == features::ENCRYPTION {
+                    (
+                        *feature,
+                        protocol_dependencies(protocol)
+                            .iter()
+                            .map(|(name, _)| name.to_string())
+                            .collect(),
+                    )
+                } else {
+                    (*feature, vec![])
+                }
+            })
+            .collect::<Vec<(&str, Vec<String>)>>();
+        helpers::fs::write(
+            protocol_manifest,
+            get_package(
+                &get_crate_name(&name, sides::PROTOCOL),
+                &dependencies.join("\n"),
+                &features,
+            ),
+            true,
+        )?;
+    }
+    ProtocolRustRender::new(true, signature)
+        .with_features()
+        .render(protocol, &protocol_dest.join("src").join("lib.rs"))?;
+    render(
+        consumer_dest,
+        producer_dest,
+        store,
+        protocol,
+        signature,
+        Some(&name),
+    )?;
+    render_workspace(dest, &members)
+}
+
+fn render_workspace(dest: &Path, members: &[String]) -> Result<(), String> {
+    let target = dest.join("Cargo.toml");
+    let mut manifest = if target.exists() {
+        fs::read_to_string(&target)
+            .map_err(|e| format!("Fail read {}; error: {}", target.to_string_lossy(), e))?
+            .parse::<Value>()
+            .map_err(|e| format!("Fail parse {}; error: {}", target.to_string_lossy(), e))?
+    } else {
+        Value::Table(Map::new())
+    };
+    let root = manifest
+        .as_table_mut()
+        .ok_or_else(|| format!("Fail parse {}", target.to_string_lossy()))?;
+    let workspace = root
+        .entry("workspace")
+        .or_insert_with(|| Value::Table(Map::new()))
+        .as_table_mut()
+        .ok_or_else(|| {
+            format!(
+                "Invalid section \"workspace\" in {}",
+                target.to_string_lossy()
+            )
+        })?;
+    let current = workspace
+        .entry("members")
+        .or_insert_with(|| Value::Array(vec![]))
+        .as_array_mut()
+        .ok_or_else(|| format!("Invalid workspace.members in {}", target.to_string_lossy()))?;
+    for member in members {
+        if !current.iter().any(|m| m.as_str() == Some(member)) {
+            current.push(Value::String(member.clone()));
+        }
+    }
+    helpers::fs::write(
+        target.clone(),
+        toml::ser::to_string_pretty(&manifest)
+            .map_err(|e| format!("Fail to write {}; error: {}", target.to_string_lossy(), e))?,
+        true,
+    )
+}
@@ -0,0 +1,156 @@
+use super::{
+    protocol::{
+        enums::{Enum, EnumItem},
+        fields::Field,
+        groups::Group,
+        structs::Struct,
+        types::PrimitiveTypes,
+    },
+    workflow::{
+        beacon::Broadcast, config::Config, request::Request, store::Store as WorkflowStore,
+    },
+    Protocol, Target,
+};
+use serde_json::{json, Value};
+
+pub const VERSION: u8 = 1;
+
+pub fn get(protocol: &Protocol, store: &WorkflowStore) -> Result<Value, String> {
+    Ok(json!({
+        "version": VERSION,
+        "protocol": get_protocol(protocol),
+        "workflow": get_workflow(store)?,
+    }))
+}
+
+pub fn get_protocol(protocol: &Protocol) -> Value {
+    json!({
+        "hash": protocol.get_hash(),
+        "source_hash": protocol.get_source_hash(),
+        "groups": protocol.groups.iter().map(group).collect::<Vec<Value>>(),
+        "structs": protocol.structs.iter().map(strct).collect::<Vec<Value>>(),
+        "enums": protocol.enums.iter().map(enums).collect::<Vec<Value>>(),
+    })
+}
+
+fn get_workflow(store: &WorkflowStore) -> Result<Value, String> {
+    Ok(json!({
+        "hash": store.get_hash(),
+        "source_hash": store.get_source_hash(),
+        "compatible": store.get_compatible().iter().map(|(protocol, workflow)| json!({
+            "protocol": protocol,
+            "workflow": workflow,
+        })).collect::<Vec<Value>>(),
+        "config": config(store.get_config()?),
+        "requests": store.requests.iter().map(request).collect::<Result<Vec<Value>, String>>()?,
+        "events": store.events.iter().map(|event| {
+            Ok(json!({
+                "reference": event.get_reference()?,
+                "default": event.is_default(),
+                "broadcasts": event.broadcasts.iter().map(broadcast).collect::<Vec<Value>>(),
+            }))
+        }).collect::<Result<Vec<Value>, String>>()?,
+        "beacons": store.beacons.iter().map(broadcast).collect::<Vec<Value>>(),
+    }))
+}
+
+fn group(group: &Group) -> Value {
+    json!({
+        "id": group.id,
+        "parent": group.parent,
+        "name": group.name,
+        "structs": group.structs,
+        "enums": group.enums,
+        "groups": group.groups,
+    })
+}
+
+fn strct(strct: &Struct) -> Value {
+    json!({
+        "id": strct.id,
+        "parent": strct.parent,
+        "name": strct.name,
+        "path": strct.path,
+        "fields": strct.fields.iter().map(field).collect::<Vec<Value>>(),
+    })
+}
+
+fn field(field: &Field) -> Value {
+    json!({
+        "id": field.id,
+        "name": field.name,
+        "kind": field.kind,
+        "ref_type_id": field.ref_type_id,
+        "ref_type_path": field.get_full_name(),
+        "repeated": field.repeated,
+        "optional": field.optional,
+    })
+}
+
+fn enums(enums: &Enum) -> Value {
+    json!({
+        "id": enums.id,
+        "parent": enums.parent,
+        "name": enums.name,
+        "path": enums.path,
+        "variants": enums.variants.iter().map(variant).collect::<Vec<Value>>(),
+    })
+}
+
+fn variant(item: &EnumItem) -> Value {
+    json!({
+        "name": item.name,
+        "kind": item
+            .types
+            .clone()
+            .and_then(PrimitiveTypes::get_entity_as_string),
+        "ref_type_id": item.ref_type_id,
+        "ref_type_path": item.type_path,
+        "repeated": item.repeated,
+    })
+}
+
+fn config(config: &Config) -> Value {
+    json!({
+        "producer": config.producer.iter().map(target).collect::<Vec<String>>(),
+        "consumer": config.consumer.iter().map(target).collect::<Vec<String>>(),
+        "self_key": config.self_key,
+        "self_key_response": config.self_key_response,
+        "assigned_key": config.assigned_key,
+        "handlers": config.get_handlers().as_str(),
+        "codec": config.get_codec().as_str(),
+        "compression": config.get_compression().as_str(),
+        "compression_threshold": config.get_compression_threshold(),
+        "encryption": config.get_encryption().as_str(),
+        "checksum": config.get_checksum().as_str(),
+    })
+}
+
+fn request(request: &Request) -> Result<Value, String> {
+    Ok(json!({
+        "request": request.get_request()?,
+        "error": request.error,
+        "actions": request.actions.iter().map(|action| {
+            json!({
+                "conclusion": action.conclusion,
+                "response": action.response,
+                "broadcasts": action.broadcast.iter().map(broadcast).collect::<Vec<Value>>(),
+            })
+        }).collect::<Vec<Value>>(),
+    }))
+}
+
+fn broadcast(broadcast: &Broadcast) -> Value {
+    json!({
+        "reference": broadcast.reference,
+        "optional": broadcast.optional,
+    })
+}
+
+pub fn target(target: &Target) -> String {
+    match target {
+        Target::Rust => String::from("rust"),
+        Target::TypeScript => String::from("typescript"),
+        Target::External(name) => name.clone(),
+    }
+}
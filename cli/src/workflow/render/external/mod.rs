@@ -0,0 +1,152 @@
+pub mod ir;
+
+use super::{
+    helpers, protocol, workflow, workflow::store::Store as WorkflowStore, Protocol, Target,
+};
+use serde_json::{json, Value};
+use std::{
+    io::Write,
+    path::{Component, Path},
+    process::{Command, Stdio},
+    thread,
+};
+
+pub const EXECUTABLE_PREFIX: &str = "clibri-gen-";
+
+pub mod sides {
+    pub const PRODUCER: &str = "producer";
+    pub const CONSUMER: &str = "consumer";
+}
+
+pub struct ExternalRender {
+    name: String,
+}
+
+impl ExternalRender {
+    pub fn new(name: &str) -> Self {
+        ExternalRender {
+            name: name.to_owned(),
+        }
+    }
+
+    pub fn render(
+        &self,
+        base: &Path,
+        side: &str,
+        store: &WorkflowStore,
+        protocol: &Protocol,
+    ) -> Result<(), String> {
+        let mut input = ir::get(protocol, store)?;
+        if let Value::Object(map) = &mut input {
+            map.insert(String::from("side"), json!(side));
+            map.insert(String::from("target"), json!(self.name));
+        }
+        let output = self.exec(side, &input)?;
+        let files = match output.get("files") {
+            Some(Value::Array(files)) => files,
+            _ => {
+                return Err(format!(
+                    "{}: output doesn't have \"files\" list",
+                    self.get_executable()
+                ));
+            }
+        };
+        for file in files {
+            let path = file.get("path").and_then(|v| v.as_str()).ok_or(format!(
+                "{}: each file should have \"path\"",
+                self.get_executable()
+            ))?;
+            let content = file.get("content").and_then(|v| v.as_str()).ok_or(format!(
+                "{}: file {} doesn't have \"content\"",
+                self.get_executable(),
+                path
+            ))?;
+            let overwrite = file
+                .get("overwrite")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let relative = Path::new(path);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(format!(
+                    "{}: path {} should be relative to destination folder",
+                    self.get_executable(),
+                    path
+                ));
+            }
+            let dest = base.join(relative);
+            if dest.exists() && !overwrite {
+                println!("[SKIP]: {}", dest.to_string_lossy());
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                if !parent.exists() {
+                    helpers::fs::create_dir_all(parent).map_err(|e| {
+                        format!(
+                            "Fail to create dest folder {}. Error: {}",
+                            parent.to_string_lossy(),
+                            e
+                        )
+                    })?;
+                }
+            }
+            // Content of plugin's file is written as it is: format of output is defined by plugin
+            helpers::stubs::known(&dest);
+            helpers::fs::save(dest, content.to_owned(), true)?;
+        }
+        Ok(())
+    }
+
+    fn exec(&self, side: &str, input: &Value) -> Result<Value, String> {
+        let executable = self.get_executable();
+        let mut child = Command::new(&executable)
+            .arg(side)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                format!(
+                    "Fail to start renderer \"{}\" for target \"{}\" (should be available in PATH). Error: {}",
+                    executable, self.name, e
+                )
+            })?;
+        // IR is written from separate thread; otherwise renderer, which writes output
+        // while reading input, would be blocked on full pipe together with clibri
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.to_string();
+            thread::spawn(move || stdin.write_all(input.as_bytes()))
+        });
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Fail to get output of {}. Error: {}", executable, e))?;
+        if let Some(writer) = writer {
+            // If renderer failed, its stderr is more informative than broken pipe
+            match writer.join() {
+                Ok(Err(e)) if output.status.success() => {
+                    return Err(format!("Fail to pass IR to {}. Error: {}", executable, e));
+                }
+                Err(_) => {
+                    return Err(format!("Fail to pass IR to {}", executable));
+                }
+                _ => {}
+            }
+        }
+        if !output.status.success() {
+            return Err(format!(
+                "Renderer {} has been finished with {}:\n{}",
+                executable,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Fail to parse output of {}. Error: {}", executable, e))
+    }
+
+    fn get_executable(&self) -> String {
+        format!("{}{}", EXECUTABLE_PREFIX, self.name)
+    }
+}
@@ -16,20 +16,55 @@ pub mod render_request_handlers_mod;
 pub mod render_request_response;
 pub mod render_request_responses_mod;
 pub mod render_static;
+pub mod render_traits;
 
 use super::{
-    helpers, workflow, workflow::store::Store as WorkflowStore, ImplementationRender, Protocol,
-    ProtocolRender, ProtocolRustRender,
+    crates, helpers, helpers::template::Template, protocol, workflow, workflow::config::Handlers,
+    workflow::store::Store as WorkflowStore, ImplementationRender, Protocol, ProtocolRender,
+    ProtocolRustRender,
 };
 use std::path::Path;
 
-pub struct RustRender {}
+// With Handlers::Trait user's implementation of ProducerHandlers takes place of Context
+// and handlers are called as methods of it instead of functions from stub's modules
+pub fn inject_context(
+    template: Template,
+    handlers: &Handlers,
+    module: &str,
+    name: &str,
+    func: &str,
+) -> Template {
+    match handlers {
+        Handlers::Files => template
+            .set("context_ref", "Context")
+            .set("context", "Context")
+            .set("scope_context", "context, ")
+            .set("handlers_mod", &format!("{}, ", module))
+            .set("handler", &format!("{}::{}::{}", module, name, func)),
+        Handlers::Trait => template
+            .set("context_ref", "ProducerHandlers")
+            .set("context", "impl ProducerHandlers")
+            .set("scope_context", "")
+            .set("handlers_mod", "")
+            .set("handler", &format!("context.{}", name)),
+    }
+}
+
+pub struct RustRender {
+    // Name of crates, if producer and consumer are rendered as standalone crates
+    crate_name: Option<String>,
+}
 
-impl RustRender {}
+impl RustRender {
+    pub fn with_crate(mut self, crate_name: Option<&str>) -> Self {
+        self.crate_name = crate_name.map(|name| name.to_owned());
+        self
+    }
+}
 
 impl ImplementationRender<ProtocolRustRender> for RustRender {
     fn new() -> Self {
-        RustRender {}
+        RustRender { crate_name: None }
     }
 
     fn render(
@@ -39,30 +74,54 @@ impl ImplementationRender<ProtocolRustRender> for RustRender {
         protocol: &mut Protocol,
         protocol_render: ProtocolRustRender,
     ) -> Result<String, String> {
+        let handlers = store.get_config()?.get_handlers();
+        let stubs = handlers == Handlers::Files;
         for request in &store.requests {
-            (render_request_response::Render::new()).render(base, request)?;
-            (render_request_handler::Render::new()).render(base, request)?;
+            if stubs {
+                (render_request_response::Render::new()).render(base, request)?;
+            }
+            (render_request_handler::Render::new()).render(base, request, &handlers)?;
+        }
+        if stubs {
+            (render_request_responses_mod::Render::new()).render(base, &store.requests)?;
         }
-        (render_request_responses_mod::Render::new()).render(base, &store.requests)?;
         (render_request_handlers_mod::Render::new()).render(base, &store.requests)?;
         for event in &store.events {
-            (render_event_impl::Render::new()).render(base, event)?;
-            (render_event_emitter::Render::new()).render(base, event)?;
+            if stubs {
+                (render_event_impl::Render::new()).render(base, event)?;
+            }
+            (render_event_emitter::Render::new()).render(base, event, &handlers)?;
         }
         for beacon in &store.beacons {
-            (render_beacon_impl::Render::new()).render(base, beacon)?;
-            (render_beacon_emitter::Render::new()).render(base, beacon)?;
+            if stubs {
+                (render_beacon_impl::Render::new()).render(base, beacon)?;
+            }
+            (render_beacon_emitter::Render::new()).render(base, beacon, &handlers)?;
         }
         (render_beacon_emitters_mod::Render::new()).render(base, &store.beacons)?;
-        (render_beacon_impl_mod::Render::new()).render(base, &store.beacons)?;
-        (render_event_impl_mod::Render::new()).render(base, &store.events)?;
+        if stubs {
+            (render_beacon_impl_mod::Render::new()).render(base, &store.beacons)?;
+            (render_event_impl_mod::Render::new()).render(base, &store.events)?;
+        } else {
+            (render_traits::Render::new()).render(base, store)?;
+        }
         (render_event_emitters_mod::Render::new()).render(base, &store.events)?;
-        (render_static::Render::new()).render(base, &store.events)?;
-        (render_protocol::Render::new()).render(base, protocol, &protocol_render)?;
+        (render_static::Render::new()).render(
+            base,
+            &store.events,
+            &handlers,
+            self.crate_name.as_deref(),
+        )?;
+        (render_protocol::Render::new()).render(
+            base,
+            protocol,
+            &protocol_render,
+            self.crate_name.as_deref(),
+        )?;
         (render_identification::Render::new()).render(base, store, protocol)?;
         (render_consumer::Render::new()).render(base, store)?;
         (render_mod::Render::new()).render(base, store, protocol)?;
-        (render_cargo::Render::new()).render(base)?;
+        (render_cargo::Render::new()).render(base, protocol, self.crate_name.as_deref())?;
         Ok(String::new())
     }
 }
@@ -1,12 +1,12 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::template::Template, inject_context, workflow::beacon::Broadcast,
+    workflow::config::Handlers,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::{
-    beacons, identification, pack, producer::Control, protocol, Context, EmitterError,
+    [[handlers_mod]]identification, pack, producer::Control, protocol, [[context_ref]], EmitterError,
     ProducerError, scope::Scope,
 };
 use clibri::server;
@@ -15,15 +15,20 @@ pub async fn emit<E: server::Error, C: server::Control<E>>(
     identification: &identification::Identification,
     beacon: &protocol::[[beacon]],
     sequence: u32,
+    ts: u64,
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     control: &Control<E, C>,
 ) -> Result<(), EmitterError> {
-    let mut scope: Scope<'_, E, C> = Scope::new(context, control, identification, filter);
-    beacons::[[beacon_mod]]::emit(beacon, &mut scope)
+    let mut scope: Scope<'_, E, C> =
+        Scope::new([[scope_context]]control, identification, filter).with_ts(ts);
+    [[handler]](beacon, &mut scope)
         .await
         .map_err(EmitterError::Emitting)?;
-    let mut response = protocol::InternalServiceGroup::BeaconConfirmation { error: None };
+    let mut response = protocol::InternalServiceGroup::BeaconConfirmation {
+        error: None,
+        __unknown: vec![],
+    };
     let buffer = pack(&sequence, &identification.uuid(), &mut response)?;
     control
         .send(buffer, Some(identification.uuid()))
@@ -47,21 +52,33 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path, beacon: &Broadcast) -> Result<(), String> {
+    pub fn render(
+        &self,
+        base: &Path,
+        beacon: &Broadcast,
+        handlers: &Handlers,
+    ) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, beacon)?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[beacon]]", &beacon.reference.replace(".", "::"));
-        output = output.replace(
-            "[[beacon_mod]]",
+        let output = inject_context(
+            Template::new("producer/rust/beacon_emitter/module", templates::MODULE),
+            handlers,
+            "beacons",
             &beacon.reference.to_lowercase().replace(".", "_"),
-        );
+            "emit",
+        )
+        .set("beacon", &beacon.reference.replace(".", "::"))
+        .set(
+            "beacon_mod",
+            &beacon.reference.to_lowercase().replace(".", "_"),
+        )
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path, beacon: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
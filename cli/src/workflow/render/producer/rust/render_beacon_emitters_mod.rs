@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"[[mods]]
@@ -27,6 +24,7 @@ pub fn pack(
     msg.pack(*sequence, Some(uuid.to_string()))
         .map_err(EmitterError::Packing)
 }"#;
+    pub const MOD: &str = r#"pub mod [[name]];"#;
 }
 
 pub struct Render {}
@@ -47,19 +45,26 @@ impl Render {
         let mut mods = String::new();
         for beacon in beacons.iter() {
             mods = format!(
-                "{}pub mod {};\n",
+                "{}{}\n",
                 mods,
-                beacon.reference.to_lowercase().replace(".", "_")
+                Template::new("producer/rust/beacon_emitters_mod/mod", templates::MOD)
+                    .set("name", &beacon.reference.to_lowercase().replace(".", "_"))
+                    .render()
             );
         }
-        let output = templates::MODULE.to_owned().replace("[[mods]]", &mods);
+        let output = Template::new(
+            "producer/rust/beacon_emitters_mod/module",
+            templates::MODULE,
+        )
+        .set("mods", &mods)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::{identification, producer::Control, protocol, Context, scope::Scope};
@@ -33,19 +32,16 @@ impl Render {
 
     pub fn render(&self, base: &Path, beacon: &Broadcast) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, beacon)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[beacon]]", &tools::into_rust_path(&beacon.reference));
-        helpers::fs::write(dest, output, true)
+        let output = Template::new("producer/rust/beacon_impl/module", templates::MODULE)
+            .set("beacon", &tools::into_rust_path(&beacon.reference))
+            .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn get_dest_file(&self, base: &Path, beacon: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::store::Store};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::store::Store,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"#![allow(dead_code)]
@@ -38,15 +37,16 @@ impl Consumer {
     ) -> Self {
         Self {
             uuid,
-            buffer: protocol::Buffer::new(),
+            buffer: protocol::Buffer::with_limits(options.limits.clone()),
             identification: identification::Identification::new(uuid, options, tx_ident_change),
             hash_accepted: false,
             confirmed: false,
         }
     }
 
-    pub fn accept_hash(&mut self) {
+    pub fn accept_hash(&mut self, version: usize) {
         self.hash_accepted = true;
+        self.identification.set_version(version);
     }
 
     pub fn is_hash_accepted(&self) -> bool {
@@ -97,6 +97,11 @@ impl Consumer {
         }
         msgs
     }
+
+    // Messages, which were skipped because of errors
+    pub fn get_errors(&mut self) -> Vec<protocol::FrameError> {
+        self.buffer.take_errors()
+    }
 }"#;
 }
 
@@ -115,22 +120,23 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &Store) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[assign_key]]",
-            &tools::into_rust_path(&store.get_config()?.get_assigned()?),
-        );
-        output = output.replace(
-            "[[self_key]]",
-            &tools::into_rust_path(&store.get_config()?.get_self()?),
-        );
+        let output = Template::new("producer/rust/consumer/module", templates::MODULE)
+            .set(
+                "assign_key",
+                &tools::into_rust_path(&store.get_config()?.get_assigned()?),
+            )
+            .set(
+                "self_key",
+                &tools::into_rust_path(&store.get_config()?.get_self()?),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -73,14 +73,15 @@ impl Render {
                 mods = format!("{}pub mod {};\n", mods, event.as_mod_name()?);
             }
         }
-        let output = templates::MODULE.to_owned().replace("[[mods]]", &mods);
+        let output = helpers::template::get("producer/rust/context/module", templates::MODULE)
+            .replace("[[mods]]", &mods);
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("emitters");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,14 +1,12 @@
 use super::{
-    helpers, helpers::render as tools, workflow::beacon::Broadcast, workflow::event::Event,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, inject_context,
+    workflow::beacon::Broadcast, workflow::config::Handlers, workflow::event::Event,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_BROADCAST: &str = r#"use super::{
-    broadcast, events, identification, producer::Control, protocol, unbound_pack, Context,
+    broadcast, [[handlers_mod]]identification, producer::Control, protocol, unbound_pack, [[context_ref]],
     EmitterError, scope::AnonymousScope,
 };
 use clibri::server;
@@ -17,13 +15,13 @@ use uuid::Uuid;
 pub async fn emit<E: server::Error, C: server::Control<E>>(
     event: protocol::[[event]],
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     control: &Control<E, C>,
 ) -> Result<(), EmitterError> {
-    let mut scope: AnonymousScope<'_, E, C> = AnonymousScope::new(context, control, filter);
+    let mut scope: AnonymousScope<'_, E, C> = AnonymousScope::new([[scope_context]]control, filter);
     let mut broadcasting: Vec<(Vec<Uuid>, Vec<u8>)> = vec![];
     let [[broadcast_vars]] =
-        events::[[event_mod]]::emit(event, &mut scope)
+        [[handler]](event, &mut scope)
             .await
             .map_err(EmitterError::Emitting)?;
 [[broadcasts_processing]]
@@ -34,7 +32,7 @@ pub async fn emit<E: server::Error, C: server::Control<E>>(
     Ok(())
 }"#;
     pub const MODULE_WITHOUT_BROADCAST: &str = r#"use super::{
-    broadcast, events, identification, producer::Control, protocol, unbound_pack, Context,
+    broadcast, [[handlers_mod]]identification, producer::Control, protocol, unbound_pack, [[context_ref]],
     EmitterError, scope::AnonymousScope,
 };
 use clibri::server;
@@ -43,18 +41,18 @@ use uuid::Uuid;
 pub async fn emit<E: server::Error, C: server::Control<E>>(
     event: protocol::[[event]],
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     control: &Control<E, C>,
 ) -> Result<(), EmitterError> {
-    let mut scope: AnonymousScope<'_, E, C> = AnonymousScope::new(context, control, filter);
-    events::[[event_mod]]::emit(event, &mut scope)
+    let mut scope: AnonymousScope<'_, E, C> = AnonymousScope::new([[scope_context]]control, filter);
+    [[handler]](event, &mut scope)
         .await
         .map_err(EmitterError::Emitting)?;
     scope.call().await;
     Ok(())
 }"#;
     pub const DEFAULT_MODULE_WITH_BROADCAST: &str = r#"use super::{
-    broadcast, events, identification, producer::Control, protocol, unbound_pack, Context,
+    broadcast, [[handlers_mod]]identification, producer::Control, protocol, unbound_pack, [[context_ref]],
     EmitterError, scope::Scope,
 };
 use clibri::server;
@@ -63,13 +61,13 @@ use uuid::Uuid;
 pub async fn emit<E: server::Error, C: server::Control<E>>(
     identification: &identification::Identification,
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     control: &Control<E, C>,
 ) -> Result<(), EmitterError> {
-    let mut scope: Scope<'_, E, C> = Scope::new(context, control, identification, filter);
+    let mut scope: Scope<'_, E, C> = Scope::new([[scope_context]]control, identification, filter);
     let mut broadcasting: Vec<(Vec<Uuid>, Vec<u8>)> = vec![];
     let [[broadcast_vars]] =
-        events::[[event_mod]]::emit(&mut scope)
+        [[handler]](&mut scope)
             .await
             .map_err(EmitterError::Emitting)?;
 [[broadcasts_processing]]
@@ -80,7 +78,7 @@ pub async fn emit<E: server::Error, C: server::Control<E>>(
     Ok(())
 }"#;
     pub const DEFAULT_MODULE_WITHOUT_BROADCAST: &str = r#"use super::{
-    broadcast, events, identification, producer::Control, protocol, unbound_pack, Context,
+    broadcast, [[handlers_mod]]identification, producer::Control, protocol, unbound_pack, [[context_ref]],
     EmitterError, scope::Scope,
 };
 use clibri::server;
@@ -89,15 +87,25 @@ use uuid::Uuid;
 pub async fn emit<E: server::Error, C: server::Control<E>>(
     identification: &identification::Identification,
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     control: &Control<E, C>,
 ) -> Result<(), EmitterError> {
-    let mut scope: Scope<'_, E, C> = Scope::new(context, control, identification, filter);
-    events::[[event_mod]]::emit(&mut scope)
+    let mut scope: Scope<'_, E, C> = Scope::new([[scope_context]]control, identification, filter);
+    [[handler]](&mut scope)
         .await
         .map_err(EmitterError::Emitting)?;
     scope.call().await;
     Ok(())
+}"#;
+    pub const BROADCAST: &str = r#"broadcasting.push((
+    [[var]].0,
+    unbound_pack(&0, &mut [[var]].1)?,
+));"#;
+    pub const BROADCAST_OPTIONAL: &str = r#"if let Some(mut broadcast_message) = [[var]].take() {
+    broadcasting.push((
+        broadcast_message.0,
+        unbound_pack(&0, &mut broadcast_message.1)?,
+    ));
 }"#;
 }
 
@@ -114,82 +122,75 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path, event: &Event) -> Result<(), String> {
+    pub fn render(&self, base: &Path, event: &Event, handlers: &Handlers) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, event)?;
-        let mut output: String = if event.is_default() {
+        let template = if event.is_default() {
             if event.broadcasts.is_empty() {
-                templates::DEFAULT_MODULE_WITHOUT_BROADCAST.to_owned()
+                Template::new(
+                    "producer/rust/event_emitter/default_module_without_broadcast",
+                    templates::DEFAULT_MODULE_WITHOUT_BROADCAST,
+                )
             } else {
-                templates::DEFAULT_MODULE_WITH_BROADCAST.to_owned()
+                Template::new(
+                    "producer/rust/event_emitter/default_module_with_broadcast",
+                    templates::DEFAULT_MODULE_WITH_BROADCAST,
+                )
             }
         } else if event.broadcasts.is_empty() {
-            templates::MODULE_WITHOUT_BROADCAST.to_owned()
+            Template::new(
+                "producer/rust/event_emitter/module_without_broadcast",
+                templates::MODULE_WITHOUT_BROADCAST,
+            )
         } else {
-            templates::MODULE_WITH_BROADCAST.to_owned()
+            Template::new(
+                "producer/rust/event_emitter/module_with_broadcast",
+                templates::MODULE_WITH_BROADCAST,
+            )
         };
-        output = output.replace("[[event]]", &tools::into_rust_path(&event.get_reference()?));
-        output = output.replace(
-            "[[event_mod]]",
-            &event.get_reference()?.to_lowercase().replace(".", "_"),
-        );
-        if !event.broadcasts.is_empty() {
-            output = output.replace("[[event]]", &tools::into_rust_path(&event.get_reference()?));
-            output = output.replace(
-                "[[event_mod]]",
-                &event.get_reference()?.to_lowercase().replace(".", "_"),
-            );
-            let mut processing = String::new();
-            let mut vars = String::new();
-            for (pos, broadcast) in event.broadcasts.iter().enumerate() {
-                let var_name = self.get_broadcast_var_name(broadcast);
-                if broadcast.optional {
-                    processing = format!(
-                        r#"{}if let Some(mut broadcast_message) = {}.take() {{
-    broadcasting.push((
-        broadcast_message.0,
-        unbound_pack(&0, &mut broadcast_message.1)?,
-    ));
-}}{}"#,
-                        processing,
-                        var_name,
-                        if pos == event.broadcasts.len() - 1 {
-                            ""
-                        } else {
-                            "\n"
-                        }
-                    );
-                } else {
-                    processing = format!(
-                        r#"{}broadcasting.push((
-    {}.0,
-    unbound_pack(&0, &mut {}.1)?,
-));{}"#,
-                        processing,
-                        var_name,
-                        var_name,
-                        if pos == event.broadcasts.len() - 1 {
-                            ""
-                        } else {
-                            "\n"
-                        }
-                    );
-                }
-                vars = format!(
-                    "{}{}mut {}",
-                    vars,
-                    if pos == 0 { "" } else { ", " },
-                    var_name
-                );
-            }
-            output = output.replace(
-                "[[broadcasts_processing]]",
-                &tools::inject_tabs(1, processing),
-            );
-            if event.broadcasts.len() > 1 {
-                vars = format!("({})", vars);
-            }
-            output = output.replace("[[broadcast_vars]]", &vars);
+        let mut processing = vec![];
+        let mut vars = vec![];
+        for broadcast in event.broadcasts.iter() {
+            let var_name = self.get_broadcast_var_name(broadcast);
+            processing.push(if broadcast.optional {
+                Template::new(
+                    "producer/rust/event_emitter/broadcast_optional",
+                    templates::BROADCAST_OPTIONAL,
+                )
+                .set("var", &var_name)
+                .render()
+            } else {
+                Template::new(
+                    "producer/rust/event_emitter/broadcast",
+                    templates::BROADCAST,
+                )
+                .set("var", &var_name)
+                .render()
+            });
+            vars.push(format!("mut {}", var_name));
         }
+        let vars = if vars.len() > 1 {
+            format!("({})", vars.join(", "))
+        } else {
+            vars.join(", ")
+        };
+        let output = inject_context(
+            template,
+            handlers,
+            "events",
+            &event.get_reference()?.to_lowercase().replace(".", "_"),
+            "emit",
+        )
+        .set("event", &tools::into_rust_path(&event.get_reference()?))
+        .set(
+            "event_mod",
+            &event.get_reference()?.to_lowercase().replace(".", "_"),
+        )
+        .set(
+            "broadcasts_processing",
+            &tools::inject_tabs(1, processing.join("\n")),
+        )
+        .set("broadcast_vars", &vars)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -200,7 +201,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, event: &Event) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("emitters");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,8 +1,5 @@
-use super::{helpers, workflow::event::Event};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::event::Event};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"[[mods]]pub mod connected;
@@ -50,6 +47,7 @@ pub async fn broadcast<E: server::Error, C: server::Control<E> + Send + Clone>(
         .map_err(|e: ProducerError<E>| EmitterError::Processing(e.to_string()))?;
     Ok(())
 }"#;
+    pub const MOD: &str = r#"pub mod [[name]];"#;
 }
 
 pub struct Render {}
@@ -71,20 +69,27 @@ impl Render {
         for event in events.iter() {
             if !event.is_default() {
                 mods = format!(
-                    "{}pub mod {};\n",
+                    "{}{}\n",
                     mods,
-                    event.get_reference()?.to_lowercase().replace(".", "_")
+                    Template::new("producer/rust/event_emitters_mod/mod", templates::MOD)
+                        .set(
+                            "name",
+                            &event.get_reference()?.to_lowercase().replace(".", "_")
+                        )
+                        .render()
                 );
             }
         }
-        let output = templates::MODULE.to_owned().replace("[[mods]]", &mods);
+        let output = Template::new("producer/rust/event_emitters_mod/module", templates::MODULE)
+            .set("mods", &mods)
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("emitters");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
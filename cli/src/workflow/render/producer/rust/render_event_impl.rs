@@ -1,10 +1,8 @@
 use super::{
-    helpers, helpers::render as tools, workflow::beacon::Broadcast, workflow::event::Event,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast,
+    workflow::event::Event,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"use super::{identification, producer::Control, protocol, Context, scope::AnonymousScope};
@@ -30,6 +28,9 @@ pub async fn emit<E: server::Error, C: server::Control<E>>(
 ) -> Result<[[broadcast_refs]], String> {
     panic!("Handler for protocol::[[event]] isn't implemented");
 }"#;
+    pub const BROADCAST_TYPE: &str = r#"type [[name]] = (Vec<Uuid>, protocol::[[reference]]);"#;
+    pub const BROADCAST_TYPE_OPTIONAL: &str =
+        r#"type [[name]] = Option<(Vec<Uuid>, protocol::[[reference]])>;"#;
 }
 
 pub struct Render {}
@@ -47,43 +48,52 @@ impl Render {
 
     pub fn render(&self, base: &Path, event: &Event) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, event)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
-        let mut output: String = if self.is_default(event)? {
-            templates::DEFAULT_MODULE.to_owned()
+        let (types, refs) = self.get_broadcasts(event);
+        let output = if self.is_default(event)? {
+            Template::new(
+                "producer/rust/event_impl/default_module",
+                templates::DEFAULT_MODULE,
+            )
         } else {
-            templates::MODULE.to_owned()
-        };
-        output = output.replace("[[event]]", &tools::into_rust_path(&event.get_reference()?));
+            Template::new("producer/rust/event_impl/module", templates::MODULE)
+        }
+        .set("event", &tools::into_rust_path(&event.get_reference()?))
+        .set("broadcast_types", &types)
+        .set("broadcast_refs", &refs)
+        .render();
+        helpers::stubs::write(dest, output)
+    }
+
+    // Returns declarations of broadcast types and output of handler
+    pub fn get_broadcasts(&self, event: &Event) -> (String, String) {
         let mut types = String::new();
         let mut refs = String::new();
         for (pos, broadcast) in event.broadcasts.iter().enumerate() {
             let type_name = self.get_broadcast_type_name(broadcast);
-            if broadcast.optional {
-                types = format!(
-                    "{}type {} = Option<(Vec<Uuid>, protocol::{})>;\n",
-                    types,
-                    type_name,
-                    tools::into_rust_path(&broadcast.reference),
-                );
-            } else {
-                types = format!(
-                    "{}type {} = (Vec<Uuid>, protocol::{});\n",
-                    types,
-                    type_name,
-                    tools::into_rust_path(&broadcast.reference),
-                );
-            }
+            types = format!(
+                "{}{}\n",
+                types,
+                if broadcast.optional {
+                    Template::new(
+                        "producer/rust/event_impl/broadcast_type_optional",
+                        templates::BROADCAST_TYPE_OPTIONAL,
+                    )
+                } else {
+                    Template::new(
+                        "producer/rust/event_impl/broadcast_type",
+                        templates::BROADCAST_TYPE,
+                    )
+                }
+                .set("name", &type_name)
+                .set("reference", &tools::into_rust_path(&broadcast.reference))
+                .render()
+            );
             refs = format!("{}{}{}", refs, if pos == 0 { "" } else { ", " }, type_name);
         }
         if event.broadcasts.len() > 1 {
             refs = format!("({})", refs);
         }
-        output = output.replace("[[broadcast_types]]", &types);
-        output = output.replace("[[broadcast_refs]]", &refs);
-        helpers::fs::write(dest, output, true)
+        (types, refs)
     }
 
     fn is_default(&self, event: &Event) -> Result<bool, String> {
@@ -101,7 +111,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, event: &Event) -> Result<PathBuf, String> {
         let dest = base.join("events");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
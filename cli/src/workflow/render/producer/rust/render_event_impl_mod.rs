@@ -1,8 +1,16 @@
-use super::{helpers, workflow::event::Event};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::event::Event};
+use std::path::{Path, PathBuf};
+
+mod templates {
+    pub const MODULE: &str = r#"use super::*;
+pub mod connected;
+pub mod disconnected;
+pub mod error;
+pub mod ready;
+pub mod shutdown;
+[[mods]]"#;
+    pub const MOD: &str = r#"pub mod [[name]];"#;
+}
 
 pub struct Render {}
 
@@ -19,31 +27,31 @@ impl Render {
 
     pub fn render(&self, base: &Path, events: &[Event]) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = String::from(
-            r#"use super::*;
-pub mod connected;
-pub mod disconnected;
-pub mod error;
-pub mod ready;
-pub mod shutdown;
-"#,
-        );
+        let mut mods = String::new();
         for event in events.iter() {
             if !event.is_default() {
-                output = format!(
-                    "{}pub mod {};\n",
-                    output,
-                    event.get_reference()?.to_lowercase().replace(".", "_")
+                mods = format!(
+                    "{}{}\n",
+                    mods,
+                    Template::new("producer/rust/event_impl_mod/mod", templates::MOD)
+                        .set(
+                            "name",
+                            &event.get_reference()?.to_lowercase().replace(".", "_")
+                        )
+                        .render()
                 );
             }
         }
+        let output = Template::new("producer/rust/event_impl_mod/module", templates::MODULE)
+            .set("mods", &mods)
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("events");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
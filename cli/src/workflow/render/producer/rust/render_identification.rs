@@ -1,10 +1,8 @@
 use super::{
-    helpers, helpers::render as tools, workflow::config::Config, workflow::store::Store, Protocol,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, workflow::config::Config,
+    workflow::store::Store, Protocol,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"#![allow(dead_code)]
@@ -67,6 +65,7 @@ pub struct Identification {
     uuid: Uuid,
     producer_indentification_strategy: producer::ProducerIdentificationStrategy,
     discredited: bool,
+    version: usize,
     tx_ident_change: UnboundedSender<IdentificationChannel>,
     pub key: Option<protocol::[[self_key]]>,
     pub assigned: Option<protocol::[[assign_key]]>,
@@ -82,6 +81,7 @@ impl Identification {
             uuid,
             producer_indentification_strategy: options.producer_indentification_strategy.clone(),
             discredited: false,
+            version: 0,
             key: None,
             assigned: None,
             tx_ident_change,
@@ -92,6 +92,20 @@ impl Identification {
         self.uuid
     }
 
+    /// Version of protocol and workflow, which consumer uses: 0 - current version;
+    /// 1.. - position of compatible version in producer::hash::COMPATIBLE + 1
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn is_current_version(&self) -> bool {
+        self.version == 0
+    }
+
+    pub fn set_version(&mut self, version: usize) {
+        self.version = version;
+    }
+
     pub fn set_key(
         &self,
         key: protocol::[[self_key]],
@@ -177,24 +191,25 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &Store, protocol: &Protocol) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = templates::MODULE.to_owned();
         let config = store.get_config()?;
-        output = output.replace(
-            "[[assign_key]]",
-            &tools::into_rust_path(&store.get_config()?.get_assigned()?),
-        );
-        output = output.replace(
-            "[[self_key]]",
-            &tools::into_rust_path(&store.get_config()?.get_self()?),
-        );
-        output = output.replace(
-            "[[self_key_overwrite]]",
-            &tools::inject_tabs(3, self.self_key_overwrite(config, protocol)?),
-        );
-        output = output.replace(
-            "[[assign_key_overwrite]]",
-            &tools::inject_tabs(3, self.assign_key_overwrite(config, protocol)?),
-        );
+        let output = Template::new("producer/rust/identification/module", templates::MODULE)
+            .set(
+                "assign_key",
+                &tools::into_rust_path(&store.get_config()?.get_assigned()?),
+            )
+            .set(
+                "self_key",
+                &tools::into_rust_path(&store.get_config()?.get_self()?),
+            )
+            .set(
+                "self_key_overwrite",
+                &tools::inject_tabs(3, self.self_key_overwrite(config, protocol)?),
+            )
+            .set(
+                "assign_key_overwrite",
+                &tools::inject_tabs(3, self.assign_key_overwrite(config, protocol)?),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -205,12 +220,19 @@ impl Render {
                 output = format!(
                     "{}{}",
                     output,
-                    (if field.optional {
-                        templates::KEY_OPT_FIELD_CHECK
+                    if field.optional {
+                        Template::new(
+                            "producer/rust/identification/key_opt_field_check",
+                            templates::KEY_OPT_FIELD_CHECK,
+                        )
                     } else {
-                        templates::KEY_FIELD_CHECK
-                    })
-                    .replace("[[field]]", &field.name,)
+                        Template::new(
+                            "producer/rust/identification/key_field_check",
+                            templates::KEY_FIELD_CHECK,
+                        )
+                    }
+                    .set("field", &field.name)
+                    .render()
                 );
                 if pos < strct.fields.len() - 1 {
                     output = format!("{}\n", output);
@@ -229,12 +251,19 @@ impl Render {
                 output = format!(
                     "{}{}",
                     output,
-                    (if field.optional {
-                        templates::KEY_OPT_FIELD_CHECK
+                    if field.optional {
+                        Template::new(
+                            "producer/rust/identification/key_opt_field_check",
+                            templates::KEY_OPT_FIELD_CHECK,
+                        )
                     } else {
-                        templates::KEY_FIELD_CHECK
-                    })
-                    .replace("[[field]]", &field.name,)
+                        Template::new(
+                            "producer/rust/identification/key_field_check",
+                            templates::KEY_FIELD_CHECK,
+                        )
+                    }
+                    .set("field", &field.name)
+                    .render()
                 );
                 if pos < strct.fields.len() - 1 {
                     output = format!("{}\n", output);
@@ -249,7 +278,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
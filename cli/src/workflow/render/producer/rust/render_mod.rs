@@ -1,8 +1,8 @@
-use super::{helpers, helpers::render as tools, workflow::store::Store, Protocol};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template,
+    protocol::encryption::Encryption, workflow::config::Handlers, workflow::store::Store, Protocol,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"
@@ -12,17 +12,7 @@ pub mod handlers;
 pub mod protocol;
 #[path = "./beacons/mod.rs"]
 pub mod beacons_callers;
-#[path = "../beacons/mod.rs"]
-pub mod beacons;
-#[path = "../events/mod.rs"]
-pub mod events;
-#[path = "../responses/mod.rs"]
-pub mod responses;
-pub mod scope;
-
-use super::context;
-use consumer::{identification, Consumer};
-use context::Context;
+[[handlers]]
 use clibri::{env::logs, server};
 use log::{debug, error, trace, warn};
 use protocol::PackingStruct;
@@ -41,6 +31,23 @@ use uuid::Uuid;
 pub mod hash {
     pub const PROTOCOL: &str = "[[protocol_hash]]";
     pub const WORKFLOW: &str = "[[workflow_hash]]";
+    pub const PROTOCOL_SOURCE: &str = "[[protocol_source_hash]]";
+    pub const WORKFLOW_SOURCE: &str = "[[workflow_source_hash]]";
+    // Hashes (protocol, workflow) of previous versions, which are declared as wire-compatible
+    pub const COMPATIBLE: &[(&str, &str)] = &[[[compatible]]];
+
+    /// Version, which is used by consumer with given hashes: 0 - current version;
+    /// 1.. - position in COMPATIBLE + 1; None - consumer isn't compatible
+    pub fn version(protocol: &str, workflow: &str) -> Option<usize> {
+        if protocol == PROTOCOL && workflow == WORKFLOW {
+            Some(0)
+        } else {
+            COMPATIBLE
+                .iter()
+                .position(|(p, w)| *p == protocol && *w == workflow)
+                .map(|pos| pos + 1)
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -69,6 +76,8 @@ pub enum ProducerError<E: server::Error> {
     FailToAddConsumer(Uuid),
     #[error("protocol error: {1}; uuid: `{0}`")]
     Protocol(Uuid, String),
+    #[error("message (id: {1}, sequence: {2}) has been skipped: {3}; uuid: `{0}`")]
+    SkippedMessage(Uuid, u32, u32, String),
 }
 use std::marker::PhantomData;
 
@@ -129,6 +138,9 @@ pub mod producer {
     pub struct Options {
         pub producer_indentification_strategy: ProducerIdentificationStrategy,
         pub consumer_error_handeling_strategy: ConsumerErrorHandelingStrategy,
+        pub limits: protocol::Limits,
+        pub compression: protocol::Compression,
+        pub checksum: bool,
     }
 
     impl Default for Options {
@@ -143,6 +155,12 @@ pub mod producer {
                 producer_indentification_strategy: ProducerIdentificationStrategy::Log,
                 consumer_error_handeling_strategy:
                     ConsumerErrorHandelingStrategy::EmitErrorAndDisconnect,
+                limits: protocol::Limits::default(),
+                compression: protocol::Compression {
+                    algorithm: protocol::CompressionAlgorithm::[[compression_algorithm]],
+                    threshold: [[compression_threshold]],
+                },
+                checksum: [[checksum]],
             }
         }
         pub fn producer_indentification_strategy(
@@ -159,6 +177,18 @@ pub mod producer {
             self.consumer_error_handeling_strategy = value;
             self
         }
+        pub fn limits(&mut self, value: protocol::Limits) -> &mut Self {
+            self.limits = value;
+            self
+        }
+        pub fn compression(&mut self, value: protocol::Compression) -> &mut Self {
+            self.compression = value;
+            self
+        }
+        pub fn checksum(&mut self, value: bool) -> &mut Self {
+            self.checksum = value;
+            self
+        }
     }
 
     #[derive(Clone, Debug)]
@@ -224,7 +254,17 @@ pub mod producer {
             buffer: Vec<u8>,
         ) -> Result<(), ProducerError<E>> {
             for uuid in uuids.iter() {
-                if let Err(err) = self.server_control.send(buffer.clone(), Some(*uuid)).await {
+                let buffer = match protocol::rebind(&buffer, Some(uuid.to_string())) {
+                    Ok(buffer) => buffer,
+                    Err(err) => {
+                        warn!(
+                            target: logs::targets::PRODUCER,
+                            "fail to prepare data for consumer {}: {}", uuid, err
+                        );
+                        continue;
+                    }
+                };
+                if let Err(err) = self.server_control.send(buffer, Some(*uuid)).await {
                     warn!(
                         target: logs::targets::PRODUCER,
                         "fail to send data to consumer {}: {}", uuid, err
@@ -238,7 +278,7 @@ pub mod producer {
     async fn add_connection<E: server::Error, C: server::Control<E>>(
         uuid: Uuid,
         consumers: &'_ mut HashMap<Uuid, Consumer>,
-        context: &mut Context,
+        context: &mut [[context]],
         control: &Control<E, C>,
         options: &Options,
         tx_ident_change: UnboundedSender<identification::IdentificationChannel>,
@@ -280,13 +320,14 @@ pub mod producer {
     async fn remove_connection<E: server::Error, C: server::Control<E>>(
         uuid: Uuid,
         consumers: &'_ mut HashMap<Uuid, Consumer>,
-        context: &mut Context,
+        context: &mut [[context]],
         control: &Control<E, C>,
     ) -> Result<(), ProducerError<E>> {
         debug!(
             target: logs::targets::PRODUCER,
             "consumer disconnected: {}", uuid,
         );
+        protocol::remove_agreement(&uuid.to_string());[[encryption_close]]
         if let Some(consumer) = consumers.remove(&uuid) {
             let filter = identification::Filter::new(consumers);
             if let Err(err) = emitters::disconnected::emit::<E, C>(
@@ -331,7 +372,7 @@ pub mod producer {
     async fn responsing_err<E: server::Error, C: server::Control<E>>(
         err: String,
         uuid: Uuid,
-        context: &mut Context,
+        context: &mut [[context]],
         control: &Control<E, C>,
         options: &Options,
         consumer: &mut Option<&mut Consumer>,
@@ -379,7 +420,7 @@ pub mod producer {
         uuid: Uuid,
         buffer: Vec<u8>,
         consumers: &'_ mut HashMap<Uuid, Consumer>,
-        context: &mut Context,
+        context: &mut [[context]],
         control: &Control<E, C>,
         options: &Options,
     ) -> Result<(), ProducerError<E>> {
@@ -388,7 +429,8 @@ pub mod producer {
             "new chunk of data from {} has been gotten",
             uuid,
         );
-        let (messages, assigned, has_key) = if let Some(consumer) = consumers.get_mut(&uuid) {
+        let (messages, errors, assigned, has_key) = if let Some(consumer) = consumers.get_mut(&uuid)
+        {
             if !consumer.is_confirmed() {
                 disconnect(uuid, consumer, control).await?;
                 emitters::error::emit::<E, C>(
@@ -419,6 +461,7 @@ pub mod producer {
             } else {
                 (
                     consumer.get_messages(),
+                    consumer.get_errors(),
                     consumer.get_identification().assigned(),
                     consumer.get_identification().has_key(),
                 )
@@ -435,6 +478,33 @@ pub mod producer {
             .await?;
             return Ok(());
         };
+        // Broken message doesn't break connection; other messages are processed as usual
+        for err in errors.into_iter() {
+            warn!(
+                target: logs::targets::PRODUCER,
+                "{}:: message (id: {}, sequence: {}) has been skipped: {:?}",
+                uuid,
+                err.id,
+                err.sequence,
+                err.error
+            );
+            emitters::error::emit::<E, C>(
+                ProducerError::SkippedMessage(
+                    uuid,
+                    err.id,
+                    err.sequence,
+                    format!("{:?}", err.error),
+                ),
+                Some(uuid),
+                context,
+                consumers
+                    .get(&uuid)
+                    .map(|consumer| consumer.get_identification()),
+                control,
+            )
+            .await
+            .map_err(ProducerError::EventEmitterError)?;
+        }
         if messages.is_empty() {
             return Ok(());
         }
@@ -459,6 +529,7 @@ pub mod producer {
                     let assigned_uuid = consumer.key(request, true);
                     if let Err(err) = match (protocol::[[indentification_self_response]] {
                         uuid: assigned_uuid.clone(),
+                        __unknown: vec![],
                     })
                     .pack(header.sequence, Some(assigned_uuid.clone()))
                     {
@@ -494,7 +565,10 @@ pub mod producer {
                         "consumer {} requested hash check",
                         uuid,
                     );
-                    let valid = if request.protocol != hash::PROTOCOL {
+                    let version = hash::version(&request.protocol, &request.workflow);
+                    let valid = if version.is_some() {
+                        true
+                    } else if request.protocol != hash::PROTOCOL {
                         warn!(
                             target: logs::targets::PRODUCER,
                             "consumer {} uses invalid protocol hash ({}); valid protocol hash: {}",
@@ -503,7 +577,7 @@ pub mod producer {
                             hash::PROTOCOL
                         );
                         false
-                    } else if request.workflow != hash::WORKFLOW {
+                    } else {
                         warn!(
                             target: logs::targets::PRODUCER,
                             "consumer {} uses invalid workflow hash ({}); valid workflow hash: {}",
@@ -512,21 +586,62 @@ pub mod producer {
                             hash::WORKFLOW
                         );
                         false
+                    };
+                    let capabilities =
+                        protocol::Capabilities::local(protocol::CODEC, &options.limits);
+                    // Consumer without negotiation doesn't send capabilities
+                    let legacy = protocol::Capabilities::legacy(protocol::CODEC);
+                    let (valid, capabilities) = if valid {
+                        match capabilities.negotiate(&protocol::Capabilities {
+                            version: request.version.unwrap_or(legacy.version),
+                            compression: request.compression.clone().unwrap_or(legacy.compression),
+                            encryption: request.encryption.clone().unwrap_or(legacy.encryption),
+                            codec: request.codec.clone().unwrap_or(legacy.codec),
+                            frame: request.frame.unwrap_or(legacy.frame),
+                            heartbeat: request.heartbeat.unwrap_or(legacy.heartbeat),
+                        }) {
+                            Ok(agreement) => {
+                                // HashRequest is sent before agreement, so its timestamp is
+                                // in seconds; clock is estimated by next packages
+                                let agreed = capabilities.agreed(&agreement);
+                                protocol::set_agreement(&uuid.to_string(), agreement);
+                                (true, agreed)
+                            }
+                            Err(err) => {
+                                warn!(
+                                    target: logs::targets::PRODUCER,
+                                    "consumer {} has incompatible capabilities: {}",
+                                    uuid,
+                                    err
+                                );
+                                (false, capabilities)
+                            }
+                        }
                     } else {
+                        (false, capabilities)
+                    };[[encryption_accept]]
+                    if let (true, Some(version)) = (valid, version) {
                         trace!(
                             target: logs::targets::PRODUCER,
-                            "consumer {} hash has been accepted",
+                            "consumer {} hash has been accepted; version: {}",
                             uuid,
+                            version,
                         );
-                        consumer.accept_hash();
-                        true
-                    };
+                        consumer.accept_hash(version);
+                    }
                     if let Err(err) = match (protocol::InternalServiceGroup::HashResponse {
                         error: if !valid {
                             Some(String::from("Hash is invalid"))
                         } else {
                             None
                         },
+                        version: Some(capabilities.version),
+                        compression: Some(capabilities.compression),
+                        encryption: Some(capabilities.encryption),
+                        codec: Some(capabilities.codec),
+                        frame: Some(capabilities.frame),
+                        heartbeat: Some(capabilities.heartbeat),[[encryption_key]]
+                        __unknown: vec![],
                     })
                     .pack(header.sequence, Some(uuid.to_string()))
                     {
@@ -632,6 +747,7 @@ pub mod producer {
                             .map_err(ProducerError::EventEmitterError)?;
                         }
                     } else {
+                        protocol::observe_clock(&uuid.to_string(), header);
                         let filter = identification::Filter::new(consumers);
                         let consumer = if let Some(consumer) = consumers.get(&uuid) {
                             consumer
@@ -641,6 +757,12 @@ pub mod producer {
                         match message {
 [[requests]]
 [[beacons]]
+                            protocol::AvailableMessages::Unknown { id, .. } => {
+                                warn!(
+                                    target: logs::targets::PRODUCER,
+                                    "unknown message {} from {} has been skipped", id, uuid
+                                );
+                            }
                             _ => {}
                         }
                     }
@@ -650,13 +772,13 @@ pub mod producer {
         Ok(())
     }
 
-    async fn listener<E: server::Error, C: server::Control<E>>(
-        mut context: Context,
+    async fn listener<E: server::Error, C: server::Control<E>[[context_generic]]>(
+        mut context: [[context_type]],
         mut rx_server_events: UnboundedReceiver<server::Events<E>>,
         mut rx_events: UnboundedReceiver<Event>,
         control: Control<E, C>,
         options: &Options,
-    ) -> (Control<E, C>, Context, Result<(), ProducerError<E>>) {
+    ) -> (Control<E, C>, [[context_type]], Result<(), ProducerError<E>>) {
         let (tx_ident_change, mut rx_ident_change): (
             UnboundedSender<identification::IdentificationChannel>,
             UnboundedReceiver<identification::IdentificationChannel>,
@@ -808,7 +930,9 @@ pub mod producer {
                             consumer.confirm();
                             control
                                 .send(
-                                    (protocol::InternalServiceGroup::ConnectConfirmationBeacon {})
+                                    (protocol::InternalServiceGroup::ConnectConfirmationBeacon {
+                                        __unknown: vec![],
+                                    })
                                         .pack(0, Some(uuid.to_string()))
                                         .map_err(|e| ProducerError::Protocol(uuid, e))?,
                                     Some(uuid),
@@ -837,10 +961,10 @@ pub mod producer {
         (control, context, result)
     }
 
-    pub async fn run<S, C, E>(
+    pub async fn run<S, C, E[[context_generic]]>(
         mut server: S,
         options: Options,
-        context: Context,
+        context: [[context_type]],
     ) -> Result<(), ProducerError<E>>
     where
         S: server::Impl<E, C>,
@@ -860,6 +984,8 @@ pub mod producer {
             phantom: PhantomData,
             events,
         };
+        protocol::set_compression(&options.compression);
+        protocol::set_checksum(options.checksum);
         let rx_server_events = server.observer().map_err(ProducerError::ServerError)?;
         let cancel = control.shutdown.clone();
         let (
@@ -934,6 +1060,22 @@ pub mod producer {
         }
     }
 }"#;
+    pub const HANDLERS_FILES: &str = r#"#[path = "../beacons/mod.rs"]
+pub mod beacons;
+#[path = "../events/mod.rs"]
+pub mod events;
+#[path = "../responses/mod.rs"]
+pub mod responses;
+pub mod scope;
+
+use super::context;
+use consumer::{identification, Consumer};
+use context::Context;"#;
+    pub const HANDLERS_TRAIT: &str = r#"pub mod scope;
+pub mod traits;
+
+use consumer::{identification, Consumer};
+pub use traits::{events, responses, ProducerHandlers};"#;
     pub const REQUEST: &str = r#"[[ref]] => {
     if let Err(err) = handlers::[[module]]::process::<E, C>(
         consumer.get_identification(),
@@ -941,6 +1083,7 @@ pub mod producer {
         context,
         request,
         header.sequence,
+        header.ts_ms(),
         control,
     )
     .await
@@ -961,6 +1104,7 @@ pub mod producer {
         consumer.get_identification(),
         beacon,
         header.sequence,
+        header.ts_ms(),
         &filter,
         context,
         control,
@@ -997,6 +1141,7 @@ pub mod producer {
         );
     }
 },"#;
+    pub const EVENT_ITEM: &str = r#"[[name]](protocol::[[ref]]),"#;
     pub const EVENT_CALLER: &str = r#"pub async fn [[module]](
     &self,
     event: protocol::[[ref]],
@@ -1005,6 +1150,27 @@ pub mod producer {
         .send(Event::[[name]](event))
         .map_err(|e| e.to_string())
 }"#;
+    pub const ENCRYPTION_ACCEPT: &str = r#"
+                    let (valid, key) = if valid {
+                        match protocol::encryption_accept(&uuid.to_string(), &request.key) {
+                            Ok(key) => (true, key),
+                            Err(err) => {
+                                warn!(
+                                    target: logs::targets::PRODUCER,
+                                    "consumer {} fails to establish encrypted session: {}",
+                                    uuid,
+                                    err
+                                );
+                                (false, vec![])
+                            }
+                        }
+                    } else {
+                        (false, vec![])
+                    };"#;
+    pub const ENCRYPTION_KEY: &str = r#"
+                        key,"#;
+    pub const ENCRYPTION_CLOSE: &str = r#"
+        protocol::encryption_close(&uuid.to_string());"#;
 }
 
 pub struct Render {}
@@ -1022,29 +1188,108 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &Store, protocol: &Protocol) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[requests]]", &self.get_requests(store)?);
-        output = output.replace("[[beacons]]", &self.get_beacons(store)?);
-        output = output.replace("[[events]]", &self.get_events(store)?);
-        output = output.replace("[[events_list]]", &self.get_events_list(store)?);
-        output = output.replace("[[events_callers]]", &self.get_events_callers(store)?);
-        output = output.replace(
-            "[[indentification_self_enum_ref]]",
-            &self.indentification_self_enum_ref(store)?,
-        );
-        output = output.replace(
-            "[[indentification_self_response]]",
-            &tools::into_rust_path(&store.get_config()?.self_key_response),
-        );
-        output = output.replace("[[protocol_hash]]", &protocol.get_hash());
-        output = output.replace("[[workflow_hash]]", &store.get_hash());
+        let output = Template::new("producer/rust/mod/module", templates::MODULE);
+        let output = match store.get_config()?.get_handlers() {
+            Handlers::Files => output
+                .set(
+                    "handlers",
+                    &Template::new(
+                        "producer/rust/mod/handlers_files",
+                        templates::HANDLERS_FILES,
+                    )
+                    .render(),
+                )
+                .set("context", "Context")
+                .set("context_type", "Context")
+                .set("context_generic", ""),
+            Handlers::Trait => output
+                .set(
+                    "handlers",
+                    &Template::new(
+                        "producer/rust/mod/handlers_trait",
+                        templates::HANDLERS_TRAIT,
+                    )
+                    .render(),
+                )
+                .set("context", "impl ProducerHandlers")
+                .set("context_type", "H")
+                .set("context_generic", ", H: ProducerHandlers"),
+        };
+        let encrypted = protocol.get_encryption() != Encryption::None;
+        let optional = |name: &str, template: &str| -> String {
+            if encrypted {
+                Template::new(name, template).render()
+            } else {
+                String::new()
+            }
+        };
+        let output = output
+            .set("requests", &self.get_requests(store)?)
+            .set("beacons", &self.get_beacons(store)?)
+            .set("events", &self.get_events(store)?)
+            .set("events_list", &self.get_events_list(store)?)
+            .set("events_callers", &self.get_events_callers(store)?)
+            .set(
+                "indentification_self_enum_ref",
+                &self.indentification_self_enum_ref(store)?,
+            )
+            .set(
+                "indentification_self_response",
+                &tools::into_rust_path(&store.get_config()?.self_key_response),
+            )
+            .set(
+                "compression_algorithm",
+                store.get_config()?.get_compression().as_variant(),
+            )
+            .set(
+                "compression_threshold",
+                &store.get_config()?.get_compression_threshold().to_string(),
+            )
+            .set(
+                "checksum",
+                &store.get_config()?.get_checksum().enabled().to_string(),
+            )
+            .set(
+                "encryption_accept",
+                &optional(
+                    "producer/rust/mod/encryption_accept",
+                    templates::ENCRYPTION_ACCEPT,
+                ),
+            )
+            .set(
+                "encryption_key",
+                &optional(
+                    "producer/rust/mod/encryption_key",
+                    templates::ENCRYPTION_KEY,
+                ),
+            )
+            .set(
+                "encryption_close",
+                &optional(
+                    "producer/rust/mod/encryption_close",
+                    templates::ENCRYPTION_CLOSE,
+                ),
+            )
+            .set("protocol_hash", &protocol.get_hash())
+            .set("workflow_hash", &store.get_hash())
+            .set("protocol_source_hash", &protocol.get_source_hash())
+            .set("workflow_source_hash", &store.get_source_hash())
+            .set(
+                "compatible",
+                &store
+                    .get_compatible()
+                    .iter()
+                    .map(|(protocol, workflow)| format!("(\"{}\", \"{}\")", protocol, workflow))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_requests(&self, store: &Store) -> Result<String, String> {
         let mut output: String = String::new();
         for (pos, request) in store.requests.iter().enumerate() {
-            let mut request_output: String = String::from(templates::REQUEST);
             let parts: Vec<String> = request
                 .get_request()?
                 .split('.')
@@ -1068,11 +1313,13 @@ impl Render {
                 }
                 format!("{}request{}", chain, ")".repeat(parts.len()))
             };
-            request_output = request_output.replace("[[ref]]", &enum_ref);
-            request_output = request_output.replace(
-                "[[module]]",
-                &request.get_request()?.to_lowercase().replace(".", "_"),
-            );
+            let request_output = Template::new("producer/rust/mod/request", templates::REQUEST)
+                .set("ref", &enum_ref)
+                .set(
+                    "module",
+                    &request.get_request()?.to_lowercase().replace(".", "_"),
+                )
+                .render();
             output = format!("{}{}", output, request_output);
             if pos < store.requests.len() - 1 {
                 output = format!("{}\n", output);
@@ -1084,7 +1331,6 @@ impl Render {
     fn get_beacons(&self, store: &Store) -> Result<String, String> {
         let mut output: String = String::new();
         for (pos, beacon) in store.beacons.iter().enumerate() {
-            let mut beacon_output: String = String::from(templates::BEACON);
             let parts: Vec<String> = beacon
                 .reference
                 .split('.')
@@ -1108,12 +1354,11 @@ impl Render {
                 }
                 format!("{}beacon{}", chain, ")".repeat(parts.len()))
             };
-            beacon_output = beacon_output.replace("[[ref]]", &enum_ref);
-            beacon_output = beacon_output.replace("[[struct]]", &beacon.reference.replace(".", ""));
-            beacon_output = beacon_output.replace(
-                "[[module]]",
-                &beacon.reference.to_lowercase().replace(".", "_"),
-            );
+            let beacon_output = Template::new("producer/rust/mod/beacon", templates::BEACON)
+                .set("ref", &enum_ref)
+                .set("struct", &beacon.reference.replace(".", ""))
+                .set("module", &beacon.reference.to_lowercase().replace(".", "_"))
+                .render();
             output = format!("{}{}", output, beacon_output);
             if pos < store.beacons.len() - 1 {
                 output = format!("{}\n", output);
@@ -1126,13 +1371,13 @@ impl Render {
         let mut output: String = String::new();
         for (pos, event) in store.events.iter().enumerate() {
             if !event.is_default() {
-                let mut event_output: String = String::from(templates::EVENT);
-                event_output = event_output.replace(
-                    "[[module]]",
-                    &event.get_reference()?.to_lowercase().replace(".", "_"),
-                );
-                event_output =
-                    event_output.replace("[[name]]", &event.get_reference()?.replace(".", ""));
+                let event_output = Template::new("producer/rust/mod/event", templates::EVENT)
+                    .set(
+                        "module",
+                        &event.get_reference()?.to_lowercase().replace(".", "_"),
+                    )
+                    .set("name", &event.get_reference()?.replace(".", ""))
+                    .render();
                 output = format!("{}{}", output, event_output);
                 if pos < store.events.len() - 1 {
                     output = format!("{}\n", output);
@@ -1147,10 +1392,12 @@ impl Render {
         for (pos, event) in store.events.iter().enumerate() {
             if !event.is_default() {
                 output = format!(
-                    "{}{}(protocol::{}),{}",
+                    "{}{}{}",
                     output,
-                    event.get_reference()?.replace(".", ""),
-                    event.get_reference()?.replace(".", "::"),
+                    Template::new("producer/rust/mod/event_item", templates::EVENT_ITEM)
+                        .set("name", &event.get_reference()?.replace(".", ""))
+                        .set("ref", &event.get_reference()?.replace(".", "::"))
+                        .render(),
                     if pos < store.events.len() - 1 {
                         "\n"
                     } else {
@@ -1166,15 +1413,15 @@ impl Render {
         let mut output: String = String::new();
         for (pos, event) in store.events.iter().enumerate() {
             if !event.is_default() {
-                let mut event_output: String = String::from(templates::EVENT_CALLER);
-                event_output = event_output.replace(
-                    "[[module]]",
-                    &event.get_reference()?.to_lowercase().replace(".", "_"),
-                );
-                event_output =
-                    event_output.replace("[[name]]", &event.get_reference()?.replace(".", ""));
-                event_output =
-                    event_output.replace("[[ref]]", &event.get_reference()?.replace(".", "::"));
+                let event_output =
+                    Template::new("producer/rust/mod/event_caller", templates::EVENT_CALLER)
+                        .set(
+                            "module",
+                            &event.get_reference()?.to_lowercase().replace(".", "_"),
+                        )
+                        .set("name", &event.get_reference()?.replace(".", ""))
+                        .set("ref", &event.get_reference()?.replace(".", "::"))
+                        .render();
                 output = format!("{}{}", output, event_output);
                 if pos < store.events.len() - 1 {
                     output = format!("{}\n", output);
@@ -1215,7 +1462,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,13 +1,13 @@
-use super::{helpers, helpers::render as tools, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, inject_context,
+    workflow::config::Handlers, workflow::request::Request,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_CONCLUSION: &str = r#"
 use super::{
-    broadcast, identification, pack, producer::Control, protocol, responses, Context, HandlerError,
+    broadcast, identification, pack, producer::Control, protocol, responses, [[context_ref]], HandlerError,
     ProducerError, scope::Scope,
 };
 use clibri::server;
@@ -16,16 +16,18 @@ use uuid::Uuid;
 pub async fn process<E: server::Error, C: server::Control<E>>(
     identification: &identification::Identification,
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     request: &protocol::[[request]],
     sequence: u32,
+    ts: u64,
     control: &Control<E, C>,
 ) -> Result<(), HandlerError> {
-    let mut scope: Scope<'_, E, C> = Scope::new(context, control, identification, filter);
+    let mut scope: Scope<'_, E, C> =
+        Scope::new([[scope_context]]control, identification, filter).with_ts(ts);
     let uuid = identification.uuid();
     let mut broadcasting: Vec<(Vec<Uuid>, Vec<u8>)> = vec![];
     let buffer =
-        match responses::[[response_mod]]::response(request, &mut scope).await {
+        match [[handler]](request, &mut scope).await {
             Ok(conclusion) => match conclusion {
 [[conclusions]]
             },
@@ -52,9 +54,20 @@ pub async fn process<E: server::Error, C: server::Control<E>>(
     pub const CONCLUSION_WITHOUT_BROADCAST: &str = r#"responses::[[response_mod]]::Response::[[conclusion]](mut response) => {
     pack(&sequence, &uuid, &mut response)?
 },"#;
+    pub const BROADCAST_DECLARATION: &str = "\tmut [[var_name]],";
+    pub const BROADCAST: &str = r#"broadcasting.push((
+    [[var_name]].0,
+    pack(&0, &uuid, &mut [[var_name]].1)?,
+));"#;
+    pub const BROADCAST_OPTIONAL: &str = r#"if let Some(mut [[var_name]]) = [[var_name]].take() {
+    broadcasting.push((
+        [[var_name]].0,
+        pack(&0, &uuid, &mut [[var_name]].1)?,
+    ));
+}"#;
     pub const MODULE_NO_CONCLUSION: &str = r#"
 use super::{
-    identification, pack, producer::Control, protocol, responses, Context, HandlerError,
+    identification, pack, producer::Control, protocol, responses, [[context_ref]], HandlerError,
     ProducerError, scope::Scope,
 };
 use clibri::server;
@@ -62,14 +75,16 @@ use clibri::server;
 pub async fn process<E: server::Error, C: server::Control<E>>(
     identification: &identification::Identification,
     filter: &identification::Filter<'_>,
-    context: &mut Context,
+    context: &mut [[context]],
     request: &protocol::[[request]],
     sequence: u32,
+    ts: u64,
     control: &Control<E, C>,
 ) -> Result<(), HandlerError> {
-    let mut scope: Scope<'_, E, C> = Scope::new(context, control, identification, filter);
+    let mut scope: Scope<'_, E, C> =
+        Scope::new([[scope_context]]control, identification, filter).with_ts(ts);
     let uuid = identification.uuid();
-    let buffer = match responses::[[response_mod]]::response(request, &mut scope).await
+    let buffer = match [[handler]](request, &mut scope).await
     {
         Ok(mut response) => pack(&sequence, &uuid, &mut response)?,
         Err(mut error) => pack(&sequence, &uuid, &mut error)?,
@@ -97,113 +112,98 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path, request: &Request) -> Result<(), String> {
+    pub fn render(
+        &self,
+        base: &Path,
+        request: &Request,
+        handlers: &Handlers,
+    ) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, request)?;
         let request_ref = request.get_request()?;
-        let mut output: String = if request.actions.len() > 1 {
-            let mut output = templates::MODULE_WITH_CONCLUSION.to_owned();
-            output = output.replace("[[response_mod]]", &self.get_response_mod_name(request)?);
-            output = output.replace("[[conclusions]]", &self.get_conclusions(request)?);
-            output
+        let template = if request.actions.len() > 1 {
+            Template::new(
+                "producer/rust/request_handler/module_with_conclusion",
+                templates::MODULE_WITH_CONCLUSION,
+            )
+            .set("conclusions", &self.get_conclusions(request)?)
         } else {
-            let mut output = templates::MODULE_NO_CONCLUSION.to_owned();
-            output = output.replace("[[response_mod]]", &self.get_response_mod_name(request)?);
-            output
+            Template::new(
+                "producer/rust/request_handler/module_no_conclusion",
+                templates::MODULE_NO_CONCLUSION,
+            )
         };
-        output = output.replace("[[request]]", &tools::into_rust_path(&request_ref));
+        let output = inject_context(
+            template,
+            handlers,
+            "responses",
+            &self.get_response_mod_name(request)?,
+            "response",
+        )
+        .set("response_mod", &self.get_response_mod_name(request)?)
+        .set("request", &tools::into_rust_path(&request_ref))
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_conclusions(&self, request: &Request) -> Result<String, String> {
-        let mut output = String::new();
-        for (pos, action) in request.actions.iter().enumerate() {
-            if action.broadcast.is_empty() {
-                let mut out = templates::CONCLUSION_WITHOUT_BROADCAST.to_owned();
-                out = out.replace("[[response_mod]]", &self.get_response_mod_name(request)?);
-                out = out.replace("[[conclusion]]", &action.get_conclusion()?);
-                output = format!(
-                    "{}{}{}",
-                    output,
-                    &tools::inject_tabs(3, out),
-                    if pos == request.actions.len() - 1 {
-                        ""
-                    } else {
-                        "\n"
-                    }
-                );
+        let mut conclusions = vec![];
+        for action in request.actions.iter() {
+            let output = if action.broadcast.is_empty() {
+                Template::new(
+                    "producer/rust/request_handler/conclusion_without_broadcast",
+                    templates::CONCLUSION_WITHOUT_BROADCAST,
+                )
+                .set("response_mod", &self.get_response_mod_name(request)?)
+                .set("conclusion", &action.get_conclusion()?)
+                .render()
             } else {
-                let mut out = templates::CONCLUSION_WITH_BROADCAST.to_owned();
-                out = out.replace("[[response_mod]]", &self.get_response_mod_name(request)?);
-                out = out.replace("[[conclusion]]", &action.get_conclusion()?);
-                let mut broadcasts_declaration = String::new();
-                let mut broadcasts_processing = String::new();
-                for (pos, broadcast) in action.broadcast.iter().enumerate() {
+                let mut broadcasts_declaration = vec![];
+                let mut broadcasts_processing = vec![];
+                for broadcast in action.broadcast.iter() {
                     let broadcast_var_name = format!(
                         "broadcast_{}",
                         broadcast.reference.replace(".", "_").to_lowercase()
                     );
-                    broadcasts_declaration = format!(
-                        "{}\tmut {},{}",
-                        broadcasts_declaration,
-                        broadcast_var_name,
-                        if pos == action.broadcast.len() - 1 {
-                            ""
-                        } else {
-                            "\n"
-                        }
+                    broadcasts_declaration.push(
+                        Template::new(
+                            "producer/rust/request_handler/broadcast_declaration",
+                            templates::BROADCAST_DECLARATION,
+                        )
+                        .set("var_name", &broadcast_var_name)
+                        .render(),
                     );
-                    if broadcast.optional {
-                        broadcasts_processing = format!(
-                            "{}{}{}",
-                            broadcasts_processing,
-                            r#"if let Some(mut [[var_name]]) = [[var_name]].take() {
-    broadcasting.push((
-        [[var_name]].0,
-        pack(&0, &uuid, &mut [[var_name]].1)?,
-    ));
-}"#,
-                            if pos == action.broadcast.len() - 1 {
-                                ""
-                            } else {
-                                "\n"
-                            }
-                        );
+                    broadcasts_processing.push(if broadcast.optional {
+                        Template::new(
+                            "producer/rust/request_handler/broadcast_optional",
+                            templates::BROADCAST_OPTIONAL,
+                        )
+                        .set("var_name", &broadcast_var_name)
+                        .render()
                     } else {
-                        broadcasts_processing = format!(
-                            "{}{}{}",
-                            broadcasts_processing,
-                            r#"broadcasting.push((
-    [[var_name]].0,
-    pack(&0, &uuid, &mut [[var_name]].1)?,
-));"#,
-                            if pos == action.broadcast.len() - 1 {
-                                ""
-                            } else {
-                                "\n"
-                            }
-                        );
-                    }
-                    broadcasts_processing =
-                        broadcasts_processing.replace("[[var_name]]", &broadcast_var_name);
+                        Template::new(
+                            "producer/rust/request_handler/broadcast",
+                            templates::BROADCAST,
+                        )
+                        .set("var_name", &broadcast_var_name)
+                        .render()
+                    });
                 }
-                out = out.replace("[[broadcasts_declaration]]", &broadcasts_declaration);
-                out = out.replace(
-                    "[[broadcasts_processing]]",
-                    &tools::inject_tabs(1, broadcasts_processing),
-                );
-                output = format!(
-                    "{}{}{}",
-                    output,
-                    &tools::inject_tabs(3, out),
-                    if pos == request.actions.len() - 1 {
-                        ""
-                    } else {
-                        "\n"
-                    }
-                );
-            }
+                Template::new(
+                    "producer/rust/request_handler/conclusion_with_broadcast",
+                    templates::CONCLUSION_WITH_BROADCAST,
+                )
+                .set("response_mod", &self.get_response_mod_name(request)?)
+                .set("conclusion", &action.get_conclusion()?)
+                .set("broadcasts_declaration", &broadcasts_declaration.join("\n"))
+                .set(
+                    "broadcasts_processing",
+                    &tools::inject_tabs(1, broadcasts_processing.join("\n")),
+                )
+                .render()
+            };
+            conclusions.push(tools::inject_tabs(3, output));
         }
-        Ok(tools::inject_tabs(1, output))
+        Ok(tools::inject_tabs(1, conclusions.join("\n")))
     }
 
     fn get_response_mod_name(&self, request: &Request) -> Result<String, String> {
@@ -213,7 +213,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, request: &Request) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("handlers");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
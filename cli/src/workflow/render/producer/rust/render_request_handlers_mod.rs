@@ -1,26 +1,8 @@
-use super::{helpers, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::request::Request};
+use std::path::{Path, PathBuf};
 
-pub struct Render {}
-
-impl Default for Render {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Render {
-    pub fn new() -> Self {
-        Self {}
-    }
-
-    pub fn render(&self, base: &Path, requests: &[Request]) -> Result<(), String> {
-        let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = String::from(
-            r#"[[mods]]
+mod templates {
+    pub const MODULE: &str = r#"[[mods]]
 use super::*;
 use clibri::server;
 use protocol::PackingStruct;
@@ -53,24 +35,51 @@ pub async fn broadcast<E: server::Error, C: server::Control<E> + Send + Clone>(
         .await
         .map_err(|e: ProducerError<E>| HandlerError::Processing(e.to_string()))?;
     Ok(())
-}"#,
-        );
+}"#;
+    pub const MOD: &str = r#"pub mod [[name]];"#;
+}
+
+pub struct Render {}
+
+impl Default for Render {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn render(&self, base: &Path, requests: &[Request]) -> Result<(), String> {
+        let dest: PathBuf = self.get_dest_file(base)?;
         let mut mods = String::new();
         for request in requests.iter() {
             mods = format!(
-                "{}pub mod {};\n",
+                "{}{}\n",
                 mods,
-                request.get_request()?.to_lowercase().replace(".", "_")
+                Template::new("producer/rust/request_handlers_mod/mod", templates::MOD)
+                    .set(
+                        "name",
+                        &request.get_request()?.to_lowercase().replace(".", "_")
+                    )
+                    .render()
             );
         }
-        output = output.replace("[[mods]]", &mods);
+        let output = Template::new(
+            "producer/rust/request_handlers_mod/module",
+            templates::MODULE,
+        )
+        .set("mods", &mods)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("handlers");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
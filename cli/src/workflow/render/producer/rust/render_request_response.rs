@@ -1,10 +1,8 @@
 use super::{
-    helpers, helpers::render as tools, workflow::beacon::Broadcast, workflow::request::Request,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast,
+    workflow::request::Request,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_CONCLUSION: &str = r#"
@@ -36,6 +34,9 @@ pub async fn response<E: server::Error, C: server::Control<E>>(
     panic!("Handler for protocol::[[request]] isn't implemented");
 }
 "#;
+    pub const BROADCAST_TYPE: &str = r#"type [[name]] = (Vec<Uuid>, protocol::[[ref]]);"#;
+    pub const BROADCAST_TYPE_OPTIONAL: &str =
+        r#"type [[name]] = Option<(Vec<Uuid>, protocol::[[ref]])>;"#;
 }
 
 pub struct Render {}
@@ -53,32 +54,32 @@ impl Render {
 
     pub fn render(&self, base: &Path, request: &Request) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, request)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
         let request_ref = request.get_request()?;
-        let mut output: String = if request.actions.len() > 1 {
-            let mut output = templates::MODULE_WITH_CONCLUSION.to_owned();
-            output = output.replace("[[broadcast_types]]", &self.get_broadcast_types(request)?);
-            output = output.replace(
-                "[[response_declaration]]",
+        let output = if request.actions.len() > 1 {
+            Template::new(
+                "producer/rust/request_response/module_with_conclusion",
+                templates::MODULE_WITH_CONCLUSION,
+            )
+            .set("broadcast_types", &self.get_broadcast_types(request)?)
+            .set(
+                "response_declaration",
                 &self.get_response_declaration(request)?,
-            );
-            output
+            )
+        } else {
+            Template::new(
+                "producer/rust/request_response/module_no_conclusion",
+                templates::MODULE_NO_CONCLUSION,
+            )
+            .set("response", &tools::into_rust_path(&request.get_response()?))
+        }
+        .set("request", &tools::into_rust_path(&request_ref));
+        let output = if let Some(error_ref) = request.error.as_ref() {
+            output.set("error", &tools::into_rust_path(error_ref))
         } else {
-            let mut output = templates::MODULE_NO_CONCLUSION.to_owned();
-            output = output.replace(
-                "[[response]]",
-                &tools::into_rust_path(&request.get_response()?),
-            );
             output
-        };
-        output = output.replace("[[request]]", &tools::into_rust_path(&request_ref));
-        if let Some(error_ref) = request.error.as_ref() {
-            output = output.replace("[[error]]", &tools::into_rust_path(error_ref));
         }
-        helpers::fs::write(dest, output, true)
+        .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn get_response_declaration(&self, request: &Request) -> Result<String, String> {
@@ -127,40 +128,44 @@ impl Render {
         Ok(tools::inject_tabs(1, output))
     }
 
-    fn get_broadcast_types(&self, request: &Request) -> Result<String, String> {
+    pub fn get_broadcast_types(&self, request: &Request) -> Result<String, String> {
         let mut output = String::new();
         for action in request.actions.iter() {
             if !action.broadcast.is_empty() {
                 for broadcast in action.broadcast.iter() {
-                    if broadcast.optional {
-                        output = format!(
-                            "{}type {} = Option<(Vec<Uuid>, protocol::{})>;\n",
-                            output,
-                            self.get_broadcast_type_name(broadcast),
-                            tools::into_rust_path(&broadcast.reference),
-                        );
+                    let template = if broadcast.optional {
+                        Template::new(
+                            "producer/rust/request_response/broadcast_type_optional",
+                            templates::BROADCAST_TYPE_OPTIONAL,
+                        )
                     } else {
-                        output = format!(
-                            "{}type {} = (Vec<Uuid>, protocol::{});\n",
-                            output,
-                            self.get_broadcast_type_name(broadcast),
-                            tools::into_rust_path(&broadcast.reference),
-                        );
-                    }
+                        Template::new(
+                            "producer/rust/request_response/broadcast_type",
+                            templates::BROADCAST_TYPE,
+                        )
+                    };
+                    output = format!(
+                        "{}{}\n",
+                        output,
+                        template
+                            .set("name", &self.get_broadcast_type_name(broadcast))
+                            .set("ref", &tools::into_rust_path(&broadcast.reference))
+                            .render()
+                    );
                 }
             }
         }
         Ok(output)
     }
 
-    fn get_broadcast_type_name(&self, broadcast: &Broadcast) -> String {
+    pub fn get_broadcast_type_name(&self, broadcast: &Broadcast) -> String {
         format!("Broadcast{}", broadcast.reference.replace(".", ""))
     }
 
     fn get_dest_file(&self, base: &Path, request: &Request) -> Result<PathBuf, String> {
         let dest = base.join("responses");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
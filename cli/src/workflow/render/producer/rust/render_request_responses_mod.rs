@@ -1,8 +1,5 @@
 use super::{helpers, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 pub struct Render {}
 
@@ -33,7 +30,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("responses");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,9 +1,6 @@
-use super::{helpers, workflow::event::Event};
+use super::{crates, helpers, workflow::config::Handlers, workflow::event::Event};
 use std::include_str;
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 #[allow(non_upper_case_globals)]
 mod paths {
@@ -49,24 +46,32 @@ impl Render {
         Self {}
     }
 
-    pub fn render(&self, base: &Path, events: &[Event]) -> Result<(), String> {
+    pub fn render(
+        &self,
+        base: &Path,
+        events: &[Event],
+        handlers: &Handlers,
+        crate_name: Option<&str>,
+    ) -> Result<(), String> {
+        let stubs = handlers == &Handlers::Files;
         if !events.iter().any(|event| match event.get_reference() {
             Ok(reference) => reference == "connected",
             Err(_) => false,
         }) {
-            if !self
-                .get_dest_file(base, paths::events::dest, paths::events::connected)?
-                .exists()
-            {
-                helpers::fs::write(
+            if stubs {
+                helpers::stubs::write(
                     self.get_dest_file(base, paths::events::dest, paths::events::connected)?,
                     include_str!("./static/events/connected.rs").to_owned(),
-                    true,
                 )?;
             }
             helpers::fs::write(
                 self.get_dest_file(base, paths::emitters::dest, paths::emitters::connected)?,
-                include_str!("./static/implementation/emitters/connected.rs").to_owned(),
+                if stubs {
+                    include_str!("./static/implementation/emitters/connected.rs")
+                } else {
+                    include_str!("./static/traits/implementation/emitters/connected.rs")
+                }
+                .to_owned(),
                 true,
             )?;
         }
@@ -74,83 +79,99 @@ impl Render {
             Ok(reference) => reference == "disconnected",
             Err(_) => false,
         }) {
-            if !self
-                .get_dest_file(base, paths::events::dest, paths::events::disconnected)?
-                .exists()
-            {
-                helpers::fs::write(
+            if stubs {
+                helpers::stubs::write(
                     self.get_dest_file(base, paths::events::dest, paths::events::disconnected)?,
                     include_str!("./static/events/disconnected.rs").to_owned(),
-                    true,
                 )?;
             }
             helpers::fs::write(
                 self.get_dest_file(base, paths::emitters::dest, paths::emitters::disconnected)?,
-                include_str!("./static/implementation/emitters/disconnected.rs").to_owned(),
+                if stubs {
+                    include_str!("./static/implementation/emitters/disconnected.rs")
+                } else {
+                    include_str!("./static/traits/implementation/emitters/disconnected.rs")
+                }
+                .to_owned(),
                 true,
             )?;
         }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::error)?
-            .exists()
-        {
-            helpers::fs::write(
+        if stubs {
+            helpers::stubs::write(
                 self.get_dest_file(base, paths::events::dest, paths::events::error)?,
                 include_str!("./static/events/error.rs").to_owned(),
-                true,
             )?;
-        }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::ready)?
-            .exists()
-        {
-            helpers::fs::write(
+            helpers::stubs::write(
                 self.get_dest_file(base, paths::events::dest, paths::events::ready)?,
                 include_str!("./static/events/ready.rs").to_owned(),
-                true,
             )?;
-        }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::shutdown)?
-            .exists()
-        {
-            helpers::fs::write(
+            helpers::stubs::write(
                 self.get_dest_file(base, paths::events::dest, paths::events::shutdown)?,
                 include_str!("./static/events/shutdown.rs").to_owned(),
-                true,
             )?;
         }
         helpers::fs::write(
             self.get_dest_file(base, paths::emitters::dest, paths::emitters::error)?,
-            include_str!("./static/implementation/emitters/error.rs").to_owned(),
+            if stubs {
+                include_str!("./static/implementation/emitters/error.rs")
+            } else {
+                include_str!("./static/traits/implementation/emitters/error.rs")
+            }
+            .to_owned(),
             true,
         )?;
         helpers::fs::write(
             self.get_dest_file(base, paths::emitters::dest, paths::emitters::ready)?,
-            include_str!("./static/implementation/emitters/ready.rs").to_owned(),
+            if stubs {
+                include_str!("./static/implementation/emitters/ready.rs")
+            } else {
+                include_str!("./static/traits/implementation/emitters/ready.rs")
+            }
+            .to_owned(),
             true,
         )?;
         helpers::fs::write(
             self.get_dest_file(base, paths::emitters::dest, paths::emitters::shutdown)?,
-            include_str!("./static/implementation/emitters/shutdown.rs").to_owned(),
+            if stubs {
+                include_str!("./static/implementation/emitters/shutdown.rs")
+            } else {
+                include_str!("./static/traits/implementation/emitters/shutdown.rs")
+            }
+            .to_owned(),
             true,
         )?;
         helpers::fs::write(
-            self.get_dest_file(base, paths::module::dest, paths::module::module)?,
-            include_str!("./static/mod.rs").to_owned(),
+            self.get_dest_file(
+                base,
+                paths::module::dest,
+                crates::root_file(crate_name, paths::module::module),
+            )?,
+            if stubs {
+                include_str!("./static/mod.rs")
+            } else {
+                include_str!("./static/traits/mod.rs")
+            }
+            .to_owned(),
             true,
         )?;
         helpers::fs::write(
             self.get_dest_file(base, paths::scope::dest, paths::scope::module)?,
-            include_str!("./static/implementation/scope/mod.rs").to_owned(),
+            if stubs {
+                include_str!("./static/implementation/scope/mod.rs")
+            } else {
+                include_str!("./static/traits/implementation/scope/mod.rs")
+            }
+            .to_owned(),
             true,
         )?;
-        self.create_if(
-            base,
-            paths::context::dest,
-            paths::context::module,
-            include_str!("./static/context.rs").to_owned(),
-        )?;
+        if stubs {
+            self.create_if(
+                base,
+                paths::context::dest,
+                paths::context::module,
+                include_str!("./static/context.rs").to_owned(),
+            )?;
+        }
         Ok(())
     }
 
@@ -173,7 +194,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, path: &str, file_name: &str) -> Result<PathBuf, String> {
         let dest = base.join(path);
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
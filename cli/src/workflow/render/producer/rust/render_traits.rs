@@ -0,0 +1,316 @@
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, render_event_impl,
+    render_request_response, workflow::event::Event, workflow::request::Request,
+    workflow::store::Store,
+};
+use std::path::{Path, PathBuf};
+
+mod templates {
+    pub const MODULE: &str = r#"use super::{
+    consumer::identification,
+    producer::Control,
+    protocol,
+    scope::{AnonymousScope, Scope},
+    ProducerError,
+};
+use clibri::server;
+use uuid::Uuid;
+
+pub mod responses {
+[[responses]]
+}
+
+pub mod events {
+[[events]]
+}
+
+#[allow(async_fn_in_trait)]
+pub trait ProducerHandlers {
+[[handlers]]
+}
+"#;
+    pub const RESPONSE: &str = r#"pub mod [[module]] {
+    use super::super::protocol;
+[[broadcast_types]]
+    pub enum Response {
+[[response_declaration]]
+    }
+}"#;
+    pub const EVENT: &str = r#"pub mod [[module]] {
+    use super::super::protocol;
+    use uuid::Uuid;
+
+[[broadcast_types]]
+    pub type Output = [[broadcast_refs]];
+}"#;
+    pub const REQUEST_HANDLER: &str = r#"async fn [[module]]<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    request: &protocol::[[request]],
+    scope: &mut Scope<'_, E, C>,
+) -> Result<[[response]], protocol::[[error]]>;"#;
+    pub const BEACON_HANDLER: &str = r#"async fn [[module]]<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    beacon: &protocol::[[beacon]],
+    scope: &mut Scope<'_, E, C>,
+) -> Result<(), String>;"#;
+    pub const EVENT_HANDLER: &str = r#"async fn [[module]]<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    event: protocol::[[event]],
+    scope: &mut AnonymousScope<'_, E, C>,
+) -> Result<[[output]], String>;"#;
+    pub const DEFAULT_EVENT_HANDLER: &str = r#"async fn [[module]]<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    scope: &mut Scope<'_, E, C>,
+) -> Result<[[output]], String>;"#;
+    pub const CONNECTED: &str = r#"#[allow(unused_variables)]
+async fn connected<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    scope: &mut Scope<'_, E, C>,
+) -> Result<(), String> {
+    Ok(())
+}"#;
+    pub const DISCONNECTED: &str = r#"#[allow(unused_variables)]
+async fn disconnected<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    scope: &mut Scope<'_, E, C>,
+) -> Result<(), String> {
+    Ok(())
+}"#;
+    pub const STATIC_HANDLERS: &str = r#"#[allow(unused_variables)]
+async fn error<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    error: ProducerError<E>,
+    uuid: Option<Uuid>,
+    identification: Option<&identification::Identification>,
+    control: &Control<E, C>,
+) -> Result<(), String> {
+    Ok(())
+}
+#[allow(unused_variables)]
+async fn ready<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    control: &Control<E, C>,
+) -> Result<(), String> {
+    Ok(())
+}
+#[allow(unused_variables)]
+async fn shutdown<E: server::Error, C: server::Control<E>>(
+    &mut self,
+    control: &Control<E, C>,
+) -> Result<(), String> {
+    Ok(())
+}"#;
+}
+
+pub struct Render {}
+
+impl Default for Render {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn render(&self, base: &Path, store: &Store) -> Result<(), String> {
+        let dest: PathBuf = self.get_dest_file(base)?;
+        let mut responses: Vec<String> = vec![];
+        let mut events: Vec<String> = vec![];
+        let mut handlers: Vec<String> = vec![];
+        for request in store.requests.iter() {
+            if request.actions.len() > 1 {
+                responses.push(self.get_response(request)?);
+            }
+            handlers.push(self.get_request_handler(request)?);
+        }
+        for beacon in store.beacons.iter() {
+            handlers.push(
+                Template::new(
+                    "producer/rust/traits/beacon_handler",
+                    templates::BEACON_HANDLER,
+                )
+                .set("module", &self.get_mod_name(&beacon.reference))
+                .set("beacon", &tools::into_rust_path(&beacon.reference))
+                .render(),
+            );
+        }
+        for event in store.events.iter() {
+            if !event.broadcasts.is_empty() {
+                events.push(self.get_event(event)?);
+            }
+            handlers.push(self.get_event_handler(event)?);
+        }
+        for (name, default) in [
+            ("connected", templates::CONNECTED),
+            ("disconnected", templates::DISCONNECTED),
+        ] {
+            if !store
+                .events
+                .iter()
+                .any(|event| matches!(event.get_reference(), Ok(reference) if reference == name))
+            {
+                handlers.push(
+                    Template::new(&format!("producer/rust/traits/{}", name), default).render(),
+                );
+            }
+        }
+        handlers.push(
+            Template::new(
+                "producer/rust/traits/static_handlers",
+                templates::STATIC_HANDLERS,
+            )
+            .render(),
+        );
+        let output = Template::new("producer/rust/traits/module", templates::MODULE)
+            .set("responses", &tools::inject_tabs(1, responses.join("\n")))
+            .set("events", &tools::inject_tabs(1, events.join("\n")))
+            .set("handlers", &tools::inject_tabs(1, handlers.join("\n")))
+            .render();
+        // Empty lines are indented by injection of tabs
+        let output = output
+            .split('\n')
+            .map(|line| line.trim_end())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        helpers::fs::write(dest, output, true)
+    }
+
+    fn get_response(&self, request: &Request) -> Result<String, String> {
+        let render = render_request_response::Render::new();
+        let mut types = render.get_broadcast_types(request)?;
+        if !types.is_empty() {
+            types = format!(
+                "{}use uuid::Uuid;\n\n{}",
+                tools::tabs(1),
+                self.as_pub(types)
+            );
+        }
+        let mut declaration: Vec<String> = vec![];
+        for action in request.actions.iter() {
+            if action.broadcast.is_empty() {
+                declaration.push(format!(
+                    "{}(protocol::{}),",
+                    action.get_conclusion()?,
+                    tools::into_rust_path(&action.get_response()?)
+                ));
+            } else {
+                let mut fields = vec![format!(
+                    "protocol::{},",
+                    tools::into_rust_path(&action.get_response()?)
+                )];
+                for broadcast in action.broadcast.iter() {
+                    fields.push(format!("{},", render.get_broadcast_type_name(broadcast)));
+                }
+                declaration.push(format!(
+                    "{}((\n{}\n)),",
+                    action.get_conclusion()?,
+                    tools::inject_tabs(1, fields.join("\n"))
+                ));
+            }
+        }
+        Ok(
+            Template::new("producer/rust/traits/response", templates::RESPONSE)
+                .set("module", &self.get_mod_name(&request.get_request()?))
+                .set("broadcast_types", &types)
+                .set(
+                    "response_declaration",
+                    &tools::inject_tabs(2, declaration.join("\n")),
+                )
+                .render(),
+        )
+    }
+
+    fn get_event(&self, event: &Event) -> Result<String, String> {
+        let (types, refs) = render_event_impl::Render::new().get_broadcasts(event);
+        Ok(
+            Template::new("producer/rust/traits/event", templates::EVENT)
+                .set("module", &self.get_mod_name(&event.get_reference()?))
+                .set("broadcast_types", &self.as_pub(types))
+                .set("broadcast_refs", &refs)
+                .render(),
+        )
+    }
+
+    fn get_request_handler(&self, request: &Request) -> Result<String, String> {
+        let module = self.get_mod_name(&request.get_request()?);
+        let output = Template::new(
+            "producer/rust/traits/request_handler",
+            templates::REQUEST_HANDLER,
+        )
+        .set("module", &module)
+        .set("request", &tools::into_rust_path(&request.get_request()?))
+        .set(
+            "response",
+            &if request.actions.len() > 1 {
+                format!("responses::{}::Response", module)
+            } else {
+                format!(
+                    "protocol::{}",
+                    tools::into_rust_path(&request.get_response()?)
+                )
+            },
+        );
+        Ok(if let Some(error_ref) = request.error.as_ref() {
+            output.set("error", &tools::into_rust_path(error_ref))
+        } else {
+            output
+        }
+        .render())
+    }
+
+    fn get_event_handler(&self, event: &Event) -> Result<String, String> {
+        let module = self.get_mod_name(&event.get_reference()?);
+        let output = if event.is_default() {
+            Template::new(
+                "producer/rust/traits/default_event_handler",
+                templates::DEFAULT_EVENT_HANDLER,
+            )
+        } else {
+            Template::new(
+                "producer/rust/traits/event_handler",
+                templates::EVENT_HANDLER,
+            )
+        };
+        Ok(output
+            .set("module", &module)
+            .set("event", &tools::into_rust_path(&event.get_reference()?))
+            .set(
+                "output",
+                &if event.broadcasts.is_empty() {
+                    String::from("()")
+                } else {
+                    format!("events::{}::Output", module)
+                },
+            )
+            .render())
+    }
+
+    fn get_mod_name(&self, reference: &str) -> String {
+        reference.to_lowercase().replace(".", "_")
+    }
+
+    // Broadcast types are declared privately in stubs, but here they are part of trait
+    fn as_pub(&self, types: String) -> String {
+        types
+            .lines()
+            .map(|line| format!("{}pub {}\n", tools::tabs(1), line))
+            .collect()
+    }
+
+    fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
+        let dest = base.join("implementation");
+        if !dest.exists() {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
+                return Err(format!(
+                    "Fail to create dest folder {}. Error: {}",
+                    dest.to_string_lossy(),
+                    e
+                ));
+            }
+        }
+        Ok(dest.join("traits.rs"))
+    }
+}
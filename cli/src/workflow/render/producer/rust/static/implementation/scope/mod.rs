@@ -1,4 +1,4 @@
-use super::{identification, producer::Control, Context};
+use super::{identification, producer::Control, protocol, Context};
 use clibri::server;
 use std::{future::Future, pin::Pin};
 
@@ -8,6 +8,8 @@ pub struct Scope<'a, E: server::Error, C: server::Control<E>> {
     pub control: &'a Control<E, C>,
     pub identification: &'a identification::Identification,
     pub filter: &'a identification::Filter<'a>,
+    // Unix time (ms) of package, which is processed; None if scope isn't created for package
+    pub ts: Option<u64>,
 }
 
 impl<'a, E: server::Error, C: server::Control<E>> Scope<'a, E, C> {
@@ -23,9 +25,27 @@ impl<'a, E: server::Error, C: server::Control<E>> Scope<'a, E, C> {
             control,
             identification,
             filter,
+            ts: None,
         }
     }
 
+    pub fn with_ts(mut self, ts: u64) -> Self {
+        self.ts = Some(ts);
+        self
+    }
+
+    // Milliseconds since package was sent by consumer. Clock skew is taken into account, when
+    // it's estimated (after agreement on version 2 of header); estimation includes latency
+    pub fn latency(&self) -> Option<i64> {
+        let ts = self.ts?;
+        Some(
+            match protocol::get_clock_skew(&self.identification.uuid().to_string()) {
+                Some(skew) => skew.age(ts),
+                None => protocol::now_ms() as i64 - ts as i64,
+            },
+        )
+    }
+
     pub fn deferred(&mut self, cb: Pin<Box<dyn Future<Output = ()>>>) {
         self.deferred = Some(cb);
     }
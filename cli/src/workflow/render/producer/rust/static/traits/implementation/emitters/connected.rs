@@ -0,0 +1,16 @@
+use super::{identification, producer::Control, scope::Scope, EmitterError, ProducerHandlers};
+use clibri::server;
+pub async fn emit<E: server::Error, C: server::Control<E> + Send + Clone>(
+    identification: &identification::Identification,
+    filter: &identification::Filter<'_>,
+    context: &mut impl ProducerHandlers,
+    control: &Control<E, C>,
+) -> Result<(), EmitterError> {
+    let mut scope: Scope<'_, E, C> = Scope::new(control, identification, filter);
+    context
+        .connected(&mut scope)
+        .await
+        .map_err(EmitterError::Emitting)?;
+    scope.call().await;
+    Ok(())
+}
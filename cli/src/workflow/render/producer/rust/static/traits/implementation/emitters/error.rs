@@ -0,0 +1,16 @@
+use super::{identification, producer::Control, EmitterError, ProducerError, ProducerHandlers};
+use clibri::server;
+use uuid::Uuid;
+
+pub async fn emit<E: server::Error, C: server::Control<E> + Send + Clone>(
+    error: ProducerError<E>,
+    uuid: Option<Uuid>,
+    context: &mut impl ProducerHandlers,
+    identification: Option<&identification::Identification>,
+    control: &Control<E, C>,
+) -> Result<(), EmitterError> {
+    context
+        .error(error, uuid, identification, control)
+        .await
+        .map_err(EmitterError::Emitting)
+}
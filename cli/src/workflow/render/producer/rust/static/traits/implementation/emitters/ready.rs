@@ -0,0 +1,12 @@
+use super::{producer::Control, EmitterError, ProducerHandlers};
+use clibri::server;
+
+pub async fn emit<E: server::Error, C: server::Control<E> + Send + Clone>(
+    context: &mut impl ProducerHandlers,
+    control: &Control<E, C>,
+) -> Result<(), EmitterError> {
+    context
+        .ready(control)
+        .await
+        .map_err(EmitterError::Emitting)
+}
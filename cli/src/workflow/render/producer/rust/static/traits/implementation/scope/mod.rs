@@ -0,0 +1,84 @@
+use super::{identification, producer::Control, protocol};
+use clibri::server;
+use std::{future::Future, pin::Pin};
+
+pub struct Scope<'a, E: server::Error, C: server::Control<E>> {
+    deferred: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    pub control: &'a Control<E, C>,
+    pub identification: &'a identification::Identification,
+    pub filter: &'a identification::Filter<'a>,
+    // Unix time (ms) of package, which is processed; None if scope isn't created for package
+    pub ts: Option<u64>,
+}
+
+impl<'a, E: server::Error, C: server::Control<E>> Scope<'a, E, C> {
+    pub fn new(
+        control: &'a Control<E, C>,
+        identification: &'a identification::Identification,
+        filter: &'a identification::Filter<'a>,
+    ) -> Self {
+        Self {
+            deferred: None,
+            control,
+            identification,
+            filter,
+            ts: None,
+        }
+    }
+
+    pub fn with_ts(mut self, ts: u64) -> Self {
+        self.ts = Some(ts);
+        self
+    }
+
+    // Milliseconds since package was sent by consumer. Clock skew is taken into account, when
+    // it's estimated (after agreement on version 2 of header); estimation includes latency
+    pub fn latency(&self) -> Option<i64> {
+        let ts = self.ts?;
+        Some(
+            match protocol::get_clock_skew(&self.identification.uuid().to_string()) {
+                Some(skew) => skew.age(ts),
+                None => protocol::now_ms() as i64 - ts as i64,
+            },
+        )
+    }
+
+    pub fn deferred(&mut self, cb: Pin<Box<dyn Future<Output = ()>>>) {
+        self.deferred = Some(cb);
+    }
+
+    pub async fn call(&mut self) {
+        if let Some(cb) = self.deferred.take() {
+            cb.await;
+        }
+    }
+}
+
+pub struct AnonymousScope<'a, E: server::Error, C: server::Control<E>> {
+    deferred: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    pub control: &'a Control<E, C>,
+    pub filter: &'a identification::Filter<'a>,
+}
+
+impl<'a, E: server::Error, C: server::Control<E>> AnonymousScope<'a, E, C> {
+    pub fn new(
+        control: &'a Control<E, C>,
+        filter: &'a identification::Filter<'a>,
+    ) -> Self {
+        Self {
+            deferred: None,
+            control,
+            filter,
+        }
+    }
+
+    pub fn deferred(&mut self, cb: Pin<Box<dyn Future<Output = ()>>>) {
+        self.deferred = Some(cb);
+    }
+
+    pub async fn call(&mut self) {
+        if let Some(cb) = self.deferred.take() {
+            cb.await;
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod implementation;
+
+pub use implementation::consumer::identification;
+pub use implementation::producer::run;
+pub use implementation::producer::Control;
+pub use implementation::producer::Options;
+pub use implementation::protocol;
+pub use implementation::scope::{AnonymousScope, Scope};
+pub use implementation::{events, responses, ProducerError, ProducerHandlers};
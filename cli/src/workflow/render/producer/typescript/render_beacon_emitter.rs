@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import { Producer, Identification, Filter, Context, Protocol } from "./index";
@@ -15,9 +12,10 @@ export function handler(
     filter: Filter,
     context: Context,
     producer: Producer,
-    sequence: number
+    sequence: number,
+    ts: bigint
 ): Promise<void> {
-	const scope = new Scope(consumer, filter, context, producer);
+	const scope = new Scope(consumer, filter, context, producer, ts);
 	return new Promise((resolve, reject) => {
 		emit(beacon, scope)
 			.then(() => {
@@ -56,16 +54,20 @@ impl Render {
 
     pub fn render(&self, base: &Path, beacon: &Broadcast) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, beacon)?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[beacon]]", &beacon.reference);
-        output = output.replace("[[beacon_mod]]", &beacon.reference.to_lowercase());
+        let output = Template::new(
+            "producer/typescript/beacon_emitter/module",
+            templates::MODULE,
+        )
+        .set("beacon", &beacon.reference)
+        .set("beacon_mod", &beacon.reference.to_lowercase())
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path, beacon: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import * as Protocol from "../../implementation/protocol";
@@ -13,6 +10,7 @@ export { Identification, Filter } from "../consumer";
 export { Context } from "../../context";
 export { Producer } from "../index";
 export { Protocol };"#;
+    pub const EXPORT: &str = r#"export { handler as [[name]]Handler } from "./[[module]]";"#;
 }
 
 pub struct Render {}
@@ -33,20 +31,33 @@ impl Render {
         let mut mods = String::new();
         for beacon in beacons.iter() {
             mods = format!(
-                "{}export {{ handler as {}Handler }} from \"./{}\";\n",
+                "{}{}\n",
                 mods,
-                helpers::string::first_letter_lowercase(&beacon.reference.replace(".", "")),
-                beacon.reference.to_lowercase()
+                Template::new(
+                    "producer/typescript/beacon_emitters_mod/export",
+                    templates::EXPORT
+                )
+                .set(
+                    "name",
+                    &helpers::string::first_letter_lowercase(&beacon.reference.replace(".", ""))
+                )
+                .set("module", &beacon.reference.to_lowercase())
+                .render()
             );
         }
-        let output = templates::MODULE.to_owned().replace("[[handlers]]", &mods);
+        let output = Template::new(
+            "producer/typescript/beacon_emitters_mod/module",
+            templates::MODULE,
+        )
+        .set("handlers", &mods)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
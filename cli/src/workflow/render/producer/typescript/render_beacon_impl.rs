@@ -1,8 +1,5 @@
-use super::{helpers, workflow::beacon::Broadcast};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::beacon::Broadcast};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import {
@@ -36,19 +33,16 @@ impl Render {
 
     pub fn render(&self, base: &Path, beacon: &Broadcast) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, beacon)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[beacon]]", &beacon.reference);
-        helpers::fs::write(dest, output, true)
+        let output = Template::new("producer/typescript/beacon_impl/module", templates::MODULE)
+            .set("beacon", &beacon.reference)
+            .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn get_dest_file(&self, base: &Path, beacon: &Broadcast) -> Result<PathBuf, String> {
         let dest = base.join("beacons");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
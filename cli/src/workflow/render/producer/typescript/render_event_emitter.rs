@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::event::Event};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::event::Event,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_BROADCAST: &str = r#"import {
@@ -77,6 +76,10 @@ import { emit } from "../../events/[[module]]";
     self._broadcasts.push([uuids, msg]);
     return self;
 },"#;
+    pub const REQUIRED_BROADCASTS: &str = r#"static REQUIRED = [[[broadcasts]]
+];"#;
+    pub const REQUIRED_BROADCAST: &str = r#"Protocol.[[reference]],"#;
+    pub const METHOD_DECLARATION: &str = r#"[[name]](msg: Protocol.[[reference]]): Output;"#;
     pub const HANDLER_WITH_BROADCAST: &str = r#"export function handler(
     event: Protocol.[[event]],
     filter: Filter,
@@ -139,27 +142,30 @@ impl Render {
     pub fn render(&self, base: &Path, event: &Event) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, event)?;
         let event_ref = event.get_reference()?;
-        let mut output: String = if !event.broadcasts.is_empty() {
-            let mut output = templates::MODULE_WITH_BROADCAST.to_owned();
-            output = output.replace(
-                "[[required_broadcasts]]",
-                &self.get_required_broadcasts(event)?,
-            );
-            output = output.replace(
-                "[[methods_declarations]]",
+        let output = if !event.broadcasts.is_empty() {
+            Template::new(
+                "producer/typescript/event_emitter/module_with_broadcast",
+                templates::MODULE_WITH_BROADCAST,
+            )
+            .set("required_broadcasts", &self.get_required_broadcasts(event)?)
+            .set(
+                "methods_declarations",
                 &self.get_methods_declarations(event)?,
-            );
-            output = output.replace(
-                "[[methods_implementations]]",
+            )
+            .set(
+                "methods_implementations",
                 &self.get_methods_implementations(event)?,
-            );
-            output
+            )
         } else {
-            templates::MODULE_NO_BROADCAST.to_owned()
-        };
-        output = output.replace("[[module]]", &tools::into_ts_path(&event_ref));
-        output = output.replace("[[event]]", &event_ref);
-        output = output.replace("[[handler]]", &self.get_handler(event)?);
+            Template::new(
+                "producer/typescript/event_emitter/module_no_broadcast",
+                templates::MODULE_NO_BROADCAST,
+            )
+        }
+        .set("module", &tools::into_ts_path(&event_ref))
+        .set("event", &event_ref)
+        .set("handler", &self.get_handler(event)?)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -170,15 +176,25 @@ impl Render {
             .iter()
             .filter(|broadcast| !broadcast.optional)
         {
-            broadcasts = format!("{}\nProtocol.{},", broadcasts, broadcast.reference);
+            broadcasts = format!(
+                "{}\n{}",
+                broadcasts,
+                Template::new(
+                    "producer/typescript/event_emitter/required_broadcast",
+                    templates::REQUIRED_BROADCAST,
+                )
+                .set("reference", &broadcast.reference)
+                .render()
+            );
         }
         Ok(tools::inject_tabs(
             1,
-            format!(
-                r#"static REQUIRED = [{}
-];"#,
-                tools::inject_tabs(1, broadcasts),
-            ),
+            Template::new(
+                "producer/typescript/event_emitter/required_broadcasts",
+                templates::REQUIRED_BROADCASTS,
+            )
+            .set("broadcasts", &tools::inject_tabs(1, broadcasts))
+            .render(),
         ))
     }
 
@@ -186,10 +202,15 @@ impl Render {
         let mut output = String::new();
         for broadcast in event.broadcasts.iter() {
             output = format!(
-                "{}\n{}(msg: Protocol.{}): Output;",
+                "{}\n{}",
                 output,
-                broadcast.reference.replace(".", ""),
-                broadcast.reference,
+                Template::new(
+                    "producer/typescript/event_emitter/method_declaration",
+                    templates::METHOD_DECLARATION,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set("reference", &broadcast.reference)
+                .render()
             );
         }
         Ok(tools::inject_tabs(2, output))
@@ -198,28 +219,43 @@ impl Render {
     fn get_methods_implementations(&self, event: &Event) -> Result<String, String> {
         let mut output = String::new();
         for broadcast in event.broadcasts.iter() {
-            let mut out = templates::BROADCAST_IMPL.to_owned();
-            out = out.replace("[[name]]", &broadcast.reference.replace(".", ""));
-            out = out.replace("[[reference]]", &broadcast.reference);
-            output = format!("{}\n{}", output, out,);
+            let out = Template::new(
+                "producer/typescript/event_emitter/broadcast_impl",
+                templates::BROADCAST_IMPL,
+            )
+            .set("name", &broadcast.reference.replace(".", ""))
+            .set("reference", &broadcast.reference)
+            .render();
+            output = format!("{}\n{}", output, out);
         }
         Ok(tools::inject_tabs(3, output))
     }
 
     fn get_handler(&self, event: &Event) -> Result<String, String> {
-        let mut output = if self.is_default(event)? {
+        let output = if self.is_default(event)? {
             if event.broadcasts.is_empty() {
-                templates::HANDLER_DEFAULT_WITHOUT_BROADCAST.to_owned()
+                Template::new(
+                    "producer/typescript/event_emitter/handler_default_without_broadcast",
+                    templates::HANDLER_DEFAULT_WITHOUT_BROADCAST,
+                )
             } else {
-                templates::HANDLER_DEFAULT_WITH_BROADCAST.to_owned()
+                Template::new(
+                    "producer/typescript/event_emitter/handler_default_with_broadcast",
+                    templates::HANDLER_DEFAULT_WITH_BROADCAST,
+                )
             }
         } else if event.broadcasts.is_empty() {
-            templates::HANDLER_WITHOUT_BROADCAST.to_owned()
+            Template::new(
+                "producer/typescript/event_emitter/handler_without_broadcast",
+                templates::HANDLER_WITHOUT_BROADCAST,
+            )
         } else {
-            templates::HANDLER_WITH_BROADCAST.to_owned()
+            Template::new(
+                "producer/typescript/event_emitter/handler_with_broadcast",
+                templates::HANDLER_WITH_BROADCAST,
+            )
         };
-        output = output.replace("[[event]]", &event.get_reference()?);
-        Ok(output)
+        Ok(output.set("event", &event.get_reference()?).render())
     }
 
     fn is_default(&self, event: &Event) -> Result<bool, String> {
@@ -233,7 +269,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, event: &Event) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("events");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
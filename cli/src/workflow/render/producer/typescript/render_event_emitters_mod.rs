@@ -1,8 +1,5 @@
-use super::{helpers, workflow::event::Event};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::event::Event};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import * as Protocol from "../../implementation/protocol";
@@ -47,6 +44,7 @@ export function broadcastAll(
             .catch(reject);
     });
 }"#;
+    pub const EXPORT: &str = r#"export { handler as [[name]]Handler } from "./[[module]]";"#;
 }
 
 pub struct Render {}
@@ -68,23 +66,36 @@ impl Render {
         for event in events.iter() {
             if !event.is_default() {
                 mods = format!(
-                    "{}export {{ handler as {}Handler }} from \"./{}\";\n",
+                    "{}{}\n",
                     mods,
-                    helpers::string::first_letter_lowercase(
-                        &event.get_reference()?.replace(".", "")
-                    ),
-                    event.get_reference()?.to_lowercase()
+                    Template::new(
+                        "producer/typescript/event_emitters_mod/export",
+                        templates::EXPORT
+                    )
+                    .set(
+                        "name",
+                        &helpers::string::first_letter_lowercase(
+                            &event.get_reference()?.replace(".", "")
+                        )
+                    )
+                    .set("module", &event.get_reference()?.to_lowercase())
+                    .render()
                 );
             }
         }
-        let output = templates::MODULE.to_owned().replace("[[handlers]]", &mods);
+        let output = Template::new(
+            "producer/typescript/event_emitters_mod/module",
+            templates::MODULE,
+        )
+        .set("handlers", &mods)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("events");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
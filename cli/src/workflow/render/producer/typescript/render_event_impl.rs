@@ -1,8 +1,5 @@
-use super::{helpers, workflow::event::Event};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::event::Event};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_BROADCAST: &str = r#"import { Filter, Producer, Context, Protocol } from "../implementation/events";
@@ -84,24 +81,33 @@ impl Render {
 
     pub fn render(&self, base: &Path, event: &Event) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, event)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
-        let mut output: String = if self.is_default(event)? {
+        let output = if self.is_default(event)? {
             if event.broadcasts.is_empty() {
-                templates::DEFAULT_MODULE_WITHOUT_BROADCAST.to_owned()
+                Template::new(
+                    "producer/typescript/event_impl/default_module_without_broadcast",
+                    templates::DEFAULT_MODULE_WITHOUT_BROADCAST,
+                )
             } else {
-                templates::DEFAULT_MODULE_WITH_BROADCAST.to_owned()
+                Template::new(
+                    "producer/typescript/event_impl/default_module_with_broadcast",
+                    templates::DEFAULT_MODULE_WITH_BROADCAST,
+                )
             }
         } else if event.broadcasts.is_empty() {
-            templates::MODULE_WITHOUT_BROADCAST.to_owned()
+            Template::new(
+                "producer/typescript/event_impl/module_without_broadcast",
+                templates::MODULE_WITHOUT_BROADCAST,
+            )
         } else {
-            templates::MODULE_WITH_BROADCAST.to_owned()
-        };
-        output = output.replace("[[event]]", &event.get_reference()?);
-        output = output.replace("[[module]]", &event.get_reference()?.to_lowercase());
-        helpers::fs::write(dest, output, true)
+            Template::new(
+                "producer/typescript/event_impl/module_with_broadcast",
+                templates::MODULE_WITH_BROADCAST,
+            )
+        }
+        .set("event", &event.get_reference()?)
+        .set("module", &event.get_reference()?.to_lowercase())
+        .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn is_default(&self, event: &Event) -> Result<bool, String> {
@@ -115,7 +121,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, event: &Event) -> Result<PathBuf, String> {
         let dest = base.join("events");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
@@ -1,10 +1,8 @@
 use super::{
-    helpers, helpers::render as tools, workflow::config::Config, workflow::store::Store, Protocol,
-};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+    helpers, helpers::render as tools, helpers::template::Template, workflow::config::Config,
+    workflow::store::Store, Protocol,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import { ProducerIdentificationStrategy, Logger } from "clibri";
@@ -14,6 +12,7 @@ export class Identification {
     private readonly _uuid: string;
     private readonly _strategy: ProducerIdentificationStrategy;
     private _discredited: boolean = false;
+    private _version: number = 0;
     private _key: Protocol.[[self_key]] | undefined;
     private _assigned: Protocol.[[assign_key]] | undefined;
     private _logger: Logger;
@@ -32,6 +31,20 @@ export class Identification {
         return this._uuid;
     }
     
+    // Version of protocol and workflow, which consumer uses: 0 - current version;
+    // 1.. - position of compatible version in Producer.hash.COMPATIBLE + 1
+    public version(): number {
+        return this._version;
+    }
+
+    public isCurrentVersion(): boolean {
+        return this._version === 0;
+    }
+
+    public setVersion(version: number) {
+        this._version = version;
+    }
+
     public getKey(): Protocol.[[self_key]] | undefined {
         return this._key;
     }
@@ -112,18 +125,22 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &Store, protocol: &Protocol) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = templates::MODULE.to_owned();
         let config = store.get_config()?;
-        output = output.replace("[[assign_key]]", &store.get_config()?.get_assigned()?);
-        output = output.replace("[[self_key]]", &store.get_config()?.get_self()?);
-        output = output.replace(
-            "[[self_key_overwrite]]",
+        let output = Template::new(
+            "producer/typescript/identification/module",
+            templates::MODULE,
+        )
+        .set("assign_key", &config.get_assigned()?)
+        .set("self_key", &config.get_self()?)
+        .set(
+            "self_key_overwrite",
             &tools::inject_tabs(3, self.self_key_overwrite(config, protocol)?),
-        );
-        output = output.replace(
-            "[[assign_key_overwrite]]",
+        )
+        .set(
+            "assign_key_overwrite",
             &tools::inject_tabs(3, self.assign_key_overwrite(config, protocol)?),
-        );
+        )
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -134,7 +151,12 @@ impl Render {
                 output = format!(
                     "{}\n{}",
                     output,
-                    templates::KEY_CHECK.replace("[[field]]", &field.name)
+                    Template::new(
+                        "producer/typescript/identification/key_check",
+                        templates::KEY_CHECK
+                    )
+                    .set("field", &field.name)
+                    .render()
                 );
             }
             Ok(output)
@@ -150,7 +172,12 @@ impl Render {
                 output = format!(
                     "{}\n{}",
                     output,
-                    templates::ASSIGNED_CHECK.replace("[[field]]", &field.name)
+                    Template::new(
+                        "producer/typescript/identification/assigned_check",
+                        templates::ASSIGNED_CHECK
+                    )
+                    .set("field", &field.name)
+                    .render()
                 );
             }
             Ok(output)
@@ -162,7 +189,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("consumer");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
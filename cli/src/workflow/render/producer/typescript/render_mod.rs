@@ -1,8 +1,8 @@
-use super::{helpers, helpers::render as tools, workflow::store::Store, Protocol};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::store::Store,
+    Protocol,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import { Consumer, Filter } from "./consumer";
@@ -30,28 +30,66 @@ export class Producer {
     static hash: {
         PROTOCOL: string;
         WORKFLOW: string;
+        PROTOCOL_SOURCE: string;
+        WORKFLOW_SOURCE: string;
+        COMPATIBLE: Array<[string, string]>;
     } = {
         PROTOCOL:
             "[[protocol_hash]]",
         WORKFLOW:
             "[[workflow_hash]]",
+        PROTOCOL_SOURCE:
+            "[[protocol_source_hash]]",
+        WORKFLOW_SOURCE:
+            "[[workflow_source_hash]]",
+        // Hashes (protocol, workflow) of previous versions, which are declared as wire-compatible
+        COMPATIBLE: [[[compatible]]],
     };
+
+    // Version, which is used by consumer with given hashes: 0 - current version;
+    // 1.. - position in COMPATIBLE + 1; undefined - consumer isn't compatible
+    static version(protocol: string, workflow: string): number | undefined {
+        if (
+            protocol === Producer.hash.PROTOCOL &&
+            workflow === Producer.hash.WORKFLOW
+        ) {
+            return 0;
+        }
+        const pos: number = Producer.hash.COMPATIBLE.findIndex(
+            ([p, w]) => p === protocol && w === workflow
+        );
+        return pos === -1 ? undefined : pos + 1;
+    }
     private readonly _server: Server;
     private readonly _subscriptions: { [key: string]: Subscription } = {};
     private readonly _consumers: Map<string, Consumer> = new Map();
     private readonly _options: Options;
     private readonly _logger: Logger;
     private readonly _context: Context;
+    private readonly _limits: Protocol.ILimits;
 
     public readonly events: {[[events_subjects_dec]]
     } = {[[events_subjects_def]]
     };
 
-    constructor(server: Server, context: Context, options?: Options) {
+    constructor(
+        server: Server,
+        context: Context,
+        options?: Options,
+        compression: Protocol.ICompression = {
+            algorithm: Protocol.CompressionAlgorithm.[[compression_algorithm]],
+            threshold: [[compression_threshold]],
+        },
+        checksum: boolean = [[checksum]],
+        limits: Protocol.ILimits = Protocol.ProtocolLimits.defaults()
+    ) {
         this._server = server;
         this._context = context;
+        this._limits = limits;
         this._options = options === undefined ? new Options({}) : options;
         this._logger = this._options.logger.clone(`Producer`);
+        Protocol.setCompression(compression);
+        Protocol.setChecksum(checksum);
         this._subscriptions.ready = this._server
             .getEvents()
             .ready.subscribe(this._onServerReady.bind(this));
@@ -171,7 +209,7 @@ export class Producer {
             );
             return;
         }
-        const consumer = new Consumer(uuid, this._options, this._logger);
+        const consumer = new Consumer(uuid, this._options, this._logger, this._limits);
         this._logger.debug(`new connection accepted: ${uuid}`);
         this._consumers.set(uuid, consumer);
         this._checkErr(
@@ -185,6 +223,7 @@ export class Producer {
     }
 
     private _onClientDisconnected(uuid: string) {
+        Protocol.PackingCapabilities.remove(uuid);
         const consumer = this._consumers.get(uuid);
         if (consumer === undefined) {
             this._checkErr(
@@ -284,14 +323,21 @@ export class Producer {
                 let hashErr: ProducerError | undefined;
                 const consumerHash =
                     extracted.body<Protocol.InternalServiceGroup.HashRequest>();
-                if (consumerHash.protocol !== Producer.hash.PROTOCOL) {
+                const version: number | undefined = Producer.version(
+                    consumerHash.protocol,
+                    consumerHash.workflow
+                );
+                if (
+                    version === undefined &&
+                    consumerHash.protocol !== Producer.hash.PROTOCOL
+                ) {
                     hashErr = new ProducerError(
                         this._logger.warn(
                             `Consumer ${event.uuid} has invalid protocol; valid protocol hash ${Producer.hash.PROTOCOL}; consumer protocol: ${consumerHash.protocol}.`
                         ),
                         ProducerErrorType.ProtocolHash
                     );
-                } else if (consumerHash.workflow !== Producer.hash.WORKFLOW) {
+                } else if (version === undefined) {
                     hashErr = new ProducerError(
                         this._logger.warn(
                             `Consumer ${event.uuid} has invalid workflow; valid workflow hash ${Producer.hash.WORKFLOW}; consumer workflow: ${consumerHash.workflow}.`
@@ -299,11 +345,71 @@ export class Producer {
                         ProducerErrorType.WorkflowHash
                     );
                 }
+                const capabilities: Protocol.ICapabilities =
+                    Protocol.PackingCapabilities.local(
+                        Protocol.codec(),
+                        this._limits
+                    );
+                // Consumer without negotiation doesn't send capabilities
+                const legacy: Protocol.ICapabilities =
+                    Protocol.PackingCapabilities.legacy(Protocol.codec());
+                let agreed: Protocol.ICapabilities = capabilities;
+                if (hashErr === undefined) {
+                    const agreement: Protocol.IAgreement | Error =
+                        Protocol.PackingCapabilities.negotiate(capabilities, {
+                            version:
+                                consumerHash.version === undefined
+                                    ? legacy.version
+                                    : consumerHash.version,
+                            compression:
+                                consumerHash.compression === undefined
+                                    ? legacy.compression
+                                    : consumerHash.compression,
+                            encryption:
+                                consumerHash.encryption === undefined
+                                    ? legacy.encryption
+                                    : consumerHash.encryption,
+                            codec:
+                                consumerHash.codec === undefined
+                                    ? legacy.codec
+                                    : consumerHash.codec,
+                            frame:
+                                consumerHash.frame === undefined
+                                    ? legacy.frame
+                                    : consumerHash.frame,
+                            heartbeat:
+                                consumerHash.heartbeat === undefined
+                                    ? legacy.heartbeat
+                                    : consumerHash.heartbeat,
+                        });
+                    if (agreement instanceof Error) {
+                        hashErr = new ProducerError(
+                            this._logger.warn(
+                                `Consumer ${event.uuid} has incompatible capabilities: ${agreement.message}`
+                            ),
+                            ProducerErrorType.Capabilities
+                        );
+                    } else {
+                        // HashRequest is sent before agreement, so its timestamp is in
+                        // seconds; clock is estimated by next packages
+                        agreed = Protocol.PackingCapabilities.agreed(
+                            capabilities,
+                            agreement
+                        );
+                        Protocol.PackingCapabilities.set(event.uuid, agreement);
+                    }
+                }
                 this.send(
                     event.uuid,
                     new Protocol.InternalServiceGroup.HashResponse({
                         error:
                             hashErr === undefined ? undefined : `Invalid hash`,
+                        version: agreed.version,
+                        compression: agreed.compression,
+                        encryption: agreed.encryption,
+                        codec: agreed.codec,
+                        frame: agreed.frame,
+                        heartbeat: agreed.heartbeat,
                     }).pack(message.header.sequence, event.uuid)
                 ).catch((err: Error) => {
                     this._logger.err(
@@ -325,8 +431,8 @@ export class Producer {
                             new Filter(this._consumers)
                         )
                     );
-                } else {
-                    consumer.acceptHash();
+                } else if (version !== undefined) {
+                    consumer.acceptHash(version);
                 }
                 continue;
             }
@@ -421,7 +527,8 @@ export class Producer {
                     );
                 }
                 continue;
-            }[[requests]][[beacons]]
+            }
+            Protocol.PackingCapabilities.observe(event.uuid, message.header);[[requests]][[beacons]]
             this._receivingErr(
                 new Error(
                     `unknown message from ${event.uuid} has been received`
@@ -568,7 +675,8 @@ if (extracted.exist) {
             new Filter(this._consumers),
             this._context,
             this,
-            message.header.sequence
+            message.header.sequence,
+            message.header.timestamp
         ),
         event.uuid,
         consumer
@@ -584,13 +692,16 @@ if (extracted.exist) {
             new Filter(this._consumers),
             this._context,
             this,
-            message.header.sequence
+            message.header.sequence,
+            message.header.timestamp
         ),
         event.uuid,
         consumer
     );
     continue;
 }"#;
+    pub const EVENT_SUBJECT_DEC: &str = r#"[[event]]: Subject<Protocol.[[ref]]>;"#;
+    pub const EVENT_SUBJECT_DEF: &str = r#"[[event]]: new Subject<Protocol.[[ref]]>(),"#;
     pub const EVENT_SUBSCRIPTION: &str = r#"this._subscriptions.[[handler]]Sub = this.events.[[event]].subscribe(
     (event: Protocol.[[ref]]) => {
         this._checkErr(
@@ -620,41 +731,58 @@ impl Render {
 
     pub fn render(&self, base: &Path, store: &Store, protocol: &Protocol) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[requests]]", &self.get_requests(store)?);
-        output = output.replace("[[beacons]]", &self.get_beacons(store)?);
-        output = output.replace(
-            "[[events_subjects_dec]]",
-            &self.get_events_subjects_dec(store)?,
-        );
-        output = output.replace(
-            "[[events_subjects_def]]",
-            &self.get_events_subjects_def(store)?,
-        );
-        output = output.replace(
-            "[[events_subjects_sub]]",
-            &self.get_events_subjects_sub(store)?,
-        );
-        output = output.replace("[[self_key]]", &store.get_config()?.get_self()?);
-        output = output.replace("[[protocol_hash]]", &protocol.get_hash());
-        output = output.replace("[[workflow_hash]]", &store.get_hash());
+        let output = Template::new("producer/typescript/mod/module", templates::MODULE)
+            .set("requests", &self.get_requests(store)?)
+            .set("beacons", &self.get_beacons(store)?)
+            .set("events_subjects_dec", &self.get_events_subjects_dec(store)?)
+            .set("events_subjects_def", &self.get_events_subjects_def(store)?)
+            .set("events_subjects_sub", &self.get_events_subjects_sub(store)?)
+            .set("self_key", &store.get_config()?.get_self()?)
+            .set(
+                "compression_algorithm",
+                store.get_config()?.get_compression().as_variant(),
+            )
+            .set(
+                "compression_threshold",
+                &store.get_config()?.get_compression_threshold().to_string(),
+            )
+            .set(
+                "checksum",
+                &store.get_config()?.get_checksum().enabled().to_string(),
+            )
+            .set("protocol_hash", &protocol.get_hash())
+            .set("workflow_hash", &store.get_hash())
+            .set("protocol_source_hash", &protocol.get_source_hash())
+            .set("workflow_source_hash", &store.get_source_hash())
+            .set(
+                "compatible",
+                &store
+                    .get_compatible()
+                    .iter()
+                    .map(|(protocol, workflow)| format!("[\"{}\", \"{}\"]", protocol, workflow))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            )
+            .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_requests(&self, store: &Store) -> Result<String, String> {
         let mut output: String = String::new();
         for request in store.requests.iter() {
-            let mut request_output: String = String::from(templates::REQUEST);
-            request_output = request_output.replace("[[ref]]", &request.get_request()?);
-            request_output = request_output.replace(
-                "[[handler]]",
-                &format!(
-                    "{}Handler",
-                    helpers::string::first_letter_lowercase(
-                        &request.get_request()?.replace(".", "")
+            let request_output =
+                Template::new("producer/typescript/mod/request", templates::REQUEST)
+                    .set("ref", &request.get_request()?)
+                    .set(
+                        "handler",
+                        &format!(
+                            "{}Handler",
+                            helpers::string::first_letter_lowercase(
+                                &request.get_request()?.replace(".", "")
+                            )
+                        ),
                     )
-                ),
-            );
+                    .render();
             output = format!("{}\n{}", output, request_output);
         }
         Ok(tools::inject_tabs(3, output))
@@ -663,15 +791,16 @@ impl Render {
     fn get_beacons(&self, store: &Store) -> Result<String, String> {
         let mut output: String = String::new();
         for beacon in store.beacons.iter() {
-            let mut beacon_output: String = String::from(templates::BEACON);
-            beacon_output = beacon_output.replace("[[ref]]", &beacon.reference);
-            beacon_output = beacon_output.replace(
-                "[[handler]]",
-                &format!(
-                    "{}Handler",
-                    helpers::string::first_letter_lowercase(&beacon.reference.replace(".", ""))
-                ),
-            );
+            let beacon_output = Template::new("producer/typescript/mod/beacon", templates::BEACON)
+                .set("ref", &beacon.reference)
+                .set(
+                    "handler",
+                    &format!(
+                        "{}Handler",
+                        helpers::string::first_letter_lowercase(&beacon.reference.replace(".", ""))
+                    ),
+                )
+                .render();
             output = format!("{}\n{}", output, beacon_output);
         }
         Ok(tools::inject_tabs(3, output))
@@ -682,12 +811,20 @@ impl Render {
         for event in store.events.iter() {
             if !event.is_default() {
                 output = format!(
-                    "{}\n{}: Subject<Protocol.{}>;",
+                    "{}\n{}",
                     output,
-                    helpers::string::first_letter_lowercase(
-                        &event.get_reference()?.replace(".", "")
-                    ),
-                    event.get_reference()?
+                    Template::new(
+                        "producer/typescript/mod/event_subject_dec",
+                        templates::EVENT_SUBJECT_DEC,
+                    )
+                    .set(
+                        "event",
+                        &helpers::string::first_letter_lowercase(
+                            &event.get_reference()?.replace(".", "")
+                        ),
+                    )
+                    .set("ref", &event.get_reference()?)
+                    .render()
                 );
             }
         }
@@ -699,12 +836,20 @@ impl Render {
         for event in store.events.iter() {
             if !event.is_default() {
                 output = format!(
-                    "{}\n{}: new Subject<Protocol.{}>(),",
+                    "{}\n{}",
                     output,
-                    helpers::string::first_letter_lowercase(
-                        &event.get_reference()?.replace(".", "")
-                    ),
-                    event.get_reference()?
+                    Template::new(
+                        "producer/typescript/mod/event_subject_def",
+                        templates::EVENT_SUBJECT_DEF,
+                    )
+                    .set(
+                        "event",
+                        &helpers::string::first_letter_lowercase(
+                            &event.get_reference()?.replace(".", "")
+                        ),
+                    )
+                    .set("ref", &event.get_reference()?)
+                    .render()
                 );
             }
         }
@@ -715,23 +860,27 @@ impl Render {
         let mut output: String = String::new();
         for event in store.events.iter() {
             if !event.is_default() {
-                let mut sub = templates::EVENT_SUBSCRIPTION.to_owned();
-                sub = sub.replace(
-                    "[[handler]]",
+                let sub = Template::new(
+                    "producer/typescript/mod/event_subscription",
+                    templates::EVENT_SUBSCRIPTION,
+                )
+                .set(
+                    "handler",
                     &format!(
                         "{}Handler",
                         helpers::string::first_letter_lowercase(
                             &event.get_reference()?.replace(".", "")
                         )
                     ),
-                );
-                sub = sub.replace(
-                    "[[event]]",
+                )
+                .set(
+                    "event",
                     &helpers::string::first_letter_lowercase(
                         &event.get_reference()?.replace(".", ""),
                     ),
-                );
-                sub = sub.replace("[[ref]]", &event.get_reference()?);
+                )
+                .set("ref", &event.get_reference()?)
+                .render();
                 output = format!("{}\n{}", output, sub);
             }
         }
@@ -741,7 +890,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
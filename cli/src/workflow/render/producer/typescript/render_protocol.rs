@@ -1,8 +1,5 @@
-use super::{Protocol, ProtocolRender, ProtocolTypescriptRender};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, Protocol, ProtocolRender, ProtocolTypescriptRender};
+use std::path::{Path, PathBuf};
 
 pub struct Render {}
 
@@ -30,7 +27,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("protocol");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
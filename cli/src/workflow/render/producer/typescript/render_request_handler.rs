@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::request::Request,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE_WITH_CONCLUSION: &str = r#"import {
@@ -53,10 +52,11 @@ export function handler(
     filter: Filter,
     context: Context,
     producer: Producer,
-    sequence: number
+    sequence: number,
+    ts: bigint
 ): Promise<void> {
 	return new Promise((resolve, reject) => {
-        const scope = new Scope(consumer, filter, context, producer);
+        const scope = new Scope(consumer, filter, context, producer, ts);
 		response(request, scope)
 			.then((res) => {
 				const error: Error | undefined = res.error();
@@ -98,6 +98,10 @@ export function handler(
     self._broadcasts.push([uuids, msg]);
     return self;
 },"#;
+    pub const REQUIRED_BROADCASTS: &str = r#"static REQUIRED_[[conclusion]] = [[[broadcasts]]
+];"#;
+    pub const REQUIRED_BROADCAST: &str = r#"Protocol.[[reference]],"#;
+    pub const METHOD_DECLARATION: &str = r#"[[name]](msg: Protocol.[[reference]]): Response;"#;
     pub const BROADCAST_CHECK: &str = r#"if (
     error === undefined &&
     this._response.getId() ===
@@ -140,9 +144,10 @@ export function handler(
     filter: Filter,
     context: Context,
     producer: Producer,
-    sequence: number
+    sequence: number,
+    ts: bigint
 ): Promise<void> {
-	const scope = new Scope(consumer, filter, context, producer);
+	const scope = new Scope(consumer, filter, context, producer, ts);
 	return new Promise((resolve, reject) => {
 		response(request, scope)
 			.then((res) => {
@@ -175,37 +180,42 @@ impl Render {
     pub fn render(&self, base: &Path, request: &Request) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, request)?;
         let request_ref = request.get_request()?;
-        let mut output: String = if request.actions.len() > 1 {
-            let mut output = templates::MODULE_WITH_CONCLUSION.to_owned();
-            output = output.replace(
-                "[[required_broadcasts]]",
+        let output = if request.actions.len() > 1 {
+            Template::new(
+                "producer/typescript/request_handler/module_with_conclusion",
+                templates::MODULE_WITH_CONCLUSION,
+            )
+            .set(
+                "required_broadcasts",
                 &self.get_required_broadcasts(request)?,
-            );
-            output = output.replace("[[expectetions]]", &self.get_expectetions(request)?);
-            output = output.replace(
-                "[[methods_declarations]]",
+            )
+            .set("expectetions", &self.get_expectetions(request)?)
+            .set(
+                "methods_declarations",
                 &self.get_methods_declarations(request)?,
-            );
-            output = output.replace(
-                "[[methods_implementations]]",
+            )
+            .set(
+                "methods_implementations",
                 &self.get_methods_implementations(request)?,
-            );
-            output = output.replace("[[broadcast_checks]]", &self.get_broadcast_checks(request)?);
-            output
+            )
+            .set("broadcast_checks", &self.get_broadcast_checks(request)?)
         } else {
-            let mut output = templates::MODULE_NO_CONCLUSION.to_owned();
-            output = output.replace(
-                "[[expectetions]]",
+            Template::new(
+                "producer/typescript/request_handler/module_no_conclusion",
+                templates::MODULE_NO_CONCLUSION,
+            )
+            .set(
+                "expectetions",
                 &format!(
                     "Protocol.{} | Protocol.{}",
                     request.get_response()?,
                     request.get_err()?
                 ),
-            );
-            output
-        };
-        output = output.replace("[[module]]", &tools::into_ts_path(&request_ref));
-        output = output.replace("[[request]]", &request_ref);
+            )
+        }
+        .set("module", &tools::into_ts_path(&request_ref))
+        .set("request", &request_ref)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
@@ -219,15 +229,27 @@ impl Render {
                     .iter()
                     .filter(|broadcast| !broadcast.optional)
                 {
-                    broadcasts = format!("{}\nProtocol.{},", broadcasts, broadcast.reference);
+                    broadcasts = format!(
+                        "{}\n{}",
+                        broadcasts,
+                        Template::new(
+                            "producer/typescript/request_handler/required_broadcast",
+                            templates::REQUIRED_BROADCAST,
+                        )
+                        .set("reference", &broadcast.reference)
+                        .render()
+                    );
                 }
                 output = format!(
-                    r#"{}{}static REQUIRED_{} = [{}
-];"#,
+                    "{}\n{}",
                     output,
-                    "\n",
-                    action.get_conclusion()?.to_uppercase(),
-                    tools::inject_tabs(1, broadcasts),
+                    Template::new(
+                        "producer/typescript/request_handler/required_broadcasts",
+                        templates::REQUIRED_BROADCASTS,
+                    )
+                    .set("conclusion", &action.get_conclusion()?.to_uppercase())
+                    .set("broadcasts", &tools::inject_tabs(1, broadcasts))
+                    .render()
                 );
             }
         }
@@ -253,10 +275,15 @@ impl Render {
         for action in request.actions.iter() {
             for broadcast in action.broadcast.iter() {
                 output = format!(
-                    "{}\n{}(msg: Protocol.{}): Response;",
+                    "{}\n{}",
                     output,
-                    broadcast.reference.replace(".", ""),
-                    broadcast.reference,
+                    Template::new(
+                        "producer/typescript/request_handler/method_declaration",
+                        templates::METHOD_DECLARATION,
+                    )
+                    .set("name", &broadcast.reference.replace(".", ""))
+                    .set("reference", &broadcast.reference)
+                    .render()
                 );
             }
         }
@@ -267,11 +294,15 @@ impl Render {
         let mut output = String::new();
         for action in request.actions.iter() {
             for broadcast in action.broadcast.iter() {
-                let mut out = templates::BROADCAST_IMPL.to_owned();
-                out = out.replace("[[name]]", &broadcast.reference.replace(".", ""));
-                out = out.replace("[[reference]]", &broadcast.reference);
-                out = out.replace("[[conclusion]]", &action.get_response()?);
-                output = format!("{}\n{}", output, out,);
+                let out = Template::new(
+                    "producer/typescript/request_handler/broadcast_impl",
+                    templates::BROADCAST_IMPL,
+                )
+                .set("name", &broadcast.reference.replace(".", ""))
+                .set("reference", &broadcast.reference)
+                .set("conclusion", &action.get_response()?)
+                .render();
+                output = format!("{}\n{}", output, out);
             }
         }
         Ok(tools::inject_tabs(3, output))
@@ -281,13 +312,17 @@ impl Render {
         let mut output = String::new();
         for action in request.actions.iter() {
             if !action.broadcast.is_empty() {
-                let mut out = templates::BROADCAST_CHECK.to_owned();
-                out = out.replace(
-                    "[[requered_conclusion]]",
+                let out = Template::new(
+                    "producer/typescript/request_handler/broadcast_check",
+                    templates::BROADCAST_CHECK,
+                )
+                .set(
+                    "requered_conclusion",
                     &action.get_conclusion()?.to_uppercase(),
-                );
-                out = out.replace("[[conclusion]]", &action.get_response()?);
-                output = format!("{}\n{}", output, out,);
+                )
+                .set("conclusion", &action.get_response()?)
+                .render();
+                output = format!("{}\n{}", output, out);
             }
         }
         Ok(tools::inject_tabs(2, output))
@@ -296,7 +331,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, request: &Request) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("responses");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
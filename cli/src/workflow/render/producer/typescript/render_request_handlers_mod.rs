@@ -1,8 +1,5 @@
-use super::{helpers, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use super::{helpers, helpers::template::Template, workflow::request::Request};
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import * as Protocol from "../../implementation/protocol";
@@ -42,6 +39,7 @@ export function broadcastAll(
             .catch(reject);
     });
 }"#;
+    pub const EXPORT: &str = r#"export { handler as [[name]]Handler } from "./[[module]]";"#;
 }
 
 pub struct Render {}
@@ -59,24 +57,38 @@ impl Render {
 
     pub fn render(&self, base: &Path, requests: &[Request]) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base)?;
-        let mut output = String::from(templates::MODULE);
         let mut mods = String::new();
         for request in requests.iter() {
             mods = format!(
-                "{}export {{ handler as {}Handler }} from \"./{}\";\n",
+                "{}{}\n",
                 mods,
-                helpers::string::first_letter_lowercase(&request.get_request()?.replace(".", "")),
-                request.get_request()?.to_lowercase()
+                Template::new(
+                    "producer/typescript/request_handlers_mod/export",
+                    templates::EXPORT
+                )
+                .set(
+                    "name",
+                    &helpers::string::first_letter_lowercase(
+                        &request.get_request()?.replace(".", "")
+                    )
+                )
+                .set("module", &request.get_request()?.to_lowercase())
+                .render()
             );
         }
-        output = output.replace("[[handlers]]", &mods);
+        let output = Template::new(
+            "producer/typescript/request_handlers_mod/module",
+            templates::MODULE,
+        )
+        .set("handlers", &mods)
+        .render();
         helpers::fs::write(dest, output, true)
     }
 
     fn get_dest_file(&self, base: &Path) -> Result<PathBuf, String> {
         let dest = base.join("implementation").join("responses");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir_all(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
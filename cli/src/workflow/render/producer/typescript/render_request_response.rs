@@ -1,8 +1,7 @@
-use super::{helpers, helpers::render as tools, workflow::request::Request};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use super::{
+    helpers, helpers::render as tools, helpers::template::Template, workflow::request::Request,
 };
+use std::path::{Path, PathBuf};
 
 mod templates {
     pub const MODULE: &str = r#"import { Response } from "../implementation/responses/[[module]]";
@@ -37,21 +36,21 @@ impl Render {
 
     pub fn render(&self, base: &Path, request: &Request) -> Result<(), String> {
         let dest: PathBuf = self.get_dest_file(base, request)?;
-        if dest.exists() {
-            println!("[SKIP]: {}", dest.to_string_lossy());
-            return Ok(());
-        }
         let request_ref = request.get_request()?;
-        let mut output = templates::MODULE.to_owned();
-        output = output.replace("[[request]]", &request_ref);
-        output = output.replace("[[module]]", &tools::into_ts_path(&request_ref));
-        helpers::fs::write(dest, output, true)
+        let output = Template::new(
+            "producer/typescript/request_response/module",
+            templates::MODULE,
+        )
+        .set("request", &request_ref)
+        .set("module", &tools::into_ts_path(&request_ref))
+        .render();
+        helpers::stubs::write(dest, output)
     }
 
     fn get_dest_file(&self, base: &Path, request: &Request) -> Result<PathBuf, String> {
         let dest = base.join("responses");
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
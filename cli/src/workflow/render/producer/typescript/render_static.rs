@@ -1,9 +1,6 @@
 use super::{helpers, workflow::event::Event};
 use std::include_str;
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 #[allow(non_upper_case_globals)]
 mod paths {
@@ -59,16 +56,10 @@ impl Render {
             Ok(reference) => reference == "connected",
             Err(_) => false,
         }) {
-            if !self
-                .get_dest_file(base, paths::events::dest, paths::events::connected)?
-                .exists()
-            {
-                helpers::fs::write(
-                    self.get_dest_file(base, paths::events::dest, paths::events::connected)?,
-                    include_str!("./static/events/connected.ts").to_owned(),
-                    true,
-                )?;
-            }
+            helpers::stubs::write(
+                self.get_dest_file(base, paths::events::dest, paths::events::connected)?,
+                include_str!("./static/events/connected.ts").to_owned(),
+            )?;
             helpers::fs::write(
                 self.get_dest_file(base, paths::emitters::dest, paths::emitters::connected)?,
                 include_str!("./static/implementation/events/connected.ts").to_owned(),
@@ -79,52 +70,28 @@ impl Render {
             Ok(reference) => reference == "disconnected",
             Err(_) => false,
         }) {
-            if !self
-                .get_dest_file(base, paths::events::dest, paths::events::disconnected)?
-                .exists()
-            {
-                helpers::fs::write(
-                    self.get_dest_file(base, paths::events::dest, paths::events::disconnected)?,
-                    include_str!("./static/events/disconnected.ts").to_owned(),
-                    true,
-                )?;
-            }
+            helpers::stubs::write(
+                self.get_dest_file(base, paths::events::dest, paths::events::disconnected)?,
+                include_str!("./static/events/disconnected.ts").to_owned(),
+            )?;
             helpers::fs::write(
                 self.get_dest_file(base, paths::emitters::dest, paths::emitters::disconnected)?,
                 include_str!("./static/implementation/events/disconnected.ts").to_owned(),
                 true,
             )?;
         }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::error)?
-            .exists()
-        {
-            helpers::fs::write(
-                self.get_dest_file(base, paths::events::dest, paths::events::error)?,
-                include_str!("./static/events/error.ts").to_owned(),
-                true,
-            )?;
-        }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::ready)?
-            .exists()
-        {
-            helpers::fs::write(
-                self.get_dest_file(base, paths::events::dest, paths::events::ready)?,
-                include_str!("./static/events/ready.ts").to_owned(),
-                true,
-            )?;
-        }
-        if !self
-            .get_dest_file(base, paths::events::dest, paths::events::shutdown)?
-            .exists()
-        {
-            helpers::fs::write(
-                self.get_dest_file(base, paths::events::dest, paths::events::shutdown)?,
-                include_str!("./static/events/shutdown.ts").to_owned(),
-                true,
-            )?;
-        }
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::error)?,
+            include_str!("./static/events/error.ts").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::ready)?,
+            include_str!("./static/events/ready.ts").to_owned(),
+        )?;
+        helpers::stubs::write(
+            self.get_dest_file(base, paths::events::dest, paths::events::shutdown)?,
+            include_str!("./static/events/shutdown.ts").to_owned(),
+        )?;
         helpers::fs::write(
             self.get_dest_file(base, paths::consumer::dest, paths::consumer::module)?,
             include_str!("./static/implementation/consumer/index.ts").to_owned(),
@@ -175,7 +142,7 @@ impl Render {
     fn get_dest_file(&self, base: &Path, path: &str, file_name: &str) -> Result<PathBuf, String> {
         let dest = base.join(path);
         if !dest.exists() {
-            if let Err(e) = fs::create_dir(&dest) {
+            if let Err(e) = helpers::fs::create_dir_all(&dest) {
                 return Err(format!(
                     "Fail to create dest folder {}. Error: {}",
                     dest.to_string_lossy(),
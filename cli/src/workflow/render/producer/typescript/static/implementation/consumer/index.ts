@@ -8,14 +8,19 @@ export { Filter } from "./filter";
 export class Consumer {
 	private readonly _options: Options;
 	private readonly _identification: Identification;
-	private readonly _buffer: Protocol.BufferReaderMessages =
-		new Protocol.BufferReaderMessages();
+	private readonly _buffer: Protocol.BufferReaderMessages;
 	private _logger: Logger;
 	private _hash: boolean = false;
 
-	constructor(uuid: string, options: Options, logger: Logger) {
+	constructor(
+		uuid: string,
+		options: Options,
+		logger: Logger,
+		limits: Protocol.ILimits
+	) {
 		this._logger = logger.clone(`[${uuid}][Consumer]`);
 		this._options = options;
+		this._buffer = new Protocol.BufferReaderMessages(limits);
 		this._identification = new Identification(
 			uuid,
 			options.producerIndentificationStrategy,
@@ -41,9 +46,10 @@ export class Consumer {
 		return this._buffer.next();
 	}
 
-	public acceptHash() {
+	public acceptHash(version: number) {
 		this._hash = true;
-		this._logger.debug(`hash has been accepted`);
+		this._identification.setVersion(version);
+		this._logger.debug(`hash has been accepted; version: ${version}`);
 	}
 
 	public isHashAccepted(): boolean {
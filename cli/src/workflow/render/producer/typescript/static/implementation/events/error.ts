@@ -7,6 +7,7 @@ export enum ProducerErrorType {
 	Disconnection = "Disconnection",
 	ProtocolHash = "ProtocolHash",
 	WorkflowHash = "WorkflowHash",
+	Capabilities = "Capabilities",
 	HashError = "HashError",
 	KeyError = "KeyError",
 	AssignedKeyError = "AssignedKeyError",
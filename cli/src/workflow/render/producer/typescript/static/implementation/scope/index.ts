@@ -1,6 +1,7 @@
 import { Filter, Identification } from "../consumer";
 import { Producer } from "../index";
 import { Context } from "../../context";
+import * as Protocol from "../protocol";
 
 type Handler = () => void;
 
@@ -9,6 +10,8 @@ export class Scope {
 	public readonly filter: Filter;
 	public readonly context: Context;
 	public readonly producer: Producer;
+	// Unix time (ms) of package, which is processed; undefined if scope isn't created for package
+	public readonly ts: bigint | undefined;
 
 	private _deferred: Handler | undefined;
 
@@ -16,12 +19,27 @@ export class Scope {
 		consumer: Identification,
 		filter: Filter,
 		context: Context,
-		producer: Producer
+		producer: Producer,
+		ts?: bigint
 	) {
 		this.consumer = consumer;
 		this.filter = filter;
 		this.context = context;
 		this.producer = producer;
+		this.ts = ts;
+	}
+
+	// Milliseconds since package was sent by consumer. Clock skew is taken into account, when
+	// it's estimated (after agreement on version 2 of header); estimation includes latency
+	public latency(): bigint | undefined {
+		if (this.ts === undefined) {
+			return undefined;
+		}
+		const skew: Protocol.IClockSkew | undefined =
+			Protocol.PackingCapabilities.clock(this.consumer.uuid());
+		return skew === undefined
+			? Protocol.MessageHeader.now() - this.ts
+			: Protocol.ClockSkew.age(skew, this.ts);
 	}
 
 	public deferred(cb: Handler) {
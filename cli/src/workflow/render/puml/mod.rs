@@ -3,7 +3,8 @@ pub mod render_event;
 pub mod render_request;
 
 use super::{
-    helpers, helpers::render as tools, workflow, workflow::store::Store as WorkflowStore, Protocol,
+    helpers, helpers::render as tools, helpers::template::Template, workflow,
+    workflow::store::Store as WorkflowStore, Protocol,
 };
 use render_beacon::RenderBeacons;
 use render_event::RenderEvent;
@@ -57,7 +58,9 @@ impl PumlRender {
             output,
             tools::inject_tabs(1, RenderBeacons::new().render(&store.beacons)?),
         );
-        output = templates::MODULE.replace("[[content]]", &output);
+        output = Template::new("puml/module", templates::MODULE)
+            .set("content", &output)
+            .render();
         helpers::fs::write(dest.to_path_buf(), output, true)
     }
 }
@@ -1,10 +1,11 @@
-use super::{helpers::render as tools, workflow::beacon::Broadcast};
+use super::{helpers::render as tools, helpers::template::Template, workflow::beacon::Broadcast};
 
 mod templates {
     pub const MODULE: &str = r#"group Beacons
     Consumer -> Producer: Connected Event
     Consumer -> Producer: Disconnected Event[[broadcasts]]
 end"#;
+    pub const BROADCAST: &str = r#"Consumer --> Producer: [[reference]]"#;
 }
 
 pub struct RenderBeacons {}
@@ -21,18 +22,24 @@ impl RenderBeacons {
     }
 
     pub fn render(&self, beacons: &[Broadcast]) -> Result<String, String> {
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace(
-            "[[broadcasts]]",
-            &tools::inject_tabs(1, self.get_beacons(beacons)?),
-        );
-        Ok(output)
+        Ok(Template::new("puml/beacon/module", templates::MODULE)
+            .set(
+                "broadcasts",
+                &tools::inject_tabs(1, self.get_beacons(beacons)?),
+            )
+            .render())
     }
 
     fn get_beacons(&self, beacons: &[Broadcast]) -> Result<String, String> {
         let mut output: String = String::new();
         for broadcast in beacons {
-            output = format!("{}\nConsumer --> Producer: {}", output, broadcast.reference,);
+            output = format!(
+                "{}\n{}",
+                output,
+                Template::new("puml/beacon/broadcast", templates::BROADCAST)
+                    .set("reference", &broadcast.reference)
+                    .render()
+            );
         }
         Ok(output)
     }
@@ -1,9 +1,10 @@
-use super::{helpers::render as tools, workflow::event::Event};
+use super::{helpers::render as tools, helpers::template::Template, workflow::event::Event};
 
 mod templates {
     pub const MODULE: &str = r#"group [[name]]
     Producer -> Producer: [[reference]][[broadcasts]]
 end"#;
+    pub const BROADCAST: &str = r#"Producer --> Consumer: [[reference]]"#;
 }
 
 pub struct RenderEvent {}
@@ -20,20 +21,26 @@ impl RenderEvent {
     }
 
     pub fn render(&self, event: &Event) -> Result<String, String> {
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace("[[name]]", &event.get_reference()?);
-        output = output.replace("[[reference]]", &event.get_reference()?);
-        output = output.replace(
-            "[[broadcasts]]",
-            &tools::inject_tabs(1, self.get_broadcasts(event)?),
-        );
-        Ok(output)
+        Ok(Template::new("puml/event/module", templates::MODULE)
+            .set("name", &event.get_reference()?)
+            .set("reference", &event.get_reference()?)
+            .set(
+                "broadcasts",
+                &tools::inject_tabs(1, self.get_broadcasts(event)?),
+            )
+            .render())
     }
 
     fn get_broadcasts(&self, event: &Event) -> Result<String, String> {
         let mut output: String = String::new();
         for broadcast in &event.broadcasts {
-            output = format!("{}\nProducer --> Consumer: {}", output, broadcast.reference,);
+            output = format!(
+                "{}\n{}",
+                output,
+                Template::new("puml/event/broadcast", templates::BROADCAST)
+                    .set("reference", &broadcast.reference)
+                    .render()
+            );
         }
         Ok(output)
     }
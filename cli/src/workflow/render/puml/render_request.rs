@@ -1,10 +1,16 @@
-use super::{helpers::render as tools, workflow::request::Request};
+use super::{helpers::render as tools, helpers::template::Template, workflow::request::Request};
 
 mod templates {
     pub const MODULE: &str = r#"group [[name]]
     Consumer -> Producer: [[request]]
     Producer -->x Consumer: <font color=red>[[error]][[conclusions]]
 end"#;
+    pub const CONCLUSION: &str = r#"
+== [[name]] ==
+    Producer -> Consumer: [[response]][[broadcasts]]"#;
+    pub const BROADCAST: &str = r#"Producer [[arrow]] Consumers: [[reference]]"#;
+    pub const RESPONSE: &str = r#"
+Producer -> Consumer: [[response]]"#;
 }
 
 pub struct RenderRequest {}
@@ -21,15 +27,15 @@ impl RenderRequest {
     }
 
     pub fn render(&self, request: &Request) -> Result<String, String> {
-        let mut output: String = templates::MODULE.to_owned();
-        output = output.replace("[[name]]", &request.get_request()?.replace(".", ""));
-        output = output.replace("[[request]]", &request.get_request()?);
-        output = output.replace("[[error]]", &request.get_err()?);
-        output = output.replace(
-            "[[conclusions]]",
-            &tools::inject_tabs(1, self.get_conclusions(request)?),
-        );
-        Ok(output)
+        Ok(Template::new("puml/request/module", templates::MODULE)
+            .set("name", &request.get_request()?.replace(".", ""))
+            .set("request", &request.get_request()?)
+            .set("error", &request.get_err()?)
+            .set(
+                "conclusions",
+                &tools::inject_tabs(1, self.get_conclusions(request)?),
+            )
+            .render())
     }
 
     fn get_conclusions(&self, request: &Request) -> Result<String, String> {
@@ -49,24 +55,28 @@ impl RenderRequest {
                 let mut broadcasts: String = String::new();
                 for broadcast in &action.broadcast {
                     broadcasts = format!(
-                        "{}\nProducer {} Consumers: {}",
+                        "{}\n{}",
                         broadcasts,
-                        if broadcast.optional { "-->" } else { "->" },
-                        broadcast.reference,
+                        Template::new("puml/request/broadcast", templates::BROADCAST)
+                            .set("arrow", if broadcast.optional { "-->" } else { "->" })
+                            .set("reference", &broadcast.reference)
+                            .render()
                     );
                 }
                 output = format!(
-                    r#"{}
-== {} ==
-    Producer -> Consumer: {}{}"#,
+                    "{}{}",
                     output,
-                    name,
-                    reference,
-                    tools::inject_tabs(1, broadcasts),
+                    Template::new("puml/request/conclusion", templates::CONCLUSION)
+                        .set("name", name)
+                        .set("response", reference)
+                        .set("broadcasts", &tools::inject_tabs(1, broadcasts))
+                        .render()
                 );
             }
         } else {
-            output = format!("\nProducer -> Consumer: {}", request.get_response()?);
+            output = Template::new("puml/request/response", templates::RESPONSE)
+                .set("response", &request.get_response()?)
+                .render();
         }
         Ok(output)
     }
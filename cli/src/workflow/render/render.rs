@@ -1,13 +1,16 @@
 pub mod consumer;
+pub mod crates;
+pub mod external;
 pub mod producer;
 pub mod puml;
 
 use super::{
-    helpers,
+    helpers, protocol,
     protocol::store::Store as Protocol,
     render::{
-        rust::RustRender as ProtocolRustRender,
-        typescript::TypescriptRender as ProtocolTypescriptRender, Render as ProtocolRender, Target,
+        rust::{self as protocol_rust, RustRender as ProtocolRustRender},
+        typescript::TypescriptRender as ProtocolTypescriptRender,
+        Render as ProtocolRender, Target,
     },
     workflow,
     workflow::store::Store as WorkflowStore,
@@ -16,15 +19,13 @@ use consumer::{
     rust::RustRender as ConsumerRustRender,
     typescript::TypescriptRender as ConsumerTypescriptRender,
 };
+use external::{sides, ExternalRender};
 use producer::{
     rust::RustRender as ProducerRustRender,
     typescript::TypescriptRender as ProducerTypescriptRender,
 };
 
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 pub trait ImplementationRender<T: ProtocolRender> {
     fn new() -> Self;
@@ -42,6 +43,8 @@ pub fn render(
     mut producer_dest: Option<PathBuf>,
     store: WorkflowStore,
     protocol: &mut Protocol,
+    signature: u16,
+    crate_name: Option<&str>,
 ) -> Result<(), String> {
     let consumer_outs = &(store.get_config()?.consumer);
     let producer_outs = &(store.get_config()?.producer);
@@ -61,11 +64,11 @@ pub fn render(
                         dest = dest.join("rust");
                         mkdir(&dest)?;
                     }
-                    (ConsumerRustRender::new()).render(
+                    ConsumerRustRender::new().with_crate(crate_name).render(
                         &dest,
                         &store,
                         protocol,
-                        ProtocolRustRender::new(true, 0),
+                        ProtocolRustRender::new(true, signature),
                     )?;
                 }
                 Target::TypeScript => {
@@ -77,9 +80,16 @@ pub fn render(
                         &dest,
                         &store,
                         protocol,
-                        ProtocolTypescriptRender::new(true, 0),
+                        ProtocolTypescriptRender::new(true, signature),
                     )?;
                 }
+                Target::External(name) => {
+                    if consumer_outs.len() > 1 {
+                        dest = dest.join(name);
+                        mkdir(&dest)?;
+                    }
+                    ExternalRender::new(name).render(&dest, sides::CONSUMER, &store, protocol)?;
+                }
             }
         }
     }
@@ -93,11 +103,11 @@ pub fn render(
                         dest = dest.join("rust");
                         mkdir(&dest)?;
                     }
-                    (ProducerRustRender::new()).render(
+                    ProducerRustRender::new().with_crate(crate_name).render(
                         &dest,
                         &store,
                         protocol,
-                        ProtocolRustRender::new(true, 0),
+                        ProtocolRustRender::new(true, signature),
                     )?;
                 }
                 Target::TypeScript => {
@@ -109,18 +119,26 @@ pub fn render(
                         &dest,
                         &store,
                         protocol,
-                        ProtocolTypescriptRender::new(true, 0),
+                        ProtocolTypescriptRender::new(true, signature),
                     )?;
                 }
+                Target::External(name) => {
+                    if producer_outs.len() > 1 {
+                        dest = dest.join(name);
+                        mkdir(&dest)?;
+                    }
+                    ExternalRender::new(name).render(&dest, sides::PRODUCER, &store, protocol)?;
+                }
             }
         }
     }
+    helpers::stubs::report_orphans();
     Ok(())
 }
 
 fn mkdir(dest: &Path) -> Result<(), String> {
     if !dest.exists() {
-        if let Err(e) = fs::create_dir(&dest) {
+        if let Err(e) = helpers::fs::create_dir_all(dest) {
             return Err(format!(
                 "Fail to create dest folder {}. Error: {}",
                 dest.to_string_lossy(),
@@ -1,4 +1,4 @@
-use super::{Beacons, Broadcast, Config, Event, Request};
+use super::{hash, stop, Beacons, Broadcast, Config, Event, Request};
 
 #[derive(Debug, Clone)]
 pub struct Store {
@@ -6,7 +6,10 @@ pub struct Store {
     pub requests: Vec<Request>,
     pub beacons: Vec<Broadcast>,
     pub config: Option<Config>,
+    // Hash of source file
     hash: String,
+    // Hashes (protocol, workflow) of previous versions, which are declared as wire-compatible
+    compatible: Vec<(String, String)>,
 }
 
 impl Store {
@@ -17,13 +20,96 @@ impl Store {
             beacons: vec![],
             config: None,
             hash,
+            compatible: vec![],
         }
     }
 
+    /// Hash of workflow, which is checked on handshake. It's calculated from parsed keys,
+    /// requests, events and beacons, so formatting, comments and order of declarations don't
+    /// change it. Settings of transport (targets, compression, checksum etc.) aren't included
     pub fn get_hash(&self) -> String {
+        hash::get_from_str(&self.get_canonical())
+            .unwrap_or_else(|e| stop!("Fail to get hash of workflow: {}", e))
+    }
+
+    /// Hash of source file of workflow; it isn't checked and can be used for debugging
+    pub fn get_source_hash(&self) -> String {
         self.hash.clone()
     }
 
+    // One line per declaration; lines are sorted, so order of declarations doesn't matter
+    fn get_canonical(&self) -> String {
+        fn broadcasts(broadcasts: &[Broadcast]) -> String {
+            let mut broadcasts: Vec<String> = broadcasts
+                .iter()
+                .map(|broadcast| {
+                    format!(
+                        "{}{}",
+                        broadcast.reference,
+                        if broadcast.optional { "?" } else { "" }
+                    )
+                })
+                .collect();
+            broadcasts.sort();
+            broadcasts.join(",")
+        }
+        let mut lines: Vec<String> = vec![];
+        for request in self.requests.iter() {
+            let mut actions: Vec<String> = request
+                .actions
+                .iter()
+                .map(|action| {
+                    format!(
+                        "{}>{}({})",
+                        action.conclusion.clone().unwrap_or_default(),
+                        action.response.clone().unwrap_or_default(),
+                        broadcasts(&action.broadcast)
+                    )
+                })
+                .collect();
+            actions.sort();
+            let mut own = request.broadcasts.clone();
+            own.sort();
+            lines.push(format!(
+                "request {}!{} {{{}}} ({})",
+                request.request.clone().unwrap_or_default(),
+                request.error.clone().unwrap_or_default(),
+                actions.join(";"),
+                own.join(",")
+            ));
+        }
+        for event in self.events.iter() {
+            lines.push(format!(
+                "event {} ({})",
+                event.reference.clone().unwrap_or_default(),
+                broadcasts(&event.broadcasts)
+            ));
+        }
+        for beacon in self.beacons.iter() {
+            lines.push(format!("beacon {}", beacon.reference));
+        }
+        lines.sort();
+        if let Some(config) = self.config.as_ref() {
+            lines.push(format!(
+                "keys {} {} {}",
+                config.self_key.clone().unwrap_or_default(),
+                config.self_key_response,
+                config.assigned_key.clone().unwrap_or_default()
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Sets hashes (protocol, workflow) of previous versions, which producer accepts along with
+    /// current one. Position in list + 1 is number of version; current version is 0
+    pub fn set_compatible(&mut self, compatible: Vec<(String, String)>) {
+        self.compatible = compatible;
+    }
+
+    pub fn get_compatible(&self) -> &[(String, String)] {
+        &self.compatible
+    }
+
     pub fn set_config(&mut self, config: Config) -> Result<(), String> {
         if self.config.is_some() {
             Err(String::from("Configuration can be defined only once"))
@@ -131,8 +131,15 @@ namespace :test do
       raise es unless errors.empty?
     end
   
+    desc 'Test Features Of Generated Protocol'
+    task :features do
+      Dir.chdir("#{PATHS::PROTOCOL_TEST}/features") do
+        sh 'bash ./run.sh'
+      end
+    end
+
     desc 'Test'
-    task :all => ['cli:build', 'generate', 'build', 'execute']
+    task :all => ['cli:build', 'generate', 'build', 'execute', 'features']
   
   end
 
@@ -0,0 +1,7 @@
+# A. Struct derives trait, which type of field doesn't have
+
+cd ../../../cli
+cargo build --release
+cd ../tests/protocol/errors
+
+../../../cli/target/release/clibri -s ./a.prot -rs ./a.rs
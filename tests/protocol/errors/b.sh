@@ -0,0 +1,7 @@
+# B. Enum derives trait, which type of variant doesn't have
+
+cd ../../../cli
+cargo build --release
+cd ../tests/protocol/errors
+
+../../../cli/target/release/clibri -s ./b.prot -rs ./b.rs
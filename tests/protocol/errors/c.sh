@@ -0,0 +1,7 @@
+# C. Default derives (c.toml) cannot be applied to type with float
+
+cd ../../../cli
+cargo build --release
+cd ../tests/protocol/errors
+
+../../../cli/target/release/clibri generate --config ./c.toml
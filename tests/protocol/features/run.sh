@@ -0,0 +1,94 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/protocol/features
+
+# Workflow adds dependencies into nearest Cargo.toml, so generated code gets own one
+rm -rf ./generated
+mkdir ./generated
+printf '[package]\nname = "generated"\nversion = "0.0.0"\n' > ./generated/Cargo.toml
+# Typescript consumer looks for nearest package.json
+printf '{ "name": "generated", "version": "0.0.0" }\n' > ./generated/package.json
+
+# $1 - name of variant; $2 - name of protocol in ./prot; $3 - name of workflow in ./prot;
+# other arguments are passed to clibri
+generate() {
+    local name=$1
+    local protocol=$2
+    local workflow=$3
+    shift 3
+    mkdir -p ./generated/$name/consumer ./generated/$name/producer
+    if ! ../../../cli/target/release/clibri generate -s ./prot/$protocol.prot -wf ./prot/$workflow.workflow -cd ./generated/$name/consumer -pd ./generated/$name/producer -o "$@"; then
+        exit 1
+    fi
+}
+
+generate binary protocol binary --unknown-fields
+generate msgpack protocol msgpack --unknown-fields
+generate cbor protocol cbor --unknown-fields
+generate compact protocol compact --unknown-fields
+generate views protocol binary --views
+# Next version of protocol: Err has new field
+generate next_binary next binary --compatible ./prot/protocol.prot
+generate next_msgpack next msgpack
+generate next_cbor next cbor
+generate next_compact next compact
+generate aes_gcm protocol aes_gcm
+generate chacha20_poly1305 protocol chacha20_poly1305
+# Same protocol and workflow with other formatting
+generate reformatted reformatted reformatted
+
+# Typescript variants are generated with same workflows, but with typescript consumer
+# $1 - name of variant; $2 - name of workflow in ./prot
+generate_ts() {
+    local name=$1
+    local workflow=$2
+    mkdir -p ./generated/ts/$name/consumer ./generated/ts/$name/producer
+    sed 's/Consumer: rust;/Consumer: typescript;/' ./prot/$workflow.workflow > ./generated/ts/$name.workflow
+    if ! ../../../cli/target/release/clibri generate -s ./prot/protocol.prot -wf ./generated/ts/$name.workflow -cd ./generated/ts/$name/consumer -pd ./generated/ts/$name/producer -o; then
+        exit 1
+    fi
+}
+
+generate_ts binary binary
+generate_ts msgpack msgpack
+generate_ts cbor cbor
+generate_ts compact compact
+
+# IR keeps hashes of protocol and workflow
+# $1 - name of variant; $2 - name of protocol in ./prot; $3 - name of workflow in ./prot;
+# other arguments are passed to clibri
+ir() {
+    local name=$1
+    local protocol=$2
+    local workflow=$3
+    shift 3
+    mkdir -p ./generated/ir
+    if ! ../../../cli/target/release/clibri ir -s ./prot/$protocol.prot -wf ./prot/$workflow.workflow -out ./generated/ir/$name.json "$@"; then
+        exit 1
+    fi
+}
+
+ir binary protocol binary
+ir msgpack protocol msgpack
+ir next next binary
+ir reformatted reformatted reformatted
+# Previous versions are given as sources and as IR
+ir compatible next binary --compatible ./prot/protocol.prot --compatible ./generated/ir/msgpack.json
+
+# Settings of runtime (compression, checksum, limits) are global
+if ! cargo test -- --test-threads=1; then
+    exit 1
+fi
+
+# Typescript tests check same vectors (./vectors) as rust tests
+cd ./typescript
+if ! npm install; then
+    exit 1
+fi
+if ! npm run build; then
+    exit 1
+fi
+if ! node ./dist/typescript/src/index.js; then
+    exit 1
+fi
+cd ..
@@ -0,0 +1,112 @@
+// Same protocol (./prot/protocol.prot) rendered with different settings of workflow; next_*
+// modules are rendered from newer version of it (./prot/next.prot). Encryption keeps state of
+// session, so *_consumer modules (protocol of consumer) are used as other side of connection.
+// reformatted module is rendered from ./prot/reformatted.prot (same protocol with other formatting).
+// Sources are generated by run.sh
+#[path = "../generated/binary/producer/implementation/protocol/mod.rs"]
+pub mod binary;
+#[path = "../generated/msgpack/producer/implementation/protocol/mod.rs"]
+pub mod msgpack;
+#[path = "../generated/cbor/producer/implementation/protocol/mod.rs"]
+pub mod cbor;
+#[path = "../generated/compact/producer/implementation/protocol/mod.rs"]
+pub mod compact;
+#[path = "../generated/views/producer/implementation/protocol/mod.rs"]
+pub mod views;
+#[path = "../generated/next_binary/producer/implementation/protocol/mod.rs"]
+pub mod next_binary;
+#[path = "../generated/next_msgpack/producer/implementation/protocol/mod.rs"]
+pub mod next_msgpack;
+#[path = "../generated/next_cbor/producer/implementation/protocol/mod.rs"]
+pub mod next_cbor;
+#[path = "../generated/next_compact/producer/implementation/protocol/mod.rs"]
+pub mod next_compact;
+#[path = "../generated/aes_gcm/producer/implementation/protocol/mod.rs"]
+pub mod aes_gcm;
+#[path = "../generated/aes_gcm/consumer/implementation/protocol/mod.rs"]
+pub mod aes_gcm_consumer;
+#[path = "../generated/chacha20_poly1305/producer/implementation/protocol/mod.rs"]
+pub mod chacha20_poly1305;
+#[path = "../generated/chacha20_poly1305/consumer/implementation/protocol/mod.rs"]
+pub mod chacha20_poly1305_consumer;
+#[path = "../generated/reformatted/producer/implementation/protocol/mod.rs"]
+pub mod reformatted;
+
+// Store.Item with all kinds of fields; $protocol is name of module with generated protocol
+#[macro_export]
+macro_rules! item {
+    ($protocol:ident) => {{
+        use $crate::$protocol::{Kind, Point, Store};
+        Store::Item {
+            small: 200,
+            short: 60000,
+            medium: 4_000_000_000,
+            big: u64::MAX,
+            tiny: -100,
+            negative: -30000,
+            offset: i32::MIN,
+            balance: i64::MIN,
+            active: true,
+            title: String::from("Title with \"quotes\" and ünïcödé"),
+            blob: vec![0, 1, 127, 128, 255],
+            deltas: vec![-1, 0, 1, i32::MAX],
+            tags: vec![String::from("a"), String::new(), String::from("c")],
+            path: vec![
+                Point {
+                    x: 1.5,
+                    y: -2.25,
+                    ..Default::default()
+                },
+                Point {
+                    x: -0.0,
+                    y: 1e100,
+                    ..Default::default()
+                },
+            ],
+            kind: Kind::Marked(String::from("marked")),
+            value: Store::Value::names(vec![String::from("x"), String::from("y")]),
+            note: Some(String::from("note")),
+            limit: None,
+            ..Default::default()
+        }
+    }};
+}
+
+// Vectors of ./vectors/<name>.json are checked by rust tests and by typescript tests
+// (./typescript), so both runtimes give same bytes. Bytes are written as hex strings
+pub fn vector(name: &str) -> binary::JsonValue {
+    let path = format!("{}/vectors/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    binary::JsonValue::parse(&content).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+pub fn text(value: &binary::JsonValue) -> &str {
+    match value {
+        binary::JsonValue::Str(text) => text,
+        other => panic!("Expecting string, but got {:?}", other),
+    }
+}
+
+pub fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|pos| u8::from_str_radix(&hex[pos..pos + 2], 16).unwrap())
+        .collect()
+}
+
+// Pairs are written as [["value", "value"], ...]. Numbers are strings, because JS cannot keep
+// i64/u64 as numbers
+pub fn pairs(value: &binary::JsonValue) -> Vec<(&str, &str)> {
+    match value {
+        binary::JsonValue::Array(pairs) => pairs
+            .iter()
+            .map(|pair| match pair {
+                binary::JsonValue::Array(pair) if pair.len() == 2 => {
+                    (text(&pair[0]), text(&pair[1]))
+                }
+                other => panic!("Expecting pair, but got {:?}", other),
+            })
+            .collect(),
+        other => panic!("Expecting array, but got {:?}", other),
+    }
+}
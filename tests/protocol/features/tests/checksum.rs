@@ -0,0 +1,136 @@
+use clibri_protocol_features_test::{binary::*, item, pairs, unhex, vector};
+
+// Header: id (4 bytes), signature (2), sequence (4), ts (8), length and flags (8); checksum (4)
+// goes after it, if flag is set
+const HEADER_LEN: usize = 26;
+const CHECKSUM_LEN: usize = 4;
+
+// Packs with checksum; default setting is restored after
+fn pack_with_checksum<T: PackingStruct + Clone>(msg: &T, sequence: u32) -> Vec<u8> {
+    set_checksum(true);
+    let packed = msg.clone().pack(sequence, None);
+    set_checksum(false);
+    packed.unwrap()
+}
+
+fn read(packed: &Vec<u8>) -> (Vec<IncomeMessage<AvailableMessages>>, Vec<FrameError>) {
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    buffer.chunk(packed, None).unwrap();
+    let mut received = vec![];
+    while let Some(package) = buffer.next() {
+        received.push(package);
+    }
+    (received, buffer.take_errors())
+}
+
+#[test]
+fn crc32c_vectors() {
+    // Check values of CRC-32C (Castagnoli), including ones from RFC 3720: "", "123456789",
+    // [0; 32], [0xFF; 32] and 0..32. Typescript tests use same vectors
+    let vector = vector("checksum");
+    for (bytes, crc) in pairs(vector.get("crc32c")) {
+        assert_eq!(
+            crc32c(&unhex(bytes)),
+            u32::from_str_radix(crc, 16).unwrap(),
+            "{}",
+            bytes
+        );
+    }
+}
+
+#[test]
+fn checksum_in_header() {
+    let item = item!(binary);
+    assert!(!get_checksum());
+    let plain = item.clone().pack(1, None).unwrap();
+    let packed = pack_with_checksum(&item, 1);
+    assert_eq!(packed.len(), plain.len() + CHECKSUM_LEN);
+    assert_eq!(
+        packed[HEADER_LEN..HEADER_LEN + CHECKSUM_LEN],
+        crc32c(&packed[HEADER_LEN + CHECKSUM_LEN..]).to_le_bytes()
+    );
+    let (received, errors) = read(&packed);
+    assert!(errors.is_empty());
+    assert_eq!(
+        received[0].header.flags & MSG_FLAG_CHECKSUM,
+        MSG_FLAG_CHECKSUM
+    );
+    assert_eq!(
+        received[0].header.checksum,
+        Some(crc32c(&packed[HEADER_LEN + CHECKSUM_LEN..]))
+    );
+    assert_eq!(
+        received[0].msg,
+        AvailableMessages::Store(Store::AvailableMessages::Item(item))
+    );
+}
+
+#[test]
+fn mixed_traffic() {
+    let item = item!(binary);
+    let mut packed = pack_with_checksum(&item, 1);
+    packed.extend(item.clone().pack(2, None).unwrap());
+    packed.extend(pack_with_checksum(&item, 3));
+    let (received, errors) = read(&packed);
+    assert!(errors.is_empty());
+    assert_eq!(
+        received
+            .iter()
+            .map(|package| package.header.checksum.is_some())
+            .collect::<Vec<bool>>(),
+        vec![true, false, true]
+    );
+}
+
+#[test]
+fn corrupted_body() {
+    let item = item!(binary);
+    let mut body = pack_with_checksum(&item, 1);
+    let last = body.len() - 1;
+    body[last] ^= 0x01;
+    let mut checksum = pack_with_checksum(&item, 2);
+    checksum[HEADER_LEN] ^= 0x01;
+    let mut packed = [body, checksum].concat();
+    packed.extend(pack_with_checksum(&item, 3));
+    let (received, errors) = read(&packed);
+    assert_eq!(
+        received
+            .iter()
+            .map(|package| package.header.sequence)
+            .collect::<Vec<u32>>(),
+        vec![3]
+    );
+    assert_eq!(errors.len(), 2);
+    for (error, sequence) in errors.iter().zip([1, 2]) {
+        assert_eq!(error.sequence, sequence);
+        assert!(
+            matches!(error.error, ReadError::Checksum(_)),
+            "{:?}",
+            error.error
+        );
+    }
+}
+
+#[test]
+fn compressed_body() {
+    let mut item = item!(binary);
+    item.title = "compressible ".repeat(200);
+    set_compression(&Compression {
+        algorithm: CompressionAlgorithm::Deflate,
+        threshold: 0,
+    });
+    let mut packed = pack_with_checksum(&item, 1);
+    set_compression(&Compression::default());
+    let (received, errors) = read(&packed);
+    assert!(errors.is_empty());
+    assert_ne!(received[0].header.flags & COMPRESSION_FLAGS, 0);
+    assert_eq!(
+        received[0].msg,
+        AvailableMessages::Store(Store::AvailableMessages::Item(item))
+    );
+    // Checksum covers body, which is sent (compressed one)
+    let last = packed.len() - 1;
+    packed[last] ^= 0x01;
+    let (_, errors) = read(&packed);
+    assert!(matches!(errors[0].error, ReadError::Checksum(_)));
+}
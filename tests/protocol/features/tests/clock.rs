@@ -0,0 +1,100 @@
+use clibri_protocol_features_test::{binary::*, item};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn local() -> Capabilities {
+    Capabilities::local(CODEC, &Limits::default())
+}
+
+fn read(packed: &Vec<u8>) -> PackageHeader {
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    buffer.chunk(packed, None).unwrap();
+    assert!(buffer.take_errors().is_empty());
+    buffer.next().unwrap().header
+}
+
+#[test]
+fn now_is_in_milliseconds() {
+    let before = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let mut last = now_ms();
+    assert!(last >= before && last - before < 1000);
+    for _ in 0..1000 {
+        let now = now_ms();
+        assert!(now >= last);
+        last = now;
+    }
+}
+
+#[test]
+fn timestamp_of_header() {
+    // Until agreement peer might be older runtime, which has timestamp in seconds
+    let before = now_ms();
+    let header = read(&item!(binary).pack(1, None).unwrap());
+    assert_eq!(header.version, 0);
+    assert!(header.ts >= before / 1000 && header.ts <= now_ms() / 1000);
+    assert_eq!(header.ts_ms(), header.ts * 1000);
+    let uuid = "timestamp";
+    set_agreement(uuid, local().negotiate(&local()).unwrap());
+    let packed = item!(binary).pack(2, Some(uuid.to_owned()));
+    remove_agreement(uuid);
+    let header = read(&packed.unwrap());
+    assert_eq!(header.version, MSG_VERSION_TS_MS);
+    assert!(header.ts >= before && header.ts <= now_ms());
+    assert_eq!(header.ts_ms(), header.ts);
+}
+
+#[test]
+fn clock_is_observed_once() {
+    let uuid = "observed";
+    let legacy = read(&item!(binary).pack(1, None).unwrap());
+    let mut header = legacy.clone();
+    header.version = MSG_VERSION_TS_MS;
+    header.ts = now_ms() + 60_000;
+    // Without agreement there is nothing to update
+    observe_clock(uuid, &header);
+    assert_eq!(get_clock_skew(uuid), None);
+    set_agreement(uuid, local().negotiate(&local()).unwrap());
+    // Timestamp in seconds isn't precise enough
+    observe_clock(uuid, &legacy);
+    assert_eq!(get_clock_skew(uuid), None);
+    observe_clock(uuid, &header);
+    let skew = get_clock_skew(uuid).unwrap();
+    assert_eq!(skew.rtt, None);
+    assert!(skew.offset > 59_000 && skew.offset <= 60_000);
+    // Estimation isn't replaced by later packages
+    header.ts = now_ms();
+    observe_clock(uuid, &header);
+    assert_eq!(get_clock_skew(uuid), Some(skew));
+    remove_agreement(uuid);
+    assert_eq!(get_clock_skew(uuid), None);
+}
+
+#[test]
+fn skew_estimation() {
+    // Request is sent at 1000, response is received at 1200; peer's clock is 4000 ms ahead
+    let skew = ClockSkew::estimate(1000, 5100, 1200);
+    assert_eq!(
+        skew,
+        ClockSkew {
+            offset: 4000,
+            rtt: Some(200),
+        }
+    );
+    assert_eq!(skew.to_local(5100), 1100);
+    assert_eq!(skew.to_local(1000), 0);
+    // Clock of peer is behind own clock
+    assert_eq!(ClockSkew::estimate(1000, 100, 1200).offset, -1000);
+    // Broken round trip isn't negative
+    assert_eq!(ClockSkew::estimate(1200, 1100, 1000).rtt, Some(0));
+    let skew = ClockSkew {
+        offset: 1000,
+        rtt: None,
+    };
+    let age = skew.age(now_ms() + 500);
+    assert!((500..1000).contains(&age), "{}", age);
+    let skew = ClockSkew::one_way(now_ms());
+    assert!(skew.offset <= 0 && skew.offset > -1000);
+    assert_eq!(skew.rtt, None);
+}
@@ -0,0 +1,156 @@
+use clibri_protocol_features_test::{binary, cbor, compact, item, msgpack, text, unhex, vector};
+
+// Packs Store.Item, reads it with Buffer and checks, that it's the same
+macro_rules! round_trip {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let item = item!($protocol);
+        let packed = item.clone().pack(1, None).unwrap();
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        buffer.chunk(&packed, None).unwrap();
+        match buffer.next().map(|msg| msg.msg) {
+            Some(AvailableMessages::Store(Store::AvailableMessages::Item(received))) => {
+                assert_eq!(received, item)
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(buffer.take_errors().is_empty());
+    }};
+}
+
+// Encodes and decodes each variant of Store.Value
+macro_rules! enum_round_trip {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        for value in [
+            Store::Value::number(u64::MAX),
+            Store::Value::signed(i64::MIN),
+            Store::Value::text(String::from("text")),
+            Store::Value::point(Point {
+                x: 0.5,
+                y: 0.25,
+                ..Default::default()
+            }),
+            Store::Value::names(vec![String::from("a"), String::new()]),
+            Store::Value::Defaults,
+        ] {
+            let body = EnumEncode::abduct(&mut value.clone()).unwrap();
+            assert_eq!(<Store::Value as EnumDecode>::extract(body), Ok(value));
+        }
+    }};
+}
+
+// Body is cut; decoding should fail instead of panic
+macro_rules! truncated {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let body = StructEncode::abduct(&mut item!($protocol)).unwrap();
+        for len in 0..body.len() {
+            assert!(
+                <Store::Item as StructDecode>::extract(body[..len].to_vec()).is_err(),
+                "{}: body cut to {} bytes is decoded",
+                CODEC,
+                len
+            );
+        }
+    }};
+}
+
+#[test]
+fn codec_names() {
+    assert_eq!(binary::CODEC, "binary");
+    assert_eq!(msgpack::CODEC, "msgpack");
+    assert_eq!(cbor::CODEC, "cbor");
+}
+
+#[test]
+fn packages_round_trip() {
+    round_trip!(binary);
+    round_trip!(msgpack);
+    round_trip!(cbor);
+}
+
+#[test]
+fn enums_round_trip() {
+    enum_round_trip!(msgpack);
+    enum_round_trip!(cbor);
+}
+
+#[test]
+fn wire_format() {
+    use cbor::StructEncode as _;
+    use msgpack::StructEncode as _;
+    // Struct is a map with names of fields as keys
+    assert_eq!(msgpack::Store::Empty::default().abduct(), Ok(vec![0x80]));
+    assert_eq!(cbor::Store::Empty::default().abduct(), Ok(vec![0xa0]));
+    assert_eq!(
+        msgpack::Point {
+            x: 1.0,
+            y: 2.0,
+            ..Default::default()
+        }
+        .abduct(),
+        Ok(vec![
+            0x82, 0xa1, b'x', 0xca, 0x3f, 0x80, 0x00, 0x00, 0xa1, b'y', 0xcb, 0x40, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00
+        ])
+    );
+    assert_eq!(
+        cbor::Point {
+            x: 1.0,
+            y: 2.0,
+            ..Default::default()
+        }
+        .abduct(),
+        Ok(vec![
+            0xa2, 0x61, b'x', 0xfa, 0x3f, 0x80, 0x00, 0x00, 0x61, b'y', 0xfb, 0x40, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00
+        ])
+    );
+}
+
+// Same vectors are checked by typescript tests
+macro_rules! shared_vector {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let vector = vector("codecs");
+        let item = item!($protocol);
+        assert_eq!(
+            Store::Item::from_json(&vector.get("item").stringify()),
+            Ok(item.clone())
+        );
+        let body = unhex(text(vector.get("bodies").get(CODEC)));
+        assert_eq!(StructEncode::abduct(&mut item.clone()), Ok(body.clone()));
+        assert_eq!(<Store::Item as StructDecode>::extract(body), Ok(item));
+    }};
+}
+
+#[test]
+fn shared_vectors() {
+    shared_vector!(binary);
+    shared_vector!(msgpack);
+    shared_vector!(cbor);
+    shared_vector!(compact);
+}
+
+#[test]
+fn truncated_bodies() {
+    truncated!(msgpack);
+    truncated!(cbor);
+}
+
+#[test]
+fn foreign_bodies() {
+    // Body of one codec isn't accepted by another one
+    let body = msgpack::StructEncode::abduct(&mut item!(msgpack)).unwrap();
+    assert!(<cbor::Store::Item as cbor::StructDecode>::extract(body).is_err());
+    let body = cbor::StructEncode::abduct(&mut item!(cbor)).unwrap();
+    assert!(<msgpack::Store::Item as msgpack::StructDecode>::extract(body).is_err());
+    // Field of other type
+    let body = msgpack::StructEncode::abduct(&mut msgpack::Key {
+        uuid: String::from("uuid"),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(<msgpack::Point as msgpack::StructDecode>::extract(body).is_err());
+}
@@ -0,0 +1,142 @@
+use clibri_protocol_features_test::{binary, compact::*, item, pairs, unhex, vector};
+
+#[test]
+fn packages_round_trip() {
+    let item = item!(compact);
+    let packed = item.clone().pack(1, None).unwrap();
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    buffer.chunk(&packed, None).unwrap();
+    match buffer.next().map(|msg| msg.msg) {
+        Some(AvailableMessages::Store(Store::AvailableMessages::Item(received))) => {
+            assert_eq!(received, item)
+        }
+        other => panic!("Unexpected message: {:?}", other),
+    }
+    for value in [
+        Store::Value::number(u64::MAX),
+        Store::Value::signed(i64::MIN),
+        Store::Value::text(String::from("text")),
+        Store::Value::names(vec![]),
+        Store::Value::Defaults,
+    ] {
+        let body = EnumEncode::abduct(&mut value.clone()).unwrap();
+        assert_eq!(<Store::Value as EnumDecode>::extract(body), Ok(value));
+    }
+}
+
+#[test]
+fn varint() {
+    for (value, bytes) in [
+        (0u64, vec![0x00]),
+        (1, vec![0x01]),
+        (127, vec![0x7f]),
+        (128, vec![0x80, 0x01]),
+        (300, vec![0xac, 0x02]),
+        (16_383, vec![0xff, 0x7f]),
+        (16_384, vec![0x80, 0x80, 0x01]),
+        (
+            u64::MAX,
+            vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+    ] {
+        let mut buf: Vec<u8> = vec![];
+        compact_write_varint(value, &mut buf);
+        assert_eq!(buf, bytes, "{}", value);
+        assert_eq!(compact_varint_len(value), bytes.len(), "{}", value);
+        let mut pos = 0;
+        assert_eq!(compact_read_varint(&bytes, &mut pos), Ok(value));
+        assert_eq!(pos, bytes.len());
+    }
+}
+
+#[test]
+fn zigzag() {
+    for (value, encoded) in [
+        (0i64, 0u64),
+        (-1, 1),
+        (1, 2),
+        (-2, 3),
+        (i64::MAX, u64::MAX - 1),
+        (i64::MIN, u64::MAX),
+    ] {
+        assert_eq!(compact_zigzag(value), encoded);
+        assert_eq!(compact_unzigzag(encoded), value);
+    }
+    // Small negative numbers take one byte
+    assert_eq!(compact_body(&-2i64), vec![0x03]);
+    assert_eq!(compact_body(&300u16), vec![0xac, 0x02]);
+}
+
+// Same vectors are checked by typescript tests
+#[test]
+fn shared_vectors() {
+    let vector = vector("compact");
+    for (value, hex) in pairs(vector.get("varint")) {
+        let value: u64 = value.parse().unwrap();
+        let bytes = unhex(hex);
+        let mut buf: Vec<u8> = vec![];
+        compact_write_varint(value, &mut buf);
+        assert_eq!(buf, bytes, "{}", value);
+        assert_eq!(compact_read_varint(&bytes, &mut 0), Ok(value));
+    }
+    for (value, encoded) in pairs(vector.get("zigzag")) {
+        let value: i64 = value.parse().unwrap();
+        let encoded: u64 = encoded.parse().unwrap();
+        assert_eq!(compact_zigzag(value), encoded);
+        assert_eq!(compact_unzigzag(encoded), value);
+    }
+}
+
+#[test]
+fn smaller_than_binary() {
+    let mut small = item!(compact);
+    small.medium = 1;
+    small.big = 2;
+    small.offset = -3;
+    small.balance = 4;
+    let compact = StructEncode::abduct(&mut small).unwrap();
+    let mut small = item!(binary);
+    small.medium = 1;
+    small.big = 2;
+    small.offset = -3;
+    small.balance = 4;
+    let binary = binary::StructEncode::abduct(&mut small).unwrap();
+    assert!(
+        compact.len() < binary.len(),
+        "compact: {} bytes; binary: {} bytes",
+        compact.len(),
+        binary.len()
+    );
+}
+
+#[test]
+fn invalid_varint() {
+    // More than 64 bits
+    assert!(compact_read_varint(&[0xff; 11], &mut 0).is_err());
+    // Last byte has continuation bit
+    assert!(compact_read_varint(&[0x80], &mut 0).is_err());
+    assert!(compact_read_varint(&[], &mut 0).is_err());
+    // Value doesn't fit into type
+    assert!(<u16 as CompactValue>::compact_read(&[0x80, 0x80, 0x04]).is_err());
+    assert!(<u32 as CompactValue>::compact_read(&[0x80, 0x80, 0x80, 0x80, 0x10]).is_err());
+    assert!(<i16 as CompactValue>::compact_read(&[0xff, 0xff, 0x07]).is_err());
+    // Bytes after value
+    assert!(<u8 as CompactValue>::compact_read(&[0x01, 0x02]).is_err());
+}
+
+#[test]
+fn truncated_bodies() {
+    let item = item!(compact);
+    let body = StructEncode::abduct(&mut item.clone()).unwrap();
+    // Fields are tagged, so body cut on boundary of optional field is still valid, but it
+    // cannot be decoded as original message
+    for len in 0..body.len() {
+        assert_ne!(
+            <Store::Item as StructDecode>::extract(body[..len].to_vec()).ok(),
+            Some(item.clone()),
+            "body cut to {} bytes",
+            len
+        );
+    }
+    assert!(<Store::Item as StructDecode>::extract(body[..body.len() - 1].to_vec()).is_err());
+}
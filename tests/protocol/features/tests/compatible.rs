@@ -0,0 +1,49 @@
+use clibri_protocol_features_test::{binary, msgpack, next_binary};
+use std::fs;
+
+// Value of "key": "value" lines of file (generated by run.sh) in order of appearance
+fn values(path: &str, key: &str) -> Vec<String> {
+    let prefix = format!("\"{}\":", key);
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with(&prefix) {
+                line.split('"').nth(3).map(String::from)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn ir_has_compatible_versions() {
+    let protocols = values("./generated/ir/compatible.json", "protocol");
+    let workflows = values("./generated/ir/compatible.json", "workflow");
+    // Version given as sources and version given as IR
+    assert_eq!(protocols, vec![binary::hash(), msgpack::hash()]);
+    let workflow = values("./generated/ir/binary.json", "hash")[1].clone();
+    assert_eq!(workflows, vec![workflow.clone(), workflow]);
+    assert_ne!(next_binary::hash(), binary::hash());
+}
+
+#[test]
+fn producer_accepts_compatible_versions() {
+    let line = |variant: &str| -> String {
+        fs::read_to_string(format!(
+            "./generated/{}/producer/implementation/mod.rs",
+            variant
+        ))
+        .unwrap()
+        .lines()
+        .find(|line| line.contains("const COMPATIBLE"))
+        .unwrap()
+        .to_owned()
+    };
+    let compatible = line("next_binary");
+    assert!(compatible.contains(&binary::hash()));
+    assert!(!compatible.contains(&next_binary::hash()));
+    assert!(line("binary").ends_with("= &[];"));
+}
@@ -0,0 +1,236 @@
+use clibri_protocol_features_test::{binary, cbor, compact, item, msgpack, text, unhex, vector};
+
+// Header: id (4 bytes), signature (2), sequence (4), ts (8), length and flags (8)
+const HEADER_LEN: usize = 26;
+
+// Store.Item, which body is compressed well
+macro_rules! compressible {
+    ($protocol:ident) => {{
+        let mut item = item!($protocol);
+        item.title = "compressible ".repeat(200);
+        item
+    }};
+}
+
+// Runs body with given settings of compression; default settings are restored after
+fn with_compression<T>(compression: binary::Compression, body: impl FnOnce() -> T) -> T {
+    binary::set_compression(&compression);
+    let result = body();
+    binary::set_compression(&Default::default());
+    result
+}
+
+// Packs and reads compressible item with each algorithm; flag of package shows algorithm
+macro_rules! round_trip {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        for (algorithm, flag) in [
+            (CompressionAlgorithm::Deflate, COMPRESSION_FLAG_DEFLATE),
+            (CompressionAlgorithm::Lz4, COMPRESSION_FLAG_LZ4),
+        ] {
+            let item = compressible!($protocol);
+            set_compression(&Compression {
+                algorithm,
+                threshold: 0,
+            });
+            let packed = item.clone().pack(1, None);
+            set_compression(&Compression::default());
+            let packed = packed.unwrap();
+            assert!(packed.len() < StructEncode::encoded_len(&item));
+            let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+            buffer.chunk(&packed, None).unwrap();
+            let package = buffer.next().unwrap();
+            assert_eq!(package.header.flags & COMPRESSION_FLAGS, flag);
+            assert_eq!(
+                package.msg,
+                AvailableMessages::Store(Store::AvailableMessages::Item(item))
+            );
+            assert!(buffer.take_errors().is_empty());
+        }
+    }};
+}
+
+#[test]
+fn packages_round_trip() {
+    round_trip!(binary);
+    round_trip!(msgpack);
+    round_trip!(cbor);
+    round_trip!(compact);
+}
+
+#[test]
+fn algorithms_round_trip() {
+    use binary::*;
+    let mut samples: Vec<Vec<u8>> = vec![
+        vec![],
+        vec![0; 1],
+        vec![7; 100_000],
+        b"abcabcabcabcabcabcabcabcabcabcabcabc".to_vec(),
+    ];
+    // Pseudo-random bytes aren't compressed at all
+    let mut seed: u32 = 1;
+    samples.push(
+        (0..4096)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect(),
+    );
+    for algorithm in [CompressionAlgorithm::Deflate, CompressionAlgorithm::Lz4] {
+        set_compression(&Compression {
+            algorithm,
+            threshold: 0,
+        });
+        for sample in samples.iter() {
+            let (flag, body) = compress(sample.clone(), COMPRESSION_FLAGS);
+            if flag == 0 {
+                assert_eq!(&body, sample);
+            } else {
+                assert!(body.len() < sample.len());
+            }
+            assert_eq!(&decompress(flag, body).unwrap(), sample);
+        }
+    }
+    set_compression(&Compression::default());
+}
+
+// Typescript runtime gives same compressed bodies (../vectors/compression.json); "foreign" body
+// is written by zlib with dynamic Huffman codes, which aren't used by own compressor
+#[test]
+fn shared_vectors() {
+    use binary::*;
+    let vector = vector("compression");
+    let body = unhex(text(vector.get("body")));
+    for (algorithm, flag, name) in [
+        (
+            CompressionAlgorithm::Deflate,
+            COMPRESSION_FLAG_DEFLATE,
+            "deflate",
+        ),
+        (CompressionAlgorithm::Lz4, COMPRESSION_FLAG_LZ4, "lz4"),
+    ] {
+        let compressed = unhex(text(vector.get("compressed").get(name)));
+        let (used, result) = with_compression(
+            Compression {
+                algorithm,
+                threshold: 0,
+            },
+            || compress(body.clone(), COMPRESSION_FLAGS),
+        );
+        assert_eq!(used, flag);
+        assert_eq!(result, compressed);
+        assert_eq!(decompress(flag, compressed), Ok(body.clone()));
+    }
+    let foreign = unhex(text(vector.get("foreign").get("deflate")));
+    assert_eq!(decompress(COMPRESSION_FLAG_DEFLATE, foreign), Ok(body));
+}
+
+#[test]
+fn threshold() {
+    use binary::*;
+    let item = compressible!(binary);
+    let len = StructEncode::encoded_len(&item);
+    for (threshold, compressed) in [(len, true), (len + 1, false)] {
+        let packed = with_compression(
+            Compression {
+                algorithm: CompressionAlgorithm::Lz4,
+                threshold,
+            },
+            || item.clone().pack(1, None).unwrap(),
+        );
+        assert_eq!(packed.len() < len, compressed);
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        buffer.chunk(&packed, None).unwrap();
+        let package = buffer.next().unwrap();
+        assert_eq!(package.header.flags & COMPRESSION_FLAGS != 0, compressed);
+    }
+    assert_eq!(get_compression(), Compression::default());
+}
+
+#[test]
+fn mixed_traffic() {
+    use binary::*;
+    let item = compressible!(binary);
+    let mut packed = item.clone().pack(1, None).unwrap();
+    for (sequence, algorithm) in [
+        (2, CompressionAlgorithm::Deflate),
+        (3, CompressionAlgorithm::Lz4),
+    ] {
+        packed.extend(with_compression(
+            Compression {
+                algorithm,
+                threshold: 0,
+            },
+            || item.clone().pack(sequence, None).unwrap(),
+        ));
+    }
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    buffer.chunk(&packed, None).unwrap();
+    let mut flags = vec![];
+    while let Some(package) = buffer.next() {
+        assert_eq!(
+            package.msg,
+            AvailableMessages::Store(Store::AvailableMessages::Item(item.clone()))
+        );
+        flags.push(package.header.flags & COMPRESSION_FLAGS);
+    }
+    assert_eq!(
+        flags,
+        vec![0, COMPRESSION_FLAG_DEFLATE, COMPRESSION_FLAG_LZ4]
+    );
+}
+
+#[test]
+fn corrupted_body() {
+    use binary::*;
+    let item = compressible!(binary);
+    for algorithm in [CompressionAlgorithm::Deflate, CompressionAlgorithm::Lz4] {
+        let mut packed = with_compression(
+            Compression {
+                algorithm,
+                threshold: 0,
+            },
+            || item.clone().pack(1, None).unwrap(),
+        );
+        // Declared size of decompressed body doesn't match data
+        packed[HEADER_LEN] ^= 0xFF;
+        packed.extend(Store::Empty::default().pack(2, None).unwrap());
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        buffer.chunk(&packed, None).unwrap();
+        let errors = buffer.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].sequence, 1);
+        assert_eq!(
+            buffer.next().map(|package| package.header.sequence),
+            Some(2)
+        );
+    }
+}
+
+#[test]
+fn decompressed_size_is_limited() {
+    use binary::*;
+    let item = compressible!(binary);
+    let packed = with_compression(
+        Compression {
+            algorithm: CompressionAlgorithm::Lz4,
+            threshold: 0,
+        },
+        || item.clone().pack(1, None).unwrap(),
+    );
+    let compressed = packed.len() - HEADER_LEN;
+    let mut buffer: Buffer<AvailableMessages> = Buffer::with_limits(Limits {
+        frame: compressed,
+        ..Default::default()
+    });
+    buffer.chunk(&packed, None).unwrap();
+    assert!(buffer.next().is_none());
+    let errors = buffer.take_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        matches!(errors[0].error, ReadError::Limit(_)),
+        "{:?}",
+        errors[0].error
+    );
+}
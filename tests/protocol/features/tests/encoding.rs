@@ -0,0 +1,117 @@
+use bytes::BytesMut;
+use clibri_protocol_features_test::{binary, cbor, compact, item, msgpack};
+
+// Checks, that encoded_len() is exact size of encoded struct and enum
+macro_rules! encoded_len {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let item = item!($protocol);
+        assert_eq!(
+            StructEncode::encoded_len(&item),
+            StructEncode::abduct(&mut item.clone()).unwrap().len()
+        );
+        let empty = Store::Empty::default();
+        assert_eq!(
+            StructEncode::encoded_len(&empty),
+            StructEncode::abduct(&mut empty.clone()).unwrap().len()
+        );
+        for value in [
+            Store::Value::number(u64::MAX),
+            Store::Value::text(String::new()),
+            Store::Value::point(Point::default()),
+            Store::Value::names(vec![String::from("a"), String::from("bc")]),
+        ] {
+            assert_eq!(
+                EnumEncode::encoded_len(&value),
+                EnumEncode::abduct(&mut value.clone()).unwrap().len()
+            );
+        }
+    }};
+}
+
+// Checks, that encode_into() appends to buffer and doesn't touch its content
+macro_rules! encode_into {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let item = item!($protocol);
+        let prefix = vec![0xAA, 0xBB, 0xCC];
+        let mut buf = prefix.clone();
+        StructEncode::encode_into(&item, &mut buf).unwrap();
+        assert_eq!(&buf[..prefix.len()], &prefix[..]);
+        assert_eq!(
+            buf[prefix.len()..].to_vec(),
+            StructEncode::abduct(&mut item.clone()).unwrap()
+        );
+    }};
+}
+
+// Writes two packages into one reused BytesMut and reads both of them
+macro_rules! pack_into {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let item = item!($protocol);
+        let mut buf = BytesMut::with_capacity(16);
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        for sequence in 1..=2 {
+            item.pack_into(sequence, None, &mut buf).unwrap();
+            Store::Empty::default()
+                .pack_into(sequence, None, &mut buf)
+                .unwrap();
+            buffer.chunk(&buf.to_vec(), None).unwrap();
+            buf.clear();
+            match buffer.next() {
+                Some(package) => {
+                    assert_eq!(package.header.sequence, sequence);
+                    assert_eq!(
+                        package.msg,
+                        AvailableMessages::Store(Store::AvailableMessages::Item(item.clone()))
+                    );
+                }
+                None => panic!("Package isn't read"),
+            }
+            match buffer.next().map(|package| package.msg) {
+                Some(AvailableMessages::Store(Store::AvailableMessages::Empty(_))) => {}
+                other => panic!("Unexpected message: {:?}", other),
+            }
+        }
+        assert!(buffer.next().is_none());
+        assert!(buffer.take_errors().is_empty());
+    }};
+}
+
+#[test]
+fn exact_encoded_len() {
+    encoded_len!(binary);
+    encoded_len!(msgpack);
+    encoded_len!(cbor);
+    encoded_len!(compact);
+}
+
+#[test]
+fn encode_into_appends() {
+    encode_into!(binary);
+    encode_into!(msgpack);
+    encode_into!(cbor);
+    encode_into!(compact);
+}
+
+#[test]
+fn pack_into_reused_buffer() {
+    pack_into!(binary);
+    pack_into!(msgpack);
+    pack_into!(cbor);
+    pack_into!(compact);
+}
+
+#[test]
+fn pack_into_matches_pack() {
+    use binary::*;
+    let item = item!(binary);
+    let mut buf = BytesMut::new();
+    item.pack_into(1, None, &mut buf).unwrap();
+    let packed = item.clone().pack(1, None).unwrap();
+    assert_eq!(buf.len(), packed.len());
+    // Timestamp of header (bytes 10..18) can differ
+    assert_eq!(buf[..10], packed[..10]);
+    assert_eq!(buf[18..], packed[18..]);
+}
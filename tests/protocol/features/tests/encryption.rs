@@ -0,0 +1,247 @@
+use clibri_protocol_features_test::{
+    aes_gcm, aes_gcm_consumer, chacha20_poly1305, chacha20_poly1305_consumer, item,
+};
+
+// Header: id (4 bytes), signature (2), sequence (4), ts (8), length and flags (8)
+const HEADER_LEN: usize = 26;
+const SEQUENCE: std::ops::Range<usize> = 6..10;
+
+fn contains(packed: &[u8], text: &str) -> bool {
+    packed.windows(text.len()).any(|w| w == text.as_bytes())
+}
+
+// Handshake as producer and consumer do it: HashRequest with public key of consumer and
+// HashResponse with public key of producer. Returns agreed algorithm
+macro_rules! handshake {
+    ($p:ident, $c:ident, $uuid:expr) => {{
+        use $c::PackingStruct as _;
+        use $p::PackingStruct as _;
+        let uuid: &str = $uuid;
+        let own = $c::Capabilities::local($c::CODEC, &$c::Limits::default());
+        let request = $c::InternalServiceGroup::HashRequest {
+            protocol: $c::hash(),
+            workflow: String::new(),
+            version: Some(own.version),
+            compression: Some(own.compression.clone()),
+            encryption: Some(own.encryption.clone()),
+            codec: Some(own.codec.clone()),
+            frame: Some(own.frame),
+            heartbeat: Some(own.heartbeat),
+            key: $c::encryption_offer(uuid).unwrap(),
+            ..Default::default()
+        }
+        .pack(1, None)
+        .unwrap();
+        let mut producer: $p::Buffer<$p::AvailableMessages> = $p::Buffer::new();
+        producer.chunk(&request, Some(uuid.to_owned())).unwrap();
+        let request = match producer.next().map(|package| package.msg) {
+            Some($p::AvailableMessages::InternalServiceGroup(
+                $p::InternalServiceGroup::AvailableMessages::HashRequest(request),
+            )) => request,
+            other => panic!("Unexpected message: {:?}", other),
+        };
+        let local = $p::Capabilities::local($p::CODEC, &$p::Limits::default());
+        let agreement = local
+            .negotiate(&$p::Capabilities {
+                version: request.version.unwrap(),
+                compression: request.compression.unwrap(),
+                encryption: request.encryption.unwrap(),
+                codec: request.codec.unwrap(),
+                frame: request.frame.unwrap(),
+                heartbeat: request.heartbeat.unwrap(),
+            })
+            .unwrap();
+        let agreed = local.agreed(&agreement);
+        $p::set_agreement(uuid, agreement.clone());
+        let response = $p::InternalServiceGroup::HashResponse {
+            error: None,
+            version: Some(agreed.version),
+            compression: Some(agreed.compression),
+            encryption: Some(agreed.encryption),
+            codec: Some(agreed.codec),
+            frame: Some(agreed.frame),
+            heartbeat: Some(agreed.heartbeat),
+            key: $p::encryption_accept(uuid, &request.key).unwrap(),
+            ..Default::default()
+        }
+        .pack(1, Some(uuid.to_owned()))
+        .unwrap();
+        let mut consumer: $c::Buffer<$c::AvailableMessages> = $c::Buffer::new();
+        consumer.chunk(&response, Some(uuid.to_owned())).unwrap();
+        assert!(consumer.take_errors().is_empty());
+        assert!($c::encryption_ready(uuid));
+        agreement.encryption.unwrap()
+    }};
+}
+
+// Packages are encrypted in both directions and can be read by other side only
+macro_rules! round_trip {
+    ($p:ident, $c:ident, $uuid:expr) => {{
+        use $c::PackingStruct as _;
+        use $p::PackingStruct as _;
+        let uuid: &str = $uuid;
+        handshake!($p, $c, uuid);
+        let item = item!($p);
+        let packed = item.clone().pack(2, Some(uuid.to_owned())).unwrap();
+        assert!(!contains(&packed, &item.title));
+        let mut consumer: $c::Buffer<$c::AvailableMessages> = $c::Buffer::new();
+        consumer.chunk(&packed, Some(uuid.to_owned())).unwrap();
+        assert!(consumer.take_errors().is_empty());
+        match consumer.next().map(|package| package.msg) {
+            Some($c::AvailableMessages::Store($c::Store::AvailableMessages::Item(received))) => {
+                assert_eq!(received, item!($c))
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        let item = item!($c);
+        let packed = item.clone().pack(2, Some(uuid.to_owned())).unwrap();
+        assert!(!contains(&packed, &item.title));
+        let mut producer: $p::Buffer<$p::AvailableMessages> = $p::Buffer::new();
+        producer.chunk(&packed, Some(uuid.to_owned())).unwrap();
+        assert!(producer.take_errors().is_empty());
+        match producer.next().map(|package| package.msg) {
+            Some($p::AvailableMessages::Store($p::Store::AvailableMessages::Item(received))) => {
+                assert_eq!(received, item!($p))
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        $p::encryption_close(uuid);
+        $c::encryption_close(uuid);
+    }};
+}
+
+#[test]
+fn packages_round_trip() {
+    round_trip!(aes_gcm, aes_gcm_consumer, "round-trip-aes-gcm");
+    round_trip!(
+        chacha20_poly1305,
+        chacha20_poly1305_consumer,
+        "round-trip-chacha20-poly1305"
+    );
+}
+
+#[test]
+fn algorithm_of_producer_is_preferred() {
+    let uuid = "preferred";
+    assert_eq!(
+        handshake!(aes_gcm, chacha20_poly1305_consumer, uuid),
+        "aes_gcm"
+    );
+    round_trip!(aes_gcm, chacha20_poly1305_consumer, uuid);
+    assert_eq!(
+        handshake!(chacha20_poly1305, aes_gcm_consumer, uuid),
+        "chacha20_poly1305"
+    );
+    round_trip!(chacha20_poly1305, aes_gcm_consumer, uuid);
+}
+
+#[test]
+fn replay_and_reordering() {
+    use aes_gcm::PackingStruct as _;
+    use aes_gcm_consumer::*;
+    let uuid = "replay";
+    handshake!(aes_gcm, aes_gcm_consumer, uuid);
+    let packed: Vec<Vec<u8>> = (2..5)
+        .map(|sequence| {
+            item!(aes_gcm)
+                .pack(sequence, Some(uuid.to_owned()))
+                .unwrap()
+        })
+        .collect();
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    // Packages out of order are accepted, but each one only once
+    for i in [2, 0, 1, 0, 2] {
+        buffer.chunk(&packed[i], Some(uuid.to_owned())).unwrap();
+    }
+    let mut received = vec![];
+    while let Some(package) = buffer.next() {
+        received.push(package.header.sequence);
+    }
+    assert_eq!(received, vec![4, 2, 3]);
+    let errors = buffer.take_errors();
+    assert_eq!(
+        errors.iter().map(|err| err.sequence).collect::<Vec<u32>>(),
+        vec![2, 4]
+    );
+    aes_gcm::encryption_close(uuid);
+    encryption_close(uuid);
+}
+
+#[test]
+fn tampered_packages() {
+    use aes_gcm::PackingStruct as _;
+    use aes_gcm_consumer::*;
+    let uuid = "tampered";
+    handshake!(aes_gcm, aes_gcm_consumer, uuid);
+    let pack = |sequence| {
+        item!(aes_gcm)
+            .pack(sequence, Some(uuid.to_owned()))
+            .unwrap()
+    };
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    // Body, counter of package and sequence in header are authenticated
+    let mut body = pack(2);
+    let last = body.len() - 1;
+    body[last] ^= 1;
+    let mut counter = pack(3);
+    counter[HEADER_LEN] ^= 1;
+    let mut sequence = pack(4);
+    sequence[SEQUENCE].copy_from_slice(&5u32.to_le_bytes());
+    for packed in [body, counter, sequence] {
+        buffer.chunk(&packed, Some(uuid.to_owned())).unwrap();
+        assert!(buffer.next().is_none());
+        assert_eq!(buffer.take_errors().len(), 1);
+    }
+    // Tampered packages don't break session
+    buffer.chunk(&pack(6), Some(uuid.to_owned())).unwrap();
+    assert_eq!(
+        buffer.next().map(|package| package.header.sequence),
+        Some(6)
+    );
+    aes_gcm::encryption_close(uuid);
+    encryption_close(uuid);
+}
+
+#[test]
+fn without_session() {
+    use aes_gcm::*;
+    let uuid = "without-session";
+    // Producer cannot pack for consumer without session
+    assert!(item!(aes_gcm).pack(1, Some(uuid.to_owned())).is_err());
+    handshake!(aes_gcm, aes_gcm_consumer, uuid);
+    let packed = item!(aes_gcm).pack(2, Some(uuid.to_owned())).unwrap();
+    let mut buffer: aes_gcm_consumer::Buffer<aes_gcm_consumer::AvailableMessages> =
+        aes_gcm_consumer::Buffer::new();
+    // Encrypted package cannot be read without session
+    buffer.chunk(&packed, None).unwrap();
+    buffer.chunk(&packed, Some(String::from("other"))).unwrap();
+    assert!(buffer.next().is_none());
+    assert_eq!(buffer.take_errors().len(), 2);
+    encryption_close(uuid);
+    assert!(!encryption_ready(uuid));
+    assert!(item!(aes_gcm).pack(3, Some(uuid.to_owned())).is_err());
+    aes_gcm_consumer::encryption_close(uuid);
+}
+
+#[test]
+fn broadcast_is_rebound() {
+    use aes_gcm::*;
+    let uuid = "broadcast";
+    handshake!(aes_gcm, aes_gcm_consumer, uuid);
+    let item = item!(aes_gcm);
+    // Package without uuid is packed once and encrypted for each consumer
+    let unbound = item.clone().pack(2, None).unwrap();
+    assert!(contains(&unbound, &item.title));
+    let rebound = rebind(&unbound, Some(uuid.to_owned())).unwrap();
+    assert!(!contains(&rebound, &item.title));
+    let mut buffer: aes_gcm_consumer::Buffer<aes_gcm_consumer::AvailableMessages> =
+        aes_gcm_consumer::Buffer::new();
+    buffer.chunk(&rebound, Some(uuid.to_owned())).unwrap();
+    assert!(buffer.take_errors().is_empty());
+    assert_eq!(
+        buffer.next().map(|package| package.header.sequence),
+        Some(2)
+    );
+    encryption_close(uuid);
+    aes_gcm_consumer::encryption_close(uuid);
+}
@@ -0,0 +1,49 @@
+use clibri_protocol_features_test::{aes_gcm, binary, msgpack, next_binary, reformatted};
+use std::fs;
+
+// Hashes of IR (generated by run.sh) in order of appearance: hash and source_hash of protocol,
+// then hash and source_hash of workflow
+fn ir_hashes(name: &str) -> Vec<String> {
+    let ir = fs::read_to_string(format!("./generated/ir/{}.json", name)).unwrap();
+    ir.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with("\"hash\"") || line.starts_with("\"source_hash\"") {
+                line.split('"').nth(3).map(String::from)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn formatting_is_ignored() {
+    assert_eq!(reformatted::hash(), binary::hash());
+    let original = ir_hashes("binary");
+    let reformatted = ir_hashes("reformatted");
+    assert_eq!(original.len(), 4);
+    assert_eq!(reformatted[0], original[0]);
+    assert_eq!(reformatted[2], original[2]);
+    // Hashes of sources are kept for debugging
+    assert_ne!(reformatted[1], original[1]);
+    assert_ne!(reformatted[3], original[3]);
+}
+
+#[test]
+fn changes_are_detected() {
+    // New field
+    assert_ne!(next_binary::hash(), binary::hash());
+    assert_ne!(ir_hashes("next")[0], ir_hashes("binary")[0]);
+    // Codec and encryption define format of bodies
+    assert_ne!(msgpack::hash(), binary::hash());
+    assert_ne!(aes_gcm::hash(), binary::hash());
+}
+
+#[test]
+fn transport_isnt_part_of_workflow() {
+    let binary = ir_hashes("binary");
+    let msgpack = ir_hashes("msgpack");
+    assert_eq!(msgpack[2], binary[2]);
+    assert_ne!(msgpack[0], binary[0]);
+}
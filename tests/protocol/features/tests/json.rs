@@ -0,0 +1,116 @@
+use clibri_protocol_features_test::{binary::*, item};
+
+// JSON of Store.Item with replaced (or removed, if value is None) field
+fn item_json_with(field: &str, value: Option<JsonValue>) -> String {
+    let mut json = item!(binary).to_json_value();
+    if let JsonValue::Object(fields) = &mut json {
+        match value {
+            Some(value) => fields
+                .iter_mut()
+                .filter(|(key, _)| key == field)
+                .for_each(|(_, current)| *current = value.clone()),
+            None => fields.retain(|(key, _)| key != field),
+        }
+    }
+    json.stringify()
+}
+
+#[test]
+fn struct_round_trip() {
+    let item = item!(binary);
+    assert_eq!(Store::Item::from_json(&item.to_json()), Ok(item));
+    let empty = Store::Empty::default();
+    assert_eq!(empty.to_json(), "{}");
+    assert_eq!(Store::Empty::from_json(&empty.to_json()), Ok(empty));
+}
+
+#[test]
+fn enum_round_trip() {
+    for value in [
+        Store::Value::number(u64::MAX),
+        Store::Value::signed(i64::MIN),
+        Store::Value::text(String::from("text")),
+        Store::Value::point(Point {
+            x: 0.5,
+            y: 0.25,
+            ..Default::default()
+        }),
+        Store::Value::names(vec![]),
+    ] {
+        assert_eq!(Store::Value::from_json(&value.to_json()), Ok(value));
+    }
+}
+
+#[test]
+fn canonical_form() {
+    let json = item!(binary).to_json_value();
+    // 64-bit integers are strings; other numbers are numbers
+    assert_eq!(
+        json.get("big"),
+        &JsonValue::Str(String::from("18446744073709551615"))
+    );
+    assert_eq!(
+        json.get("balance"),
+        &JsonValue::Str(String::from("-9223372036854775808"))
+    );
+    assert_eq!(
+        json.get("medium"),
+        &JsonValue::Number(String::from("4000000000"))
+    );
+    // Enum is { "Variant": value }
+    assert_eq!(
+        json.get("kind"),
+        &JsonValue::Object(vec![(
+            String::from("Marked"),
+            JsonValue::Str(String::from("marked"))
+        )])
+    );
+    assert_eq!(json.get("limit"), &JsonValue::Null);
+}
+
+#[test]
+fn missed_optional_fields() {
+    let json = item_json_with("note", None);
+    assert_eq!(
+        Store::Item::from_json(&json).map(|item| item.note),
+        Ok(None)
+    );
+}
+
+#[test]
+fn tagged_messages() {
+    let msg = AvailableMessages::Store(Store::AvailableMessages::Item(item!(binary)));
+    let json = msg.to_json_value();
+    assert_eq!(json_tag(&json), Ok("Store.Item"));
+    assert_eq!(AvailableMessages::from_json(&msg.to_json()), Ok(msg));
+}
+
+#[test]
+fn invalid_json() {
+    assert!(Store::Item::from_json("{\"small\":").is_err());
+    assert!(Store::Item::from_json("{} {}").is_err());
+    assert!(Store::Item::from_json("[]").is_err());
+    assert!(AvailableMessages::from_json("{\"message\":{}}").is_err());
+    let unknown = json_tagged("Store.Missed", JsonValue::Object(vec![]));
+    assert!(AvailableMessages::from_json(&unknown.stringify()).is_err());
+}
+
+#[test]
+fn invalid_fields() {
+    for (field, value) in [
+        ("small", JsonValue::Str(String::from("200"))),
+        ("small", JsonValue::Number(String::from("300"))),
+        ("big", JsonValue::Str(String::from("-1"))),
+        ("active", JsonValue::Number(String::from("1"))),
+        ("tags", JsonValue::Str(String::from("a"))),
+        (
+            "kind",
+            JsonValue::Object(vec![(String::from("Missed"), JsonValue::Null)]),
+        ),
+    ] {
+        let err = Store::Item::from_json(&item_json_with(field, Some(value))).expect_err(field);
+        assert!(err.contains(&format!("Item.{}", field)), "{}", err);
+    }
+    // Required field is missed
+    assert!(Store::Item::from_json(&item_json_with("title", None)).is_err());
+}
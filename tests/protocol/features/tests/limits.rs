@@ -0,0 +1,139 @@
+use clibri_protocol_features_test::{binary, cbor, compact, item, msgpack};
+
+// Reads item with given limits; returns result of chunk and errors of packages. Store.Empty
+// goes after item to check, that package with exceeded limit doesn't break stream
+macro_rules! read {
+    ($protocol:ident, $limits:expr) => {{
+        use $protocol::*;
+        let mut packed = item!($protocol).pack(1, None).unwrap();
+        packed.extend_from_slice(&Store::Empty::default().pack(2, None).unwrap());
+        let mut buffer: Buffer<AvailableMessages> = Buffer::with_limits($limits);
+        let result = buffer.chunk(&packed, None);
+        let mut received = vec![];
+        while let Some(package) = buffer.next() {
+            received.push(package.header.sequence);
+        }
+        (result, buffer.take_errors(), received)
+    }};
+}
+
+// Checks, that decoding limit is applied: within limit item is read, otherwise item is
+// rejected with ReadError::Limit and following package is still read
+macro_rules! decoding_limits {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let within = [
+            Limits::default(),
+            Limits {
+                // Title of item is 35 bytes
+                string: 35,
+                ..Default::default()
+            },
+        ];
+        for limits in within {
+            let (result, errors, received) = read!($protocol, limits);
+            assert!(result.is_ok());
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(received, vec![1, 2]);
+        }
+        let exceeded = [
+            Limits {
+                string: 34,
+                ..Default::default()
+            },
+            // Blob has 5 items
+            Limits {
+                items: 4,
+                ..Default::default()
+            },
+            Limits {
+                depth: 1,
+                ..Default::default()
+            },
+        ];
+        for limits in exceeded {
+            let (result, errors, received) = read!($protocol, limits.clone());
+            assert!(result.is_ok());
+            assert_eq!(errors.len(), 1, "{:?}", limits);
+            assert_eq!(errors[0].sequence, 1);
+            assert!(
+                matches!(errors[0].error, ReadError::Limit(_)),
+                "{:?}",
+                errors[0].error
+            );
+            assert_eq!(received, vec![2]);
+        }
+    }};
+}
+
+#[test]
+fn decoding() {
+    decoding_limits!(binary);
+    decoding_limits!(msgpack);
+    decoding_limits!(cbor);
+    decoding_limits!(compact);
+}
+
+#[test]
+fn frame() {
+    use binary::*;
+    let packed = item!(binary).pack(1, None).unwrap();
+    let body = StructEncode::encoded_len(&item!(binary));
+    let mut buffer: Buffer<AvailableMessages> = Buffer::with_limits(Limits {
+        frame: body,
+        ..Default::default()
+    });
+    buffer.chunk(&packed, None).unwrap();
+    assert!(buffer.next().is_some());
+    let mut buffer: Buffer<AvailableMessages> = Buffer::with_limits(Limits {
+        frame: body - 1,
+        ..Default::default()
+    });
+    assert!(matches!(
+        buffer.chunk(&packed, None),
+        Err(ReadError::Limit(_))
+    ));
+    assert_eq!(buffer.len(), 0);
+    assert!(buffer.next().is_none());
+}
+
+#[test]
+fn hostile_header() {
+    use binary::*;
+    let mut packed = Store::Empty::default().pack(1, None).unwrap();
+    // Length of body is last 8 bytes of header; flags are kept in highest bits
+    let len = packed.len() - 8;
+    packed[len..].copy_from_slice(&(u32::MAX as u64).to_le_bytes());
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    assert!(matches!(
+        buffer.chunk(&packed, None),
+        Err(ReadError::Limit(_))
+    ));
+    assert_eq!(buffer.len(), 0);
+}
+
+#[test]
+fn pending() {
+    use binary::*;
+    let mut packed = Store::Empty::default().pack(1, None).unwrap();
+    packed.extend_from_slice(&Store::Empty::default().pack(2, None).unwrap());
+    let mut buffer: Buffer<AvailableMessages> = Buffer::with_limits(Limits {
+        pending: 1,
+        ..Default::default()
+    });
+    assert!(matches!(
+        buffer.chunk(&packed, None),
+        Err(ReadError::Limit(_))
+    ));
+    assert_eq!(buffer.pending(), 1);
+    assert_eq!(
+        buffer.next().map(|package| package.header.sequence),
+        Some(1)
+    );
+    // Second package is kept in buffer and is read, when queue has space
+    buffer.chunk(&vec![], None).unwrap();
+    assert_eq!(
+        buffer.next().map(|package| package.header.sequence),
+        Some(2)
+    );
+}
@@ -0,0 +1,148 @@
+use clibri_protocol_features_test::{binary::*, item};
+
+// Highest byte of body size (last byte of header) keeps flags of package
+const FLAGS: usize = 25;
+const VERSION_SHIFT: u8 = 4;
+
+fn local() -> Capabilities {
+    Capabilities::local(CODEC, &Limits::default())
+}
+
+fn read(packed: &Vec<u8>) -> Result<IncomeMessage<AvailableMessages>, ReadError> {
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    buffer.chunk(packed, None)?;
+    assert!(buffer.take_errors().is_empty());
+    Ok(buffer.next().unwrap())
+}
+
+#[test]
+fn common_settings() {
+    let peer = Capabilities {
+        version: 1,
+        compression: vec![COMPRESSION_FLAG_LZ4],
+        frame: 1024,
+        heartbeat: true,
+        ..local()
+    };
+    let agreement = local().negotiate(&peer).unwrap();
+    assert_eq!(agreement.version, 1);
+    assert_eq!(agreement.compression, COMPRESSION_FLAG_LZ4);
+    assert_eq!(agreement.encryption, None);
+    assert_eq!(agreement.frame, 1024);
+    // Runtime itself doesn't send heartbeat packages
+    assert!(!agreement.heartbeat);
+    assert!(agreement.clock.is_none());
+    let agreement = local().negotiate(&local()).unwrap();
+    assert_eq!(agreement.version, MSG_VERSION);
+    assert_eq!(agreement.compression, COMPRESSION_FLAGS);
+}
+
+#[test]
+fn negotiation_errors() {
+    let codec = Capabilities {
+        codec: String::from("msgpack"),
+        ..local()
+    };
+    assert!(local().negotiate(&codec).unwrap_err().contains("Codec"));
+    let encryption = Capabilities {
+        encryption: vec![String::from("aes_gcm")],
+        ..local()
+    };
+    assert!(local()
+        .negotiate(&encryption)
+        .unwrap_err()
+        .contains("encryption"));
+    let own = Capabilities {
+        encryption: vec![String::from("chacha20_poly1305")],
+        ..local()
+    };
+    assert!(own.negotiate(&encryption).is_err());
+}
+
+#[test]
+fn legacy_peer() {
+    let agreement = local().negotiate(&Capabilities::legacy(CODEC)).unwrap();
+    assert_eq!(agreement.version, 0);
+    assert_eq!(agreement.compression, 0);
+    let uuid = "legacy";
+    set_agreement(uuid, agreement);
+    let mut item = item!(binary);
+    item.title = "compressible ".repeat(200);
+    set_compression(&Compression {
+        algorithm: CompressionAlgorithm::Lz4,
+        threshold: 0,
+    });
+    let packed = item.clone().pack(1, Some(uuid.to_owned()));
+    set_compression(&Compression::default());
+    remove_agreement(uuid);
+    // Legacy peer cannot read compressed bodies
+    let package = read(&packed.unwrap()).unwrap();
+    assert_eq!(package.header.version, 0);
+    assert_eq!(package.header.flags & COMPRESSION_FLAGS, 0);
+}
+
+#[test]
+fn version_of_header() {
+    // Until agreement peer might be older runtime
+    let package = read(&item!(binary).pack(1, None).unwrap()).unwrap();
+    assert_eq!(package.header.version, 0);
+    let uuid = "version";
+    set_agreement(uuid, local().negotiate(&local()).unwrap());
+    let packed = item!(binary).pack(1, Some(uuid.to_owned()));
+    remove_agreement(uuid);
+    let package = read(&packed.unwrap()).unwrap();
+    assert_eq!(package.header.version, MSG_VERSION);
+    assert_eq!(
+        package.msg,
+        AvailableMessages::Store(Store::AvailableMessages::Item(item!(binary)))
+    );
+}
+
+#[test]
+fn unsupported_version() {
+    let mut packed = item!(binary).pack(1, None).unwrap();
+    packed[FLAGS] |= (MSG_VERSION + 1) << VERSION_SHIFT;
+    assert!(matches!(read(&packed), Err(ReadError::Header(_))));
+}
+
+#[test]
+fn frame_of_peer() {
+    let item = item!(binary);
+    let uuid = "frame";
+    set_agreement(
+        uuid,
+        local()
+            .negotiate(&Capabilities {
+                frame: StructEncode::encoded_len(&item) as u64 - 1,
+                ..local()
+            })
+            .unwrap(),
+    );
+    let packed = item.clone().pack(1, Some(uuid.to_owned()));
+    let empty = Store::Empty::default().pack(2, Some(uuid.to_owned()));
+    remove_agreement(uuid);
+    // Peer cannot read package bigger than its limit
+    assert!(packed.is_err());
+    assert!(empty.is_ok());
+}
+
+#[test]
+fn capabilities_are_optional() {
+    // HashRequest of runtime before negotiation doesn't have capabilities
+    let request = InternalServiceGroup::HashRequest {
+        protocol: hash(),
+        workflow: String::new(),
+        ..Default::default()
+    };
+    let package = read(&request.clone().pack(1, None).unwrap()).unwrap();
+    match package.msg {
+        AvailableMessages::InternalServiceGroup(
+            InternalServiceGroup::AvailableMessages::HashRequest(received),
+        ) => {
+            assert_eq!(received.version, None);
+            assert_eq!(received.codec, None);
+            assert_eq!(received, request);
+        }
+        other => panic!("Unexpected message: {:?}", other),
+    }
+}
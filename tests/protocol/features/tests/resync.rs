@@ -0,0 +1,106 @@
+use clibri_protocol_features_test::{binary, cbor, compact, item, msgpack};
+
+// Header: id (4 bytes), signature (2), sequence (4), ts (8), length and flags (8)
+const HEADER_LEN: usize = 26;
+const SIGNATURE: std::ops::Range<usize> = 4..6;
+
+// Packs three items; middle one is changed by corrupt
+macro_rules! chunk {
+    ($protocol:ident, $corrupt:expr) => {{
+        use $protocol::*;
+        let mut packed = vec![];
+        for sequence in 1..=3 {
+            let mut package = item!($protocol).pack(sequence, None).unwrap();
+            if sequence == 2 {
+                let corrupt: fn(&mut Vec<u8>) = $corrupt;
+                corrupt(&mut package);
+            }
+            packed.extend_from_slice(&package);
+        }
+        packed
+    }};
+}
+
+// Reads chunk; returns sequences of read packages and errors of packages
+macro_rules! read {
+    ($protocol:ident, $packed:expr) => {{
+        use $protocol::*;
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        buffer.chunk(&$packed, None).unwrap();
+        let mut received = vec![];
+        while let Some(package) = buffer.next() {
+            assert_eq!(
+                package.msg,
+                AvailableMessages::Store(Store::AvailableMessages::Item(item!($protocol)))
+            );
+            received.push(package.header.sequence);
+        }
+        assert_eq!(buffer.len(), 0);
+        (received, buffer.take_errors())
+    }};
+}
+
+// Checks, that corrupted body and wrong signature fail only own package
+macro_rules! resync {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let packed = chunk!($protocol, |package| {
+            for byte in package[HEADER_LEN..].iter_mut() {
+                *byte = 0xFF;
+            }
+        });
+        let (received, errors) = read!($protocol, packed);
+        assert_eq!(received, vec![1, 3]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].id, StructEncode::get_id(&item!($protocol)));
+        assert_eq!(errors[0].sequence, 2);
+        assert!(
+            matches!(errors[0].error, ReadError::Parsing(_)),
+            "{:?}",
+            errors[0].error
+        );
+        let packed = chunk!($protocol, |package| {
+            package[SIGNATURE].copy_from_slice(&u16::MAX.to_le_bytes());
+        });
+        let (received, errors) = read!($protocol, packed);
+        assert_eq!(received, vec![1, 3]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].sequence, 2);
+        assert!(
+            matches!(errors[0].error, ReadError::Signature(_)),
+            "{:?}",
+            errors[0].error
+        );
+    }};
+}
+
+#[test]
+fn bad_frame_is_skipped() {
+    resync!(binary);
+    resync!(msgpack);
+    resync!(cbor);
+    resync!(compact);
+}
+
+#[test]
+fn bad_frame_in_split_chunks() {
+    use binary::*;
+    let packed = chunk!(binary, |package| {
+        let last = package.len() - 1;
+        package[last] ^= 0xFF;
+        package[HEADER_LEN] ^= 0xFF;
+    });
+    let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+    let mut received = vec![];
+    for byte in packed {
+        buffer.chunk(&vec![byte], None).unwrap();
+        while let Some(package) = buffer.next() {
+            received.push(package.header.sequence);
+        }
+    }
+    assert_eq!(received, vec![1, 3]);
+    let errors = buffer.take_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].sequence, 2);
+    assert!(buffer.take_errors().is_empty());
+}
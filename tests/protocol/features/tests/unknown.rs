@@ -0,0 +1,135 @@
+use clibri_protocol_features_test::{
+    binary, cbor, compact, msgpack, next_binary, next_cbor, next_compact, next_msgpack, views,
+};
+
+// Id, which isn't used by protocol
+const UNKNOWN_ID: u32 = 1000;
+
+// Reads all packages of buffer
+macro_rules! read {
+    ($protocol:ident, $packed:expr) => {{
+        use $protocol::*;
+        let mut buffer: Buffer<AvailableMessages> = Buffer::new();
+        buffer.chunk(&$packed, None).unwrap();
+        assert!(buffer.take_errors().is_empty());
+        let mut received = vec![];
+        while let Some(package) = buffer.next() {
+            received.push(package.msg);
+        }
+        received
+    }};
+}
+
+// Err of next version has field "code"; older version keeps it and sends it back
+macro_rules! unknown_fields {
+    ($old:ident, $next:ident) => {{
+        let sent = $next::Err {
+            msg: String::from("message"),
+            code: 42,
+            ..Default::default()
+        };
+        let received = match &read!(
+            $old,
+            $next::PackingStruct::pack(&mut sent.clone(), 1, None).unwrap()
+        )[..]
+        {
+            [$old::AvailableMessages::Err(err)] => err.clone(),
+            other => panic!("Unexpected messages: {:?}", other),
+        };
+        assert_eq!(received.msg, sent.msg);
+        assert_eq!(received.__unknown.len(), 1);
+        assert_eq!(
+            $old::StructEncode::encoded_len(&received),
+            $old::StructEncode::abduct(&mut received.clone())
+                .unwrap()
+                .len()
+        );
+        match &read!(
+            $next,
+            $old::PackingStruct::pack(&mut received.clone(), 2, None).unwrap()
+        )[..]
+        {
+            [$next::AvailableMessages::Err(err)] => assert_eq!(err, &sent),
+            other => panic!("Unexpected messages: {:?}", other),
+        }
+    }};
+}
+
+// Package with unknown id is read as AvailableMessages::Unknown and can be sent further as is
+macro_rules! unknown_message {
+    ($protocol:ident) => {{
+        use $protocol::*;
+        let body = vec![1, 2, 3, 4];
+        let packed = pack_buffer(UNKNOWN_ID, 0, 1, body.clone(), None).unwrap();
+        let received = read!($protocol, packed.clone());
+        assert_eq!(
+            received,
+            vec![AvailableMessages::Unknown {
+                id: UNKNOWN_ID,
+                body: body.clone()
+            }]
+        );
+        if let AvailableMessages::Unknown { id, body } = &received[0] {
+            let relayed = pack_buffer(*id, 0, 1, body.clone(), None).unwrap();
+            // Timestamp of header (bytes 10..18) can differ
+            assert_eq!(relayed[..10], packed[..10]);
+            assert_eq!(relayed[18..], packed[18..]);
+        }
+    }};
+}
+
+#[test]
+fn fields_are_preserved() {
+    unknown_fields!(binary, next_binary);
+    unknown_fields!(msgpack, next_msgpack);
+    unknown_fields!(cbor, next_cbor);
+    unknown_fields!(compact, next_compact);
+}
+
+// Without --unknown-fields field "code" is dropped, so newer version can't read sent back struct
+#[test]
+fn fields_are_dropped() {
+    let sent = next_binary::Err {
+        msg: String::from("message"),
+        code: 42,
+        ..Default::default()
+    };
+    let received = match &read!(
+        views,
+        next_binary::PackingStruct::pack(&mut sent.clone(), 1, None).unwrap()
+    )[..]
+    {
+        [views::AvailableMessages::Err(err)] => err.clone(),
+        other => panic!("Unexpected messages: {:?}", other),
+    };
+    assert_eq!(received.msg, sent.msg);
+    let mut buffer: next_binary::Buffer<next_binary::AvailableMessages> =
+        next_binary::Buffer::new();
+    buffer
+        .chunk(
+            &views::PackingStruct::pack(&mut received.clone(), 2, None).unwrap(),
+            None,
+        )
+        .unwrap();
+    assert_eq!(buffer.take_errors().len(), 1);
+    assert!(buffer.next().is_none());
+}
+
+#[test]
+fn messages_are_preserved() {
+    unknown_message!(binary);
+    unknown_message!(msgpack);
+    unknown_message!(cbor);
+    unknown_message!(compact);
+}
+
+#[test]
+fn unknown_fields_are_hidden() {
+    use binary::*;
+    let err = Err {
+        msg: String::from("message"),
+        ..Default::default()
+    };
+    assert!(err.__unknown.is_empty());
+    assert_eq!(err.to_json(), r#"{"msg":"message"}"#);
+}
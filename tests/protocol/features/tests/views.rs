@@ -0,0 +1,89 @@
+use clibri_protocol_features_test::{item, views::*};
+
+fn packed() -> (Store::Item, Vec<u8>) {
+    let item = item!(views);
+    let packed = item.clone().pack(1, None).unwrap();
+    (item, packed)
+}
+
+#[test]
+fn view_matches_owned() {
+    let (item, packed) = packed();
+    let (frame, rest) = FrameView::read(&packed).unwrap().unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(frame.header.id, Store::ItemView::get_id());
+    let view: Store::ItemView = frame.view().unwrap();
+    assert_eq!(view.to_owned().unwrap(), item);
+    assert_eq!(view.big, item.big);
+    assert_eq!(view.note, Some("note"));
+    assert_eq!(view.limit, None);
+    let tags: Vec<&str> = view.tags.iter().map(|tag| tag.unwrap()).collect();
+    assert_eq!(tags, vec!["a", "", "c"]);
+    let path: Vec<Point> = view
+        .path
+        .iter()
+        .map(|point| point.unwrap().to_owned().unwrap())
+        .collect();
+    assert_eq!(path, item.path);
+    assert_eq!(view.value.get().unwrap().to_owned().unwrap(), item.value);
+}
+
+#[test]
+fn view_borrows_buffer() {
+    let (_, packed) = packed();
+    let (frame, _) = FrameView::read(&packed).unwrap().unwrap();
+    let view: Store::ItemView = frame.view().unwrap();
+    let range = packed.as_ptr_range();
+    assert!(range.contains(&view.title.as_ptr()));
+    assert!(range.contains(&view.blob.as_ptr()));
+}
+
+#[test]
+fn sequential_frames() {
+    let (item, mut buf) = packed();
+    buf.extend_from_slice(&Point::default().pack(2, None).unwrap());
+    let (first, rest) = FrameView::read(&buf).unwrap().unwrap();
+    let (second, rest) = FrameView::read(rest).unwrap().unwrap();
+    assert!(rest.is_empty());
+    assert!(FrameView::read(rest).unwrap().is_none());
+    let view: Store::ItemView = first.view().unwrap();
+    assert_eq!(view.to_owned().unwrap(), item);
+    assert_eq!(second.header.id, PointView::get_id());
+    let view: PointView = second.view().unwrap();
+    assert_eq!(view.to_owned().unwrap(), Point::default());
+}
+
+#[test]
+fn partial_frame() {
+    let (_, packed) = packed();
+    for len in [0, 1, packed.len() / 2, packed.len() - 1] {
+        assert!(FrameView::read(&packed[..len]).unwrap().is_none());
+    }
+}
+
+#[test]
+fn truncated_view() {
+    let (_, packed) = packed();
+    let (frame, _) = FrameView::read(&packed).unwrap().unwrap();
+    let body = frame.body;
+    for len in 1..body.len() {
+        assert!(
+            <Store::ItemView as ViewDecode>::view(&body[..len]).is_err(),
+            "body cut at {} is read",
+            len
+        );
+    }
+}
+
+#[test]
+fn compressed_frame() {
+    set_compression(&Compression {
+        algorithm: CompressionAlgorithm::Lz4,
+        threshold: 0,
+    });
+    let packed = item!(views).pack(1, None);
+    set_compression(&Compression::default());
+    let packed = packed.unwrap();
+    let (frame, _) = FrameView::read(&packed).unwrap().unwrap();
+    assert!(frame.view::<Store::ItemView>().is_err());
+}
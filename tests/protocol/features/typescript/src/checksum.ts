@@ -0,0 +1,100 @@
+import {
+	BufferReaderMessages,
+	MessageHeader,
+	PackingChecksum,
+	Store,
+	setChecksum,
+	getChecksum,
+} from "../../generated/ts/binary/consumer/protocol/protocol";
+import { Test, vector, pairs, unhex, equal, unwrap } from "./common";
+
+function item(): Store.Item {
+	return unwrap(Store.Item.fromJson(vector("codecs").item), "item");
+}
+
+// Packs with checksum; default setting is restored after
+function packWithChecksum(sequence: number): Buffer {
+	setChecksum(true);
+	try {
+		return Buffer.from(item().pack(sequence));
+	} finally {
+		setChecksum(false);
+	}
+}
+
+function read(packed: Buffer): { checksums: Array<number | undefined>; errors: number } {
+	const reader = new BufferReaderMessages();
+	const errors = reader.chunk(packed);
+	const checksums: Array<number | undefined> = [];
+	let offset = 0;
+	let msg = reader.next();
+	while (msg !== undefined) {
+		// Headers are read from packed bytes, because received message doesn't keep checksum
+		const header = new MessageHeader(packed.subarray(offset));
+		equal(header.sequence, msg.header.sequence, "sequence of header");
+		checksums.push(header.checksum);
+		offset += header.size + header.len;
+		msg = reader.next();
+	}
+	return { checksums, errors: errors === undefined ? 0 : errors.length };
+}
+
+// Same values of CRC-32C (Castagnoli) as rust tests check (../vectors/checksum.json)
+function crc32cVectors() {
+	pairs(vector("checksum").crc32c).forEach(([bytes, crc]) => {
+		equal(PackingChecksum.crc32c(unhex(bytes)), parseInt(crc, 16), `crc32c of "${bytes}"`);
+		equal(
+			PackingChecksum.crc32c(new Uint8Array(unhex(bytes)).buffer),
+			parseInt(crc, 16),
+			`crc32c of "${bytes}" as ArrayBuffer`
+		);
+	});
+}
+
+function checksumInHeader() {
+	equal(getChecksum(), false, "checksum is disabled by default");
+	const plain = Buffer.from(item().pack(1));
+	const packed = packWithChecksum(1);
+	equal(packed.byteLength, plain.byteLength + MessageHeader.CHECKSUM_LENGTH, "packed length");
+	const header = new MessageHeader(packed);
+	equal(header.flags & MessageHeader.FLAG_CHECKSUM, MessageHeader.FLAG_CHECKSUM, "flag");
+	equal(header.checksum, PackingChecksum.crc32c(packed.subarray(header.size)), "checksum");
+	const received = read(packed);
+	equal(received.errors, 0, "errors");
+	equal(received.checksums.length, 1, "received messages");
+}
+
+function mixedTraffic() {
+	const packed = Buffer.concat([
+		packWithChecksum(1),
+		Buffer.from(item().pack(2)),
+		packWithChecksum(3),
+	]);
+	const received = read(packed);
+	equal(received.errors, 0, "errors");
+	equal(
+		received.checksums.map((checksum) => checksum !== undefined).join(","),
+		"true,false,true",
+		"packages with checksum"
+	);
+}
+
+function corruptedBody() {
+	const body = packWithChecksum(1);
+	body[body.byteLength - 1] ^= 0x01;
+	const checksum = packWithChecksum(2);
+	checksum[new MessageHeader(checksum).size - MessageHeader.CHECKSUM_LENGTH] ^= 0x01;
+	const reader = new BufferReaderMessages();
+	const errors = reader.chunk(Buffer.concat([body, checksum, packWithChecksum(3)]));
+	equal(errors === undefined ? 0 : errors.length, 2, "errors");
+	const msg = reader.next();
+	equal(msg === undefined ? undefined : msg.header.sequence, 3, "received message");
+	equal(reader.next(), undefined, "no more messages");
+}
+
+export const tests: Test[] = [
+	["checksum: crc32c vectors", crc32cVectors],
+	["checksum: checksum in header", checksumInHeader],
+	["checksum: mixed traffic", mixedTraffic],
+	["checksum: corrupted body", corruptedBody],
+];
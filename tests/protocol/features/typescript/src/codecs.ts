@@ -0,0 +1,47 @@
+import * as Binary from "../../generated/ts/binary/consumer/protocol/protocol";
+import * as MsgPack from "../../generated/ts/msgpack/consumer/protocol/protocol";
+import * as Cbor from "../../generated/ts/cbor/consumer/protocol/protocol";
+import * as Compact from "../../generated/ts/compact/consumer/protocol/protocol";
+import { Test, vector, unhex, hex, equal, unwrap } from "./common";
+
+interface IItem {
+	encode(): ArrayBufferLike;
+	toJson(): any;
+}
+
+interface IItemClass {
+	from(obj: any): IItem | Error;
+	fromJson(json: any): IItem | Error;
+}
+
+// Item of ../vectors/codecs.json is encoded into the same body as rust encodes it into,
+// and body of rust is decoded back into item
+function shared(codec: string, name: () => string, Item: IItemClass) {
+	equal(name(), codec, "codec");
+	const target = vector("codecs");
+	const item = unwrap(Item.fromJson(target.item), `${codec}: item from json`);
+	equal(hex(item.encode()), target.bodies[codec], `${codec}: encoded body`);
+	const decoded = unwrap(Item.from(unhex(target.bodies[codec])), `${codec}: decoded body`);
+	equal(JSON.stringify(decoded.toJson()), JSON.stringify(item.toJson()), `${codec}: decoded item`);
+	// Decoded item gives same bytes again
+	equal(hex(decoded.encode()), target.bodies[codec], `${codec}: encoded decoded item`);
+}
+
+// Cut body is an error, not a partially filled item
+function truncated(codec: string, Item: IItemClass) {
+	const body = unhex(vector("codecs").bodies[codec]);
+	for (let len = 0; len < body.byteLength; len += 1) {
+		if (!(Item.from(body.slice(0, len)) instanceof Error)) {
+			throw new Error(`${codec}: body cut to ${len} bytes of ${body.byteLength} is decoded`);
+		}
+	}
+}
+
+export const tests: Test[] = [
+	["codecs: binary vector", () => shared("binary", Binary.codec, Binary.Store.Item)],
+	["codecs: msgpack vector", () => shared("msgpack", MsgPack.codec, MsgPack.Store.Item)],
+	["codecs: cbor vector", () => shared("cbor", Cbor.codec, Cbor.Store.Item)],
+	["codecs: compact vector", () => shared("compact", Compact.codec, Compact.Store.Item)],
+	["codecs: msgpack truncated", () => truncated("msgpack", MsgPack.Store.Item)],
+	["codecs: cbor truncated", () => truncated("cbor", Cbor.Store.Item)],
+];
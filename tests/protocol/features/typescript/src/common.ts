@@ -0,0 +1,38 @@
+import * as fs from "fs";
+import * as path from "path";
+
+export type Test = [string, () => void];
+
+// Vectors of ../vectors/<name>.json are checked by rust tests too. Bytes are written as hex strings
+export function vector(name: string): any {
+	return JSON.parse(
+		fs.readFileSync(path.resolve(__dirname, "../../../../vectors", `${name}.json`), "utf8")
+	);
+}
+
+export function unhex(hex: string): Buffer {
+	return Buffer.from(hex, "hex");
+}
+
+export function hex(bytes: ArrayBufferLike | Uint8Array): string {
+	return Buffer.from(bytes instanceof Uint8Array ? bytes : new Uint8Array(bytes)).toString("hex");
+}
+
+// Pairs are written as [["value", "value"], ...]. Numbers are strings, because JS cannot keep
+// i64/u64 as numbers
+export function pairs(value: any): Array<[string, string]> {
+	return value;
+}
+
+export function equal<T>(actual: T, expected: T, msg: string) {
+	if (actual !== expected) {
+		throw new Error(`${msg}: ${actual} !== ${expected}`);
+	}
+}
+
+export function unwrap<T>(value: T | Error, msg: string): T {
+	if (value instanceof Error) {
+		throw new Error(`${msg}: ${value.message}`);
+	}
+	return value;
+}
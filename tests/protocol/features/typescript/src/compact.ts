@@ -0,0 +1,47 @@
+import { ProtocolCompact } from "../../generated/ts/compact/consumer/protocol/protocol";
+import { Test, vector, pairs, unhex, hex, equal } from "./common";
+
+function varint() {
+	pairs(vector("compact").varint).forEach(([value, bytes]) => {
+		const writer = new ProtocolCompact.Writer();
+		writer.varint(BigInt(value));
+		equal(hex(writer.bytes()), bytes, `varint ${value}`);
+		const reader = new ProtocolCompact.Reader(unhex(bytes));
+		equal(reader.varint(), BigInt(value), `varint ${value} from ${bytes}`);
+		equal(reader.rest(), 0, `rest after varint ${value}`);
+	});
+}
+
+function zigzag() {
+	pairs(vector("compact").zigzag).forEach(([value, encoded]) => {
+		const writer = new ProtocolCompact.Writer();
+		writer.zigzag(BigInt(value));
+		const expected = new ProtocolCompact.Writer();
+		expected.varint(BigInt(encoded));
+		equal(hex(writer.bytes()), hex(expected.bytes()), `zigzag ${value}`);
+		equal(
+			new ProtocolCompact.Reader(expected.bytes()).zigzag(),
+			BigInt(value),
+			`zigzag ${value} from ${encoded}`
+		);
+	});
+}
+
+function invalidVarint() {
+	// More than 64 bits; last byte has continuation bit; nothing to read
+	["ffffffffffffffffffffff", "80", ""].forEach((bytes: string) => {
+		let err: Error | undefined;
+		try {
+			new ProtocolCompact.Reader(unhex(bytes)).varint();
+		} catch (e) {
+			err = e instanceof Error ? e : new Error(`${e}`);
+		}
+		equal(err instanceof Error, true, `varint from "${bytes}" is an error`);
+	});
+}
+
+export const tests: Test[] = [
+	["compact: varint", varint],
+	["compact: zigzag", zigzag],
+	["compact: invalid varint", invalidVarint],
+];
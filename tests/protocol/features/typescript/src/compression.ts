@@ -0,0 +1,150 @@
+import * as Binary from "../../generated/ts/binary/consumer/protocol/protocol";
+import * as MsgPack from "../../generated/ts/msgpack/consumer/protocol/protocol";
+import * as Cbor from "../../generated/ts/cbor/consumer/protocol/protocol";
+import * as Compact from "../../generated/ts/compact/consumer/protocol/protocol";
+import {
+	CompressionAlgorithm,
+	ICompression,
+	MessageHeader,
+	PackingCompression,
+} from "../../generated/ts/binary/consumer/protocol/protocol";
+import { Test, vector, unhex, hex, equal, unwrap } from "./common";
+
+const algorithms: Array<[CompressionAlgorithm, number, string]> = [
+	[CompressionAlgorithm.Deflate, PackingCompression.FLAG_DEFLATE, "deflate"],
+	[CompressionAlgorithm.Lz4, PackingCompression.FLAG_LZ4, "lz4"],
+];
+
+// Compression works with ArrayBuffer; Buffer can be a view of shared pool
+function bytes(value: Uint8Array): ArrayBufferLike {
+	return new Uint8Array(value).buffer;
+}
+
+interface ISettings {
+	setCompression(compression: ICompression): void;
+	getCompression(): ICompression;
+}
+
+// Runs body with given algorithm and threshold 0; default settings are restored after. Each
+// protocol keeps own settings
+function withCompression<T>(
+	algorithm: CompressionAlgorithm,
+	body: () => T,
+	target: ISettings = Binary
+): T {
+	const defaults = target.getCompression();
+	target.setCompression({ algorithm, threshold: 0 });
+	try {
+		return body();
+	} finally {
+		target.setCompression(defaults);
+	}
+}
+
+// Rust runtime gives same compressed bodies (../vectors/compression.json); "foreign" body
+// is written by zlib with dynamic Huffman codes, which aren't used by own compressor
+function sharedVectors() {
+	const target = vector("compression");
+	const body = bytes(unhex(target.body));
+	algorithms.forEach(([algorithm, flag, name]) => {
+		const compressed = withCompression(algorithm, () =>
+			PackingCompression.compress(body)
+		);
+		equal(compressed.flag, flag, `${name}: flag`);
+		equal(hex(compressed.body), target.compressed[name], `${name}: compressed body`);
+		const decompressed = PackingCompression.decompress(
+			flag,
+			bytes(unhex(target.compressed[name]))
+		);
+		equal(hex(unwrap(decompressed, `${name}: decompressed`)), target.body, `${name}: body`);
+	});
+	const foreign = PackingCompression.decompress(
+		PackingCompression.FLAG_DEFLATE,
+		bytes(unhex(target.foreign.deflate))
+	);
+	equal(hex(unwrap(foreign, "foreign: decompressed")), target.body, "foreign: body");
+}
+
+function algorithmsRoundTrip() {
+	const samples: Uint8Array[] = [
+		new Uint8Array(0),
+		new Uint8Array(1),
+		new Uint8Array(100000).fill(7),
+		Buffer.from("abcabcabcabcabcabcabcabcabcabcabcabc"),
+	];
+	// Pseudo-random bytes aren't compressed at all
+	let seed = 1;
+	const random = new Uint8Array(4096);
+	for (let i = 0; i < random.byteLength; i += 1) {
+		seed = (Math.imul(seed, 1103515245) + 12345) >>> 0;
+		random[i] = (seed >>> 16) & 0xff;
+	}
+	samples.push(random);
+	algorithms.forEach(([algorithm, , name]) => {
+		samples.forEach((sample: Uint8Array) => {
+			const body = bytes(sample);
+			const compressed = withCompression(algorithm, () =>
+				PackingCompression.compress(body)
+			);
+			const msg = `${name}: sample of ${sample.byteLength} bytes`;
+			if (compressed.flag === 0) {
+				equal(hex(compressed.body), hex(sample), `${msg} is kept`);
+			} else {
+				equal(compressed.body.byteLength < sample.byteLength, true, `${msg} is smaller`);
+			}
+			const decompressed = PackingCompression.decompress(compressed.flag, compressed.body);
+			equal(hex(unwrap(decompressed, msg)), hex(sample), `${msg} is restored`);
+		});
+	});
+}
+
+// Packs and reads compressible item with each algorithm; flag of package shows algorithm
+function packagesRoundTrip() {
+	[Binary, MsgPack, Cbor, Compact].forEach((target: any) => {
+		const codec: string = target.codec();
+		const obj = vector("codecs").item;
+		const item = unwrap(
+			target.Store.Item.fromJson({ ...obj, title: "compressible ".repeat(200) }),
+			`${codec}: item`
+		);
+		algorithms.forEach(([algorithm, flag, name]) => {
+			const packed = Buffer.from(withCompression(algorithm, () => item.pack(1), target));
+			equal(packed.byteLength < item.encode().byteLength, true, `${codec}: ${name} is smaller`);
+			equal(
+				new MessageHeader(packed).flags & PackingCompression.FLAGS,
+				flag,
+				`${codec}: ${name} flag`
+			);
+			const reader = new target.BufferReaderMessages();
+			equal(reader.chunk(packed), undefined, `${codec}: ${name} errors`);
+			const received = reader.next();
+			equal(
+				received === undefined ? undefined : JSON.stringify(received.getRef().toJson()),
+				JSON.stringify(item.toJson()),
+				`${codec}: ${name} received item`
+			);
+		});
+	});
+}
+
+function corruptedBody() {
+	const body = bytes(unhex(vector("compression").body));
+	algorithms.forEach(([algorithm, flag, name]) => {
+		const compressed = withCompression(algorithm, () => PackingCompression.compress(body));
+		// Declared size of decompressed body doesn't match data
+		const corrupted = new Uint8Array(compressed.body.slice(0));
+		corrupted[0] ^= 0xff;
+		equal(
+			PackingCompression.decompress(flag, corrupted.buffer) instanceof Error,
+			true,
+			`${name}: corrupted body is an error`
+		);
+	});
+}
+
+export const tests: Test[] = [
+	["compression: shared vectors", sharedVectors],
+	["compression: algorithms round trip", algorithmsRoundTrip],
+	["compression: packages round trip", packagesRoundTrip],
+	["compression: corrupted body", corruptedBody],
+];
@@ -0,0 +1,30 @@
+import * as checksum from "./checksum";
+import * as codecs from "./codecs";
+import * as compact from "./compact";
+import * as compression from "./compression";
+import * as limits from "./limits";
+import * as negotiation from "./negotiation";
+import { Test } from "./common";
+
+const tests: Test[] = ([] as Test[]).concat(
+	checksum.tests,
+	codecs.tests,
+	compact.tests,
+	compression.tests,
+	limits.tests,
+	negotiation.tests
+);
+
+let failed: number = 0;
+tests.forEach(([name, test]) => {
+	try {
+		test();
+		console.log(`[OK]\t${name}`);
+	} catch (err) {
+		failed += 1;
+		console.log(`[FAIL]\t${name}: ${err instanceof Error ? err.message : err}`);
+	}
+});
+if (failed > 0) {
+	process.exit(1);
+}
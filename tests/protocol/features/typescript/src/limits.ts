@@ -0,0 +1,138 @@
+import * as Binary from "../../generated/ts/binary/consumer/protocol/protocol";
+import * as MsgPack from "../../generated/ts/msgpack/consumer/protocol/protocol";
+import * as Cbor from "../../generated/ts/cbor/consumer/protocol/protocol";
+import * as Compact from "../../generated/ts/compact/consumer/protocol/protocol";
+import {
+	ILimits,
+	ProtocolLimits,
+	MessageHeader,
+} from "../../generated/ts/binary/consumer/protocol/protocol";
+import { Test, vector, equal, unwrap } from "./common";
+
+interface IPackable {
+	pack(sequence: number, uuid?: string): ArrayBufferLike;
+}
+
+interface IReader {
+	chunk(buffer: Buffer, uuid?: string): Error[] | undefined;
+	next(): { header: { sequence: number } } | undefined;
+	pending(): number;
+	len(): number;
+}
+
+interface IProtocol {
+	item(): IPackable;
+	empty(): IPackable;
+	reader(limits: ILimits): IReader;
+}
+
+function protocol(target: any): IProtocol {
+	return {
+		item: () => unwrap(target.Store.Item.fromJson(vector("codecs").item), "item"),
+		empty: () => target.Store.Empty.defaults(),
+		reader: (limits: ILimits) => new target.BufferReaderMessages(limits),
+	};
+}
+
+const protocols: Array<[string, IProtocol]> = [
+	["binary", protocol(Binary)],
+	["msgpack", protocol(MsgPack)],
+	["cbor", protocol(Cbor)],
+	["compact", protocol(Compact)],
+];
+
+function limits(changes: Partial<ILimits>): ILimits {
+	return { ...ProtocolLimits.defaults(), ...changes };
+}
+
+function packed(...packages: ArrayBufferLike[]): Buffer {
+	return Buffer.concat(packages.map((pkg: ArrayBufferLike) => Buffer.from(pkg)));
+}
+
+function sequences(reader: IReader): number[] {
+	const received: number[] = [];
+	let msg = reader.next();
+	while (msg !== undefined) {
+		received.push(msg.header.sequence);
+		msg = reader.next();
+	}
+	return received;
+}
+
+// Store.Empty goes after item to check, that package with exceeded limit doesn't break stream
+function decoding() {
+	protocols.forEach(([codec, target]) => {
+		const bytes = packed(target.item().pack(1), target.empty().pack(2));
+		// Title of item is 35 bytes
+		[limits({}), limits({ string: 35 })].forEach((within: ILimits) => {
+			const reader = target.reader(within);
+			const errors = reader.chunk(bytes);
+			equal(errors, undefined, `${codec}: errors within ${JSON.stringify(within)}`);
+			equal(sequences(reader).join(","), "1,2", `${codec}: received within limits`);
+		});
+		// Blob has 5 items
+		[limits({ string: 34 }), limits({ items: 4 }), limits({ depth: 1 })].forEach(
+			(exceeded: ILimits) => {
+				const reader = target.reader(exceeded);
+				const errors = reader.chunk(bytes);
+				equal(
+					errors === undefined ? 0 : errors.length,
+					1,
+					`${codec}: errors with ${JSON.stringify(exceeded)}`
+				);
+				equal(sequences(reader).join(","), "2", `${codec}: received with exceeded limit`);
+			}
+		);
+	});
+}
+
+function frame() {
+	const [, target] = protocols[0];
+	const bytes = packed(target.item().pack(1));
+	const body = new MessageHeader(bytes).len;
+	const within = target.reader(limits({ frame: body }));
+	equal(within.chunk(bytes), undefined, "errors within frame");
+	equal(sequences(within).join(","), "1", "received within frame");
+	const exceeded = target.reader(limits({ frame: body - 1 }));
+	const errors = exceeded.chunk(bytes);
+	equal(errors === undefined ? 0 : errors.length, 1, "errors with exceeded frame");
+	equal(exceeded.len(), 0, "buffer after exceeded frame");
+	equal(exceeded.next(), undefined, "received with exceeded frame");
+}
+
+function hostileHeader() {
+	const [, target] = protocols[0];
+	const bytes = packed(target.empty().pack(1));
+	// Length of body goes after timestamp; flags are kept in highest bits
+	bytes.writeUInt32LE(
+		0xffffffff,
+		MessageHeader.ID_LENGTH +
+			MessageHeader.SIGN_LENGTH +
+			MessageHeader.SEQ_LENGTH +
+			MessageHeader.TS_LENGTH
+	);
+	const reader = target.reader(limits({}));
+	const errors = reader.chunk(bytes);
+	equal(errors === undefined ? 0 : errors.length, 1, "errors of hostile header");
+	equal(reader.len(), 0, "buffer after hostile header");
+}
+
+function pending() {
+	const [, target] = protocols[0];
+	const bytes = packed(target.empty().pack(1), target.empty().pack(2));
+	const reader = target.reader(limits({ pending: 1 }));
+	const errors = reader.chunk(bytes);
+	equal(errors === undefined ? 0 : errors.length, 1, "errors with exceeded pending");
+	equal(reader.pending(), 1, "pending messages");
+	equal(sequences(reader).join(","), "1", "received with exceeded pending");
+	// Second package is kept in buffer and is read, when queue has space
+	equal(reader.chunk(Buffer.alloc(0)), undefined, "errors after queue is free");
+	equal(sequences(reader).join(","), "2", "received after queue is free");
+}
+
+export const tests: Test[] = [
+	["limits: decoding", decoding],
+	["limits: frame", frame],
+	["limits: hostile header", hostileHeader],
+	["limits: pending", pending],
+];
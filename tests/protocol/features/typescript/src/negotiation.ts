@@ -0,0 +1,148 @@
+import {
+	BufferReaderMessages,
+	CompressionAlgorithm,
+	IAgreement,
+	ICapabilities,
+	MessageHeader,
+	PackingCapabilities,
+	PackingCompression,
+	ProtocolLimits,
+	Store,
+	codec,
+	setCompression,
+	getCompression,
+} from "../../generated/ts/binary/consumer/protocol/protocol";
+import { Test, vector, equal, unwrap } from "./common";
+
+function local(): ICapabilities {
+	return PackingCapabilities.local(codec(), ProtocolLimits.defaults());
+}
+
+function item(): Store.Item {
+	return unwrap(Store.Item.fromJson(vector("codecs").item), "item");
+}
+
+function header(packed: ArrayBufferLike): MessageHeader {
+	return new MessageHeader(Buffer.from(packed));
+}
+
+// Packs with agreement for peer; agreement is removed after
+function packFor(agreement: IAgreement, pack: (uuid: string) => ArrayBufferLike): ArrayBufferLike {
+	const uuid = "peer";
+	PackingCapabilities.set(uuid, agreement);
+	try {
+		return pack(uuid);
+	} finally {
+		PackingCapabilities.remove(uuid);
+	}
+}
+
+function negotiate(own: ICapabilities, peer: ICapabilities): IAgreement {
+	return unwrap(PackingCapabilities.negotiate(own, peer), "agreement");
+}
+
+function commonSettings() {
+	const agreement = negotiate(local(), {
+		...local(),
+		version: 1,
+		compression: [PackingCompression.FLAG_LZ4],
+		frame: BigInt(1024),
+		heartbeat: true,
+	});
+	equal(agreement.version, 1, "version");
+	equal(agreement.compression, PackingCompression.FLAG_LZ4, "compression");
+	equal(agreement.encryption, undefined, "encryption");
+	equal(agreement.frame, BigInt(1024), "frame");
+	// Runtime itself doesn't send heartbeat packages
+	equal(agreement.heartbeat, false, "heartbeat");
+	equal(agreement.clock, undefined, "clock");
+	const own = negotiate(local(), local());
+	equal(own.version, MessageHeader.VERSION, "own version");
+	equal(own.compression, PackingCompression.FLAGS, "own compression");
+}
+
+function negotiationErrors() {
+	const codecs = PackingCapabilities.negotiate(local(), { ...local(), codec: "msgpack" });
+	equal(codecs instanceof Error && codecs.message.includes("Codec"), true, "codec error");
+	const encryption: ICapabilities = { ...local(), encryption: ["aes_gcm"] };
+	const missed = PackingCapabilities.negotiate(local(), encryption);
+	equal(missed instanceof Error && missed.message.includes("encryption"), true, "encryption error");
+	const own: ICapabilities = { ...local(), encryption: ["chacha20_poly1305"] };
+	equal(PackingCapabilities.negotiate(own, encryption) instanceof Error, true, "other encryption");
+}
+
+// Legacy peer (runtime before negotiation) reads headers of version 0 without compression
+function legacyPeer() {
+	const agreement = negotiate(local(), PackingCapabilities.legacy(codec()));
+	equal(agreement.version, 0, "version");
+	equal(agreement.compression, 0, "compression");
+	const compressible = unwrap(
+		Store.Item.fromJson({ ...vector("codecs").item, title: "compressible ".repeat(200) }),
+		"compressible item"
+	);
+	const defaults = getCompression();
+	setCompression({ algorithm: CompressionAlgorithm.Lz4, threshold: 0 });
+	const packed = (() => {
+		try {
+			return packFor(agreement, (uuid: string) => compressible.pack(1, uuid));
+		} finally {
+			setCompression(defaults);
+		}
+	})();
+	equal(header(packed).version, 0, "version of header");
+	equal(header(packed).flags & PackingCompression.FLAGS, 0, "compression of body");
+	const reader = new BufferReaderMessages();
+	equal(reader.chunk(Buffer.from(packed)), undefined, "errors");
+	const received = reader.next();
+	equal(received === undefined ? undefined : received.header.version, 0, "received version");
+}
+
+function versionOfHeader() {
+	// Until agreement peer might be older runtime
+	equal(header(item().pack(1)).version, 0, "version before agreement");
+	const packed = packFor(negotiate(local(), local()), (uuid: string) => item().pack(1, uuid));
+	equal(header(packed).version, MessageHeader.VERSION, "version after agreement");
+	const reader = new BufferReaderMessages();
+	equal(reader.chunk(Buffer.from(packed)), undefined, "errors");
+	const received = reader.next();
+	equal(
+		received === undefined ? undefined : JSON.stringify(received.getRef().toJson()),
+		JSON.stringify(item().toJson()),
+		"received item"
+	);
+}
+
+function unsupportedVersion() {
+	const packed = Buffer.from(item().pack(1));
+	// Highest byte of body size (last byte of header) keeps flags of package
+	packed[MessageHeader.SIZE - 1] |= (MessageHeader.VERSION + 1) << MessageHeader.VERSION_SHIFT;
+	const reader = new BufferReaderMessages();
+	const errors = reader.chunk(packed);
+	equal(errors === undefined ? 0 : errors.length, 1, "errors");
+	equal(reader.next(), undefined, "received message");
+}
+
+function frameOfPeer() {
+	const agreement = negotiate(local(), {
+		...local(),
+		frame: BigInt(item().encode().byteLength - 1),
+	});
+	// Peer cannot read package bigger than its limit
+	let err: Error | undefined;
+	try {
+		packFor(agreement, (uuid: string) => item().pack(1, uuid));
+	} catch (e) {
+		err = e instanceof Error ? e : new Error(`${e}`);
+	}
+	equal(err instanceof Error, true, "package bigger than limit of peer");
+	packFor(agreement, (uuid: string) => Store.Empty.defaults().pack(2, uuid));
+}
+
+export const tests: Test[] = [
+	["negotiation: common settings", commonSettings],
+	["negotiation: errors", negotiationErrors],
+	["negotiation: legacy peer", legacyPeer],
+	["negotiation: version of header", versionOfHeader],
+	["negotiation: unsupported version", unsupportedVersion],
+	["negotiation: frame of peer", frameOfPeer],
+];
@@ -6,8 +6,10 @@
 use std::convert::TryFrom;
 use std::io::Cursor;
 use std::collections::{ HashMap };
-use bytes::{ Buf };
+use bytes::{ Buf, BufMut };
 use std::time::{ SystemTime, UNIX_EPOCH };
+use std::sync::atomic::{ AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering };
+use std::sync::Mutex;
 
 pub mod sizes {
     use std::mem;
@@ -33,6 +35,131 @@ pub enum ESize {
     U64(u64),
 }
 
+// Limits of incoming data. Buffer checks size of frames and count of pending messages;
+// other limits are applied while message is decoded
+#[derive(Debug, Clone)]
+pub struct Limits {
+    // Max length of package body in bytes
+    pub frame: usize,
+    // Max length of string in bytes
+    pub string: usize,
+    // Max count of items in array
+    pub items: usize,
+    // Max nesting of structs and enums
+    pub depth: usize,
+    // Max count of decoded messages, which aren't taken from buffer
+    pub pending: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            frame: 64 * 1024 * 1024,
+            string: 16 * 1024 * 1024,
+            items: 4 * 1024 * 1024,
+            depth: 64,
+            pending: 4096,
+        }
+    }
+}
+
+struct LimitsContext {
+    limits: Limits,
+    depth: usize,
+    exceeded: Option<String>,
+}
+
+thread_local! {
+    static LIMITS: std::cell::RefCell<Option<LimitsContext>> = std::cell::RefCell::new(None);
+}
+
+// Applies limits to everything decoded by decode. Returns description of exceeded
+// limit (if it was) beside result
+pub fn with_limits<T>(limits: &Limits, decode: impl FnOnce() -> T) -> (T, Option<String>) {
+    let prev = LIMITS.with(|ctx| {
+        ctx.replace(Some(LimitsContext {
+            limits: limits.clone(),
+            depth: 0,
+            exceeded: None,
+        }))
+    });
+    let result = decode();
+    let ctx = LIMITS.with(|ctx| ctx.replace(prev));
+    (result, ctx.and_then(|ctx| ctx.exceeded))
+}
+
+fn limits_check(check: impl FnOnce(&mut LimitsContext) -> Option<String>) -> Result<(), String> {
+    LIMITS.with(|ctx| {
+        if let Some(ctx) = ctx.borrow_mut().as_mut() {
+            if let Some(err) = check(ctx) {
+                ctx.exceeded = Some(err.clone());
+                return Err(err);
+            }
+        }
+        Ok(())
+    })
+}
+
+pub fn limits_frame(len: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if len > ctx.limits.frame {
+            Some(format!(
+                "Size of decompressed body ({} bytes) is bigger than limit ({} bytes)",
+                len, ctx.limits.frame
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_string(len: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if len > ctx.limits.string {
+            Some(format!(
+                "Length of string ({} bytes) is bigger than limit ({} bytes)",
+                len, ctx.limits.string
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_items(count: usize) -> Result<(), String> {
+    limits_check(|ctx| {
+        if count > ctx.limits.items {
+            Some(format!(
+                "Count of items in array ({}) is bigger than limit ({})",
+                count, ctx.limits.items
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn limits_nested<T>(decode: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    limits_check(|ctx| {
+        if ctx.depth >= ctx.limits.depth {
+            Some(format!(
+                "Nesting is deeper than limit ({})",
+                ctx.limits.depth
+            ))
+        } else {
+            ctx.depth += 1;
+            None
+        }
+    })?;
+    let result = decode();
+    LIMITS.with(|ctx| {
+        if let Some(ctx) = ctx.borrow_mut().as_mut() {
+            ctx.depth -= 1;
+        }
+    });
+    result
+}
+
 pub enum Source<'a> {
     Storage(&'a mut Storage),
     Buffer(&'a Vec<u8>),
@@ -46,17 +173,19 @@ where
     fn defaults() -> Self;
     fn extract_from_storage(&mut self, storage: Storage) -> Result<(), String>;
     fn extract(buf: Vec<u8>) -> Result<Self, String> {
-        let mut instance: Self = Self::defaults();
-        let storage = match Storage::new(buf) {
-            Ok(storage) => storage,
-            Err(e) => {
-                return Err(e);
+        limits_nested(|| {
+            let mut instance: Self = Self::defaults();
+            let storage = match Storage::new(buf) {
+                Ok(storage) => storage,
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+            match instance.extract_from_storage(storage) {
+                Ok(()) => Ok(instance),
+                Err(e) => Err(e),
             }
-        };
-        match instance.extract_from_storage(storage) {
-            Ok(()) => Ok(instance),
-            Err(e) => Err(e),
-        }
+        })
     }
 }
 
@@ -96,7 +225,7 @@ where
 {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<T, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
-            Self::extract(buf.clone())
+            limits_nested(|| Self::extract(buf.clone()))
         } else {
             Err("Fail get buffer".to_string())
         }
@@ -130,7 +259,8 @@ where
                 buffer = buffer
                     .drain((sizes::U64_LEN + item_len as usize)..)
                     .collect();
-                match T::extract(item_buf) {
+                limits_items(res.len() + 1)?;
+                match limits_nested(|| T::extract(item_buf)) {
                     Ok(i) => res.push(i),
                     Err(e) => {
                         return Err(e);
@@ -324,6 +454,7 @@ impl Decode<bool> for bool {
 impl Decode<String> for String {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<String, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_string(buf.len())?;
             Ok(String::from_utf8_lossy(buf).to_string())
         } else {
             Err("Fail get buffer".to_string())
@@ -337,17 +468,19 @@ where
 {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<T, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
-            let sctruct_storage = match Storage::new(buf.to_vec()) {
-                Ok(storage) => storage,
-                Err(e) => {
-                    return Err(e);
+            limits_nested(|| {
+                let sctruct_storage = match Storage::new(buf.to_vec()) {
+                    Ok(storage) => storage,
+                    Err(e) => {
+                        return Err(e);
+                    }
+                };
+                let mut strct: T = T::defaults();
+                match strct.extract_from_storage(sctruct_storage) {
+                    Ok(_) => Ok(strct),
+                    Err(e) => Err(e),
                 }
-            };
-            let mut strct: T = T::defaults();
-            match strct.extract_from_storage(sctruct_storage) {
-                Ok(_) => Ok(strct),
-                Err(e) => Err(e),
-            }
+            })
         } else {
             Err("Fail get buffer".to_string())
         }
@@ -357,6 +490,7 @@ where
 impl Decode<Vec<u8>> for Vec<u8> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u8>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U8_LEN)?;
             let mut res: Vec<u8> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -375,6 +509,7 @@ impl Decode<Vec<u8>> for Vec<u8> {
 impl Decode<Vec<u16>> for Vec<u16> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u16>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U16_LEN)?;
             let mut res: Vec<u16> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -397,6 +532,7 @@ impl Decode<Vec<u16>> for Vec<u16> {
 impl Decode<Vec<u32>> for Vec<u32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U32_LEN)?;
             let mut res: Vec<u32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -419,6 +555,7 @@ impl Decode<Vec<u32>> for Vec<u32> {
 impl Decode<Vec<u64>> for Vec<u64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<u64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U64_LEN)?;
             let mut res: Vec<u64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -441,6 +578,7 @@ impl Decode<Vec<u64>> for Vec<u64> {
 impl Decode<Vec<i8>> for Vec<i8> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i8>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I8_LEN)?;
             let mut res: Vec<i8> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -459,6 +597,7 @@ impl Decode<Vec<i8>> for Vec<i8> {
 impl Decode<Vec<i16>> for Vec<i16> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i16>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I16_LEN)?;
             let mut res: Vec<i16> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -481,6 +620,7 @@ impl Decode<Vec<i16>> for Vec<i16> {
 impl Decode<Vec<i32>> for Vec<i32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I32_LEN)?;
             let mut res: Vec<i32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -503,6 +643,7 @@ impl Decode<Vec<i32>> for Vec<i32> {
 impl Decode<Vec<i64>> for Vec<i64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<i64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::I64_LEN)?;
             let mut res: Vec<i64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -525,6 +666,7 @@ impl Decode<Vec<i64>> for Vec<i64> {
 impl Decode<Vec<f32>> for Vec<f32> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<f32>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::F32_LEN)?;
             let mut res: Vec<f32> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -547,6 +689,7 @@ impl Decode<Vec<f32>> for Vec<f32> {
 impl Decode<Vec<f64>> for Vec<f64> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<f64>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::F64_LEN)?;
             let mut res: Vec<f64> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             let len = buf.len() as u64;
@@ -569,6 +712,7 @@ impl Decode<Vec<f64>> for Vec<f64> {
 impl Decode<Vec<bool>> for Vec<bool> {
     fn get_from_storage(source: Source, id: Option<u16>) -> Result<Vec<bool>, String> {
         if let Ok(buf) = Self::get_buf_from_source(source, id) {
+            limits_items(buf.len() / sizes::U8_LEN)?;
             let mut res: Vec<bool> = vec![];
             let mut cursor: Cursor<&[u8]> = Cursor::new(buf);
             loop {
@@ -599,6 +743,8 @@ impl Decode<Vec<String>> for Vec<String> {
                     return Err(format!("To extract length of string (u32) value from array buffer should have length at least {} bytes, but length is {}", sizes::U32_LEN, buf.len()));
                 }
                 let item_len: u32 = cursor.get_u32_le();
+                limits_string(item_len as usize)?;
+                limits_items(res.len() + 1)?;
                 if buffer.len() < sizes::U32_LEN + item_len as usize {
                     return Err(format!("Cannot extract string, because expecting {} bytes, but length of buffer is {}", item_len, (buffer.len() - sizes::U32_LEN)));
                 }
@@ -644,19 +790,13 @@ where
                 buffer = buffer
                     .drain((sizes::U64_LEN + item_len as usize)..)
                     .collect();
-                let sctruct_storage = match Storage::new(item_buf) {
-                    Ok(storage) => storage,
-                    Err(e) => {
-                        return Err(e);
-                    }
-                };
-                let mut strct: T = T::defaults();
-                match strct.extract_from_storage(sctruct_storage) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(e);
-                    }
-                }
+                limits_items(res.len() + 1)?;
+                let strct = limits_nested(|| {
+                    let sctruct_storage = Storage::new(item_buf)?;
+                    let mut strct: T = T::defaults();
+                    strct.extract_from_storage(sctruct_storage)?;
+                    Ok(strct)
+                })?;
                 res.push(strct);
             }
             Ok(res)
@@ -686,213 +826,1084 @@ where
     }
 }
 
-fn get_value_buffer(id: Option<u16>, size: ESize, mut value: Vec<u8>) -> Result<Vec<u8>, String> {
-    let mut buffer: Vec<u8> = vec![];
-    if let Some(id) = id {
-        buffer.append(&mut id.to_le_bytes().to_vec());
-        match size {
-            ESize::U8(size) => {
-                buffer.append(&mut 8_u8.to_le_bytes().to_vec());
-                buffer.append(&mut size.to_le_bytes().to_vec());
-            }
-            ESize::U16(size) => {
-                buffer.append(&mut 16_u8.to_le_bytes().to_vec());
-                buffer.append(&mut size.to_le_bytes().to_vec());
-            }
-            ESize::U32(size) => {
-                buffer.append(&mut 32_u8.to_le_bytes().to_vec());
-                buffer.append(&mut size.to_le_bytes().to_vec());
-            }
-            ESize::U64(size) => {
-                buffer.append(&mut 64_u8.to_le_bytes().to_vec());
-                buffer.append(&mut size.to_le_bytes().to_vec());
-            }
-        };
-    }
-    buffer.append(&mut value);
-    Ok(buffer)
+// Body of package can be compressed with deflate (RFC 1951) or LZ4 (block format). Compressed
+// body: | u32 length of original body | compressed data |. Algorithm is marked with flag in
+// header of package, so compressed and not compressed packages can be mixed in one stream.
+// Typescript runtime has same implementation and gives same bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    None,
+    Deflate,
+    Lz4,
 }
 
-pub fn get_empty_buffer_val(id: Option<u16>) -> Result<Vec<u8>, String> {
-    get_value_buffer(id, ESize::U8(0), vec![])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    pub algorithm: CompressionAlgorithm,
+    // Bodies, which are smaller (in bytes), aren't compressed
+    pub threshold: usize,
 }
 
-pub trait StructEncode {
-    fn get_id(&self) -> u32;
-    fn get_signature(&self) -> u16;
-    fn abduct(&mut self) -> Result<Vec<u8>, String>;
+impl Default for Compression {
+    fn default() -> Self {
+        Compression {
+            algorithm: CompressionAlgorithm::None,
+            threshold: 1024,
+        }
+    }
 }
 
-pub trait EnumEncode {
-    fn get_id(&self) -> u32;
-    fn get_signature(&self) -> u16;
-    fn abduct(&mut self) -> Result<Vec<u8>, String>;
+pub const COMPRESSION_FLAG_DEFLATE: u8 = 1;
+pub const COMPRESSION_FLAG_LZ4: u8 = 2;
+
+static COMPRESSION_ALGORITHM: AtomicU8 = AtomicU8::new(0);
+static COMPRESSION_THRESHOLD: AtomicUsize = AtomicUsize::new(1024);
+
+// Compression is used for all packages, which are packed by current process. Incoming packages
+// are decompressed in any case
+pub fn set_compression(compression: &Compression) {
+    COMPRESSION_ALGORITHM.store(
+        match compression.algorithm {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Deflate => COMPRESSION_FLAG_DEFLATE,
+            CompressionAlgorithm::Lz4 => COMPRESSION_FLAG_LZ4,
+        },
+        Ordering::Relaxed,
+    );
+    COMPRESSION_THRESHOLD.store(compression.threshold, Ordering::Relaxed);
 }
 
-pub trait EncodeEnum {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String>;
-    fn encode(&mut self) -> Result<Vec<u8>, String> {
-        self.get_buf_to_store(None)
+pub fn get_compression() -> Compression {
+    Compression {
+        algorithm: match COMPRESSION_ALGORITHM.load(Ordering::Relaxed) {
+            COMPRESSION_FLAG_DEFLATE => CompressionAlgorithm::Deflate,
+            COMPRESSION_FLAG_LZ4 => CompressionAlgorithm::Lz4,
+            _ => CompressionAlgorithm::None,
+        },
+        threshold: COMPRESSION_THRESHOLD.load(Ordering::Relaxed),
     }
 }
 
-impl<T> EncodeEnum for T
-where
-    T: EnumEncode,
-{
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        match self.abduct() {
-            Ok(buf) => get_value_buffer(id, ESize::U64(buf.len() as u64), buf.to_vec()),
-            Err(e) => Err(e),
-        }
+// Returns flag of algorithm, which should be used for body of given size; 0 - no compression.
+// Configured algorithm is used, if peer can read it (readable - flags of algorithms); otherwise
+// other algorithm, which peer can read
+pub fn compression_flag(len: usize, readable: u8) -> u8 {
+    let flag = match COMPRESSION_ALGORITHM.load(Ordering::Relaxed) {
+        0 => 0,
+        flag if flag & readable != 0 => flag,
+        _ => readable & readable.wrapping_neg(),
+    };
+    if flag == 0 || len < COMPRESSION_THRESHOLD.load(Ordering::Relaxed) || len > u32::MAX as usize {
+        0
+    } else {
+        flag
     }
 }
 
-impl<T> EncodeEnum for Vec<T>
-where
-    T: EnumEncode,
-{
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec![];
-        for val in self.iter_mut() {
-            let val_as_bytes = match val.abduct() {
-                Ok(buf) => buf,
-                Err(e) => {
-                    return Err(e);
-                }
-            };
-            buffer.append(&mut (val_as_bytes.len() as u64).to_le_bytes().to_vec());
-            buffer.append(&mut val_as_bytes.to_vec());
+// Body is kept as it is, if compression doesn't make it smaller
+pub fn compress(body: Vec<u8>, readable: u8) -> (u8, Vec<u8>) {
+    let flag = compression_flag(body.len(), readable);
+    let data = match compress_body(flag, &body) {
+        Some(data) => data,
+        None => {
+            return (0, body);
         }
-        get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
+    };
+    if data.len() + 4 >= body.len() {
+        return (0, body);
     }
+    let mut compressed: Vec<u8> = Vec::with_capacity(data.len() + 4);
+    compressed.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    compressed.extend_from_slice(&data);
+    (flag, compressed)
 }
 
-pub trait Encode {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String>;
-    fn encode(&mut self) -> Result<Vec<u8>, String> {
-        self.get_buf_to_store(None)
+pub fn decompress(flag: u8, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    if flag == 0 {
+        return Ok(body);
+    }
+    if body.len() < 4 {
+        return Err(String::from("Compressed body is too small"));
     }
+    let len = u32::from_le_bytes([body[0], body[1], body[2], body[3]]) as usize;
+    limits_frame(len)?;
+    let decompressed = decompress_body(flag, &body[4..], len)?;
+    if decompressed.len() != len {
+        return Err(format!(
+            "Decompressed body has {} bytes, but expected {} bytes",
+            decompressed.len(),
+            len
+        ));
+    }
+    Ok(decompressed)
 }
 
-impl Encode for u8 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::U8_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+// Algorithms are kept apart from settings of compression, so standalone protocol crate
+// can be built without them
+pub const COMPRESSION_FLAGS: u8 = COMPRESSION_FLAG_DEFLATE | COMPRESSION_FLAG_LZ4;
+
+// Returns None, if flag doesn't define known algorithm
+pub(crate) fn compress_body(flag: u8, body: &[u8]) -> Option<Vec<u8>> {
+    match flag {
+        COMPRESSION_FLAG_DEFLATE => Some(deflate_compress(body)),
+        COMPRESSION_FLAG_LZ4 => Some(lz4_compress(body)),
+        _ => None,
     }
 }
 
-impl Encode for u16 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::U16_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+pub(crate) fn decompress_body(flag: u8, body: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    match flag {
+        COMPRESSION_FLAG_DEFLATE => deflate_decompress(body, len),
+        COMPRESSION_FLAG_LZ4 => lz4_decompress(body, len),
+        _ => Err(format!("Unknown compression flag: {}", flag)),
     }
 }
 
-impl Encode for u32 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::U32_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
-    }
+
+fn compression_hash(value: u32, bits: u32) -> usize {
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - bits)) as usize
 }
 
-impl Encode for u64 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::U64_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
-    }
+const LZ4_HASH_BITS: u32 = 12;
+const LZ4_MIN_MATCH: usize = 4;
+// Last 5 bytes are always literals; last match starts 12 bytes before end at least
+const LZ4_LAST_LITERALS: usize = 5;
+const LZ4_MF_LIMIT: usize = 12;
+const LZ4_MAX_OFFSET: usize = 65535;
+
+fn lz4_read_u32(src: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([src[pos], src[pos + 1], src[pos + 2], src[pos + 3]])
 }
 
-impl Encode for i8 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::I8_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+fn lz4_put_len(mut len: usize, out: &mut Vec<u8>) {
+    while len >= 255 {
+        out.push(255);
+        len -= 255;
     }
+    out.push(len as u8);
 }
 
-impl Encode for i16 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::I16_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+fn lz4_put_sequence(literals: &[u8], matched: Option<(usize, usize)>, out: &mut Vec<u8>) {
+    let match_len = matched.map(|(_, len)| len - LZ4_MIN_MATCH).unwrap_or(0);
+    out.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literals.len() >= 15 {
+        lz4_put_len(literals.len() - 15, out);
+    }
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            lz4_put_len(match_len - 15, out);
+        }
     }
 }
 
-impl Encode for i32 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::I32_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+fn lz4_compress(src: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::with_capacity(src.len());
+    let mut table: Vec<usize> = vec![usize::MAX; 1 << LZ4_HASH_BITS];
+    let mut anchor: usize = 0;
+    let mut pos: usize = 0;
+    while pos + LZ4_MF_LIMIT <= src.len() {
+        let sequence = lz4_read_u32(src, pos);
+        let hash = compression_hash(sequence, LZ4_HASH_BITS);
+        let candidate = table[hash];
+        table[hash] = pos;
+        if candidate != usize::MAX
+            && pos - candidate <= LZ4_MAX_OFFSET
+            && lz4_read_u32(src, candidate) == sequence
+        {
+            let mut len = LZ4_MIN_MATCH;
+            while pos + len < src.len() - LZ4_LAST_LITERALS
+                && src[candidate + len] == src[pos + len]
+            {
+                len += 1;
+            }
+            lz4_put_sequence(&src[anchor..pos], Some((pos - candidate, len)), &mut out);
+            pos += len;
+            anchor = pos;
+        } else {
+            pos += 1;
+        }
     }
+    lz4_put_sequence(&src[anchor..], None, &mut out);
+    out
 }
 
-impl Encode for i64 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::I64_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+fn lz4_read_len(src: &[u8], pos: &mut usize) -> Result<usize, String> {
+    let mut len: usize = 0;
+    loop {
+        let byte = *src
+            .get(*pos)
+            .ok_or_else(|| String::from("LZ4: unexpected end of data"))?;
+        *pos += 1;
+        len += byte as usize;
+        if byte != 255 {
+            return Ok(len);
+        }
     }
 }
 
-impl Encode for f32 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::F32_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+fn lz4_decompress(src: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut out: Vec<u8> = Vec::new();
+    let mut pos: usize = 0;
+    loop {
+        let token = *src
+            .get(pos)
+            .ok_or_else(|| String::from("LZ4: unexpected end of data"))?;
+        pos += 1;
+        let mut literals = (token >> 4) as usize;
+        if literals == 15 {
+            literals += lz4_read_len(src, &mut pos)?;
+        }
+        if src.len() - pos < literals || len - out.len() < literals {
+            return Err(String::from("LZ4: literals are out of bounds"));
+        }
+        out.extend_from_slice(&src[pos..pos + literals]);
+        pos += literals;
+        // Last sequence has only literals
+        if pos == src.len() {
+            return Ok(out);
+        }
+        if src.len() - pos < 2 {
+            return Err(String::from("LZ4: unexpected end of data"));
+        }
+        let offset = u16::from_le_bytes([src[pos], src[pos + 1]]) as usize;
+        pos += 2;
+        if offset == 0 || offset > out.len() {
+            return Err(format!("LZ4: invalid offset {}", offset));
+        }
+        let mut match_len = (token & 15) as usize;
+        if match_len == 15 {
+            match_len += lz4_read_len(src, &mut pos)?;
+        }
+        match_len += LZ4_MIN_MATCH;
+        if len - out.len() < match_len {
+            return Err(String::from("LZ4: match is out of bounds"));
+        }
+        let start = out.len() - offset;
+        for i in 0..match_len {
+            let byte = out[start + i];
+            out.push(byte);
+        }
     }
 }
 
-impl Encode for f64 {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::F64_LEN as u8),
-            self.to_le_bytes().to_vec(),
-        )
+const DEFLATE_HASH_BITS: u32 = 15;
+const DEFLATE_MIN_MATCH: usize = 3;
+const DEFLATE_MAX_MATCH: usize = 258;
+const DEFLATE_WINDOW: usize = 32768;
+const DEFLATE_LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const DEFLATE_LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DEFLATE_DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DEFLATE_DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const DEFLATE_CODES_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct DeflateWriter {
+    out: Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl DeflateWriter {
+    fn put(&mut self, value: u32, len: u32) {
+        self.bits |= value << self.count;
+        self.count += len;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
     }
-}
 
-impl Encode for bool {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        get_value_buffer(
-            id,
-            ESize::U8(sizes::BOOL_LEN as u8),
-            if self == &true { vec![1] } else { vec![0] },
-        )
+    // Huffman codes are written starting from most significant bit
+    fn put_code(&mut self, code: u32, len: u32) {
+        let mut reversed: u32 = 0;
+        for i in 0..len {
+            reversed |= ((code >> i) & 1) << (len - 1 - i);
+        }
+        self.put(reversed, len);
     }
-}
 
-impl Encode for String {
-    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
-        let buf = self.as_bytes();
-        get_value_buffer(id, ESize::U64(buf.len() as u64), buf.to_vec())
+    fn put_symbol(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.put_code(0x30 + symbol, 8),
+            144..=255 => self.put_code(0x190 + symbol - 144, 9),
+            256..=279 => self.put_code(symbol - 256, 7),
+            _ => self.put_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
     }
 }
 
-impl<T> Encode for T
+fn deflate_code(base: &[u16], value: usize) -> usize {
+    base.iter()
+        .rposition(|b| (*b as usize) <= value)
+        .unwrap_or(0)
+}
+
+// One final block with fixed Huffman codes
+fn deflate_compress(src: &[u8]) -> Vec<u8> {
+    let mut writer = DeflateWriter {
+        out: Vec::with_capacity(src.len()),
+        bits: 0,
+        count: 0,
+    };
+    writer.put(1, 1);
+    writer.put(1, 2);
+    let mut table: Vec<usize> = vec![usize::MAX; 1 << DEFLATE_HASH_BITS];
+    let mut pos: usize = 0;
+    while pos < src.len() {
+        let mut matched: Option<(usize, usize)> = None;
+        if pos + DEFLATE_MIN_MATCH <= src.len() {
+            let hash = compression_hash(
+                ((src[pos] as u32) << 16) | ((src[pos + 1] as u32) << 8) | src[pos + 2] as u32,
+                DEFLATE_HASH_BITS,
+            );
+            let candidate = table[hash];
+            table[hash] = pos;
+            if candidate != usize::MAX && pos - candidate <= DEFLATE_WINDOW {
+                let mut len: usize = 0;
+                while len < DEFLATE_MAX_MATCH
+                    && pos + len < src.len()
+                    && src[candidate + len] == src[pos + len]
+                {
+                    len += 1;
+                }
+                if len >= DEFLATE_MIN_MATCH {
+                    matched = Some((pos - candidate, len));
+                }
+            }
+        }
+        if let Some((distance, len)) = matched {
+            let code = deflate_code(&DEFLATE_LENGTH_BASE, len);
+            writer.put_symbol(257 + code as u32);
+            writer.put(
+                (len - DEFLATE_LENGTH_BASE[code] as usize) as u32,
+                DEFLATE_LENGTH_EXTRA[code] as u32,
+            );
+            let code = deflate_code(&DEFLATE_DIST_BASE, distance);
+            writer.put_code(code as u32, 5);
+            writer.put(
+                (distance - DEFLATE_DIST_BASE[code] as usize) as u32,
+                DEFLATE_DIST_EXTRA[code] as u32,
+            );
+            pos += len;
+        } else {
+            writer.put_symbol(src[pos] as u32);
+            pos += 1;
+        }
+    }
+    writer.put_symbol(256);
+    writer.finish()
+}
+
+struct DeflateReader<'a> {
+    src: &'a [u8],
+    pos: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl<'a> DeflateReader<'a> {
+    fn get(&mut self, len: u32) -> Result<u32, String> {
+        while self.count < len {
+            let byte = *self
+                .src
+                .get(self.pos)
+                .ok_or_else(|| String::from("Deflate: unexpected end of data"))?;
+            self.bits |= (byte as u32) << self.count;
+            self.count += 8;
+            self.pos += 1;
+        }
+        let value = self.bits & ((1u32 << len) - 1);
+        self.bits >>= len;
+        self.count -= len;
+        Ok(value)
+    }
+}
+
+struct DeflateHuffman {
+    count: [u16; 16],
+    symbol: Vec<u16>,
+}
+
+impl DeflateHuffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut count = [0u16; 16];
+        for len in lengths.iter() {
+            count[*len as usize] += 1;
+        }
+        let mut left: i32 = 1;
+        for len in 1..16 {
+            left = (left << 1) - count[len] as i32;
+            if left < 0 {
+                return Err(String::from("Deflate: over-subscribed Huffman code"));
+            }
+        }
+        let mut offsets = [0usize; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + count[len] as usize;
+        }
+        let mut symbol: Vec<u16> = vec![0; lengths.len()];
+        for (sym, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                symbol[offsets[*len as usize]] = sym as u16;
+                offsets[*len as usize] += 1;
+            }
+        }
+        Ok(DeflateHuffman { count, symbol })
+    }
+
+    fn decode(&self, reader: &mut DeflateReader) -> Result<usize, String> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= reader.get(1)? as i32;
+            let count = self.count[len] as i32;
+            if code - count < first {
+                return Ok(self.symbol[(index + (code - first)) as usize] as usize);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(String::from("Deflate: invalid Huffman code"))
+    }
+}
+
+fn deflate_codes(
+    reader: &mut DeflateReader,
+    lengths: &DeflateHuffman,
+    distances: &DeflateHuffman,
+    out: &mut Vec<u8>,
+    len: usize,
+) -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(reader)?;
+        if symbol < 256 {
+            if out.len() >= len {
+                return Err(String::from("Deflate: data is out of bounds"));
+            }
+            out.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let code = symbol - 257;
+            if code >= DEFLATE_LENGTH_BASE.len() {
+                return Err(format!("Deflate: invalid length code {}", symbol));
+            }
+            let length = DEFLATE_LENGTH_BASE[code] as usize
+                + reader.get(DEFLATE_LENGTH_EXTRA[code] as u32)? as usize;
+            let code = distances.decode(reader)?;
+            if code >= DEFLATE_DIST_BASE.len() {
+                return Err(format!("Deflate: invalid distance code {}", code));
+            }
+            let distance = DEFLATE_DIST_BASE[code] as usize
+                + reader.get(DEFLATE_DIST_EXTRA[code] as u32)? as usize;
+            if distance > out.len() {
+                return Err(format!("Deflate: invalid distance {}", distance));
+            }
+            if len - out.len() < length {
+                return Err(String::from("Deflate: data is out of bounds"));
+            }
+            let start = out.len() - distance;
+            for i in 0..length {
+                let byte = out[start + i];
+                out.push(byte);
+            }
+        }
+    }
+}
+
+fn deflate_dynamic(reader: &mut DeflateReader) -> Result<(DeflateHuffman, DeflateHuffman), String> {
+    let nlen = reader.get(5)? as usize + 257;
+    let ndist = reader.get(5)? as usize + 1;
+    let ncode = reader.get(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(String::from("Deflate: invalid count of codes"));
+    }
+    let mut lengths = [0u8; 19];
+    for index in DEFLATE_CODES_ORDER.iter().take(ncode) {
+        lengths[*index] = reader.get(3)? as u8;
+    }
+    let codes = DeflateHuffman::new(&lengths)?;
+    let mut lengths: Vec<u8> = vec![0; nlen + ndist];
+    let mut index: usize = 0;
+    while index < nlen + ndist {
+        let symbol = codes.decode(reader)?;
+        if symbol < 16 {
+            lengths[index] = symbol as u8;
+            index += 1;
+            continue;
+        }
+        let (len, repeat) = match symbol {
+            16 => {
+                if index == 0 {
+                    return Err(String::from("Deflate: repeat without previous length"));
+                }
+                (lengths[index - 1], 3 + reader.get(2)? as usize)
+            }
+            17 => (0, 3 + reader.get(3)? as usize),
+            _ => (0, 11 + reader.get(7)? as usize),
+        };
+        if index + repeat > nlen + ndist {
+            return Err(String::from("Deflate: too many lengths"));
+        }
+        for length in lengths.iter_mut().skip(index).take(repeat) {
+            *length = len;
+        }
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(String::from("Deflate: no end of block code"));
+    }
+    Ok((
+        DeflateHuffman::new(&lengths[..nlen])?,
+        DeflateHuffman::new(&lengths[nlen..])?,
+    ))
+}
+
+fn deflate_decompress(src: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut reader = DeflateReader {
+        src,
+        pos: 0,
+        bits: 0,
+        count: 0,
+    };
+    let mut out: Vec<u8> = Vec::new();
+    loop {
+        let last = reader.get(1)?;
+        match reader.get(2)? {
+            0 => {
+                // Stored block starts from next byte
+                reader.bits = 0;
+                reader.count = 0;
+                if src.len() - reader.pos < 4 {
+                    return Err(String::from("Deflate: unexpected end of data"));
+                }
+                let size = u16::from_le_bytes([src[reader.pos], src[reader.pos + 1]]);
+                let nsize = u16::from_le_bytes([src[reader.pos + 2], src[reader.pos + 3]]);
+                if size != !nsize {
+                    return Err(String::from("Deflate: invalid size of stored block"));
+                }
+                reader.pos += 4;
+                let size = size as usize;
+                if src.len() - reader.pos < size || len - out.len() < size {
+                    return Err(String::from("Deflate: stored block is out of bounds"));
+                }
+                out.extend_from_slice(&src[reader.pos..reader.pos + size]);
+                reader.pos += size;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                for (symbol, length) in lengths.iter_mut().enumerate() {
+                    *length = match symbol {
+                        0..=143 => 8,
+                        144..=255 => 9,
+                        256..=279 => 7,
+                        _ => 8,
+                    };
+                }
+                deflate_codes(
+                    &mut reader,
+                    &DeflateHuffman::new(&lengths)?,
+                    &DeflateHuffman::new(&[5u8; 30])?,
+                    &mut out,
+                    len,
+                )?;
+            }
+            2 => {
+                let (lengths, distances) = deflate_dynamic(&mut reader)?;
+                deflate_codes(&mut reader, &lengths, &distances, &mut out, len)?;
+            }
+            kind => {
+                return Err(format!("Deflate: invalid type of block {}", kind));
+            }
+        }
+        if last == 1 {
+            return Ok(out);
+        }
+    }
+}
+
+// Checksum is CRC32C (Castagnoli) of body as it's sent (after compression and middleware).
+// Package with checksum is marked with flag in header and has additional u32 field after
+// length of body, so packages with and without checksum can be mixed in one stream.
+// Typescript runtime has same implementation
+const CHECKSUM_POLYNOMIAL: u32 = 0x82F6_3B78;
+const CHECKSUM_TABLE: [u32; 256] = checksum_table();
+
+static CHECKSUM: AtomicBool = AtomicBool::new(false);
+
+const fn checksum_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CHECKSUM_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+// Checksum is added to all packages, which are packed by current process. Incoming packages
+// are validated, if they have checksum
+pub fn set_checksum(enabled: bool) {
+    CHECKSUM.store(enabled, Ordering::Relaxed);
+}
+
+pub fn get_checksum() -> bool {
+    CHECKSUM.load(Ordering::Relaxed)
+}
+
+pub fn crc32c(buf: &[u8]) -> u32 {
+    let mut crc: u32 = !0;
+    for byte in buf {
+        crc = CHECKSUM_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn get_value_buffer(id: Option<u16>, size: ESize, mut value: Vec<u8>) -> Result<Vec<u8>, String> {
+    let mut buffer: Vec<u8> = vec![];
+    if let Some(id) = id {
+        buffer.append(&mut id.to_le_bytes().to_vec());
+        match size {
+            ESize::U8(size) => {
+                buffer.append(&mut 8_u8.to_le_bytes().to_vec());
+                buffer.append(&mut size.to_le_bytes().to_vec());
+            }
+            ESize::U16(size) => {
+                buffer.append(&mut 16_u8.to_le_bytes().to_vec());
+                buffer.append(&mut size.to_le_bytes().to_vec());
+            }
+            ESize::U32(size) => {
+                buffer.append(&mut 32_u8.to_le_bytes().to_vec());
+                buffer.append(&mut size.to_le_bytes().to_vec());
+            }
+            ESize::U64(size) => {
+                buffer.append(&mut 64_u8.to_le_bytes().to_vec());
+                buffer.append(&mut size.to_le_bytes().to_vec());
+            }
+        };
+    }
+    buffer.append(&mut value);
+    Ok(buffer)
+}
+
+pub fn get_empty_buffer_val(id: Option<u16>) -> Result<Vec<u8>, String> {
+    get_value_buffer(id, ESize::U8(0), vec![])
+}
+
+fn get_size_value(size: &ESize) -> usize {
+    match size {
+        ESize::U8(size) => *size as usize,
+        ESize::U16(size) => *size as usize,
+        ESize::U32(size) => *size as usize,
+        ESize::U64(size) => *size as usize,
+    }
+}
+
+// Same layout as get_value_buffer gives, but without intermediate buffers
+pub fn get_value_len(id: Option<u16>, size: &ESize) -> usize {
+    let header = match id {
+        Some(_) => {
+            sizes::U16_LEN
+                + sizes::U8_LEN
+                + match size {
+                    ESize::U8(_) => sizes::U8_LEN,
+                    ESize::U16(_) => sizes::U16_LEN,
+                    ESize::U32(_) => sizes::U32_LEN,
+                    ESize::U64(_) => sizes::U64_LEN,
+                }
+        }
+        None => 0,
+    };
+    header + get_size_value(size)
+}
+
+pub fn put_value_header(id: Option<u16>, size: &ESize, buf: &mut impl BufMut) {
+    if let Some(id) = id {
+        buf.put_u16_le(id);
+        match size {
+            ESize::U8(size) => {
+                buf.put_u8(8);
+                buf.put_u8(*size);
+            }
+            ESize::U16(size) => {
+                buf.put_u8(16);
+                buf.put_u16_le(*size);
+            }
+            ESize::U32(size) => {
+                buf.put_u8(32);
+                buf.put_u32_le(*size);
+            }
+            ESize::U64(size) => {
+                buf.put_u8(64);
+                buf.put_u64_le(*size);
+            }
+        };
+    }
+}
+
+pub fn get_empty_len_to_store(id: Option<u16>) -> usize {
+    get_value_len(id, &ESize::U8(0))
+}
+
+pub fn put_empty_to_store(id: Option<u16>, buf: &mut impl BufMut) {
+    put_value_header(id, &ESize::U8(0), buf);
+}
+
+// Generic encode_into/pack_into aren't available on trait objects, so generated
+// types implement abduct and pack with this helper
+pub fn encode_to_vec<F>(capacity: usize, encode: F) -> Result<Vec<u8>, String>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<(), String>,
+{
+    let mut buffer: Vec<u8> = Vec::with_capacity(capacity);
+    encode(&mut buffer)?;
+    Ok(buffer)
+}
+
+pub trait StructEncode {
+    fn get_id(&self) -> u32;
+    fn get_signature(&self) -> u16;
+    // Exact size of encoded struct; nothing is encoded to get it
+    fn encoded_len(&self) -> usize;
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String>
+    where
+        Self: Sized;
+    fn abduct(&mut self) -> Result<Vec<u8>, String>;
+}
+
+pub trait EnumEncode {
+    fn get_id(&self) -> u32;
+    fn get_signature(&self) -> u16;
+    // Exact size of encoded enum; nothing is encoded to get it
+    fn encoded_len(&self) -> usize;
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String>
+    where
+        Self: Sized;
+    fn abduct(&mut self) -> Result<Vec<u8>, String>;
+}
+
+pub trait EncodeEnum {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String>;
+    fn encode(&mut self) -> Result<Vec<u8>, String> {
+        self.get_buf_to_store(None)
+    }
+    // Size of value without header of property
+    fn value_size(&self) -> ESize;
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String>;
+    fn len_to_store(&self, id: Option<u16>) -> usize {
+        get_value_len(id, &self.value_size())
+    }
+    fn put_to_store(&self, id: Option<u16>, buf: &mut impl BufMut) -> Result<(), String> {
+        put_value_header(id, &self.value_size(), buf);
+        self.value_into(buf)
+    }
+}
+
+impl<T> EncodeEnum for T
+where
+    T: EnumEncode,
+{
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        match self.abduct() {
+            Ok(buf) => get_value_buffer(id, ESize::U64(buf.len() as u64), buf.to_vec()),
+            Err(e) => Err(e),
+        }
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.encoded_len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.encode_into(buf)
+    }
+}
+
+impl<T> EncodeEnum for Vec<T>
+where
+    T: EnumEncode,
+{
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        let mut buffer: Vec<u8> = vec![];
+        for val in self.iter_mut() {
+            let val_as_bytes = match val.abduct() {
+                Ok(buf) => buf,
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+            buffer.append(&mut (val_as_bytes.len() as u64).to_le_bytes().to_vec());
+            buffer.append(&mut val_as_bytes.to_vec());
+        }
+        get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U64_LEN + val.encoded_len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(val.encoded_len() as u64);
+            val.encode_into(buf)?;
+        }
+        Ok(())
+    }
+}
+
+pub trait Encode {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String>;
+    fn encode(&mut self) -> Result<Vec<u8>, String> {
+        self.get_buf_to_store(None)
+    }
+    // Size of value without header of property
+    fn value_size(&self) -> ESize;
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String>;
+    fn len_to_store(&self, id: Option<u16>) -> usize {
+        get_value_len(id, &self.value_size())
+    }
+    fn put_to_store(&self, id: Option<u16>, buf: &mut impl BufMut) -> Result<(), String> {
+        put_value_header(id, &self.value_size(), buf);
+        self.value_into(buf)
+    }
+}
+
+impl Encode for u8 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::U8_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U8_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u8(*self);
+        Ok(())
+    }
+}
+
+impl Encode for u16 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::U16_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U16_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u16_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for u32 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::U32_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u32_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for u64 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::U64_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::U64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u64_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for i8 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::I8_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I8_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i8(*self);
+        Ok(())
+    }
+}
+
+impl Encode for i16 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::I16_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I16_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i16_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for i32 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::I32_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i32_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for i64 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::I64_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::I64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_i64_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for f32 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::F32_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::F32_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_f32_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for f64 {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::F64_LEN as u8),
+            self.to_le_bytes().to_vec(),
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::F64_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_f64_le(*self);
+        Ok(())
+    }
+}
+
+impl Encode for bool {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        get_value_buffer(
+            id,
+            ESize::U8(sizes::BOOL_LEN as u8),
+            if self == &true { vec![1] } else { vec![0] },
+        )
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U8(sizes::BOOL_LEN as u8)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_u8(if *self { 1 } else { 0 });
+        Ok(())
+    }
+}
+
+impl Encode for String {
+    fn get_buf_to_store(&mut self, id: Option<u16>) -> Result<Vec<u8>, String> {
+        let buf = self.as_bytes();
+        get_value_buffer(id, ESize::U64(buf.len() as u64), buf.to_vec())
+    }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_slice(self.as_bytes());
+        Ok(())
+    }
+}
+
+impl<T> Encode for T
 where
     T: StructEncode,
 {
@@ -902,6 +1913,12 @@ where
             Err(e) => Err(e),
         }
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(self.encoded_len() as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.encode_into(buf)
+    }
 }
 
 impl Encode for Vec<u8> {
@@ -913,6 +1930,13 @@ impl Encode for Vec<u8> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        buf.put_slice(self);
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u16> {
@@ -924,6 +1948,15 @@ impl Encode for Vec<u16> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U16_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u16_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u32> {
@@ -935,6 +1968,15 @@ impl Encode for Vec<u32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<u64> {
@@ -946,6 +1988,15 @@ impl Encode for Vec<u64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i8> {
@@ -957,6 +2008,15 @@ impl Encode for Vec<i8> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i8(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i16> {
@@ -968,6 +2028,15 @@ impl Encode for Vec<i16> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I16_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i16_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i32> {
@@ -979,6 +2048,15 @@ impl Encode for Vec<i32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<i64> {
@@ -990,6 +2068,15 @@ impl Encode for Vec<i64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::I64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_i64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<f32> {
@@ -1001,6 +2088,15 @@ impl Encode for Vec<f32> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::F32_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_f32_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<f64> {
@@ -1012,6 +2108,15 @@ impl Encode for Vec<f64> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::F64_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_f64_le(*val);
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<String> {
@@ -1024,6 +2129,20 @@ impl Encode for Vec<String> {
         }
         get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U32_LEN + val.len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u32_le(val.len() as u32);
+            buf.put_slice(val.as_bytes());
+        }
+        Ok(())
+    }
 }
 
 impl Encode for Vec<bool> {
@@ -1036,6 +2155,15 @@ impl Encode for Vec<bool> {
         }
         get_value_buffer(id, ESize::U64(len as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64((self.len() * sizes::U8_LEN) as u64)
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u8(if *val { 1 } else { 0 });
+        }
+        Ok(())
+    }
 }
 
 impl<T> Encode for Vec<T>
@@ -1056,6 +2184,20 @@ where
         }
         get_value_buffer(id, ESize::U64(buffer.len() as u64), buffer.to_vec())
     }
+    fn value_size(&self) -> ESize {
+        ESize::U64(
+            self.iter()
+                .map(|val| sizes::U64_LEN + val.encoded_len())
+                .sum::<usize>() as u64,
+        )
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for val in self.iter() {
+            buf.put_u64_le(val.encoded_len() as u64);
+            val.encode_into(buf)?;
+        }
+        Ok(())
+    }
 }
 
 impl<T> Encode for Option<T>
@@ -1068,8 +2210,24 @@ where
             None => get_empty_buffer_val(id),
         }
     }
+    fn value_size(&self) -> ESize {
+        match self {
+            Some(v) => v.value_size(),
+            None => ESize::U8(0),
+        }
+    }
+    fn value_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match self {
+            Some(v) => v.value_into(buf),
+            None => Ok(()),
+        }
+    }
 }
 
+// Properties, which aren't known by current version of protocol (id, body). Struct
+// keeps them and writes them back on encoding
+pub type UnknownFields = Vec<(u16, Vec<u8>)>;
+
 #[derive(Debug, Clone)]
 pub struct Storage {
     map: HashMap<u16, Vec<u8>>,
@@ -1114,6 +2272,13 @@ impl Storage {
         } else {
             return Err("Fail to set cursor position".to_string());
         }
+        if buf.len() < pos + sizes::U16_LEN {
+            return Err(format!(
+                "Cannot extract id of property at {}, because length of buffer is {}",
+                pos,
+                buf.len()
+            ));
+        }
         let id = cursor.get_u16_le();
         Ok((id, pos + sizes::U16_LEN))
     }
@@ -1125,15 +2290,27 @@ impl Storage {
         } else {
             return Err("Fail to set cursor position".to_string());
         }
+        let prop_rank_len: usize = 1;
+        let prop_size_len = match buf.get(pos) {
+            Some(8) => sizes::U8_LEN,
+            Some(16) => sizes::U16_LEN,
+            Some(32) => sizes::U32_LEN,
+            Some(64) => sizes::U64_LEN,
+            _ => 0,
+        };
+        if buf.len() < pos + prop_rank_len + prop_size_len {
+            return Err(format!(
+                "Cannot extract length of property at {}, because length of buffer is {}",
+                pos,
+                buf.len()
+            ));
+        }
         let prop_body_len_rank = cursor.get_u8();
         let prop_body_len_usize: usize;
-        let prop_rank_len: usize = 1;
-        let prop_size_len: usize;
         match prop_body_len_rank {
             8 => {
                 if let Ok(val) = usize::try_from(cursor.get_u8()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U8_LEN;
                 } else {
                     return Err("Fail convert length of name from u8 to usize".to_string());
                 }
@@ -1141,7 +2318,6 @@ impl Storage {
             16 => {
                 if let Ok(val) = usize::try_from(cursor.get_u16_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U16_LEN;
                 } else {
                     return Err("Fail convert length of name from u16 to usize".to_string());
                 }
@@ -1149,7 +2325,6 @@ impl Storage {
             32 => {
                 if let Ok(val) = usize::try_from(cursor.get_u32_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U32_LEN;
                 } else {
                     return Err("Fail convert length of name from u32 to usize".to_string());
                 }
@@ -1157,7 +2332,6 @@ impl Storage {
             64 => {
                 if let Ok(val) = usize::try_from(cursor.get_u64_le()) {
                     prop_body_len_usize = val;
-                    prop_size_len = sizes::U64_LEN;
                 } else {
                     return Err("Fail convert length of name from u64 to usize".to_string());
                 }
@@ -1166,6 +2340,14 @@ impl Storage {
                 return Err(format!("Unknown rank has been gotten: {}", v));
             }
         };
+        // Length is taken from incoming data; it shouldn't be trusted
+        if buf.len() - (pos + prop_rank_len + prop_size_len) < prop_body_len_usize {
+            return Err(format!(
+                "Length of property body ({} bytes) is bigger than rest of buffer ({} bytes)",
+                prop_body_len_usize,
+                buf.len() - (pos + prop_rank_len + prop_size_len)
+            ));
+        }
         let mut prop_body_buf = vec![0; prop_body_len_usize];
         prop_body_buf.copy_from_slice(
             &buf[(pos + prop_rank_len + prop_size_len)
@@ -1190,6 +2372,17 @@ impl Storage {
     pub fn get(&mut self, id: u16) -> Option<&Vec<u8>> {
         self.map.get(&id)
     }
+
+    pub fn unknown(&self, known: &[u16]) -> UnknownFields {
+        let mut fields: UnknownFields = self
+            .map
+            .iter()
+            .filter(|(id, _)| !known.contains(id))
+            .map(|(id, body)| (*id, body.clone()))
+            .collect();
+        fields.sort_by_key(|(id, _)| *id);
+        fields
+    }
 }
 
 const MSG_HEADER_LEN: usize = sizes::U32_LEN + // {u32} message ID
@@ -1197,29 +2390,54 @@ const MSG_HEADER_LEN: usize = sizes::U32_LEN + // {u32} message ID
                                 sizes::U32_LEN + // {u32} sequence
                                 sizes::U64_LEN + // {u64} body size
                                 sizes::U64_LEN; // {u64} timestamp
-
-pub trait PackingMiddlewareInterface {
-    fn decode(
-        buffer: Vec<u8>,
-        _id: u32,
-        _sequence: u32,
-        _uuid: Option<String>,
-    ) -> Result<Vec<u8>, String> {
-        Ok(buffer)
-    }
-    fn encode(
-        buffer: Vec<u8>,
-        _id: u32,
-        _sequence: u32,
-        _uuid: Option<String>,
-    ) -> Result<Vec<u8>, String> {
-        Ok(buffer)
+const MSG_CHECKSUM_LEN: usize = sizes::U32_LEN; // {u32} CRC32C of body (if flag is set)
+
+// Highest byte of body size is used for flags of package (compression, version, checksum)
+const MSG_LEN_MASK: u64 = 0x00FF_FFFF_FFFF_FFFF;
+const MSG_FLAGS_SHIFT: u64 = 56;
+// Header with this flag has checksum of body after body size
+pub const MSG_FLAG_CHECKSUM: u8 = 0x80;
+// Bits 4..6 of flags keep version of header; packages of older runtimes have version 0.
+// Headers of all versions have same layout; since version 2 timestamp is in milliseconds
+// (before in seconds)
+pub const MSG_VERSION: u8 = 2;
+pub const MSG_VERSION_TS_MS: u8 = 2;
+const MSG_VERSION_MASK: u8 = 0x70;
+const MSG_VERSION_SHIFT: u8 = 4;
+
+static LAST_TS: AtomicU64 = AtomicU64::new(0);
+
+// Unix time in milliseconds. It doesn't go back within process even if system clock does, so
+// packages of one producer (consumer) are ordered by timestamp
+pub fn now_ms() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    LAST_TS.fetch_max(now, Ordering::Relaxed).max(now)
+}
+
+// Converts timestamp between versions of header
+fn convert_ts(ts: u64, from: u8, to: u8) -> u64 {
+    match (from >= MSG_VERSION_TS_MS, to >= MSG_VERSION_TS_MS) {
+        (true, false) => ts / 1000,
+        (false, true) => ts.saturating_mul(1000),
+        _ => ts,
     }
 }
 
-pub struct PackingMiddleware {}
-
-impl PackingMiddlewareInterface for PackingMiddleware {
+pub trait PackingMiddlewareInterface {
+    // Transparent middleware doesn't change body; package is written in one pass then.
+    // It's opt-in, because inherent encode() of PackingMiddleware cannot change trait's
+    // default
+    fn transparent() -> bool {
+        false
+    }
+    // Bound middleware encodes body for certain consumer. Package, which is packed without
+    // uuid (broadcast), isn't encoded and should be rebound for each consumer
+    fn bound() -> bool {
+        false
+    }
     fn decode(
         buffer: Vec<u8>,
         _id: u32,
@@ -1244,12 +2462,32 @@ pub struct PackageHeader {
     pub signature: u16,
     pub sequence: u32,
     pub len: u64,
+    // Unix time of sending in units of version of header; use ts_ms() to get milliseconds
     pub ts: u64,
     pub len_usize: usize,
+    pub flags: u8,
+    pub version: u8,
+    pub checksum: Option<u32>,
+}
+
+impl PackageHeader {
+    // Unix time of sending in milliseconds (headers before version 2 have precision in seconds)
+    pub fn ts_ms(&self) -> u64 {
+        convert_ts(self.ts, self.version, MSG_VERSION_TS_MS)
+    }
+}
+
+fn get_header_len(flags: u8) -> usize {
+    if flags & MSG_FLAG_CHECKSUM != 0 {
+        MSG_HEADER_LEN + MSG_CHECKSUM_LEN
+    } else {
+        MSG_HEADER_LEN
+    }
 }
 
+// Flags are in last byte of basic header; they define whether header has checksum
 pub fn has_buffer_header(buf: &[u8]) -> bool {
-    buf.len() >= MSG_HEADER_LEN
+    buf.len() >= MSG_HEADER_LEN && buf.len() >= get_header_len(buf[MSG_HEADER_LEN - 1])
 }
 
 pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
@@ -1267,6 +2505,24 @@ pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
     let ts: u64 = header.get_u64_le();
     // Get length of payload and payload
     let len: u64 = header.get_u64_le();
+    let flags: u8 = (len >> MSG_FLAGS_SHIFT) as u8;
+    let len: u64 = len & MSG_LEN_MASK;
+    let version: u8 = (flags & MSG_VERSION_MASK) >> MSG_VERSION_SHIFT;
+    if version > MSG_VERSION {
+        return Err(format!(
+            "Version of header {} isn't supported; supported versions: 0..{}",
+            version, MSG_VERSION
+        ));
+    }
+    // Get checksum of body
+    let checksum: Option<u32> = if flags & MSG_FLAG_CHECKSUM != 0 {
+        if buf.len() < get_header_len(flags) {
+            return Err(format!("Cannot extract checksum of package because size of header {} bytes, but size of buffer {} bytes.", get_header_len(flags), buf.len()));
+        }
+        Some(header.get_u32_le())
+    } else {
+        None
+    };
     let len_usize = match usize::try_from(len) {
         Ok(v) => v,
         Err(e) => {
@@ -1280,40 +2536,214 @@ pub fn get_header_from_buffer(buf: &[u8]) -> Result<PackageHeader, String> {
         len,
         ts,
         len_usize,
+        flags,
+        version,
+        checksum,
     })
 }
 
 pub fn has_buffer_body(buf: &[u8], header: &PackageHeader) -> bool {
-    buf.len() >= header.len_usize + MSG_HEADER_LEN
+    buf.len() >= get_package_len(header)
+}
+
+pub fn get_package_len(header: &PackageHeader) -> usize {
+    header.len_usize + get_header_len(header.flags)
+}
+
+// Body is checked before middleware, so corrupted package doesn't get into decryption
+// or decompression
+pub fn check_body(header: &PackageHeader, body: &[u8]) -> Result<(), String> {
+    match header.checksum {
+        Some(checksum) if crc32c(body) != checksum => Err(format!(
+            "Checksum of package id={} (sequence {}) dismatch; expectation: {}; body: {}",
+            header.id,
+            header.sequence,
+            checksum,
+            crc32c(body)
+        )),
+        _ => Ok(()),
+    }
 }
 
 pub fn get_body_from_buffer(
     buf: &[u8],
     header: &PackageHeader,
     uuid: Option<String>,
-) -> Result<(Vec<u8>, Vec<u8>), String> {
-    if buf.len() < header.len_usize + MSG_HEADER_LEN {
-        return Err(format!("Cannot extract body of package because size in header {} bytes, but size of buffer {} bytes.", header.len, buf.len() - MSG_HEADER_LEN));
+) -> Result<(Vec<u8>, Vec<u8>), ReadError> {
+    let header_len = get_header_len(header.flags);
+    if buf.len() < header.len_usize + header_len {
+        return Err(ReadError::Parsing(format!("Cannot extract body of package because size in header {} bytes, but size of buffer {} bytes.", header.len, buf.len() - header_len)));
     }
     // Get body
     let mut body = vec![0; header.len_usize];
-    body.copy_from_slice(&buf[MSG_HEADER_LEN..(MSG_HEADER_LEN + header.len_usize)]);
-    let mut rest = vec![0; buf.len() - MSG_HEADER_LEN - header.len_usize];
-    rest.copy_from_slice(&buf[(MSG_HEADER_LEN + header.len_usize)..]);
+    body.copy_from_slice(&buf[header_len..(header_len + header.len_usize)]);
+    let mut rest = vec![0; buf.len() - header_len - header.len_usize];
+    rest.copy_from_slice(&buf[(header_len + header.len_usize)..]);
+    check_body(header, &body).map_err(ReadError::Checksum)?;
     match PackingMiddleware::decode(body, header.id, header.sequence, uuid) {
-        Ok(buffer) => Ok((buffer, rest)),
-        Err(e) => Err(e),
+        Ok(buffer) => Ok((
+            decompress(header.flags & COMPRESSION_FLAGS, buffer).map_err(ReadError::Parsing)?,
+            rest,
+        )),
+        Err(e) => Err(ReadError::Parsing(e)),
+    }
+}
+
+// Adapts package, which was packed without uuid (broadcast), for consumer: body is encoded by
+// bound middleware; compression and version of header follow agreement with consumer
+pub fn rebind(buf: &[u8], uuid: Option<String>) -> Result<Vec<u8>, String> {
+    let header = get_header_from_buffer(buf)?;
+    if !has_buffer_body(buf, &header) {
+        return Err(format!(
+            "Cannot rebind package because size in header {} bytes, but size of buffer {} bytes.",
+            header.len,
+            buf.len() - get_header_len(header.flags)
+        ));
+    }
+    let peer = get_peer(&uuid);
+    let compression = header.flags & COMPRESSION_FLAGS;
+    let readable = compression & !peer.compression == 0;
+    if !PackingMiddleware::bound() && readable && header.version == peer.version {
+        peer.check(header.len_usize)?;
+        return Ok(buf.to_vec());
+    }
+    let body = buf[get_header_len(header.flags)..get_package_len(&header)].to_vec();
+    let (compression, body) = if readable {
+        (compression, body)
+    } else if PackingMiddleware::bound() || PackingMiddleware::transparent() {
+        compress(decompress(compression, body)?, peer.compression)
+    } else {
+        return Err(format!(
+            "Package id={} is compressed with algorithm, which consumer cannot read",
+            header.id
+        ));
+    };
+    let body = if PackingMiddleware::bound() {
+        PackingMiddleware::encode(body, header.id, header.sequence, uuid)?
+    } else {
+        body
+    };
+    peer.check(body.len())?;
+    let checksum = header.checksum.map(|_| crc32c(&body));
+    let mut flags = compression | peer.version << MSG_VERSION_SHIFT;
+    if checksum.is_some() {
+        flags |= MSG_FLAG_CHECKSUM;
+    }
+    let mut rebound: Vec<u8> = Vec::with_capacity(get_header_len(flags) + body.len());
+    // Timestamp, body size (and checksum) are last fields of header
+    rebound.put_slice(&buf[..(MSG_HEADER_LEN - sizes::U64_LEN * 2)]);
+    rebound.put_u64_le(convert_ts(header.ts, header.version, peer.version));
+    rebound.put_u64_le(body.len() as u64 | (flags as u64) << MSG_FLAGS_SHIFT);
+    if let Some(checksum) = checksum {
+        rebound.put_u32_le(checksum);
+    }
+    rebound.put_slice(&body);
+    Ok(rebound)
+}
+
+// Settings of packages for peer: agreed ones or own ones, if there is no agreement. Until
+// agreement headers have version 0, because peer might be older runtime
+struct Peer {
+    version: u8,
+    // Flags of compression algorithms, which peer can read
+    compression: u8,
+    // Max size of body; 0 - isn't limited
+    frame: u64,
+}
+
+impl Peer {
+    fn check(&self, len: usize) -> Result<(), String> {
+        if self.frame != 0 && len as u64 > self.frame {
+            Err(format!(
+                "Size of package body ({} bytes) is bigger than limit of peer ({} bytes)",
+                len, self.frame
+            ))
+        } else {
+            Ok(())
+        }
     }
 }
 
-pub fn pack<T>(mut msg: T, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>
+fn get_peer(uuid: &Option<String>) -> Peer {
+    match uuid.as_ref().and_then(|uuid| get_agreement(uuid)) {
+        Some(agreement) => Peer {
+            version: agreement.version,
+            compression: agreement.compression,
+            frame: agreement.frame,
+        },
+        None => Peer {
+            version: 0,
+            compression: COMPRESSION_FLAGS,
+            frame: 0,
+        },
+    }
+}
+
+pub fn pack<T>(msg: T, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>
 where
-    T: StructEncode,
+    T: PackingStruct,
 {
-    match msg.abduct() {
-        Ok(buffer) => pack_buffer(msg.get_id(), msg.get_signature(), sequence, buffer, uuid),
-        Err(e) => Err(e),
+    let mut buf: Vec<u8> = vec![];
+    msg.pack_into(sequence, uuid, &mut buf)?;
+    Ok(buf)
+}
+
+fn put_header(
+    msg_id: u32,
+    signature: u16,
+    sequence: u32,
+    len: usize,
+    flags: u8,
+    checksum: Option<u32>,
+    buf: &mut impl BufMut,
+) -> Result<(), String> {
+    let flags = if checksum.is_some() {
+        flags | MSG_FLAG_CHECKSUM
+    } else {
+        flags
+    };
+    let version = (flags & MSG_VERSION_MASK) >> MSG_VERSION_SHIFT;
+    buf.put_u32_le(msg_id);
+    buf.put_u16_le(signature);
+    buf.put_u32_le(sequence);
+    buf.put_u64_le(convert_ts(now_ms(), MSG_VERSION_TS_MS, version));
+    buf.put_u64_le(len as u64 | (flags as u64) << MSG_FLAGS_SHIFT);
+    if let Some(checksum) = checksum {
+        buf.put_u32_le(checksum);
     }
+    Ok(())
+}
+
+fn put_buffer(
+    msg_id: u32,
+    signature: u16,
+    sequence: u32,
+    msg_buf: Vec<u8>,
+    uuid: Option<String>,
+    buf: &mut impl BufMut,
+) -> Result<(), String> {
+    let peer = get_peer(&uuid);
+    peer.check(msg_buf.len())?;
+    // Body is compressed before middleware, because middleware can encrypt it
+    let (flags, msg_buf) = compress(msg_buf, peer.compression);
+    let buffer = PackingMiddleware::encode(msg_buf, msg_id, sequence, uuid)?;
+    peer.check(buffer.len())?;
+    let checksum = if get_checksum() {
+        Some(crc32c(&buffer))
+    } else {
+        None
+    };
+    put_header(
+        msg_id,
+        signature,
+        sequence,
+        buffer.len(),
+        flags | peer.version << MSG_VERSION_SHIFT,
+        checksum,
+        buf,
+    )?;
+    buf.put_slice(&buffer);
+    Ok(())
 }
 
 pub fn pack_buffer(
@@ -1323,42 +2753,124 @@ pub fn pack_buffer(
     msg_buf: Vec<u8>,
     uuid: Option<String>,
 ) -> Result<Vec<u8>, String> {
-    let buffer = match PackingMiddleware::encode(msg_buf, msg_id, sequence, uuid) {
-        Ok(buffer) => buffer,
-        Err(e) => {
-            return Err(e);
-        }
-    };
-    match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(duration) => {
-            let mut buf: Vec<u8> = vec![];
-            buf.append(&mut msg_id.to_le_bytes().to_vec());
-            buf.append(&mut signature.to_le_bytes().to_vec());
-            buf.append(&mut sequence.to_le_bytes().to_vec());
-            buf.append(&mut duration.as_secs().to_le_bytes().to_vec());
-            buf.append(&mut (buffer.len() as u64).to_le_bytes().to_vec());
-            buf.append(&mut buffer.to_vec());
-            Ok(buf)
-        }
-        Err(e) => Err(e.to_string()),
-    }
+    let mut buf: Vec<u8> = Vec::with_capacity(MSG_HEADER_LEN + MSG_CHECKSUM_LEN + msg_buf.len());
+    put_buffer(msg_id, signature, sequence, msg_buf, uuid, &mut buf)?;
+    Ok(buf)
 }
 
 pub trait PackingStruct: StructEncode {
-    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
-        match self.abduct() {
-            Ok(buf) => pack_buffer(self.get_id(), self.get_signature(), sequence, buf, uuid),
-            Err(e) => Err(e),
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>;
+    // Writes package in one pass; buffer (for example BytesMut) can be reused
+    fn pack_into(
+        &self,
+        sequence: u32,
+        uuid: Option<String>,
+        buf: &mut impl BufMut,
+    ) -> Result<(), String>
+    where
+        Self: Sized,
+    {
+        let len = self.encoded_len();
+        let peer = get_peer(&uuid);
+        if PackingMiddleware::transparent()
+            && compression_flag(len, peer.compression) == 0
+            && !get_checksum()
+        {
+            peer.check(len)?;
+            put_header(
+                self.get_id(),
+                self.get_signature(),
+                sequence,
+                len,
+                peer.version << MSG_VERSION_SHIFT,
+                None,
+                buf,
+            )?;
+            return self.encode_into(buf);
         }
+        let mut body: Vec<u8> = Vec::with_capacity(len);
+        self.encode_into(&mut body)?;
+        put_buffer(
+            self.get_id(),
+            self.get_signature(),
+            sequence,
+            body,
+            uuid,
+            buf,
+        )
     }
 }
 
 pub trait PackingEnum: EnumEncode {
-    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
-        match self.abduct() {
-            Ok(buf) => pack_buffer(self.get_id(), self.get_signature(), sequence, buf, uuid),
-            Err(e) => Err(e),
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String>;
+    // Writes package in one pass; buffer (for example BytesMut) can be reused
+    fn pack_into(
+        &self,
+        sequence: u32,
+        uuid: Option<String>,
+        buf: &mut impl BufMut,
+    ) -> Result<(), String>
+    where
+        Self: Sized,
+    {
+        let len = self.encoded_len();
+        let peer = get_peer(&uuid);
+        if PackingMiddleware::transparent()
+            && compression_flag(len, peer.compression) == 0
+            && !get_checksum()
+        {
+            peer.check(len)?;
+            put_header(
+                self.get_id(),
+                self.get_signature(),
+                sequence,
+                len,
+                peer.version << MSG_VERSION_SHIFT,
+                None,
+                buf,
+            )?;
+            return self.encode_into(buf);
         }
+        let mut body: Vec<u8> = Vec::with_capacity(len);
+        self.encode_into(&mut body)?;
+        put_buffer(
+            self.get_id(),
+            self.get_signature(),
+            sequence,
+            body,
+            uuid,
+            buf,
+        )
+    }
+}
+
+// Names of encryption algorithms, which are offered to peer
+pub fn encryption_algorithms() -> Vec<String> {
+    vec![]
+}
+
+// Methods can be overridden with inherent impl of PackingMiddleware (impl PackingMiddleware
+// { fn encode(...) }). Packages are passed through encode() unless inherent transparent()
+// returns true: it declares, that encode() doesn't change body, so package is written in
+// one pass
+pub struct PackingMiddleware {}
+
+impl PackingMiddlewareInterface for PackingMiddleware {
+    fn decode(
+        buffer: Vec<u8>,
+        _id: u32,
+        _sequence: u32,
+        _uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(buffer)
+    }
+    fn encode(
+        buffer: Vec<u8>,
+        _id: u32,
+        _sequence: u32,
+        _uuid: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(buffer)
     }
 }
 
@@ -1367,6 +2879,18 @@ pub enum ReadError {
     Header(String),
     Parsing(String),
     Signature(String),
+    // One of Limits is exceeded
+    Limit(String),
+    // Body of package doesn't match checksum in header
+    Checksum(String),
+}
+
+// Error of one package. Package is skipped, but following packages are read as usual
+#[derive(Debug)]
+pub struct FrameError {
+    pub id: u32,
+    pub sequence: u32,
+    pub error: ReadError,
 }
 
 #[derive(Clone)]
@@ -1383,6 +2907,8 @@ pub trait DecodeBuffer<T> {
 pub struct Buffer<T: Clone> {
     buffer: Vec<u8>,
     queue: Vec<IncomeMessage<T>>,
+    errors: Vec<FrameError>,
+    limits: Limits,
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -1399,9 +2925,13 @@ where
                 header.signature
             )))
         } else {
-            match self.get_msg(header.id, buf) {
-                Ok(msg) => Ok(msg),
-                Err(e) => Err(ReadError::Parsing(format!(
+            match with_limits(&self.limits, || self.get_msg(header.id, buf)) {
+                (Ok(msg), _) => Ok(msg),
+                (Err(e), Some(_)) => Err(ReadError::Limit(format!(
+                    "Fail get message id={}, signature={} due error: {}",
+                    header.id, header.signature, e
+                ))),
+                (Err(e), None) => Err(ReadError::Parsing(format!(
                     "Fail get message id={}, signature={} due error: {}",
                     header.id, header.signature, e
                 ))),
@@ -1410,74 +2940,657 @@ where
     }
 
     pub fn new() -> Self {
+        Self::with_limits(Limits::default())
+    }
+
+    pub fn with_limits(limits: Limits) -> Self {
         Buffer {
             buffer: vec![],
             queue: vec![],
+            errors: vec![],
+            limits,
         }
     }
 
+    // Fails only if stream can't be read further (broken header, too big package or too
+    // many pending messages). Errors of separate packages are available via take_errors()
     #[allow(clippy::ptr_arg)]
     pub fn chunk(&mut self, buf: &Vec<u8>, uuid: Option<String>) -> Result<(), ReadError> {
         // Add data into buffer
-        self.buffer.append(&mut buf.clone());
-        if !has_buffer_header(&self.buffer) {
-            return Ok(());
+        self.buffer.extend_from_slice(buf);
+        while has_buffer_header(&self.buffer) {
+            // Get header
+            let header: PackageHeader = match get_header_from_buffer(&self.buffer) {
+                Ok(v) => v,
+                Err(e) => {
+                    return Err(ReadError::Header(e));
+                }
+            };
+            if header.len_usize > self.limits.frame {
+                // Rest of buffer can't be trusted anymore
+                self.buffer.clear();
+                return Err(ReadError::Limit(format!(
+                    "Size of package id={} ({} bytes) is bigger than limit ({} bytes)",
+                    header.id, header.len, self.limits.frame
+                )));
+            }
+            if !has_buffer_body(&self.buffer, &header) {
+                return Ok(());
+            }
+            if self.queue.len() >= self.limits.pending {
+                return Err(ReadError::Limit(format!(
+                    "Count of pending messages is bigger than limit ({})",
+                    self.limits.pending
+                )));
+            }
+            // Package is removed from buffer in any case; header defines where next one starts
+            let package: Vec<u8> = self.buffer.drain(..get_package_len(&header)).collect();
+            let msg = match with_limits(&self.limits, || {
+                get_body_from_buffer(&package, &header, uuid.clone())
+            }) {
+                (Ok((body, _)), _) => self.get_message(&header, &body),
+                (Err(ReadError::Parsing(e)), Some(_)) => Err(ReadError::Limit(e)),
+                (Err(e), _) => Err(e),
+            };
+            match msg {
+                Ok(msg) => self.queue.push(IncomeMessage { header, msg }),
+                Err(error) => self.errors.push(FrameError {
+                    id: header.id,
+                    sequence: header.sequence,
+                    error,
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<IncomeMessage<T>> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let message = Some(self.queue[0].clone());
+        if self.queue.len() > 1 {
+            self.queue = self.queue.drain(1..).collect();
+        } else {
+            self.queue.clear();
+        }
+        message
+    }
+
+    pub fn take_errors(&mut self) -> Vec<FrameError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+// Capabilities are exchanged while hash is checked: consumer sends own ones with HashRequest,
+// producer responds with own ones in HashResponse. Each side agrees settings of packages for
+// peer, so producer and consumer can be built with different settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    // Highest version of header, which can be read
+    pub version: u8,
+    // Flags of compression algorithms, which can be read
+    pub compression: Vec<u8>,
+    // Names of encryption algorithms in order of preference
+    pub encryption: Vec<String>,
+    pub codec: String,
+    // Max size of package body in bytes, which can be read; 0 - isn't limited
+    pub frame: u64,
+    // Heartbeat packages are supported
+    pub heartbeat: bool,
+}
+
+// Settings of packages for peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agreement {
+    pub version: u8,
+    // Flags of compression algorithms, which peer can read
+    pub compression: u8,
+    pub encryption: Option<String>,
+    pub frame: u64,
+    pub heartbeat: bool,
+    // Clock of peer; None until it's estimated
+    pub clock: Option<ClockSkew>,
+}
+
+// Difference between clock of peer and own clock. It's estimated only by headers with timestamp
+// in milliseconds (version 2), which are sent after agreement. Consumer estimates it by round
+// trip of hash check; producer has only timestamp of first package of consumer, so its
+// estimation includes latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    // Clock of peer minus own clock in milliseconds
+    pub offset: i64,
+    // Round trip in milliseconds; error of offset isn't bigger than half of it.
+    // None - offset is estimated by one package
+    pub rtt: Option<u64>,
+}
+
+impl ClockSkew {
+    // sent and received - own time of sending request and getting response; peer_ts - timestamp
+    // of response (clock of peer). All values are in milliseconds
+    pub fn estimate(sent: u64, peer_ts: u64, received: u64) -> Self {
+        let rtt = received.saturating_sub(sent);
+        ClockSkew {
+            offset: peer_ts as i64 - (sent + rtt / 2) as i64,
+            rtt: Some(rtt),
         }
-        // Get header
-        let header: PackageHeader = match get_header_from_buffer(&self.buffer) {
-            Ok(v) => v,
-            Err(e) => {
-                return Err(ReadError::Header(e));
+    }
+
+    // Estimation by timestamp of package, which is just received
+    pub fn one_way(peer_ts: u64) -> Self {
+        ClockSkew {
+            offset: peer_ts as i64 - now_ms() as i64,
+            rtt: None,
+        }
+    }
+
+    // Converts timestamp of peer (ms) into own clock
+    pub fn to_local(&self, peer_ts: u64) -> u64 {
+        (peer_ts as i64 - self.offset).max(0) as u64
+    }
+
+    // Milliseconds since package with given timestamp (clock of peer) was sent
+    pub fn age(&self, peer_ts: u64) -> i64 {
+        now_ms() as i64 - self.to_local(peer_ts) as i64
+    }
+}
+
+impl Capabilities {
+    pub fn local(codec: &str, limits: &Limits) -> Self {
+        Capabilities {
+            version: MSG_VERSION,
+            compression: vec![COMPRESSION_FLAG_DEFLATE, COMPRESSION_FLAG_LZ4],
+            encryption: encryption_algorithms(),
+            codec: codec.to_owned(),
+            frame: limits.frame as u64,
+            // Runtime doesn't send heartbeat packages
+            heartbeat: false,
+        }
+    }
+
+    // Capabilities of peer, which doesn't send them (runtime before negotiation): it reads
+    // headers of version 0 without compression
+    pub fn legacy(codec: &str) -> Self {
+        Capabilities {
+            version: 0,
+            compression: vec![],
+            encryption: vec![],
+            codec: codec.to_owned(),
+            frame: 0,
+            heartbeat: false,
+        }
+    }
+
+    // Encryption algorithm is chosen by own preference; peer should use it as well
+    pub fn negotiate(&self, peer: &Capabilities) -> Result<Agreement, String> {
+        if self.codec != peer.codec {
+            return Err(format!(
+                "Codec dismatch; own codec: {}; codec of peer: {}",
+                self.codec, peer.codec
+            ));
+        }
+        let encryption = if self.encryption.is_empty() && peer.encryption.is_empty() {
+            None
+        } else {
+            Some(
+                self.encryption
+                    .iter()
+                    .find(|algorithm| peer.encryption.contains(algorithm))
+                    .cloned()
+                    .ok_or_else(|| {
+                        format!(
+                            "No common encryption; own algorithms: {:?}; algorithms of peer: {:?}",
+                            self.encryption, peer.encryption
+                        )
+                    })?,
+            )
+        };
+        Ok(Agreement {
+            version: self.version.min(peer.version),
+            compression: peer
+                .compression
+                .iter()
+                .fold(0, |flags, flag| flags | (flag & COMPRESSION_FLAGS)),
+            encryption,
+            frame: peer.frame,
+            heartbeat: self.heartbeat && peer.heartbeat,
+            clock: None,
+        })
+    }
+
+    // Capabilities, which are sent in response: encryption is limited to agreed algorithm
+    pub fn agreed(&self, agreement: &Agreement) -> Self {
+        Capabilities {
+            encryption: agreement.encryption.iter().cloned().collect(),
+            ..self.clone()
+        }
+    }
+}
+
+static AGREEMENTS: Mutex<Option<HashMap<String, Agreement>>> = Mutex::new(None);
+
+fn agreements<T>(cb: impl FnOnce(&mut HashMap<String, Agreement>) -> T) -> Option<T> {
+    AGREEMENTS
+        .lock()
+        .ok()
+        .map(|mut agreements| cb(agreements.get_or_insert_with(HashMap::new)))
+}
+
+// Packages, which are packed with uuid, follow agreement with this peer
+pub fn set_agreement(uuid: &str, agreement: Agreement) {
+    agreements(|agreements| agreements.insert(uuid.to_owned(), agreement));
+}
+
+pub fn get_agreement(uuid: &str) -> Option<Agreement> {
+    agreements(|agreements| agreements.get(uuid).cloned()).flatten()
+}
+
+// Estimates clock of peer by package, which is just received, if it isn't estimated yet.
+// Timestamps of headers before version 2 are in seconds and aren't used
+pub fn observe_clock(uuid: &str, header: &PackageHeader) {
+    if header.version < MSG_VERSION_TS_MS {
+        return;
+    }
+    agreements(|agreements| {
+        if let Some(agreement) = agreements.get_mut(uuid) {
+            if agreement.clock.is_none() {
+                agreement.clock = Some(ClockSkew::one_way(header.ts));
             }
-        };
-        if !has_buffer_body(&self.buffer, &header) {
-            return Ok(());
         }
-        let (body, rest) = match get_body_from_buffer(&self.buffer, &header, uuid.clone()) {
-            Ok(v) => v,
-            Err(e) => {
-                return Err(ReadError::Parsing(e));
+    });
+}
+
+pub fn get_clock_skew(uuid: &str) -> Option<ClockSkew> {
+    get_agreement(uuid).and_then(|agreement| agreement.clock)
+}
+
+pub fn remove_agreement(uuid: &str) {
+    agreements(|agreements| agreements.remove(uuid));
+}
+
+
+// Canonical JSON form of messages: names of fields are taken from protocol, enums are
+// { "Variant": value }, i64/u64 are strings (JS cannot keep such numbers safely)
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    // Number is kept as text to avoid lost of precision
+    Number(String),
+    Str(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+static JSON_NULL: JsonValue = JsonValue::Null;
+
+impl JsonValue {
+    pub fn parse(src: &str) -> Result<JsonValue, String> {
+        let mut reader = JsonReader {
+            chars: src.chars().collect(),
+            pos: 0,
+        };
+        let value = reader.value()?;
+        reader.spaces();
+        if reader.pos < reader.chars.len() {
+            Err(format!("Unexpected symbol at position {}", reader.pos))
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn stringify(&self) -> String {
+        match self {
+            JsonValue::Null => String::from("null"),
+            JsonValue::Bool(value) => value.to_string(),
+            JsonValue::Number(value) => value.clone(),
+            JsonValue::Str(value) => json_escape(value),
+            JsonValue::Array(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| item.stringify())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            JsonValue::Object(fields) => format!(
+                "{{{}}}",
+                fields
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", json_escape(key), value.stringify()))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    /// Returns value of object's field or null if field doesn't exist
+    pub fn get(&self, key: &str) -> &JsonValue {
+        if let JsonValue::Object(fields) = self {
+            if let Some((_, value)) = fields.iter().find(|(name, _)| name == key) {
+                return value;
             }
-        };
-        self.buffer = rest;
-        match Self::get_message(self, &header, &body) {
-            Ok(msg) => {
-                self.queue.push(IncomeMessage { header, msg });
-                if !self.buffer.is_empty() {
-                    self.chunk(&vec![], uuid)
-                } else {
-                    Ok(())
+        }
+        &JSON_NULL
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+struct JsonReader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl JsonReader {
+    fn spaces(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        if let Some(c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            Ok(*c)
+        } else {
+            Err(String::from("Unexpected end of JSON"))
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        for c in word.chars() {
+            if self.next()? != c {
+                return Err(format!("Expecting \"{}\" at position {}", word, self.pos));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<JsonValue, String> {
+        self.spaces();
+        match self.chars.get(self.pos) {
+            Some('n') => self.expect("null").map(|_| JsonValue::Null),
+            Some('t') => self.expect("true").map(|_| JsonValue::Bool(true)),
+            Some('f') => self.expect("false").map(|_| JsonValue::Bool(false)),
+            Some('"') => self.string().map(JsonValue::Str),
+            Some('[') => {
+                self.pos += 1;
+                let mut items: Vec<JsonValue> = vec![];
+                self.spaces();
+                if self.chars.get(self.pos) == Some(&']') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.spaces();
+                    match self.next()? {
+                        ',' => continue,
+                        ']' => return Ok(JsonValue::Array(items)),
+                        c => return Err(format!("Unexpected \"{}\" in array", c)),
+                    }
                 }
             }
-            Err(e) => Err(e),
+            Some('{') => {
+                self.pos += 1;
+                let mut fields: Vec<(String, JsonValue)> = vec![];
+                self.spaces();
+                if self.chars.get(self.pos) == Some(&'}') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    self.spaces();
+                    let key = self.string()?;
+                    self.spaces();
+                    if self.next()? != ':' {
+                        return Err(format!("Expecting \":\" after key \"{}\"", key));
+                    }
+                    fields.push((key, self.value()?));
+                    self.spaces();
+                    match self.next()? {
+                        ',' => continue,
+                        '}' => return Ok(JsonValue::Object(fields)),
+                        c => return Err(format!("Unexpected \"{}\" in object", c)),
+                    }
+                }
+            }
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && (self.chars[self.pos].is_ascii_digit()
+                        || ['-', '+', '.', 'e', 'E'].contains(&self.chars[self.pos]))
+                {
+                    self.pos += 1;
+                }
+                Ok(JsonValue::Number(self.chars[start..self.pos].iter().collect()))
+            }
+            Some(c) => Err(format!("Unexpected \"{}\" at position {}", c, self.pos)),
+            None => Err(String::from("Unexpected end of JSON")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.next()? != '"' {
+            return Err(format!("Expecting string at position {}", self.pos));
+        }
+        let mut output = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Ok(output),
+                '\\' => match self.next()? {
+                    'n' => output.push('\n'),
+                    'r' => output.push('\r'),
+                    't' => output.push('\t'),
+                    'b' => output.push('\u{8}'),
+                    'f' => output.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex()?;
+                        if (0xD800..0xDC00).contains(&code) {
+                            // Surrogate pair
+                            self.expect("\\u")?;
+                            let low = self.hex()?;
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                        }
+                        output.push(
+                            std::char::from_u32(code)
+                                .ok_or_else(|| format!("Invalid unicode code {}", code))?,
+                        );
+                    }
+                    c => output.push(c),
+                },
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let mut code: u32 = 0;
+        for _ in 0..4 {
+            let c = self.next()?;
+            code = code * 16
+                + c.to_digit(16)
+                    .ok_or_else(|| format!("Invalid hex symbol \"{}\"", c))?;
+        }
+        Ok(code)
+    }
+}
+
+pub trait ToJson {
+    fn to_json_value(&self) -> JsonValue;
+    fn to_json(&self) -> String {
+        self.to_json_value().stringify()
+    }
+}
+
+pub trait FromJson: Sized {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String>;
+    fn from_json(src: &str) -> Result<Self, String> {
+        Self::from_json_value(&JsonValue::parse(src)?)
+    }
+}
+
+macro_rules! json_number {
+    ($type:ty) => {
+        impl ToJson for $type {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Number(self.to_string())
+            }
+        }
+        impl FromJson for $type {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                match value {
+                    JsonValue::Number(n) => n.parse::<$type>().map_err(|e| e.to_string()),
+                    _ => Err(format!("Expecting number for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! json_number_as_string {
+    ($type:ty) => {
+        impl ToJson for $type {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Str(self.to_string())
+            }
+        }
+        impl FromJson for $type {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                match value {
+                    JsonValue::Str(n) | JsonValue::Number(n) => {
+                        n.parse::<$type>().map_err(|e| e.to_string())
+                    }
+                    _ => Err(format!("Expecting string for {}", stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+json_number!(u8);
+json_number!(u16);
+json_number!(u32);
+json_number!(i8);
+json_number!(i16);
+json_number!(i32);
+json_number!(f32);
+json_number!(f64);
+json_number_as_string!(u64);
+json_number_as_string!(i64);
+
+impl ToJson for bool {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err(String::from("Expecting bool")),
+        }
+    }
+}
+
+impl ToJson for String {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Str(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Str(value) => Ok(value.clone()),
+            _ => Err(String::from("Expecting string")),
         }
     }
+}
 
-    #[allow(clippy::should_implement_trait)]
-    pub fn next(&mut self) -> Option<IncomeMessage<T>> {
-        if self.queue.is_empty() {
-            return None;
-        }
-        let message = Some(self.queue[0].clone());
-        if self.queue.len() > 1 {
-            self.queue = self.queue.drain(1..).collect();
-        } else {
-            self.queue.clear();
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(|item| item.to_json_value()).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| T::from_json_value(item).map_err(|e| format!("[{}]: {}", i, e)))
+                .collect(),
+            _ => Err(String::from("Expecting array")),
         }
-        message
     }
+}
 
-    pub fn len(&self) -> usize {
-        self.buffer.len()
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json_value(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json_value(),
+            None => JsonValue::Null,
+        }
     }
+}
 
-    pub fn pending(&self) -> usize {
-        self.queue.len()
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json_value(value).map(Some),
+        }
     }
 }
 
+/// Message with type tag: { "name": "Group.Message", "message": {...} }
+pub fn json_tagged(name: &str, message: JsonValue) -> JsonValue {
+    JsonValue::Object(vec![
+        (String::from("name"), JsonValue::Str(name.to_owned())),
+        (String::from("message"), message),
+    ])
+}
 
-#[derive(Debug, Clone)]
+/// Returns type tag of message defined with json_tagged
+pub fn json_tag(value: &JsonValue) -> Result<&str, String> {
+    match value.get("name") {
+        JsonValue::Str(name) => Ok(name),
+        _ => Err(String::from("Expecting message with field \"name\"")),
+    }
+}
+
+pub const CODEC: &str = "binary";
+#[derive(Debug, Clone, PartialEq)]
 pub enum AvailableMessages {
     EnumExampleA(EnumExampleA),
     EnumExampleB(EnumExampleB),
@@ -1494,6 +3607,65 @@ pub enum AvailableMessages {
     GroupA(GroupA::AvailableMessages),
     GroupB(GroupB::AvailableMessages),
     GroupD(GroupD::AvailableMessages),
+    Unknown { id: u32, body: Vec<u8> },
+}
+impl ToJson for AvailableMessages {
+    fn to_json_value(&self) -> JsonValue {
+        match self {
+            Self::EnumExampleA(m) => json_tagged("EnumExampleA", m.to_json_value()),
+            Self::EnumExampleB(m) => json_tagged("EnumExampleB", m.to_json_value()),
+            Self::EnumExampleC(m) => json_tagged("EnumExampleC", m.to_json_value()),
+            Self::StructExampleA(m) => json_tagged("StructExampleA", m.to_json_value()),
+            Self::StructExampleB(m) => json_tagged("StructExampleB", m.to_json_value()),
+            Self::StructExampleC(m) => json_tagged("StructExampleC", m.to_json_value()),
+            Self::StructExampleD(m) => json_tagged("StructExampleD", m.to_json_value()),
+            Self::StructExampleE(m) => json_tagged("StructExampleE", m.to_json_value()),
+            Self::StructExampleF(m) => json_tagged("StructExampleF", m.to_json_value()),
+            Self::StructExampleG(m) => json_tagged("StructExampleG", m.to_json_value()),
+            Self::StructExampleEmpty(m) => json_tagged("StructExampleEmpty", m.to_json_value()),
+            Self::StructExampleJ(m) => json_tagged("StructExampleJ", m.to_json_value()),
+            Self::GroupA(m) => m.to_json_value(),
+            Self::GroupB(m) => m.to_json_value(),
+            Self::GroupD(m) => m.to_json_value(),
+            Self::Unknown { id, body } => json_tagged(
+                "Unknown",
+                JsonValue::Object(vec![
+                    (String::from("id"), id.to_json_value()),
+                    (String::from("body"), body.to_json_value()),
+                ]),
+            ),
+        }
+    }
+}
+impl FromJson for AvailableMessages {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        let name = json_tag(value)?;
+        match name {
+            "EnumExampleA" => Ok(Self::EnumExampleA(FromJson::from_json_value(value.get("message"))?)),
+            "EnumExampleB" => Ok(Self::EnumExampleB(FromJson::from_json_value(value.get("message"))?)),
+            "EnumExampleC" => Ok(Self::EnumExampleC(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleA" => Ok(Self::StructExampleA(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleB" => Ok(Self::StructExampleB(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleC" => Ok(Self::StructExampleC(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleD" => Ok(Self::StructExampleD(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleE" => Ok(Self::StructExampleE(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleF" => Ok(Self::StructExampleF(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleG" => Ok(Self::StructExampleG(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleEmpty" => Ok(Self::StructExampleEmpty(FromJson::from_json_value(value.get("message"))?)),
+            "StructExampleJ" => Ok(Self::StructExampleJ(FromJson::from_json_value(value.get("message"))?)),
+            name if name.starts_with("GroupA.") => Ok(Self::GroupA(FromJson::from_json_value(value)?)),
+            name if name.starts_with("GroupB.") => Ok(Self::GroupB(FromJson::from_json_value(value)?)),
+            name if name.starts_with("GroupD.") => Ok(Self::GroupD(FromJson::from_json_value(value)?)),
+            "Unknown" => {
+                let message = value.get("message");
+                Ok(Self::Unknown {
+                    id: FromJson::from_json_value(message.get("id"))?,
+                    body: FromJson::from_json_value(message.get("body"))?,
+                })
+            }
+            _ => Err(format!("Unknown message {}", name)),
+        }
+    }
 }
 #[derive(Debug, Clone, PartialEq)]
 pub enum EnumExampleA {
@@ -1528,22 +3700,60 @@ impl EnumEncode for EnumExampleA {
     fn get_id(&self) -> u32 { 1 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let (buf, index) = match self {
-            Self::Option_a(v) => (v.encode(), 0),
-            Self::Option_b(v) => (v.encode(), 1),
-            _ => { return Err(String::from("Not supportable option")); },
-        };
-        let mut buf = match buf {
-            Ok(buf) => buf,
-            Err(e) => { return Err(e); },
-        };
-        let mut buffer: Vec<u8> = vec!();
-        buffer.append(&mut (index as u16).to_le_bytes().to_vec());
-        buffer.append(&mut buf);
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Option_a(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_b(v) => sizes::U16_LEN + v.len_to_store(None),
+            _ => 0,
+        }
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match self {
+            Self::Option_a(v) => {
+                buf.put_u16_le(0);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_b(v) => {
+                buf.put_u16_le(1);
+                v.put_to_store(None, buf)
+            }
+            _ => Err(String::from("Not supportable option")),
+        }
+    }
+}
+impl PackingEnum for EnumExampleA {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl ToJson for EnumExampleA {
+    fn to_json_value(&self) -> JsonValue {
+        let (name, value) = match self {
+            Self::Option_a(value) => ("Option_a", value.to_json_value()),
+            Self::Option_b(value) => ("Option_b", value.to_json_value()),
+            Self::Defaults => ("Defaults", JsonValue::Null),
+        };
+        JsonValue::Object(vec![(String::from(name), value)])
+    }
+}
+impl FromJson for EnumExampleA {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Object(fields) if fields.len() == 1 => {
+                let (name, value) = &fields[0];
+                match name.as_str() {
+                    "Option_a" => Ok(Self::Option_a(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleA.Option_a: {}", e))?)),
+                    "Option_b" => Ok(Self::Option_b(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleA.Option_b: {}", e))?)),
+                    "Defaults" => Ok(Self::Defaults),
+                    _ => Err(format!("Unknown variant {} of EnumExampleA", name)),
+                }
+            }
+            _ => Err(String::from("Expecting object with one field for EnumExampleA")),
+        }
     }
 }
-impl PackingEnum for EnumExampleA {}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum EnumExampleB {
@@ -1623,31 +3833,123 @@ impl EnumEncode for EnumExampleB {
     fn get_id(&self) -> u32 { 2 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let (buf, index) = match self {
-            Self::Option_str(v) => (v.encode(), 0),
-            Self::Option_u8(v) => (v.encode(), 1),
-            Self::Option_u16(v) => (v.encode(), 2),
-            Self::Option_u32(v) => (v.encode(), 3),
-            Self::Option_u64(v) => (v.encode(), 4),
-            Self::Option_i8(v) => (v.encode(), 5),
-            Self::Option_i16(v) => (v.encode(), 6),
-            Self::Option_i32(v) => (v.encode(), 7),
-            Self::Option_i64(v) => (v.encode(), 8),
-            Self::Option_f32(v) => (v.encode(), 9),
-            Self::Option_f64(v) => (v.encode(), 10),
-            _ => { return Err(String::from("Not supportable option")); },
-        };
-        let mut buf = match buf {
-            Ok(buf) => buf,
-            Err(e) => { return Err(e); },
-        };
-        let mut buffer: Vec<u8> = vec!();
-        buffer.append(&mut (index as u16).to_le_bytes().to_vec());
-        buffer.append(&mut buf);
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Option_str(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u8(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u16(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u64(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i8(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i16(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i64(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_f32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_f64(v) => sizes::U16_LEN + v.len_to_store(None),
+            _ => 0,
+        }
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match self {
+            Self::Option_str(v) => {
+                buf.put_u16_le(0);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u8(v) => {
+                buf.put_u16_le(1);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u16(v) => {
+                buf.put_u16_le(2);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u32(v) => {
+                buf.put_u16_le(3);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u64(v) => {
+                buf.put_u16_le(4);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i8(v) => {
+                buf.put_u16_le(5);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i16(v) => {
+                buf.put_u16_le(6);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i32(v) => {
+                buf.put_u16_le(7);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i64(v) => {
+                buf.put_u16_le(8);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_f32(v) => {
+                buf.put_u16_le(9);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_f64(v) => {
+                buf.put_u16_le(10);
+                v.put_to_store(None, buf)
+            }
+            _ => Err(String::from("Not supportable option")),
+        }
+    }
+}
+impl PackingEnum for EnumExampleB {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl ToJson for EnumExampleB {
+    fn to_json_value(&self) -> JsonValue {
+        let (name, value) = match self {
+            Self::Option_str(value) => ("Option_str", value.to_json_value()),
+            Self::Option_u8(value) => ("Option_u8", value.to_json_value()),
+            Self::Option_u16(value) => ("Option_u16", value.to_json_value()),
+            Self::Option_u32(value) => ("Option_u32", value.to_json_value()),
+            Self::Option_u64(value) => ("Option_u64", value.to_json_value()),
+            Self::Option_i8(value) => ("Option_i8", value.to_json_value()),
+            Self::Option_i16(value) => ("Option_i16", value.to_json_value()),
+            Self::Option_i32(value) => ("Option_i32", value.to_json_value()),
+            Self::Option_i64(value) => ("Option_i64", value.to_json_value()),
+            Self::Option_f32(value) => ("Option_f32", value.to_json_value()),
+            Self::Option_f64(value) => ("Option_f64", value.to_json_value()),
+            Self::Defaults => ("Defaults", JsonValue::Null),
+        };
+        JsonValue::Object(vec![(String::from(name), value)])
+    }
+}
+impl FromJson for EnumExampleB {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Object(fields) if fields.len() == 1 => {
+                let (name, value) = &fields[0];
+                match name.as_str() {
+                    "Option_str" => Ok(Self::Option_str(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_str: {}", e))?)),
+                    "Option_u8" => Ok(Self::Option_u8(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_u8: {}", e))?)),
+                    "Option_u16" => Ok(Self::Option_u16(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_u16: {}", e))?)),
+                    "Option_u32" => Ok(Self::Option_u32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_u32: {}", e))?)),
+                    "Option_u64" => Ok(Self::Option_u64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_u64: {}", e))?)),
+                    "Option_i8" => Ok(Self::Option_i8(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_i8: {}", e))?)),
+                    "Option_i16" => Ok(Self::Option_i16(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_i16: {}", e))?)),
+                    "Option_i32" => Ok(Self::Option_i32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_i32: {}", e))?)),
+                    "Option_i64" => Ok(Self::Option_i64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_i64: {}", e))?)),
+                    "Option_f32" => Ok(Self::Option_f32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_f32: {}", e))?)),
+                    "Option_f64" => Ok(Self::Option_f64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleB.Option_f64: {}", e))?)),
+                    "Defaults" => Ok(Self::Defaults),
+                    _ => Err(format!("Unknown variant {} of EnumExampleB", name)),
+                }
+            }
+            _ => Err(String::from("Expecting object with one field for EnumExampleB")),
+        }
     }
 }
-impl PackingEnum for EnumExampleB {}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum EnumExampleC {
@@ -1727,31 +4029,123 @@ impl EnumEncode for EnumExampleC {
     fn get_id(&self) -> u32 { 3 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let (buf, index) = match self {
-            Self::Option_str(v) => (v.encode(), 0),
-            Self::Option_u8(v) => (v.encode(), 1),
-            Self::Option_u16(v) => (v.encode(), 2),
-            Self::Option_u32(v) => (v.encode(), 3),
-            Self::Option_u64(v) => (v.encode(), 4),
-            Self::Option_i8(v) => (v.encode(), 5),
-            Self::Option_i16(v) => (v.encode(), 6),
-            Self::Option_i32(v) => (v.encode(), 7),
-            Self::Option_i64(v) => (v.encode(), 8),
-            Self::Option_f32(v) => (v.encode(), 9),
-            Self::Option_f64(v) => (v.encode(), 10),
-            _ => { return Err(String::from("Not supportable option")); },
-        };
-        let mut buf = match buf {
-            Ok(buf) => buf,
-            Err(e) => { return Err(e); },
-        };
-        let mut buffer: Vec<u8> = vec!();
-        buffer.append(&mut (index as u16).to_le_bytes().to_vec());
-        buffer.append(&mut buf);
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Option_str(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u8(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u16(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_u64(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i8(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i16(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_i64(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_f32(v) => sizes::U16_LEN + v.len_to_store(None),
+            Self::Option_f64(v) => sizes::U16_LEN + v.len_to_store(None),
+            _ => 0,
+        }
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match self {
+            Self::Option_str(v) => {
+                buf.put_u16_le(0);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u8(v) => {
+                buf.put_u16_le(1);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u16(v) => {
+                buf.put_u16_le(2);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u32(v) => {
+                buf.put_u16_le(3);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_u64(v) => {
+                buf.put_u16_le(4);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i8(v) => {
+                buf.put_u16_le(5);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i16(v) => {
+                buf.put_u16_le(6);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i32(v) => {
+                buf.put_u16_le(7);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_i64(v) => {
+                buf.put_u16_le(8);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_f32(v) => {
+                buf.put_u16_le(9);
+                v.put_to_store(None, buf)
+            }
+            Self::Option_f64(v) => {
+                buf.put_u16_le(10);
+                v.put_to_store(None, buf)
+            }
+            _ => Err(String::from("Not supportable option")),
+        }
+    }
+}
+impl PackingEnum for EnumExampleC {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl ToJson for EnumExampleC {
+    fn to_json_value(&self) -> JsonValue {
+        let (name, value) = match self {
+            Self::Option_str(value) => ("Option_str", value.to_json_value()),
+            Self::Option_u8(value) => ("Option_u8", value.to_json_value()),
+            Self::Option_u16(value) => ("Option_u16", value.to_json_value()),
+            Self::Option_u32(value) => ("Option_u32", value.to_json_value()),
+            Self::Option_u64(value) => ("Option_u64", value.to_json_value()),
+            Self::Option_i8(value) => ("Option_i8", value.to_json_value()),
+            Self::Option_i16(value) => ("Option_i16", value.to_json_value()),
+            Self::Option_i32(value) => ("Option_i32", value.to_json_value()),
+            Self::Option_i64(value) => ("Option_i64", value.to_json_value()),
+            Self::Option_f32(value) => ("Option_f32", value.to_json_value()),
+            Self::Option_f64(value) => ("Option_f64", value.to_json_value()),
+            Self::Defaults => ("Defaults", JsonValue::Null),
+        };
+        JsonValue::Object(vec![(String::from(name), value)])
+    }
+}
+impl FromJson for EnumExampleC {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Object(fields) if fields.len() == 1 => {
+                let (name, value) = &fields[0];
+                match name.as_str() {
+                    "Option_str" => Ok(Self::Option_str(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_str: {}", e))?)),
+                    "Option_u8" => Ok(Self::Option_u8(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_u8: {}", e))?)),
+                    "Option_u16" => Ok(Self::Option_u16(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_u16: {}", e))?)),
+                    "Option_u32" => Ok(Self::Option_u32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_u32: {}", e))?)),
+                    "Option_u64" => Ok(Self::Option_u64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_u64: {}", e))?)),
+                    "Option_i8" => Ok(Self::Option_i8(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_i8: {}", e))?)),
+                    "Option_i16" => Ok(Self::Option_i16(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_i16: {}", e))?)),
+                    "Option_i32" => Ok(Self::Option_i32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_i32: {}", e))?)),
+                    "Option_i64" => Ok(Self::Option_i64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_i64: {}", e))?)),
+                    "Option_f32" => Ok(Self::Option_f32(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_f32: {}", e))?)),
+                    "Option_f64" => Ok(Self::Option_f64(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleC.Option_f64: {}", e))?)),
+                    "Defaults" => Ok(Self::Defaults),
+                    _ => Err(format!("Unknown variant {} of EnumExampleC", name)),
+                }
+            }
+            _ => Err(String::from("Expecting object with one field for EnumExampleC")),
+        }
     }
 }
-impl PackingEnum for EnumExampleC {}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleA {
@@ -1768,6 +4162,8 @@ pub struct StructExampleA {
     pub field_f32: f32,
     pub field_f64: f64,
     pub field_bool: bool,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -1790,6 +4186,7 @@ impl StructDecode for StructExampleA {
             field_f32: 0.0,
             field_f64: 0.0,
             field_bool: true,
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -1845,6 +4242,7 @@ impl StructDecode for StructExampleA {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]);
         Ok(())
     }
 }
@@ -1854,63 +4252,96 @@ impl StructEncode for StructExampleA {
     fn get_id(&self) -> u32 { 4 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_str.get_buf_to_store(Some(5)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_str_empty.get_buf_to_store(Some(6)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u8.get_buf_to_store(Some(7)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u16.get_buf_to_store(Some(8)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u32.get_buf_to_store(Some(9)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u64.get_buf_to_store(Some(10)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i8.get_buf_to_store(Some(11)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i16.get_buf_to_store(Some(12)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i32.get_buf_to_store(Some(13)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i64.get_buf_to_store(Some(14)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f32.get_buf_to_store(Some(15)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f64.get_buf_to_store(Some(16)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_bool.get_buf_to_store(Some(17)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_str.len_to_store(Some(5));
+        len += self.field_str_empty.len_to_store(Some(6));
+        len += self.field_u8.len_to_store(Some(7));
+        len += self.field_u16.len_to_store(Some(8));
+        len += self.field_u32.len_to_store(Some(9));
+        len += self.field_u64.len_to_store(Some(10));
+        len += self.field_i8.len_to_store(Some(11));
+        len += self.field_i16.len_to_store(Some(12));
+        len += self.field_i32.len_to_store(Some(13));
+        len += self.field_i64.len_to_store(Some(14));
+        len += self.field_f32.len_to_store(Some(15));
+        len += self.field_f64.len_to_store(Some(16));
+        len += self.field_bool.len_to_store(Some(17));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_str.put_to_store(Some(5), buf)?;
+        self.field_str_empty.put_to_store(Some(6), buf)?;
+        self.field_u8.put_to_store(Some(7), buf)?;
+        self.field_u16.put_to_store(Some(8), buf)?;
+        self.field_u32.put_to_store(Some(9), buf)?;
+        self.field_u64.put_to_store(Some(10), buf)?;
+        self.field_i8.put_to_store(Some(11), buf)?;
+        self.field_i16.put_to_store(Some(12), buf)?;
+        self.field_i32.put_to_store(Some(13), buf)?;
+        self.field_i64.put_to_store(Some(14), buf)?;
+        self.field_f32.put_to_store(Some(15), buf)?;
+        self.field_f64.put_to_store(Some(16), buf)?;
+        self.field_bool.put_to_store(Some(17), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleA {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleA {
+    fn default() -> Self {
+        <StructExampleA as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleA {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_str"), self.field_str.to_json_value()),
+            (String::from("field_str_empty"), self.field_str_empty.to_json_value()),
+            (String::from("field_u8"), self.field_u8.to_json_value()),
+            (String::from("field_u16"), self.field_u16.to_json_value()),
+            (String::from("field_u32"), self.field_u32.to_json_value()),
+            (String::from("field_u64"), self.field_u64.to_json_value()),
+            (String::from("field_i8"), self.field_i8.to_json_value()),
+            (String::from("field_i16"), self.field_i16.to_json_value()),
+            (String::from("field_i32"), self.field_i32.to_json_value()),
+            (String::from("field_i64"), self.field_i64.to_json_value()),
+            (String::from("field_f32"), self.field_f32.to_json_value()),
+            (String::from("field_f64"), self.field_f64.to_json_value()),
+            (String::from("field_bool"), self.field_bool.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleA {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleA"));
+        }
+        Ok(StructExampleA {
+            field_str: FromJson::from_json_value(value.get("field_str")).map_err(|e| format!("StructExampleA.field_str: {}", e))?,
+            field_str_empty: FromJson::from_json_value(value.get("field_str_empty")).map_err(|e| format!("StructExampleA.field_str_empty: {}", e))?,
+            field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleA.field_u8: {}", e))?,
+            field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleA.field_u16: {}", e))?,
+            field_u32: FromJson::from_json_value(value.get("field_u32")).map_err(|e| format!("StructExampleA.field_u32: {}", e))?,
+            field_u64: FromJson::from_json_value(value.get("field_u64")).map_err(|e| format!("StructExampleA.field_u64: {}", e))?,
+            field_i8: FromJson::from_json_value(value.get("field_i8")).map_err(|e| format!("StructExampleA.field_i8: {}", e))?,
+            field_i16: FromJson::from_json_value(value.get("field_i16")).map_err(|e| format!("StructExampleA.field_i16: {}", e))?,
+            field_i32: FromJson::from_json_value(value.get("field_i32")).map_err(|e| format!("StructExampleA.field_i32: {}", e))?,
+            field_i64: FromJson::from_json_value(value.get("field_i64")).map_err(|e| format!("StructExampleA.field_i64: {}", e))?,
+            field_f32: FromJson::from_json_value(value.get("field_f32")).map_err(|e| format!("StructExampleA.field_f32: {}", e))?,
+            field_f64: FromJson::from_json_value(value.get("field_f64")).map_err(|e| format!("StructExampleA.field_f64: {}", e))?,
+            field_bool: FromJson::from_json_value(value.get("field_bool")).map_err(|e| format!("StructExampleA.field_bool: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleA { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleB {
@@ -1940,6 +4371,8 @@ pub struct StructExampleB {
     pub field_f64_empty: Vec<f64>,
     pub field_bool_empty: Vec<bool>,
     pub field_struct_empty: Vec<StructExampleA>,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -1975,6 +4408,7 @@ impl StructDecode for StructExampleB {
             field_f64_empty: vec![],
             field_bool_empty: vec![],
             field_struct_empty: vec![],
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2082,6 +4516,7 @@ impl StructDecode for StructExampleB {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44]);
         Ok(())
     }
 }
@@ -2091,115 +4526,148 @@ impl StructEncode for StructExampleB {
     fn get_id(&self) -> u32 { 18 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_str.get_buf_to_store(Some(19)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u8.get_buf_to_store(Some(20)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u16.get_buf_to_store(Some(21)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u32.get_buf_to_store(Some(22)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u64.get_buf_to_store(Some(23)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i8.get_buf_to_store(Some(24)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i16.get_buf_to_store(Some(25)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i32.get_buf_to_store(Some(26)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i64.get_buf_to_store(Some(27)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f32.get_buf_to_store(Some(28)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f64.get_buf_to_store(Some(29)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_bool.get_buf_to_store(Some(30)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_struct.get_buf_to_store(Some(31)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_str_empty.get_buf_to_store(Some(32)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u8_empty.get_buf_to_store(Some(33)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u16_empty.get_buf_to_store(Some(34)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u32_empty.get_buf_to_store(Some(35)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u64_empty.get_buf_to_store(Some(36)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i8_empty.get_buf_to_store(Some(37)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i16_empty.get_buf_to_store(Some(38)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i32_empty.get_buf_to_store(Some(39)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i64_empty.get_buf_to_store(Some(40)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f32_empty.get_buf_to_store(Some(41)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f64_empty.get_buf_to_store(Some(42)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_bool_empty.get_buf_to_store(Some(43)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_struct_empty.get_buf_to_store(Some(44)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_str.len_to_store(Some(19));
+        len += self.field_u8.len_to_store(Some(20));
+        len += self.field_u16.len_to_store(Some(21));
+        len += self.field_u32.len_to_store(Some(22));
+        len += self.field_u64.len_to_store(Some(23));
+        len += self.field_i8.len_to_store(Some(24));
+        len += self.field_i16.len_to_store(Some(25));
+        len += self.field_i32.len_to_store(Some(26));
+        len += self.field_i64.len_to_store(Some(27));
+        len += self.field_f32.len_to_store(Some(28));
+        len += self.field_f64.len_to_store(Some(29));
+        len += self.field_bool.len_to_store(Some(30));
+        len += self.field_struct.len_to_store(Some(31));
+        len += self.field_str_empty.len_to_store(Some(32));
+        len += self.field_u8_empty.len_to_store(Some(33));
+        len += self.field_u16_empty.len_to_store(Some(34));
+        len += self.field_u32_empty.len_to_store(Some(35));
+        len += self.field_u64_empty.len_to_store(Some(36));
+        len += self.field_i8_empty.len_to_store(Some(37));
+        len += self.field_i16_empty.len_to_store(Some(38));
+        len += self.field_i32_empty.len_to_store(Some(39));
+        len += self.field_i64_empty.len_to_store(Some(40));
+        len += self.field_f32_empty.len_to_store(Some(41));
+        len += self.field_f64_empty.len_to_store(Some(42));
+        len += self.field_bool_empty.len_to_store(Some(43));
+        len += self.field_struct_empty.len_to_store(Some(44));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_str.put_to_store(Some(19), buf)?;
+        self.field_u8.put_to_store(Some(20), buf)?;
+        self.field_u16.put_to_store(Some(21), buf)?;
+        self.field_u32.put_to_store(Some(22), buf)?;
+        self.field_u64.put_to_store(Some(23), buf)?;
+        self.field_i8.put_to_store(Some(24), buf)?;
+        self.field_i16.put_to_store(Some(25), buf)?;
+        self.field_i32.put_to_store(Some(26), buf)?;
+        self.field_i64.put_to_store(Some(27), buf)?;
+        self.field_f32.put_to_store(Some(28), buf)?;
+        self.field_f64.put_to_store(Some(29), buf)?;
+        self.field_bool.put_to_store(Some(30), buf)?;
+        self.field_struct.put_to_store(Some(31), buf)?;
+        self.field_str_empty.put_to_store(Some(32), buf)?;
+        self.field_u8_empty.put_to_store(Some(33), buf)?;
+        self.field_u16_empty.put_to_store(Some(34), buf)?;
+        self.field_u32_empty.put_to_store(Some(35), buf)?;
+        self.field_u64_empty.put_to_store(Some(36), buf)?;
+        self.field_i8_empty.put_to_store(Some(37), buf)?;
+        self.field_i16_empty.put_to_store(Some(38), buf)?;
+        self.field_i32_empty.put_to_store(Some(39), buf)?;
+        self.field_i64_empty.put_to_store(Some(40), buf)?;
+        self.field_f32_empty.put_to_store(Some(41), buf)?;
+        self.field_f64_empty.put_to_store(Some(42), buf)?;
+        self.field_bool_empty.put_to_store(Some(43), buf)?;
+        self.field_struct_empty.put_to_store(Some(44), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleB {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleB {
+    fn default() -> Self {
+        <StructExampleB as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleB {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_str"), self.field_str.to_json_value()),
+            (String::from("field_u8"), self.field_u8.to_json_value()),
+            (String::from("field_u16"), self.field_u16.to_json_value()),
+            (String::from("field_u32"), self.field_u32.to_json_value()),
+            (String::from("field_u64"), self.field_u64.to_json_value()),
+            (String::from("field_i8"), self.field_i8.to_json_value()),
+            (String::from("field_i16"), self.field_i16.to_json_value()),
+            (String::from("field_i32"), self.field_i32.to_json_value()),
+            (String::from("field_i64"), self.field_i64.to_json_value()),
+            (String::from("field_f32"), self.field_f32.to_json_value()),
+            (String::from("field_f64"), self.field_f64.to_json_value()),
+            (String::from("field_bool"), self.field_bool.to_json_value()),
+            (String::from("field_struct"), self.field_struct.to_json_value()),
+            (String::from("field_str_empty"), self.field_str_empty.to_json_value()),
+            (String::from("field_u8_empty"), self.field_u8_empty.to_json_value()),
+            (String::from("field_u16_empty"), self.field_u16_empty.to_json_value()),
+            (String::from("field_u32_empty"), self.field_u32_empty.to_json_value()),
+            (String::from("field_u64_empty"), self.field_u64_empty.to_json_value()),
+            (String::from("field_i8_empty"), self.field_i8_empty.to_json_value()),
+            (String::from("field_i16_empty"), self.field_i16_empty.to_json_value()),
+            (String::from("field_i32_empty"), self.field_i32_empty.to_json_value()),
+            (String::from("field_i64_empty"), self.field_i64_empty.to_json_value()),
+            (String::from("field_f32_empty"), self.field_f32_empty.to_json_value()),
+            (String::from("field_f64_empty"), self.field_f64_empty.to_json_value()),
+            (String::from("field_bool_empty"), self.field_bool_empty.to_json_value()),
+            (String::from("field_struct_empty"), self.field_struct_empty.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleB {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleB"));
+        }
+        Ok(StructExampleB {
+            field_str: FromJson::from_json_value(value.get("field_str")).map_err(|e| format!("StructExampleB.field_str: {}", e))?,
+            field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleB.field_u8: {}", e))?,
+            field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleB.field_u16: {}", e))?,
+            field_u32: FromJson::from_json_value(value.get("field_u32")).map_err(|e| format!("StructExampleB.field_u32: {}", e))?,
+            field_u64: FromJson::from_json_value(value.get("field_u64")).map_err(|e| format!("StructExampleB.field_u64: {}", e))?,
+            field_i8: FromJson::from_json_value(value.get("field_i8")).map_err(|e| format!("StructExampleB.field_i8: {}", e))?,
+            field_i16: FromJson::from_json_value(value.get("field_i16")).map_err(|e| format!("StructExampleB.field_i16: {}", e))?,
+            field_i32: FromJson::from_json_value(value.get("field_i32")).map_err(|e| format!("StructExampleB.field_i32: {}", e))?,
+            field_i64: FromJson::from_json_value(value.get("field_i64")).map_err(|e| format!("StructExampleB.field_i64: {}", e))?,
+            field_f32: FromJson::from_json_value(value.get("field_f32")).map_err(|e| format!("StructExampleB.field_f32: {}", e))?,
+            field_f64: FromJson::from_json_value(value.get("field_f64")).map_err(|e| format!("StructExampleB.field_f64: {}", e))?,
+            field_bool: FromJson::from_json_value(value.get("field_bool")).map_err(|e| format!("StructExampleB.field_bool: {}", e))?,
+            field_struct: FromJson::from_json_value(value.get("field_struct")).map_err(|e| format!("StructExampleB.field_struct: {}", e))?,
+            field_str_empty: FromJson::from_json_value(value.get("field_str_empty")).map_err(|e| format!("StructExampleB.field_str_empty: {}", e))?,
+            field_u8_empty: FromJson::from_json_value(value.get("field_u8_empty")).map_err(|e| format!("StructExampleB.field_u8_empty: {}", e))?,
+            field_u16_empty: FromJson::from_json_value(value.get("field_u16_empty")).map_err(|e| format!("StructExampleB.field_u16_empty: {}", e))?,
+            field_u32_empty: FromJson::from_json_value(value.get("field_u32_empty")).map_err(|e| format!("StructExampleB.field_u32_empty: {}", e))?,
+            field_u64_empty: FromJson::from_json_value(value.get("field_u64_empty")).map_err(|e| format!("StructExampleB.field_u64_empty: {}", e))?,
+            field_i8_empty: FromJson::from_json_value(value.get("field_i8_empty")).map_err(|e| format!("StructExampleB.field_i8_empty: {}", e))?,
+            field_i16_empty: FromJson::from_json_value(value.get("field_i16_empty")).map_err(|e| format!("StructExampleB.field_i16_empty: {}", e))?,
+            field_i32_empty: FromJson::from_json_value(value.get("field_i32_empty")).map_err(|e| format!("StructExampleB.field_i32_empty: {}", e))?,
+            field_i64_empty: FromJson::from_json_value(value.get("field_i64_empty")).map_err(|e| format!("StructExampleB.field_i64_empty: {}", e))?,
+            field_f32_empty: FromJson::from_json_value(value.get("field_f32_empty")).map_err(|e| format!("StructExampleB.field_f32_empty: {}", e))?,
+            field_f64_empty: FromJson::from_json_value(value.get("field_f64_empty")).map_err(|e| format!("StructExampleB.field_f64_empty: {}", e))?,
+            field_bool_empty: FromJson::from_json_value(value.get("field_bool_empty")).map_err(|e| format!("StructExampleB.field_bool_empty: {}", e))?,
+            field_struct_empty: FromJson::from_json_value(value.get("field_struct_empty")).map_err(|e| format!("StructExampleB.field_struct_empty: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleB { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleC {
@@ -2215,6 +4683,8 @@ pub struct StructExampleC {
     pub field_f32: Option<f32>,
     pub field_f64: Option<f64>,
     pub field_bool: Option<bool>,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2236,6 +4706,7 @@ impl StructDecode for StructExampleC {
             field_f32: None,
             field_f64: None,
             field_bool: None,
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2287,6 +4758,7 @@ impl StructDecode for StructExampleC {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57]);
         Ok(())
     }
 }
@@ -2296,59 +4768,92 @@ impl StructEncode for StructExampleC {
     fn get_id(&self) -> u32 { 45 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_str.get_buf_to_store(Some(46)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u8.get_buf_to_store(Some(47)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u16.get_buf_to_store(Some(48)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u32.get_buf_to_store(Some(49)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u64.get_buf_to_store(Some(50)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i8.get_buf_to_store(Some(51)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i16.get_buf_to_store(Some(52)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i32.get_buf_to_store(Some(53)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i64.get_buf_to_store(Some(54)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f32.get_buf_to_store(Some(55)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f64.get_buf_to_store(Some(56)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_bool.get_buf_to_store(Some(57)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_str.len_to_store(Some(46));
+        len += self.field_u8.len_to_store(Some(47));
+        len += self.field_u16.len_to_store(Some(48));
+        len += self.field_u32.len_to_store(Some(49));
+        len += self.field_u64.len_to_store(Some(50));
+        len += self.field_i8.len_to_store(Some(51));
+        len += self.field_i16.len_to_store(Some(52));
+        len += self.field_i32.len_to_store(Some(53));
+        len += self.field_i64.len_to_store(Some(54));
+        len += self.field_f32.len_to_store(Some(55));
+        len += self.field_f64.len_to_store(Some(56));
+        len += self.field_bool.len_to_store(Some(57));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_str.put_to_store(Some(46), buf)?;
+        self.field_u8.put_to_store(Some(47), buf)?;
+        self.field_u16.put_to_store(Some(48), buf)?;
+        self.field_u32.put_to_store(Some(49), buf)?;
+        self.field_u64.put_to_store(Some(50), buf)?;
+        self.field_i8.put_to_store(Some(51), buf)?;
+        self.field_i16.put_to_store(Some(52), buf)?;
+        self.field_i32.put_to_store(Some(53), buf)?;
+        self.field_i64.put_to_store(Some(54), buf)?;
+        self.field_f32.put_to_store(Some(55), buf)?;
+        self.field_f64.put_to_store(Some(56), buf)?;
+        self.field_bool.put_to_store(Some(57), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleC {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleC {
+    fn default() -> Self {
+        <StructExampleC as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleC {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_str"), self.field_str.to_json_value()),
+            (String::from("field_u8"), self.field_u8.to_json_value()),
+            (String::from("field_u16"), self.field_u16.to_json_value()),
+            (String::from("field_u32"), self.field_u32.to_json_value()),
+            (String::from("field_u64"), self.field_u64.to_json_value()),
+            (String::from("field_i8"), self.field_i8.to_json_value()),
+            (String::from("field_i16"), self.field_i16.to_json_value()),
+            (String::from("field_i32"), self.field_i32.to_json_value()),
+            (String::from("field_i64"), self.field_i64.to_json_value()),
+            (String::from("field_f32"), self.field_f32.to_json_value()),
+            (String::from("field_f64"), self.field_f64.to_json_value()),
+            (String::from("field_bool"), self.field_bool.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleC {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleC"));
+        }
+        Ok(StructExampleC {
+            field_str: FromJson::from_json_value(value.get("field_str")).map_err(|e| format!("StructExampleC.field_str: {}", e))?,
+            field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleC.field_u8: {}", e))?,
+            field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleC.field_u16: {}", e))?,
+            field_u32: FromJson::from_json_value(value.get("field_u32")).map_err(|e| format!("StructExampleC.field_u32: {}", e))?,
+            field_u64: FromJson::from_json_value(value.get("field_u64")).map_err(|e| format!("StructExampleC.field_u64: {}", e))?,
+            field_i8: FromJson::from_json_value(value.get("field_i8")).map_err(|e| format!("StructExampleC.field_i8: {}", e))?,
+            field_i16: FromJson::from_json_value(value.get("field_i16")).map_err(|e| format!("StructExampleC.field_i16: {}", e))?,
+            field_i32: FromJson::from_json_value(value.get("field_i32")).map_err(|e| format!("StructExampleC.field_i32: {}", e))?,
+            field_i64: FromJson::from_json_value(value.get("field_i64")).map_err(|e| format!("StructExampleC.field_i64: {}", e))?,
+            field_f32: FromJson::from_json_value(value.get("field_f32")).map_err(|e| format!("StructExampleC.field_f32: {}", e))?,
+            field_f64: FromJson::from_json_value(value.get("field_f64")).map_err(|e| format!("StructExampleC.field_f64: {}", e))?,
+            field_bool: FromJson::from_json_value(value.get("field_bool")).map_err(|e| format!("StructExampleC.field_bool: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleC { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleD {
@@ -2364,6 +4869,8 @@ pub struct StructExampleD {
     pub field_f32: Option<Vec<f32>>,
     pub field_f64: Option<Vec<f64>>,
     pub field_bool: Option<Vec<bool>>,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2385,6 +4892,7 @@ impl StructDecode for StructExampleD {
             field_f32: None,
             field_f64: None,
             field_bool: None,
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2436,6 +4944,7 @@ impl StructDecode for StructExampleD {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70]);
         Ok(())
     }
 }
@@ -2445,65 +4954,100 @@ impl StructEncode for StructExampleD {
     fn get_id(&self) -> u32 { 58 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_str.get_buf_to_store(Some(59)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u8.get_buf_to_store(Some(60)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u16.get_buf_to_store(Some(61)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u32.get_buf_to_store(Some(62)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_u64.get_buf_to_store(Some(63)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i8.get_buf_to_store(Some(64)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i16.get_buf_to_store(Some(65)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i32.get_buf_to_store(Some(66)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_i64.get_buf_to_store(Some(67)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f32.get_buf_to_store(Some(68)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_f64.get_buf_to_store(Some(69)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_bool.get_buf_to_store(Some(70)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_str.len_to_store(Some(59));
+        len += self.field_u8.len_to_store(Some(60));
+        len += self.field_u16.len_to_store(Some(61));
+        len += self.field_u32.len_to_store(Some(62));
+        len += self.field_u64.len_to_store(Some(63));
+        len += self.field_i8.len_to_store(Some(64));
+        len += self.field_i16.len_to_store(Some(65));
+        len += self.field_i32.len_to_store(Some(66));
+        len += self.field_i64.len_to_store(Some(67));
+        len += self.field_f32.len_to_store(Some(68));
+        len += self.field_f64.len_to_store(Some(69));
+        len += self.field_bool.len_to_store(Some(70));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_str.put_to_store(Some(59), buf)?;
+        self.field_u8.put_to_store(Some(60), buf)?;
+        self.field_u16.put_to_store(Some(61), buf)?;
+        self.field_u32.put_to_store(Some(62), buf)?;
+        self.field_u64.put_to_store(Some(63), buf)?;
+        self.field_i8.put_to_store(Some(64), buf)?;
+        self.field_i16.put_to_store(Some(65), buf)?;
+        self.field_i32.put_to_store(Some(66), buf)?;
+        self.field_i64.put_to_store(Some(67), buf)?;
+        self.field_f32.put_to_store(Some(68), buf)?;
+        self.field_f64.put_to_store(Some(69), buf)?;
+        self.field_bool.put_to_store(Some(70), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleD {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleD {
+    fn default() -> Self {
+        <StructExampleD as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleD {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_str"), self.field_str.to_json_value()),
+            (String::from("field_u8"), self.field_u8.to_json_value()),
+            (String::from("field_u16"), self.field_u16.to_json_value()),
+            (String::from("field_u32"), self.field_u32.to_json_value()),
+            (String::from("field_u64"), self.field_u64.to_json_value()),
+            (String::from("field_i8"), self.field_i8.to_json_value()),
+            (String::from("field_i16"), self.field_i16.to_json_value()),
+            (String::from("field_i32"), self.field_i32.to_json_value()),
+            (String::from("field_i64"), self.field_i64.to_json_value()),
+            (String::from("field_f32"), self.field_f32.to_json_value()),
+            (String::from("field_f64"), self.field_f64.to_json_value()),
+            (String::from("field_bool"), self.field_bool.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleD {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleD"));
+        }
+        Ok(StructExampleD {
+            field_str: FromJson::from_json_value(value.get("field_str")).map_err(|e| format!("StructExampleD.field_str: {}", e))?,
+            field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleD.field_u8: {}", e))?,
+            field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleD.field_u16: {}", e))?,
+            field_u32: FromJson::from_json_value(value.get("field_u32")).map_err(|e| format!("StructExampleD.field_u32: {}", e))?,
+            field_u64: FromJson::from_json_value(value.get("field_u64")).map_err(|e| format!("StructExampleD.field_u64: {}", e))?,
+            field_i8: FromJson::from_json_value(value.get("field_i8")).map_err(|e| format!("StructExampleD.field_i8: {}", e))?,
+            field_i16: FromJson::from_json_value(value.get("field_i16")).map_err(|e| format!("StructExampleD.field_i16: {}", e))?,
+            field_i32: FromJson::from_json_value(value.get("field_i32")).map_err(|e| format!("StructExampleD.field_i32: {}", e))?,
+            field_i64: FromJson::from_json_value(value.get("field_i64")).map_err(|e| format!("StructExampleD.field_i64: {}", e))?,
+            field_f32: FromJson::from_json_value(value.get("field_f32")).map_err(|e| format!("StructExampleD.field_f32: {}", e))?,
+            field_f64: FromJson::from_json_value(value.get("field_f64")).map_err(|e| format!("StructExampleD.field_f64: {}", e))?,
+            field_bool: FromJson::from_json_value(value.get("field_bool")).map_err(|e| format!("StructExampleD.field_bool: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleD { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleE {
     pub field_a: EnumExampleA,
     pub field_b: EnumExampleB,
     pub field_c: EnumExampleC,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2516,6 +5060,7 @@ impl StructDecode for StructExampleE {
             field_a: EnumExampleA::Defaults,
             field_b: EnumExampleB::Defaults,
             field_c: EnumExampleC::Defaults,
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2531,6 +5076,7 @@ impl StructDecode for StructExampleE {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[72, 73, 74]);
         Ok(())
     }
 }
@@ -2540,29 +5086,64 @@ impl StructEncode for StructExampleE {
     fn get_id(&self) -> u32 { 71 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_a.get_buf_to_store(Some(72)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_b.get_buf_to_store(Some(73)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_c.get_buf_to_store(Some(74)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_a.len_to_store(Some(72));
+        len += self.field_b.len_to_store(Some(73));
+        len += self.field_c.len_to_store(Some(74));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_a.put_to_store(Some(72), buf)?;
+        self.field_b.put_to_store(Some(73), buf)?;
+        self.field_c.put_to_store(Some(74), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleE {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleE {
+    fn default() -> Self {
+        <StructExampleE as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleE {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_a"), self.field_a.to_json_value()),
+            (String::from("field_b"), self.field_b.to_json_value()),
+            (String::from("field_c"), self.field_c.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleE {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleE"));
+        }
+        Ok(StructExampleE {
+            field_a: FromJson::from_json_value(value.get("field_a")).map_err(|e| format!("StructExampleE.field_a: {}", e))?,
+            field_b: FromJson::from_json_value(value.get("field_b")).map_err(|e| format!("StructExampleE.field_b: {}", e))?,
+            field_c: FromJson::from_json_value(value.get("field_c")).map_err(|e| format!("StructExampleE.field_c: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleE { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleF {
     pub field_a: Option<EnumExampleA>,
     pub field_b: Option<EnumExampleB>,
     pub field_c: Option<EnumExampleC>,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2575,6 +5156,7 @@ impl StructDecode for StructExampleF {
             field_a: None,
             field_b: None,
             field_c: None,
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2614,6 +5196,7 @@ impl StructDecode for StructExampleF {
         } else {
             return Err("Buffer for property field_c isn't found".to_string());
         }
+        self.__unknown = storage.unknown(&[76, 77, 78]);
         Ok(())
     }
 }
@@ -2623,49 +5206,63 @@ impl StructEncode for StructExampleF {
     fn get_id(&self) -> u32 { 75 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        if let Some(mut val) = self.field_a.clone() {
-            match val.get_buf_to_store(Some(76)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
-        } else {
-            match get_empty_buffer_val(Some(76)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
-        }
-        if let Some(mut val) = self.field_b.clone() {
-            match val.get_buf_to_store(Some(77)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
-        } else {
-            match get_empty_buffer_val(Some(77)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
-        }
-        if let Some(mut val) = self.field_c.clone() {
-            match val.get_buf_to_store(Some(78)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
-        } else {
-            match get_empty_buffer_val(Some(78)) {
-                Ok(mut buf) => { buffer.append(&mut buf); },
-                Err(e) => { return  Err(e); },
-            };
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += match &self.field_a { Some(val) => val.len_to_store(Some(76)), None => get_empty_len_to_store(Some(76)) };
+        len += match &self.field_b { Some(val) => val.len_to_store(Some(77)), None => get_empty_len_to_store(Some(77)) };
+        len += match &self.field_c { Some(val) => val.len_to_store(Some(78)), None => get_empty_len_to_store(Some(78)) };
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        match &self.field_a { Some(val) => val.put_to_store(Some(76), buf)?, None => put_empty_to_store(Some(76), buf) };
+        match &self.field_b { Some(val) => val.put_to_store(Some(77), buf)?, None => put_empty_to_store(Some(77), buf) };
+        match &self.field_c { Some(val) => val.put_to_store(Some(78), buf)?, None => put_empty_to_store(Some(78), buf) };
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleF {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleF {
+    fn default() -> Self {
+        <StructExampleF as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleF {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_a"), self.field_a.to_json_value()),
+            (String::from("field_b"), self.field_b.to_json_value()),
+            (String::from("field_c"), self.field_c.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleF {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleF"));
         }
-        Ok(buffer)
+        Ok(StructExampleF {
+            field_a: FromJson::from_json_value(value.get("field_a")).map_err(|e| format!("StructExampleF.field_a: {}", e))?,
+            field_b: FromJson::from_json_value(value.get("field_b")).map_err(|e| format!("StructExampleF.field_b: {}", e))?,
+            field_c: FromJson::from_json_value(value.get("field_c")).map_err(|e| format!("StructExampleF.field_c: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleF { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleG {
     pub field_a: StructExampleA,
     pub field_b: StructExampleB,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2689,6 +5286,7 @@ impl StructDecode for StructExampleG {
                 field_f32: 0.0,
                 field_f64: 0.0,
                 field_bool: true,
+                __unknown: vec![],
             },
             field_b: StructExampleB {
                 field_str: vec![],
@@ -2717,7 +5315,9 @@ impl StructDecode for StructExampleG {
                 field_f64_empty: vec![],
                 field_bool_empty: vec![],
                 field_struct_empty: vec![],
+                __unknown: vec![],
             },
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2729,6 +5329,7 @@ impl StructDecode for StructExampleG {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[80, 81]);
         Ok(())
     }
 }
@@ -2738,22 +5339,57 @@ impl StructEncode for StructExampleG {
     fn get_id(&self) -> u32 { 79 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_a.get_buf_to_store(Some(80)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_b.get_buf_to_store(Some(81)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_a.len_to_store(Some(80));
+        len += self.field_b.len_to_store(Some(81));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_a.put_to_store(Some(80), buf)?;
+        self.field_b.put_to_store(Some(81), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleG {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleG {
+    fn default() -> Self {
+        <StructExampleG as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleG {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_a"), self.field_a.to_json_value()),
+            (String::from("field_b"), self.field_b.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleG {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleG"));
+        }
+        Ok(StructExampleG {
+            field_a: FromJson::from_json_value(value.get("field_a")).map_err(|e| format!("StructExampleG.field_a: {}", e))?,
+            field_b: FromJson::from_json_value(value.get("field_b")).map_err(|e| format!("StructExampleG.field_b: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleG { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleEmpty {
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2763,9 +5399,11 @@ impl StructDecode for StructExampleEmpty {
     }
     fn defaults() -> StructExampleEmpty {
         StructExampleEmpty {
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
+        self.__unknown = storage.unknown(&[]);
         Ok(())
     }
 }
@@ -2775,17 +5413,52 @@ impl StructEncode for StructExampleEmpty {
     fn get_id(&self) -> u32 { 82 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleEmpty {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleEmpty {
+    fn default() -> Self {
+        <StructExampleEmpty as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleEmpty {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+        ])
+    }
+}
+impl FromJson for StructExampleEmpty {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleEmpty"));
+        }
+        Ok(StructExampleEmpty {
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleEmpty { }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructExampleJ {
     pub field_a: Option<StructExampleA>,
     pub field_b: Option<StructExampleB>,
     pub field_c: StructExampleEmpty,
+    #[doc(hidden)]
+    pub __unknown: UnknownFields,
 }
 #[allow(unused_variables)]
 #[allow(unused_mut)]
@@ -2798,7 +5471,9 @@ impl StructDecode for StructExampleJ {
             field_a: None,
             field_b: None,
             field_c: StructExampleEmpty {
+                __unknown: vec![],
             },
+            __unknown: vec![],
         }
     }
     fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2814,6 +5489,7 @@ impl StructDecode for StructExampleJ {
             Ok(val) => val,
             Err(e) => { return Err(e) },
         };
+        self.__unknown = storage.unknown(&[84, 85, 86]);
         Ok(())
     }
 }
@@ -2823,34 +5499,87 @@ impl StructEncode for StructExampleJ {
     fn get_id(&self) -> u32 { 83 }
     fn get_signature(&self) -> u16 { 0 }
     fn abduct(&mut self) -> Result<Vec<u8>, String> {
-        let mut buffer: Vec<u8> = vec!();
-        match self.field_a.get_buf_to_store(Some(84)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_b.get_buf_to_store(Some(85)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        match self.field_c.get_buf_to_store(Some(86)) {
-            Ok(mut buf) => { buffer.append(&mut buf); }
-            Err(e) => { return Err(e) },
-        };
-        Ok(buffer)
+        encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+    }
+    fn encoded_len(&self) -> usize {
+        let mut len: usize = 0;
+        len += self.field_a.len_to_store(Some(84));
+        len += self.field_b.len_to_store(Some(85));
+        len += self.field_c.len_to_store(Some(86));
+        len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+        len
+    }
+    fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+        self.field_a.put_to_store(Some(84), buf)?;
+        self.field_b.put_to_store(Some(85), buf)?;
+        self.field_c.put_to_store(Some(86), buf)?;
+        for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+        Ok(())
+    }
+}
+impl PackingStruct for StructExampleJ {
+    fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+        encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+    }
+}
+impl Default for StructExampleJ {
+    fn default() -> Self {
+        <StructExampleJ as StructDecode>::defaults()
+    }
+}
+impl ToJson for StructExampleJ {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("field_a"), self.field_a.to_json_value()),
+            (String::from("field_b"), self.field_b.to_json_value()),
+            (String::from("field_c"), self.field_c.to_json_value()),
+        ])
+    }
+}
+impl FromJson for StructExampleJ {
+    fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+        if !matches!(value, JsonValue::Object(_)) {
+            return Err(String::from("Expecting object for StructExampleJ"));
+        }
+        Ok(StructExampleJ {
+            field_a: FromJson::from_json_value(value.get("field_a")).map_err(|e| format!("StructExampleJ.field_a: {}", e))?,
+            field_b: FromJson::from_json_value(value.get("field_b")).map_err(|e| format!("StructExampleJ.field_b: {}", e))?,
+            field_c: FromJson::from_json_value(value.get("field_c")).map_err(|e| format!("StructExampleJ.field_c: {}", e))?,
+            __unknown: vec![],
+        })
     }
 }
-impl PackingStruct for StructExampleJ { }
 
 pub mod GroupA {
     use super::*;
     use std::io::Cursor;
-    use bytes::{ Buf };
-    #[derive(Debug, Clone)]
+    use bytes::{ Buf, BufMut };
+    #[derive(Debug, Clone, PartialEq)]
     pub enum AvailableMessages {
         EnumExampleA(EnumExampleA),
         StructExampleA(StructExampleA),
         StructExampleB(StructExampleB),
     }
+    impl ToJson for AvailableMessages {
+        fn to_json_value(&self) -> JsonValue {
+            match self {
+                Self::EnumExampleA(m) => json_tagged("GroupA.EnumExampleA", m.to_json_value()),
+                Self::StructExampleA(m) => json_tagged("GroupA.StructExampleA", m.to_json_value()),
+                Self::StructExampleB(m) => json_tagged("GroupA.StructExampleB", m.to_json_value()),
+            }
+        }
+    }
+    impl FromJson for AvailableMessages {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            let name = json_tag(value)?;
+            match name {
+                "GroupA.EnumExampleA" => Ok(Self::EnumExampleA(FromJson::from_json_value(value.get("message"))?)),
+                "GroupA.StructExampleA" => Ok(Self::StructExampleA(FromJson::from_json_value(value.get("message"))?)),
+                "GroupA.StructExampleB" => Ok(Self::StructExampleB(FromJson::from_json_value(value.get("message"))?)),
+                _ => Err(format!("Unknown message {}", name)),
+            }
+        }
+    }
 
     #[derive(Debug, Clone, PartialEq)]
     pub enum EnumExampleA {
@@ -2885,28 +5614,68 @@ pub mod GroupA {
         fn get_id(&self) -> u32 { 88 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let (buf, index) = match self {
-                Self::Option_a(v) => (v.encode(), 0),
-                Self::Option_b(v) => (v.encode(), 1),
-                _ => { return Err(String::from("Not supportable option")); },
-            };
-            let mut buf = match buf {
-                Ok(buf) => buf,
-                Err(e) => { return Err(e); },
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            match self {
+                Self::Option_a(v) => sizes::U16_LEN + v.len_to_store(None),
+                Self::Option_b(v) => sizes::U16_LEN + v.len_to_store(None),
+                _ => 0,
+            }
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            match self {
+                Self::Option_a(v) => {
+                    buf.put_u16_le(0);
+                    v.put_to_store(None, buf)
+                }
+                Self::Option_b(v) => {
+                    buf.put_u16_le(1);
+                    v.put_to_store(None, buf)
+                }
+                _ => Err(String::from("Not supportable option")),
+            }
+        }
+    }
+    impl PackingEnum for EnumExampleA {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl ToJson for EnumExampleA {
+        fn to_json_value(&self) -> JsonValue {
+            let (name, value) = match self {
+                Self::Option_a(value) => ("Option_a", value.to_json_value()),
+                Self::Option_b(value) => ("Option_b", value.to_json_value()),
+                Self::Defaults => ("Defaults", JsonValue::Null),
             };
-            let mut buffer: Vec<u8> = vec!();
-            buffer.append(&mut (index as u16).to_le_bytes().to_vec());
-            buffer.append(&mut buf);
-            Ok(buffer)
+            JsonValue::Object(vec![(String::from(name), value)])
+        }
+    }
+    impl FromJson for EnumExampleA {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            match value {
+                JsonValue::Object(fields) if fields.len() == 1 => {
+                    let (name, value) = &fields[0];
+                    match name.as_str() {
+                        "Option_a" => Ok(Self::Option_a(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleA.Option_a: {}", e))?)),
+                        "Option_b" => Ok(Self::Option_b(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleA.Option_b: {}", e))?)),
+                        "Defaults" => Ok(Self::Defaults),
+                        _ => Err(format!("Unknown variant {} of EnumExampleA", name)),
+                    }
+                }
+                _ => Err(String::from("Expecting object with one field for EnumExampleA")),
+            }
         }
     }
-    impl PackingEnum for EnumExampleA {}
 
     #[derive(Debug, Clone, PartialEq)]
     pub struct StructExampleA {
         pub field_u8: u8,
         pub field_u16: u16,
         pub opt: EnumExampleA,
+        #[doc(hidden)]
+        pub __unknown: UnknownFields,
     }
     #[allow(unused_variables)]
     #[allow(unused_mut)]
@@ -2919,6 +5688,7 @@ pub mod GroupA {
                 field_u8: 0,
                 field_u16: 0,
                 opt: GroupA::EnumExampleA::Defaults,
+                __unknown: vec![],
             }
         }
         fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2934,6 +5704,7 @@ pub mod GroupA {
                 Ok(val) => val,
                 Err(e) => { return Err(e) },
             };
+            self.__unknown = storage.unknown(&[90, 91, 92]);
             Ok(())
         }
     }
@@ -2943,29 +5714,64 @@ pub mod GroupA {
         fn get_id(&self) -> u32 { 89 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let mut buffer: Vec<u8> = vec!();
-            match self.field_u8.get_buf_to_store(Some(90)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.field_u16.get_buf_to_store(Some(91)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.opt.get_buf_to_store(Some(92)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            Ok(buffer)
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            let mut len: usize = 0;
+            len += self.field_u8.len_to_store(Some(90));
+            len += self.field_u16.len_to_store(Some(91));
+            len += self.opt.len_to_store(Some(92));
+            len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+            len
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            self.field_u8.put_to_store(Some(90), buf)?;
+            self.field_u16.put_to_store(Some(91), buf)?;
+            self.opt.put_to_store(Some(92), buf)?;
+            for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+            Ok(())
+        }
+    }
+    impl PackingStruct for StructExampleA {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl Default for StructExampleA {
+        fn default() -> Self {
+            <StructExampleA as StructDecode>::defaults()
+        }
+    }
+    impl ToJson for StructExampleA {
+        fn to_json_value(&self) -> JsonValue {
+            JsonValue::Object(vec![
+                (String::from("field_u8"), self.field_u8.to_json_value()),
+                (String::from("field_u16"), self.field_u16.to_json_value()),
+                (String::from("opt"), self.opt.to_json_value()),
+            ])
+        }
+    }
+    impl FromJson for StructExampleA {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            if !matches!(value, JsonValue::Object(_)) {
+                return Err(String::from("Expecting object for StructExampleA"));
+            }
+            Ok(StructExampleA {
+                field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleA.field_u8: {}", e))?,
+                field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleA.field_u16: {}", e))?,
+                opt: FromJson::from_json_value(value.get("opt")).map_err(|e| format!("StructExampleA.opt: {}", e))?,
+                __unknown: vec![],
+            })
         }
     }
-    impl PackingStruct for StructExampleA { }
 
     #[derive(Debug, Clone, PartialEq)]
     pub struct StructExampleB {
         pub field_u8: u8,
         pub field_u16: u16,
         pub strct: StructExampleA,
+        #[doc(hidden)]
+        pub __unknown: UnknownFields,
     }
     #[allow(unused_variables)]
     #[allow(unused_mut)]
@@ -2981,7 +5787,9 @@ pub mod GroupA {
                     field_u8: 0,
                     field_u16: 0,
                     opt: GroupA::EnumExampleA::Defaults,
+                    __unknown: vec![],
                 },
+                __unknown: vec![],
             }
         }
         fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -2997,6 +5805,7 @@ pub mod GroupA {
                 Ok(val) => val,
                 Err(e) => { return Err(e) },
             };
+            self.__unknown = storage.unknown(&[94, 95, 96]);
             Ok(())
         }
     }
@@ -3006,40 +5815,93 @@ pub mod GroupA {
         fn get_id(&self) -> u32 { 93 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let mut buffer: Vec<u8> = vec!();
-            match self.field_u8.get_buf_to_store(Some(94)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.field_u16.get_buf_to_store(Some(95)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.strct.get_buf_to_store(Some(96)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            Ok(buffer)
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            let mut len: usize = 0;
+            len += self.field_u8.len_to_store(Some(94));
+            len += self.field_u16.len_to_store(Some(95));
+            len += self.strct.len_to_store(Some(96));
+            len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+            len
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            self.field_u8.put_to_store(Some(94), buf)?;
+            self.field_u16.put_to_store(Some(95), buf)?;
+            self.strct.put_to_store(Some(96), buf)?;
+            for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+            Ok(())
+        }
+    }
+    impl PackingStruct for StructExampleB {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl Default for StructExampleB {
+        fn default() -> Self {
+            <StructExampleB as StructDecode>::defaults()
+        }
+    }
+    impl ToJson for StructExampleB {
+        fn to_json_value(&self) -> JsonValue {
+            JsonValue::Object(vec![
+                (String::from("field_u8"), self.field_u8.to_json_value()),
+                (String::from("field_u16"), self.field_u16.to_json_value()),
+                (String::from("strct"), self.strct.to_json_value()),
+            ])
+        }
+    }
+    impl FromJson for StructExampleB {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            if !matches!(value, JsonValue::Object(_)) {
+                return Err(String::from("Expecting object for StructExampleB"));
+            }
+            Ok(StructExampleB {
+                field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleB.field_u8: {}", e))?,
+                field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleB.field_u16: {}", e))?,
+                strct: FromJson::from_json_value(value.get("strct")).map_err(|e| format!("StructExampleB.strct: {}", e))?,
+                __unknown: vec![],
+            })
         }
     }
-    impl PackingStruct for StructExampleB { }
 
 }
 
 pub mod GroupB {
     use super::*;
     use std::io::Cursor;
-    use bytes::{ Buf };
-    #[derive(Debug, Clone)]
+    use bytes::{ Buf, BufMut };
+    #[derive(Debug, Clone, PartialEq)]
     pub enum AvailableMessages {
         StructExampleA(StructExampleA),
         GroupC(GroupC::AvailableMessages),
     }
+    impl ToJson for AvailableMessages {
+        fn to_json_value(&self) -> JsonValue {
+            match self {
+                Self::StructExampleA(m) => json_tagged("GroupB.StructExampleA", m.to_json_value()),
+                Self::GroupC(m) => m.to_json_value(),
+            }
+        }
+    }
+    impl FromJson for AvailableMessages {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            let name = json_tag(value)?;
+            match name {
+                "GroupB.StructExampleA" => Ok(Self::StructExampleA(FromJson::from_json_value(value.get("message"))?)),
+                name if name.starts_with("GroupB.GroupC.") => Ok(Self::GroupC(FromJson::from_json_value(value)?)),
+                _ => Err(format!("Unknown message {}", name)),
+            }
+        }
+    }
 
     #[derive(Debug, Clone, PartialEq)]
     pub struct StructExampleA {
         pub field_u8: u8,
         pub field_u16: u16,
+        #[doc(hidden)]
+        pub __unknown: UnknownFields,
     }
     #[allow(unused_variables)]
     #[allow(unused_mut)]
@@ -3051,6 +5913,7 @@ pub mod GroupB {
             StructExampleA {
                 field_u8: 0,
                 field_u16: 0,
+                __unknown: vec![],
             }
         }
         fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -3062,6 +5925,7 @@ pub mod GroupB {
                 Ok(val) => val,
                 Err(e) => { return Err(e) },
             };
+            self.__unknown = storage.unknown(&[99, 100]);
             Ok(())
         }
     }
@@ -3071,34 +5935,87 @@ pub mod GroupB {
         fn get_id(&self) -> u32 { 98 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let mut buffer: Vec<u8> = vec!();
-            match self.field_u8.get_buf_to_store(Some(99)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.field_u16.get_buf_to_store(Some(100)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            Ok(buffer)
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            let mut len: usize = 0;
+            len += self.field_u8.len_to_store(Some(99));
+            len += self.field_u16.len_to_store(Some(100));
+            len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+            len
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            self.field_u8.put_to_store(Some(99), buf)?;
+            self.field_u16.put_to_store(Some(100), buf)?;
+            for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+            Ok(())
+        }
+    }
+    impl PackingStruct for StructExampleA {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl Default for StructExampleA {
+        fn default() -> Self {
+            <StructExampleA as StructDecode>::defaults()
+        }
+    }
+    impl ToJson for StructExampleA {
+        fn to_json_value(&self) -> JsonValue {
+            JsonValue::Object(vec![
+                (String::from("field_u8"), self.field_u8.to_json_value()),
+                (String::from("field_u16"), self.field_u16.to_json_value()),
+            ])
+        }
+    }
+    impl FromJson for StructExampleA {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            if !matches!(value, JsonValue::Object(_)) {
+                return Err(String::from("Expecting object for StructExampleA"));
+            }
+            Ok(StructExampleA {
+                field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleA.field_u8: {}", e))?,
+                field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleA.field_u16: {}", e))?,
+                __unknown: vec![],
+            })
         }
     }
-    impl PackingStruct for StructExampleA { }
 
     pub mod GroupC {
         use super::*;
         use std::io::Cursor;
-        use bytes::{ Buf };
-        #[derive(Debug, Clone)]
+        use bytes::{ Buf, BufMut };
+        #[derive(Debug, Clone, PartialEq)]
         pub enum AvailableMessages {
             StructExampleA(StructExampleA),
             StructExampleB(StructExampleB),
         }
+        impl ToJson for AvailableMessages {
+            fn to_json_value(&self) -> JsonValue {
+                match self {
+                    Self::StructExampleA(m) => json_tagged("GroupB.GroupC.StructExampleA", m.to_json_value()),
+                    Self::StructExampleB(m) => json_tagged("GroupB.GroupC.StructExampleB", m.to_json_value()),
+                }
+            }
+        }
+        impl FromJson for AvailableMessages {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                let name = json_tag(value)?;
+                match name {
+                    "GroupB.GroupC.StructExampleA" => Ok(Self::StructExampleA(FromJson::from_json_value(value.get("message"))?)),
+                    "GroupB.GroupC.StructExampleB" => Ok(Self::StructExampleB(FromJson::from_json_value(value.get("message"))?)),
+                    _ => Err(format!("Unknown message {}", name)),
+                }
+            }
+        }
 
         #[derive(Debug, Clone, PartialEq)]
         pub struct StructExampleA {
             pub field_u8: u8,
             pub field_u16: u16,
+            #[doc(hidden)]
+            pub __unknown: UnknownFields,
         }
         #[allow(unused_variables)]
         #[allow(unused_mut)]
@@ -3110,6 +6027,7 @@ pub mod GroupB {
                 StructExampleA {
                     field_u8: 0,
                     field_u16: 0,
+                    __unknown: vec![],
                 }
             }
             fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -3121,6 +6039,7 @@ pub mod GroupB {
                     Ok(val) => val,
                     Err(e) => { return Err(e) },
                 };
+                self.__unknown = storage.unknown(&[103, 104]);
                 Ok(())
             }
         }
@@ -3130,25 +6049,60 @@ pub mod GroupB {
             fn get_id(&self) -> u32 { 102 }
             fn get_signature(&self) -> u16 { 0 }
             fn abduct(&mut self) -> Result<Vec<u8>, String> {
-                let mut buffer: Vec<u8> = vec!();
-                match self.field_u8.get_buf_to_store(Some(103)) {
-                    Ok(mut buf) => { buffer.append(&mut buf); }
-                    Err(e) => { return Err(e) },
-                };
-                match self.field_u16.get_buf_to_store(Some(104)) {
-                    Ok(mut buf) => { buffer.append(&mut buf); }
-                    Err(e) => { return Err(e) },
-                };
-                Ok(buffer)
+                encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+            }
+            fn encoded_len(&self) -> usize {
+                let mut len: usize = 0;
+                len += self.field_u8.len_to_store(Some(103));
+                len += self.field_u16.len_to_store(Some(104));
+                len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+                len
+            }
+            fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+                self.field_u8.put_to_store(Some(103), buf)?;
+                self.field_u16.put_to_store(Some(104), buf)?;
+                for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+                Ok(())
+            }
+        }
+        impl PackingStruct for StructExampleA {
+            fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+                encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+            }
+        }
+        impl Default for StructExampleA {
+            fn default() -> Self {
+                <StructExampleA as StructDecode>::defaults()
+            }
+        }
+        impl ToJson for StructExampleA {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Object(vec![
+                    (String::from("field_u8"), self.field_u8.to_json_value()),
+                    (String::from("field_u16"), self.field_u16.to_json_value()),
+                ])
+            }
+        }
+        impl FromJson for StructExampleA {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                if !matches!(value, JsonValue::Object(_)) {
+                    return Err(String::from("Expecting object for StructExampleA"));
+                }
+                Ok(StructExampleA {
+                    field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleA.field_u8: {}", e))?,
+                    field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleA.field_u16: {}", e))?,
+                    __unknown: vec![],
+                })
             }
         }
-        impl PackingStruct for StructExampleA { }
 
         #[derive(Debug, Clone, PartialEq)]
         pub struct StructExampleB {
             pub field_u8: u8,
             pub field_u16: u16,
             pub strct: StructExampleA,
+            #[doc(hidden)]
+            pub __unknown: UnknownFields,
         }
         #[allow(unused_variables)]
         #[allow(unused_mut)]
@@ -3163,7 +6117,9 @@ pub mod GroupB {
                     strct: GroupB::GroupC::StructExampleA {
                         field_u8: 0,
                         field_u16: 0,
+                        __unknown: vec![],
                     },
+                    __unknown: vec![],
                 }
             }
             fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -3179,6 +6135,7 @@ pub mod GroupB {
                     Ok(val) => val,
                     Err(e) => { return Err(e) },
                 };
+                self.__unknown = storage.unknown(&[106, 107, 108]);
                 Ok(())
             }
         }
@@ -3188,23 +6145,56 @@ pub mod GroupB {
             fn get_id(&self) -> u32 { 105 }
             fn get_signature(&self) -> u16 { 0 }
             fn abduct(&mut self) -> Result<Vec<u8>, String> {
-                let mut buffer: Vec<u8> = vec!();
-                match self.field_u8.get_buf_to_store(Some(106)) {
-                    Ok(mut buf) => { buffer.append(&mut buf); }
-                    Err(e) => { return Err(e) },
-                };
-                match self.field_u16.get_buf_to_store(Some(107)) {
-                    Ok(mut buf) => { buffer.append(&mut buf); }
-                    Err(e) => { return Err(e) },
-                };
-                match self.strct.get_buf_to_store(Some(108)) {
-                    Ok(mut buf) => { buffer.append(&mut buf); }
-                    Err(e) => { return Err(e) },
-                };
-                Ok(buffer)
+                encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+            }
+            fn encoded_len(&self) -> usize {
+                let mut len: usize = 0;
+                len += self.field_u8.len_to_store(Some(106));
+                len += self.field_u16.len_to_store(Some(107));
+                len += self.strct.len_to_store(Some(108));
+                len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+                len
+            }
+            fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+                self.field_u8.put_to_store(Some(106), buf)?;
+                self.field_u16.put_to_store(Some(107), buf)?;
+                self.strct.put_to_store(Some(108), buf)?;
+                for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+                Ok(())
+            }
+        }
+        impl PackingStruct for StructExampleB {
+            fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+                encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+            }
+        }
+        impl Default for StructExampleB {
+            fn default() -> Self {
+                <StructExampleB as StructDecode>::defaults()
+            }
+        }
+        impl ToJson for StructExampleB {
+            fn to_json_value(&self) -> JsonValue {
+                JsonValue::Object(vec![
+                    (String::from("field_u8"), self.field_u8.to_json_value()),
+                    (String::from("field_u16"), self.field_u16.to_json_value()),
+                    (String::from("strct"), self.strct.to_json_value()),
+                ])
+            }
+        }
+        impl FromJson for StructExampleB {
+            fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+                if !matches!(value, JsonValue::Object(_)) {
+                    return Err(String::from("Expecting object for StructExampleB"));
+                }
+                Ok(StructExampleB {
+                    field_u8: FromJson::from_json_value(value.get("field_u8")).map_err(|e| format!("StructExampleB.field_u8: {}", e))?,
+                    field_u16: FromJson::from_json_value(value.get("field_u16")).map_err(|e| format!("StructExampleB.field_u16: {}", e))?,
+                    strct: FromJson::from_json_value(value.get("strct")).map_err(|e| format!("StructExampleB.strct: {}", e))?,
+                    __unknown: vec![],
+                })
             }
         }
-        impl PackingStruct for StructExampleB { }
 
     }
 
@@ -3213,12 +6203,30 @@ pub mod GroupB {
 pub mod GroupD {
     use super::*;
     use std::io::Cursor;
-    use bytes::{ Buf };
-    #[derive(Debug, Clone)]
+    use bytes::{ Buf, BufMut };
+    #[derive(Debug, Clone, PartialEq)]
     pub enum AvailableMessages {
         EnumExampleP(EnumExampleP),
         StructExampleP(StructExampleP),
     }
+    impl ToJson for AvailableMessages {
+        fn to_json_value(&self) -> JsonValue {
+            match self {
+                Self::EnumExampleP(m) => json_tagged("GroupD.EnumExampleP", m.to_json_value()),
+                Self::StructExampleP(m) => json_tagged("GroupD.StructExampleP", m.to_json_value()),
+            }
+        }
+    }
+    impl FromJson for AvailableMessages {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            let name = json_tag(value)?;
+            match name {
+                "GroupD.EnumExampleP" => Ok(Self::EnumExampleP(FromJson::from_json_value(value.get("message"))?)),
+                "GroupD.StructExampleP" => Ok(Self::StructExampleP(FromJson::from_json_value(value.get("message"))?)),
+                _ => Err(format!("Unknown message {}", name)),
+            }
+        }
+    }
 
     #[derive(Debug, Clone, PartialEq)]
     pub enum EnumExampleP {
@@ -3263,30 +6271,82 @@ pub mod GroupD {
         fn get_id(&self) -> u32 { 114 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let (buf, index) = match self {
-                Self::Option_a(v) => (v.encode(), 0),
-                Self::Option_b(v) => (v.encode(), 1),
-                Self::Option_c(v) => (v.encode(), 2),
-                Self::Option_d(v) => (v.encode(), 3),
-                _ => { return Err(String::from("Not supportable option")); },
-            };
-            let mut buf = match buf {
-                Ok(buf) => buf,
-                Err(e) => { return Err(e); },
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            match self {
+                Self::Option_a(v) => sizes::U16_LEN + v.len_to_store(None),
+                Self::Option_b(v) => sizes::U16_LEN + v.len_to_store(None),
+                Self::Option_c(v) => sizes::U16_LEN + v.len_to_store(None),
+                Self::Option_d(v) => sizes::U16_LEN + v.len_to_store(None),
+                _ => 0,
+            }
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            match self {
+                Self::Option_a(v) => {
+                    buf.put_u16_le(0);
+                    v.put_to_store(None, buf)
+                }
+                Self::Option_b(v) => {
+                    buf.put_u16_le(1);
+                    v.put_to_store(None, buf)
+                }
+                Self::Option_c(v) => {
+                    buf.put_u16_le(2);
+                    v.put_to_store(None, buf)
+                }
+                Self::Option_d(v) => {
+                    buf.put_u16_le(3);
+                    v.put_to_store(None, buf)
+                }
+                _ => Err(String::from("Not supportable option")),
+            }
+        }
+    }
+    impl PackingEnum for EnumExampleP {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl ToJson for EnumExampleP {
+        fn to_json_value(&self) -> JsonValue {
+            let (name, value) = match self {
+                Self::Option_a(value) => ("Option_a", value.to_json_value()),
+                Self::Option_b(value) => ("Option_b", value.to_json_value()),
+                Self::Option_c(value) => ("Option_c", value.to_json_value()),
+                Self::Option_d(value) => ("Option_d", value.to_json_value()),
+                Self::Defaults => ("Defaults", JsonValue::Null),
             };
-            let mut buffer: Vec<u8> = vec!();
-            buffer.append(&mut (index as u16).to_le_bytes().to_vec());
-            buffer.append(&mut buf);
-            Ok(buffer)
+            JsonValue::Object(vec![(String::from(name), value)])
+        }
+    }
+    impl FromJson for EnumExampleP {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            match value {
+                JsonValue::Object(fields) if fields.len() == 1 => {
+                    let (name, value) = &fields[0];
+                    match name.as_str() {
+                        "Option_a" => Ok(Self::Option_a(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleP.Option_a: {}", e))?)),
+                        "Option_b" => Ok(Self::Option_b(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleP.Option_b: {}", e))?)),
+                        "Option_c" => Ok(Self::Option_c(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleP.Option_c: {}", e))?)),
+                        "Option_d" => Ok(Self::Option_d(FromJson::from_json_value(value).map_err(|e| format!("EnumExampleP.Option_d: {}", e))?)),
+                        "Defaults" => Ok(Self::Defaults),
+                        _ => Err(format!("Unknown variant {} of EnumExampleP", name)),
+                    }
+                }
+                _ => Err(String::from("Expecting object with one field for EnumExampleP")),
+            }
         }
     }
-    impl PackingEnum for EnumExampleP {}
 
     #[derive(Debug, Clone, PartialEq)]
     pub struct StructExampleP {
         pub field_a: StructExampleA,
         pub field_b: GroupB::StructExampleA,
         pub field_c: GroupB::GroupC::StructExampleA,
+        #[doc(hidden)]
+        pub __unknown: UnknownFields,
     }
     #[allow(unused_variables)]
     #[allow(unused_mut)]
@@ -3310,15 +6370,19 @@ pub mod GroupD {
                     field_f32: 0.0,
                     field_f64: 0.0,
                     field_bool: true,
+                    __unknown: vec![],
                 },
                 field_b: GroupB::StructExampleA {
                     field_u8: 0,
                     field_u16: 0,
+                    __unknown: vec![],
                 },
                 field_c: GroupB::GroupC::StructExampleA {
                     field_u8: 0,
                     field_u16: 0,
+                    __unknown: vec![],
                 },
+                __unknown: vec![],
             }
         }
         fn extract_from_storage(&mut self, mut storage: Storage) -> Result<(), String> {
@@ -3334,6 +6398,7 @@ pub mod GroupD {
                 Ok(val) => val,
                 Err(e) => { return Err(e) },
             };
+            self.__unknown = storage.unknown(&[111, 112, 113]);
             Ok(())
         }
     }
@@ -3343,23 +6408,56 @@ pub mod GroupD {
         fn get_id(&self) -> u32 { 110 }
         fn get_signature(&self) -> u16 { 0 }
         fn abduct(&mut self) -> Result<Vec<u8>, String> {
-            let mut buffer: Vec<u8> = vec!();
-            match self.field_a.get_buf_to_store(Some(111)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.field_b.get_buf_to_store(Some(112)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            match self.field_c.get_buf_to_store(Some(113)) {
-                Ok(mut buf) => { buffer.append(&mut buf); }
-                Err(e) => { return Err(e) },
-            };
-            Ok(buffer)
+            encode_to_vec(self.encoded_len(), |buf| self.encode_into(buf))
+        }
+        fn encoded_len(&self) -> usize {
+            let mut len: usize = 0;
+            len += self.field_a.len_to_store(Some(111));
+            len += self.field_b.len_to_store(Some(112));
+            len += self.field_c.len_to_store(Some(113));
+            len += self.__unknown.iter().map(|(id, body)| body.len_to_store(Some(*id))).sum::<usize>();
+            len
+        }
+        fn encode_into(&self, buf: &mut impl BufMut) -> Result<(), String> {
+            self.field_a.put_to_store(Some(111), buf)?;
+            self.field_b.put_to_store(Some(112), buf)?;
+            self.field_c.put_to_store(Some(113), buf)?;
+            for (id, body) in self.__unknown.iter() { body.put_to_store(Some(*id), buf)?; }
+            Ok(())
+        }
+    }
+    impl PackingStruct for StructExampleP {
+        fn pack(&mut self, sequence: u32, uuid: Option<String>) -> Result<Vec<u8>, String> {
+            encode_to_vec(0, |buf| self.pack_into(sequence, uuid, buf))
+        }
+    }
+    impl Default for StructExampleP {
+        fn default() -> Self {
+            <StructExampleP as StructDecode>::defaults()
+        }
+    }
+    impl ToJson for StructExampleP {
+        fn to_json_value(&self) -> JsonValue {
+            JsonValue::Object(vec![
+                (String::from("field_a"), self.field_a.to_json_value()),
+                (String::from("field_b"), self.field_b.to_json_value()),
+                (String::from("field_c"), self.field_c.to_json_value()),
+            ])
+        }
+    }
+    impl FromJson for StructExampleP {
+        fn from_json_value(value: &JsonValue) -> Result<Self, String> {
+            if !matches!(value, JsonValue::Object(_)) {
+                return Err(String::from("Expecting object for StructExampleP"));
+            }
+            Ok(StructExampleP {
+                field_a: FromJson::from_json_value(value.get("field_a")).map_err(|e| format!("StructExampleP.field_a: {}", e))?,
+                field_b: FromJson::from_json_value(value.get("field_b")).map_err(|e| format!("StructExampleP.field_b: {}", e))?,
+                field_c: FromJson::from_json_value(value.get("field_c")).map_err(|e| format!("StructExampleP.field_c: {}", e))?,
+                __unknown: vec![],
+            })
         }
     }
-    impl PackingStruct for StructExampleP { }
 
 }
 
@@ -3446,10 +6544,10 @@ impl DecodeBuffer<AvailableMessages> for Buffer<AvailableMessages> {
                 Ok(m) => Ok(AvailableMessages::GroupD(GroupD::AvailableMessages::StructExampleP(m))),
                 Err(e) => Err(e),
             },
-            _ => Err(String::from("No message has been found"))
+            _ => Ok(AvailableMessages::Unknown { id, body: buf.to_vec() }),
         }
     }
     fn get_signature(&self) -> u16 { 0 }
 }
 
-pub fn hash() -> String { String::from("156DE162AA1210095CDAB2912D619327D2541381BFF5A3B282B6E0AEB5627C1A") }
+pub fn hash() -> String { String::from("3C5C99E0FF623717AF39B2751D444404ED16A9B336079A3148149A09D0B797F8") }
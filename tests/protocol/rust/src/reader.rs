@@ -83,6 +83,7 @@ fn check_StructExampleA(entity: StructExampleA) {
         field_f32: 0.1,
         field_f64: 0.2,
         field_bool: true,
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleA: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -119,6 +120,7 @@ fn check_StructExampleB(entity: StructExampleB) {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
             StructExampleA {
                 field_str: String::from("test"),
@@ -134,6 +136,7 @@ fn check_StructExampleB(entity: StructExampleB) {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
             StructExampleA {
                 field_str: String::from("test"),
@@ -149,6 +152,7 @@ fn check_StructExampleB(entity: StructExampleB) {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
         ],
         field_str_empty: vec![],
@@ -164,6 +168,7 @@ fn check_StructExampleB(entity: StructExampleB) {
         field_f64_empty: vec![],
         field_bool_empty: vec![],
         field_struct_empty: vec![],
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleB: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -185,6 +190,7 @@ fn check_StructExampleC(entity: StructExampleC) {
         field_f32: None,
         field_f64: None,
         field_bool: None,
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleC: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -206,6 +212,7 @@ fn check_StructExampleD(entity: StructExampleD) {
         field_f32: None,
         field_f64: None,
         field_bool: None,
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleD: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -218,6 +225,7 @@ fn check_StructExampleE(entity: StructExampleE) {
         field_a: EnumExampleA::Option_a(String::from("Option_a")),
         field_b: EnumExampleB::Option_u8(1),
         field_c: EnumExampleC::Option_u8(vec![1]),
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleE: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -230,6 +238,7 @@ fn check_StructExampleF(entity: StructExampleF) {
         field_a: None,
         field_b: None,
         field_c: Some(EnumExampleC::Option_u8(vec![1])),
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleF: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -253,6 +262,7 @@ fn check_StructExampleG(entity: StructExampleG) {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: StructExampleB {
             field_str: vec![String::from("test_a"), String::from("test_b")],
@@ -282,6 +292,7 @@ fn check_StructExampleG(entity: StructExampleG) {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
                 StructExampleA {
                     field_str: String::from("test"),
@@ -297,6 +308,7 @@ fn check_StructExampleG(entity: StructExampleG) {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
                 StructExampleA {
                     field_str: String::from("test"),
@@ -312,6 +324,7 @@ fn check_StructExampleG(entity: StructExampleG) {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
             ],
             field_str_empty: vec![],
@@ -327,7 +340,9 @@ fn check_StructExampleG(entity: StructExampleG) {
             field_f64_empty: vec![],
             field_bool_empty: vec![],
             field_struct_empty: vec![],
+            ..Default::default()
         },
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleG: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -351,9 +366,11 @@ fn check_StructExampleJ(entity: StructExampleJ) {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         }),
         field_b: None,
-        field_c: StructExampleEmpty {},
+        field_c: StructExampleEmpty { ..Default::default() },
+        ..Default::default()
     };
     if entity != src {
         stop!("StructExampleJ: failed: \n\t{:?}\n\t{:?})", entity, src)
@@ -362,7 +379,7 @@ fn check_StructExampleJ(entity: StructExampleJ) {
 
 #[allow(non_snake_case)]
 fn check_StructExampleEmpty(entity: StructExampleEmpty) {
-    let src = StructExampleEmpty {};
+    let src = StructExampleEmpty { ..Default::default() };
     if entity != src {
         stop!("StructExampleEmpty: failed: \n\t{:?}\n\t{:?})", entity, src)
     }
@@ -374,6 +391,7 @@ fn check_GroupAStructExampleA(entity: GroupA::StructExampleA) {
         field_u8: 1,
         field_u16: 2,
         opt: GroupA::EnumExampleA::Option_a(String::from("Option_a")),
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -393,7 +411,9 @@ fn check_GroupAStructExampleB(entity: GroupA::StructExampleB) {
             field_u8: 1,
             field_u16: 2,
             opt: GroupA::EnumExampleA::Option_a(String::from("Option_a")),
+            ..Default::default()
         },
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -409,6 +429,7 @@ fn check_GroupBStructExampleA(entity: GroupB::StructExampleA) {
     let src = GroupB::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -424,6 +445,7 @@ fn check_GroupCStructExampleA(entity: GroupB::GroupC::StructExampleA) {
     let src = GroupB::GroupC::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -442,7 +464,9 @@ fn check_GroupCStructExampleB(entity: GroupB::GroupC::StructExampleB) {
         strct: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -470,15 +494,19 @@ fn check_GroupDStructExampleP(entity: GroupD::StructExampleP) {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: GroupB::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
         field_c: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     };
     if entity != src {
         stop!(
@@ -505,6 +533,7 @@ fn check_GroupDEnumExamplePOption_a(entity: GroupD::EnumExampleP) {
         field_f32: 0.1,
         field_f64: 0.2,
         field_bool: true,
+        ..Default::default()
     });
     if entity != src {
         stop!(
@@ -532,15 +561,19 @@ fn check_GroupDEnumExamplePOption_b(entity: GroupD::EnumExampleP) {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: GroupB::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
         field_c: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     });
     if entity != src {
         stop!(
@@ -556,6 +589,7 @@ fn check_GroupDEnumExamplePOption_c(entity: GroupD::EnumExampleP) {
     let src = GroupD::EnumExampleP::Option_c(GroupB::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     });
     if entity != src {
         stop!(
@@ -571,6 +605,7 @@ fn check_GroupDEnumExamplePOption_d(entity: GroupD::EnumExampleP) {
     let src = GroupD::EnumExampleP::Option_d(GroupB::GroupC::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     });
     if entity != src {
         stop!(
@@ -1194,6 +1229,19 @@ pub fn read() -> Result<(), String> {
                 let msg = buffer.next();
                 if let Some(msg) = msg {
                     count += 1;
+                    // Each message is kept as it is by canonical JSON
+                    match AvailableMessages::from_json(&msg.msg.to_json()) {
+                        Ok(restored) => {
+                            if restored != msg.msg {
+                                stop!(
+                                    "JSON round trip failed: \n\t{:?}\n\t{:?})",
+                                    msg.msg,
+                                    restored
+                                )
+                            }
+                        }
+                        Err(e) => stop!("Fail to restore {:?} from JSON: {}", msg.msg, e),
+                    }
                     match msg.msg {
                         AvailableMessages::EnumExampleA(entity) => match entity {
                             EnumExampleA::Option_a(entity) => {
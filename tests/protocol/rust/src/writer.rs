@@ -230,6 +230,7 @@ pub fn write() -> Result<(), String> {
         field_f32: 0.1,
         field_f64: 0.2,
         field_bool: true,
+        ..Default::default()
     });
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -254,15 +255,19 @@ pub fn write() -> Result<(), String> {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: GroupB::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
         field_c: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     });
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -275,6 +280,7 @@ pub fn write() -> Result<(), String> {
     let mut usecase = GroupD::EnumExampleP::Option_c(GroupB::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     });
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -287,6 +293,7 @@ pub fn write() -> Result<(), String> {
     let mut usecase = GroupD::EnumExampleP::Option_d(GroupB::GroupC::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     });
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -311,6 +318,7 @@ pub fn write() -> Result<(), String> {
         field_f32: 0.1,
         field_f64: 0.2,
         field_bool: true,
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -348,6 +356,7 @@ pub fn write() -> Result<(), String> {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
             StructExampleA {
                 field_str: String::from("test"),
@@ -363,6 +372,7 @@ pub fn write() -> Result<(), String> {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
             StructExampleA {
                 field_str: String::from("test"),
@@ -378,6 +388,7 @@ pub fn write() -> Result<(), String> {
                 field_f32: 0.1,
                 field_f64: 0.2,
                 field_bool: true,
+                ..Default::default()
             },
         ],
         field_str_empty: vec![],
@@ -393,6 +404,7 @@ pub fn write() -> Result<(), String> {
         field_f64_empty: vec![],
         field_bool_empty: vec![],
         field_struct_empty: vec![],
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -415,6 +427,7 @@ pub fn write() -> Result<(), String> {
         field_f32: None,
         field_f64: None,
         field_bool: None,
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -437,6 +450,7 @@ pub fn write() -> Result<(), String> {
         field_f32: None,
         field_f64: None,
         field_bool: None,
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -450,6 +464,7 @@ pub fn write() -> Result<(), String> {
         field_a: EnumExampleA::Option_a(String::from("Option_a")),
         field_b: EnumExampleB::Option_u8(1),
         field_c: EnumExampleC::Option_u8(vec![1]),
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -463,6 +478,7 @@ pub fn write() -> Result<(), String> {
         field_a: None,
         field_b: None,
         field_c: Some(EnumExampleC::Option_u8(vec![1])),
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -487,6 +503,7 @@ pub fn write() -> Result<(), String> {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: StructExampleB {
             field_str: vec![String::from("test_a"), String::from("test_b")],
@@ -516,6 +533,7 @@ pub fn write() -> Result<(), String> {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
                 StructExampleA {
                     field_str: String::from("test"),
@@ -531,6 +549,7 @@ pub fn write() -> Result<(), String> {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
                 StructExampleA {
                     field_str: String::from("test"),
@@ -546,6 +565,7 @@ pub fn write() -> Result<(), String> {
                     field_f32: 0.1,
                     field_f64: 0.2,
                     field_bool: true,
+                    ..Default::default()
                 },
             ],
             field_str_empty: vec![],
@@ -561,7 +581,9 @@ pub fn write() -> Result<(), String> {
             field_f64_empty: vec![],
             field_bool_empty: vec![],
             field_struct_empty: vec![],
+            ..Default::default()
         },
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -586,9 +608,11 @@ pub fn write() -> Result<(), String> {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         }),
         field_b: None,
-        field_c: StructExampleEmpty {},
+        field_c: StructExampleEmpty { ..Default::default() },
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -599,7 +623,7 @@ pub fn write() -> Result<(), String> {
     }
     buffer.append(&mut usecase.pack(0, None).unwrap());
 
-    let mut usecase = StructExampleEmpty {};
+    let mut usecase = StructExampleEmpty { ..Default::default() };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
             if let Err(e) = write_file(root.join("./StructExampleEmpty.prot.bin"), &buf) {
@@ -613,6 +637,7 @@ pub fn write() -> Result<(), String> {
         field_u8: 1,
         field_u16: 2,
         opt: GroupA::EnumExampleA::Option_a(String::from("Option_a")),
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -629,7 +654,9 @@ pub fn write() -> Result<(), String> {
             field_u8: 1,
             field_u16: 2,
             opt: GroupA::EnumExampleA::Option_a(String::from("Option_a")),
+            ..Default::default()
         },
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -642,6 +669,7 @@ pub fn write() -> Result<(), String> {
     let mut usecase = GroupB::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -654,6 +682,7 @@ pub fn write() -> Result<(), String> {
     let mut usecase = GroupB::GroupC::StructExampleA {
         field_u8: 1,
         field_u16: 2,
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -669,7 +698,9 @@ pub fn write() -> Result<(), String> {
         strct: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
@@ -694,15 +725,19 @@ pub fn write() -> Result<(), String> {
             field_f32: 0.1,
             field_f64: 0.2,
             field_bool: true,
+            ..Default::default()
         },
         field_b: GroupB::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
         field_c: GroupB::GroupC::StructExampleA {
             field_u8: 1,
             field_u16: 2,
+            ..Default::default()
         },
+        ..Default::default()
     };
     if let Ok(buf) = usecase.encode() {
         if !middleware {
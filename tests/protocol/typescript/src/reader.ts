@@ -281,6 +281,32 @@ export function read(): Promise<void> {
 									break;
 								}
 								count += 1;
+								// Each message is kept as it is by canonical JSON
+								const json: string = JSON.stringify(
+									Protocol.toJson(pack.msg)
+								);
+								const restored:
+									| Protocol.IAvailableMessages
+									| Error = Protocol.fromJson(JSON.parse(json));
+								if (restored instanceof Error) {
+									return reject(
+										new Error(
+											`Fail to restore ${json} from JSON: ${restored.message}`
+										)
+									);
+								}
+								if (
+									JSON.stringify(Protocol.toJson(restored)) !==
+									json
+								) {
+									return reject(
+										new Error(
+											`JSON round trip failed: \n\t${json}\n\t${JSON.stringify(
+												Protocol.toJson(restored)
+											)}`
+										)
+									);
+								}
 								if (pack.msg.EnumExampleA !== undefined) {
 									if (
 										pack.msg.EnumExampleA.Option_a !==
@@ -1,4 +1,8 @@
 rm -rf ./consumer/rust/src/consumer
 rm -rf ./producer/rust/src/producer
 rm -rf ./consumer/typescript/src/consumer
-rm -rf ./producer/typescript/src/producer
\ No newline at end of file
+rm -rf ./producer/typescript/src/producer
+rm -rf ./external/tmp
+rm -rf ./stubs/tmp
+rm -rf ./traits/tmp
+rm -rf ./crates/tmp
@@ -1,6 +1,13 @@
 #[path = "../../producer/rust/src/producer/implementation/protocol/mod.rs"]
 pub mod protocol;
 
+// Message is kept as it is by canonical JSON
+fn json_equal<T: protocol::ToJson + protocol::FromJson + PartialEq>(strct: &T) -> bool {
+    T::from_json(&strct.to_json())
+        .map(|restored| &restored == strct)
+        .unwrap_or(false)
+}
+
 pub mod struct_a {
     use super::*;
     pub fn get() -> protocol::StructA {
@@ -21,7 +28,7 @@ pub mod struct_a {
         }
     }
     pub fn equal(strct: protocol::StructA) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_b {
@@ -57,7 +64,7 @@ pub mod struct_b {
         }
     }
     pub fn equal(strct: protocol::StructB) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_c {
@@ -79,7 +86,7 @@ pub mod struct_c {
         }
     }
     pub fn equal(strct: protocol::StructC) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_d {
@@ -101,7 +108,7 @@ pub mod struct_d {
         }
     }
     pub fn equal(strct: protocol::StructD) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_e {
@@ -114,7 +121,7 @@ pub mod struct_e {
         }
     }
     pub fn equal(strct: protocol::StructE) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_f {
@@ -127,7 +134,7 @@ pub mod struct_f {
         }
     }
     pub fn equal(strct: protocol::StructF) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_g {
@@ -139,7 +146,7 @@ pub mod struct_g {
         }
     }
     pub fn equal(strct: protocol::StructG) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_j {
@@ -152,6 +159,6 @@ pub mod struct_j {
         }
     }
     pub fn equal(strct: protocol::StructJ) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
@@ -1,5 +1,12 @@
 use super::protocol;
 
+// Message is kept as it is by canonical JSON
+fn json_equal<T: protocol::ToJson + protocol::FromJson + PartialEq>(strct: &T) -> bool {
+    T::from_json(&strct.to_json())
+        .map(|restored| &restored == strct)
+        .unwrap_or(false)
+}
+
 pub mod struct_a {
     use super::*;
     pub fn get() -> protocol::StructA {
@@ -20,7 +27,7 @@ pub mod struct_a {
         }
     }
     pub fn equal(strct: protocol::StructA) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_b {
@@ -56,7 +63,7 @@ pub mod struct_b {
         }
     }
     pub fn equal(strct: protocol::StructB) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_c {
@@ -78,7 +85,7 @@ pub mod struct_c {
         }
     }
     pub fn equal(strct: protocol::StructC) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_d {
@@ -100,7 +107,7 @@ pub mod struct_d {
         }
     }
     pub fn equal(strct: protocol::StructD) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_e {
@@ -113,7 +120,7 @@ pub mod struct_e {
         }
     }
     pub fn equal(strct: protocol::StructE) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_f {
@@ -126,7 +133,7 @@ pub mod struct_f {
         }
     }
     pub fn equal(strct: protocol::StructF) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_g {
@@ -138,7 +145,7 @@ pub mod struct_g {
         }
     }
     pub fn equal(strct: protocol::StructG) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_j {
@@ -151,7 +158,7 @@ pub mod struct_j {
         }
     }
     pub fn equal(strct: protocol::StructJ) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_empty {
@@ -160,7 +167,7 @@ pub mod struct_empty {
         protocol::StructEmpty {}
     }
     pub fn equal(strct: protocol::StructEmpty) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_empty_a {
@@ -169,7 +176,7 @@ pub mod struct_empty_a {
         protocol::StructEmptyA {}
     }
     pub fn equal(strct: protocol::StructEmptyA) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod struct_empty_b {
@@ -178,7 +185,7 @@ pub mod struct_empty_b {
         protocol::StructEmptyB {}
     }
     pub fn equal(strct: protocol::StructEmptyB) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod beacon_a {
@@ -187,7 +194,7 @@ pub mod beacon_a {
         protocol::BeaconA { field: struct_a::get()}
     }
     pub fn equal(strct: protocol::BeaconA) -> bool {
-        get() == strct
+        get() == strct && json_equal(&strct)
     }
 }
 pub mod beacons {
@@ -198,7 +205,7 @@ pub mod beacons {
             protocol::Beacons::BeaconA { }
         }
         pub fn equal(strct: protocol::Beacons::BeaconA) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
     pub mod beacon_b {
@@ -207,7 +214,7 @@ pub mod beacons {
             protocol::Beacons::BeaconB { field: struct_b::get() }
         }
         pub fn equal(strct: protocol::Beacons::BeaconB) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
     pub mod sub {
@@ -218,7 +225,7 @@ pub mod beacons {
                 protocol::Beacons::Sub::BeaconA { field: struct_g::get() }
             }
             pub fn equal(strct: protocol::Beacons::Sub::BeaconA) -> bool {
-                get() == strct
+                get() == strct && json_equal(&strct)
             }
         }
     }
@@ -235,7 +242,7 @@ pub mod group_a {
             }
         }
         pub fn equal(strct: protocol::GroupA::StructA) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
     pub mod struct_b {
@@ -248,7 +255,7 @@ pub mod group_a {
             }
         }
         pub fn equal(strct: protocol::GroupA::StructB) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
 }
@@ -264,7 +271,7 @@ pub mod group_b {
             }
         }
         pub fn equal(strct: protocol::GroupB::StructA) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
     pub mod group_c {
@@ -278,7 +285,7 @@ pub mod group_b {
                 }
             }
             pub fn equal(strct: protocol::GroupB::GroupC::StructA) -> bool {
-                get() == strct
+                get() == strct && json_equal(&strct)
             }
         }
         pub mod struct_b {
@@ -291,7 +298,7 @@ pub mod group_b {
                 }
             }
             pub fn equal(strct: protocol::GroupB::GroupC::StructB) -> bool {
-                get() == strct
+                get() == strct && json_equal(&strct)
             }
         }
     }
@@ -310,7 +317,7 @@ pub mod group_d {
             }
         }
         pub fn equal(strct: protocol::GroupD::StructP) -> bool {
-            get() == strct
+            get() == strct && json_equal(&strct)
         }
     }
 
@@ -0,0 +1,42 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/crates
+
+clibri=../../../cli/target/release/clibri
+rm -rf ./tmp
+mkdir -p ./tmp
+# Same workflow as rust-rust tests; second workspace has trait handlers
+cp ../prot/protocol-rs-rs.workflow ./tmp/files.workflow
+sed 's/Consumer: rust;/Consumer: rust;\n   Handlers: trait;/' ../prot/protocol-rs-rs.workflow > ./tmp/traits.workflow
+# Both workspaces share target folder to build dependencies once
+export CARGO_TARGET_DIR="$(pwd)/tmp/target"
+
+for name in files traits; do
+    if ! $clibri generate -s ../prot/protocol.prot -wf ./tmp/$name.workflow --crates ./tmp/$name > /dev/null; then
+        echo "[FAIL] Fail to generate crates with $name handlers"
+        exit 1
+    fi
+    for side in producer consumer; do
+        if ! grep -qx "pub use ${name}_protocol::\*;" ./tmp/$name/$name-$side/src/implementation/protocol/mod.rs; then
+            echo "[FAIL] Protocol of $name-$side doesn't refer to $name-protocol crate"
+            exit 1
+        fi
+    done
+    if ! grep -q "^\[features\]" ./tmp/$name/$name-protocol/Cargo.toml; then
+        echo "[FAIL] Features of $name-protocol aren't defined"
+        exit 1
+    fi
+    cd ./tmp/$name
+    if ! cargo build; then
+        echo "[FAIL] Crates with $name handlers aren't built"
+        exit 1
+    fi
+    # Compression is optional part of protocol
+    if ! cargo build --no-default-features; then
+        echo "[FAIL] Crates with $name handlers aren't built without compression"
+        exit 1
+    fi
+    cd ../..
+    echo "[OK] Crates with $name handlers are built"
+done
+rm -rf ./tmp
@@ -0,0 +1,13 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/errors
+
+mkdir -p ./m/consumer ./m/producer
+../../../cli/target/release/clibri generate -s ../prot/protocol.prot -wf ./m.workflow -cd ./m/consumer -pd ./m/producer --views
+# Views are checked with options, so nothing is written
+if [ -n "$(find ./m -type f)" ]; then
+    echo "Files are written, though options are invalid"
+    rm -rf ./m
+    exit 1
+fi
+rm -rf ./m
@@ -0,0 +1,5 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/errors
+
+../../../cli/target/release/clibri -s ../prot/protocol.prot -wf ./o.workflow
@@ -0,0 +1,7 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/errors
+
+mkdir -p ./q/consumer ./q/producer
+../../../cli/target/release/clibri generate -s ../prot/protocol.prot -wf ./q.workflow -cd ./q/consumer -pd ./q/producer --compatible ./q/missing.prot
+rm -rf ./q
@@ -0,0 +1,8 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/errors
+
+mkdir -p ./r/consumer ./r/producer
+../../../cli/target/release/clibri ir -s ../prot/protocol.prot -out ./r/protocol.json
+../../../cli/target/release/clibri generate -s ../prot/protocol.prot -wf ./r.workflow -cd ./r/consumer -pd ./r/producer --compatible ./r/protocol.json
+rm -rf ./r
@@ -0,0 +1,26 @@
+// Checks IR, which fake renderer got, against IR of "clibri ir" for same sources
+const fs = require("fs");
+const assert = require("assert");
+
+const expected = JSON.parse(fs.readFileSync("./tmp/ir.json").toString());
+for (const side of ["producer", "consumer"]) {
+	const received = JSON.parse(fs.readFileSync(`./tmp/ir/${side}.json`).toString());
+	assert.strictEqual(received.side, side);
+	assert.strictEqual(received.target, "fake");
+	assert.deepStrictEqual(received.workflow.config.producer, ["fake"]);
+	assert.deepStrictEqual(received.workflow.config.consumer, ["fake"]);
+	delete received.side;
+	delete received.target;
+	assert.deepStrictEqual(received, expected);
+	assert.ok(received.protocol.structs.some((strct) => strct.name === "StructA"));
+	assert.ok(received.workflow.requests.length > 0);
+	assert.strictEqual(
+		fs.readFileSync(`./tmp/${side}/lib.txt`).toString(),
+		`${side} of fake`
+	);
+	assert.strictEqual(
+		fs.readFileSync(`./tmp/${side}/nested/dir/readme.md`).toString(),
+		"nested"
+	);
+}
+console.log("[OK]\tExternal renderer gets IR and its files are written");
@@ -0,0 +1,40 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/external
+
+clibri=../../../cli/target/release/clibri
+rm -rf ./tmp
+mkdir -p ./tmp/ir ./tmp/producer ./tmp/consumer
+# Same workflow as rust-rust tests, but both sides are rendered by ./clibri-gen-fake
+sed -e 's/Producer: rust;/Producer: fake;/' -e 's/Consumer: rust;/Consumer: fake;/' ../prot/protocol-rs-rs.workflow > ./tmp/protocol.workflow
+export PATH="$(pwd):$PATH"
+export FAKE_IR_DIR="$(pwd)/tmp/ir"
+
+generate() {
+    $clibri generate -s ../prot/protocol.prot -wf ./tmp/protocol.workflow -pd ./tmp/producer -cd ./tmp/consumer
+}
+
+if ! generate; then
+    echo "[FAIL] Fail to generate with external renderer"
+    exit 1
+fi
+if ! $clibri ir -s ../prot/protocol.prot -wf ./tmp/protocol.workflow -out ./tmp/ir.json; then
+    exit 1
+fi
+if ! node ./check.js; then
+    exit 1
+fi
+
+# Renderer can write only inside of destination folder
+for mode in absolute parent; do
+    if FAKE_MODE=$mode generate; then
+        echo "[FAIL] Path of renderer ($mode) has been accepted"
+        exit 1
+    fi
+done
+if [ -e /tmp/clibri-gen-fake.txt ] || [ -e ./tmp/escaped.txt ]; then
+    echo "[FAIL] File outside of destination folder has been written"
+    exit 1
+fi
+echo "[OK] Paths outside of destination folder are rejected"
+rm -rf ./tmp
@@ -0,0 +1,66 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/stubs
+
+clibri=../../../cli/target/release/clibri
+stub=./tmp/producer/responses/structa.rs
+rm -rf ./tmp
+mkdir -p ./tmp/expected
+# Dependencies are added into nearest Cargo.toml
+printf '[package]\nname = "stubs"\nversion = "0.0.0"\n' > ./tmp/Cargo.toml
+cp ./tmp/Cargo.toml ./tmp/expected/Cargo.toml
+
+generate() {
+    $clibri generate -s ../prot/protocol.prot -wf ../prot/protocol-rs-rs.workflow -pd $1/producer -cd $1/consumer $2
+}
+
+if ! generate ./tmp/expected > /dev/null || ! generate ./tmp > /dev/null; then
+    echo "[FAIL] Fail to generate workflow"
+    exit 1
+fi
+
+# Stale signature is reported, but stub isn't touched
+sed -i 's/request: &protocol::StructA,/request: \&protocol::StructB,/' $stub
+cp $stub ./tmp/stale.rs
+if ! generate ./tmp > ./tmp/output.txt; then
+    echo "[FAIL] Fail to generate workflow with stale stub"
+    exit 1
+fi
+if ! grep -q "\[STALE\]: .*structa.rs" ./tmp/output.txt; then
+    echo "[FAIL] Stale signature hasn't been reported"
+    exit 1
+fi
+if ! cmp -s $stub ./tmp/stale.rs; then
+    echo "[FAIL] Stale stub has been changed in report mode"
+    exit 1
+fi
+echo "[OK] Stale signature is reported"
+
+# Patch restores signature
+if ! generate ./tmp "--stubs patch" > /dev/null; then
+    echo "[FAIL] Fail to patch stale stub"
+    exit 1
+fi
+if ! cmp -s $stub ./tmp/expected/producer/responses/structa.rs; then
+    echo "[FAIL] Signature hasn't been restored by patch"
+    diff $stub ./tmp/expected/producer/responses/structa.rs
+    exit 1
+fi
+echo "[OK] Patch restores signature"
+
+# Handler, which doesn't belong to workflow anymore, is reported as orphan
+cp $stub ./tmp/producer/responses/removed.rs
+if ! generate ./tmp > ./tmp/output.txt; then
+    echo "[FAIL] Fail to generate workflow with orphaned stub"
+    exit 1
+fi
+if ! grep -q "\[ORPHAN\]: .*responses/removed.rs" ./tmp/output.txt; then
+    echo "[FAIL] Removed handler hasn't been reported as orphan"
+    exit 1
+fi
+if grep "\[ORPHAN\]" ./tmp/output.txt | grep -qv "removed.rs"; then
+    echo "[FAIL] Actual handlers have been reported as orphans"
+    exit 1
+fi
+echo "[OK] Removed handler is reported as orphan"
+rm -rf ./tmp
@@ -0,0 +1,65 @@
+cd ../../../cli
+cargo build --release
+cd ../tests/workflow/traits
+
+clibri=../../../cli/target/release/clibri
+rm -rf ./tmp
+mkdir -p ./tmp/src
+# Same workflow as rust-rust tests, but handlers are rendered as traits
+sed 's/Consumer: rust;/Consumer: rust;\n   Handlers: trait;/' ../prot/protocol-rs-rs.workflow > ./tmp/protocol.workflow
+# Dependencies are added into nearest Cargo.toml
+printf '[package]\nname = "traits"\nversion = "0.0.0"\nedition = "2018"\n\n[lib]\npath = "src/lib.rs"\n' > ./tmp/Cargo.toml
+printf "pub mod consumer;\npub mod producer;\n" > ./tmp/src/lib.rs
+
+if ! $clibri generate -s ../prot/protocol.prot -wf ./tmp/protocol.workflow -pd ./tmp/src/producer -cd ./tmp/src/consumer > /dev/null; then
+    echo "[FAIL] Fail to generate workflow with trait handlers"
+    exit 1
+fi
+if ! grep -q "#\[allow(async_fn_in_trait)\]" ./tmp/src/producer/implementation/traits.rs || ! grep -q "run<S, C, E, H: ProducerHandlers>" ./tmp/src/producer/implementation/mod.rs; then
+    echo "[FAIL] Producer isn't rendered with ProducerHandlers trait"
+    exit 1
+fi
+if [ -d ./tmp/src/producer/responses ]; then
+    echo "[FAIL] Handler stubs are rendered in trait mode"
+    exit 1
+fi
+if ! grep -q "pub trait ConsumerHandlers" ./tmp/src/consumer/implementation/traits.rs || ! grep -q "connect<C, E, Ctrl, H: ConsumerHandlers>" ./tmp/src/consumer/implementation/consumer/mod.rs; then
+    echo "[FAIL] Consumer isn't rendered with ConsumerHandlers trait"
+    exit 1
+fi
+if [ -d ./tmp/src/consumer/broadcasts ] || [ -d ./tmp/src/consumer/events ]; then
+    echo "[FAIL] Broadcast and event handlers are rendered in trait mode"
+    exit 1
+fi
+
+cd ./tmp
+if ! cargo build; then
+    echo "[FAIL] Producer and consumer with trait handlers aren't built"
+    exit 1
+fi
+echo "[OK] Producer and consumer with trait handlers are built"
+
+# Missing handler is an error of compilation
+cp ./src/lib.rs ./lib.rs.bak
+echo "pub struct Handlers; impl producer::ProducerHandlers for Handlers {}" >> ./src/lib.rs
+if cargo build 2> ./output.txt; then
+    echo "[FAIL] Producer without handlers is built"
+    exit 1
+fi
+if ! grep -q "E0046" ./output.txt; then
+    echo "[FAIL] Missing handlers aren't reported by compiler"
+    cat ./output.txt
+    exit 1
+fi
+mv ./lib.rs.bak ./src/lib.rs
+echo "[OK] Missing handlers are reported by compiler"
+
+# Consumer's handlers have default implementations
+echo "pub struct Handlers; impl consumer::ConsumerHandlers for Handlers {}" >> ./src/lib.rs
+if ! cargo build; then
+    echo "[FAIL] Consumer's handlers don't have defaults"
+    exit 1
+fi
+echo "[OK] Consumer's handlers have defaults"
+cd ..
+rm -rf ./tmp